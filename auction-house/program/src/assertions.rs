@@ -0,0 +1,66 @@
+//! Basic checks of accounts handlers take as `UncheckedAccount` to stay within the stack limit.
+//!
+//! Only the raw bytes needed are read instead of unpacking the whole account, and the lenient paths kept for
+//! accounts created by older versions of the program are reported with a [`LenientPathTaken`] event.
+use crate::{
+    constants::TRADE_STATE_WITH_RENT_PAYER_SIZE, errors::AuctionHouseError,
+    token_interface::is_token_program, LenientPath, LenientPathTaken,
+};
+
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program_memory::sol_memcmp, program_pack::Pack, pubkey::PUBKEY_BYTES},
+};
+use spl_token::state::{Account as SplAccount, Mint as SplMint};
+
+/// Offset of the account state of a token account, zero while it is uninitialized.
+const TOKEN_ACCOUNT_STATE_OFFSET: usize = 108;
+/// Offset of the `is_initialized` flag of a mint.
+const MINT_IS_INITIALIZED_OFFSET: usize = 45;
+
+/// Check `token_account` is owned by either token program, initialized and holds tokens of `mint`.
+pub fn assert_token_account_basic(token_account: &AccountInfo, mint: &Pubkey) -> Result<()> {
+    if !is_token_program(token_account.owner) {
+        return Err(AuctionHouseError::IncorrectOwner.into());
+    }
+    let data = token_account.try_borrow_data()?;
+    if data.len() < SplAccount::LEN || data[TOKEN_ACCOUNT_STATE_OFFSET] == 0 {
+        return Err(AuctionHouseError::UninitializedAccount.into());
+    }
+    if sol_memcmp(&data[..PUBKEY_BYTES], mint.as_ref(), PUBKEY_BYTES) != 0 {
+        return Err(AuctionHouseError::PublicKeyMismatch.into());
+    }
+    Ok(())
+}
+
+/// Check `mint` is owned by either token program and initialized.
+pub fn assert_mint_basic(mint: &AccountInfo) -> Result<()> {
+    if !is_token_program(mint.owner) {
+        return Err(AuctionHouseError::IncorrectOwner.into());
+    }
+    let data = mint.try_borrow_data()?;
+    if data.len() < SplMint::LEN || data[MINT_IS_INITIALIZED_OFFSET] == 0 {
+        return Err(AuctionHouseError::UninitializedAccount.into());
+    }
+    Ok(())
+}
+
+/// Emit a [`LenientPathTaken`] event for `account`.
+pub fn emit_lenient_path(auction_house: Pubkey, account: Pubkey, path: LenientPath) {
+    emit!(LenientPathTaken {
+        auction_house,
+        account,
+        path,
+    });
+}
+
+/// Emit a [`LenientPathTaken`] event if the trade state was created before rent payers were recorded.
+pub fn emit_if_legacy_trade_state(
+    auction_house: Pubkey,
+    trade_state: Pubkey,
+    trade_state_data: &[u8],
+) {
+    if trade_state_data.len() < TRADE_STATE_WITH_RENT_PAYER_SIZE {
+        emit_lenient_path(auction_house, trade_state, LenientPath::LegacyTradeState);
+    }
+}
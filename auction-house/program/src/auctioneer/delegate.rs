@@ -1,6 +1,9 @@
 use anchor_lang::prelude::*;
 
-use crate::{constants::*, errors::AuctionHouseError, AuctionHouse, Auctioneer, AuthorityScope};
+use crate::{
+    constants::*, errors::AuctionHouseError, utils::emit_ah_event, AhEventKind, AuctionHouse,
+    Auctioneer, AuthorityScope,
+};
 
 /// Accounts for the [`delegate_auctioneer` handler](auction_house/fn.delegate_auctioneer.html).
 #[derive(Accounts)]
@@ -67,5 +70,16 @@ pub fn delegate_auctioneer<'info>(
         auctioneer.scopes[scope as usize] = true;
     }
 
+    emit_ah_event(
+        AhEventKind::DelegateAuctioneer,
+        ctx.accounts.auction_house.key(),
+        None,
+        0,
+        vec![
+            ctx.accounts.authority.key(),
+            ctx.accounts.auctioneer_authority.key(),
+        ],
+    )?;
+
     Ok(())
 }
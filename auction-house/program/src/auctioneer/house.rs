@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::*, errors::AuctionHouseError, utils::*, AhEventKind, AuctionHouse, AuthorityScope,
+};
+
+/// Accounts for the [`auctioneer_update_auction_house` handler](auction_house/fn.auctioneer_update_auction_house.html).
+#[derive(Accounts)]
+pub struct AuctioneerUpdateAuctionHouse<'info> {
+    /// The auctioneer authority - typically a PDA of the Auctioneer program running this action.
+    pub auctioneer_authority: Signer<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// The auctioneer PDA owned by Auction House storing scopes.
+    #[account(
+        seeds = [
+            AUCTIONEER.as_bytes(),
+            auction_house.key().as_ref(),
+            auctioneer_authority.key().as_ref()
+        ],
+        bump = auction_house.auctioneer_pda_bump
+    )]
+    pub ah_auctioneer_pda: UncheckedAccount<'info>,
+}
+
+/// Update the trading parameters of the Auction House. Authority, withdrawal destinations and safety switches stay
+/// with the Auction House authority.
+pub fn auctioneer_update_auction_house<'info>(
+    ctx: Context<'_, '_, '_, 'info, AuctioneerUpdateAuctionHouse<'info>>,
+    seller_fee_basis_points: Option<u16>,
+    can_change_sale_price: Option<bool>,
+    tick_size: Option<u64>,
+) -> Result<()> {
+    let auctioneer_authority = &ctx.accounts.auctioneer_authority;
+    let ah_auctioneer_pda = &ctx.accounts.ah_auctioneer_pda;
+    let auction_house = &mut ctx.accounts.auction_house;
+
+    if !auction_house.has_auctioneer {
+        return Err(AuctionHouseError::NoAuctioneerProgramSet.into());
+    }
+
+    assert_valid_auctioneer_and_scope(
+        &auction_house.key(),
+        &auctioneer_authority.key(),
+        ah_auctioneer_pda,
+        AuthorityScope::UpdateHouse,
+    )?;
+
+    if let Some(sfbp) = seller_fee_basis_points {
+        if sfbp > 10000 {
+            return Err(AuctionHouseError::InvalidBasisPoints.into());
+        }
+
+        auction_house.seller_fee_basis_points = sfbp;
+    }
+    if let Some(chsp) = can_change_sale_price {
+        auction_house.can_change_sale_price = chsp;
+    }
+    if let Some(ts) = tick_size {
+        auction_house.tick_size = ts;
+    }
+
+    emit_ah_event(
+        AhEventKind::UpdateAuctionHouse,
+        auction_house.key(),
+        None,
+        0,
+        vec![auctioneer_authority.key()],
+    )?;
+
+    Ok(())
+}
@@ -1,4 +1,8 @@
 pub mod delegate;
+pub mod house;
+pub mod treasury;
 pub mod update;
 pub use delegate::*;
+pub use house::*;
+pub use treasury::*;
 pub use update::*;
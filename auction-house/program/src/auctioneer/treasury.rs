@@ -0,0 +1,155 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program::invoke_signed, system_instruction},
+};
+
+use crate::{
+    constants::*,
+    errors::AuctionHouseError,
+    token_interface::{is_treasury_token_program, transfer},
+    utils::*,
+    AhEventKind, AuctionHouse, AuthorityScope,
+};
+
+/// Accounts for the [`auctioneer_withdraw_from_treasury` handler](auction_house/fn.auctioneer_withdraw_from_treasury.html).
+#[derive(Accounts)]
+pub struct AuctioneerWithdrawFromTreasury<'info> {
+    /// CHECK: Checked in has_one constraint of the auction house.
+    /// Treasury mint account, either native SOL mint or a SPL Token or Token-2022 mint.
+    pub treasury_mint: UncheckedAccount<'info>,
+
+    /// The auctioneer authority - typically a PDA of the Auctioneer program running this action.
+    pub auctioneer_authority: Signer<'info>,
+
+    /// CHECK: Checked in has_one constraint of the auction house.
+    /// SOL or SPL token account set by the Auction House authority to receive treasury withdrawals.
+    #[account(mut)]
+    pub treasury_withdrawal_destination: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House treasury PDA account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            TREASURY.as_bytes()
+        ],
+        bump=auction_house.treasury_bump
+    )]
+    pub auction_house_treasury: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            treasury_mint.key().as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=treasury_mint,
+        has_one=treasury_withdrawal_destination,
+        has_one=auction_house_treasury
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// The auctioneer PDA owned by Auction House storing scopes.
+    #[account(
+        seeds = [
+            AUCTIONEER.as_bytes(),
+            auction_house.key().as_ref(),
+            auctioneer_authority.key().as_ref()
+        ],
+        bump = auction_house.auctioneer_pda_bump
+    )]
+    pub ah_auctioneer_pda: UncheckedAccount<'info>,
+
+    /// CHECK: Checked in constraint to be SPL Token or Token-2022.
+    /// Token program of the treasury mint.
+    #[account(constraint = is_treasury_token_program(token_program.key, &treasury_mint.to_account_info()) @ AuctionHouseError::InvalidTokenProgram)]
+    pub token_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Withdraw `amount` from the Auction House treasury to the treasury withdrawal destination set by the authority.
+pub fn auctioneer_withdraw_from_treasury<'info>(
+    ctx: Context<'_, '_, '_, 'info, AuctioneerWithdrawFromTreasury<'info>>,
+    amount: u64,
+) -> Result<()> {
+    let treasury_mint = &ctx.accounts.treasury_mint;
+    let auctioneer_authority = &ctx.accounts.auctioneer_authority;
+    let treasury_withdrawal_destination = &ctx.accounts.treasury_withdrawal_destination;
+    let auction_house_treasury = &ctx.accounts.auction_house_treasury;
+    let auction_house = &ctx.accounts.auction_house;
+    let ah_auctioneer_pda = &ctx.accounts.ah_auctioneer_pda;
+    let token_program = &ctx.accounts.token_program;
+    let system_program = &ctx.accounts.system_program;
+
+    if !auction_house.has_auctioneer {
+        return Err(AuctionHouseError::NoAuctioneerProgramSet.into());
+    }
+
+    assert_valid_auctioneer_and_scope(
+        &auction_house.key(),
+        &auctioneer_authority.key(),
+        ah_auctioneer_pda,
+        AuthorityScope::WithdrawTreasury,
+    )?;
+
+    assert_not_paused(auction_house, PAUSE_WITHDRAW)?;
+
+    let is_native = treasury_mint.key() == spl_token::native_mint::id();
+    let auction_house_seeds = [
+        PREFIX.as_bytes(),
+        auction_house.creator.as_ref(),
+        auction_house.treasury_mint.as_ref(),
+        &[auction_house.bump],
+    ];
+
+    let ah_key = auction_house.key();
+    let auction_house_treasury_seeds = [
+        PREFIX.as_bytes(),
+        ah_key.as_ref(),
+        TREASURY.as_bytes(),
+        &[auction_house.treasury_bump],
+    ];
+    if !is_native {
+        transfer(
+            token_program,
+            auction_house_treasury,
+            treasury_mint,
+            treasury_withdrawal_destination,
+            &auction_house.to_account_info(),
+            amount,
+            &[&auction_house_seeds],
+        )?;
+    } else {
+        invoke_signed(
+            &system_instruction::transfer(
+                &auction_house_treasury.key(),
+                &treasury_withdrawal_destination.key(),
+                amount,
+            ),
+            &[
+                auction_house_treasury.to_account_info(),
+                treasury_withdrawal_destination.to_account_info(),
+                system_program.to_account_info(),
+            ],
+            &[&auction_house_treasury_seeds],
+        )?;
+    }
+
+    emit_ah_event(
+        AhEventKind::WithdrawFromTreasury,
+        ah_key,
+        None,
+        amount,
+        vec![
+            treasury_withdrawal_destination.key(),
+            auctioneer_authority.key(),
+        ],
+    )?;
+
+    Ok(())
+}
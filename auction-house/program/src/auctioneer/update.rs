@@ -1,6 +1,9 @@
 use anchor_lang::prelude::*;
 
-use crate::{constants::*, errors::AuctionHouseError, AuctionHouse, Auctioneer, AuthorityScope};
+use crate::{
+    constants::*, errors::AuctionHouseError, utils::emit_ah_event, AhEventKind, AuctionHouse,
+    Auctioneer, AuthorityScope,
+};
 
 #[derive(Accounts)]
 pub struct UpdateAuctioneer<'info> {
@@ -63,5 +66,16 @@ pub fn update_auctioneer<'info>(
         auctioneer.scopes[scope as usize] = true;
     }
 
+    emit_ah_event(
+        AhEventKind::UpdateAuctioneer,
+        ctx.accounts.auction_house.key(),
+        None,
+        0,
+        vec![
+            ctx.accounts.authority.key(),
+            ctx.accounts.auctioneer_authority.key(),
+        ],
+    )?;
+
     Ok(())
 }
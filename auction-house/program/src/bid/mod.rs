@@ -6,12 +6,11 @@ use anchor_lang::{
     solana_program::{program::invoke, system_instruction},
     AnchorDeserialize,
 };
-use anchor_spl::token::{Mint, Token, TokenAccount};
-use solana_program::program_memory::sol_memset;
+use anchor_spl::token::TokenAccount;
 
 use crate::{
-    constants::*, errors::AuctionHouseError, utils::*, AuctionHouse, AuthorityScope,
-    TRADE_STATE_SIZE,
+    constants::*, errors::AuctionHouseError, order_book::*, token_interface::*, utils::*,
+    AhEventKind, AuctionHouse, AuthorityScope, SessionKey,
 };
 
 /// Accounts for the [`public_bid` handler](fn.public_bid.html).
@@ -32,7 +31,9 @@ pub struct PublicBuy<'info> {
     /// CHECK: Validated in public_bid_logic.
     transfer_authority: UncheckedAccount<'info>,
 
-    treasury_mint: Box<Account<'info, Mint>>,
+    /// CHECK: Checked in has_one constraint of the auction house.
+    /// Auction House instance treasury mint account.
+    treasury_mint: UncheckedAccount<'info>,
     token_account: Box<Account<'info, TokenAccount>>,
 
     /// CHECK: Validated in public_bid_logic.
@@ -95,15 +96,18 @@ pub struct PublicBuy<'info> {
     )]
     buyer_trade_state: UncheckedAccount<'info>,
 
-    token_program: Program<'info, Token>,
+    /// CHECK: Checked in constraint to be SPL Token or Token-2022.
+    /// Token program of the treasury mint.
+    #[account(constraint = is_treasury_token_program(token_program.key, &treasury_mint.to_account_info()) @ AuctionHouseError::InvalidTokenProgram)]
+    token_program: UncheckedAccount<'info>,
     system_program: Program<'info, System>,
     rent: Sysvar<'info, Rent>,
 }
 
 /// Create a bid on a specific SPL token.
 /// Public bids are specific to the token itself, rather than the auction, and remain open indefinitely until either the user closes it or the requirements for the bid are met and it is matched with a counter bid and closed as a transaction.
-pub fn public_bid(
-    ctx: Context<PublicBuy>,
+pub fn public_bid<'info>(
+    ctx: Context<'_, '_, '_, 'info, PublicBuy<'info>>,
     trade_state_bump: u8,
     escrow_payment_bump: u8,
     buyer_price: u64,
@@ -113,7 +117,7 @@ pub fn public_bid(
         ctx.accounts.wallet.to_owned(),
         ctx.accounts.payment_account.to_owned(),
         ctx.accounts.transfer_authority.to_owned(),
-        *ctx.accounts.treasury_mint.to_owned(),
+        ctx.accounts.treasury_mint.to_owned(),
         *ctx.accounts.token_account.to_owned(),
         ctx.accounts.metadata.to_owned(),
         ctx.accounts.escrow_payment_account.to_owned(),
@@ -129,6 +133,7 @@ pub fn public_bid(
         buyer_price,
         token_size,
         true,
+        ctx.remaining_accounts,
     )
 }
 
@@ -150,7 +155,9 @@ pub struct AuctioneerPublicBuy<'info> {
     /// CHECK: Validated in public_bid_logic.
     transfer_authority: UncheckedAccount<'info>,
 
-    treasury_mint: Box<Account<'info, Mint>>,
+    /// CHECK: Checked in has_one constraint of the auction house.
+    /// Auction House instance treasury mint account.
+    treasury_mint: UncheckedAccount<'info>,
 
     token_account: Box<Account<'info, TokenAccount>>,
 
@@ -229,15 +236,18 @@ pub struct AuctioneerPublicBuy<'info> {
     )]
     pub ah_auctioneer_pda: UncheckedAccount<'info>,
 
-    token_program: Program<'info, Token>,
+    /// CHECK: Checked in constraint to be SPL Token or Token-2022.
+    /// Token program of the treasury mint.
+    #[account(constraint = is_treasury_token_program(token_program.key, &treasury_mint.to_account_info()) @ AuctionHouseError::InvalidTokenProgram)]
+    token_program: UncheckedAccount<'info>,
     system_program: Program<'info, System>,
     rent: Sysvar<'info, Rent>,
 }
 
 /// Create a bid on a specific SPL token.
 /// Public bids are specific to the token itself, rather than the auction, and remain open indefinitely until either the user closes it or the requirements for the bid are met and it is matched with a counter bid and closed as a transaction.
-pub fn auctioneer_public_bid(
-    ctx: Context<AuctioneerPublicBuy>,
+pub fn auctioneer_public_bid<'info>(
+    ctx: Context<'_, '_, '_, 'info, AuctioneerPublicBuy<'info>>,
     trade_state_bump: u8,
     escrow_payment_bump: u8,
     buyer_price: u64,
@@ -247,7 +257,7 @@ pub fn auctioneer_public_bid(
         ctx.accounts.wallet.to_owned(),
         ctx.accounts.payment_account.to_owned(),
         ctx.accounts.transfer_authority.to_owned(),
-        *ctx.accounts.treasury_mint.to_owned(),
+        ctx.accounts.treasury_mint.to_owned(),
         *ctx.accounts.token_account.to_owned(),
         ctx.accounts.metadata.to_owned(),
         ctx.accounts.escrow_payment_account.to_owned(),
@@ -265,6 +275,7 @@ pub fn auctioneer_public_bid(
         buyer_price,
         token_size,
         true,
+        ctx.remaining_accounts,
     )
 }
 
@@ -289,8 +300,9 @@ pub struct Buy<'info> {
     /// SPL token account transfer authority.
     transfer_authority: UncheckedAccount<'info>,
 
+    /// CHECK: Checked in has_one constraint of the auction house.
     /// Auction House instance treasury mint account.
-    treasury_mint: Account<'info, Mint>,
+    treasury_mint: UncheckedAccount<'info>,
 
     /// SPL token account.
     token_account: Box<Account<'info, TokenAccount>>,
@@ -361,7 +373,10 @@ pub struct Buy<'info> {
     )]
     buyer_trade_state: UncheckedAccount<'info>,
 
-    token_program: Program<'info, Token>,
+    /// CHECK: Checked in constraint to be SPL Token or Token-2022.
+    /// Token program of the treasury mint.
+    #[account(constraint = is_treasury_token_program(token_program.key, &treasury_mint.to_account_info()) @ AuctionHouseError::InvalidTokenProgram)]
+    token_program: UncheckedAccount<'info>,
     system_program: Program<'info, System>,
     rent: Sysvar<'info, Rent>,
 }
@@ -394,9 +409,391 @@ pub fn private_bid<'info>(
         buyer_price,
         token_size,
         false,
+        ctx.remaining_accounts,
     )
 }
 
+/// Accounts for the [`private_bid_v2` handler](fn.private_bid_v2.html).
+#[derive(Accounts)]
+#[instruction(buyer_price: u64, token_size: u64)]
+pub struct BuyV2<'info> {
+    /// User wallet account.
+    wallet: Signer<'info>,
+
+    /// CHECK: Validated in bid_logic.
+    /// User SOL or SPL account to transfer funds from.
+    #[account(mut)]
+    payment_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in bid_logic.
+    /// SPL token account transfer authority.
+    transfer_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Checked in has_one constraint of the auction house.
+    /// Auction House instance treasury mint account.
+    treasury_mint: UncheckedAccount<'info>,
+
+    /// SPL token account.
+    token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Validated in bid_logic.
+    /// SPL token account metadata.
+    metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint with the canonical bump.
+    /// Buyer escrow payment account PDA.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref()
+        ],
+        bump
+    )]
+    escrow_payment_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in bid_logic.
+    /// Auction House instance authority account.
+    authority: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump = auction_house.bump,
+        has_one = authority,
+        has_one = treasury_mint,
+        has_one = auction_house_fee_account
+    )]
+    auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance fee account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump = auction_house.fee_payer_bump
+    )]
+    auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint with the canonical bump.
+    /// Buyer trade state PDA.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            treasury_mint.key().as_ref(),
+            token_account.mint.as_ref(),
+            buyer_price.to_le_bytes().as_ref(),
+            token_size.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    buyer_trade_state: UncheckedAccount<'info>,
+
+    /// CHECK: Checked in constraint to be SPL Token or Token-2022.
+    /// Token program of the treasury mint.
+    #[account(constraint = is_treasury_token_program(token_program.key, &treasury_mint.to_account_info()) @ AuctionHouseError::InvalidTokenProgram)]
+    token_program: UncheckedAccount<'info>,
+    system_program: Program<'info, System>,
+    rent: Sysvar<'info, Rent>,
+}
+
+/// Create a private bid, with the trade state and escrow payment bumps derived on chain instead of passed by the client.
+pub fn private_bid_v2<'info>(
+    ctx: Context<'_, '_, '_, 'info, BuyV2<'info>>,
+    buyer_price: u64,
+    token_size: u64,
+) -> Result<()> {
+    let trade_state_bump = *ctx
+        .bumps
+        .get("buyer_trade_state")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+    let escrow_payment_bump = *ctx
+        .bumps
+        .get("escrow_payment_account")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+
+    bid_logic(
+        ctx.accounts.wallet.to_owned(),
+        ctx.accounts.payment_account.to_owned(),
+        ctx.accounts.transfer_authority.to_owned(),
+        ctx.accounts.treasury_mint.to_owned(),
+        *ctx.accounts.token_account.to_owned(),
+        ctx.accounts.metadata.to_owned(),
+        ctx.accounts.escrow_payment_account.to_owned(),
+        ctx.accounts.authority.to_owned(),
+        *ctx.accounts.auction_house.to_owned(),
+        ctx.accounts.auction_house_fee_account.to_owned(),
+        ctx.accounts.buyer_trade_state.to_owned(),
+        ctx.accounts.token_program.to_owned(),
+        ctx.accounts.system_program.to_owned(),
+        ctx.accounts.rent.to_owned(),
+        trade_state_bump,
+        escrow_payment_bump,
+        buyer_price,
+        token_size,
+        false,
+        ctx.remaining_accounts,
+    )
+}
+
+/// Accounts for the [`session_private_bid` handler](fn.session_private_bid.html).
+#[derive(Accounts)]
+#[instruction(
+    trade_state_bump: u8,
+    escrow_payment_bump: u8,
+    buyer_price: u64,
+    token_size: u64
+)]
+pub struct SessionBuy<'info> {
+    /// Session key approved by the wallet. Pays for the trade state unless the authority signs.
+    #[account(mut)]
+    session_key: Signer<'info>,
+
+    /// CHECK: Validated by the has_one constraint on the session key account.
+    /// User wallet account.
+    wallet: UncheckedAccount<'info>,
+
+    /// Session key PDA recording the spend limit and expiry.
+    #[account(
+        mut,
+        seeds = [
+            SESSION_KEY.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref(),
+            session_key.key().as_ref()
+        ],
+        bump = session_key_account.bump,
+        has_one = wallet,
+        has_one = session_key,
+        has_one = auction_house
+    )]
+    session_key_account: Box<Account<'info, SessionKey>>,
+
+    /// CHECK: Checked in has_one constraint of the auction house.
+    /// Auction House instance treasury mint account.
+    treasury_mint: UncheckedAccount<'info>,
+
+    /// SPL token account.
+    token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Validated in session_private_bid.
+    /// SPL token account metadata.
+    metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Buyer escrow payment account PDA.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref()
+        ],
+        bump = escrow_payment_bump
+    )]
+    escrow_payment_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in session_private_bid.
+    /// Auction House instance authority account.
+    authority: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump = auction_house.bump,
+        has_one = authority,
+        has_one = treasury_mint,
+        has_one = auction_house_fee_account
+    )]
+    auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance fee account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump = auction_house.fee_payer_bump
+    )]
+    auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Buyer trade state PDA.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            treasury_mint.key().as_ref(),
+            token_account.mint.as_ref(),
+            buyer_price.to_le_bytes().as_ref(),
+            token_size.to_le_bytes().as_ref()
+        ],
+        bump = trade_state_bump
+    )]
+    buyer_trade_state: UncheckedAccount<'info>,
+
+    system_program: Program<'info, System>,
+    rent: Sysvar<'info, Rent>,
+}
+
+/// Create a private bid on behalf of a wallet using an approved session key.
+/// The bid is debited against the session key spend limit and must be covered by funds the wallet already deposited in escrow.
+pub fn session_private_bid<'info>(
+    ctx: Context<'_, '_, '_, 'info, SessionBuy<'info>>,
+    trade_state_bump: u8,
+    _escrow_payment_bump: u8,
+    buyer_price: u64,
+    token_size: u64,
+) -> Result<()> {
+    let session_key = &ctx.accounts.session_key;
+    let wallet = &ctx.accounts.wallet;
+    let session_key_account = &mut ctx.accounts.session_key_account;
+    let treasury_mint = &ctx.accounts.treasury_mint;
+    let token_account = &ctx.accounts.token_account;
+    let metadata = &ctx.accounts.metadata;
+    let escrow_payment_account = &ctx.accounts.escrow_payment_account;
+    let authority = &ctx.accounts.authority;
+    let auction_house = &ctx.accounts.auction_house;
+    let auction_house_fee_account = &ctx.accounts.auction_house_fee_account;
+    let buyer_trade_state = &ctx.accounts.buyer_trade_state;
+    let system_program = &ctx.accounts.system_program;
+    let rent = &ctx.accounts.rent;
+
+    assert_not_paused(auction_house, PAUSE_BUY)?;
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    if Clock::get()?.unix_timestamp >= session_key_account.expiry {
+        return Err(AuctionHouseError::SessionKeyExpired.into());
+    }
+
+    let spent = session_key_account
+        .spent
+        .checked_add(buyer_price)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+    if spent > session_key_account.max_spend {
+        return Err(AuctionHouseError::SessionKeySpendLimitExceeded.into());
+    }
+
+    assert_price_on_tick(auction_house, buyer_price)?;
+
+    assert_valid_trade_state(
+        &wallet.key(),
+        auction_house,
+        buyer_price,
+        token_size,
+        buyer_trade_state,
+        &token_account.mint.key(),
+        &token_account.key(),
+        trade_state_bump,
+    )?;
+    let order_book_summary =
+        find_order_book_summary(ctx.remaining_accounts, auction_house, &token_account.mint)?;
+    let auction_house_key = auction_house.key();
+    let seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        FEE_PAYER.as_bytes(),
+        &[auction_house.fee_payer_bump],
+    ];
+    let (fee_payer, fee_seeds) = get_fee_payer(
+        authority,
+        auction_house,
+        session_key.to_account_info(),
+        auction_house_fee_account.to_account_info(),
+        &seeds,
+    )?;
+
+    // The session key cannot move funds out of the wallet, so the escrow has to cover the bid already.
+    let is_native = treasury_mint.key() == spl_token::native_mint::id();
+    let escrow_balance = if is_native {
+        escrow_payment_account
+            .lamports()
+            .saturating_sub(rent.minimum_balance(escrow_payment_account.data_len()))
+    } else {
+        unpack_token_account(escrow_payment_account)?.amount
+    };
+    if escrow_balance < buyer_price {
+        return Err(AuctionHouseError::InsufficientEscrowForSessionBid.into());
+    }
+
+    assert_metadata_valid(metadata, token_account)?;
+
+    let ts_info = buyer_trade_state.to_account_info();
+    if ts_info.data_is_empty() {
+        let wallet_key = wallet.key();
+        let token_account_key = token_account.key();
+        create_or_allocate_account_raw(
+            crate::id(),
+            &ts_info,
+            &rent.to_account_info(),
+            system_program,
+            &fee_payer,
+            SESSION_TRADE_STATE_SIZE,
+            fee_seeds,
+            &[
+                PREFIX.as_bytes(),
+                wallet_key.as_ref(),
+                auction_house_key.as_ref(),
+                token_account_key.as_ref(),
+                auction_house.treasury_mint.as_ref(),
+                token_account.mint.as_ref(),
+                &buyer_price.to_le_bytes(),
+                &token_size.to_le_bytes(),
+                &[trade_state_bump],
+            ],
+        )?;
+        write_trade_state(&ts_info, trade_state_bump, fee_payer.key)?;
+        write_session_key(&ts_info, &session_key.key())?;
+
+        record_order_opened(
+            order_book_summary,
+            &auction_house_key,
+            &token_account.mint,
+            OrderSide::Bid,
+            buyer_price,
+        )?;
+
+        // Only debit the spend limit for new bids so the same bid can be resent.
+        session_key_account.spent = spent;
+    }
+
+    emit_ah_event(
+        AhEventKind::Bid,
+        auction_house_key,
+        Some(token_account.mint),
+        buyer_price,
+        vec![wallet.key(), session_key.key()],
+    )?;
+
+    Ok(())
+}
+
 /// Accounts for the [`auctioneer_private_bid` handler](fn.auctioneer_private_bid.html).
 #[derive(Accounts)]
 #[instruction(
@@ -418,8 +815,9 @@ pub struct AuctioneerBuy<'info> {
     /// SPL token account transfer authority.
     transfer_authority: UncheckedAccount<'info>,
 
+    /// CHECK: Checked in has_one constraint of the auction house.
     /// Auction House instance treasury mint account.
-    treasury_mint: Box<Account<'info, Mint>>,
+    treasury_mint: UncheckedAccount<'info>,
 
     /// SPL token account.
     token_account: Box<Account<'info, TokenAccount>>,
@@ -505,7 +903,10 @@ pub struct AuctioneerBuy<'info> {
     )]
     pub ah_auctioneer_pda: UncheckedAccount<'info>,
 
-    token_program: Program<'info, Token>,
+    /// CHECK: Checked in constraint to be SPL Token or Token-2022.
+    /// Token program of the treasury mint.
+    #[account(constraint = is_treasury_token_program(token_program.key, &treasury_mint.to_account_info()) @ AuctionHouseError::InvalidTokenProgram)]
+    token_program: UncheckedAccount<'info>,
     system_program: Program<'info, System>,
     rent: Sysvar<'info, Rent>,
 }
@@ -522,7 +923,7 @@ pub fn auctioneer_private_bid<'info>(
         ctx.accounts.wallet.to_owned(),
         ctx.accounts.payment_account.to_owned(),
         ctx.accounts.transfer_authority.to_owned(),
-        *ctx.accounts.treasury_mint.to_owned(),
+        ctx.accounts.treasury_mint.to_owned(),
         *ctx.accounts.token_account.to_owned(),
         ctx.accounts.metadata.to_owned(),
         ctx.accounts.escrow_payment_account.to_owned(),
@@ -540,6 +941,7 @@ pub fn auctioneer_private_bid<'info>(
         buyer_price,
         token_size,
         false,
+        ctx.remaining_accounts,
     )
 }
 
@@ -548,7 +950,7 @@ pub fn bid_logic<'info>(
     wallet: Signer<'info>,
     payment_account: UncheckedAccount<'info>,
     transfer_authority: UncheckedAccount<'info>,
-    treasury_mint: Account<'info, Mint>,
+    treasury_mint: UncheckedAccount<'info>,
     token_account: Account<'info, TokenAccount>,
     metadata: UncheckedAccount<'info>,
     escrow_payment_account: UncheckedAccount<'info>,
@@ -556,7 +958,7 @@ pub fn bid_logic<'info>(
     auction_house: Account<'info, AuctionHouse>,
     auction_house_fee_account: UncheckedAccount<'info>,
     buyer_trade_state: UncheckedAccount<'info>,
-    token_program: Program<'info, Token>,
+    token_program: UncheckedAccount<'info>,
     system_program: Program<'info, System>,
     rent: Sysvar<'info, Rent>,
     trade_state_bump: u8,
@@ -564,12 +966,17 @@ pub fn bid_logic<'info>(
     buyer_price: u64,
     token_size: u64,
     public: bool,
+    remaining_accounts: &[AccountInfo<'info>],
 ) -> Result<()> {
+    assert_not_paused(&auction_house, PAUSE_BUY)?;
+
     // If it has an auctioneer authority delegated must use auctioneer_* handler.
     if auction_house.has_auctioneer {
         return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
     }
 
+    assert_price_on_tick(&auction_house, buyer_price)?;
+
     assert_valid_trade_state(
         &wallet.key(),
         &auction_house,
@@ -580,6 +987,8 @@ pub fn bid_logic<'info>(
         &token_account.key(),
         trade_state_bump,
     )?;
+    let order_book_summary =
+        find_order_book_summary(remaining_accounts, &auction_house, &token_account.mint)?;
     let auction_house_key = auction_house.key();
     let seeds = [
         PREFIX.as_bytes(),
@@ -645,28 +1054,21 @@ pub fn bid_logic<'info>(
             )?;
         }
     } else {
-        let escrow_payment_loaded: spl_token::state::Account =
-            assert_initialized(&escrow_payment_account)?;
+        let escrow_payment_loaded = unpack_token_account(&escrow_payment_account)?;
 
         if escrow_payment_loaded.amount < buyer_price {
             let diff = buyer_price
                 .checked_sub(escrow_payment_loaded.amount)
                 .ok_or(AuctionHouseError::NumericalOverflow)?;
-            invoke(
-                &spl_token::instruction::transfer(
-                    &token_program.key(),
-                    &payment_account.key(),
-                    &escrow_payment_account.key(),
-                    &transfer_authority.key(),
-                    &[],
-                    diff,
-                )?,
-                &[
-                    transfer_authority.to_account_info(),
-                    payment_account.to_account_info(),
-                    escrow_payment_account.to_account_info(),
-                    token_program.to_account_info(),
-                ],
+            // Top up by enough for the escrow to be credited the difference after any transfer fee.
+            transfer(
+                &token_program,
+                &payment_account,
+                &treasury_mint,
+                &escrow_payment_account,
+                &transfer_authority,
+                amount_before_transfer_fee(&treasury_mint, diff)?,
+                &[],
             )?;
         }
     }
@@ -683,7 +1085,7 @@ pub fn bid_logic<'info>(
                 &rent.to_account_info(),
                 &system_program,
                 &fee_payer,
-                TRADE_STATE_SIZE,
+                TRADE_STATE_WITH_RENT_PAYER_SIZE,
                 fee_seeds,
                 &[
                     PREFIX.as_bytes(),
@@ -703,7 +1105,7 @@ pub fn bid_logic<'info>(
                 &rent.to_account_info(),
                 &system_program,
                 &fee_payer,
-                TRADE_STATE_SIZE,
+                TRADE_STATE_WITH_RENT_PAYER_SIZE,
                 fee_seeds,
                 &[
                     PREFIX.as_bytes(),
@@ -718,12 +1120,29 @@ pub fn bid_logic<'info>(
                 ],
             )?;
         }
-        sol_memset(
-            *ts_info.try_borrow_mut_data()?,
-            trade_state_bump,
-            TRADE_STATE_SIZE,
-        );
+        write_trade_state(&ts_info, trade_state_bump, fee_payer.key)?;
+
+        record_order_opened(
+            order_book_summary,
+            &auction_house_key,
+            &token_account.mint,
+            OrderSide::Bid,
+            buyer_price,
+        )?;
     }
+
+    emit_ah_event(
+        if public {
+            AhEventKind::PublicBid
+        } else {
+            AhEventKind::Bid
+        },
+        auction_house_key,
+        Some(token_account.mint),
+        buyer_price,
+        vec![wallet.key()],
+    )?;
+
     // Allow The same bid to be sent with no issues
     Ok(())
 }
@@ -734,7 +1153,7 @@ pub fn auctioneer_bid_logic<'info>(
     wallet: Signer<'info>,
     payment_account: UncheckedAccount<'info>,
     transfer_authority: UncheckedAccount<'info>,
-    treasury_mint: Account<'info, Mint>,
+    treasury_mint: UncheckedAccount<'info>,
     token_account: Account<'info, TokenAccount>,
     metadata: UncheckedAccount<'info>,
     escrow_payment_account: UncheckedAccount<'info>,
@@ -744,7 +1163,7 @@ pub fn auctioneer_bid_logic<'info>(
     authority: UncheckedAccount<'info>,
     auctioneer_authority: Signer<'info>,
     ah_auctioneer_pda: UncheckedAccount<'info>,
-    token_program: Program<'info, Token>,
+    token_program: UncheckedAccount<'info>,
     system_program: Program<'info, System>,
     rent: Sysvar<'info, Rent>,
     trade_state_bump: u8,
@@ -752,9 +1171,12 @@ pub fn auctioneer_bid_logic<'info>(
     buyer_price: u64,
     token_size: u64,
     public: bool,
+    remaining_accounts: &[AccountInfo<'info>],
 ) -> Result<()> {
     let ah_auctioneer_pda_account = ah_auctioneer_pda.to_account_info();
 
+    assert_not_paused(auction_house, PAUSE_BUY)?;
+
     if !auction_house.has_auctioneer {
         return Err(AuctionHouseError::NoAuctioneerProgramSet.into());
     }
@@ -766,6 +1188,8 @@ pub fn auctioneer_bid_logic<'info>(
         AuthorityScope::Buy,
     )?;
 
+    assert_price_on_tick(auction_house, buyer_price)?;
+
     assert_valid_trade_state(
         &wallet.key(),
         auction_house,
@@ -776,6 +1200,8 @@ pub fn auctioneer_bid_logic<'info>(
         &token_account.key(),
         trade_state_bump,
     )?;
+    let order_book_summary =
+        find_order_book_summary(remaining_accounts, auction_house, &token_account.mint)?;
     let auction_house_key = auction_house.key();
     let seeds = [
         PREFIX.as_bytes(),
@@ -841,28 +1267,21 @@ pub fn auctioneer_bid_logic<'info>(
             )?;
         }
     } else {
-        let escrow_payment_loaded: spl_token::state::Account =
-            assert_initialized(&escrow_payment_account)?;
+        let escrow_payment_loaded = unpack_token_account(&escrow_payment_account)?;
 
         if escrow_payment_loaded.amount < buyer_price {
             let diff = buyer_price
                 .checked_sub(escrow_payment_loaded.amount)
                 .ok_or(AuctionHouseError::NumericalOverflow)?;
-            invoke(
-                &spl_token::instruction::transfer(
-                    &token_program.key(),
-                    &payment_account.key(),
-                    &escrow_payment_account.key(),
-                    &transfer_authority.key(),
-                    &[],
-                    diff,
-                )?,
-                &[
-                    transfer_authority.to_account_info(),
-                    payment_account.to_account_info(),
-                    escrow_payment_account.to_account_info(),
-                    token_program.to_account_info(),
-                ],
+            // Top up by enough for the escrow to be credited the difference after any transfer fee.
+            transfer(
+                &token_program,
+                &payment_account,
+                &treasury_mint,
+                &escrow_payment_account,
+                &transfer_authority,
+                amount_before_transfer_fee(&treasury_mint, diff)?,
+                &[],
             )?;
         }
     }
@@ -879,7 +1298,7 @@ pub fn auctioneer_bid_logic<'info>(
                 &rent.to_account_info(),
                 &system_program,
                 &fee_payer,
-                TRADE_STATE_SIZE,
+                TRADE_STATE_WITH_RENT_PAYER_SIZE,
                 fee_seeds,
                 &[
                     PREFIX.as_bytes(),
@@ -899,7 +1318,7 @@ pub fn auctioneer_bid_logic<'info>(
                 &rent.to_account_info(),
                 &system_program,
                 &fee_payer,
-                TRADE_STATE_SIZE,
+                TRADE_STATE_WITH_RENT_PAYER_SIZE,
                 fee_seeds,
                 &[
                     PREFIX.as_bytes(),
@@ -914,12 +1333,29 @@ pub fn auctioneer_bid_logic<'info>(
                 ],
             )?;
         }
-        sol_memset(
-            *ts_info.try_borrow_mut_data()?,
-            trade_state_bump,
-            TRADE_STATE_SIZE,
-        );
+        write_trade_state(&ts_info, trade_state_bump, fee_payer.key)?;
+
+        record_order_opened(
+            order_book_summary,
+            &auction_house_key,
+            &token_account.mint,
+            OrderSide::Bid,
+            buyer_price,
+        )?;
     }
+
+    emit_ah_event(
+        if public {
+            AhEventKind::PublicBid
+        } else {
+            AhEventKind::Bid
+        },
+        auction_house_key,
+        Some(token_account.mint),
+        buyer_price,
+        vec![wallet.key()],
+    )?;
+
     // Allow The same bid to be sent with no issues
     Ok(())
 }
@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::*, AuctionHouse, BundleListing};
+
+/// Accounts for the [`cancel_bundle_listing` handler](auction_house/fn.cancel_bundle_listing.html).
+#[derive(Accounts)]
+pub struct CancelBundleListing<'info> {
+    /// Seller that created the bundle listing, receives its lamports back.
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+
+    /// Bundle listing PDA of the seller, closed back to the seller.
+    #[account(
+        mut,
+        close = seller,
+        seeds = [
+            PREFIX.as_bytes(),
+            seller.key().as_ref(),
+            auction_house.key().as_ref(),
+            BUNDLE_LISTING.as_bytes(),
+            &bundle_listing.bundle_id.to_le_bytes()
+        ],
+        bump=bundle_listing.bump,
+        has_one=seller,
+        has_one=auction_house
+    )]
+    pub bundle_listing: Account<'info, BundleListing>,
+}
+
+/// Close the bundle listing. The bundled items stay listed on their own until canceled with `cancel`.
+pub fn cancel_bundle_listing<'info>(
+    _ctx: Context<'_, '_, '_, 'info, CancelBundleListing<'info>>,
+) -> Result<()> {
+    Ok(())
+}
@@ -0,0 +1,125 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::*,
+    errors::AuctionHouseError,
+    utils::{assert_not_paused, read_custodial_identity},
+    AuctionHouse, BundleItem, BundleListing,
+};
+
+/// Accounts for the [`create_bundle_listing` handler](auction_house/fn.create_bundle_listing.html).
+#[derive(Accounts)]
+#[instruction(bundle_id: u64)]
+pub struct CreateBundleListing<'info> {
+    /// Seller wallet owning every listed item, pays for the bundle listing.
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+
+    /// Bundle listing PDA recording the items sold together.
+    #[account(
+        init,
+        payer = seller,
+        space = BUNDLE_LISTING_SIZE,
+        seeds = [
+            PREFIX.as_bytes(),
+            seller.key().as_ref(),
+            auction_house.key().as_ref(),
+            BUNDLE_LISTING.as_bytes(),
+            &bundle_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub bundle_listing: Account<'info, BundleListing>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Group seller listings created by `sell` into a lot sold for the sum of their prices. The seller trade states are
+/// passed as remaining accounts in `items` order.
+pub fn create_bundle_listing<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreateBundleListing<'info>>,
+    bundle_id: u64,
+    items: Vec<BundleItem>,
+) -> Result<()> {
+    let seller = &ctx.accounts.seller;
+    let auction_house = &ctx.accounts.auction_house;
+
+    assert_not_paused(auction_house, PAUSE_SELL)?;
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    if items.len() < 2
+        || items.len() > MAX_BUNDLE_ITEMS
+        || ctx.remaining_accounts.len() != items.len()
+    {
+        return Err(AuctionHouseError::InvalidBundleListing.into());
+    }
+
+    let mut price: u64 = 0;
+    for (i, (item, trade_state)) in items.iter().zip(ctx.remaining_accounts).enumerate() {
+        if item.trade_state != trade_state.key()
+            || items[..i]
+                .iter()
+                .any(|other| other.trade_state == item.trade_state)
+            || trade_state.data_is_empty()
+        {
+            return Err(AuctionHouseError::InvalidBundleListing.into());
+        }
+
+        // Only listings of the seller's own token account can be bundled, not public bids.
+        let (trade_state_key, trade_state_bump) = Pubkey::find_program_address(
+            &[
+                PREFIX.as_bytes(),
+                seller.key().as_ref(),
+                auction_house.key().as_ref(),
+                item.token_account.as_ref(),
+                auction_house.treasury_mint.as_ref(),
+                item.token_mint.as_ref(),
+                &item.price.to_le_bytes(),
+                &item.token_size.to_le_bytes(),
+            ],
+            ctx.program_id,
+        );
+        if trade_state_key != item.trade_state
+            || trade_state.try_borrow_data()?[0] != trade_state_bump
+        {
+            return Err(AuctionHouseError::InvalidBundleListing.into());
+        }
+
+        // Custodial listings need the user approval only execute_sale checks.
+        if read_custodial_identity(&trade_state.try_borrow_data()?).is_some() {
+            return Err(AuctionHouseError::InvalidCustodialApproval.into());
+        }
+
+        price = price
+            .checked_add(item.price)
+            .ok_or(AuctionHouseError::NumericalOverflow)?;
+    }
+
+    let bundle_listing = &mut ctx.accounts.bundle_listing;
+    bundle_listing.auction_house = auction_house.key();
+    bundle_listing.seller = seller.key();
+    bundle_listing.bundle_id = bundle_id;
+    bundle_listing.price = price;
+    bundle_listing.items = items;
+    bundle_listing.bump = *ctx
+        .bumps
+        .get("bundle_listing")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+
+    Ok(())
+}
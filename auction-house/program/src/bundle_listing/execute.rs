@@ -0,0 +1,523 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program::invoke_signed, system_instruction},
+};
+use anchor_spl::{associated_token::AssociatedToken, token::Token};
+use solana_program::program_memory::sol_memset;
+
+use crate::{
+    compliance::enforce_compliance, constants::*, errors::AuctionHouseError, order_book::*,
+    revenue_share::accrue_revenue_share, token_interface::*, utils::*, AhEventKind, AuctionHouse,
+    BundleListing,
+};
+
+/// Accounts for the [`execute_bundle_sale` handler](auction_house/fn.execute_bundle_sale.html).
+#[derive(Accounts)]
+#[instruction(escrow_payment_bump: u8, program_as_signer_bump: u8)]
+pub struct ExecuteBundleSale<'info> {
+    /// Buyer user wallet account.
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    /// CHECK: Checked in has_one constraint of the bundle listing.
+    /// Seller user wallet account.
+    #[account(mut)]
+    pub seller: UncheckedAccount<'info>,
+
+    /// CHECK: Checked in has_one constraint.
+    /// Auction House treasury mint account.
+    pub treasury_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Buyer escrow payment account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            buyer.key().as_ref()
+        ],
+        bump=escrow_payment_bump
+    )]
+    pub escrow_payment_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_bundle_sale.
+    /// Seller SOL or SPL account to receive payment at.
+    #[account(mut)]
+    pub seller_payment_receipt_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_bundle_sale.
+    /// Auction House instance authority.
+    pub authority: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority,
+        has_one=treasury_mint,
+        has_one=auction_house_treasury,
+        has_one=auction_house_fee_account
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance fee account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump=auction_house.fee_payer_bump
+    )]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance treasury account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            TREASURY.as_bytes()
+        ],
+        bump=auction_house.treasury_bump
+    )]
+    pub auction_house_treasury: UncheckedAccount<'info>,
+
+    /// Bundle listing PDA of the seller, closed back to the seller once sold.
+    #[account(
+        mut,
+        close = seller,
+        seeds = [
+            PREFIX.as_bytes(),
+            seller.key().as_ref(),
+            auction_house.key().as_ref(),
+            BUNDLE_LISTING.as_bytes(),
+            &bundle_listing.bundle_id.to_le_bytes()
+        ],
+        bump=bundle_listing.bump,
+        has_one=seller,
+        has_one=auction_house
+    )]
+    pub bundle_listing: Box<Account<'info, BundleListing>>,
+
+    /// Token program of the tokens sold and, unless Token-2022 owning it leads the remaining accounts, of the treasury mint.
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub ata_program: Program<'info, AssociatedToken>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(seeds=[PREFIX.as_bytes(), SIGNER.as_bytes()], bump=program_as_signer_bump)]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Buy every item of a bundle listing for `buyer_price` paid from the buyer escrow, or none of them. For each item the
+/// remaining accounts hold its seller trade state, token account, token mint, metadata and buyer receipt token account,
+/// followed by its creators as in `execute_sale`. Creator and Auction House fees are computed on the item price.
+pub fn execute_bundle_sale<'info>(
+    ctx: Context<'_, '_, '_, 'info, ExecuteBundleSale<'info>>,
+    escrow_payment_bump: u8,
+    program_as_signer_bump: u8,
+    buyer_price: u64,
+) -> Result<()> {
+    let buyer = &ctx.accounts.buyer;
+    let seller = &ctx.accounts.seller;
+    let treasury_mint = &ctx.accounts.treasury_mint;
+    let escrow_payment_account = &ctx.accounts.escrow_payment_account;
+    let seller_payment_receipt_account = &ctx.accounts.seller_payment_receipt_account;
+    let authority = &ctx.accounts.authority;
+    let auction_house = &ctx.accounts.auction_house;
+    let auction_house_fee_account = &ctx.accounts.auction_house_fee_account;
+    let auction_house_treasury = &ctx.accounts.auction_house_treasury;
+    let bundle_listing = &ctx.accounts.bundle_listing;
+    let token_program = &ctx.accounts.token_program;
+    let system_program = &ctx.accounts.system_program;
+    let ata_program = &ctx.accounts.ata_program;
+    let program_as_signer = &ctx.accounts.program_as_signer;
+    let rent = &ctx.accounts.rent;
+
+    let escrow_clone = escrow_payment_account.to_account_info();
+    let auction_house_clone = auction_house.to_account_info();
+    let ata_clone = ata_program.to_account_info();
+    let token_clone = token_program.to_account_info();
+    let sys_clone = system_program.to_account_info();
+    let rent_clone = rent.to_account_info();
+    let treasury_clone = auction_house_treasury.to_account_info();
+
+    assert_not_paused(auction_house, PAUSE_EXECUTE_SALE)?;
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    if buyer_price != bundle_listing.price {
+        return Err(AuctionHouseError::BundlePriceMismatch.into());
+    }
+
+    let is_native = treasury_mint.key() == spl_token::native_mint::id();
+
+    let auction_house_key = auction_house.key();
+    let seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        FEE_PAYER.as_bytes(),
+        &[auction_house.fee_payer_bump],
+    ];
+
+    let (fee_payer, fee_payer_seeds) = get_fee_payer(
+        authority,
+        auction_house,
+        buyer.to_account_info(),
+        auction_house_fee_account.to_account_info(),
+        &seeds,
+    )?;
+    let fee_payer_clone = fee_payer.to_account_info();
+
+    // For native purchases, verify that the amount in escrow is sufficient to actually purchase the bundle.
+    // The fee payer makes up the shortfall up to the amount of rent for an empty account.
+    if is_native {
+        let diff = rent_checked_sub(escrow_payment_account.to_account_info(), buyer_price)?;
+        if diff != buyer_price {
+            let shortfall = std::cmp::min(
+                buyer_price
+                    .checked_sub(diff)
+                    .ok_or(AuctionHouseError::NumericalOverflow)?,
+                rent.minimum_balance(escrow_payment_account.data_len()),
+            );
+            invoke_signed(
+                &system_instruction::transfer(fee_payer.key, escrow_payment_account.key, shortfall),
+                &[
+                    fee_payer.to_account_info(),
+                    escrow_payment_account.to_account_info(),
+                    system_program.to_account_info(),
+                ],
+                &[fee_payer_seeds],
+            )?;
+        }
+    }
+
+    let wallet_key = buyer.key();
+    let escrow_signer_seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        wallet_key.as_ref(),
+        &[escrow_payment_bump],
+    ];
+
+    let ah_seeds = [
+        PREFIX.as_bytes(),
+        auction_house.creator.as_ref(),
+        auction_house.treasury_mint.as_ref(),
+        &[auction_house.bump],
+    ];
+
+    // with the native account, the escrow is its own owner,
+    // whereas with token, it is the auction house that is owner.
+    let signer_seeds_for_royalties = if is_native {
+        escrow_signer_seeds
+    } else {
+        ah_seeds
+    };
+
+    let program_as_signer_seeds = [
+        PREFIX.as_bytes(),
+        SIGNER.as_bytes(),
+        &[program_as_signer_bump],
+    ];
+
+    let remaining_accounts = &mut ctx.remaining_accounts.iter();
+    let treasury_token_program =
+        next_treasury_token_program(remaining_accounts, treasury_mint, &token_clone, is_native)?;
+
+    // Trade state rent goes back to whoever funded it, not whoever pays for the sale. A sponsor who paid for a
+    // listing is passed among the remaining accounts.
+    let seller_info = seller.to_account_info();
+    let auction_house_fee_account_info = auction_house_fee_account.to_account_info();
+    let seller_rent_recipient_candidates: Vec<&AccountInfo<'info>> =
+        [&seller_info, &auction_house_fee_account_info]
+            .into_iter()
+            .chain(ctx.remaining_accounts)
+            .collect();
+
+    let mut seller_proceeds: u64 = 0;
+    let mut auction_house_fees_paid: u64 = 0;
+    for item in bundle_listing.items.iter() {
+        let seller_trade_state = next_account_info(remaining_accounts)?;
+        let token_account = next_account_info(remaining_accounts)?;
+        let token_mint = next_account_info(remaining_accounts)?;
+        let metadata = next_account_info(remaining_accounts)?;
+        let buyer_receipt_token_account = next_account_info(remaining_accounts)?;
+
+        // An item sold or canceled on its own since the bundle was listed fails the whole bundle.
+        if seller_trade_state.key() != item.trade_state
+            || token_account.key() != item.token_account
+            || token_mint.key() != item.token_mint
+            || seller_trade_state.data_is_empty()
+        {
+            return Err(AuctionHouseError::InvalidBundleListing.into());
+        }
+
+        // Custodial listings need the user approval only execute_sale checks.
+        if read_custodial_identity(&seller_trade_state.try_borrow_data()?).is_some() {
+            return Err(AuctionHouseError::InvalidCustodialApproval.into());
+        }
+
+        let token_account_data = assert_is_ata(token_account, &seller.key(), &item.token_mint)?;
+        if get_delegate_from_token_account(token_account)? != Some(program_as_signer.key()) {
+            msg!("No delegate detected on token account.");
+            return Err(AuctionHouseError::BothPartiesNeedToAgreeToSale.into());
+        }
+        if token_account_data.amount < item.token_size {
+            return Err(AuctionHouseError::NotEnoughTokensAvailableForPurchase.into());
+        }
+
+        assert_derivation(
+            &mpl_token_metadata::id(),
+            metadata,
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                item.token_mint.as_ref(),
+            ],
+        )?;
+        if metadata.data_is_empty() {
+            return Err(AuctionHouseError::MetadataDoesntExist.into());
+        }
+
+        if auction_house.enforce_royalties {
+            assert_royalties_enforced(
+                remaining_accounts.as_slice(),
+                metadata,
+                item.price,
+                is_native,
+            )?;
+        }
+
+        let leftover_after_royalties = pay_creator_fees(
+            remaining_accounts,
+            metadata,
+            &escrow_clone,
+            &auction_house_clone,
+            &fee_payer_clone,
+            treasury_mint,
+            &ata_clone,
+            &treasury_token_program,
+            &sys_clone,
+            &rent_clone,
+            &signer_seeds_for_royalties,
+            fee_payer_seeds,
+            item.price,
+            is_native,
+        )?;
+
+        let auction_house_fee_paid = pay_auction_house_fees(
+            auction_house,
+            &treasury_clone,
+            &escrow_clone,
+            treasury_mint,
+            &treasury_token_program,
+            &sys_clone,
+            &signer_seeds_for_royalties,
+            item.price,
+            is_native,
+        )?;
+
+        seller_proceeds = leftover_after_royalties
+            .checked_sub(auction_house_fee_paid)
+            .and_then(|proceeds| proceeds.checked_add(seller_proceeds))
+            .ok_or(AuctionHouseError::NumericalOverflow)?;
+        auction_house_fees_paid = auction_house_fees_paid
+            .checked_add(auction_house_fee_paid)
+            .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+        if buyer_receipt_token_account.data_is_empty() {
+            make_ata(
+                buyer_receipt_token_account.to_account_info(),
+                buyer.to_account_info(),
+                token_mint.to_account_info(),
+                fee_payer.to_account_info(),
+                ata_program.to_account_info(),
+                token_program.to_account_info(),
+                system_program.to_account_info(),
+                rent.to_account_info(),
+                fee_payer_seeds,
+            )?;
+        }
+
+        let buyer_rec_acct =
+            assert_is_ata(buyer_receipt_token_account, &buyer.key(), &item.token_mint)?;
+
+        // make sure you cant get rugged
+        if buyer_rec_acct.delegate.is_some() {
+            return Err(AuctionHouseError::BuyerATACannotHaveDelegate.into());
+        }
+
+        invoke_signed(
+            &spl_token::instruction::transfer(
+                token_program.key,
+                &token_account.key(),
+                &buyer_receipt_token_account.key(),
+                &program_as_signer.key(),
+                &[],
+                item.token_size,
+            )?,
+            &[
+                token_account.clone(),
+                buyer_receipt_token_account.clone(),
+                program_as_signer.to_account_info(),
+                token_clone.clone(),
+            ],
+            &[&program_as_signer_seeds],
+        )?;
+
+        record_order_closed(
+            find_order_book_summary(ctx.remaining_accounts, auction_house, &item.token_mint)?,
+            &auction_house_key,
+            &item.token_mint,
+            OrderSide::Ask,
+            item.price,
+        )?;
+
+        let seller_rent_recipient = trade_state_rent_recipient(
+            &seller_trade_state.try_borrow_data()?,
+            &seller_rent_recipient_candidates,
+            &fee_payer_clone,
+        )?;
+        let curr_seller_lamp = seller_trade_state.lamports();
+        **seller_trade_state.lamports.borrow_mut() = 0;
+        let seller_ts_len = seller_trade_state.data_len();
+        sol_memset(*seller_trade_state.try_borrow_mut_data()?, 0, seller_ts_len);
+        **seller_rent_recipient.lamports.borrow_mut() = seller_rent_recipient
+            .lamports()
+            .checked_add(curr_seller_lamp)
+            .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+        emit_ah_event(
+            AhEventKind::ExecuteSale,
+            auction_house_key,
+            Some(item.token_mint),
+            item.price,
+            vec![buyer.key(), seller.key()],
+        )?;
+    }
+
+    if !is_native {
+        if seller_payment_receipt_account.data_is_empty() {
+            make_ata(
+                seller_payment_receipt_account.to_account_info(),
+                seller.to_account_info(),
+                treasury_mint.to_account_info(),
+                fee_payer.to_account_info(),
+                ata_program.to_account_info(),
+                treasury_token_program.clone(),
+                system_program.to_account_info(),
+                rent.to_account_info(),
+                fee_payer_seeds,
+            )?;
+        }
+
+        let seller_rec_acct = assert_is_ata(
+            &seller_payment_receipt_account.to_account_info(),
+            &seller.key(),
+            &treasury_mint.key(),
+        )?;
+
+        // make sure you cant get rugged
+        if seller_rec_acct.delegate.is_some() {
+            return Err(AuctionHouseError::SellerATACannotHaveDelegate.into());
+        }
+
+        transfer(
+            &treasury_token_program,
+            escrow_payment_account,
+            treasury_mint,
+            seller_payment_receipt_account,
+            &auction_house.to_account_info(),
+            seller_proceeds,
+            &[&ah_seeds],
+        )?;
+    } else {
+        assert_keys_equal(seller_payment_receipt_account.key(), seller.key())?;
+        invoke_signed(
+            &system_instruction::transfer(
+                escrow_payment_account.key,
+                seller_payment_receipt_account.key,
+                seller_proceeds,
+            ),
+            &[
+                escrow_payment_account.to_account_info(),
+                seller_payment_receipt_account.to_account_info(),
+                system_program.to_account_info(),
+            ],
+            &[&escrow_signer_seeds],
+        )?;
+    }
+
+    // The linked wallet registry entries of the buyer and seller follow the item accounts when
+    // the wash trading guard is on.
+    if auction_house.wash_trading_guard {
+        let buyer_linked_wallet = next_account_info(remaining_accounts)?;
+        let seller_linked_wallet = next_account_info(remaining_accounts)?;
+        assert_not_self_trade(
+            &auction_house_key,
+            &buyer.key(),
+            &seller.key(),
+            buyer_linked_wallet,
+            seller_linked_wallet,
+        )?;
+    }
+
+    // The revenue share account and, for SPL treasuries, its token account follow.
+    if auction_house.has_revenue_share {
+        let revenue_share_info = next_account_info(remaining_accounts)?;
+        let revenue_share_vault = if is_native {
+            revenue_share_info
+        } else {
+            next_account_info(remaining_accounts)?
+        };
+        accrue_revenue_share(
+            auction_house,
+            &auction_house_treasury.to_account_info(),
+            revenue_share_info,
+            revenue_share_vault,
+            &treasury_token_program,
+            &system_program.to_account_info(),
+            auction_house_fees_paid,
+            is_native,
+        )?;
+    }
+
+    // The compliance config and the buyer and seller wallet volume PDAs come last.
+    if auction_house.has_compliance {
+        let compliance_config_info = next_account_info(remaining_accounts)?;
+        let buyer_wallet_volume = next_account_info(remaining_accounts)?;
+        let seller_wallet_volume = next_account_info(remaining_accounts)?;
+        enforce_compliance(
+            &auction_house_key,
+            compliance_config_info,
+            [
+                (&buyer.key(), buyer_wallet_volume),
+                (&seller.key(), seller_wallet_volume),
+            ],
+            &auction_house_fee_account.to_account_info(),
+            &system_program.to_account_info(),
+            &rent.to_account_info(),
+            &seeds,
+            buyer_price,
+        )?;
+    }
+
+    emit_if_fee_account_low(auction_house, &auction_house_fee_account.to_account_info());
+
+    Ok(())
+}
@@ -0,0 +1,6 @@
+pub mod cancel;
+pub mod create;
+pub mod execute;
+pub use cancel::*;
+pub use create::*;
+pub use execute::*;
@@ -1,7 +1,9 @@
 use anchor_lang::{prelude::*, solana_program::program::invoke, AnchorDeserialize};
 use solana_program::program_memory::sol_memset;
 
-use crate::{constants::*, errors::*, utils::*, AuctionHouse, AuthorityScope, *};
+use crate::{
+    constants::*, errors::*, order_book::*, utils::*, AhEventKind, AuctionHouse, AuthorityScope, *,
+};
 
 /// Accounts for the [`cancel` handler](auction_house/fn.cancel.html).
 #[derive(Accounts)]
@@ -155,7 +157,12 @@ pub fn cancel<'info>(
         return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
     }
 
-    cancel_logic(ctx.accounts, buyer_price, token_size)
+    cancel_logic(
+        ctx.accounts,
+        ctx.remaining_accounts,
+        buyer_price,
+        token_size,
+    )
 }
 
 pub fn auctioneer_cancel<'info>(
@@ -180,12 +187,175 @@ pub fn auctioneer_cancel<'info>(
 
     let mut accounts: Cancel<'info> = (*ctx.accounts).clone().into();
 
-    cancel_logic(&mut accounts, buyer_price, token_size)
+    cancel_logic(
+        &mut accounts,
+        ctx.remaining_accounts,
+        buyer_price,
+        token_size,
+    )
+}
+
+/// Accounts for the [`session_cancel` handler](auction_house/fn.session_cancel.html).
+#[derive(Accounts)]
+#[instruction(buyer_price: u64, token_size: u64)]
+pub struct SessionCancel<'info> {
+    /// Session key approved by the wallet, refunded the trade state rent if it paid for the bid.
+    #[account(mut)]
+    pub session_key: Signer<'info>,
+
+    /// CHECK: Validated by the has_one constraint on the session key account.
+    /// User wallet account, receives the trade state lamports if it paid for them.
+    #[account(mut)]
+    pub wallet: UncheckedAccount<'info>,
+
+    /// Session key PDA recording the spend limit and expiry, credited back the price of a canceled bid it placed.
+    #[account(
+        mut,
+        seeds = [
+            SESSION_KEY.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref(),
+            session_key.key().as_ref()
+        ],
+        bump=session_key_account.bump,
+        has_one=wallet,
+        has_one=session_key,
+        has_one=auction_house
+    )]
+    pub session_key_account: Box<Account<'info, SessionKey>>,
+
+    /// SPL token account containing the token of the sale to be canceled.
+    pub token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Token mint account of SPL token.
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance fee account, refunded the trade state rent if it paid for the bid.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump=auction_house.fee_payer_bump
+    )]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in session_cancel.
+    /// Trade state PDA account representing the bid or ask to be canceled.
+    #[account(mut)]
+    pub trade_state: UncheckedAccount<'info>,
+}
+
+/// Cancel a bid or ask on behalf of a wallet using an approved session key, returning the trade state lamports to whoever paid for them.
+/// The price of a bid the session key placed is credited back to the spend limit. The token delegate is left in place as only the wallet can revoke it.
+pub fn session_cancel<'info>(
+    ctx: Context<'_, '_, '_, 'info, SessionCancel<'info>>,
+    buyer_price: u64,
+    token_size: u64,
+) -> Result<()> {
+    let wallet = &ctx.accounts.wallet;
+    let session_key_account = &mut ctx.accounts.session_key_account;
+    let token_account = &ctx.accounts.token_account;
+    let token_mint = &ctx.accounts.token_mint;
+    let auction_house = &ctx.accounts.auction_house;
+    let trade_state = &ctx.accounts.trade_state;
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    if Clock::get()?.unix_timestamp >= session_key_account.expiry {
+        return Err(AuctionHouseError::SessionKeyExpired.into());
+    }
+
+    let ts_bump = trade_state.try_borrow_data()?[0];
+    assert_valid_trade_state(
+        &wallet.key(),
+        auction_house,
+        buyer_price,
+        token_size,
+        &trade_state.to_account_info(),
+        &token_account.mint.key(),
+        &token_account.key(),
+        ts_bump,
+    )?;
+    assert_keys_equal(token_mint.key(), token_account.mint)?;
+
+    // Asks are listed from the wallet's own token account, bids against someone else's.
+    let side = if token_account.owner == wallet.key() {
+        OrderSide::Ask
+    } else {
+        OrderSide::Bid
+    };
+    record_order_closed(
+        find_order_book_summary(ctx.remaining_accounts, auction_house, &token_account.mint)?,
+        &auction_house.key(),
+        &token_account.mint,
+        side,
+        buyer_price,
+    )?;
+
+    // Only bids the session key placed itself were debited, so only those are credited back to the spend limit.
+    if side == OrderSide::Bid
+        && read_session_key(&trade_state.try_borrow_data()?)
+            == Some(session_key_account.session_key)
+    {
+        session_key_account.spent = session_key_account.spent.saturating_sub(buyer_price);
+    }
+
+    // Rent goes back to whoever funded the trade state, the wallet, the session key or the Auction House.
+    let wallet_info = wallet.to_account_info();
+    let session_key_info = ctx.accounts.session_key.to_account_info();
+    let auction_house_fee_account_info = ctx.accounts.auction_house_fee_account.to_account_info();
+    let rent_recipient = trade_state_rent_recipient(
+        &trade_state.try_borrow_data()?,
+        &[
+            &wallet_info,
+            &session_key_info,
+            &auction_house_fee_account_info,
+        ],
+        &wallet_info,
+    )?;
+    let curr_lamp = trade_state.lamports();
+    **trade_state.lamports.borrow_mut() = 0;
+
+    **rent_recipient.lamports.borrow_mut() = rent_recipient
+        .lamports()
+        .checked_add(curr_lamp)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+    let ts_len = trade_state.data_len();
+    sol_memset(*trade_state.try_borrow_mut_data()?, 0, ts_len);
+
+    emit_ah_event(
+        AhEventKind::Cancel,
+        auction_house.key(),
+        Some(token_account.mint),
+        buyer_price,
+        vec![wallet.key(), session_key_account.session_key],
+    )?;
+
+    Ok(())
 }
 
 #[allow(clippy::needless_lifetimes)]
 fn cancel_logic<'info>(
     accounts: &mut Cancel<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
     buyer_price: u64,
     token_size: u64,
 ) -> Result<()> {
@@ -247,14 +417,55 @@ fn cancel_logic<'info>(
         )?;
     }
 
+    let order_book_summary =
+        find_order_book_summary(remaining_accounts, auction_house, &token_account.mint)?;
+
+    // Asks are listed from the wallet's own token account, bids against someone else's.
     let curr_lamp = trade_state.lamports();
+    if curr_lamp > 0 {
+        let side = if token_account.owner == wallet.key() {
+            OrderSide::Ask
+        } else {
+            OrderSide::Bid
+        };
+        record_order_closed(
+            order_book_summary,
+            &auction_house_key,
+            &token_account.mint,
+            side,
+            buyer_price,
+        )?;
+    }
+    // Rent goes back to whoever funded the trade state, not whoever pays for the cancel. A sponsor who paid for
+    // it, as for signed listings, is passed among the remaining accounts.
+    let wallet_info = wallet.to_account_info();
+    let auction_house_fee_account_info = auction_house_fee_account.to_account_info();
+    let rent_recipient_candidates: Vec<&AccountInfo<'info>> =
+        [&wallet_info, &auction_house_fee_account_info]
+            .into_iter()
+            .chain(remaining_accounts)
+            .collect();
+    let rent_recipient = trade_state_rent_recipient(
+        &trade_state.try_borrow_data()?,
+        &rent_recipient_candidates,
+        &fee_payer,
+    )?;
     **trade_state.lamports.borrow_mut() = 0;
 
-    **fee_payer.lamports.borrow_mut() = fee_payer
+    **rent_recipient.lamports.borrow_mut() = rent_recipient
         .lamports()
         .checked_add(curr_lamp)
         .ok_or(AuctionHouseError::NumericalOverflow)?;
-    sol_memset(*trade_state.try_borrow_mut_data()?, 0, TRADE_STATE_SIZE);
+    let ts_len = trade_state.data_len();
+    sol_memset(*trade_state.try_borrow_mut_data()?, 0, ts_len);
+
+    emit_ah_event(
+        AhEventKind::Cancel,
+        auction_house.key(),
+        Some(token_account.mint),
+        buyer_price,
+        vec![wallet.key()],
+    )?;
 
     Ok(())
 }
@@ -0,0 +1,258 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::*, errors::AuctionHouseError, utils::create_or_allocate_account_raw, AuctionHouse,
+    ComplianceConfig, WalletVolume,
+};
+
+/// Accounts for the [`create_compliance_config` handler](auction_house/fn.create_compliance_config.html).
+#[derive(Accounts)]
+pub struct CreateComplianceConfig<'info> {
+    /// Authority key for the Auction House.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+
+    /// Compliance config PDA of the Auction House.
+    #[account(
+        init,
+        payer = authority,
+        space = COMPLIANCE_CONFIG_SIZE,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            COMPLIANCE_CONFIG.as_bytes()
+        ],
+        bump
+    )]
+    pub compliance_config: Account<'info, ComplianceConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Cap the volume every wallet can trade per epoch and freeze `frozen_wallets` out of sales.
+pub fn create_compliance_config<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreateComplianceConfig<'info>>,
+    volume_cap_per_epoch: u64,
+    frozen_wallets: Vec<Pubkey>,
+) -> Result<()> {
+    if frozen_wallets.len() > MAX_FROZEN_WALLETS {
+        return Err(AuctionHouseError::TooManyFrozenWallets.into());
+    }
+
+    let compliance_config = &mut ctx.accounts.compliance_config;
+    compliance_config.auction_house = ctx.accounts.auction_house.key();
+    compliance_config.volume_cap_per_epoch = volume_cap_per_epoch;
+    compliance_config.frozen_wallets = frozen_wallets;
+    compliance_config.bump = *ctx
+        .bumps
+        .get("compliance_config")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+
+    ctx.accounts.auction_house.has_compliance = true;
+
+    Ok(())
+}
+
+/// Accounts for the [`update_compliance_config` handler](auction_house/fn.update_compliance_config.html).
+#[derive(Accounts)]
+pub struct UpdateComplianceConfig<'info> {
+    /// Authority key for the Auction House.
+    pub authority: Signer<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+
+    /// Compliance config PDA of the Auction House.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            COMPLIANCE_CONFIG.as_bytes()
+        ],
+        bump=compliance_config.bump,
+        has_one=auction_house
+    )]
+    pub compliance_config: Account<'info, ComplianceConfig>,
+}
+
+/// Replace the volume cap and the freeze list of the compliance config.
+pub fn update_compliance_config<'info>(
+    ctx: Context<'_, '_, '_, 'info, UpdateComplianceConfig<'info>>,
+    volume_cap_per_epoch: u64,
+    frozen_wallets: Vec<Pubkey>,
+) -> Result<()> {
+    if frozen_wallets.len() > MAX_FROZEN_WALLETS {
+        return Err(AuctionHouseError::TooManyFrozenWallets.into());
+    }
+
+    let compliance_config = &mut ctx.accounts.compliance_config;
+    compliance_config.volume_cap_per_epoch = volume_cap_per_epoch;
+    compliance_config.frozen_wallets = frozen_wallets;
+
+    Ok(())
+}
+
+/// Accounts for the [`close_compliance_config` handler](auction_house/fn.close_compliance_config.html).
+#[derive(Accounts)]
+pub struct CloseComplianceConfig<'info> {
+    /// Authority key for the Auction House, receives the config lamports back.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+
+    /// Compliance config PDA of the Auction House, closed back to the authority.
+    #[account(
+        mut,
+        close = authority,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            COMPLIANCE_CONFIG.as_bytes()
+        ],
+        bump=compliance_config.bump,
+        has_one=auction_house
+    )]
+    pub compliance_config: Account<'info, ComplianceConfig>,
+}
+
+/// Close the compliance config, lifting the volume cap and the freeze list.
+pub fn close_compliance_config<'info>(
+    ctx: Context<'_, '_, '_, 'info, CloseComplianceConfig<'info>>,
+) -> Result<()> {
+    ctx.accounts.auction_house.has_compliance = false;
+
+    Ok(())
+}
+
+/// Reject a sale of `amount` when the buyer or seller is frozen or would trade over the volume cap of the
+/// current epoch, and add it to both wallet volumes. Missing wallet volume PDAs are created by the fee account.
+pub fn enforce_compliance<'a>(
+    auction_house_key: &Pubkey,
+    compliance_config_info: &AccountInfo<'a>,
+    wallets: [(&Pubkey, &AccountInfo<'a>); 2],
+    auction_house_fee_account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    rent: &AccountInfo<'a>,
+    fee_payer_seeds: &[&[u8]],
+    amount: u64,
+) -> Result<()> {
+    let (compliance_config_key, _) = Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            auction_house_key.as_ref(),
+            COMPLIANCE_CONFIG.as_bytes(),
+        ],
+        &crate::id(),
+    );
+    if compliance_config_info.key() != compliance_config_key
+        || compliance_config_info.owner != &crate::id()
+    {
+        return Err(AuctionHouseError::InvalidComplianceAccount.into());
+    }
+    let compliance_config =
+        ComplianceConfig::try_deserialize(&mut compliance_config_info.try_borrow_data()?.as_ref())?;
+
+    let epoch = Clock::get()?.epoch;
+    for (wallet, wallet_volume_info) in wallets {
+        if compliance_config.frozen_wallets.contains(wallet) {
+            return Err(AuctionHouseError::WalletFrozen.into());
+        }
+
+        let (wallet_volume_key, wallet_volume_bump) = Pubkey::find_program_address(
+            &[
+                PREFIX.as_bytes(),
+                auction_house_key.as_ref(),
+                WALLET_VOLUME.as_bytes(),
+                wallet.as_ref(),
+            ],
+            &crate::id(),
+        );
+        if wallet_volume_info.key() != wallet_volume_key {
+            return Err(AuctionHouseError::InvalidComplianceAccount.into());
+        }
+
+        let mut wallet_volume = if wallet_volume_info.data_is_empty() {
+            create_or_allocate_account_raw(
+                crate::id(),
+                wallet_volume_info,
+                rent,
+                system_program,
+                auction_house_fee_account,
+                WALLET_VOLUME_SIZE,
+                fee_payer_seeds,
+                &[
+                    PREFIX.as_bytes(),
+                    auction_house_key.as_ref(),
+                    WALLET_VOLUME.as_bytes(),
+                    wallet.as_ref(),
+                    &[wallet_volume_bump],
+                ],
+            )?;
+
+            WalletVolume {
+                auction_house: *auction_house_key,
+                wallet: *wallet,
+                epoch,
+                volume: 0,
+                bump: wallet_volume_bump,
+            }
+        } else {
+            if wallet_volume_info.owner != &crate::id() {
+                return Err(AuctionHouseError::InvalidComplianceAccount.into());
+            }
+            WalletVolume::try_deserialize(&mut wallet_volume_info.try_borrow_data()?.as_ref())?
+        };
+
+        // Volume is rolling per epoch, the first sale of a new epoch starts from zero.
+        if wallet_volume.epoch != epoch {
+            wallet_volume.epoch = epoch;
+            wallet_volume.volume = 0;
+        }
+        wallet_volume.volume = wallet_volume
+            .volume
+            .checked_add(amount)
+            .ok_or(AuctionHouseError::NumericalOverflow)?;
+        if wallet_volume.volume > compliance_config.volume_cap_per_epoch {
+            return Err(AuctionHouseError::WalletVolumeCapExceeded.into());
+        }
+
+        wallet_volume.try_serialize(&mut *wallet_volume_info.try_borrow_mut_data()?)?;
+    }
+
+    Ok(())
+}
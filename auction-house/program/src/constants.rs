@@ -6,15 +6,265 @@ pub const PURCHASE_RECEIPT_PREFIX: &str = "purchase_receipt";
 pub const BID_RECEIPT_PREFIX: &str = "bid_receipt";
 pub const LISTING_RECEIPT_PREFIX: &str = "listing_receipt";
 pub const AUCTIONEER: &str = "auctioneer";
+pub const SESSION_KEY: &str = "session_key";
+pub const REVENUE_SHARE: &str = "revenue_share";
+pub const REVENUE_SHARE_BITMAP_LEN: usize = 1024;
+pub const PENDING_UPDATE: &str = "pending_update";
+pub const LINKED_WALLET: &str = "linked_wallet";
+pub const ORDER_BOOK_SUMMARY: &str = "order_book_summary";
+pub const PENDING_LISTING: &str = "pending_listing";
+pub const SIGNED_LISTING: &str = "signed_listing";
+pub const ROYALTY_MANDATE: &str = "royalty_mandate";
+pub const PROCEEDS_ROUTE: &str = "proceeds_route";
+pub const BUNDLE_LISTING: &str = "bundle_listing";
+pub const COMPLIANCE_CONFIG: &str = "compliance_config";
+pub const WALLET_VOLUME: &str = "wallet_volume";
+pub const LAYAWAY: &str = "layaway";
+pub const FREE_SALE_COUNTER: &str = "free_sale_counter";
+pub const EPOCH_REPORT: &str = "epoch_report";
+pub const DEFERRED_SETTLEMENT: &str = "deferred_settlement";
+pub const LAST_SALE: &str = "last_sale";
+pub const CUSTODIAL_IDENTITY: &str = "custodial_identity";
+pub const CUSTODIAL_APPROVAL: &str = "custodial_approval";
+pub const SWAP_PROPOSAL: &str = "swap_proposal";
+pub const AH_EVENT_TAG: &str = "ah_event";
+// Bumped whenever the logged `AhEventV1` layout changes
+pub const AH_EVENT_SCHEMA_VERSION: u8 = 1;
+// Roughly two days of slots before a queued authority change can be applied
+pub const PENDING_UPDATE_DELAY_SLOTS: u64 = 432_000;
 pub const TRADE_STATE_SIZE: usize = 1;
-pub const MAX_NUM_SCOPES: usize = 7;
+// Trade states of `sell` and `buy` record the wallet or fee account which paid their rent after the bump
+pub const TRADE_STATE_WITH_RENT_PAYER_SIZE: usize = TRADE_STATE_SIZE + 32;
+// Trade states of `custodial_sell` also record the hash of the user identity approving the sale
+pub const CUSTODIAL_TRADE_STATE_SIZE: usize = TRADE_STATE_WITH_RENT_PAYER_SIZE + 32;
+// Trade states of `session_private_bid` also record the session key which placed the bid
+pub const SESSION_TRADE_STATE_SIZE: usize = TRADE_STATE_WITH_RENT_PAYER_SIZE + 32;
+pub const MAX_NUM_SCOPES: usize = 10;
+pub const MAX_BUNDLE_ITEMS: usize = 5;
+pub const MAX_FROZEN_WALLETS: usize = 32;
+// Bits of `AuctionHouse::paused_operations`, cancels and user escrow withdrawals are never paused
+pub const PAUSE_SELL: u8 = 1 << 0;
+pub const PAUSE_BUY: u8 = 1 << 1;
+pub const PAUSE_EXECUTE_SALE: u8 = 1 << 2;
+pub const PAUSE_DEPOSIT: u8 = 1 << 3;
+pub const PAUSE_WITHDRAW: u8 = 1 << 4;
+pub const PAUSE_ALL: u8 =
+    PAUSE_SELL | PAUSE_BUY | PAUSE_EXECUTE_SALE | PAUSE_DEPOSIT | PAUSE_WITHDRAW;
+// Share of the ask price paid to the cranker of `match_orders`
+pub const MATCH_ORDERS_BOUNTY_BASIS_POINTS: u16 = 10;
 pub const AUCTIONEER_SIZE: usize = 8 +                      // Anchor discriminator/sighash
 32 +                                                        // Auctioneer authority
 32 +                                                        // Auction house instance
 MAX_NUM_SCOPES +                                            // Array of AuthorityScope bools
+61                                                          // Padding
+;
+
+pub const SESSION_KEY_SIZE: usize = 8 +                    // Anchor discriminator/sighash
+32 +                                                        // Wallet
+32 +                                                        // Auction house instance
+32 +                                                        // Session key authority
+8 +                                                         // Max spend
+8 +                                                         // Spent
+8 +                                                         // Expiry
+1 +                                                         // Bump
+64                                                          // Padding
+;
+
+pub const REVENUE_SHARE_SIZE: usize = 8 +                  // Anchor discriminator/sighash
+32 +                                                        // Auction house instance
+32 +                                                        // Governance mint
+2 +                                                         // Basis points of house fees
+8 +                                                         // Accrued since last snapshot
+8 +                                                         // Snapshot count
+1 +                                                         // Bump
 64                                                          // Padding
 ;
 
+pub const REVENUE_SHARE_SNAPSHOT_SIZE: usize = 8 +         // Anchor discriminator/sighash
+32 +                                                        // Revenue share
+8 +                                                         // Index
+8 +                                                         // Slot
+32 +                                                        // Merkle root
+8 +                                                         // Governance mint supply
+8 +                                                         // Amount
+8 +                                                         // Claimed
+1 +                                                         // Bump
+4 + REVENUE_SHARE_BITMAP_LEN                                // Claimed bitmap
+;
+
+pub const PENDING_UPDATE_SIZE: usize = 8 +                 // Anchor discriminator/sighash
+32 +                                                        // Auction house instance
+32 +                                                        // Payer
+32 +                                                        // New authority
+32 +                                                        // New treasury withdrawal destination
+8 +                                                         // Executable slot
+1 +                                                         // Bump
+64                                                          // Padding
+;
+
+pub const LINKED_WALLET_SIZE: usize = 8 +                  // Anchor discriminator/sighash
+32 +                                                        // Auction house instance
+32 +                                                        // Wallet
+32 +                                                        // Group shared by linked wallets
+1 +                                                         // Bump
+8 +                                                         // Max offered valuation
+24                                                          // Padding
+;
+
+pub const ORDER_BOOK_SUMMARY_SIZE: usize = 8 +             // Anchor discriminator/sighash
+32 +                                                        // Auction house instance
+32 +                                                        // Token mint
+9 +                                                         // Best ask
+9 +                                                         // Best bid
+8 +                                                         // Open asks
+8 +                                                         // Open bids
+1 +                                                         // Bump
+8 +                                                         // Open asks at the best ask
+8 +                                                         // Open bids at the best bid
+48                                                          // Padding
+;
+
+pub const PENDING_LISTING_SIZE: usize = 8 +                // Anchor discriminator/sighash
+32 +                                                        // Auction house instance
+32 +                                                        // Wallet
+32 +                                                        // Token account
+8 +                                                         // Buyer price
+8 +                                                         // Token size
+1 +                                                         // Bump
+32                                                          // Padding
+;
+
+pub const ROYALTY_MANDATE_SIZE: usize = 8 +                // Anchor discriminator/sighash
+32 +                                                        // Creator
+32 +                                                        // Destination
+1 +                                                         // Bump
+32                                                          // Padding
+;
+
+pub const SIGNED_LISTING_NONCE_SIZE: usize = 8 +           // Anchor discriminator/sighash
+32 +                                                        // Auction house instance
+32 +                                                        // Wallet
+8 +                                                         // Nonce
+1 +                                                         // Canceled
+1 +                                                         // Bump
+32                                                          // Padding
+;
+
+pub const PROCEEDS_ROUTE_SIZE: usize = 8 +                 // Anchor discriminator/sighash
+32 +                                                        // Seller
+32 +                                                        // Seller trade state
+32 +                                                        // Destination
+1 +                                                         // Bump
+32                                                          // Padding
+;
+
+pub const DEFERRED_SETTLEMENT_SIZE: usize = 8 +            // Anchor discriminator/sighash
+32 +                                                        // Auction house instance
+32 +                                                        // Purchase receipt
+32 +                                                        // Buyer
+32 +                                                        // Seller
+32 +                                                        // Destination of the proceeds
+8 +                                                         // Amount held
+8 +                                                         // Settle after
+1 +                                                         // Disputed
+1 +                                                         // Bump
+32                                                          // Padding
+;
+
+pub const LAST_SALE_SIZE: usize = 8 +                      // Anchor discriminator/sighash
+32 +                                                        // Auction house instance
+32 +                                                        // Token mint
+32 +                                                        // Buyer
+32 +                                                        // Seller
+8 +                                                         // Slot of the sale
+1 +                                                         // Bump
+32                                                          // Padding
+;
+
+pub const BUNDLE_ITEM_SIZE: usize = 32 +                   // Seller trade state
+32 +                                                        // Token account
+32 +                                                        // Token mint
+8 +                                                         // Price
+8                                                           // Token size
+;
+
+pub const BUNDLE_LISTING_SIZE: usize = 8 +                 // Anchor discriminator/sighash
+32 +                                                        // Auction house instance
+32 +                                                        // Seller
+8 +                                                         // Bundle id
+8 +                                                         // Price
+4 + MAX_BUNDLE_ITEMS * BUNDLE_ITEM_SIZE +                   // Items
+1 +                                                         // Bump
+32                                                          // Padding
+;
+
+pub const COMPLIANCE_CONFIG_SIZE: usize = 8 +             // Anchor discriminator/sighash
+32 +                                                        // Auction house instance
+8 +                                                         // Volume cap per wallet per epoch
+4 + MAX_FROZEN_WALLETS * 32 +                               // Frozen wallets
+1 +                                                         // Bump
+32                                                          // Padding
+;
+
+pub const FREE_SALE_COUNTER_SIZE: usize = 8 +              // Anchor discriminator/sighash
+32 +                                                        // Auction house instance
+32 +                                                        // Seller
+8 +                                                         // Epoch
+2 +                                                         // Free sales in the epoch
+1 +                                                         // Bump
+32                                                          // Padding
+;
+
+pub const EPOCH_REPORT_SIZE: usize = 8 +                   // Anchor discriminator/sighash
+32 +                                                        // Auction house instance
+8 +                                                         // Epoch
+8 +                                                         // House fees
+8 +                                                         // Creator royalties paid
+8 +                                                         // Volume
+8 +                                                         // Sale count
+1 +                                                         // Bump
+32                                                          // Padding
+;
+
+pub const WALLET_VOLUME_SIZE: usize = 8 +                  // Anchor discriminator/sighash
+32 +                                                        // Auction house instance
+32 +                                                        // Wallet
+8 +                                                         // Epoch
+8 +                                                         // Volume traded in the epoch
+1 +                                                         // Bump
+32                                                          // Padding
+;
+
+pub const LAYAWAY_SIZE: usize = 8 +                        // Anchor discriminator/sighash
+32 +                                                        // Auction house instance
+32 +                                                        // Seller
+32 +                                                        // Buyer
+32 +                                                        // Seller trade state
+32 +                                                        // Token mint
+8 +                                                         // Token size
+8 +                                                         // Price
+1 +                                                         // Installments
+8 +                                                         // Installment interval
+8 +                                                         // Started at
+8 +                                                         // Amount paid
+2 +                                                         // Penalty basis points
+1 +                                                         // Bump
+1 +                                                         // Escrow bump
+32                                                          // Padding
+;
+
+pub const SWAP_PROPOSAL_SIZE: usize = 8 +                  // Anchor discriminator/sighash
+32 +                                                        // Auction house instance
+32 +                                                        // Proposer
+32 +                                                        // Offered token account
+32 +                                                        // Offered mint
+8 +                                                         // Offered size
+32 +                                                        // Target mint
+8 +                                                         // Target size
+1 +                                                         // Bump
+32                                                          // Padding
+;
+
 pub const AUCTION_HOUSE_SIZE: usize = 8 +                   // key
 32 +                                                        // fee Payer
 32 +                                                        // treasury
@@ -32,5 +282,21 @@ pub const AUCTION_HOUSE_SIZE: usize = 8 +                   // key
 8 +                                                         // escrow payment bump
 1 +                                                         // has external auctioneer program as an authority
 8 +                                                         // auctioneer pda bump
-203                                                         // padding
+1 +                                                         // auto receipts
+8 +                                                         // fee account target balance
+8 +                                                         // fee account low balance threshold
+1 +                                                         // has revenue share
+1 +                                                         // wash trading guard
+1 +                                                         // enforce royalties
+1 +                                                         // has compliance config
+8 +                                                         // tick size
+1 +                                                         // free sales require sign off
+2 +                                                         // max free sales per seller per epoch
+1 +                                                         // program as signer bump
+1 +                                                         // paused operations
+2 +                                                         // epoch report retention in epochs
+8 +                                                         // dispute window in seconds
+8 +                                                         // listing cooldown in slots
+1 +                                                         // has order book summaries
+150                                                         // padding
 ;
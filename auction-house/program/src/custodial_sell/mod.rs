@@ -0,0 +1,164 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+use crate::{
+    constants::*, errors::AuctionHouseError, sell::*, utils::get_program_as_signer_bump,
+    AuctionHouse,
+};
+
+/// Accounts for the [`custodial_sell` handler](auction_house/fn.custodial_sell.html).
+#[derive(Accounts, Clone)]
+#[instruction(buyer_price: u64, token_size: u64)]
+pub struct CustodialSell<'info> {
+    /// CHECK: Validated as a signer in custodial_sell.
+    /// Wallet of the custodial platform holding the token on behalf of the user.
+    pub wallet: UncheckedAccount<'info>,
+
+    /// SPL token account containing token for sale.
+    #[account(mut)]
+    pub token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Validated by assert_metadata_valid.
+    /// Metaplex metadata account decorating SPL mint account.
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Verified through CPI
+    /// Auction House authority account.
+    pub authority: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority,
+        has_one=auction_house_fee_account
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance fee account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump=auction_house.fee_payer_bump
+    )]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint with the canonical bump.
+    /// Seller trade state PDA account encoding the sell order and the user identity hash.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_account.mint.as_ref(),
+            &buyer_price.to_le_bytes(),
+            &token_size.to_le_bytes()
+        ],
+        bump
+    )]
+    pub seller_trade_state: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint with the canonical bump.
+    /// Free seller trade state PDA account encoding a free sell order.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_account.mint.as_ref(),
+            &0u64.to_le_bytes(),
+            &token_size.to_le_bytes()
+        ],
+        bump
+    )]
+    pub free_seller_trade_state: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint with the bump stored on the auction house.
+    #[account(seeds=[PREFIX.as_bytes(), SIGNER.as_bytes()], bump=get_program_as_signer_bump(&auction_house))]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> From<CustodialSell<'info>> for Sell<'info> {
+    fn from(a: CustodialSell<'info>) -> Sell<'info> {
+        Sell {
+            wallet: a.wallet,
+            token_account: a.token_account,
+            metadata: a.metadata,
+            authority: a.authority,
+            auction_house: *a.auction_house,
+            auction_house_fee_account: a.auction_house_fee_account,
+            seller_trade_state: a.seller_trade_state,
+            free_seller_trade_state: a.free_seller_trade_state,
+            token_program: a.token_program,
+            system_program: a.system_program,
+            program_as_signer: a.program_as_signer,
+            rent: a.rent,
+        }
+    }
+}
+
+/// List a token held by a custodial platform like `sell_v2`, recording the hash of the user identity in the trade
+/// state. The platform wallet signs the listing, while `execute_sale` requires the user to approve the sale through
+/// an ed25519 verification instruction, see [`custodial_identity_hash`](crate::utils::custodial_identity_hash).
+pub fn custodial_sell<'info>(
+    ctx: Context<'_, '_, '_, 'info, CustodialSell<'info>>,
+    buyer_price: u64,
+    token_size: u64,
+    user_identity_hash: [u8; 32],
+) -> Result<()> {
+    let auction_house = &ctx.accounts.auction_house;
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    // The platform stands in for the user, so the listing is never made without it.
+    if !ctx.accounts.wallet.is_signer {
+        return Err(AuctionHouseError::SaleRequiresSigner.into());
+    }
+
+    let trade_state_bump = *ctx
+        .bumps
+        .get("seller_trade_state")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+    let free_trade_state_bump = *ctx
+        .bumps
+        .get("free_seller_trade_state")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+    let program_as_signer_bump = get_program_as_signer_bump(auction_house);
+
+    let mut accounts: Sell<'info> = (*ctx.accounts).clone().into();
+
+    sell_logic(
+        &mut accounts,
+        ctx.program_id,
+        ctx.remaining_accounts,
+        trade_state_bump,
+        free_trade_state_bump,
+        program_as_signer_bump,
+        buyer_price,
+        token_size,
+        Some(user_identity_hash),
+    )
+}
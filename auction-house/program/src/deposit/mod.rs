@@ -1,6 +1,9 @@
 use anchor_lang::{prelude::*, solana_program::program::invoke, AnchorDeserialize};
 
-use crate::{constants::*, errors::*, utils::*, AuctionHouse, AuthorityScope, *};
+use crate::{
+    constants::*, errors::*, token_interface::*, utils::*, AhEventKind, AuctionHouse,
+    AuthorityScope, *,
+};
 
 /// Accounts for the [`deposit` handler](auction_house/fn.deposit.html).
 #[derive(Accounts)]
@@ -31,8 +34,9 @@ pub struct Deposit<'info> {
     )]
     pub escrow_payment_account: UncheckedAccount<'info>,
 
+    /// CHECK: Checked in has_one constraint of the auction house.
     /// Auction House instance treasury mint account.
-    pub treasury_mint: Box<Account<'info, Mint>>,
+    pub treasury_mint: UncheckedAccount<'info>,
 
     /// CHECK: Validated in deposit_logic.
     /// Auction House instance authority account.
@@ -65,7 +69,10 @@ pub struct Deposit<'info> {
     )]
     pub auction_house_fee_account: UncheckedAccount<'info>,
 
-    pub token_program: Program<'info, Token>,
+    /// CHECK: Checked in constraint to be SPL Token or Token-2022.
+    /// Token program of the treasury mint.
+    #[account(constraint = is_treasury_token_program(token_program.key, &treasury_mint.to_account_info()) @ AuctionHouseError::InvalidTokenProgram)]
+    pub token_program: UncheckedAccount<'info>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
@@ -132,8 +139,9 @@ pub struct AuctioneerDeposit<'info> {
     )]
     pub escrow_payment_account: UncheckedAccount<'info>,
 
+    /// CHECK: Checked in has_one constraint of the auction house.
     /// Auction House instance treasury mint account.
-    pub treasury_mint: Box<Account<'info, Mint>>,
+    pub treasury_mint: UncheckedAccount<'info>,
 
     /// CHECK: Validated in deposit_logic.
     /// Auction House instance authority account.
@@ -182,7 +190,10 @@ pub struct AuctioneerDeposit<'info> {
     )]
     pub ah_auctioneer_pda: UncheckedAccount<'info>,
 
-    pub token_program: Program<'info, Token>,
+    /// CHECK: Checked in constraint to be SPL Token or Token-2022.
+    /// Token program of the treasury mint.
+    #[account(constraint = is_treasury_token_program(token_program.key, &treasury_mint.to_account_info()) @ AuctionHouseError::InvalidTokenProgram)]
+    pub token_program: UncheckedAccount<'info>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
@@ -212,8 +223,120 @@ pub fn auctioneer_deposit<'info>(
     deposit_logic(&mut accounts, escrow_payment_bump, amount)
 }
 
+/// Accounts for the [`deposit_v2` handler](auction_house/fn.deposit_v2.html).
+#[derive(Accounts, Clone)]
+pub struct DepositV2<'info> {
+    /// User wallet account.
+    pub wallet: Signer<'info>,
+
+    /// CHECK: Validated in deposit_logic.
+    /// User SOL or SPL account to transfer funds from.
+    #[account(mut)]
+    pub payment_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in deposit_logic.
+    /// SPL token account transfer authority.
+    pub transfer_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint with the canonical bump.
+    /// Buyer escrow payment account PDA.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref()
+        ],
+        bump
+    )]
+    pub escrow_payment_account: UncheckedAccount<'info>,
+
+    /// CHECK: Checked in has_one constraint of the auction house.
+    /// Auction House instance treasury mint account.
+    pub treasury_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in deposit_logic.
+    /// Auction House instance authority account.
+    pub authority: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority,
+        has_one=treasury_mint,
+        has_one=auction_house_fee_account
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance fee account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump=auction_house.fee_payer_bump
+    )]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Checked in constraint to be SPL Token or Token-2022.
+    /// Token program of the treasury mint.
+    #[account(constraint = is_treasury_token_program(token_program.key, &treasury_mint.to_account_info()) @ AuctionHouseError::InvalidTokenProgram)]
+    pub token_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> From<DepositV2<'info>> for Deposit<'info> {
+    fn from(a: DepositV2<'info>) -> Deposit<'info> {
+        Deposit {
+            wallet: a.wallet,
+            payment_account: a.payment_account,
+            transfer_authority: a.transfer_authority,
+            escrow_payment_account: a.escrow_payment_account,
+            treasury_mint: a.treasury_mint,
+            authority: a.authority,
+            auction_house: a.auction_house,
+            auction_house_fee_account: a.auction_house_fee_account,
+            token_program: a.token_program,
+            system_program: a.system_program,
+            rent: a.rent,
+        }
+    }
+}
+
+/// Deposit, with the escrow payment bump derived on chain instead of passed by the client.
+pub fn deposit_v2<'info>(
+    ctx: Context<'_, '_, '_, 'info, DepositV2<'info>>,
+    amount: u64,
+) -> Result<()> {
+    let auction_house = &ctx.accounts.auction_house;
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    let escrow_payment_bump = *ctx
+        .bumps
+        .get("escrow_payment_account")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+
+    let mut accounts: Deposit<'info> = (*ctx.accounts).clone().into();
+
+    deposit_logic(&mut accounts, escrow_payment_bump, amount)
+}
+
 #[allow(clippy::needless_lifetimes)]
-/// Deposit `amount` into the escrow payment account for your specific wallet.
+/// Deposit `amount` into the escrow payment account for your specific wallet. For Token-2022 treasury mints with a
+/// transfer fee the escrow is credited `amount` less the fee.
 fn deposit_logic<'info>(
     accounts: &mut Deposit<'info>,
     escrow_payment_bump: u8,
@@ -231,6 +354,8 @@ fn deposit_logic<'info>(
     let token_program = &accounts.token_program;
     let rent = &accounts.rent;
 
+    assert_not_paused(auction_house, PAUSE_DEPOSIT)?;
+
     let auction_house_key = auction_house.key();
     let seeds = [
         PREFIX.as_bytes(),
@@ -270,24 +395,19 @@ fn deposit_logic<'info>(
         is_native,
     )?;
 
-    if !is_native {
+    // The escrow is credited net of any transfer fee of the treasury mint.
+    let credited_amount = if !is_native {
         assert_is_ata(payment_account, &wallet.key(), &treasury_mint.key())?;
-        invoke(
-            &spl_token::instruction::transfer(
-                token_program.key,
-                &payment_account.key(),
-                &escrow_payment_account.key(),
-                &transfer_authority.key(),
-                &[],
-                amount,
-            )?,
-            &[
-                escrow_payment_account.to_account_info(),
-                payment_account.to_account_info(),
-                token_program.to_account_info(),
-                transfer_authority.to_account_info(),
-            ],
+        transfer(
+            token_program,
+            payment_account,
+            treasury_mint,
+            escrow_payment_account,
+            transfer_authority,
+            amount,
+            &[],
         )?;
+        amount_after_transfer_fee(treasury_mint, amount)?
     } else {
         assert_keys_equal(payment_account.key(), wallet.key())?;
 
@@ -307,7 +427,16 @@ fn deposit_logic<'info>(
                 system_program.to_account_info(),
             ],
         )?;
-    }
+        amount
+    };
+
+    emit_ah_event(
+        AhEventKind::Deposit,
+        auction_house_key,
+        None,
+        credited_amount,
+        vec![wallet.key()],
+    )?;
 
     Ok(())
 }
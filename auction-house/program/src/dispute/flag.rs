@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::*, errors::AuctionHouseError, receipt::PurchaseReceipt, AuctionHouse,
+    DeferredSettlement,
+};
+
+/// Accounts for the [`flag_purchase` handler](auction_house/fn.flag_purchase.html).
+#[derive(Accounts)]
+pub struct FlagPurchase<'info> {
+    /// Authority key for the Auction House.
+    pub authority: Signer<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// Purchase receipt of the disputed sale.
+    #[account(
+        mut,
+        constraint = purchase_receipt.auction_house == auction_house.key() @ AuctionHouseError::InvalidDeferredSettlement
+    )]
+    pub purchase_receipt: Box<Account<'info, PurchaseReceipt>>,
+
+    /// Deferred settlement holding the seller proceeds of the sale.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            DEFERRED_SETTLEMENT.as_bytes(),
+            purchase_receipt.key().as_ref()
+        ],
+        bump=deferred_settlement.bump,
+        has_one=auction_house,
+        has_one=purchase_receipt
+    )]
+    pub deferred_settlement: Box<Account<'info, DeferredSettlement>>,
+}
+
+/// Mark a purchase disputed within the dispute window of the Auction House, freezing its held proceeds until the
+/// authority resolves the dispute.
+pub fn flag_purchase<'info>(ctx: Context<'_, '_, '_, 'info, FlagPurchase<'info>>) -> Result<()> {
+    let purchase_receipt = &mut ctx.accounts.purchase_receipt;
+    let deferred_settlement = &mut ctx.accounts.deferred_settlement;
+
+    if Clock::get()?.unix_timestamp >= deferred_settlement.settle_after {
+        return Err(AuctionHouseError::DisputeWindowClosed.into());
+    }
+    if deferred_settlement.disputed {
+        return Err(AuctionHouseError::PurchaseDisputed.into());
+    }
+
+    deferred_settlement.disputed = true;
+    purchase_receipt.disputed = true;
+
+    Ok(())
+}
@@ -0,0 +1,209 @@
+pub mod flag;
+pub mod release;
+pub mod resolve;
+pub use flag::*;
+pub use release::*;
+pub use resolve::*;
+
+use anchor_lang::{prelude::*, solana_program::program::invoke_signed};
+use std::slice::Iter;
+
+use crate::{
+    constants::*, errors::AuctionHouseError, id, token_interface::*, utils::*, AuctionHouse,
+    DeferredSettlement,
+};
+
+/// Open the deferred settlement holding back `amount` of seller proceeds of a sale for the dispute window of the
+/// Auction House. Rent of the settlement and, for SPL treasuries, of its token account is paid by the fee account.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn open_deferred_settlement<'a>(
+    deferred_settlement_info: &AccountInfo<'a>,
+    settlement_vault: &AccountInfo<'a>,
+    auction_house: &Account<'a, AuctionHouse>,
+    auction_house_fee_account: &AccountInfo<'a>,
+    treasury_mint: &AccountInfo<'a>,
+    ata_program: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    rent: &AccountInfo<'a>,
+    fee_payer_seeds: &[&[u8]],
+    seller_trade_state: &Pubkey,
+    buyer_trade_state: &Pubkey,
+    buyer: Pubkey,
+    seller: Pubkey,
+    destination: Pubkey,
+    amount: u64,
+    is_native: bool,
+) -> Result<()> {
+    let (purchase_receipt, _) = Pubkey::find_program_address(
+        &[
+            PURCHASE_RECEIPT_PREFIX.as_bytes(),
+            seller_trade_state.as_ref(),
+            buyer_trade_state.as_ref(),
+        ],
+        &id(),
+    );
+    let auction_house_key = auction_house.key();
+    let bump = assert_derivation(
+        &id(),
+        deferred_settlement_info,
+        &[
+            PREFIX.as_bytes(),
+            auction_house_key.as_ref(),
+            DEFERRED_SETTLEMENT.as_bytes(),
+            purchase_receipt.as_ref(),
+        ],
+    )?;
+    // A settlement still open for the same trade states has not been paid out yet.
+    if !deferred_settlement_info.data_is_empty() {
+        return Err(AuctionHouseError::InvalidDeferredSettlement.into());
+    }
+
+    let deferred_settlement_seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        DEFERRED_SETTLEMENT.as_bytes(),
+        purchase_receipt.as_ref(),
+        &[bump],
+    ];
+    create_or_allocate_account_raw(
+        id(),
+        deferred_settlement_info,
+        rent,
+        system_program,
+        auction_house_fee_account,
+        DEFERRED_SETTLEMENT_SIZE,
+        fee_payer_seeds,
+        &deferred_settlement_seeds,
+    )?;
+
+    if !is_native {
+        if settlement_vault.data_is_empty() {
+            make_ata(
+                settlement_vault.clone(),
+                deferred_settlement_info.clone(),
+                treasury_mint.clone(),
+                auction_house_fee_account.clone(),
+                ata_program.clone(),
+                token_program.clone(),
+                system_program.clone(),
+                rent.clone(),
+                fee_payer_seeds,
+            )?;
+        }
+        assert_is_ata(
+            settlement_vault,
+            deferred_settlement_info.key,
+            treasury_mint.key,
+        )?;
+    }
+
+    let settle_after = Clock::get()?
+        .unix_timestamp
+        .checked_add(auction_house.dispute_window)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+    DeferredSettlement {
+        auction_house: auction_house_key,
+        purchase_receipt,
+        buyer,
+        seller,
+        destination,
+        amount,
+        settle_after,
+        disputed: false,
+        bump,
+    }
+    .try_serialize(&mut *deferred_settlement_info.try_borrow_mut_data()?)?;
+
+    Ok(())
+}
+
+/// Pay the proceeds held by a deferred settlement out to `recipient`. Token accounts of SPL Token treasuries are
+/// closed back to the fee account, Token-2022 ones may still hold withheld transfer fees.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn pay_out_deferred_settlement<'info>(
+    deferred_settlement: &Account<'info, DeferredSettlement>,
+    settlement_vault: &AccountInfo<'info>,
+    recipient: &AccountInfo<'info>,
+    treasury_mint: &AccountInfo<'info>,
+    auction_house_fee_account: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    is_native: bool,
+) -> Result<()> {
+    if is_native {
+        let deferred_settlement_info = deferred_settlement.to_account_info();
+        **deferred_settlement_info.lamports.borrow_mut() = deferred_settlement_info
+            .lamports()
+            .checked_sub(deferred_settlement.amount)
+            .ok_or(AuctionHouseError::NumericalOverflow)?;
+        **recipient.lamports.borrow_mut() = recipient
+            .lamports()
+            .checked_add(deferred_settlement.amount)
+            .ok_or(AuctionHouseError::NumericalOverflow)?;
+        return Ok(());
+    }
+
+    assert_is_ata(
+        settlement_vault,
+        &deferred_settlement.key(),
+        treasury_mint.key,
+    )?;
+    let purchase_receipt = deferred_settlement.purchase_receipt;
+    let deferred_settlement_seeds = [
+        PREFIX.as_bytes(),
+        deferred_settlement.auction_house.as_ref(),
+        DEFERRED_SETTLEMENT.as_bytes(),
+        purchase_receipt.as_ref(),
+        &[deferred_settlement.bump],
+    ];
+    transfer(
+        token_program,
+        settlement_vault,
+        treasury_mint,
+        recipient,
+        &deferred_settlement.to_account_info(),
+        deferred_settlement.amount,
+        &[&deferred_settlement_seeds],
+    )?;
+
+    if *token_program.key == spl_token::id() {
+        invoke_signed(
+            &spl_token::instruction::close_account(
+                token_program.key,
+                settlement_vault.key,
+                auction_house_fee_account.key,
+                &deferred_settlement.key(),
+                &[],
+            )?,
+            &[
+                settlement_vault.clone(),
+                auction_house_fee_account.clone(),
+                deferred_settlement.to_account_info(),
+                token_program.clone(),
+            ],
+            &[&deferred_settlement_seeds],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Take the deferred settlement and, for SPL treasuries, its token account from the remaining accounts of a sale when
+/// the Auction House holds proceeds back for a dispute window.
+pub(crate) fn next_deferred_settlement<'a, 'b>(
+    remaining_accounts: &mut Iter<'b, AccountInfo<'a>>,
+    auction_house: &AuctionHouse,
+    is_native: bool,
+) -> Result<Option<(&'b AccountInfo<'a>, &'b AccountInfo<'a>)>> {
+    if auction_house.dispute_window == 0 {
+        return Ok(None);
+    }
+
+    let deferred_settlement_info = next_account_info(remaining_accounts)?;
+    let settlement_vault = if is_native {
+        deferred_settlement_info
+    } else {
+        next_account_info(remaining_accounts)?
+    };
+    Ok(Some((deferred_settlement_info, settlement_vault)))
+}
@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::*, dispute::pay_out_deferred_settlement, errors::AuctionHouseError,
+    token_interface::is_treasury_token_program, AuctionHouse, DeferredSettlement,
+};
+
+/// Accounts for the [`release_settlement` handler](auction_house/fn.release_settlement.html).
+#[derive(Accounts)]
+pub struct ReleaseSettlement<'info> {
+    /// CHECK: Checked in constraint of the deferred settlement.
+    /// Seller SOL or SPL account, or the routed destination, the proceeds are released to.
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+
+    /// CHECK: Checked in has_one constraint of the auction house.
+    /// Treasury mint account, either native SOL mint or a SPL Token or Token-2022 mint.
+    pub treasury_mint: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            treasury_mint.key().as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=treasury_mint,
+        has_one=auction_house_fee_account
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance fee account, receives the rent of the settlement back.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump=auction_house.fee_payer_bump
+    )]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// Deferred settlement holding the seller proceeds, closed back to the fee account.
+    #[account(
+        mut,
+        close = auction_house_fee_account,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            DEFERRED_SETTLEMENT.as_bytes(),
+            deferred_settlement.purchase_receipt.as_ref()
+        ],
+        bump=deferred_settlement.bump,
+        has_one=auction_house,
+        has_one=destination @ AuctionHouseError::InvalidDeferredSettlement
+    )]
+    pub deferred_settlement: Box<Account<'info, DeferredSettlement>>,
+
+    /// CHECK: Validated in release_settlement.
+    /// Associated token account of the deferred settlement for SPL treasuries, unused for native SOL.
+    #[account(mut)]
+    pub settlement_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Checked in constraint to be SPL Token or Token-2022.
+    /// Token program of the treasury mint.
+    #[account(constraint = is_treasury_token_program(token_program.key, &treasury_mint.to_account_info()) @ AuctionHouseError::InvalidTokenProgram)]
+    pub token_program: UncheckedAccount<'info>,
+}
+
+/// Release the held proceeds of an undisputed sale to the seller once the dispute window passed. Anyone may crank it.
+pub fn release_settlement<'info>(
+    ctx: Context<'_, '_, '_, 'info, ReleaseSettlement<'info>>,
+) -> Result<()> {
+    let destination = &ctx.accounts.destination;
+    let treasury_mint = &ctx.accounts.treasury_mint;
+    let auction_house_fee_account = &ctx.accounts.auction_house_fee_account;
+    let deferred_settlement = &ctx.accounts.deferred_settlement;
+    let settlement_token_account = &ctx.accounts.settlement_token_account;
+    let token_program = &ctx.accounts.token_program;
+
+    if deferred_settlement.disputed {
+        return Err(AuctionHouseError::PurchaseDisputed.into());
+    }
+    if Clock::get()?.unix_timestamp < deferred_settlement.settle_after {
+        return Err(AuctionHouseError::DisputeWindowOpen.into());
+    }
+
+    let is_native = treasury_mint.key() == spl_token::native_mint::id();
+    pay_out_deferred_settlement(
+        deferred_settlement,
+        &settlement_token_account.to_account_info(),
+        &destination.to_account_info(),
+        &treasury_mint.to_account_info(),
+        &auction_house_fee_account.to_account_info(),
+        &token_program.to_account_info(),
+        is_native,
+    )?;
+
+    Ok(())
+}
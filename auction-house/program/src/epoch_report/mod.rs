@@ -0,0 +1,147 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::*, errors::AuctionHouseError, utils::create_or_allocate_account_raw, AuctionHouse,
+    EpochReport,
+};
+
+/// Accounts for the [`close_epoch_report` handler](auction_house/fn.close_epoch_report.html).
+#[derive(Accounts)]
+pub struct CloseEpochReport<'info> {
+    /// Authority key for the Auction House.
+    pub authority: Signer<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority,
+        has_one=auction_house_fee_account
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+
+    /// CHECK: Verified through the `auction_house` has_one constraint.
+    /// Auction House instance fee account, which paid for the report and receives its lamports back.
+    #[account(mut)]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// Epoch report PDA of the Auction House, closed back to the fee account.
+    #[account(
+        mut,
+        close = auction_house_fee_account,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            EPOCH_REPORT.as_bytes(),
+            &epoch_report.epoch.to_le_bytes()
+        ],
+        bump=epoch_report.bump,
+        has_one=auction_house
+    )]
+    pub epoch_report: Account<'info, EpochReport>,
+}
+
+/// Close an epoch report once `epoch_report_retention` more epochs have ended after its own.
+pub fn close_epoch_report<'info>(
+    ctx: Context<'_, '_, '_, 'info, CloseEpochReport<'info>>,
+) -> Result<()> {
+    let closable_epoch = ctx
+        .accounts
+        .epoch_report
+        .epoch
+        .checked_add(ctx.accounts.auction_house.epoch_report_retention as u64)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+    if Clock::get()?.epoch <= closable_epoch {
+        return Err(AuctionHouseError::EpochReportRetained.into());
+    }
+
+    Ok(())
+}
+
+/// Add a sale of `price` to the report of the current epoch. The report of a new epoch is created by the fee payer
+/// on its first sale.
+#[allow(clippy::too_many_arguments)]
+pub fn record_epoch_sale<'a>(
+    auction_house_key: &Pubkey,
+    epoch_report_info: &AccountInfo<'a>,
+    fee_payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    rent: &AccountInfo<'a>,
+    fee_payer_seeds: &[&[u8]],
+    price: u64,
+    house_fee: u64,
+    royalties_paid: u64,
+) -> Result<()> {
+    let epoch = Clock::get()?.epoch;
+    let (epoch_report_key, epoch_report_bump) = Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            auction_house_key.as_ref(),
+            EPOCH_REPORT.as_bytes(),
+            &epoch.to_le_bytes(),
+        ],
+        &crate::id(),
+    );
+    if epoch_report_info.key() != epoch_report_key {
+        return Err(AuctionHouseError::InvalidEpochReport.into());
+    }
+
+    let mut epoch_report = if epoch_report_info.data_is_empty() {
+        create_or_allocate_account_raw(
+            crate::id(),
+            epoch_report_info,
+            rent,
+            system_program,
+            fee_payer,
+            EPOCH_REPORT_SIZE,
+            fee_payer_seeds,
+            &[
+                PREFIX.as_bytes(),
+                auction_house_key.as_ref(),
+                EPOCH_REPORT.as_bytes(),
+                &epoch.to_le_bytes(),
+                &[epoch_report_bump],
+            ],
+        )?;
+
+        EpochReport {
+            auction_house: *auction_house_key,
+            epoch,
+            house_fees: 0,
+            royalties_paid: 0,
+            volume: 0,
+            sale_count: 0,
+            bump: epoch_report_bump,
+        }
+    } else {
+        if epoch_report_info.owner != &crate::id() {
+            return Err(AuctionHouseError::InvalidEpochReport.into());
+        }
+        EpochReport::try_deserialize(&mut epoch_report_info.try_borrow_data()?.as_ref())?
+    };
+
+    epoch_report.house_fees = epoch_report
+        .house_fees
+        .checked_add(house_fee)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+    epoch_report.royalties_paid = epoch_report
+        .royalties_paid
+        .checked_add(royalties_paid)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+    epoch_report.volume = epoch_report
+        .volume
+        .checked_add(price)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+    epoch_report.sale_count = epoch_report
+        .sale_count
+        .checked_add(1)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+    epoch_report.try_serialize(&mut *epoch_report_info.try_borrow_mut_data()?)?;
+
+    Ok(())
+}
@@ -165,4 +165,234 @@ pub enum AuctionHouseError {
     // 6040
     #[msg("Calculated partial price does not not partial price that was provided.")]
     PartialPriceMismatch,
+
+    // 6041
+    #[msg("The session key has expired.")]
+    SessionKeyExpired,
+
+    // 6042
+    #[msg("The bid exceeds the remaining spend limit of the session key.")]
+    SessionKeySpendLimitExceeded,
+
+    // 6043
+    #[msg("The escrow account must be funded by the wallet before bidding with a session key.")]
+    InsufficientEscrowForSessionBid,
+
+    // 6044
+    #[msg("The fee account can only be topped up from a native SOL treasury.")]
+    TopUpRequiresNativeTreasury,
+
+    // 6045
+    #[msg("The merkle proof does not match the revenue share snapshot.")]
+    InvalidRevenueShareProof,
+
+    // 6046
+    #[msg("The revenue share has already been claimed for this snapshot.")]
+    RevenueShareAlreadyClaimed,
+
+    // 6047
+    #[msg("The claim index is out of range of the revenue share snapshot.")]
+    RevenueShareClaimIndexOutOfRange,
+
+    // 6048
+    #[msg("The revenue share snapshot slot or supply is invalid.")]
+    InvalidRevenueShareSnapshot,
+
+    // 6049
+    #[msg("An authority update is already pending, apply or cancel it first.")]
+    PendingUpdateAlreadyQueued,
+
+    // 6050
+    #[msg("The timelock of the pending authority update has not elapsed yet.")]
+    PendingUpdateNotExecutable,
+
+    // 6051
+    #[msg("The buyer and seller are the same or linked wallets.")]
+    SelfTradeNotAllowed,
+
+    // 6052
+    #[msg("The order book summary does not belong to this Auction House and mint.")]
+    InvalidOrderBookSummary,
+
+    // 6053
+    #[msg(
+        "Listings can only be migrated between different Auction Houses sharing a treasury mint."
+    )]
+    InvalidListingMigration,
+
+    // 6054
+    #[msg("Auction House enforces royalties: every creator must be paid the full seller fee basis points.")]
+    RoyaltiesNotEnforced,
+
+    // 6055
+    #[msg("Sale receipt accounts must be created with prepare_sale_accounts first.")]
+    SaleAccountsNotPrepared,
+
+    // 6056
+    #[msg("Signed listing must be preceded by an ed25519 verification of the listing message by the seller.")]
+    InvalidListingSignature,
+
+    // 6057
+    #[msg("Signed listing has expired.")]
+    SignedListingExpired,
+
+    // 6058
+    #[msg("Token account must delegate exactly the listed size to the program to post a signed listing.")]
+    SignedListingNotDelegated,
+
+    // 6059
+    #[msg("Royalty mandate does not belong to the creator or its destination does not match.")]
+    InvalidRoyaltyMandate,
+
+    // 6060
+    #[msg("Token program must be SPL Token or Token-2022 and own the treasury mint.")]
+    InvalidTokenProgram,
+
+    // 6061
+    #[msg("Bundle listing items are invalid or do not match the accounts passed.")]
+    InvalidBundleListing,
+
+    // 6062
+    #[msg("Buyer price does not match the bundle listing price.")]
+    BundlePriceMismatch,
+
+    // 6063
+    #[msg("Compliance config or wallet volume account is missing or invalid.")]
+    InvalidComplianceAccount,
+
+    // 6064
+    #[msg("Wallet is frozen by the Auction House compliance config.")]
+    WalletFrozen,
+
+    // 6065
+    #[msg("Sale exceeds the wallet volume cap for the current epoch.")]
+    WalletVolumeCapExceeded,
+
+    // 6066
+    #[msg("Too many frozen wallets in the compliance config.")]
+    TooManyFrozenWallets,
+
+    // 6067
+    #[msg("Price is not a multiple of the Auction House tick size.")]
+    PriceNotOnTick,
+
+    // 6068
+    #[msg("Layaway needs a price, at least one installment, a positive interval and a penalty of at most 100%.")]
+    InvalidLayawaySchedule,
+
+    // 6069
+    #[msg("Installment is empty or exceeds the layaway balance.")]
+    InvalidInstallment,
+
+    // 6070
+    #[msg("Layaway missed an installment deadline.")]
+    LayawayDefaulted,
+
+    // 6071
+    #[msg("Layaway has not missed an installment deadline.")]
+    LayawayNotDefaulted,
+
+    // 6072
+    #[msg("Layaway installments do not cover the price yet.")]
+    LayawayNotPaid,
+
+    // 6073
+    #[msg("Free sales on this Auction House need the sign off of its authority.")]
+    FreeSaleRequiresAuctionHouseSignoff,
+
+    // 6074
+    #[msg("Seller reached the free sale cap for this epoch.")]
+    FreeSaleCapExceeded,
+
+    // 6075
+    #[msg("Free sale counter account is missing or invalid.")]
+    InvalidFreeSaleCounter,
+
+    // 6076
+    #[msg("Metadata account data is malformed.")]
+    InvalidMetadata,
+
+    // 6077
+    #[msg("Operation is paused by the Auction House authority.")]
+    OperationPaused,
+
+    // 6078
+    #[msg("Paused operations contain unknown bits.")]
+    InvalidPausedOperations,
+
+    // 6079
+    #[msg("Bid price is below the ask price.")]
+    BidBelowAsk,
+
+    // 6080
+    #[msg("Balances moved by the sale do not match its ledger.")]
+    LedgerMismatch,
+
+    // 6081
+    #[msg("Proceeds route is invalid or does not match the seller receipt.")]
+    InvalidProceedsRoute,
+
+    // 6082
+    #[msg("Epoch report is invalid or not for the current epoch.")]
+    InvalidEpochReport,
+
+    // 6083
+    #[msg("Epoch report is still within its retention period.")]
+    EpochReportRetained,
+
+    // 6084
+    #[msg("Deferred settlement is invalid or does not match the sale.")]
+    InvalidDeferredSettlement,
+
+    // 6085
+    #[msg("Dispute window of the purchase has closed.")]
+    DisputeWindowClosed,
+
+    // 6086
+    #[msg("Dispute window of the purchase is still open.")]
+    DisputeWindowOpen,
+
+    // 6087
+    #[msg("Purchase is disputed and can only be resolved by the Auction House authority.")]
+    PurchaseDisputed,
+
+    // 6088
+    #[msg("Purchase is not disputed.")]
+    PurchaseNotDisputed,
+
+    // 6089
+    #[msg("Dispute window cannot be negative.")]
+    InvalidDisputeWindow,
+
+    // 6090
+    #[msg("Rent payer recorded in the trade state was not provided.")]
+    RentPayerNotProvided,
+
+    // 6091
+    #[msg("Last sale account does not match the mint.")]
+    InvalidLastSale,
+
+    // 6092
+    #[msg("Wallet took part in a sale of the mint too recently to list it again.")]
+    ListingCooldownActive,
+
+    // 6093
+    #[msg("Custodial sale must be preceded by an ed25519 verification of the sale approval by the user.")]
+    InvalidCustodialApproval,
+
+    // 6094
+    #[msg("Custodial listing was made for a different user identity.")]
+    CustodialIdentityMismatch,
+
+    // 6095
+    #[msg("Swap must exchange a positive amount of two different mints.")]
+    InvalidSwap,
+
+    // 6096
+    #[msg("Order book summary address of the mint must be passed once the Auction House has order book summaries.")]
+    OrderBookSummaryRequired,
+
+    // 6097
+    #[msg("Swap valuation exceeds the maximum agreed to by the proposer.")]
+    SwapValuationTooHigh,
 }
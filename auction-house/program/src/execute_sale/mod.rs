@@ -1,4 +1,17 @@
-use crate::{constants::*, errors::*, utils::*, AuctionHouse, AuthorityScope, *};
+use crate::{
+    assertions::*,
+    compliance::enforce_compliance,
+    constants::*,
+    dispute::{next_deferred_settlement, open_deferred_settlement},
+    epoch_report::record_epoch_sale,
+    errors::*,
+    metadata_reader::MetadataRoyalties,
+    order_book::*,
+    revenue_share::accrue_revenue_share,
+    token_interface::*,
+    utils::*,
+    AhEventKind, AuctionHouse, AuthorityScope, *,
+};
 use anchor_lang::{
     prelude::*,
     solana_program::{program::invoke, program_pack::Pack},
@@ -143,6 +156,7 @@ pub struct ExecuteSale<'info> {
     )]
     pub free_trade_state: UncheckedAccount<'info>,
 
+    /// Token program of the token sold and, unless Token-2022 owning it leads the remaining accounts, of the treasury mint.
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
     pub ata_program: Program<'info, AssociatedToken>,
@@ -194,21 +208,823 @@ pub fn execute_sale<'info>(
 ) -> Result<()> {
     let auction_house = &ctx.accounts.auction_house;
 
-    // If it has an auctioneer authority delegated must use auctioneer_* handler.
-    if auction_house.has_auctioneer {
-        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    execute_sale_logic(
+        ctx,
+        escrow_payment_bump,
+        free_trade_state_bump,
+        program_as_signer_bump,
+        buyer_price,
+        token_size,
+        partial_order_size,
+        partial_order_price,
+        false,
+        None,
+    )
+}
+
+/// Accounts for the [`execute_sale_v2` handler](auction_house/fn.execute_sale_v2.html).
+#[derive(Accounts, Clone)]
+#[instruction(buyer_price: u64, token_size: u64)]
+pub struct ExecuteSaleV2<'info> {
+    /// CHECK: Validated in execute_sale_logic.
+    /// Buyer user wallet account.
+    #[account(mut)]
+    pub buyer: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_sale_logic.
+    /// Seller user wallet account.
+    #[account(mut)]
+    pub seller: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_sale_logic.
+    // cannot mark these as real Accounts or else we blow stack size limit
+    ///Token account where the SPL token is stored.
+    #[account(mut)]
+    pub token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_sale_logic.
+    /// Token mint account for the SPL token.
+    pub token_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_sale_logic.
+    /// Metaplex metadata account decorating SPL mint account.
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_sale_logic.
+    // cannot mark these as real Accounts or else we blow stack size limit
+    /// Auction House treasury mint account.
+    pub treasury_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint with the canonical bump.
+    /// Buyer escrow payment account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            buyer.key().as_ref()
+        ],
+        bump
+    )]
+    pub escrow_payment_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_sale_logic.
+    /// Seller SOL or SPL account to receive payment at.
+    #[account(mut)]
+    pub seller_payment_receipt_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_sale_logic.
+    /// Buyer SPL token account to receive purchased item at.
+    #[account(mut)]
+    pub buyer_receipt_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_sale_logic.
+    /// Auction House instance authority.
+    pub authority: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority,
+        has_one=treasury_mint,
+        has_one=auction_house_treasury,
+        has_one=auction_house_fee_account
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance fee account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump=auction_house.fee_payer_bump
+    )]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance treasury account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            TREASURY.as_bytes()
+        ],
+        bump=auction_house.treasury_bump
+    )]
+    pub auction_house_treasury: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_sale_logic.
+    /// Buyer trade state PDA account encoding the buy order.
+    #[account(mut)]
+    pub buyer_trade_state: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_sale_logic.
+    /// Seller trade state PDA account encoding the sell order.
+    pub seller_trade_state: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint with the canonical bump.
+    /// Free seller trade state PDA account encoding a free sell order.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            seller.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_mint.key().as_ref(),
+            &0u64.to_le_bytes(),
+            &token_size.to_le_bytes()
+        ],
+        bump
+    )]
+    pub free_trade_state: UncheckedAccount<'info>,
+
+    /// Token program of the token sold and, unless Token-2022 owning it leads the remaining accounts, of the treasury mint.
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub ata_program: Program<'info, AssociatedToken>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint with the bump stored on the auction house.
+    #[account(seeds=[PREFIX.as_bytes(), SIGNER.as_bytes()], bump=get_program_as_signer_bump(&auction_house))]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> From<ExecuteSaleV2<'info>> for ExecuteSale<'info> {
+    fn from(a: ExecuteSaleV2<'info>) -> ExecuteSale<'info> {
+        ExecuteSale {
+            buyer: a.buyer,
+            seller: a.seller,
+            token_account: a.token_account,
+            token_mint: a.token_mint,
+            metadata: a.metadata,
+            treasury_mint: a.treasury_mint,
+            escrow_payment_account: a.escrow_payment_account,
+            seller_payment_receipt_account: a.seller_payment_receipt_account,
+            buyer_receipt_token_account: a.buyer_receipt_token_account,
+            authority: a.authority,
+            auction_house: a.auction_house,
+            auction_house_fee_account: a.auction_house_fee_account,
+            auction_house_treasury: a.auction_house_treasury,
+            buyer_trade_state: a.buyer_trade_state,
+            seller_trade_state: a.seller_trade_state,
+            free_trade_state: a.free_trade_state,
+            token_program: a.token_program,
+            system_program: a.system_program,
+            ata_program: a.ata_program,
+            program_as_signer: a.program_as_signer,
+            rent: a.rent,
+        }
+    }
+}
+
+/// Execute sale, with the escrow payment and free trade state bumps derived on chain and the program as signer bump
+/// read from the auction house instead of passed by the client.
+pub fn execute_sale_v2<'info>(
+    ctx: Context<'_, '_, '_, 'info, ExecuteSaleV2<'info>>,
+    buyer_price: u64,
+    token_size: u64,
+    partial_order_size: Option<u64>,
+    partial_order_price: Option<u64>,
+) -> Result<()> {
+    let auction_house = &ctx.accounts.auction_house;
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    let escrow_payment_bump = *ctx
+        .bumps
+        .get("escrow_payment_account")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+    let free_trade_state_bump = *ctx
+        .bumps
+        .get("free_trade_state")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+    let program_as_signer_bump = get_program_as_signer_bump(auction_house);
+
+    let mut accounts: ExecuteSale<'info> = (*ctx.accounts).clone().into();
+
+    execute_sale_logic(
+        Context::new(
+            ctx.program_id,
+            &mut accounts,
+            ctx.remaining_accounts,
+            ctx.bumps,
+        ),
+        escrow_payment_bump,
+        free_trade_state_bump,
+        program_as_signer_bump,
+        buyer_price,
+        token_size,
+        partial_order_size,
+        partial_order_price,
+        false,
+        None,
+    )
+}
+
+/// Accounts for the [`prepare_sale_accounts` handler](auction_house/fn.prepare_sale_accounts.html).
+#[derive(Accounts)]
+pub struct PrepareSaleAccounts<'info> {
+    /// CHECK: Owner of the buyer receipt token account.
+    /// Buyer user wallet account.
+    pub buyer: UncheckedAccount<'info>,
+
+    /// CHECK: Owner of the seller payment receipt account.
+    /// Seller user wallet account.
+    pub seller: UncheckedAccount<'info>,
+
+    /// CHECK: Used as the mint of the buyer receipt token account.
+    /// Token mint account for the SPL token.
+    pub token_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in prepare_sale_accounts.
+    /// Metaplex metadata account decorating SPL mint account.
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Checked in has_one constraint.
+    /// Auction House treasury mint account.
+    pub treasury_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Created in prepare_sale_accounts when the treasury is an SPL token.
+    /// Seller SPL account to receive payment at.
+    #[account(mut)]
+    pub seller_payment_receipt_account: UncheckedAccount<'info>,
+
+    /// CHECK: Created in prepare_sale_accounts.
+    /// Buyer SPL token account to receive purchased item at.
+    #[account(mut)]
+    pub buyer_receipt_token_account: UncheckedAccount<'info>,
+
+    /// Auction House instance authority.
+    pub authority: Signer<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority,
+        has_one=treasury_mint,
+        has_one=auction_house_fee_account
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance fee account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump=auction_house.fee_payer_bump
+    )]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// Token program of the token sold and, unless Token-2022 owning it leads the remaining accounts, of the treasury mint.
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub ata_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Create the associated token accounts a sale needs ahead of `execute_sale_prepared`, paid by the Auction House fee account.
+/// For SPL treasuries the creators and their token accounts are passed as remaining accounts, in metadata order, after
+/// the Token-2022 program when it owns the treasury mint.
+pub fn prepare_sale_accounts<'info>(
+    ctx: Context<'_, '_, '_, 'info, PrepareSaleAccounts<'info>>,
+) -> Result<()> {
+    let buyer = &ctx.accounts.buyer;
+    let seller = &ctx.accounts.seller;
+    let token_mint = &ctx.accounts.token_mint;
+    let metadata = &ctx.accounts.metadata;
+    let treasury_mint = &ctx.accounts.treasury_mint;
+    let seller_payment_receipt_account = &ctx.accounts.seller_payment_receipt_account;
+    let buyer_receipt_token_account = &ctx.accounts.buyer_receipt_token_account;
+    let auction_house = &ctx.accounts.auction_house;
+    let auction_house_fee_account = &ctx.accounts.auction_house_fee_account;
+    let token_program = &ctx.accounts.token_program;
+    let system_program = &ctx.accounts.system_program;
+    let ata_program = &ctx.accounts.ata_program;
+    let rent = &ctx.accounts.rent;
+
+    let is_native = treasury_mint.key() == spl_token::native_mint::id();
+
+    let auction_house_key = auction_house.key();
+    let fee_payer_seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        FEE_PAYER.as_bytes(),
+        &[auction_house.fee_payer_bump],
+    ];
+
+    if buyer_receipt_token_account.data_is_empty() {
+        make_ata(
+            buyer_receipt_token_account.to_account_info(),
+            buyer.to_account_info(),
+            token_mint.to_account_info(),
+            auction_house_fee_account.to_account_info(),
+            ata_program.to_account_info(),
+            token_program.to_account_info(),
+            system_program.to_account_info(),
+            rent.to_account_info(),
+            &fee_payer_seeds,
+        )?;
+    }
+    assert_is_ata(
+        &buyer_receipt_token_account.to_account_info(),
+        &buyer.key(),
+        &token_mint.key(),
+    )?;
+
+    emit_ah_event(
+        AhEventKind::PrepareSaleAccounts,
+        auction_house_key,
+        Some(token_mint.key()),
+        0,
+        vec![buyer.key(), seller.key()],
+    )?;
+
+    if is_native {
+        return Ok(());
+    }
+
+    let remaining_accounts = &mut ctx.remaining_accounts.iter();
+    let treasury_token_program = next_treasury_token_program(
+        remaining_accounts,
+        treasury_mint,
+        &token_program.to_account_info(),
+        is_native,
+    )?;
+
+    if seller_payment_receipt_account.data_is_empty() {
+        make_ata(
+            seller_payment_receipt_account.to_account_info(),
+            seller.to_account_info(),
+            treasury_mint.to_account_info(),
+            auction_house_fee_account.to_account_info(),
+            ata_program.to_account_info(),
+            treasury_token_program.clone(),
+            system_program.to_account_info(),
+            rent.to_account_info(),
+            &fee_payer_seeds,
+        )?;
+    }
+    assert_is_ata(
+        &seller_payment_receipt_account.to_account_info(),
+        &seller.key(),
+        &treasury_mint.key(),
+    )?;
+
+    assert_derivation(
+        &mpl_token_metadata::id(),
+        &metadata.to_account_info(),
+        &[
+            mpl_token_metadata::state::PREFIX.as_bytes(),
+            mpl_token_metadata::id().as_ref(),
+            token_mint.key().as_ref(),
+        ],
+    )?;
+    if metadata.data_is_empty() {
+        return Err(AuctionHouseError::MetadataDoesntExist.into());
+    }
+
+    let metadata_data = metadata.try_borrow_data()?;
+    for creator in MetadataRoyalties::from_bytes(&metadata_data)?.creators() {
+        let creator_info = next_account_info(remaining_accounts)?;
+        assert_keys_equal(creator.address, creator_info.key())?;
+        let creator_info = next_royalty_payee(remaining_accounts, creator_info)?;
+        let creator_token_account_info = next_account_info(remaining_accounts)?;
+        if creator_token_account_info.data_is_empty() {
+            make_ata(
+                creator_token_account_info.to_account_info(),
+                creator_info.to_account_info(),
+                treasury_mint.to_account_info(),
+                auction_house_fee_account.to_account_info(),
+                ata_program.to_account_info(),
+                treasury_token_program.clone(),
+                system_program.to_account_info(),
+                rent.to_account_info(),
+                &fee_payer_seeds,
+            )?;
+        }
+        assert_is_ata(
+            creator_token_account_info,
+            creator_info.key,
+            &treasury_mint.key(),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Execute sale like `execute_sale`, but without creating the buyer and seller receipt accounts. They must have been
+/// created beforehand with `prepare_sale_accounts`, keeping the sale within compute limits.
+pub fn execute_sale_prepared<'info>(
+    ctx: Context<'_, '_, '_, 'info, ExecuteSale<'info>>,
+    escrow_payment_bump: u8,
+    free_trade_state_bump: u8,
+    program_as_signer_bump: u8,
+    buyer_price: u64,
+    token_size: u64,
+    partial_order_size: Option<u64>,
+    partial_order_price: Option<u64>,
+) -> Result<()> {
+    let auction_house = &ctx.accounts.auction_house;
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    execute_sale_logic(
+        ctx,
+        escrow_payment_bump,
+        free_trade_state_bump,
+        program_as_signer_bump,
+        buyer_price,
+        token_size,
+        partial_order_size,
+        partial_order_price,
+        true,
+        None,
+    )
+}
+
+/// Accounts for the [`match_orders` handler](auction_house/fn.match_orders.html).
+#[derive(Accounts, Clone)]
+#[instruction(ask_price: u64, bid_price: u64, token_size: u64)]
+pub struct MatchOrders<'info> {
+    /// Permissionless cranker submitting the match, paid the match bounty.
+    pub cranker: Signer<'info>,
+
+    /// CHECK: Validated in match_orders.
+    /// Cranker SOL or SPL account to receive the match bounty at.
+    #[account(mut)]
+    pub cranker_payment_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_sale_logic.
+    /// Buyer user wallet account.
+    #[account(mut)]
+    pub buyer: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_sale_logic.
+    /// Seller user wallet account.
+    #[account(mut)]
+    pub seller: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_sale_logic.
+    // cannot mark these as real Accounts or else we blow stack size limit
+    ///Token account where the SPL token is stored.
+    #[account(mut)]
+    pub token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_sale_logic.
+    /// Token mint account for the SPL token.
+    pub token_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_sale_logic.
+    /// Metaplex metadata account decorating SPL mint account.
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_sale_logic.
+    // cannot mark these as real Accounts or else we blow stack size limit
+    /// Auction House treasury mint account.
+    pub treasury_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint with the canonical bump.
+    /// Buyer escrow payment account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            buyer.key().as_ref()
+        ],
+        bump
+    )]
+    pub escrow_payment_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_sale_logic.
+    /// Seller SOL or SPL account to receive payment at.
+    #[account(mut)]
+    pub seller_payment_receipt_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_sale_logic.
+    /// Buyer SPL token account to receive purchased item at.
+    #[account(mut)]
+    pub buyer_receipt_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_sale_logic.
+    /// Auction House instance authority.
+    pub authority: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority,
+        has_one=treasury_mint,
+        has_one=auction_house_treasury,
+        has_one=auction_house_fee_account
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance fee account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump=auction_house.fee_payer_bump
+    )]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance treasury account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            TREASURY.as_bytes()
+        ],
+        bump=auction_house.treasury_bump
+    )]
+    pub auction_house_treasury: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_sale_logic.
+    /// Buyer trade state PDA account encoding the buy order.
+    #[account(mut)]
+    pub buyer_trade_state: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_sale_logic.
+    /// Seller trade state PDA account encoding the sell order.
+    #[account(mut)]
+    pub seller_trade_state: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint with the canonical bump.
+    /// Free seller trade state PDA account encoding a free sell order.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            seller.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_mint.key().as_ref(),
+            &0u64.to_le_bytes(),
+            &token_size.to_le_bytes()
+        ],
+        bump
+    )]
+    pub free_trade_state: UncheckedAccount<'info>,
+
+    /// Token program of the token sold and, unless Token-2022 owning it leads the remaining accounts, of the treasury mint.
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub ata_program: Program<'info, AssociatedToken>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint with the bump stored on the auction house.
+    #[account(seeds=[PREFIX.as_bytes(), SIGNER.as_bytes()], bump=get_program_as_signer_bump(&auction_house))]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> From<MatchOrders<'info>> for ExecuteSale<'info> {
+    fn from(a: MatchOrders<'info>) -> ExecuteSale<'info> {
+        ExecuteSale {
+            buyer: a.buyer,
+            seller: a.seller,
+            token_account: a.token_account,
+            token_mint: a.token_mint,
+            metadata: a.metadata,
+            treasury_mint: a.treasury_mint,
+            escrow_payment_account: a.escrow_payment_account,
+            seller_payment_receipt_account: a.seller_payment_receipt_account,
+            buyer_receipt_token_account: a.buyer_receipt_token_account,
+            authority: a.authority,
+            auction_house: a.auction_house,
+            auction_house_fee_account: a.auction_house_fee_account,
+            auction_house_treasury: a.auction_house_treasury,
+            buyer_trade_state: a.buyer_trade_state,
+            seller_trade_state: a.seller_trade_state,
+            free_trade_state: a.free_trade_state,
+            token_program: a.token_program,
+            system_program: a.system_program,
+            ata_program: a.ata_program,
+            program_as_signer: a.program_as_signer,
+            rent: a.rent,
+        }
+    }
+}
+
+/// Settle a crossing bid and ask at the ask price, paying the cranker a bounty of
+/// `MATCH_ORDERS_BOUNTY_BASIS_POINTS` of the ask out of the spread, or out of the house fee when the orders are at the
+/// same price. Takes the same remaining accounts as `execute_sale`.
+pub fn match_orders<'info>(
+    ctx: Context<'_, '_, '_, 'info, MatchOrders<'info>>,
+    ask_price: u64,
+    bid_price: u64,
+    token_size: u64,
+) -> Result<()> {
+    let auction_house = &ctx.accounts.auction_house;
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    if auction_house.requires_sign_off {
+        return Err(AuctionHouseError::CannotTakeThisActionWithoutAuctionHouseSignOff.into());
+    }
+
+    if bid_price < ask_price {
+        return Err(AuctionHouseError::BidBelowAsk.into());
+    }
+
+    let escrow_payment_bump = *ctx
+        .bumps
+        .get("escrow_payment_account")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+    let free_trade_state_bump = *ctx
+        .bumps
+        .get("free_trade_state")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+    let program_as_signer_bump = get_program_as_signer_bump(auction_house);
+
+    let mut accounts: ExecuteSale<'info> = (*ctx.accounts).clone().into();
+
+    execute_sale_logic(
+        Context::new(
+            ctx.program_id,
+            &mut accounts,
+            ctx.remaining_accounts,
+            ctx.bumps.clone(),
+        ),
+        escrow_payment_bump,
+        free_trade_state_bump,
+        program_as_signer_bump,
+        ask_price,
+        token_size,
+        None,
+        None,
+        false,
+        Some(bid_price),
+    )?;
+
+    pay_match_bounty(&ctx, escrow_payment_bump, ask_price, bid_price)
+}
+
+fn pay_match_bounty<'info>(
+    ctx: &Context<'_, '_, '_, 'info, MatchOrders<'info>>,
+    escrow_payment_bump: u8,
+    ask_price: u64,
+    bid_price: u64,
+) -> Result<()> {
+    let cranker = &ctx.accounts.cranker;
+    let cranker_payment_account = &ctx.accounts.cranker_payment_account;
+    let buyer = &ctx.accounts.buyer;
+    let treasury_mint = &ctx.accounts.treasury_mint;
+    let escrow_payment_account = &ctx.accounts.escrow_payment_account;
+    let auction_house = &ctx.accounts.auction_house;
+    let auction_house_treasury = &ctx.accounts.auction_house_treasury;
+    let token_program = &ctx.accounts.token_program;
+    let system_program = &ctx.accounts.system_program;
+
+    let is_native = treasury_mint.key() == spl_token::native_mint::id();
+
+    let bounty = (ask_price as u128)
+        .checked_mul(MATCH_ORDERS_BOUNTY_BASIS_POINTS as u128)
+        .ok_or(AuctionHouseError::NumericalOverflow)?
+        .checked_div(10000)
+        .ok_or(AuctionHouseError::NumericalOverflow)? as u64;
+    let spread = bid_price
+        .checked_sub(ask_price)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+    // The spread belongs to the buyer, so the bounty only comes out of the house fee when there is none.
+    let (source, bounty) = if spread > 0 {
+        (escrow_payment_account.to_account_info(), bounty.min(spread))
+    } else {
+        let house_fee = (ask_price as u128)
+            .checked_mul(auction_house.seller_fee_basis_points as u128)
+            .ok_or(AuctionHouseError::NumericalOverflow)?
+            .checked_div(10000)
+            .ok_or(AuctionHouseError::NumericalOverflow)? as u64;
+        (
+            auction_house_treasury.to_account_info(),
+            bounty.min(house_fee),
+        )
+    };
+
+    if bounty == 0 {
+        return Ok(());
     }
 
-    execute_sale_logic(
-        ctx,
-        escrow_payment_bump,
-        free_trade_state_bump,
-        program_as_signer_bump,
-        buyer_price,
-        token_size,
-        partial_order_size,
-        partial_order_price,
-    )
+    let auction_house_key = auction_house.key();
+    let ah_seeds = [
+        PREFIX.as_bytes(),
+        auction_house.creator.as_ref(),
+        auction_house.treasury_mint.as_ref(),
+        &[auction_house.bump],
+    ];
+
+    if !is_native {
+        assert_is_ata(
+            &cranker_payment_account.to_account_info(),
+            &cranker.key(),
+            &treasury_mint.key(),
+        )?;
+
+        let treasury_token_program = next_treasury_token_program(
+            &mut ctx.remaining_accounts.iter(),
+            treasury_mint,
+            token_program,
+            is_native,
+        )?;
+        let available = unpack_token_account(&source)?.amount;
+
+        // Both the escrow and the treasury token accounts are owned by the auction house.
+        transfer(
+            &treasury_token_program,
+            &source,
+            treasury_mint,
+            cranker_payment_account,
+            &auction_house.to_account_info(),
+            bounty.min(available),
+            &[&ah_seeds],
+        )?;
+    } else {
+        assert_keys_equal(cranker_payment_account.key(), cranker.key())?;
+
+        let buyer_key = buyer.key();
+        let escrow_bump = [escrow_payment_bump];
+        let treasury_bump = [auction_house.treasury_bump];
+        let signer_seeds: [&[u8]; 4] = if spread > 0 {
+            [
+                PREFIX.as_bytes(),
+                auction_house_key.as_ref(),
+                buyer_key.as_ref(),
+                &escrow_bump,
+            ]
+        } else {
+            [
+                PREFIX.as_bytes(),
+                auction_house_key.as_ref(),
+                TREASURY.as_bytes(),
+                &treasury_bump,
+            ]
+        };
+
+        invoke_signed(
+            &system_instruction::transfer(
+                source.key,
+                cranker_payment_account.key,
+                rent_checked_sub(source.clone(), bounty)?,
+            ),
+            &[
+                source,
+                cranker_payment_account.to_account_info(),
+                system_program.to_account_info(),
+            ],
+            &[&signer_seeds],
+        )?;
+    }
+
+    Ok(())
 }
 
 #[derive(Accounts)]
@@ -374,6 +1190,7 @@ pub struct AuctioneerExecuteSale<'info> {
     )]
     pub ah_auctioneer_pda: UncheckedAccount<'info>,
 
+    /// Token program of the token sold and, unless Token-2022 owning it leads the remaining accounts, of the treasury mint.
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
     pub ata_program: Program<'info, AssociatedToken>,
@@ -408,6 +1225,16 @@ pub fn auctioneer_execute_sale<'info>(
         AuthorityScope::ExecuteSale,
     )?;
 
+    // Automatic purchase receipts are paid by the Auction House fee account.
+    if auction_house.auto_receipts {
+        assert_valid_auctioneer_and_scope(
+            &auction_house.key(),
+            &auctioneer_authority.key(),
+            ah_auctioneer_pda,
+            AuthorityScope::PrintReceipt,
+        )?;
+    }
+
     // Duplicate the logic methods to avoid going over the compute limit.
     auctioneer_execute_sale_logic(
         ctx,
@@ -420,6 +1247,8 @@ pub fn auctioneer_execute_sale<'info>(
 }
 
 /// Execute sale between provided buyer and seller trade state accounts transferring funds to seller wallet and token to buyer wallet.
+/// With a Token-2022 treasury mint charging a transfer fee the escrow is debited the full price, while the creators, the
+/// Auction House treasury and the seller each receive their share net of the fee.
 #[inline(never)]
 fn auctioneer_execute_sale_logic<'info>(
     ctx: Context<'_, '_, '_, 'info, AuctioneerExecuteSale<'info>>,
@@ -451,6 +1280,8 @@ fn auctioneer_execute_sale_logic<'info>(
     let program_as_signer = &ctx.accounts.program_as_signer;
     let rent = &ctx.accounts.rent;
 
+    assert_not_paused(auction_house, PAUSE_EXECUTE_SALE)?;
+
     let metadata_clone = metadata.to_account_info();
     let escrow_clone = escrow_payment_account.to_account_info();
     let auction_house_clone = auction_house.to_account_info();
@@ -470,10 +1301,15 @@ fn auctioneer_execute_sale_logic<'info>(
             AuctionHouseError::CannotMatchFreeSalesWithoutAuctionHouseOrSellerSignoff.into(),
         );
     }
+    if buyer_price == 0 && auction_house.free_sales_require_sign_off && !authority_clone.is_signer {
+        return Err(AuctionHouseError::FreeSaleRequiresAuctionHouseSignoff.into());
+    }
 
+    // Unchecked to save stack, so their raw bytes are checked instead.
+    assert_token_account_basic(&token_account_clone, &token_mint.key())?;
+    assert_mint_basic(&token_mint.to_account_info())?;
+    assert_mint_basic(&treasury_mint.to_account_info())?;
     let token_account_mint = get_mint_from_token_account(&token_account_clone)?;
-
-    assert_keys_equal(token_mint.key(), token_account_mint)?;
     let delegate = get_delegate_from_token_account(&token_account_clone)?;
     if let Some(d) = delegate {
         assert_keys_equal(program_as_signer.key(), d)?;
@@ -483,6 +1319,10 @@ fn auctioneer_execute_sale_logic<'info>(
     }
     let buyer_ts_data = &mut buyer_trade_state.try_borrow_mut_data()?;
     let seller_ts_data = &mut seller_trade_state.try_borrow_mut_data()?;
+    // Auctioneers do not forward the user approval custodial listings need.
+    if read_custodial_identity(&seller_ts_data[..]).is_some() {
+        return Err(AuctionHouseError::InvalidCustodialApproval.into());
+    }
     let ts_bump = buyer_ts_data[0];
     assert_valid_trade_state(
         &buyer.key(),
@@ -538,6 +1378,11 @@ fn auctioneer_execute_sale_logic<'info>(
     if is_native {
         let diff = rent_checked_sub(escrow_payment_account.to_account_info(), buyer_price)?;
         if diff != buyer_price {
+            emit_lenient_path(
+                auction_house_key,
+                escrow_payment_account.key(),
+                LenientPath::EscrowRentShortfall,
+            );
             // Return the shortfall amount (if greater than 0 but less than rent), but don't exceed the minimum rent the account should need.
             let shortfall = std::cmp::min(
                 buyer_price
@@ -585,15 +1430,28 @@ fn auctioneer_execute_sale_logic<'info>(
         ah_seeds
     };
 
+    let remaining_accounts = &mut ctx.remaining_accounts.iter();
+    let treasury_token_program =
+        next_treasury_token_program(remaining_accounts, treasury_mint, &token_clone, is_native)?;
+
+    if auction_house.enforce_royalties {
+        assert_royalties_enforced(
+            remaining_accounts.as_slice(),
+            &metadata_clone,
+            buyer_price,
+            is_native,
+        )?;
+    }
+
     let buyer_leftover_after_royalties = pay_creator_fees(
-        &mut ctx.remaining_accounts.iter(),
+        remaining_accounts,
         &metadata_clone,
         &escrow_clone,
         &auction_house_clone,
         &fee_payer_clone,
         treasury_mint,
         &ata_clone,
-        &token_clone,
+        &treasury_token_program,
         &sys_clone,
         &rent_clone,
         &signer_seeds_for_royalties,
@@ -606,7 +1464,8 @@ fn auctioneer_execute_sale_logic<'info>(
         auction_house,
         &treasury_clone,
         &escrow_clone,
-        &token_clone,
+        treasury_mint,
+        &treasury_token_program,
         &sys_clone,
         &signer_seeds_for_royalties,
         buyer_price,
@@ -617,6 +1476,44 @@ fn auctioneer_execute_sale_logic<'info>(
         .checked_sub(auction_house_fee_paid)
         .ok_or(AuctionHouseError::NumericalOverflow)?;
 
+    // The deferred settlement and, for SPL treasuries, its token account follow the creator accounts when the
+    // Auction House holds proceeds back for a dispute window.
+    let seller_payment_info = seller_payment_receipt_account.to_account_info();
+    let proceeds_account =
+        match next_deferred_settlement(remaining_accounts, auction_house, is_native)? {
+            Some((deferred_settlement_info, settlement_vault)) => {
+                let amount_held = if is_native {
+                    buyer_leftover_after_royalties_and_house_fee
+                } else {
+                    amount_after_transfer_fee(
+                        &treasury_mint.to_account_info(),
+                        buyer_leftover_after_royalties_and_house_fee,
+                    )?
+                };
+                open_deferred_settlement(
+                    deferred_settlement_info,
+                    settlement_vault,
+                    auction_house,
+                    &auction_house_fee_account.to_account_info(),
+                    &treasury_mint.to_account_info(),
+                    &ata_clone,
+                    &treasury_token_program,
+                    &sys_clone,
+                    &rent_clone,
+                    &seeds,
+                    &seller_trade_state.key(),
+                    &buyer_trade_state.key(),
+                    buyer.key(),
+                    seller.key(),
+                    seller_payment_receipt_account.key(),
+                    amount_held,
+                    is_native,
+                )?;
+                settlement_vault
+            }
+            None => &seller_payment_info,
+        };
+
     if !is_native {
         if seller_payment_receipt_account.data_is_empty() {
             make_ata(
@@ -625,7 +1522,7 @@ fn auctioneer_execute_sale_logic<'info>(
                 treasury_mint.to_account_info(),
                 fee_payer.to_account_info(),
                 ata_program.to_account_info(),
-                token_program.to_account_info(),
+                treasury_token_program.clone(),
                 system_program.to_account_info(),
                 rent.to_account_info(),
                 fee_payer_seeds,
@@ -643,21 +1540,14 @@ fn auctioneer_execute_sale_logic<'info>(
             return Err(AuctionHouseError::SellerATACannotHaveDelegate.into());
         }
 
-        invoke_signed(
-            &spl_token::instruction::transfer(
-                token_program.key,
-                &escrow_payment_account.key(),
-                &seller_payment_receipt_account.key(),
-                &auction_house.key(),
-                &[],
-                buyer_leftover_after_royalties_and_house_fee,
-            )?,
-            &[
-                escrow_payment_account.to_account_info(),
-                seller_payment_receipt_account.to_account_info(),
-                token_program.to_account_info(),
-                auction_house.to_account_info(),
-            ],
+        // The seller receives the proceeds net of any transfer fee of the treasury mint.
+        transfer(
+            &treasury_token_program,
+            escrow_payment_account,
+            treasury_mint,
+            proceeds_account,
+            &auction_house.to_account_info(),
+            buyer_leftover_after_royalties_and_house_fee,
             &[&ah_seeds],
         )?;
     } else {
@@ -665,12 +1555,12 @@ fn auctioneer_execute_sale_logic<'info>(
         invoke_signed(
             &system_instruction::transfer(
                 escrow_payment_account.key,
-                seller_payment_receipt_account.key,
+                proceeds_account.key,
                 buyer_leftover_after_royalties_and_house_fee,
             ),
             &[
                 escrow_payment_account.to_account_info(),
-                seller_payment_receipt_account.to_account_info(),
+                proceeds_account.clone(),
                 system_program.to_account_info(),
             ],
             &[&escrow_signer_seeds],
@@ -728,41 +1618,246 @@ fn auctioneer_execute_sale_logic<'info>(
         &[&program_as_signer_seeds],
     )?;
 
+    // The linked wallet registry entries of the buyer and seller follow the deferred settlement accounts when
+    // the wash trading guard is on.
+    if auction_house.wash_trading_guard {
+        let buyer_linked_wallet = next_account_info(remaining_accounts)?;
+        let seller_linked_wallet = next_account_info(remaining_accounts)?;
+        assert_not_self_trade(
+            &auction_house.key(),
+            &buyer.key(),
+            &seller.key(),
+            buyer_linked_wallet,
+            seller_linked_wallet,
+        )?;
+    }
+
+    // The purchase receipt, if any, follows in the remaining accounts.
+    if auction_house.auto_receipts {
+        let purchase_receipt_info = next_account_info(remaining_accounts)?;
+        print_purchase_receipt_from_sale(
+            purchase_receipt_info,
+            &auction_house_fee_account.to_account_info(),
+            &system_program.to_account_info(),
+            &rent.to_account_info(),
+            &seeds,
+            &seller_trade_state.key(),
+            &buyer_trade_state.key(),
+            PurchaseReceipt {
+                bookkeeper: auction_house_fee_account.key(),
+                buyer: buyer.key(),
+                seller: seller.key(),
+                auction_house: auction_house.key(),
+                metadata: metadata.key(),
+                token_size,
+                price: buyer_price,
+                bump: 0,
+                created_at: Clock::get()?.unix_timestamp,
+                self_trade: buyer.key() == seller.key(),
+                sold_by_creator: is_verified_creator(&metadata.to_account_info(), &seller.key())?,
+                disputed: false,
+            },
+        )?;
+    }
+
+    // The revenue share account and, for SPL treasuries, its token account follow the purchase receipt.
+    if auction_house.has_revenue_share {
+        let revenue_share_info = next_account_info(remaining_accounts)?;
+        let revenue_share_vault = if is_native {
+            revenue_share_info
+        } else {
+            next_account_info(remaining_accounts)?
+        };
+        accrue_revenue_share(
+            auction_house,
+            &auction_house_treasury.to_account_info(),
+            revenue_share_info,
+            revenue_share_vault,
+            &treasury_token_program,
+            &system_program.to_account_info(),
+            auction_house_fee_paid,
+            is_native,
+        )?;
+    }
+
+    // The compliance config and the buyer and seller wallet volume PDAs follow the revenue share accounts.
+    if auction_house.has_compliance {
+        let compliance_config_info = next_account_info(remaining_accounts)?;
+        let buyer_wallet_volume = next_account_info(remaining_accounts)?;
+        let seller_wallet_volume = next_account_info(remaining_accounts)?;
+        enforce_compliance(
+            &auction_house_key,
+            compliance_config_info,
+            [
+                (&buyer.key(), buyer_wallet_volume),
+                (&seller.key(), seller_wallet_volume),
+            ],
+            &auction_house_fee_account.to_account_info(),
+            &system_program.to_account_info(),
+            &rent.to_account_info(),
+            &seeds,
+            buyer_price,
+        )?;
+    }
+
+    // The seller's free sale counter follows the compliance accounts when free sales are capped.
+    if buyer_price == 0 && auction_house.max_free_sales_per_epoch > 0 {
+        let free_sale_counter_info = next_account_info(remaining_accounts)?;
+        record_free_sale(
+            auction_house,
+            &auction_house_key,
+            &seller.key(),
+            free_sale_counter_info,
+            &fee_payer_clone,
+            &system_program.to_account_info(),
+            &rent.to_account_info(),
+            fee_payer_seeds,
+        )?;
+    }
+
+    // The epoch report follows the free sale counter when epoch reports are kept.
+    if auction_house.epoch_report_retention > 0 {
+        let epoch_report_info = next_account_info(remaining_accounts)?;
+        let royalties_paid = buyer_price
+            .checked_sub(buyer_leftover_after_royalties)
+            .ok_or(AuctionHouseError::NumericalOverflow)?;
+        record_epoch_sale(
+            &auction_house_key,
+            epoch_report_info,
+            &fee_payer_clone,
+            &system_program.to_account_info(),
+            &rent.to_account_info(),
+            fee_payer_seeds,
+            buyer_price,
+            auction_house_fee_paid,
+            royalties_paid,
+        )?;
+    }
+
+    // The last sale PDA of the mint follows the epoch report while the listing cooldown is on.
+    if auction_house.listing_cooldown_slots > 0 {
+        let last_sale_info = next_account_info(remaining_accounts)?;
+        record_last_sale(
+            &auction_house_key,
+            &token_mint.key(),
+            &buyer.key(),
+            &seller.key(),
+            last_sale_info,
+            &fee_payer_clone,
+            &system_program.to_account_info(),
+            &rent.to_account_info(),
+            fee_payer_seeds,
+        )?;
+    }
+
+    let order_book_summary =
+        find_order_book_summary(ctx.remaining_accounts, auction_house, &token_mint.key())?;
+    record_order_closed(
+        order_book_summary,
+        &auction_house_key,
+        &token_mint.key(),
+        OrderSide::Ask,
+        u64::MAX,
+    )?;
+    record_order_closed(
+        order_book_summary,
+        &auction_house_key,
+        &token_mint.key(),
+        OrderSide::Bid,
+        buyer_price,
+    )?;
+
+    // Trade state rent goes back to whoever funded it, not whoever pays for the sale.
+    emit_if_legacy_trade_state(
+        auction_house_key,
+        seller_trade_state.key(),
+        &seller_ts_data[..],
+    );
+    // A sponsor who paid for the listing, as for signed listings, is passed among the remaining accounts.
+    let seller_info = seller.to_account_info();
+    let auction_house_fee_account_info = auction_house_fee_account.to_account_info();
+    let seller_rent_recipient_candidates: Vec<&AccountInfo<'info>> =
+        [&seller_info, &auction_house_fee_account_info]
+            .into_iter()
+            .chain(ctx.remaining_accounts)
+            .collect();
+    let seller_rent_recipient = trade_state_rent_recipient(
+        &seller_ts_data[..],
+        &seller_rent_recipient_candidates,
+        &fee_payer,
+    )?;
     let curr_seller_lamp = seller_trade_state.lamports();
     **seller_trade_state.lamports.borrow_mut() = 0;
-    sol_memset(&mut *seller_ts_data, 0, TRADE_STATE_SIZE);
+    let seller_ts_len = seller_ts_data.len();
+    sol_memset(&mut *seller_ts_data, 0, seller_ts_len);
 
-    **fee_payer.lamports.borrow_mut() = fee_payer
+    **seller_rent_recipient.lamports.borrow_mut() = seller_rent_recipient
         .lamports()
         .checked_add(curr_seller_lamp)
         .ok_or(AuctionHouseError::NumericalOverflow)?;
 
+    emit_if_legacy_trade_state(
+        auction_house_key,
+        buyer_trade_state.key(),
+        &buyer_ts_data[..],
+    );
+    let buyer_rent_recipient = trade_state_rent_recipient(
+        &buyer_ts_data[..],
+        &[
+            &buyer.to_account_info(),
+            &auction_house_fee_account.to_account_info(),
+        ],
+        &fee_payer,
+    )?;
     let curr_buyer_lamp = buyer_trade_state.lamports();
     **buyer_trade_state.lamports.borrow_mut() = 0;
-    sol_memset(&mut *buyer_ts_data, 0, TRADE_STATE_SIZE);
-    **fee_payer.lamports.borrow_mut() = fee_payer
+    let buyer_ts_len = buyer_ts_data.len();
+    sol_memset(&mut *buyer_ts_data, 0, buyer_ts_len);
+    **buyer_rent_recipient.lamports.borrow_mut() = buyer_rent_recipient
         .lamports()
         .checked_add(curr_buyer_lamp)
         .ok_or(AuctionHouseError::NumericalOverflow)?;
 
     if free_trade_state.lamports() > 0 {
+        record_order_closed(
+            order_book_summary,
+            &auction_house_key,
+            &token_mint.key(),
+            OrderSide::Ask,
+            0,
+        )?;
+        let free_rent_recipient = trade_state_rent_recipient(
+            &free_trade_state.try_borrow_data()?,
+            &seller_rent_recipient_candidates,
+            &fee_payer,
+        )?;
         let curr_buyer_lamp = free_trade_state.lamports();
         **free_trade_state.lamports.borrow_mut() = 0;
 
-        **fee_payer.lamports.borrow_mut() = fee_payer
+        **free_rent_recipient.lamports.borrow_mut() = free_rent_recipient
             .lamports()
             .checked_add(curr_buyer_lamp)
             .ok_or(AuctionHouseError::NumericalOverflow)?;
-        sol_memset(
-            *free_trade_state.try_borrow_mut_data()?,
-            0,
-            TRADE_STATE_SIZE,
-        );
+        let free_ts_len = free_trade_state.data_len();
+        sol_memset(*free_trade_state.try_borrow_mut_data()?, 0, free_ts_len);
     }
+
+    emit_if_fee_account_low(auction_house, &auction_house_fee_account.to_account_info());
+
+    emit_ah_event(
+        AhEventKind::ExecuteSale,
+        auction_house.key(),
+        Some(token_mint.key()),
+        buyer_price,
+        vec![buyer.key(), seller.key()],
+    )?;
+
     Ok(())
 }
 
 /// Execute sale between provided buyer and seller trade state accounts transferring funds to seller wallet and token to buyer wallet.
+/// With a Token-2022 treasury mint charging a transfer fee the escrow is debited the full price, while the creators, the
+/// Auction House treasury and the seller each receive their share net of the fee.
 #[inline(never)]
 fn execute_sale_logic<'info>(
     ctx: Context<'_, '_, '_, 'info, ExecuteSale<'info>>,
@@ -773,6 +1868,8 @@ fn execute_sale_logic<'info>(
     token_size: u64,
     partial_order_size: Option<u64>,
     partial_order_price: Option<u64>,
+    prepared: bool,
+    matched_bid_price: Option<u64>,
 ) -> Result<()> {
     let buyer = &ctx.accounts.buyer;
     let seller = &ctx.accounts.seller;
@@ -796,6 +1893,8 @@ fn execute_sale_logic<'info>(
     let program_as_signer = &ctx.accounts.program_as_signer;
     let rent = &ctx.accounts.rent;
 
+    assert_not_paused(auction_house, PAUSE_EXECUTE_SALE)?;
+
     let metadata_clone = metadata.to_account_info();
     let escrow_clone = escrow_payment_account.to_account_info();
     let auction_house_clone = auction_house.to_account_info();
@@ -815,10 +1914,15 @@ fn execute_sale_logic<'info>(
             AuctionHouseError::CannotMatchFreeSalesWithoutAuctionHouseOrSellerSignoff.into(),
         );
     }
+    if buyer_price == 0 && auction_house.free_sales_require_sign_off && !authority_clone.is_signer {
+        return Err(AuctionHouseError::FreeSaleRequiresAuctionHouseSignoff.into());
+    }
 
+    // Unchecked to save stack, so their raw bytes are checked instead.
+    assert_token_account_basic(&token_account_clone, &token_mint.key())?;
+    assert_mint_basic(&token_mint.to_account_info())?;
+    assert_mint_basic(&treasury_mint.to_account_info())?;
     let token_account_mint = get_mint_from_token_account(&token_account_clone)?;
-
-    assert_keys_equal(token_mint.key(), token_account_mint)?;
     let delegate = get_delegate_from_token_account(&token_account_clone)?;
     if let Some(d) = delegate {
         assert_keys_equal(program_as_signer.key(), d)?;
@@ -829,6 +1933,7 @@ fn execute_sale_logic<'info>(
 
     let buyer_ts_data = &mut buyer_trade_state.try_borrow_mut_data()?;
     let seller_ts_data = &mut seller_trade_state.try_borrow_mut_data()?;
+    let custodial_identity = read_custodial_identity(&seller_ts_data[..]);
     let ts_bump = if buyer_ts_data.len() > 0 {
         buyer_ts_data[0]
     } else {
@@ -865,10 +1970,11 @@ fn execute_sale_logic<'info>(
             (size, price)
         }
         (None, None) => {
+            // A matched bid may be above the ask the sale settles at.
             assert_valid_trade_state(
                 &buyer.key(),
                 auction_house,
-                buyer_price,
+                matched_bid_price.unwrap_or(buyer_price),
                 token_size,
                 buyer_trade_state,
                 &token_mint.key(),
@@ -891,6 +1997,20 @@ fn execute_sale_logic<'info>(
         return Err(AuctionHouseError::BothPartiesNeedToAgreeToSale.into());
     }
 
+    // Matched orders settle at the ask, which the seller trade state must encode.
+    if matched_bid_price.is_some() {
+        assert_valid_trade_state(
+            &seller.key(),
+            auction_house,
+            buyer_price,
+            token_size,
+            seller_trade_state,
+            &token_mint.key(),
+            &token_account.key(),
+            seller_ts_data[0],
+        )?;
+    }
+
     let auction_house_key = auction_house.key();
     let seeds = [
         PREFIX.as_bytes(),
@@ -901,13 +2021,18 @@ fn execute_sale_logic<'info>(
 
     let wallet_to_use = if buyer.is_signer { buyer } else { seller };
 
-    let (fee_payer, fee_payer_seeds) = get_fee_payer(
-        authority,
-        auction_house,
-        wallet_to_use.to_account_info(),
-        auction_house_fee_account.to_account_info(),
-        &seeds,
-    )?;
+    // Neither party signs a permissionless match, so the Auction House fee account pays for it.
+    let (fee_payer, fee_payer_seeds) = if matched_bid_price.is_some() {
+        (auction_house_fee_account.to_account_info(), &seeds[..])
+    } else {
+        get_fee_payer(
+            authority,
+            auction_house,
+            wallet_to_use.to_account_info(),
+            auction_house_fee_account.to_account_info(),
+            &seeds,
+        )?
+    };
     let fee_payer_clone = fee_payer.to_account_info();
 
     assert_is_ata(
@@ -931,6 +2056,11 @@ fn execute_sale_logic<'info>(
     if is_native {
         let diff = rent_checked_sub(escrow_payment_account.to_account_info(), price)?;
         if diff != price {
+            emit_lenient_path(
+                auction_house_key,
+                escrow_payment_account.key(),
+                LenientPath::EscrowRentShortfall,
+            );
             // Return the shortfall amount (if greater than 0 but less than rent), but don't exceed the minimum rent the account should need.
             let shortfall = std::cmp::min(
                 price
@@ -978,15 +2108,44 @@ fn execute_sale_logic<'info>(
         ah_seeds
     };
 
+    let remaining_accounts = &mut ctx.remaining_accounts.iter();
+    let treasury_token_program =
+        next_treasury_token_program(remaining_accounts, treasury_mint, &token_clone, is_native)?;
+
+    if auction_house.enforce_royalties {
+        assert_royalties_enforced(
+            remaining_accounts.as_slice(),
+            &metadata_clone,
+            price,
+            is_native,
+        )?;
+    }
+
+    #[cfg(feature = "ledger-checks")]
+    let mut ledger = crate::ledger::post_sale(
+        remaining_accounts,
+        auction_house,
+        &metadata_clone,
+        &treasury_mint.to_account_info(),
+        &escrow_clone,
+        &treasury_clone,
+        &seller_payment_receipt_account.to_account_info(),
+        &token_account_clone,
+        &buyer_receipt_clone,
+        price,
+        size,
+        is_native,
+    )?;
+
     let buyer_leftover_after_royalties = pay_creator_fees(
-        &mut ctx.remaining_accounts.iter(),
+        remaining_accounts,
         &metadata_clone,
         &escrow_clone,
         &auction_house_clone,
         &fee_payer_clone,
         treasury_mint,
         &ata_clone,
-        &token_clone,
+        &treasury_token_program,
         &sys_clone,
         &rent_clone,
         &signer_seeds_for_royalties,
@@ -999,7 +2158,8 @@ fn execute_sale_logic<'info>(
         auction_house,
         &treasury_clone,
         &escrow_clone,
-        &token_clone,
+        treasury_mint,
+        &treasury_token_program,
         &sys_clone,
         &signer_seeds_for_royalties,
         price,
@@ -1010,60 +2170,115 @@ fn execute_sale_logic<'info>(
         .checked_sub(auction_house_fee_paid)
         .ok_or(AuctionHouseError::NumericalOverflow)?;
 
-    if !is_native {
-        if seller_payment_receipt_account.data_is_empty() {
-            make_ata(
-                seller_payment_receipt_account.to_account_info(),
-                seller.to_account_info(),
-                treasury_mint.to_account_info(),
-                fee_payer.to_account_info(),
-                ata_program.to_account_info(),
-                token_program.to_account_info(),
-                system_program.to_account_info(),
-                rent.to_account_info(),
-                fee_payer_seeds,
-            )?;
+    // The proceeds route address of the listing follows the creator accounts, whether or not the seller set one.
+    let proceeds_destination =
+        next_proceeds_destination(remaining_accounts, &seller.key(), &seller_trade_state.key())?;
+    if let Some(destination) = proceeds_destination {
+        if seller_payment_receipt_account.key() != destination {
+            return Err(AuctionHouseError::InvalidProceedsRoute.into());
         }
+    }
 
-        let seller_rec_acct = assert_is_ata(
-            &seller_payment_receipt_account.to_account_info(),
-            &seller.key(),
-            &treasury_mint.key(),
-        )?;
+    // The deferred settlement and, for SPL treasuries, its token account follow the proceeds route when the
+    // Auction House holds proceeds back for a dispute window.
+    let seller_payment_info = seller_payment_receipt_account.to_account_info();
+    let proceeds_account =
+        match next_deferred_settlement(remaining_accounts, auction_house, is_native)? {
+            Some((deferred_settlement_info, settlement_vault)) => {
+                let amount_held = if is_native {
+                    buyer_leftover_after_royalties_and_house_fee
+                } else {
+                    amount_after_transfer_fee(
+                        &treasury_mint.to_account_info(),
+                        buyer_leftover_after_royalties_and_house_fee,
+                    )?
+                };
+                open_deferred_settlement(
+                    deferred_settlement_info,
+                    settlement_vault,
+                    auction_house,
+                    &auction_house_fee_account.to_account_info(),
+                    &treasury_mint.to_account_info(),
+                    &ata_clone,
+                    &treasury_token_program,
+                    &sys_clone,
+                    &rent_clone,
+                    &seeds,
+                    &seller_trade_state.key(),
+                    &buyer_trade_state.key(),
+                    buyer.key(),
+                    seller.key(),
+                    seller_payment_receipt_account.key(),
+                    amount_held,
+                    is_native,
+                )?;
+                #[cfg(feature = "ledger-checks")]
+                ledger.reroute(&seller_payment_info, settlement_vault, !is_native)?;
+                settlement_vault
+            }
+            None => &seller_payment_info,
+        };
+
+    if !is_native {
+        let seller_rec_acct = if proceeds_destination.is_some() {
+            // A routed destination may be any token account of the treasury mint, e.g. of a split program.
+            let routed_acct =
+                unpack_token_account(&seller_payment_receipt_account.to_account_info())?;
+            assert_keys_equal(routed_acct.mint, treasury_mint.key())?;
+            routed_acct
+        } else {
+            if seller_payment_receipt_account.data_is_empty() {
+                if prepared {
+                    return Err(AuctionHouseError::SaleAccountsNotPrepared.into());
+                }
+                make_ata(
+                    seller_payment_receipt_account.to_account_info(),
+                    seller.to_account_info(),
+                    treasury_mint.to_account_info(),
+                    fee_payer.to_account_info(),
+                    ata_program.to_account_info(),
+                    treasury_token_program.clone(),
+                    system_program.to_account_info(),
+                    rent.to_account_info(),
+                    fee_payer_seeds,
+                )?;
+            }
+
+            assert_is_ata(
+                &seller_payment_receipt_account.to_account_info(),
+                &seller.key(),
+                &treasury_mint.key(),
+            )?
+        };
 
         // make sure you cant get rugged
         if seller_rec_acct.delegate.is_some() {
             return Err(AuctionHouseError::SellerATACannotHaveDelegate.into());
         }
 
-        invoke_signed(
-            &spl_token::instruction::transfer(
-                token_program.key,
-                &escrow_payment_account.key(),
-                &seller_payment_receipt_account.key(),
-                &auction_house.key(),
-                &[],
-                buyer_leftover_after_royalties_and_house_fee,
-            )?,
-            &[
-                escrow_payment_account.to_account_info(),
-                seller_payment_receipt_account.to_account_info(),
-                token_program.to_account_info(),
-                auction_house.to_account_info(),
-            ],
+        // The seller receives the proceeds net of any transfer fee of the treasury mint.
+        transfer(
+            &treasury_token_program,
+            escrow_payment_account,
+            treasury_mint,
+            proceeds_account,
+            &auction_house.to_account_info(),
+            buyer_leftover_after_royalties_and_house_fee,
             &[&ah_seeds],
         )?;
     } else {
-        assert_keys_equal(seller_payment_receipt_account.key(), seller.key())?;
+        if proceeds_destination.is_none() {
+            assert_keys_equal(seller_payment_receipt_account.key(), seller.key())?;
+        }
         invoke_signed(
             &system_instruction::transfer(
                 escrow_payment_account.key,
-                seller_payment_receipt_account.key,
+                proceeds_account.key,
                 buyer_leftover_after_royalties_and_house_fee,
             ),
             &[
                 escrow_payment_account.to_account_info(),
-                seller_payment_receipt_account.to_account_info(),
+                proceeds_account.clone(),
                 system_program.to_account_info(),
             ],
             &[&escrow_signer_seeds],
@@ -1071,6 +2286,9 @@ fn execute_sale_logic<'info>(
     }
 
     if buyer_receipt_token_account.data_is_empty() {
+        if prepared {
+            return Err(AuctionHouseError::SaleAccountsNotPrepared.into());
+        }
         make_ata(
             buyer_receipt_token_account.to_account_info(),
             buyer.to_account_info(),
@@ -1115,6 +2333,169 @@ fn execute_sale_logic<'info>(
         &[&program_as_signer_seeds],
     )?;
 
+    // Checked before the house fee is shared out of the treasury below.
+    #[cfg(feature = "ledger-checks")]
+    ledger.verify(fee_payer.key)?;
+
+    // The linked wallet registry entries of the buyer and seller follow the deferred settlement accounts when
+    // the wash trading guard is on.
+    if auction_house.wash_trading_guard {
+        let buyer_linked_wallet = next_account_info(remaining_accounts)?;
+        let seller_linked_wallet = next_account_info(remaining_accounts)?;
+        assert_not_self_trade(
+            &auction_house.key(),
+            &buyer.key(),
+            &seller.key(),
+            buyer_linked_wallet,
+            seller_linked_wallet,
+        )?;
+    }
+
+    // The purchase receipt, if any, follows in the remaining accounts.
+    if auction_house.auto_receipts {
+        let purchase_receipt_info = next_account_info(remaining_accounts)?;
+        print_purchase_receipt_from_sale(
+            purchase_receipt_info,
+            &auction_house_fee_account.to_account_info(),
+            &system_program.to_account_info(),
+            &rent.to_account_info(),
+            &seeds,
+            &seller_trade_state.key(),
+            &buyer_trade_state.key(),
+            PurchaseReceipt {
+                bookkeeper: auction_house_fee_account.key(),
+                buyer: buyer.key(),
+                seller: seller.key(),
+                auction_house: auction_house.key(),
+                metadata: metadata.key(),
+                token_size: size,
+                price,
+                bump: 0,
+                created_at: Clock::get()?.unix_timestamp,
+                self_trade: buyer.key() == seller.key(),
+                sold_by_creator: is_verified_creator(&metadata.to_account_info(), &seller.key())?,
+                disputed: false,
+            },
+        )?;
+    }
+
+    // The revenue share account and, for SPL treasuries, its token account follow the purchase receipt.
+    if auction_house.has_revenue_share {
+        let revenue_share_info = next_account_info(remaining_accounts)?;
+        let revenue_share_vault = if is_native {
+            revenue_share_info
+        } else {
+            next_account_info(remaining_accounts)?
+        };
+        accrue_revenue_share(
+            auction_house,
+            &auction_house_treasury.to_account_info(),
+            revenue_share_info,
+            revenue_share_vault,
+            &treasury_token_program,
+            &system_program.to_account_info(),
+            auction_house_fee_paid,
+            is_native,
+        )?;
+    }
+
+    // The compliance config and the buyer and seller wallet volume PDAs follow the revenue share accounts.
+    if auction_house.has_compliance {
+        let compliance_config_info = next_account_info(remaining_accounts)?;
+        let buyer_wallet_volume = next_account_info(remaining_accounts)?;
+        let seller_wallet_volume = next_account_info(remaining_accounts)?;
+        enforce_compliance(
+            &auction_house_key,
+            compliance_config_info,
+            [
+                (&buyer.key(), buyer_wallet_volume),
+                (&seller.key(), seller_wallet_volume),
+            ],
+            &auction_house_fee_account.to_account_info(),
+            &system_program.to_account_info(),
+            &rent.to_account_info(),
+            &seeds,
+            price,
+        )?;
+    }
+
+    // The seller's free sale counter follows the compliance accounts when free sales are capped.
+    if buyer_price == 0 && auction_house.max_free_sales_per_epoch > 0 {
+        let free_sale_counter_info = next_account_info(remaining_accounts)?;
+        record_free_sale(
+            auction_house,
+            &auction_house_key,
+            &seller.key(),
+            free_sale_counter_info,
+            &fee_payer_clone,
+            &system_program.to_account_info(),
+            &rent.to_account_info(),
+            fee_payer_seeds,
+        )?;
+    }
+
+    // The epoch report follows the free sale counter when epoch reports are kept.
+    if auction_house.epoch_report_retention > 0 {
+        let epoch_report_info = next_account_info(remaining_accounts)?;
+        let royalties_paid = price
+            .checked_sub(buyer_leftover_after_royalties)
+            .ok_or(AuctionHouseError::NumericalOverflow)?;
+        record_epoch_sale(
+            &auction_house_key,
+            epoch_report_info,
+            &fee_payer_clone,
+            &system_program.to_account_info(),
+            &rent.to_account_info(),
+            fee_payer_seeds,
+            price,
+            auction_house_fee_paid,
+            royalties_paid,
+        )?;
+    }
+
+    // The last sale PDA of the mint follows the epoch report while the listing cooldown is on.
+    if auction_house.listing_cooldown_slots > 0 {
+        let last_sale_info = next_account_info(remaining_accounts)?;
+        record_last_sale(
+            &auction_house_key,
+            &token_mint.key(),
+            &buyer.key(),
+            &seller.key(),
+            last_sale_info,
+            &fee_payer_clone,
+            &system_program.to_account_info(),
+            &rent.to_account_info(),
+            fee_payer_seeds,
+        )?;
+    }
+
+    // The instructions sysvar follows the last sale PDA when the listing was made by a custodial platform.
+    if let Some(identity) = custodial_identity {
+        let instructions_info = next_account_info(remaining_accounts)?;
+        let user = verify_custodial_approval(
+            instructions_info,
+            &identity,
+            &custodial_approval_message(
+                &auction_house_key,
+                &seller_trade_state.key(),
+                &buyer.key(),
+                price,
+                size,
+            ),
+        )?;
+        emit!(CustodialSaleApproved {
+            auction_house: auction_house_key,
+            seller_trade_state: seller_trade_state.key(),
+            user,
+            buyer: buyer.key(),
+            price,
+            token_size: size,
+        });
+    }
+
+    let order_book_summary =
+        find_order_book_summary(ctx.remaining_accounts, auction_house, &token_mint.key())?;
+
     if token_account_data.amount == 0 {
         invoke(
             &revoke(
@@ -1131,38 +2512,106 @@ fn execute_sale_logic<'info>(
             ],
         )?;
 
+        record_order_closed(
+            order_book_summary,
+            &auction_house_key,
+            &token_mint.key(),
+            OrderSide::Ask,
+            buyer_price,
+        )?;
+        record_order_closed(
+            order_book_summary,
+            &auction_house_key,
+            &token_mint.key(),
+            OrderSide::Bid,
+            matched_bid_price.unwrap_or(buyer_price),
+        )?;
+
+        // Trade state rent goes back to whoever funded it, not whoever pays for the sale.
+        emit_if_legacy_trade_state(
+            auction_house_key,
+            seller_trade_state.key(),
+            &seller_ts_data[..],
+        );
+        // A sponsor who paid for the listing, as for signed listings, is passed among the remaining accounts.
+        let seller_info = seller.to_account_info();
+        let auction_house_fee_account_info = auction_house_fee_account.to_account_info();
+        let seller_rent_recipient_candidates: Vec<&AccountInfo<'info>> =
+            [&seller_info, &auction_house_fee_account_info]
+                .into_iter()
+                .chain(ctx.remaining_accounts)
+                .collect();
+        let seller_rent_recipient = trade_state_rent_recipient(
+            &seller_ts_data[..],
+            &seller_rent_recipient_candidates,
+            &fee_payer,
+        )?;
         let curr_seller_lamp = seller_trade_state.lamports();
         **seller_trade_state.lamports.borrow_mut() = 0;
-        sol_memset(&mut *seller_ts_data, 0, TRADE_STATE_SIZE);
+        let seller_ts_len = seller_ts_data.len();
+        sol_memset(&mut *seller_ts_data, 0, seller_ts_len);
 
-        **fee_payer.lamports.borrow_mut() = fee_payer
+        **seller_rent_recipient.lamports.borrow_mut() = seller_rent_recipient
             .lamports()
             .checked_add(curr_seller_lamp)
             .ok_or(AuctionHouseError::NumericalOverflow)?;
 
         if free_trade_state.lamports() > 0 {
+            record_order_closed(
+                order_book_summary,
+                &auction_house_key,
+                &token_mint.key(),
+                OrderSide::Ask,
+                0,
+            )?;
+            let free_rent_recipient = trade_state_rent_recipient(
+                &free_trade_state.try_borrow_data()?,
+                &seller_rent_recipient_candidates,
+                &fee_payer,
+            )?;
             let curr_buyer_lamp = free_trade_state.lamports();
             **free_trade_state.lamports.borrow_mut() = 0;
 
-            **fee_payer.lamports.borrow_mut() = fee_payer
+            **free_rent_recipient.lamports.borrow_mut() = free_rent_recipient
                 .lamports()
                 .checked_add(curr_buyer_lamp)
                 .ok_or(AuctionHouseError::NumericalOverflow)?;
-            sol_memset(
-                *free_trade_state.try_borrow_mut_data()?,
-                0,
-                TRADE_STATE_SIZE,
-            );
+            let free_ts_len = free_trade_state.data_len();
+            sol_memset(*free_trade_state.try_borrow_mut_data()?, 0, free_ts_len);
         }
 
+        emit_if_legacy_trade_state(
+            auction_house_key,
+            buyer_trade_state.key(),
+            &buyer_ts_data[..],
+        );
+        let buyer_rent_recipient = trade_state_rent_recipient(
+            &buyer_ts_data[..],
+            &[
+                &buyer.to_account_info(),
+                &auction_house_fee_account.to_account_info(),
+            ],
+            &fee_payer,
+        )?;
         let curr_buyer_lamp = buyer_trade_state.lamports();
         **buyer_trade_state.lamports.borrow_mut() = 0;
-        sol_memset(&mut *buyer_ts_data, 0, TRADE_STATE_SIZE);
-        **fee_payer.lamports.borrow_mut() = fee_payer
+        let buyer_ts_len = buyer_ts_data.len();
+        sol_memset(&mut *buyer_ts_data, 0, buyer_ts_len);
+        **buyer_rent_recipient.lamports.borrow_mut() = buyer_rent_recipient
             .lamports()
             .checked_add(curr_buyer_lamp)
             .ok_or(AuctionHouseError::NumericalOverflow)?;
     };
 
+    emit_if_fee_account_low(auction_house, &auction_house_fee_account.to_account_info());
+
+    emit_ah_event(
+        AhEventKind::ExecuteSale,
+        auction_house.key(),
+        Some(token_mint.key()),
+        price,
+        vec![buyer.key(), seller.key()],
+    )?;
+
     Ok(())
 }
@@ -0,0 +1,255 @@
+use anchor_lang::{prelude::*, solana_program::program::invoke_signed};
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use solana_program::program_memory::sol_memset;
+
+use crate::{
+    constants::*, errors::AuctionHouseError, order_book::*, utils::*, AuctionHouse, Layaway,
+};
+
+/// Accounts for the [`begin_layaway` handler](auction_house/fn.begin_layaway.html).
+#[derive(Accounts)]
+#[instruction(program_as_signer_bump: u8, price: u64, token_size: u64)]
+pub struct BeginLayaway<'info> {
+    /// Buyer paying for the listing in installments, pays for the layaway accounts.
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    /// Seller agreeing to the installment schedule, receives the seller trade state lamports back if it paid for them.
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    /// CHECK: Checked in has_one constraint.
+    /// Auction House instance treasury mint account.
+    pub treasury_mint: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=treasury_mint
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance fee account, refunded the seller trade state rent if it paid for the listing.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump=auction_house.fee_payer_bump
+    )]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in begin_layaway.
+    /// Seller SPL token account listed by `sell`.
+    #[account(mut)]
+    pub token_account: UncheckedAccount<'info>,
+
+    /// Mint of the listed token.
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Seller trade state PDA of the listing, closed as the listing is locked.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            seller.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_mint.key().as_ref(),
+            &price.to_le_bytes(),
+            &token_size.to_le_bytes()
+        ],
+        bump
+    )]
+    pub seller_trade_state: UncheckedAccount<'info>,
+
+    /// Layaway PDA recording the installment schedule.
+    #[account(
+        init,
+        payer = buyer,
+        space = LAYAWAY_SIZE,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            LAYAWAY.as_bytes(),
+            seller_trade_state.key().as_ref()
+        ],
+        bump
+    )]
+    pub layaway: Box<Account<'info, Layaway>>,
+
+    /// Token account PDA holding the listed tokens until the layaway is completed or canceled.
+    #[account(
+        init,
+        payer = buyer,
+        seeds = [
+            PREFIX.as_bytes(),
+            layaway.key().as_ref(),
+            LAYAWAY.as_bytes()
+        ],
+        bump,
+        token::mint = token_mint,
+        token::authority = program_as_signer
+    )]
+    pub layaway_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(seeds=[PREFIX.as_bytes(), SIGNER.as_bytes()], bump=program_as_signer_bump)]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Take a listing off the market to be paid in `installments`, each due `installment_interval` seconds after the
+/// previous one. The listed tokens move to a token account held by the program and the seller trade state is closed.
+#[allow(clippy::too_many_arguments)]
+pub fn begin_layaway<'info>(
+    ctx: Context<'_, '_, '_, 'info, BeginLayaway<'info>>,
+    program_as_signer_bump: u8,
+    price: u64,
+    token_size: u64,
+    installments: u8,
+    installment_interval: i64,
+    penalty_basis_points: u16,
+) -> Result<()> {
+    let buyer = &ctx.accounts.buyer;
+    let seller = &ctx.accounts.seller;
+    let auction_house = &ctx.accounts.auction_house;
+    let token_account = &ctx.accounts.token_account;
+    let token_mint = &ctx.accounts.token_mint;
+    let seller_trade_state = &ctx.accounts.seller_trade_state;
+    let layaway_token_account = &ctx.accounts.layaway_token_account;
+    let program_as_signer = &ctx.accounts.program_as_signer;
+    let token_program = &ctx.accounts.token_program;
+
+    assert_not_paused(auction_house, PAUSE_BUY)?;
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    if price == 0
+        || installments == 0
+        || installment_interval <= 0
+        || penalty_basis_points > 10000
+    {
+        return Err(AuctionHouseError::InvalidLayawaySchedule.into());
+    }
+
+    if seller_trade_state.data_is_empty() {
+        return Err(AuctionHouseError::TradeStateDoesntExist.into());
+    }
+
+    // Custodial listings need the user approval only execute_sale checks.
+    if read_custodial_identity(&seller_trade_state.try_borrow_data()?).is_some() {
+        return Err(AuctionHouseError::InvalidCustodialApproval.into());
+    }
+
+    let token_account_data = assert_is_ata(token_account, &seller.key(), &token_mint.key())?;
+    if get_delegate_from_token_account(token_account)? != Some(program_as_signer.key()) {
+        msg!("No delegate detected on token account.");
+        return Err(AuctionHouseError::BothPartiesNeedToAgreeToSale.into());
+    }
+    if token_account_data.amount < token_size {
+        return Err(AuctionHouseError::NotEnoughTokensAvailableForPurchase.into());
+    }
+
+    let program_as_signer_seeds = [
+        PREFIX.as_bytes(),
+        SIGNER.as_bytes(),
+        &[program_as_signer_bump],
+    ];
+
+    invoke_signed(
+        &spl_token::instruction::transfer(
+            token_program.key,
+            &token_account.key(),
+            &layaway_token_account.key(),
+            &program_as_signer.key(),
+            &[],
+            token_size,
+        )?,
+        &[
+            token_account.to_account_info(),
+            layaway_token_account.to_account_info(),
+            program_as_signer.to_account_info(),
+            token_program.to_account_info(),
+        ],
+        &[&program_as_signer_seeds],
+    )?;
+
+    record_order_closed(
+        find_order_book_summary(ctx.remaining_accounts, auction_house, &token_mint.key())?,
+        &auction_house.key(),
+        &token_mint.key(),
+        OrderSide::Ask,
+        price,
+    )?;
+
+    // Trade state rent goes back to whoever funded it. A sponsor who paid for the listing is passed among the
+    // remaining accounts.
+    let seller_info = seller.to_account_info();
+    let auction_house_fee_account_info = ctx.accounts.auction_house_fee_account.to_account_info();
+    let seller_rent_recipient_candidates: Vec<&AccountInfo<'info>> =
+        [&seller_info, &auction_house_fee_account_info]
+            .into_iter()
+            .chain(ctx.remaining_accounts)
+            .collect();
+    let seller_rent_recipient = trade_state_rent_recipient(
+        &seller_trade_state.try_borrow_data()?,
+        &seller_rent_recipient_candidates,
+        &seller_info,
+    )?;
+    let curr_seller_lamp = seller_trade_state.lamports();
+    **seller_trade_state.lamports.borrow_mut() = 0;
+    let seller_ts_len = seller_trade_state.data_len();
+    sol_memset(*seller_trade_state.try_borrow_mut_data()?, 0, seller_ts_len);
+    **seller_rent_recipient.lamports.borrow_mut() = seller_rent_recipient
+        .lamports()
+        .checked_add(curr_seller_lamp)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+    let layaway_key = ctx.accounts.layaway.key();
+    let (_, escrow_bump) = Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            layaway_key.as_ref(),
+        ],
+        ctx.program_id,
+    );
+
+    let layaway = &mut ctx.accounts.layaway;
+    layaway.auction_house = auction_house.key();
+    layaway.seller = seller.key();
+    layaway.buyer = buyer.key();
+    layaway.seller_trade_state = seller_trade_state.key();
+    layaway.token_mint = token_mint.key();
+    layaway.token_size = token_size;
+    layaway.price = price;
+    layaway.installments = installments;
+    layaway.installment_interval = installment_interval;
+    layaway.started_at = Clock::get()?.unix_timestamp;
+    layaway.amount_paid = 0;
+    layaway.penalty_basis_points = penalty_basis_points;
+    layaway.bump = *ctx
+        .bumps
+        .get("layaway")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+    layaway.escrow_bump = escrow_bump;
+
+    Ok(())
+}
@@ -0,0 +1,270 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program::invoke_signed, system_instruction},
+};
+use anchor_spl::{associated_token::AssociatedToken, token::Token};
+
+use crate::{
+    constants::*, errors::AuctionHouseError, layaway::*, token_interface::*, utils::*,
+    AuctionHouse, Layaway,
+};
+
+/// Accounts for the [`cancel_layaway` handler](auction_house/fn.cancel_layaway.html).
+#[derive(Accounts)]
+#[instruction(program_as_signer_bump: u8)]
+pub struct CancelLayaway<'info> {
+    /// Seller of the layaway, keeps the penalty.
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    /// CHECK: Checked in has_one constraint of the layaway.
+    /// Buyer user wallet account, receives the layaway rent back.
+    #[account(mut)]
+    pub buyer: UncheckedAccount<'info>,
+
+    /// CHECK: Checked in has_one constraint of the layaway.
+    /// Mint of the tokens held for the layaway.
+    pub token_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in cancel_layaway.
+    /// Seller SPL token account to return the tokens to.
+    #[account(mut)]
+    pub seller_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Checked in has_one constraint.
+    /// Auction House treasury mint account.
+    pub treasury_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Layaway escrow account holding the installments.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            layaway.key().as_ref()
+        ],
+        bump=layaway.escrow_bump
+    )]
+    pub layaway_escrow: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in cancel_layaway.
+    /// Seller SOL or SPL account to receive the penalty at.
+    #[account(mut)]
+    pub seller_payment_receipt_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in cancel_layaway.
+    /// Buyer SOL or SPL account to receive the refund at.
+    #[account(mut)]
+    pub buyer_refund_account: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=treasury_mint
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// Layaway PDA, closed back to the buyer.
+    #[account(
+        mut,
+        close = buyer,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            LAYAWAY.as_bytes(),
+            layaway.seller_trade_state.as_ref()
+        ],
+        bump=layaway.bump,
+        has_one=buyer,
+        has_one=seller,
+        has_one=auction_house,
+        has_one=token_mint
+    )]
+    pub layaway: Box<Account<'info, Layaway>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Token account PDA holding the tokens of the layaway.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            layaway.key().as_ref(),
+            LAYAWAY.as_bytes()
+        ],
+        bump
+    )]
+    pub layaway_token_account: UncheckedAccount<'info>,
+
+    /// Token program of the tokens held and, unless Token-2022 owning it leads the remaining accounts, of the treasury mint.
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub ata_program: Program<'info, AssociatedToken>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(seeds=[PREFIX.as_bytes(), SIGNER.as_bytes()], bump=program_as_signer_bump)]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Cancel a layaway that missed an installment deadline. The seller keeps the penalty out of the amount paid and gets
+/// the tokens back, the rest of the escrow is refunded to the buyer.
+pub fn cancel_layaway<'info>(
+    ctx: Context<'_, '_, '_, 'info, CancelLayaway<'info>>,
+    program_as_signer_bump: u8,
+) -> Result<()> {
+    let seller = &ctx.accounts.seller;
+    let buyer = &ctx.accounts.buyer;
+    let token_mint = &ctx.accounts.token_mint;
+    let seller_token_account = &ctx.accounts.seller_token_account;
+    let treasury_mint = &ctx.accounts.treasury_mint;
+    let layaway_escrow = &ctx.accounts.layaway_escrow;
+    let seller_payment_receipt_account = &ctx.accounts.seller_payment_receipt_account;
+    let buyer_refund_account = &ctx.accounts.buyer_refund_account;
+    let auction_house = &ctx.accounts.auction_house;
+    let layaway = &ctx.accounts.layaway;
+    let layaway_token_account = &ctx.accounts.layaway_token_account;
+    let token_program = &ctx.accounts.token_program;
+    let system_program = &ctx.accounts.system_program;
+    let ata_program = &ctx.accounts.ata_program;
+    let program_as_signer = &ctx.accounts.program_as_signer;
+    let rent = &ctx.accounts.rent;
+
+    if !layaway
+        .is_defaulted(Clock::get()?.unix_timestamp)
+        .ok_or(AuctionHouseError::NumericalOverflow)?
+    {
+        return Err(AuctionHouseError::LayawayNotDefaulted.into());
+    }
+
+    let penalty = layaway
+        .penalty()
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+    let refund = layaway
+        .amount_paid
+        .checked_sub(penalty)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+    let is_native = treasury_mint.key() == spl_token::native_mint::id();
+
+    let auction_house_key = auction_house.key();
+    let layaway_key = layaway.key();
+    let escrow_signer_seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        layaway_key.as_ref(),
+        &[layaway.escrow_bump],
+    ];
+
+    let ah_seeds = [
+        PREFIX.as_bytes(),
+        auction_house.creator.as_ref(),
+        auction_house.treasury_mint.as_ref(),
+        &[auction_house.bump],
+    ];
+
+    let program_as_signer_seeds = [
+        PREFIX.as_bytes(),
+        SIGNER.as_bytes(),
+        &[program_as_signer_bump],
+    ];
+
+    let token_clone = token_program.to_account_info();
+    let remaining_accounts = &mut ctx.remaining_accounts.iter();
+    let treasury_token_program =
+        next_treasury_token_program(remaining_accounts, treasury_mint, &token_clone, is_native)?;
+
+    if !is_native {
+        if penalty > 0 {
+            if seller_payment_receipt_account.data_is_empty() {
+                make_ata(
+                    seller_payment_receipt_account.to_account_info(),
+                    seller.to_account_info(),
+                    treasury_mint.to_account_info(),
+                    seller.to_account_info(),
+                    ata_program.to_account_info(),
+                    treasury_token_program.clone(),
+                    system_program.to_account_info(),
+                    rent.to_account_info(),
+                    &[],
+                )?;
+            }
+            assert_is_ata(
+                seller_payment_receipt_account,
+                &seller.key(),
+                &treasury_mint.key(),
+            )?;
+            transfer(
+                &treasury_token_program,
+                layaway_escrow,
+                treasury_mint,
+                seller_payment_receipt_account,
+                &auction_house.to_account_info(),
+                penalty,
+                &[&ah_seeds],
+            )?;
+        }
+
+        if refund > 0 {
+            assert_is_ata(buyer_refund_account, &buyer.key(), &treasury_mint.key())?;
+            transfer(
+                &treasury_token_program,
+                layaway_escrow,
+                treasury_mint,
+                buyer_refund_account,
+                &auction_house.to_account_info(),
+                refund,
+                &[&ah_seeds],
+            )?;
+        }
+    } else {
+        assert_keys_equal(seller_payment_receipt_account.key(), seller.key())?;
+        assert_keys_equal(buyer_refund_account.key(), buyer.key())?;
+        if penalty > 0 {
+            invoke_signed(
+                &system_instruction::transfer(
+                    layaway_escrow.key,
+                    seller_payment_receipt_account.key,
+                    penalty,
+                ),
+                &[
+                    layaway_escrow.to_account_info(),
+                    seller_payment_receipt_account.to_account_info(),
+                    system_program.to_account_info(),
+                ],
+                &[&escrow_signer_seeds],
+            )?;
+        }
+    }
+
+    // The refund of native treasuries is what the escrow holds after the penalty.
+    close_layaway_escrow(
+        auction_house,
+        &layaway_escrow.to_account_info(),
+        &buyer.to_account_info(),
+        &treasury_token_program,
+        &system_program.to_account_info(),
+        &escrow_signer_seeds,
+        &ah_seeds,
+        is_native,
+    )?;
+
+    assert_is_ata(seller_token_account, &seller.key(), &token_mint.key())?;
+    release_layaway_tokens(
+        &layaway_token_account.to_account_info(),
+        &seller_token_account.to_account_info(),
+        &buyer.to_account_info(),
+        &program_as_signer.to_account_info(),
+        &token_clone,
+        &program_as_signer_seeds,
+        layaway.token_size,
+    )?;
+
+    Ok(())
+}
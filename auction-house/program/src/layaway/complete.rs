@@ -0,0 +1,454 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program::invoke_signed, system_instruction},
+};
+use anchor_spl::{associated_token::AssociatedToken, token::Token};
+
+use crate::{
+    compliance::enforce_compliance, constants::*, errors::AuctionHouseError, layaway::*,
+    revenue_share::accrue_revenue_share, token_interface::*, utils::*, AhEventKind, AuctionHouse,
+    Layaway,
+};
+
+/// Accounts for the [`complete_layaway` handler](auction_house/fn.complete_layaway.html).
+#[derive(Accounts)]
+#[instruction(program_as_signer_bump: u8)]
+pub struct CompleteLayaway<'info> {
+    /// Buyer of the layaway, receives the layaway rent back.
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    /// CHECK: Checked in has_one constraint of the layaway.
+    /// Seller user wallet account.
+    #[account(mut)]
+    pub seller: UncheckedAccount<'info>,
+
+    /// CHECK: Checked in has_one constraint of the layaway.
+    /// Mint of the tokens sold.
+    pub token_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in complete_layaway.
+    /// Metaplex metadata account of the tokens sold.
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Checked in has_one constraint.
+    /// Auction House treasury mint account.
+    pub treasury_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Layaway escrow account holding the installments.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            layaway.key().as_ref()
+        ],
+        bump=layaway.escrow_bump
+    )]
+    pub layaway_escrow: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in complete_layaway.
+    /// Seller SOL or SPL account to receive payment at.
+    #[account(mut)]
+    pub seller_payment_receipt_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in complete_layaway.
+    /// Buyer SPL token account to receive the tokens at.
+    #[account(mut)]
+    pub buyer_receipt_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in complete_layaway.
+    /// Auction House instance authority.
+    pub authority: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority,
+        has_one=treasury_mint,
+        has_one=auction_house_treasury,
+        has_one=auction_house_fee_account
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance fee account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump=auction_house.fee_payer_bump
+    )]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance treasury account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            TREASURY.as_bytes()
+        ],
+        bump=auction_house.treasury_bump
+    )]
+    pub auction_house_treasury: UncheckedAccount<'info>,
+
+    /// Layaway PDA, closed back to the buyer once completed.
+    #[account(
+        mut,
+        close = buyer,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            LAYAWAY.as_bytes(),
+            layaway.seller_trade_state.as_ref()
+        ],
+        bump=layaway.bump,
+        has_one=buyer,
+        has_one=seller,
+        has_one=auction_house,
+        has_one=token_mint
+    )]
+    pub layaway: Box<Account<'info, Layaway>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Token account PDA holding the tokens sold.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            layaway.key().as_ref(),
+            LAYAWAY.as_bytes()
+        ],
+        bump
+    )]
+    pub layaway_token_account: UncheckedAccount<'info>,
+
+    /// Token program of the tokens sold and, unless Token-2022 owning it leads the remaining accounts, of the treasury mint.
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub ata_program: Program<'info, AssociatedToken>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(seeds=[PREFIX.as_bytes(), SIGNER.as_bytes()], bump=program_as_signer_bump)]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Execute the sale of a fully paid layaway from its escrow. The remaining accounts are the creators as in
+/// `execute_sale`, followed by the linked wallet, revenue share and compliance accounts as in `execute_bundle_sale`.
+pub fn complete_layaway<'info>(
+    ctx: Context<'_, '_, '_, 'info, CompleteLayaway<'info>>,
+    program_as_signer_bump: u8,
+) -> Result<()> {
+    let buyer = &ctx.accounts.buyer;
+    let seller = &ctx.accounts.seller;
+    let token_mint = &ctx.accounts.token_mint;
+    let metadata = &ctx.accounts.metadata;
+    let treasury_mint = &ctx.accounts.treasury_mint;
+    let layaway_escrow = &ctx.accounts.layaway_escrow;
+    let seller_payment_receipt_account = &ctx.accounts.seller_payment_receipt_account;
+    let buyer_receipt_token_account = &ctx.accounts.buyer_receipt_token_account;
+    let authority = &ctx.accounts.authority;
+    let auction_house = &ctx.accounts.auction_house;
+    let auction_house_fee_account = &ctx.accounts.auction_house_fee_account;
+    let auction_house_treasury = &ctx.accounts.auction_house_treasury;
+    let layaway = &ctx.accounts.layaway;
+    let layaway_token_account = &ctx.accounts.layaway_token_account;
+    let token_program = &ctx.accounts.token_program;
+    let system_program = &ctx.accounts.system_program;
+    let ata_program = &ctx.accounts.ata_program;
+    let program_as_signer = &ctx.accounts.program_as_signer;
+    let rent = &ctx.accounts.rent;
+
+    let escrow_clone = layaway_escrow.to_account_info();
+    let auction_house_clone = auction_house.to_account_info();
+    let ata_clone = ata_program.to_account_info();
+    let token_clone = token_program.to_account_info();
+    let sys_clone = system_program.to_account_info();
+    let rent_clone = rent.to_account_info();
+    let treasury_clone = auction_house_treasury.to_account_info();
+
+    if layaway.amount_paid < layaway.price {
+        return Err(AuctionHouseError::LayawayNotPaid.into());
+    }
+
+    assert_derivation(
+        &mpl_token_metadata::id(),
+        metadata,
+        &[
+            mpl_token_metadata::state::PREFIX.as_bytes(),
+            mpl_token_metadata::id().as_ref(),
+            token_mint.key().as_ref(),
+        ],
+    )?;
+    if metadata.data_is_empty() {
+        return Err(AuctionHouseError::MetadataDoesntExist.into());
+    }
+
+    let is_native = treasury_mint.key() == spl_token::native_mint::id();
+
+    let auction_house_key = auction_house.key();
+    let seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        FEE_PAYER.as_bytes(),
+        &[auction_house.fee_payer_bump],
+    ];
+
+    let (fee_payer, fee_payer_seeds) = get_fee_payer(
+        authority,
+        auction_house,
+        buyer.to_account_info(),
+        auction_house_fee_account.to_account_info(),
+        &seeds,
+    )?;
+    let fee_payer_clone = fee_payer.to_account_info();
+
+    let layaway_key = layaway.key();
+    let escrow_signer_seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        layaway_key.as_ref(),
+        &[layaway.escrow_bump],
+    ];
+
+    let ah_seeds = [
+        PREFIX.as_bytes(),
+        auction_house.creator.as_ref(),
+        auction_house.treasury_mint.as_ref(),
+        &[auction_house.bump],
+    ];
+
+    // with the native account, the escrow is its own owner,
+    // whereas with token, it is the auction house that is owner.
+    let signer_seeds_for_royalties = if is_native {
+        escrow_signer_seeds
+    } else {
+        ah_seeds
+    };
+
+    let program_as_signer_seeds = [
+        PREFIX.as_bytes(),
+        SIGNER.as_bytes(),
+        &[program_as_signer_bump],
+    ];
+
+    let remaining_accounts = &mut ctx.remaining_accounts.iter();
+    let treasury_token_program =
+        next_treasury_token_program(remaining_accounts, treasury_mint, &token_clone, is_native)?;
+
+    if auction_house.enforce_royalties {
+        assert_royalties_enforced(
+            remaining_accounts.as_slice(),
+            metadata,
+            layaway.price,
+            is_native,
+        )?;
+    }
+
+    let leftover_after_royalties = pay_creator_fees(
+        remaining_accounts,
+        metadata,
+        &escrow_clone,
+        &auction_house_clone,
+        &fee_payer_clone,
+        treasury_mint,
+        &ata_clone,
+        &treasury_token_program,
+        &sys_clone,
+        &rent_clone,
+        &signer_seeds_for_royalties,
+        fee_payer_seeds,
+        layaway.price,
+        is_native,
+    )?;
+
+    let auction_house_fee_paid = pay_auction_house_fees(
+        auction_house,
+        &treasury_clone,
+        &escrow_clone,
+        treasury_mint,
+        &treasury_token_program,
+        &sys_clone,
+        &signer_seeds_for_royalties,
+        layaway.price,
+        is_native,
+    )?;
+
+    let seller_proceeds = leftover_after_royalties
+        .checked_sub(auction_house_fee_paid)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+    if buyer_receipt_token_account.data_is_empty() {
+        make_ata(
+            buyer_receipt_token_account.to_account_info(),
+            buyer.to_account_info(),
+            token_mint.to_account_info(),
+            fee_payer.to_account_info(),
+            ata_program.to_account_info(),
+            token_program.to_account_info(),
+            system_program.to_account_info(),
+            rent.to_account_info(),
+            fee_payer_seeds,
+        )?;
+    }
+
+    let buyer_rec_acct =
+        assert_is_ata(buyer_receipt_token_account, &buyer.key(), &token_mint.key())?;
+
+    // make sure you cant get rugged
+    if buyer_rec_acct.delegate.is_some() {
+        return Err(AuctionHouseError::BuyerATACannotHaveDelegate.into());
+    }
+
+    release_layaway_tokens(
+        &layaway_token_account.to_account_info(),
+        &buyer_receipt_token_account.to_account_info(),
+        &buyer.to_account_info(),
+        &program_as_signer.to_account_info(),
+        &token_clone,
+        &program_as_signer_seeds,
+        layaway.token_size,
+    )?;
+
+    if !is_native {
+        if seller_payment_receipt_account.data_is_empty() {
+            make_ata(
+                seller_payment_receipt_account.to_account_info(),
+                seller.to_account_info(),
+                treasury_mint.to_account_info(),
+                fee_payer.to_account_info(),
+                ata_program.to_account_info(),
+                treasury_token_program.clone(),
+                system_program.to_account_info(),
+                rent.to_account_info(),
+                fee_payer_seeds,
+            )?;
+        }
+
+        let seller_rec_acct = assert_is_ata(
+            &seller_payment_receipt_account.to_account_info(),
+            &seller.key(),
+            &treasury_mint.key(),
+        )?;
+
+        // make sure you cant get rugged
+        if seller_rec_acct.delegate.is_some() {
+            return Err(AuctionHouseError::SellerATACannotHaveDelegate.into());
+        }
+
+        transfer(
+            &treasury_token_program,
+            layaway_escrow,
+            treasury_mint,
+            seller_payment_receipt_account,
+            &auction_house.to_account_info(),
+            seller_proceeds,
+            &[&ah_seeds],
+        )?;
+    } else {
+        assert_keys_equal(seller_payment_receipt_account.key(), seller.key())?;
+        invoke_signed(
+            &system_instruction::transfer(
+                layaway_escrow.key,
+                seller_payment_receipt_account.key,
+                seller_proceeds,
+            ),
+            &[
+                layaway_escrow.to_account_info(),
+                seller_payment_receipt_account.to_account_info(),
+                system_program.to_account_info(),
+            ],
+            &[&escrow_signer_seeds],
+        )?;
+    }
+
+    close_layaway_escrow(
+        auction_house,
+        &escrow_clone,
+        &buyer.to_account_info(),
+        &treasury_token_program,
+        &sys_clone,
+        &escrow_signer_seeds,
+        &ah_seeds,
+        is_native,
+    )?;
+
+    // The linked wallet registry entries of the buyer and seller follow the creators when
+    // the wash trading guard is on.
+    if auction_house.wash_trading_guard {
+        let buyer_linked_wallet = next_account_info(remaining_accounts)?;
+        let seller_linked_wallet = next_account_info(remaining_accounts)?;
+        assert_not_self_trade(
+            &auction_house_key,
+            &buyer.key(),
+            &seller.key(),
+            buyer_linked_wallet,
+            seller_linked_wallet,
+        )?;
+    }
+
+    // The revenue share account and, for SPL treasuries, its token account follow.
+    if auction_house.has_revenue_share {
+        let revenue_share_info = next_account_info(remaining_accounts)?;
+        let revenue_share_vault = if is_native {
+            revenue_share_info
+        } else {
+            next_account_info(remaining_accounts)?
+        };
+        accrue_revenue_share(
+            auction_house,
+            &auction_house_treasury.to_account_info(),
+            revenue_share_info,
+            revenue_share_vault,
+            &treasury_token_program,
+            &system_program.to_account_info(),
+            auction_house_fee_paid,
+            is_native,
+        )?;
+    }
+
+    // The compliance config and the buyer and seller wallet volume PDAs come last.
+    if auction_house.has_compliance {
+        let compliance_config_info = next_account_info(remaining_accounts)?;
+        let buyer_wallet_volume = next_account_info(remaining_accounts)?;
+        let seller_wallet_volume = next_account_info(remaining_accounts)?;
+        enforce_compliance(
+            &auction_house_key,
+            compliance_config_info,
+            [
+                (&buyer.key(), buyer_wallet_volume),
+                (&seller.key(), seller_wallet_volume),
+            ],
+            &auction_house_fee_account.to_account_info(),
+            &system_program.to_account_info(),
+            &rent.to_account_info(),
+            &seeds,
+            layaway.price,
+        )?;
+    }
+
+    emit_ah_event(
+        AhEventKind::ExecuteSale,
+        auction_house_key,
+        Some(token_mint.key()),
+        layaway.price,
+        vec![buyer.key(), seller.key()],
+    )?;
+
+    emit_if_fee_account_low(auction_house, &auction_house_fee_account.to_account_info());
+
+    Ok(())
+}
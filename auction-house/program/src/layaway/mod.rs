@@ -0,0 +1,115 @@
+pub mod begin;
+pub mod cancel;
+pub mod complete;
+pub mod pay;
+pub use begin::*;
+pub use cancel::*;
+pub use complete::*;
+pub use pay::*;
+
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program::invoke_signed, system_instruction},
+};
+
+use crate::{token_interface::*, AuctionHouse};
+
+/// Empty the layaway escrow into `destination`, closing the token account of SPL treasuries.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn close_layaway_escrow<'info>(
+    auction_house: &Account<'info, AuctionHouse>,
+    layaway_escrow: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    escrow_signer_seeds: &[&[u8]],
+    ah_seeds: &[&[u8]],
+    is_native: bool,
+) -> Result<()> {
+    if is_native {
+        invoke_signed(
+            &system_instruction::transfer(
+                layaway_escrow.key,
+                destination.key,
+                layaway_escrow.lamports(),
+            ),
+            &[
+                layaway_escrow.clone(),
+                destination.clone(),
+                system_program.clone(),
+            ],
+            &[escrow_signer_seeds],
+        )?;
+    } else if !layaway_escrow.data_is_empty() {
+        assert_token_program(token_program)?;
+        // Token-2022 shares the SPL Token instruction layout.
+        let mut instruction = spl_token::instruction::close_account(
+            &spl_token::id(),
+            layaway_escrow.key,
+            destination.key,
+            &auction_house.key(),
+            &[],
+        )?;
+        instruction.program_id = *token_program.key;
+        invoke_signed(
+            &instruction,
+            &[
+                layaway_escrow.clone(),
+                destination.clone(),
+                auction_house.to_account_info(),
+                token_program.clone(),
+            ],
+            &[ah_seeds],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Move the tokens held for the layaway to `destination` and close the custody account back to the buyer.
+pub(crate) fn release_layaway_tokens<'info>(
+    layaway_token_account: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    buyer: &AccountInfo<'info>,
+    program_as_signer: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    program_as_signer_seeds: &[&[u8]],
+    token_size: u64,
+) -> Result<()> {
+    invoke_signed(
+        &spl_token::instruction::transfer(
+            token_program.key,
+            layaway_token_account.key,
+            destination.key,
+            program_as_signer.key,
+            &[],
+            token_size,
+        )?,
+        &[
+            layaway_token_account.clone(),
+            destination.clone(),
+            program_as_signer.clone(),
+            token_program.clone(),
+        ],
+        &[program_as_signer_seeds],
+    )?;
+
+    invoke_signed(
+        &spl_token::instruction::close_account(
+            token_program.key,
+            layaway_token_account.key,
+            buyer.key,
+            program_as_signer.key,
+            &[],
+        )?,
+        &[
+            layaway_token_account.clone(),
+            buyer.clone(),
+            program_as_signer.clone(),
+            token_program.clone(),
+        ],
+        &[program_as_signer_seeds],
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,173 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program::invoke, system_instruction},
+};
+
+use crate::{
+    constants::*, errors::AuctionHouseError, token_interface::*, utils::*, AuctionHouse, Layaway,
+};
+
+/// Accounts for the [`pay_installment` handler](auction_house/fn.pay_installment.html).
+#[derive(Accounts)]
+pub struct PayInstallment<'info> {
+    /// Buyer of the layaway.
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    /// CHECK: Validated in pay_installment.
+    /// Buyer SOL or SPL account to pay the installment from.
+    #[account(mut)]
+    pub payment_account: UncheckedAccount<'info>,
+
+    /// CHECK: Checked in has_one constraint.
+    /// Auction House instance treasury mint account.
+    pub treasury_mint: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=treasury_mint
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// Layaway PDA of the buyer.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            LAYAWAY.as_bytes(),
+            layaway.seller_trade_state.as_ref()
+        ],
+        bump=layaway.bump,
+        has_one=buyer,
+        has_one=auction_house
+    )]
+    pub layaway: Box<Account<'info, Layaway>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Layaway escrow account accruing the installments.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            layaway.key().as_ref()
+        ],
+        bump=layaway.escrow_bump
+    )]
+    pub layaway_escrow: UncheckedAccount<'info>,
+
+    /// CHECK: Checked in constraint.
+    /// Token program of the treasury mint.
+    #[account(constraint = is_treasury_token_program(token_program.key, &treasury_mint.to_account_info()) @ AuctionHouseError::InvalidTokenProgram)]
+    pub token_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Pay `amount` towards the layaway price into its escrow. Installments are refused once a deadline was missed.
+pub fn pay_installment<'info>(
+    ctx: Context<'_, '_, '_, 'info, PayInstallment<'info>>,
+    amount: u64,
+) -> Result<()> {
+    let buyer = &ctx.accounts.buyer;
+    let payment_account = &ctx.accounts.payment_account;
+    let treasury_mint = &ctx.accounts.treasury_mint;
+    let auction_house = &ctx.accounts.auction_house;
+    let layaway_escrow = &ctx.accounts.layaway_escrow;
+    let token_program = &ctx.accounts.token_program;
+    let system_program = &ctx.accounts.system_program;
+    let rent = &ctx.accounts.rent;
+
+    let layaway = &ctx.accounts.layaway;
+    if layaway
+        .is_defaulted(Clock::get()?.unix_timestamp)
+        .ok_or(AuctionHouseError::NumericalOverflow)?
+    {
+        return Err(AuctionHouseError::LayawayDefaulted.into());
+    }
+
+    let is_native = treasury_mint.key() == spl_token::native_mint::id();
+
+    // The escrow is credited net of any transfer fee of the treasury mint.
+    let credited_amount = if is_native {
+        amount
+    } else {
+        amount_after_transfer_fee(treasury_mint, amount)?
+    };
+    let balance = layaway
+        .price
+        .checked_sub(layaway.amount_paid)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+    if credited_amount == 0 || credited_amount > balance {
+        return Err(AuctionHouseError::InvalidInstallment.into());
+    }
+
+    let auction_house_key = auction_house.key();
+    let layaway_key = layaway.key();
+    let escrow_signer_seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        layaway_key.as_ref(),
+        &[layaway.escrow_bump],
+    ];
+
+    create_program_token_account_if_not_present(
+        layaway_escrow,
+        system_program,
+        &buyer.to_account_info(),
+        token_program,
+        treasury_mint,
+        &auction_house.to_account_info(),
+        rent,
+        &escrow_signer_seeds,
+        &[],
+        is_native,
+    )?;
+
+    if !is_native {
+        assert_is_ata(payment_account, &buyer.key(), &treasury_mint.key())?;
+        transfer(
+            token_program,
+            payment_account,
+            treasury_mint,
+            layaway_escrow,
+            &buyer.to_account_info(),
+            amount,
+            &[],
+        )?;
+    } else {
+        assert_keys_equal(payment_account.key(), buyer.key())?;
+
+        // Reach rental exemption and then add the installment.
+        let checked_amount = rent_checked_add(layaway_escrow.to_account_info(), 0)?
+            .checked_add(amount)
+            .ok_or(AuctionHouseError::NumericalOverflow)?;
+        invoke(
+            &system_instruction::transfer(
+                &payment_account.key(),
+                &layaway_escrow.key(),
+                checked_amount,
+            ),
+            &[
+                layaway_escrow.to_account_info(),
+                payment_account.to_account_info(),
+                system_program.to_account_info(),
+            ],
+        )?;
+    }
+
+    let layaway = &mut ctx.accounts.layaway;
+    layaway.amount_paid = layaway
+        .amount_paid
+        .checked_add(credited_amount)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+    Ok(())
+}
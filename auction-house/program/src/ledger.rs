@@ -0,0 +1,231 @@
+//! Double-entry ledger of the balances moved by `execute_sale`, compiled in with the `ledger-checks` feature.
+//!
+//! Every transfer of a sale is posted up front as a debit of its source and a credit of its destination, computed
+//! from the sale terms rather than from the transfer helpers. Once the transfers are done, the balance of every party
+//! must have changed by exactly its postings, so a fee feature that mis-splits a sale fails the transaction instead of
+//! silently moving funds to the wrong party.
+use crate::{
+    errors::AuctionHouseError, metadata_reader::MetadataRoyalties, token_interface::*,
+    utils::next_royalty_payee, AuctionHouse,
+};
+
+use anchor_lang::prelude::*;
+use std::slice::Iter;
+
+/// Balance of a party of the sale before any transfer, with the change its postings add up to.
+struct LedgerEntry<'a> {
+    account: AccountInfo<'a>,
+    is_token: bool,
+    before: u64,
+    delta: i128,
+}
+
+impl<'a> LedgerEntry<'a> {
+    fn new(account: &AccountInfo<'a>, is_token: bool) -> Result<Self> {
+        Ok(LedgerEntry {
+            account: account.clone(),
+            is_token,
+            before: balance(account, is_token)?,
+            delta: 0,
+        })
+    }
+}
+
+/// Token amount of a token account, or lamports of a native account. Token accounts created during the sale start
+/// out empty.
+fn balance(account: &AccountInfo, is_token: bool) -> Result<u64> {
+    if !is_token {
+        return Ok(account.lamports());
+    }
+    if account.data_is_empty() {
+        return Ok(0);
+    }
+    Ok(unpack_token_account(account)?.amount)
+}
+
+#[derive(Default)]
+pub struct SaleLedger<'a> {
+    entries: Vec<LedgerEntry<'a>>,
+}
+
+impl<'a> SaleLedger<'a> {
+    fn entry(&mut self, account: &AccountInfo<'a>, is_token: bool) -> Result<&mut LedgerEntry<'a>> {
+        let index = match self
+            .entries
+            .iter()
+            .position(|entry| entry.account.key == account.key)
+        {
+            Some(index) => index,
+            None => {
+                self.entries.push(LedgerEntry::new(account, is_token)?);
+                self.entries.len() - 1
+            }
+        };
+        Ok(&mut self.entries[index])
+    }
+
+    /// Post a transfer of `amount` from `source` to `destination`, which is credited `received`. The two differ by
+    /// the transfer fee a Token-2022 mint withholds.
+    pub fn post(
+        &mut self,
+        source: &AccountInfo<'a>,
+        destination: &AccountInfo<'a>,
+        amount: u64,
+        received: u64,
+        is_token: bool,
+    ) -> Result<()> {
+        self.entry(source, is_token)?.delta -= amount as i128;
+        self.entry(destination, is_token)?.delta += received as i128;
+        Ok(())
+    }
+
+    /// Move the credits posted to `from` over to `to`, e.g. a deferred settlement holding the proceeds back.
+    pub fn reroute(
+        &mut self,
+        from: &AccountInfo<'a>,
+        to: &AccountInfo<'a>,
+        is_token: bool,
+    ) -> Result<()> {
+        let delta = std::mem::take(&mut self.entry(from, is_token)?.delta);
+        self.entry(to, is_token)?.delta += delta;
+        Ok(())
+    }
+
+    /// Assert the postings debit `account` by exactly `amount`, i.e. the credits of a sale add up to what it costs.
+    pub fn assert_debited(&self, account: &AccountInfo<'a>, amount: u64) -> Result<()> {
+        let delta = self
+            .entries
+            .iter()
+            .find(|entry| entry.account.key == account.key)
+            .map_or(0, |entry| entry.delta);
+        if delta != -(amount as i128) {
+            msg!(
+                "Ledger of {} posts {} instead of -{}",
+                account.key,
+                delta,
+                amount
+            );
+            return Err(AuctionHouseError::LedgerMismatch.into());
+        }
+        Ok(())
+    }
+
+    /// Assert the balance of every party changed by its postings. The lamports of `fee_payer` also pay for accounts
+    /// created during the sale, so they are not checked.
+    pub fn verify(&self, fee_payer: &Pubkey) -> Result<()> {
+        for entry in &self.entries {
+            if !entry.is_token && entry.account.key == fee_payer {
+                continue;
+            }
+            let after = balance(&entry.account, entry.is_token)?;
+            let delta = after as i128 - entry.before as i128;
+            if delta != entry.delta {
+                msg!(
+                    "Ledger of {} expected {} but balance changed by {}",
+                    entry.account.key,
+                    entry.delta,
+                    delta
+                );
+                return Err(AuctionHouseError::LedgerMismatch.into());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Post every transfer of a sale of `size` tokens for `price`. `remaining_accounts` must be positioned at the creator
+/// accounts `pay_creator_fees` consumes.
+#[allow(clippy::too_many_arguments)]
+pub fn post_sale<'a>(
+    remaining_accounts: &Iter<AccountInfo<'a>>,
+    auction_house: &AuctionHouse,
+    metadata: &AccountInfo<'a>,
+    treasury_mint: &AccountInfo<'a>,
+    escrow_payment_account: &AccountInfo<'a>,
+    auction_house_treasury: &AccountInfo<'a>,
+    seller_payment_receipt_account: &AccountInfo<'a>,
+    token_account: &AccountInfo<'a>,
+    buyer_receipt_token_account: &AccountInfo<'a>,
+    price: u64,
+    size: u64,
+    is_native: bool,
+) -> Result<SaleLedger<'a>> {
+    let mut ledger = SaleLedger::default();
+    let is_token = !is_native;
+    let received = |amount: u64| -> Result<u64> {
+        if is_native {
+            Ok(amount)
+        } else {
+            amount_after_transfer_fee(treasury_mint, amount)
+        }
+    };
+
+    let data = metadata.try_borrow_data()?;
+    let royalties = MetadataRoyalties::from_bytes(&data)?;
+    let total_royalties = (royalties.seller_fee_basis_points as u128)
+        .checked_mul(price as u128)
+        .ok_or(AuctionHouseError::NumericalOverflow)?
+        .checked_div(10000)
+        .ok_or(AuctionHouseError::NumericalOverflow)? as u64;
+
+    let mut royalties_paid: u64 = 0;
+    let remaining_accounts = &mut remaining_accounts.clone();
+    for creator in royalties.creators() {
+        let creator_info = next_account_info(remaining_accounts)?;
+        let payee_info = next_royalty_payee(remaining_accounts, creator_info)?;
+        let payee_info = if is_native {
+            payee_info
+        } else {
+            next_account_info(remaining_accounts)?
+        };
+        let creator_fee = (creator.share as u128)
+            .checked_mul(total_royalties as u128)
+            .ok_or(AuctionHouseError::NumericalOverflow)?
+            .checked_div(100)
+            .ok_or(AuctionHouseError::NumericalOverflow)? as u64;
+        if creator_fee > 0 {
+            ledger.post(
+                escrow_payment_account,
+                payee_info,
+                creator_fee,
+                received(creator_fee)?,
+                is_token,
+            )?;
+        }
+        royalties_paid = royalties_paid
+            .checked_add(creator_fee)
+            .ok_or(AuctionHouseError::NumericalOverflow)?;
+    }
+
+    let auction_house_fee = (auction_house.seller_fee_basis_points as u128)
+        .checked_mul(price as u128)
+        .ok_or(AuctionHouseError::NumericalOverflow)?
+        .checked_div(10000)
+        .ok_or(AuctionHouseError::NumericalOverflow)? as u64;
+    ledger.post(
+        escrow_payment_account,
+        auction_house_treasury,
+        auction_house_fee,
+        received(auction_house_fee)?,
+        is_token,
+    )?;
+
+    // The seller gets whatever is left, including the dust of flooring the creator shares.
+    let seller_proceeds = price
+        .checked_sub(royalties_paid)
+        .and_then(|proceeds| proceeds.checked_sub(auction_house_fee))
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+    ledger.post(
+        escrow_payment_account,
+        seller_payment_receipt_account,
+        seller_proceeds,
+        received(seller_proceeds)?,
+        is_token,
+    )?;
+
+    ledger.post(token_account, buyer_receipt_token_account, size, size, true)?;
+
+    ledger.assert_debited(escrow_payment_account, price)?;
+
+    Ok(ledger)
+}
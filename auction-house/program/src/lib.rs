@@ -3,25 +3,52 @@
 //!
 //! Full docs can be found [here](https://docs.metaplex.com/auction-house/definition).
 
+pub mod assertions;
 pub mod auctioneer;
 pub mod bid;
+pub mod bundle_listing;
 pub mod cancel;
+pub mod compliance;
 pub mod constants;
+pub mod custodial_sell;
 pub mod deposit;
+pub mod dispute;
+pub mod epoch_report;
 pub mod errors;
 pub mod execute_sale;
+pub mod layaway;
+#[cfg(feature = "ledger-checks")]
+pub mod ledger;
+pub mod linked_wallet;
+pub mod metadata_reader;
+pub mod migrate;
+pub mod order_book;
 pub mod pda;
+pub mod pending_listing;
+pub mod pending_update;
+pub mod proceeds_route;
 pub mod receipt;
+pub mod revenue_share;
+pub mod royalty_mandate;
 pub mod sell;
+pub mod session_key;
+pub mod signed_listing;
 pub mod state;
+pub mod swap;
+#[cfg(all(feature = "test-bpf", not(target_arch = "bpf")))]
+pub mod test_utils;
+pub mod token_interface;
 pub mod utils;
 pub mod withdraw;
 
 pub use state::*;
 
 use crate::{
-    auctioneer::*, bid::*, cancel::*, constants::*, deposit::*, errors::AuctionHouseError,
-    execute_sale::*, receipt::*, sell::*, utils::*, withdraw::*,
+    auctioneer::*, bid::*, bundle_listing::*, cancel::*, compliance::*, constants::*,
+    custodial_sell::*, deposit::*, dispute::*, epoch_report::*, errors::AuctionHouseError,
+    execute_sale::*, layaway::*, linked_wallet::*, migrate::*, order_book::*, pending_listing::*,
+    pending_update::*, proceeds_route::*, receipt::*, revenue_share::*, royalty_mandate::*,
+    sell::*, session_key::*, signed_listing::*, swap::*, token_interface::*, utils::*, withdraw::*,
 };
 
 use anchor_lang::{
@@ -51,6 +78,8 @@ pub mod auction_house {
         let auction_house = &ctx.accounts.auction_house;
         let system_program = &ctx.accounts.system_program;
 
+        assert_not_paused(auction_house, PAUSE_WITHDRAW)?;
+
         let auction_house_key = auction_house.key();
         let seeds = [
             PREFIX.as_bytes(),
@@ -73,6 +102,14 @@ pub mod auction_house {
             &[&seeds],
         )?;
 
+        emit_ah_event(
+            AhEventKind::WithdrawFromFee,
+            auction_house_key,
+            None,
+            amount,
+            vec![fee_withdrawal_destination.key()],
+        )?;
+
         Ok(())
     }
 
@@ -88,6 +125,8 @@ pub mod auction_house {
         let token_program = &ctx.accounts.token_program;
         let system_program = &ctx.accounts.system_program;
 
+        assert_not_paused(auction_house, PAUSE_WITHDRAW)?;
+
         let is_native = treasury_mint.key() == spl_token::native_mint::id();
         let auction_house_seeds = [
             PREFIX.as_bytes(),
@@ -104,48 +143,115 @@ pub mod auction_house {
             &[auction_house.treasury_bump],
         ];
         if !is_native {
+            transfer(
+                token_program,
+                auction_house_treasury,
+                treasury_mint,
+                treasury_withdrawal_destination,
+                &auction_house.to_account_info(),
+                amount,
+                &[&auction_house_seeds],
+            )?;
+        } else {
             invoke_signed(
-                &spl_token::instruction::transfer(
-                    token_program.key,
+                &system_instruction::transfer(
                     &auction_house_treasury.key(),
                     &treasury_withdrawal_destination.key(),
-                    &auction_house.key(),
-                    &[],
                     amount,
-                )?,
+                ),
                 &[
                     auction_house_treasury.to_account_info(),
                     treasury_withdrawal_destination.to_account_info(),
-                    token_program.to_account_info(),
-                    auction_house.to_account_info(),
+                    system_program.to_account_info(),
                 ],
-                &[&auction_house_seeds],
+                &[&auction_house_treasury_seeds],
             )?;
-        } else {
+        }
+
+        emit_ah_event(
+            AhEventKind::WithdrawFromTreasury,
+            ah_key,
+            None,
+            amount,
+            vec![treasury_withdrawal_destination.key()],
+        )?;
+
+        Ok(())
+    }
+
+    /// Permissionless crank moving lamports from the Auction House treasury to the fee account, up to the configured target balance.
+    pub fn top_up_fee_account<'info>(
+        ctx: Context<'_, '_, '_, 'info, TopUpFeeAccount<'info>>,
+    ) -> Result<()> {
+        let treasury_mint = &ctx.accounts.treasury_mint;
+        let auction_house_fee_account = &ctx.accounts.auction_house_fee_account;
+        let auction_house_treasury = &ctx.accounts.auction_house_treasury;
+        let auction_house = &ctx.accounts.auction_house;
+        let system_program = &ctx.accounts.system_program;
+        let rent = &ctx.accounts.rent;
+
+        if treasury_mint.key() != spl_token::native_mint::id() {
+            return Err(AuctionHouseError::TopUpRequiresNativeTreasury.into());
+        }
+
+        let ah_key = auction_house.key();
+        let auction_house_treasury_seeds = [
+            PREFIX.as_bytes(),
+            ah_key.as_ref(),
+            TREASURY.as_bytes(),
+            &[auction_house.treasury_bump],
+        ];
+
+        // Keep the treasury rent exempt, only the surplus can be moved.
+        let available = auction_house_treasury
+            .lamports()
+            .saturating_sub(rent.minimum_balance(auction_house_treasury.data_len()));
+        let amount = auction_house
+            .fee_account_target_balance
+            .saturating_sub(auction_house_fee_account.lamports())
+            .min(available);
+
+        if amount > 0 {
             invoke_signed(
                 &system_instruction::transfer(
                     &auction_house_treasury.key(),
-                    &treasury_withdrawal_destination.key(),
+                    &auction_house_fee_account.key(),
                     amount,
                 ),
                 &[
                     auction_house_treasury.to_account_info(),
-                    treasury_withdrawal_destination.to_account_info(),
+                    auction_house_fee_account.to_account_info(),
                     system_program.to_account_info(),
                 ],
                 &[&auction_house_treasury_seeds],
             )?;
         }
 
+        emit_if_fee_account_low(auction_house, &auction_house_fee_account.to_account_info());
+
+        emit_ah_event(AhEventKind::TopUpFeeAccount, ah_key, None, amount, vec![])?;
+
         Ok(())
     }
 
-    /// Update Auction House values such as seller fee basis points, update authority, treasury account, etc.
+    /// Update Auction House values such as seller fee basis points, update authority, treasury account, etc. Authority and treasury withdrawal destination changes are queued behind a timelock.
     pub fn update_auction_house<'info>(
         ctx: Context<'_, '_, '_, 'info, UpdateAuctionHouse<'info>>,
         seller_fee_basis_points: Option<u16>,
         requires_sign_off: Option<bool>,
         can_change_sale_price: Option<bool>,
+        auto_receipts: Option<bool>,
+        fee_account_target_balance: Option<u64>,
+        fee_account_low_balance_threshold: Option<u64>,
+        wash_trading_guard: Option<bool>,
+        enforce_royalties: Option<bool>,
+        tick_size: Option<u64>,
+        free_sales_require_sign_off: Option<bool>,
+        max_free_sales_per_epoch: Option<u16>,
+        paused_operations: Option<u8>,
+        epoch_report_retention: Option<u16>,
+        dispute_window: Option<i64>,
+        listing_cooldown_slots: Option<u64>,
     ) -> Result<()> {
         let treasury_mint = &ctx.accounts.treasury_mint;
         let payer = &ctx.accounts.payer;
@@ -175,11 +281,80 @@ pub mod auction_house {
         if let Some(chsp) = can_change_sale_price {
             auction_house.can_change_sale_price = chsp;
         }
+        if let Some(ar) = auto_receipts {
+            auction_house.auto_receipts = ar;
+        }
+        if let Some(fatb) = fee_account_target_balance {
+            auction_house.fee_account_target_balance = fatb;
+        }
+        if let Some(falbt) = fee_account_low_balance_threshold {
+            auction_house.fee_account_low_balance_threshold = falbt;
+        }
+        if let Some(wtg) = wash_trading_guard {
+            auction_house.wash_trading_guard = wtg;
+        }
+        if let Some(er) = enforce_royalties {
+            auction_house.enforce_royalties = er;
+        }
+        if let Some(ts) = tick_size {
+            auction_house.tick_size = ts;
+        }
+        if let Some(fsrs) = free_sales_require_sign_off {
+            auction_house.free_sales_require_sign_off = fsrs;
+        }
+        if let Some(mfspe) = max_free_sales_per_epoch {
+            auction_house.max_free_sales_per_epoch = mfspe;
+        }
+        if let Some(po) = paused_operations {
+            if po & !PAUSE_ALL != 0 {
+                return Err(AuctionHouseError::InvalidPausedOperations.into());
+            }
+
+            auction_house.paused_operations = po;
+        }
+        if let Some(ert) = epoch_report_retention {
+            auction_house.epoch_report_retention = ert;
+        }
+        if let Some(dw) = dispute_window {
+            if dw < 0 {
+                return Err(AuctionHouseError::InvalidDisputeWindow.into());
+            }
+
+            auction_house.dispute_window = dw;
+        }
+        if let Some(lcs) = listing_cooldown_slots {
+            auction_house.listing_cooldown_slots = lcs;
+        }
+
+        // Houses created before the bump was stored get it on their next update, sparing v2 handlers the derivation.
+        if auction_house.program_as_signer_bump == 0 {
+            auction_house.program_as_signer_bump = pda::find_program_as_signer_address().1;
+        }
 
-        auction_house.authority = new_authority.key();
-        auction_house.treasury_withdrawal_destination = treasury_withdrawal_destination.key();
         auction_house.fee_withdrawal_destination = fee_withdrawal_destination.key();
 
+        // Authority and treasury withdrawal destination changes are timelocked so sellers can react.
+        if new_authority.key() != auction_house.authority
+            || treasury_withdrawal_destination.key()
+                != auction_house.treasury_withdrawal_destination
+        {
+            let pending_update_bump = *ctx
+                .bumps
+                .get("pending_update")
+                .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+
+            queue_pending_update(
+                &ctx.accounts.pending_update,
+                &payer.to_account_info(),
+                &rent.to_account_info(),
+                &system_program.to_account_info(),
+                auction_house.key(),
+                pending_update_bump,
+                new_authority.key(),
+                treasury_withdrawal_destination.key(),
+            )?;
+        }
+
         if !is_native {
             if treasury_withdrawal_destination.data_is_empty() {
                 make_ata(
@@ -207,6 +382,14 @@ pub mod auction_house {
             )?;
         }
 
+        emit_ah_event(
+            AhEventKind::UpdateAuctionHouse,
+            auction_house.key(),
+            None,
+            0,
+            vec![auction_house.authority],
+        )?;
+
         Ok(())
     }
 
@@ -241,6 +424,7 @@ pub mod auction_house {
             .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
         auction_house.fee_payer_bump = fee_payer_bump;
         auction_house.treasury_bump = treasury_bump;
+        auction_house.program_as_signer_bump = pda::find_program_as_signer_address().1;
         if seller_fee_basis_points > 10000 {
             return Err(AuctionHouseError::InvalidBasisPoints.into());
         }
@@ -256,6 +440,7 @@ pub mod auction_house {
         auction_house.fee_withdrawal_destination = fee_withdrawal_destination.key();
 
         let is_native = treasury_mint.key() == spl_token::native_mint::id();
+        unpack_mint(treasury_mint)?;
 
         let ah_key = auction_house.key();
 
@@ -306,6 +491,14 @@ pub mod auction_house {
             )?;
         }
 
+        emit_ah_event(
+            AhEventKind::CreateAuctionHouse,
+            ah_key,
+            None,
+            0,
+            vec![authority.key()],
+        )?;
+
         Ok(())
     }
 
@@ -326,6 +519,15 @@ pub mod auction_house {
         )
     }
 
+    /// Create a private buy bid like `buy`, deriving the trade state and escrow payment bumps on chain.
+    pub fn buy_v2<'info>(
+        ctx: Context<'_, '_, '_, 'info, BuyV2<'info>>,
+        buyer_price: u64,
+        token_size: u64,
+    ) -> Result<()> {
+        private_bid_v2(ctx, buyer_price, token_size)
+    }
+
     pub fn auctioneer_buy<'info>(
         ctx: Context<'_, '_, '_, 'info, AuctioneerBuy<'info>>,
         trade_state_bump: u8,
@@ -376,6 +578,23 @@ pub mod auction_house {
         )
     }
 
+    /// Create a private buy bid on behalf of a wallet with an approved session key, debiting the session key spend limit. The escrow must already hold the bid amount.
+    pub fn session_buy<'info>(
+        ctx: Context<'_, '_, '_, 'info, SessionBuy<'info>>,
+        trade_state_bump: u8,
+        escrow_payment_bump: u8,
+        buyer_price: u64,
+        token_size: u64,
+    ) -> Result<()> {
+        session_private_bid(
+            ctx,
+            trade_state_bump,
+            escrow_payment_bump,
+            buyer_price,
+            token_size,
+        )
+    }
+
     /// Cancel a bid or ask by revoking the token delegate, transferring all lamports from the trade state account to the fee payer, and setting the trade state account data to zero so it can be garbage collected.
     pub fn cancel<'info>(
         ctx: Context<'_, '_, '_, 'info, Cancel<'info>>,
@@ -394,6 +613,16 @@ pub mod auction_house {
         cancel::auctioneer_cancel(ctx, buyer_price, token_size)
     }
 
+    /// Cancel a bid or ask on behalf of a wallet with an approved session key, returning the trade state lamports to the wallet.
+    /// Canceled bids are credited back to the spend limit of the session key.
+    pub fn session_cancel<'info>(
+        ctx: Context<'_, '_, '_, 'info, SessionCancel<'info>>,
+        buyer_price: u64,
+        token_size: u64,
+    ) -> Result<()> {
+        cancel::session_cancel(ctx, buyer_price, token_size)
+    }
+
     /// Deposit `amount` into the escrow payment account for your specific wallet.
     pub fn deposit<'info>(
         ctx: Context<'_, '_, '_, 'info, Deposit<'info>>,
@@ -403,6 +632,14 @@ pub mod auction_house {
         deposit::deposit(ctx, escrow_payment_bump, amount)
     }
 
+    /// Deposit `amount` into the escrow payment account for your specific wallet, deriving the escrow payment bump on chain.
+    pub fn deposit_v2<'info>(
+        ctx: Context<'_, '_, '_, 'info, DepositV2<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        deposit::deposit_v2(ctx, amount)
+    }
+
     /// Deposit `amount` into the escrow payment account for your specific wallet.
     pub fn auctioneer_deposit<'info>(
         ctx: Context<'_, '_, '_, 'info, AuctioneerDeposit<'info>>,
@@ -434,6 +671,65 @@ pub mod auction_house {
         )
     }
 
+    /// Execute a sale like `execute_sale`, deriving the escrow payment and free trade state bumps on chain and reading
+    /// the program as signer bump from the auction house.
+    pub fn execute_sale_v2<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteSaleV2<'info>>,
+        buyer_price: u64,
+        token_size: u64,
+        partial_order_size: Option<u64>,
+        partial_order_price: Option<u64>,
+    ) -> Result<()> {
+        execute_sale::execute_sale_v2(
+            ctx,
+            buyer_price,
+            token_size,
+            partial_order_size,
+            partial_order_price,
+        )
+    }
+
+    /// Permissionless crank settling a bid at or above an ask for the same token at the ask price, paying the cranker a
+    /// small bounty.
+    pub fn match_orders<'info>(
+        ctx: Context<'_, '_, '_, 'info, MatchOrders<'info>>,
+        ask_price: u64,
+        bid_price: u64,
+        token_size: u64,
+    ) -> Result<()> {
+        execute_sale::match_orders(ctx, ask_price, bid_price, token_size)
+    }
+
+    /// Create the buyer, seller and creator token accounts needed by `execute_sale_prepared`, paid by the fee account.
+    pub fn prepare_sale_accounts<'info>(
+        ctx: Context<'_, '_, '_, 'info, PrepareSaleAccounts<'info>>,
+    ) -> Result<()> {
+        execute_sale::prepare_sale_accounts(ctx)
+    }
+
+    /// Execute a sale whose receipt accounts were created beforehand with `prepare_sale_accounts`.
+    pub fn execute_sale_prepared<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteSale<'info>>,
+        escrow_payment_bump: u8,
+        _free_trade_state_bump: u8,
+        program_as_signer_bump: u8,
+        buyer_price: u64,
+        token_size: u64,
+        partial_order_size: Option<u64>,
+        partial_order_price: Option<u64>,
+    ) -> Result<()> {
+        execute_sale::execute_sale_prepared(
+            ctx,
+            escrow_payment_bump,
+            _free_trade_state_bump,
+            program_as_signer_bump,
+            buyer_price,
+            token_size,
+            partial_order_size,
+            partial_order_price,
+        )
+    }
+
     pub fn auctioneer_execute_sale<'info>(
         ctx: Context<'_, '_, '_, 'info, AuctioneerExecuteSale<'info>>,
         escrow_payment_bump: u8,
@@ -470,6 +766,26 @@ pub mod auction_house {
         )
     }
 
+    /// List a token like `sell`, deriving the trade state bumps on chain and reading the program as signer bump from the
+    /// auction house.
+    pub fn sell_v2<'info>(
+        ctx: Context<'_, '_, '_, 'info, SellV2<'info>>,
+        buyer_price: u64,
+        token_size: u64,
+    ) -> Result<()> {
+        sell::sell_v2(ctx, buyer_price, token_size)
+    }
+
+    /// List a token held by a custodial platform on behalf of a user, whose approval `execute_sale` then requires.
+    pub fn custodial_sell<'info>(
+        ctx: Context<'_, '_, '_, 'info, CustodialSell<'info>>,
+        buyer_price: u64,
+        token_size: u64,
+        user_identity_hash: [u8; 32],
+    ) -> Result<()> {
+        custodial_sell::custodial_sell(ctx, buyer_price, token_size, user_identity_hash)
+    }
+
     pub fn auctioneer_sell<'info>(
         ctx: Context<'_, '_, '_, 'info, AuctioneerSell<'info>>,
         trade_state_bump: u8,
@@ -495,6 +811,14 @@ pub mod auction_house {
         withdraw::withdraw(ctx, escrow_payment_bump, amount)
     }
 
+    /// Withdraw `amount` from the escrow payment account for your specific wallet, deriving the escrow payment bump on chain.
+    pub fn withdraw_v2<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawV2<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        withdraw::withdraw_v2(ctx, amount)
+    }
+
     /// Withdraw `amount` from the escrow payment account for your specific wallet.
     pub fn auctioneer_withdraw<'info>(
         ctx: Context<'_, '_, '_, 'info, AuctioneerWithdraw<'info>>,
@@ -532,6 +856,15 @@ pub mod auction_house {
             ],
             &[&escrow_signer_seeds],
         )?;
+
+        emit_ah_event(
+            AhEventKind::CloseEscrowAccount,
+            auction_house_key,
+            None,
+            0,
+            vec![wallet_key],
+        )?;
+
         Ok(())
     }
 
@@ -549,6 +882,376 @@ pub mod auction_house {
         auctioneer::update_auctioneer(ctx, scopes)
     }
 
+    /// Withdraw `amount` from the Auction House Treasury Account to its withdrawal destination, as an auctioneer.
+    pub fn auctioneer_withdraw_from_treasury<'info>(
+        ctx: Context<'_, '_, '_, 'info, AuctioneerWithdrawFromTreasury<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        auctioneer::auctioneer_withdraw_from_treasury(ctx, amount)
+    }
+
+    /// Update the seller fee basis points, sale price changes and tick size of the Auction House, as an auctioneer.
+    pub fn auctioneer_update_auction_house<'info>(
+        ctx: Context<'_, '_, '_, 'info, AuctioneerUpdateAuctionHouse<'info>>,
+        seller_fee_basis_points: Option<u16>,
+        can_change_sale_price: Option<bool>,
+        tick_size: Option<u64>,
+    ) -> Result<()> {
+        auctioneer::auctioneer_update_auction_house(
+            ctx,
+            seller_fee_basis_points,
+            can_change_sale_price,
+            tick_size,
+        )
+    }
+
+    /// Approve a session key to bid and cancel on behalf of the wallet up to `max_spend` until `expiry`.
+    pub fn approve_session_key<'info>(
+        ctx: Context<'_, '_, '_, 'info, ApproveSessionKey<'info>>,
+        max_spend: u64,
+        expiry: i64,
+    ) -> Result<()> {
+        session_key::approve_session_key(ctx, max_spend, expiry)
+    }
+
+    /// Revoke a session key by closing its PDA.
+    pub fn revoke_session_key<'info>(
+        ctx: Context<'_, '_, '_, 'info, RevokeSessionKey<'info>>,
+    ) -> Result<()> {
+        session_key::revoke_session_key(ctx)
+    }
+
+    /// Route a percentage of house fees into a revenue share PDA claimable by holders of a governance mint.
+    pub fn create_revenue_share<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateRevenueShare<'info>>,
+        basis_points: u16,
+    ) -> Result<()> {
+        revenue_share::create_revenue_share(ctx, basis_points)
+    }
+
+    /// Snapshot the accrued revenue share so governance token holders at `slot` can claim it.
+    pub fn snapshot_revenue_share<'info>(
+        ctx: Context<'_, '_, '_, 'info, SnapshotRevenueShare<'info>>,
+        merkle_root: [u8; 32],
+        supply: u64,
+        slot: u64,
+    ) -> Result<()> {
+        revenue_share::snapshot_revenue_share(ctx, merkle_root, supply, slot)
+    }
+
+    /// Claim the pro-rata part of a revenue share snapshot by presenting a governance token account and its merkle proof.
+    pub fn claim_revenue_share<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClaimRevenueShare<'info>>,
+        snapshot_index: u64,
+        index: u64,
+        balance: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        revenue_share::claim_revenue_share(ctx, snapshot_index, index, balance, proof)
+    }
+
+    /// Apply an authority or treasury withdrawal destination change queued by `update_auction_house` once its timelock elapsed.
+    pub fn apply_pending_update<'info>(
+        ctx: Context<'_, '_, '_, 'info, ApplyPendingUpdate<'info>>,
+    ) -> Result<()> {
+        pending_update::apply_pending_update(ctx)
+    }
+
+    /// Cancel an authority or treasury withdrawal destination change queued by `update_auction_house`.
+    pub fn cancel_pending_update<'info>(
+        ctx: Context<'_, '_, '_, 'info, CancelPendingUpdate<'info>>,
+    ) -> Result<()> {
+        pending_update::cancel_pending_update(ctx)
+    }
+
+    /// Register a wallet under a group in the linked-wallet registry checked by the wash trading guard.
+    pub fn register_linked_wallet<'info>(
+        ctx: Context<'_, '_, '_, 'info, RegisterLinkedWallet<'info>>,
+        group: Pubkey,
+    ) -> Result<()> {
+        linked_wallet::register_linked_wallet(ctx, group)
+    }
+
+    /// Remove a wallet from the linked-wallet registry.
+    pub fn remove_linked_wallet<'info>(
+        ctx: Context<'_, '_, '_, 'info, RemoveLinkedWallet<'info>>,
+    ) -> Result<()> {
+        linked_wallet::remove_linked_wallet(ctx)
+    }
+
+    /// Create the order book summary of a token mint. From then on, every order of the Auction House must pass the
+    /// summary address of its mint among the remaining accounts.
+    pub fn create_order_book_summary<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateOrderBookSummary<'info>>,
+    ) -> Result<()> {
+        order_book::create_order_book_summary(ctx)
+    }
+
+    /// Move a listing to another Auction House, canceling the source trade state and printing a new listing receipt.
+    pub fn migrate_listing<'info>(
+        ctx: Context<'_, '_, '_, 'info, MigrateListing<'info>>,
+        trade_state_bump: u8,
+        receipt_bump: u8,
+        buyer_price: u64,
+        token_size: u64,
+    ) -> Result<()> {
+        migrate::migrate_listing(ctx, trade_state_bump, receipt_bump, buyer_price, token_size)
+    }
+
+    /// Record the intended price and size of a listing in a pending listing PDA, to be finalized in a separate transaction.
+    pub fn initiate_listing<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitiateListing<'info>>,
+        buyer_price: u64,
+        token_size: u64,
+    ) -> Result<()> {
+        pending_listing::initiate_listing(ctx, buyer_price, token_size)
+    }
+
+    /// Finalize a pending listing by approving the token delegate and creating the seller trade state with the recorded parameters.
+    pub fn finalize_listing<'info>(
+        ctx: Context<'_, '_, '_, 'info, FinalizeListing<'info>>,
+        trade_state_bump: u8,
+        free_trade_state_bump: u8,
+        program_as_signer_bump: u8,
+    ) -> Result<()> {
+        pending_listing::finalize_listing(
+            ctx,
+            trade_state_bump,
+            free_trade_state_bump,
+            program_as_signer_bump,
+        )
+    }
+
+    /// Create a sell bid from a listing the seller signed off-chain, verified through the ed25519 program.
+    pub fn post_signed_listing<'info>(
+        ctx: Context<'_, '_, '_, 'info, PostSignedListing<'info>>,
+        trade_state_bump: u8,
+        program_as_signer_bump: u8,
+        buyer_price: u64,
+        token_size: u64,
+        expiry: i64,
+        nonce: u64,
+    ) -> Result<()> {
+        signed_listing::post_signed_listing(
+            ctx,
+            trade_state_bump,
+            program_as_signer_bump,
+            buyer_price,
+            token_size,
+            expiry,
+            nonce,
+        )
+    }
+
+    /// Cancel a listing the seller signed off-chain before it is posted, so its signature can't be posted.
+    pub fn cancel_signed_listing<'info>(
+        ctx: Context<'_, '_, '_, 'info, CancelSignedListing<'info>>,
+        nonce: u64,
+    ) -> Result<()> {
+        signed_listing::cancel_signed_listing(ctx, nonce)
+    }
+
+    /// Redirect the royalties of the signing creator to a destination such as a DAO treasury or split contract.
+    pub fn set_royalty_mandate<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetRoyaltyMandate<'info>>,
+    ) -> Result<()> {
+        royalty_mandate::set_royalty_mandate(ctx)
+    }
+
+    /// Revoke the royalty mandate of the signing creator.
+    pub fn revoke_royalty_mandate<'info>(
+        ctx: Context<'_, '_, '_, 'info, RevokeRoyaltyMandate<'info>>,
+    ) -> Result<()> {
+        royalty_mandate::revoke_royalty_mandate(ctx)
+    }
+
+    /// Route the proceeds of a listing to a destination such as a split program wallet instead of the seller.
+    pub fn set_proceeds_route<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetProceedsRoute<'info>>,
+    ) -> Result<()> {
+        proceeds_route::set_proceeds_route(ctx)
+    }
+
+    /// Revoke the proceeds route of a listing of the signing seller.
+    pub fn revoke_proceeds_route<'info>(
+        ctx: Context<'_, '_, '_, 'info, RevokeProceedsRoute<'info>>,
+    ) -> Result<()> {
+        proceeds_route::revoke_proceeds_route(ctx)
+    }
+
+    /// Sell the items listed by `sell` under the seller trade states passed as remaining accounts together as one lot.
+    pub fn create_bundle_listing<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateBundleListing<'info>>,
+        bundle_id: u64,
+        items: Vec<BundleItem>,
+    ) -> Result<()> {
+        bundle_listing::create_bundle_listing(ctx, bundle_id, items)
+    }
+
+    /// Buy all items of a bundle listing atomically, paying creator fees per item metadata.
+    pub fn execute_bundle_sale<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteBundleSale<'info>>,
+        escrow_payment_bump: u8,
+        program_as_signer_bump: u8,
+        buyer_price: u64,
+    ) -> Result<()> {
+        bundle_listing::execute_bundle_sale(
+            ctx,
+            escrow_payment_bump,
+            program_as_signer_bump,
+            buyer_price,
+        )
+    }
+
+    /// Close a bundle listing, leaving its items listed on their own.
+    pub fn cancel_bundle_listing<'info>(
+        ctx: Context<'_, '_, '_, 'info, CancelBundleListing<'info>>,
+    ) -> Result<()> {
+        bundle_listing::cancel_bundle_listing(ctx)
+    }
+
+    /// Move a listing into a layaway: the tokens go to program custody and the buyer pays `price` in `installments`
+    /// installments, one due every `installment_interval` seconds.
+    #[allow(clippy::too_many_arguments)]
+    pub fn begin_layaway<'info>(
+        ctx: Context<'_, '_, '_, 'info, BeginLayaway<'info>>,
+        program_as_signer_bump: u8,
+        price: u64,
+        token_size: u64,
+        installments: u8,
+        installment_interval: i64,
+        penalty_basis_points: u16,
+    ) -> Result<()> {
+        layaway::begin_layaway(
+            ctx,
+            program_as_signer_bump,
+            price,
+            token_size,
+            installments,
+            installment_interval,
+            penalty_basis_points,
+        )
+    }
+
+    /// Pay `amount` towards a layaway into its escrow.
+    pub fn pay_installment<'info>(
+        ctx: Context<'_, '_, '_, 'info, PayInstallment<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        layaway::pay_installment(ctx, amount)
+    }
+
+    /// Settle a fully paid layaway, paying fees and the seller and releasing the tokens to the buyer.
+    pub fn complete_layaway<'info>(
+        ctx: Context<'_, '_, '_, 'info, CompleteLayaway<'info>>,
+        program_as_signer_bump: u8,
+    ) -> Result<()> {
+        layaway::complete_layaway(ctx, program_as_signer_bump)
+    }
+
+    /// Cancel a defaulted layaway, returning the tokens to the seller along with the penalty and refunding the buyer.
+    pub fn cancel_layaway<'info>(
+        ctx: Context<'_, '_, '_, 'info, CancelLayaway<'info>>,
+        program_as_signer_bump: u8,
+    ) -> Result<()> {
+        layaway::cancel_layaway(ctx, program_as_signer_bump)
+    }
+
+    /// Offer tokens of one mint for tokens of another, delegating the offered tokens to the program as signer.
+    pub fn propose_swap<'info>(
+        ctx: Context<'_, '_, '_, 'info, ProposeSwap<'info>>,
+        program_as_signer_bump: u8,
+        offered_size: u64,
+        target_size: u64,
+        max_offered_valuation: u64,
+    ) -> Result<()> {
+        swap::propose_swap(
+            ctx,
+            program_as_signer_bump,
+            offered_size,
+            target_size,
+            max_offered_valuation,
+        )
+    }
+
+    /// Exchange the tokens of a swap proposal, each side paying royalties on the valuation supplied by the authority.
+    pub fn accept_swap<'info>(
+        ctx: Context<'_, '_, '_, 'info, AcceptSwap<'info>>,
+        proposer_escrow_bump: u8,
+        acceptor_escrow_bump: u8,
+        program_as_signer_bump: u8,
+        offered_valuation: u64,
+        target_valuation: u64,
+    ) -> Result<()> {
+        swap::accept_swap(
+            ctx,
+            proposer_escrow_bump,
+            acceptor_escrow_bump,
+            program_as_signer_bump,
+            offered_valuation,
+            target_valuation,
+        )
+    }
+
+    /// Close a swap proposal and revoke the delegation of the offered tokens.
+    pub fn cancel_swap<'info>(ctx: Context<'_, '_, '_, 'info, CancelSwap<'info>>) -> Result<()> {
+        swap::cancel_swap(ctx)
+    }
+
+    /// Cap the volume each wallet can trade per epoch and freeze wallets out of sales.
+    pub fn create_compliance_config<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateComplianceConfig<'info>>,
+        volume_cap_per_epoch: u64,
+        frozen_wallets: Vec<Pubkey>,
+    ) -> Result<()> {
+        compliance::create_compliance_config(ctx, volume_cap_per_epoch, frozen_wallets)
+    }
+
+    /// Replace the volume cap and the freeze list of the compliance config.
+    pub fn update_compliance_config<'info>(
+        ctx: Context<'_, '_, '_, 'info, UpdateComplianceConfig<'info>>,
+        volume_cap_per_epoch: u64,
+        frozen_wallets: Vec<Pubkey>,
+    ) -> Result<()> {
+        compliance::update_compliance_config(ctx, volume_cap_per_epoch, frozen_wallets)
+    }
+
+    /// Close the compliance config, lifting the volume cap and the freeze list.
+    pub fn close_compliance_config<'info>(
+        ctx: Context<'_, '_, '_, 'info, CloseComplianceConfig<'info>>,
+    ) -> Result<()> {
+        compliance::close_compliance_config(ctx)
+    }
+
+    /// Close an epoch report of the Auction House once its retention period is over, reclaiming its rent.
+    pub fn close_epoch_report<'info>(
+        ctx: Context<'_, '_, '_, 'info, CloseEpochReport<'info>>,
+    ) -> Result<()> {
+        epoch_report::close_epoch_report(ctx)
+    }
+
+    /// Flag a purchase as disputed within the dispute window, freezing its held proceeds.
+    pub fn flag_purchase<'info>(
+        ctx: Context<'_, '_, '_, 'info, FlagPurchase<'info>>,
+    ) -> Result<()> {
+        dispute::flag_purchase(ctx)
+    }
+
+    /// Release the held proceeds of an undisputed purchase to the seller once the dispute window passed.
+    pub fn release_settlement<'info>(
+        ctx: Context<'_, '_, '_, 'info, ReleaseSettlement<'info>>,
+    ) -> Result<()> {
+        dispute::release_settlement(ctx)
+    }
+
+    /// Resolve a disputed purchase, releasing the held proceeds to the seller or refunding the buyer.
+    pub fn resolve_dispute<'info>(
+        ctx: Context<'_, '_, '_, 'info, ResolveDispute<'info>>,
+        release_to_seller: bool,
+    ) -> Result<()> {
+        dispute::resolve_dispute(ctx, release_to_seller)
+    }
+
     /// Create a listing receipt by creating a `listing_receipt` account.
     pub fn print_listing_receipt<'info>(
         ctx: Context<'_, '_, '_, 'info, PrintListingReceipt<'info>>,
@@ -592,8 +1295,10 @@ pub mod auction_house {
 #[derive(Accounts)]
 #[instruction(bump: u8, fee_payer_bump: u8, treasury_bump: u8)]
 pub struct CreateAuctionHouse<'info> {
-    /// Treasury mint account, either native SOL mint or a SPL token mint.
-    pub treasury_mint: Account<'info, Mint>,
+    /// CHECK: Checked in constraint to be owned by SPL Token or Token-2022.
+    /// Treasury mint account, either native SOL mint or a SPL Token or Token-2022 mint.
+    #[account(constraint = is_token_program(treasury_mint.owner) @ AuctionHouseError::InvalidTokenProgram)]
+    pub treasury_mint: UncheckedAccount<'info>,
 
     /// Key paying SOL fees for setting up the Auction House.
     #[account(mut)]
@@ -631,7 +1336,10 @@ pub struct CreateAuctionHouse<'info> {
     #[account(mut, seeds=[PREFIX.as_bytes(), auction_house.key().as_ref(), TREASURY.as_bytes()], bump=treasury_bump)]
     pub auction_house_treasury: UncheckedAccount<'info>,
 
-    pub token_program: Program<'info, Token>,
+    /// CHECK: Checked in constraint to be SPL Token or Token-2022.
+    /// Token program of the treasury mint.
+    #[account(constraint = is_treasury_token_program(token_program.key, &treasury_mint.to_account_info()) @ AuctionHouseError::InvalidTokenProgram)]
+    pub token_program: UncheckedAccount<'info>,
     pub system_program: Program<'info, System>,
     pub ata_program: Program<'info, AssociatedToken>,
     pub rent: Sysvar<'info, Rent>,
@@ -640,10 +1348,13 @@ pub struct CreateAuctionHouse<'info> {
 /// Accounts for the [`update_auction_house` handler](auction_house/fn.update_auction_house.html).
 #[derive(Accounts)]
 pub struct UpdateAuctionHouse<'info> {
-    /// Treasury mint account, either native SOL mint or a SPL token mint.
-    pub treasury_mint: Account<'info, Mint>,
+    /// CHECK: Checked in constraint to be owned by SPL Token or Token-2022.
+    /// Treasury mint account, either native SOL mint or a SPL Token or Token-2022 mint.
+    #[account(constraint = is_token_program(treasury_mint.owner) @ AuctionHouseError::InvalidTokenProgram)]
+    pub treasury_mint: UncheckedAccount<'info>,
 
     /// Key paying SOL fees for setting up the Auction House.
+    #[account(mut)]
     pub payer: Signer<'info>,
 
     /// Authority key for the Auction House.
@@ -671,7 +1382,15 @@ pub struct UpdateAuctionHouse<'info> {
     #[account(mut, seeds=[PREFIX.as_bytes(), auction_house.creator.as_ref(), treasury_mint.key().as_ref()], bump=auction_house.bump, has_one=authority, has_one=treasury_mint)]
     pub auction_house: Account<'info, AuctionHouse>,
 
-    pub token_program: Program<'info, Token>,
+    /// CHECK: Validated in update_auction_house.
+    /// Pending update PDA, created when the authority or treasury withdrawal destination changes.
+    #[account(mut, seeds=[PREFIX.as_bytes(), auction_house.key().as_ref(), PENDING_UPDATE.as_bytes()], bump)]
+    pub pending_update: UncheckedAccount<'info>,
+
+    /// CHECK: Checked in constraint to be SPL Token or Token-2022.
+    /// Token program of the treasury mint.
+    #[account(constraint = is_treasury_token_program(token_program.key, &treasury_mint.to_account_info()) @ AuctionHouseError::InvalidTokenProgram)]
+    pub token_program: UncheckedAccount<'info>,
     pub system_program: Program<'info, System>,
     pub ata_program: Program<'info, AssociatedToken>,
     pub rent: Sysvar<'info, Rent>,
@@ -680,8 +1399,9 @@ pub struct UpdateAuctionHouse<'info> {
 /// Accounts for the [`withdraw_from_treasury` handler](auction_house/fn.withdraw_from_treasury.html).
 #[derive(Accounts)]
 pub struct WithdrawFromTreasury<'info> {
-    /// Treasury mint account, either native SOL mint or a SPL token mint.
-    pub treasury_mint: Account<'info, Mint>,
+    /// CHECK: Checked in has_one constraint of the auction house.
+    /// Treasury mint account, either native SOL mint or a SPL Token or Token-2022 mint.
+    pub treasury_mint: UncheckedAccount<'info>,
 
     /// Authority key for the Auction House.
     pub authority: Signer<'info>,
@@ -700,10 +1420,37 @@ pub struct WithdrawFromTreasury<'info> {
     #[account(mut, seeds=[PREFIX.as_bytes(), auction_house.creator.as_ref(), treasury_mint.key().as_ref()], bump=auction_house.bump, has_one=authority, has_one=treasury_mint, has_one=treasury_withdrawal_destination, has_one=auction_house_treasury)]
     pub auction_house: Account<'info, AuctionHouse>,
 
-    pub token_program: Program<'info, Token>,
+    /// CHECK: Checked in constraint to be SPL Token or Token-2022.
+    /// Token program of the treasury mint.
+    #[account(constraint = is_treasury_token_program(token_program.key, &treasury_mint.to_account_info()) @ AuctionHouseError::InvalidTokenProgram)]
+    pub token_program: UncheckedAccount<'info>,
     pub system_program: Program<'info, System>,
 }
 
+/// Accounts for the [`top_up_fee_account` handler](auction_house/fn.top_up_fee_account.html).
+#[derive(Accounts)]
+pub struct TopUpFeeAccount<'info> {
+    /// Treasury mint account, must be the native SOL mint.
+    pub treasury_mint: Account<'info, Mint>,
+
+    /// Auction House instance fee account.
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(mut, seeds=[PREFIX.as_bytes(), auction_house.key().as_ref(), FEE_PAYER.as_bytes()], bump=auction_house.fee_payer_bump)]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// Auction House treasury PDA account.
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(mut, seeds=[PREFIX.as_bytes(), auction_house.key().as_ref(), TREASURY.as_bytes()], bump=auction_house.treasury_bump)]
+    pub auction_house_treasury: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(seeds=[PREFIX.as_bytes(), auction_house.creator.as_ref(), treasury_mint.key().as_ref()], bump=auction_house.bump, has_one=treasury_mint, has_one=auction_house_fee_account, has_one=auction_house_treasury)]
+    pub auction_house: Account<'info, AuctionHouse>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
 /// Accounts for the [`withdraw_from_fee` handler](auction_house/fn.withdraw_from_fee.html).
 #[derive(Accounts)]
 pub struct WithdrawFromFee<'info> {
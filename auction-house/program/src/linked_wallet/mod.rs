@@ -0,0 +1,123 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::*, utils::emit_ah_event, AhEventKind, AuctionHouse, LinkedWallet};
+
+/// Accounts for the [`register_linked_wallet` handler](auction_house/fn.register_linked_wallet.html).
+#[derive(Accounts)]
+pub struct RegisterLinkedWallet<'info> {
+    /// Authority key for the Auction House.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+
+    /// CHECK: Any wallet can be registered by the authority.
+    /// Wallet being linked to the group.
+    pub wallet: UncheckedAccount<'info>,
+
+    /// The linked wallet registry entry PDA.
+    #[account(
+        init,
+        payer = authority,
+        space = LINKED_WALLET_SIZE,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            LINKED_WALLET.as_bytes(),
+            wallet.key().as_ref()
+        ],
+        bump
+    )]
+    pub linked_wallet: Account<'info, LinkedWallet>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Register `wallet` in the linked-wallet registry of the Auction House under `group`.
+pub fn register_linked_wallet<'info>(
+    ctx: Context<'_, '_, '_, 'info, RegisterLinkedWallet<'info>>,
+    group: Pubkey,
+) -> Result<()> {
+    let linked_wallet = &mut ctx.accounts.linked_wallet;
+
+    linked_wallet.auction_house = ctx.accounts.auction_house.key();
+    linked_wallet.wallet = ctx.accounts.wallet.key();
+    linked_wallet.group = group;
+    linked_wallet.bump = *ctx
+        .bumps
+        .get("linked_wallet")
+        .ok_or(crate::errors::AuctionHouseError::BumpSeedNotInHashMap)?;
+
+    emit_ah_event(
+        AhEventKind::RegisterLinkedWallet,
+        ctx.accounts.auction_house.key(),
+        None,
+        0,
+        vec![ctx.accounts.authority.key(), ctx.accounts.wallet.key()],
+    )?;
+
+    Ok(())
+}
+
+/// Accounts for the [`remove_linked_wallet` handler](auction_house/fn.remove_linked_wallet.html).
+#[derive(Accounts)]
+pub struct RemoveLinkedWallet<'info> {
+    /// Authority key for the Auction House.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+
+    /// The linked wallet registry entry PDA, closed back to the authority.
+    #[account(
+        mut,
+        close = authority,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            LINKED_WALLET.as_bytes(),
+            linked_wallet.wallet.as_ref()
+        ],
+        bump=linked_wallet.bump,
+        has_one=auction_house
+    )]
+    pub linked_wallet: Account<'info, LinkedWallet>,
+}
+
+/// Remove a wallet from the linked-wallet registry of the Auction House.
+pub fn remove_linked_wallet<'info>(
+    ctx: Context<'_, '_, '_, 'info, RemoveLinkedWallet<'info>>,
+) -> Result<()> {
+    emit_ah_event(
+        AhEventKind::RemoveLinkedWallet,
+        ctx.accounts.auction_house.key(),
+        None,
+        0,
+        vec![
+            ctx.accounts.authority.key(),
+            ctx.accounts.linked_wallet.wallet,
+        ],
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,111 @@
+//! Borrowed reader of the royalty fields of a token metadata account.
+//!
+//! Sales only need the seller fee basis points, the creators and `primary_sale_happened`, so instead of deserializing
+//! the whole account, including the name, symbol and uri strings, the reader skips over the strings by their length
+//! prefix and reads the creators in place.
+use crate::errors::AuctionHouseError;
+
+use anchor_lang::{prelude::*, solana_program::pubkey::PUBKEY_BYTES};
+use arrayref::array_ref;
+
+/// `Key::MetadataV1` of token metadata.
+const METADATA_V1_KEY: u8 = 4;
+/// Key, update authority and mint precede the metadata data.
+const DATA_OFFSET: usize = 1 + PUBKEY_BYTES + PUBKEY_BYTES;
+const STRING_LEN_PREFIX_LEN: usize = 4;
+/// Address, verified flag and share.
+const CREATOR_LEN: usize = PUBKEY_BYTES + 1 + 1;
+
+/// Creator of a token metadata account as stored in the account data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MetadataCreator {
+    pub address: Pubkey,
+    pub verified: bool,
+    /// In percentages, not basis points.
+    pub share: u8,
+}
+
+/// Royalty fields of a token metadata account, borrowing the creators from the account data.
+#[derive(Clone, Copy, Debug)]
+pub struct MetadataRoyalties<'a> {
+    pub seller_fee_basis_points: u16,
+    pub primary_sale_happened: bool,
+    creators: Option<&'a [u8]>,
+}
+
+impl<'a> MetadataRoyalties<'a> {
+    /// Read the royalty fields from the data of a token metadata account.
+    pub fn from_bytes(data: &'a [u8]) -> Result<Self> {
+        if data.first() != Some(&METADATA_V1_KEY) {
+            return Err(AuctionHouseError::InvalidMetadata.into());
+        }
+
+        let mut offset = DATA_OFFSET;
+        // Name, symbol and uri.
+        for _ in 0..3 {
+            let len = u32::from_le_bytes(*array_ref![
+                read(data, offset, STRING_LEN_PREFIX_LEN)?,
+                0,
+                STRING_LEN_PREFIX_LEN
+            ]) as usize;
+            offset = offset
+                .checked_add(STRING_LEN_PREFIX_LEN)
+                .and_then(|offset| offset.checked_add(len))
+                .ok_or(AuctionHouseError::InvalidMetadata)?;
+        }
+
+        let seller_fee_basis_points = u16::from_le_bytes(*array_ref![read(data, offset, 2)?, 0, 2]);
+        offset += 2;
+
+        let creators = match read(data, offset, 1)?[0] {
+            0 => {
+                offset += 1;
+                None
+            }
+            1 => {
+                let count =
+                    u32::from_le_bytes(*array_ref![read(data, offset + 1, 4)?, 0, 4]) as usize;
+                offset += 1 + 4;
+                let len = count
+                    .checked_mul(CREATOR_LEN)
+                    .ok_or(AuctionHouseError::InvalidMetadata)?;
+                let creators = read(data, offset, len)?;
+                offset += len;
+                Some(creators)
+            }
+            _ => return Err(AuctionHouseError::InvalidMetadata.into()),
+        };
+
+        let primary_sale_happened = read(data, offset, 1)?[0] != 0;
+
+        Ok(Self {
+            seller_fee_basis_points,
+            primary_sale_happened,
+            creators,
+        })
+    }
+
+    /// Whether the metadata lists creators at all, an empty list still counts as present.
+    pub fn has_creators(&self) -> bool {
+        self.creators.is_some()
+    }
+
+    /// Creators of the metadata in order, none when the metadata has no creators.
+    pub fn creators(&self) -> impl Iterator<Item = MetadataCreator> + 'a {
+        self.creators
+            .unwrap_or(&[])
+            .chunks_exact(CREATOR_LEN)
+            .map(|creator| MetadataCreator {
+                address: Pubkey::new_from_array(*array_ref![creator, 0, PUBKEY_BYTES]),
+                verified: creator[PUBKEY_BYTES] != 0,
+                share: creator[PUBKEY_BYTES + 1],
+            })
+    }
+}
+
+fn read(data: &[u8], offset: usize, len: usize) -> Result<&[u8]> {
+    offset
+        .checked_add(len)
+        .and_then(|end| data.get(offset..end))
+        .ok_or_else(|| AuctionHouseError::InvalidMetadata.into())
+}
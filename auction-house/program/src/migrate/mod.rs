@@ -0,0 +1,314 @@
+use anchor_lang::{prelude::*, AnchorDeserialize};
+use anchor_spl::token::TokenAccount;
+use solana_program::program_memory::sol_memset;
+
+use crate::{
+    constants::*,
+    errors::AuctionHouseError,
+    order_book::*,
+    receipt::{ListingReceipt, LISTING_RECEIPT_SIZE},
+    utils::*,
+    AhEventKind, AuctionHouse,
+};
+
+/// Accounts for the [`migrate_listing` handler](auction_house/fn.migrate_listing.html).
+#[derive(Accounts)]
+#[instruction(
+    trade_state_bump: u8,
+    receipt_bump: u8,
+    buyer_price: u64,
+    token_size: u64
+)]
+pub struct MigrateListing<'info> {
+    /// Seller wallet, receives the source trade state lamports if it paid for them and pays for the new listing.
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    /// SPL token account containing the token for sale.
+    pub token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Verified in migrate_listing.
+    /// Metaplex metadata account decorating SPL mint account.
+    pub metadata: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account the listing is migrated from.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            source_auction_house.creator.as_ref(),
+            source_auction_house.treasury_mint.as_ref()
+        ],
+        bump=source_auction_house.bump
+    )]
+    pub source_auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Source Auction House fee account, refunded the source trade state rent if it paid for the listing.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            source_auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump=source_auction_house.fee_payer_bump
+    )]
+    pub source_auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in migrate_listing.
+    /// Seller trade state PDA account of the source Auction House, closed by the migration.
+    #[account(mut)]
+    pub source_trade_state: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account the listing is migrated to.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            destination_auction_house.creator.as_ref(),
+            destination_auction_house.treasury_mint.as_ref()
+        ],
+        bump=destination_auction_house.bump
+    )]
+    pub destination_auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Seller trade state PDA account of the destination Auction House.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            wallet.key().as_ref(),
+            destination_auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            destination_auction_house.treasury_mint.as_ref(),
+            token_account.mint.as_ref(),
+            &buyer_price.to_le_bytes(),
+            &token_size.to_le_bytes()
+        ],
+        bump=trade_state_bump
+    )]
+    pub destination_trade_state: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Listing receipt PDA account of the destination trade state.
+    #[account(
+        mut,
+        seeds = [
+            LISTING_RECEIPT_PREFIX.as_bytes(),
+            destination_trade_state.key().as_ref()
+        ],
+        bump=receipt_bump
+    )]
+    pub listing_receipt: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Move a listing to another Auction House sharing the same treasury mint, closing the source trade state and
+/// creating an equivalent one with a new listing receipt on the destination. The token delegate is kept as
+/// `program_as_signer` is shared by all Auction Houses. The listing receipt of the source trade state, if one
+/// was printed, can be passed as a remaining account to be marked canceled, next to the last sale PDA of the mint
+/// while the destination listing cooldown is on and the order book summaries of the mint on either Auction House.
+pub fn migrate_listing<'info>(
+    ctx: Context<'_, '_, '_, 'info, MigrateListing<'info>>,
+    trade_state_bump: u8,
+    receipt_bump: u8,
+    buyer_price: u64,
+    token_size: u64,
+) -> Result<()> {
+    let wallet = &ctx.accounts.wallet;
+    let token_account = &ctx.accounts.token_account;
+    let metadata = &ctx.accounts.metadata;
+    let source_auction_house = &ctx.accounts.source_auction_house;
+    let source_trade_state = &ctx.accounts.source_trade_state;
+    let destination_auction_house = &ctx.accounts.destination_auction_house;
+    let destination_trade_state = &ctx.accounts.destination_trade_state;
+    let listing_receipt = &ctx.accounts.listing_receipt;
+    let system_program = &ctx.accounts.system_program;
+    let rent = &ctx.accounts.rent;
+
+    assert_not_paused(destination_auction_house, PAUSE_SELL)?;
+    assert_price_on_tick(destination_auction_house, buyer_price)?;
+
+    if source_auction_house.key() == destination_auction_house.key()
+        || source_auction_house.treasury_mint != destination_auction_house.treasury_mint
+    {
+        return Err(AuctionHouseError::InvalidListingMigration.into());
+    }
+
+    // Auctioneer listings are priced by the auctioneer program and cannot be moved.
+    if source_auction_house.has_auctioneer || destination_auction_house.has_auctioneer {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    if destination_auction_house.requires_sign_off {
+        return Err(AuctionHouseError::CannotTakeThisActionWithoutAuctionHouseSignOff.into());
+    }
+
+    if source_trade_state.data_is_empty() {
+        return Err(AuctionHouseError::InvalidListingMigration.into());
+    }
+    let ts_bump = source_trade_state.try_borrow_data()?[0];
+    assert_valid_trade_state(
+        &wallet.key(),
+        source_auction_house,
+        buyer_price,
+        token_size,
+        &source_trade_state.to_account_info(),
+        &token_account.mint,
+        &token_account.key(),
+        ts_bump,
+    )?;
+    assert_is_ata(
+        &token_account.to_account_info(),
+        &wallet.key(),
+        &token_account.mint,
+    )?;
+    assert_metadata_valid(metadata, token_account)?;
+
+    if token_size > token_account.amount {
+        return Err(AuctionHouseError::InvalidTokenAmount.into());
+    }
+
+    assert_listing_cooldown(
+        destination_auction_house,
+        &destination_auction_house.key(),
+        &token_account.mint,
+        &wallet.key(),
+        ctx.remaining_accounts,
+    )?;
+
+    // Cancel the source listing, returning its lamports to whoever paid for them.
+    let wallet_info = wallet.to_account_info();
+    let source_auction_house_fee_account_info = ctx
+        .accounts
+        .source_auction_house_fee_account
+        .to_account_info();
+    let rent_recipient = trade_state_rent_recipient(
+        &source_trade_state.try_borrow_data()?,
+        &[&wallet_info, &source_auction_house_fee_account_info],
+        &wallet_info,
+    )?;
+    let curr_lamp = source_trade_state.lamports();
+    **source_trade_state.lamports.borrow_mut() = 0;
+    **rent_recipient.lamports.borrow_mut() = rent_recipient
+        .lamports()
+        .checked_add(curr_lamp)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+    let source_ts_len = source_trade_state.data_len();
+    sol_memset(*source_trade_state.try_borrow_mut_data()?, 0, source_ts_len);
+    record_order_closed(
+        find_order_book_summary(
+            ctx.remaining_accounts,
+            source_auction_house,
+            &token_account.mint,
+        )?,
+        &source_auction_house.key(),
+        &token_account.mint,
+        OrderSide::Ask,
+        buyer_price,
+    )?;
+
+    let (source_listing_receipt_key, _) =
+        crate::pda::find_listing_receipt_address(&source_trade_state.key());
+    if let Some(source_listing_receipt) = ctx
+        .remaining_accounts
+        .iter()
+        .find(|info| *info.key == source_listing_receipt_key)
+    {
+        let mut receipt: Account<ListingReceipt> = Account::try_from(source_listing_receipt)?;
+        assert_keys_equal(receipt.trade_state, source_trade_state.key())?;
+        receipt.canceled_at = Some(Clock::get()?.unix_timestamp);
+        receipt.exit(ctx.program_id)?;
+    }
+
+    // List on the destination at the same price and size.
+    let ts_info = destination_trade_state.to_account_info();
+    if ts_info.data_is_empty() {
+        let wallet_key = wallet.key();
+        let destination_auction_house_key = destination_auction_house.key();
+        let token_account_key = token_account.key();
+        create_or_allocate_account_raw(
+            *ctx.program_id,
+            &ts_info,
+            &rent.to_account_info(),
+            system_program,
+            wallet,
+            TRADE_STATE_WITH_RENT_PAYER_SIZE,
+            &[],
+            &[
+                PREFIX.as_bytes(),
+                wallet_key.as_ref(),
+                destination_auction_house_key.as_ref(),
+                token_account_key.as_ref(),
+                destination_auction_house.treasury_mint.as_ref(),
+                token_account.mint.as_ref(),
+                &buyer_price.to_le_bytes(),
+                &token_size.to_le_bytes(),
+                &[trade_state_bump],
+            ],
+        )?;
+        write_trade_state(&ts_info, trade_state_bump, wallet.key)?;
+
+        record_order_opened(
+            find_order_book_summary(
+                ctx.remaining_accounts,
+                destination_auction_house,
+                &token_account.mint,
+            )?,
+            &destination_auction_house_key,
+            &token_account.mint,
+            OrderSide::Ask,
+            buyer_price,
+        )?;
+    }
+    ts_info.data.borrow_mut()[0] = trade_state_bump;
+
+    let receipt_info = listing_receipt.to_account_info();
+    if receipt_info.data_is_empty() {
+        let destination_trade_state_key = destination_trade_state.key();
+        create_or_allocate_account_raw(
+            *ctx.program_id,
+            &receipt_info,
+            &rent.to_account_info(),
+            system_program,
+            wallet,
+            LISTING_RECEIPT_SIZE,
+            &[],
+            &[
+                LISTING_RECEIPT_PREFIX.as_bytes(),
+                destination_trade_state_key.as_ref(),
+                &[receipt_bump],
+            ],
+        )?;
+    }
+
+    let receipt = ListingReceipt {
+        trade_state: destination_trade_state.key(),
+        bookkeeper: wallet.key(),
+        auction_house: destination_auction_house.key(),
+        seller: wallet.key(),
+        metadata: metadata.key(),
+        purchase_receipt: None,
+        price: buyer_price,
+        token_size,
+        bump: receipt_bump,
+        trade_state_bump,
+        created_at: Clock::get()?.unix_timestamp,
+        canceled_at: None,
+        sold_by_creator: is_verified_creator(&metadata.to_account_info(), &wallet.key())?,
+    };
+
+    receipt.try_serialize(&mut *receipt_info.try_borrow_mut_data()?)?;
+
+    emit_ah_event(
+        AhEventKind::MigrateListing,
+        destination_auction_house.key(),
+        Some(token_account.mint),
+        buyer_price,
+        vec![wallet.key()],
+    )?;
+
+    Ok(())
+}
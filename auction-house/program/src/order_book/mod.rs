@@ -0,0 +1,232 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::{
+    constants::*, errors::AuctionHouseError, pda::find_order_book_summary_address,
+    utils::emit_ah_event, AhEventKind, AuctionHouse, OrderBookSummary,
+};
+
+/// Side of the order book a trade state belongs to.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum OrderSide {
+    Ask,
+    Bid,
+}
+
+/// Accounts for the [`create_order_book_summary` handler](auction_house/fn.create_order_book_summary.html).
+#[derive(Accounts)]
+pub struct CreateOrderBookSummary<'info> {
+    /// Pays for the order book summary account.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Auction House instance PDA account, flagged as having order book summaries.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+
+    /// Token mint account the order book is summarized for.
+    pub token_mint: Account<'info, Mint>,
+
+    /// The order book summary PDA.
+    #[account(
+        init,
+        payer = payer,
+        space = ORDER_BOOK_SUMMARY_SIZE,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            ORDER_BOOK_SUMMARY.as_bytes(),
+            token_mint.key().as_ref()
+        ],
+        bump
+    )]
+    pub order_book_summary: Account<'info, OrderBookSummary>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Create the order book summary of `token_mint`, kept up to date by trades sent with it.
+pub fn create_order_book_summary<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreateOrderBookSummary<'info>>,
+) -> Result<()> {
+    let order_book_summary = &mut ctx.accounts.order_book_summary;
+
+    order_book_summary.auction_house = ctx.accounts.auction_house.key();
+    order_book_summary.token_mint = ctx.accounts.token_mint.key();
+    order_book_summary.best_ask = None;
+    order_book_summary.best_bid = None;
+    order_book_summary.open_asks = 0;
+    order_book_summary.open_bids = 0;
+    order_book_summary.best_ask_count = 0;
+    order_book_summary.best_bid_count = 0;
+    order_book_summary.bump = *ctx
+        .bumps
+        .get("order_book_summary")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+
+    // From now on every order of the house must be sent with the summary of its mint, if there is one.
+    ctx.accounts.auction_house.has_order_book_summaries = true;
+
+    emit_ah_event(
+        AhEventKind::CreateOrderBookSummary,
+        ctx.accounts.auction_house.key(),
+        Some(ctx.accounts.token_mint.key()),
+        0,
+        vec![ctx.accounts.payer.key()],
+    )?;
+
+    Ok(())
+}
+
+/// Order book summary of `token_mint` among `remaining_accounts`, looked up by address.
+///
+/// Once an Auction House has order book summaries, the summary address of the mint must be passed with every order
+/// opened or closed, whether a summary was created for the mint or not, so that no order escapes the counts.
+pub fn find_order_book_summary<'a, 'info>(
+    remaining_accounts: &'a [AccountInfo<'info>],
+    auction_house: &Account<AuctionHouse>,
+    token_mint: &Pubkey,
+) -> Result<Option<&'a AccountInfo<'info>>> {
+    if !auction_house.has_order_book_summaries {
+        return Ok(None);
+    }
+
+    let (order_book_summary_key, _) =
+        find_order_book_summary_address(&auction_house.key(), token_mint);
+    let order_book_summary_info = remaining_accounts
+        .iter()
+        .find(|info| *info.key == order_book_summary_key)
+        .ok_or(AuctionHouseError::OrderBookSummaryRequired)?;
+
+    if order_book_summary_info.data_is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(order_book_summary_info))
+}
+
+/// Count a newly opened order in the order book summary, if the mint has one.
+pub fn record_order_opened(
+    order_book_summary: Option<&AccountInfo>,
+    auction_house: &Pubkey,
+    token_mint: &Pubkey,
+    side: OrderSide,
+    price: u64,
+) -> Result<()> {
+    let order_book_summary_info = match order_book_summary {
+        Some(info) => info,
+        None => return Ok(()),
+    };
+    let mut summary = load_order_book_summary(order_book_summary_info, auction_house, token_mint)?;
+
+    // Auctioneer listings are priced at u64::MAX and do not set a best ask.
+    match side {
+        OrderSide::Ask => {
+            summary.open_asks = summary
+                .open_asks
+                .checked_add(1)
+                .ok_or(AuctionHouseError::NumericalOverflow)?;
+            if price != u64::MAX {
+                match summary.best_ask {
+                    Some(best) if best < price => {}
+                    Some(best) if best == price => {
+                        summary.best_ask_count = summary
+                            .best_ask_count
+                            .checked_add(1)
+                            .ok_or(AuctionHouseError::NumericalOverflow)?;
+                    }
+                    _ => {
+                        summary.best_ask = Some(price);
+                        summary.best_ask_count = 1;
+                    }
+                }
+            }
+        }
+        OrderSide::Bid => {
+            summary.open_bids = summary
+                .open_bids
+                .checked_add(1)
+                .ok_or(AuctionHouseError::NumericalOverflow)?;
+            match summary.best_bid {
+                Some(best) if best > price => {}
+                Some(best) if best == price => {
+                    summary.best_bid_count = summary
+                        .best_bid_count
+                        .checked_add(1)
+                        .ok_or(AuctionHouseError::NumericalOverflow)?;
+                }
+                _ => {
+                    summary.best_bid = Some(price);
+                    summary.best_bid_count = 1;
+                }
+            }
+        }
+    }
+
+    summary.exit(&crate::id())
+}
+
+/// Remove a closed order from the order book summary, if the mint has one.
+///
+/// The summary counts the orders at the best price only, so the best price is cleared once the last of them closes,
+/// until the next order on that side is opened. Orders opened before the summary was created are not counted, so
+/// closing them leaves the counts at zero.
+pub fn record_order_closed(
+    order_book_summary: Option<&AccountInfo>,
+    auction_house: &Pubkey,
+    token_mint: &Pubkey,
+    side: OrderSide,
+    price: u64,
+) -> Result<()> {
+    let order_book_summary_info = match order_book_summary {
+        Some(info) => info,
+        None => return Ok(()),
+    };
+    let mut summary = load_order_book_summary(order_book_summary_info, auction_house, token_mint)?;
+
+    match side {
+        OrderSide::Ask => {
+            summary.open_asks = summary.open_asks.saturating_sub(1);
+            if summary.best_ask == Some(price) {
+                summary.best_ask_count = summary.best_ask_count.saturating_sub(1);
+            }
+            if summary.open_asks == 0 || summary.best_ask_count == 0 {
+                summary.best_ask = None;
+                summary.best_ask_count = 0;
+            }
+        }
+        OrderSide::Bid => {
+            summary.open_bids = summary.open_bids.saturating_sub(1);
+            if summary.best_bid == Some(price) {
+                summary.best_bid_count = summary.best_bid_count.saturating_sub(1);
+            }
+            if summary.open_bids == 0 || summary.best_bid_count == 0 {
+                summary.best_bid = None;
+                summary.best_bid_count = 0;
+            }
+        }
+    }
+
+    summary.exit(&crate::id())
+}
+
+fn load_order_book_summary<'info>(
+    order_book_summary_info: &AccountInfo<'info>,
+    auction_house: &Pubkey,
+    token_mint: &Pubkey,
+) -> Result<Account<'info, OrderBookSummary>> {
+    let summary: Account<'info, OrderBookSummary> = Account::try_from(order_book_summary_info)?;
+    if summary.auction_house != *auction_house || summary.token_mint != *token_mint {
+        return Err(AuctionHouseError::InvalidOrderBookSummary.into());
+    }
+
+    Ok(summary)
+}
@@ -138,6 +138,241 @@ pub fn find_auctioneer_pda(auction_house: &Pubkey, auctioneer_authority: &Pubkey
     )
 }
 
+pub fn find_session_key_address(
+    auction_house: &Pubkey,
+    wallet: &Pubkey,
+    session_key: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            SESSION_KEY.as_bytes(),
+            auction_house.as_ref(),
+            wallet.as_ref(),
+            session_key.as_ref(),
+        ],
+        &id(),
+    )
+}
+
+pub fn find_revenue_share_address(auction_house: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[REVENUE_SHARE.as_bytes(), auction_house.as_ref()], &id())
+}
+
+pub fn find_revenue_share_snapshot_address(auction_house: &Pubkey, index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            REVENUE_SHARE.as_bytes(),
+            auction_house.as_ref(),
+            &index.to_le_bytes(),
+        ],
+        &id(),
+    )
+}
+
+pub fn find_pending_update_address(auction_house: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            auction_house.as_ref(),
+            PENDING_UPDATE.as_bytes(),
+        ],
+        &id(),
+    )
+}
+
+pub fn find_linked_wallet_address(auction_house: &Pubkey, wallet: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            auction_house.as_ref(),
+            LINKED_WALLET.as_bytes(),
+            wallet.as_ref(),
+        ],
+        &id(),
+    )
+}
+
+pub fn find_royalty_mandate_address(creator: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            ROYALTY_MANDATE.as_bytes(),
+            creator.as_ref(),
+        ],
+        &id(),
+    )
+}
+
+pub fn find_proceeds_route_address(seller: &Pubkey, seller_trade_state: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            PROCEEDS_ROUTE.as_bytes(),
+            seller.as_ref(),
+            seller_trade_state.as_ref(),
+        ],
+        &id(),
+    )
+}
+
+pub fn find_compliance_config_address(auction_house: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            auction_house.as_ref(),
+            COMPLIANCE_CONFIG.as_bytes(),
+        ],
+        &id(),
+    )
+}
+
+pub fn find_wallet_volume_address(auction_house: &Pubkey, wallet: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            auction_house.as_ref(),
+            WALLET_VOLUME.as_bytes(),
+            wallet.as_ref(),
+        ],
+        &id(),
+    )
+}
+
+pub fn find_free_sale_counter_address(auction_house: &Pubkey, seller: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            auction_house.as_ref(),
+            FREE_SALE_COUNTER.as_bytes(),
+            seller.as_ref(),
+        ],
+        &id(),
+    )
+}
+
+pub fn find_epoch_report_address(auction_house: &Pubkey, epoch: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            auction_house.as_ref(),
+            EPOCH_REPORT.as_bytes(),
+            &epoch.to_le_bytes(),
+        ],
+        &id(),
+    )
+}
+
+pub fn find_pending_listing_address(
+    wallet: &Pubkey,
+    auction_house: &Pubkey,
+    token_account: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            wallet.as_ref(),
+            auction_house.as_ref(),
+            PENDING_LISTING.as_bytes(),
+            token_account.as_ref(),
+        ],
+        &id(),
+    )
+}
+
+pub fn find_bundle_listing_address(
+    seller: &Pubkey,
+    auction_house: &Pubkey,
+    bundle_id: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            seller.as_ref(),
+            auction_house.as_ref(),
+            BUNDLE_LISTING.as_bytes(),
+            &bundle_id.to_le_bytes(),
+        ],
+        &id(),
+    )
+}
+
+pub fn find_layaway_address(auction_house: &Pubkey, seller_trade_state: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            auction_house.as_ref(),
+            LAYAWAY.as_bytes(),
+            seller_trade_state.as_ref(),
+        ],
+        &id(),
+    )
+}
+
+pub fn find_layaway_token_account_address(layaway: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PREFIX.as_bytes(), layaway.as_ref(), LAYAWAY.as_bytes()],
+        &id(),
+    )
+}
+
+pub fn find_layaway_escrow_address(auction_house: &Pubkey, layaway: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PREFIX.as_bytes(), auction_house.as_ref(), layaway.as_ref()],
+        &id(),
+    )
+}
+
+pub fn find_signed_listing_nonce_address(
+    auction_house: &Pubkey,
+    wallet: &Pubkey,
+    nonce: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            SIGNED_LISTING.as_bytes(),
+            auction_house.as_ref(),
+            wallet.as_ref(),
+            &nonce.to_le_bytes(),
+        ],
+        &id(),
+    )
+}
+
+pub fn find_swap_proposal_address(
+    proposer: &Pubkey,
+    auction_house: &Pubkey,
+    offered_mint: &Pubkey,
+    target_mint: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            proposer.as_ref(),
+            auction_house.as_ref(),
+            SWAP_PROPOSAL.as_bytes(),
+            offered_mint.as_ref(),
+            target_mint.as_ref(),
+        ],
+        &id(),
+    )
+}
+
+pub fn find_order_book_summary_address(
+    auction_house: &Pubkey,
+    token_mint: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            auction_house.as_ref(),
+            ORDER_BOOK_SUMMARY.as_bytes(),
+            token_mint.as_ref(),
+        ],
+        &id(),
+    )
+}
+
 pub fn find_auctioneer_trade_state_address(
     wallet: &Pubkey,
     auction_house: &Pubkey,
@@ -160,3 +395,30 @@ pub fn find_auctioneer_trade_state_address(
         &id(),
     )
 }
+
+pub fn find_deferred_settlement_address(
+    auction_house: &Pubkey,
+    purchase_receipt: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            auction_house.as_ref(),
+            DEFERRED_SETTLEMENT.as_bytes(),
+            purchase_receipt.as_ref(),
+        ],
+        &id(),
+    )
+}
+
+pub fn find_last_sale_address(auction_house: &Pubkey, token_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            auction_house.as_ref(),
+            LAST_SALE.as_bytes(),
+            token_mint.as_ref(),
+        ],
+        &id(),
+    )
+}
@@ -0,0 +1,259 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+use crate::{
+    constants::*, errors::AuctionHouseError, sell::*, utils::*, AhEventKind, AuctionHouse,
+    PendingListing,
+};
+
+/// Accounts for the [`initiate_listing` handler](auction_house/fn.initiate_listing.html).
+#[derive(Accounts)]
+#[instruction(buyer_price: u64, token_size: u64)]
+pub struct InitiateListing<'info> {
+    /// Seller wallet, pays for the pending listing.
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    /// SPL token account containing the token for sale.
+    pub token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+
+    /// Pending listing PDA recording the intended price and size.
+    #[account(
+        init,
+        payer = wallet,
+        space = PENDING_LISTING_SIZE,
+        seeds = [
+            PREFIX.as_bytes(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            PENDING_LISTING.as_bytes(),
+            token_account.key().as_ref()
+        ],
+        bump
+    )]
+    pub pending_listing: Account<'info, PendingListing>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Record the intended `buyer_price` and `token_size` of a listing so it can be finalized in a later transaction.
+pub fn initiate_listing<'info>(
+    ctx: Context<'_, '_, '_, 'info, InitiateListing<'info>>,
+    buyer_price: u64,
+    token_size: u64,
+) -> Result<()> {
+    let wallet = &ctx.accounts.wallet;
+    let token_account = &ctx.accounts.token_account;
+    let auction_house = &ctx.accounts.auction_house;
+
+    assert_not_paused(auction_house, PAUSE_SELL)?;
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    assert_is_ata(
+        &token_account.to_account_info(),
+        &wallet.key(),
+        &token_account.mint,
+    )?;
+
+    if token_size > token_account.amount {
+        return Err(AuctionHouseError::InvalidTokenAmount.into());
+    }
+
+    let pending_listing = &mut ctx.accounts.pending_listing;
+    pending_listing.auction_house = auction_house.key();
+    pending_listing.wallet = wallet.key();
+    pending_listing.token_account = token_account.key();
+    pending_listing.buyer_price = buyer_price;
+    pending_listing.token_size = token_size;
+    pending_listing.bump = *ctx
+        .bumps
+        .get("pending_listing")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+
+    emit_ah_event(
+        AhEventKind::InitiateListing,
+        auction_house.key(),
+        Some(token_account.mint),
+        buyer_price,
+        vec![wallet.key()],
+    )?;
+
+    Ok(())
+}
+
+/// Accounts for the [`finalize_listing` handler](auction_house/fn.finalize_listing.html).
+#[derive(Accounts, Clone)]
+#[instruction(
+    trade_state_bump: u8,
+    free_trade_state_bump: u8,
+    program_as_signer_bump: u8
+)]
+pub struct FinalizeListing<'info> {
+    /// CHECK: Validated as a signer in sell_logic.
+    /// Seller wallet, receives the pending listing lamports.
+    #[account(mut)]
+    pub wallet: UncheckedAccount<'info>,
+
+    /// SPL token account containing token for sale.
+    #[account(mut)]
+    pub token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Validated by assert_metadata_valid.
+    /// Metaplex metadata account decorating SPL mint account.
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Verified through CPI
+    /// Auction House authority account.
+    pub authority: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority,
+        has_one=auction_house_fee_account
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance fee account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump=auction_house.fee_payer_bump
+    )]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// Pending listing PDA created by `initiate_listing`, closed once the listing is finalized.
+    #[account(
+        mut,
+        close = wallet,
+        seeds = [
+            PREFIX.as_bytes(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            PENDING_LISTING.as_bytes(),
+            token_account.key().as_ref()
+        ],
+        bump=pending_listing.bump,
+        has_one=wallet,
+        has_one=auction_house,
+        has_one=token_account
+    )]
+    pub pending_listing: Box<Account<'info, PendingListing>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Seller trade state PDA account encoding the sell order.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_account.mint.as_ref(),
+            &pending_listing.buyer_price.to_le_bytes(),
+            &pending_listing.token_size.to_le_bytes()
+        ],
+        bump=trade_state_bump
+    )]
+    pub seller_trade_state: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Free seller trade state PDA account encoding a free sell order.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_account.mint.as_ref(),
+            &0u64.to_le_bytes(),
+            &pending_listing.token_size.to_le_bytes()
+        ],
+        bump=free_trade_state_bump
+    )]
+    pub free_seller_trade_state: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(seeds=[PREFIX.as_bytes(), SIGNER.as_bytes()], bump=program_as_signer_bump)]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> From<FinalizeListing<'info>> for Sell<'info> {
+    fn from(a: FinalizeListing<'info>) -> Sell<'info> {
+        Sell {
+            wallet: a.wallet,
+            token_account: a.token_account,
+            metadata: a.metadata,
+            authority: a.authority,
+            auction_house: *a.auction_house,
+            auction_house_fee_account: a.auction_house_fee_account,
+            seller_trade_state: a.seller_trade_state,
+            free_seller_trade_state: a.free_seller_trade_state,
+            token_program: a.token_program,
+            system_program: a.system_program,
+            program_as_signer: a.program_as_signer,
+            rent: a.rent,
+        }
+    }
+}
+
+/// Approve the program as the token delegate and create the `seller_trade_state` using the price and size recorded by `initiate_listing`.
+pub fn finalize_listing<'info>(
+    ctx: Context<'_, '_, '_, 'info, FinalizeListing<'info>>,
+    trade_state_bump: u8,
+    free_trade_state_bump: u8,
+    program_as_signer_bump: u8,
+) -> Result<()> {
+    if ctx.accounts.auction_house.has_auctioneer {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    let buyer_price = ctx.accounts.pending_listing.buyer_price;
+    let token_size = ctx.accounts.pending_listing.token_size;
+    let mut accounts: Sell<'info> = (*ctx.accounts).clone().into();
+
+    sell_logic(
+        &mut accounts,
+        ctx.program_id,
+        ctx.remaining_accounts,
+        trade_state_bump,
+        free_trade_state_bump,
+        program_as_signer_bump,
+        buyer_price,
+        token_size,
+        None,
+    )
+}
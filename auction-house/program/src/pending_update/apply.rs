@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::*, errors::AuctionHouseError, utils::emit_ah_event, AhEventKind, AuctionHouse,
+    PendingUpdate,
+};
+
+/// Accounts for the [`apply_pending_update` handler](auction_house/fn.apply_pending_update.html).
+#[derive(Accounts)]
+pub struct ApplyPendingUpdate<'info> {
+    /// Auction House instance PDA account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+
+    /// The pending update PDA, closed back to the payer that queued it.
+    #[account(
+        mut,
+        close = payer,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            PENDING_UPDATE.as_bytes()
+        ],
+        bump=pending_update.bump,
+        has_one=auction_house,
+        has_one=payer
+    )]
+    pub pending_update: Account<'info, PendingUpdate>,
+
+    /// CHECK: Validated by the has_one constraint on the pending update.
+    /// Account that paid for the pending update PDA.
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+}
+
+/// Apply a queued authority or treasury withdrawal destination change once its timelock elapsed. Callable by anyone.
+pub fn apply_pending_update<'info>(
+    ctx: Context<'_, '_, '_, 'info, ApplyPendingUpdate<'info>>,
+) -> Result<()> {
+    let auction_house = &mut ctx.accounts.auction_house;
+    let pending_update = &ctx.accounts.pending_update;
+
+    if Clock::get()?.slot < pending_update.executable_slot {
+        return Err(AuctionHouseError::PendingUpdateNotExecutable.into());
+    }
+
+    auction_house.authority = pending_update.authority;
+    auction_house.treasury_withdrawal_destination = pending_update.treasury_withdrawal_destination;
+
+    emit_ah_event(
+        AhEventKind::ApplyPendingUpdate,
+        auction_house.key(),
+        None,
+        0,
+        vec![
+            auction_house.authority,
+            auction_house.treasury_withdrawal_destination,
+        ],
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::*, utils::emit_ah_event, AhEventKind, AuctionHouse, PendingUpdate};
+
+/// Accounts for the [`cancel_pending_update` handler](auction_house/fn.cancel_pending_update.html).
+#[derive(Accounts)]
+pub struct CancelPendingUpdate<'info> {
+    /// Authority key for the Auction House.
+    pub authority: Signer<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+
+    /// The pending update PDA, closed back to the payer that queued it.
+    #[account(
+        mut,
+        close = payer,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            PENDING_UPDATE.as_bytes()
+        ],
+        bump=pending_update.bump,
+        has_one=auction_house,
+        has_one=payer
+    )]
+    pub pending_update: Account<'info, PendingUpdate>,
+
+    /// CHECK: Validated by the has_one constraint on the pending update.
+    /// Account that paid for the pending update PDA.
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+}
+
+/// Cancel a queued authority or treasury withdrawal destination change before it is applied.
+pub fn cancel_pending_update<'info>(
+    ctx: Context<'_, '_, '_, 'info, CancelPendingUpdate<'info>>,
+) -> Result<()> {
+    emit_ah_event(
+        AhEventKind::CancelPendingUpdate,
+        ctx.accounts.auction_house.key(),
+        None,
+        0,
+        vec![ctx.accounts.authority.key()],
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,61 @@
+pub mod apply;
+pub mod cancel;
+pub use apply::*;
+pub use cancel::*;
+
+use anchor_lang::prelude::*;
+
+use crate::{constants::*, errors::AuctionHouseError, utils::*, PendingUpdate};
+
+/// Queue a change of the Auction House authority or treasury withdrawal destination, executable after `PENDING_UPDATE_DELAY_SLOTS`.
+pub fn queue_pending_update<'info>(
+    pending_update: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    rent: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    auction_house: Pubkey,
+    pending_update_bump: u8,
+    authority: Pubkey,
+    treasury_withdrawal_destination: Pubkey,
+) -> Result<()> {
+    // Only one change can be queued at a time, it has to be applied or canceled first.
+    if !pending_update.data_is_empty() {
+        return Err(AuctionHouseError::PendingUpdateAlreadyQueued.into());
+    }
+
+    let pending_update_seeds = [
+        PREFIX.as_bytes(),
+        auction_house.as_ref(),
+        PENDING_UPDATE.as_bytes(),
+        &[pending_update_bump],
+    ];
+
+    create_or_allocate_account_raw(
+        crate::id(),
+        pending_update,
+        rent,
+        system_program,
+        payer,
+        PENDING_UPDATE_SIZE,
+        &[],
+        &pending_update_seeds,
+    )?;
+
+    let executable_slot = Clock::get()?
+        .slot
+        .checked_add(PENDING_UPDATE_DELAY_SLOTS)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+    let update = PendingUpdate {
+        auction_house,
+        payer: payer.key(),
+        authority,
+        treasury_withdrawal_destination,
+        executable_slot,
+        bump: pending_update_bump,
+    };
+
+    update.try_serialize(&mut *pending_update.try_borrow_mut_data()?)?;
+
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::*, errors::AuctionHouseError, ProceedsRoute};
+
+/// Accounts for the [`set_proceeds_route` handler](auction_house/fn.set_proceeds_route.html).
+#[derive(Accounts)]
+pub struct SetProceedsRoute<'info> {
+    /// Seller of the listing, pays for the route.
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    /// CHECK: Validated as a live listing in set_proceeds_route.
+    /// Seller trade state of the listing created by `sell`.
+    pub seller_trade_state: UncheckedAccount<'info>,
+
+    /// CHECK: Any account chosen by the seller can receive its proceeds.
+    /// Destination of the sale proceeds, e.g. a split program wallet or its token account for SPL treasuries.
+    pub destination: UncheckedAccount<'info>,
+
+    /// Proceeds route PDA of the listing.
+    #[account(
+        init,
+        payer = seller,
+        space = PROCEEDS_ROUTE_SIZE,
+        seeds = [
+            PREFIX.as_bytes(),
+            PROCEEDS_ROUTE.as_bytes(),
+            seller.key().as_ref(),
+            seller_trade_state.key().as_ref()
+        ],
+        bump
+    )]
+    pub proceeds_route: Account<'info, ProceedsRoute>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Route the proceeds of the listing under `seller_trade_state` to `destination` instead of the seller wallet or ATA.
+pub fn set_proceeds_route<'info>(
+    ctx: Context<'_, '_, '_, 'info, SetProceedsRoute<'info>>,
+) -> Result<()> {
+    let seller_trade_state = &ctx.accounts.seller_trade_state;
+    if seller_trade_state.owner != &crate::id() || seller_trade_state.data_is_empty() {
+        return Err(AuctionHouseError::InvalidProceedsRoute.into());
+    }
+
+    let proceeds_route = &mut ctx.accounts.proceeds_route;
+
+    proceeds_route.seller = ctx.accounts.seller.key();
+    proceeds_route.seller_trade_state = seller_trade_state.key();
+    proceeds_route.destination = ctx.accounts.destination.key();
+    proceeds_route.bump = *ctx
+        .bumps
+        .get("proceeds_route")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+
+    Ok(())
+}
+
+/// Accounts for the [`revoke_proceeds_route` handler](auction_house/fn.revoke_proceeds_route.html).
+#[derive(Accounts)]
+pub struct RevokeProceedsRoute<'info> {
+    /// Seller that set the route, receives its lamports back.
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    /// Proceeds route PDA of the listing, closed back to the seller.
+    #[account(
+        mut,
+        close = seller,
+        seeds = [
+            PREFIX.as_bytes(),
+            PROCEEDS_ROUTE.as_bytes(),
+            seller.key().as_ref(),
+            proceeds_route.seller_trade_state.as_ref()
+        ],
+        bump=proceeds_route.bump,
+        has_one=seller
+    )]
+    pub proceeds_route: Account<'info, ProceedsRoute>,
+}
+
+/// Close the proceeds route of a listing, e.g. once it sold or was cancelled.
+pub fn revoke_proceeds_route<'info>(
+    _ctx: Context<'_, '_, '_, 'info, RevokeProceedsRoute<'info>>,
+) -> Result<()> {
+    Ok(())
+}
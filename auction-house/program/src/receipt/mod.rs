@@ -5,6 +5,7 @@ use crate::{
     id,
     instruction::{Buy, ExecuteSale, Sell},
     utils::*,
+    AhEventKind,
 };
 use anchor_lang::{prelude::*, AnchorDeserialize, AnchorSerialize};
 use solana_program::{sysvar, sysvar::instructions::get_instruction_relative};
@@ -54,7 +55,8 @@ pub const LISTING_RECEIPT_SIZE: usize = 8 + //key
 1 + // bump
 1 + // trade_state_bump
 8 + // created_at
-1 + 8; // canceled_at;
+1 + 8 + // canceled_at
+1; // sold_by_creator
 
 /// Receipt for a listing transaction.
 #[account]
@@ -71,6 +73,8 @@ pub struct ListingReceipt {
     pub trade_state_bump: u8,
     pub created_at: i64,
     pub canceled_at: Option<i64>,
+    /// The seller is a verified creator of the listed NFT.
+    pub sold_by_creator: bool,
 }
 
 pub const PURCHASE_RECEIPT_SIZE: usize = 8 + //key
@@ -82,7 +86,10 @@ pub const PURCHASE_RECEIPT_SIZE: usize = 8 + //key
 8 + // token_size
 8 + // price
 1 + // bump
-8; // created_at
+8 + // created_at
+1 + // self_trade
+1 + // sold_by_creator
+1; // disputed
 
 /// Receipt for a purchase transaction.
 #[account]
@@ -96,6 +103,11 @@ pub struct PurchaseReceipt {
     pub price: u64,
     pub bump: u8,
     pub created_at: i64,
+    pub self_trade: bool,
+    /// The seller is a verified creator of the purchased NFT.
+    pub sold_by_creator: bool,
+    /// The Auction House authority flagged the purchase within its dispute window.
+    pub disputed: bool,
 }
 
 /// Accounts for the [`print_listing_receipt` hanlder](fn.print_listing_receipt.html).
@@ -122,7 +134,8 @@ pub struct PrintListingReceipt<'info> {
 ///
 /// The previous instruction is checked to ensure that it is a "Listing" type to
 /// match the receipt type being created. Passing in an empty account results in the PDA
-/// being created; an existing account will be written over.
+/// being created; an existing account will be written over. The listed NFT metadata account
+/// may follow in the remaining accounts to flag listings by one of its verified creators.
 pub fn print_listing_receipt<'info>(
     ctx: Context<'_, '_, '_, 'info, PrintListingReceipt<'info>>,
     receipt_bump: u8,
@@ -180,6 +193,14 @@ pub fn print_listing_receipt<'info>(
         )?;
     }
 
+    let sold_by_creator = match ctx.remaining_accounts.first() {
+        Some(metadata_info) => {
+            assert_keys_equal(metadata_info.key(), metadata.pubkey)?;
+            is_verified_creator(metadata_info, &wallet.pubkey)?
+        }
+        None => false,
+    };
+
     let receipt = ListingReceipt {
         trade_state: seller_trade_state.pubkey,
         bookkeeper: bookkeeper_account.key(),
@@ -193,10 +214,19 @@ pub fn print_listing_receipt<'info>(
         trade_state_bump: sell_data.trade_state_bump,
         created_at: clock.unix_timestamp,
         canceled_at: None,
+        sold_by_creator,
     };
 
     receipt.try_serialize(&mut *receipt_account.try_borrow_mut_data()?)?;
 
+    emit_ah_event(
+        AhEventKind::PrintListingReceipt,
+        receipt.auction_house,
+        None,
+        receipt.price,
+        vec![receipt.seller, receipt.bookkeeper],
+    )?;
+
     Ok(())
 }
 
@@ -250,6 +280,14 @@ pub fn cancel_listing_receipt<'info>(
 
     receipt.try_serialize(&mut *receipt_data)?;
 
+    emit_ah_event(
+        AhEventKind::CancelListingReceipt,
+        receipt.auction_house,
+        None,
+        receipt.price,
+        vec![receipt.seller],
+    )?;
+
     Ok(())
 }
 
@@ -363,6 +401,14 @@ pub fn print_bid_receipt<'info>(
 
     receipt.try_serialize(&mut *receipt_account.try_borrow_mut_data()?)?;
 
+    emit_ah_event(
+        AhEventKind::PrintBidReceipt,
+        receipt.auction_house,
+        None,
+        receipt.price,
+        vec![receipt.buyer, receipt.bookkeeper],
+    )?;
+
     Ok(())
 }
 
@@ -416,6 +462,14 @@ pub fn cancel_bid_receipt<'info>(
 
     receipt.try_serialize(&mut *receipt_data)?;
 
+    emit_ah_event(
+        AhEventKind::CancelBidReceipt,
+        receipt.auction_house,
+        None,
+        receipt.price,
+        vec![receipt.buyer],
+    )?;
+
     Ok(())
 }
 
@@ -538,6 +592,11 @@ pub fn print_purchase_receipt<'info>(
         )?;
     }
 
+    let mut listing_receipt_data = listing_receipt_info.try_borrow_mut_data()?;
+    let mut listing_receipt_data_slice: &[u8] = &listing_receipt_data;
+
+    let mut listing_receipt = ListingReceipt::try_deserialize(&mut listing_receipt_data_slice)?;
+
     let purchase = PurchaseReceipt {
         buyer: buyer.pubkey,
         seller: seller.pubkey,
@@ -548,15 +607,13 @@ pub fn print_purchase_receipt<'info>(
         price: execute_sale_data.buyer_price,
         token_size: execute_sale_data.token_size,
         created_at: timestamp,
+        self_trade: buyer.pubkey == seller.pubkey,
+        sold_by_creator: listing_receipt.sold_by_creator,
+        disputed: false,
     };
 
     purchase.try_serialize(&mut *purchase_receipt_account.try_borrow_mut_data()?)?;
 
-    let mut listing_receipt_data = listing_receipt_info.try_borrow_mut_data()?;
-    let mut listing_receipt_data_slice: &[u8] = &listing_receipt_data;
-
-    let mut listing_receipt = ListingReceipt::try_deserialize(&mut listing_receipt_data_slice)?;
-
     listing_receipt.purchase_receipt = Some(purchase_receipt_account.key());
 
     listing_receipt.try_serialize(&mut *listing_receipt_data)?;
@@ -570,5 +627,62 @@ pub fn print_purchase_receipt<'info>(
 
     bid_receipt.try_serialize(&mut *bid_receipt_data)?;
 
+    emit_ah_event(
+        AhEventKind::PrintPurchaseReceipt,
+        purchase.auction_house,
+        None,
+        purchase.price,
+        vec![purchase.buyer, purchase.seller, purchase.bookkeeper],
+    )?;
+
+    Ok(())
+}
+
+/// Create or overwrite the Purchase Receipt for a sale from within `execute_sale` when the
+/// Auction House has `auto_receipts` enabled. Rent for a new receipt is paid by the Auction House fee account.
+#[allow(clippy::too_many_arguments)]
+pub fn print_purchase_receipt_from_sale<'a>(
+    purchase_receipt_info: &AccountInfo<'a>,
+    auction_house_fee_account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    rent: &AccountInfo<'a>,
+    fee_payer_seeds: &[&[u8]],
+    seller_trade_state: &Pubkey,
+    buyer_trade_state: &Pubkey,
+    mut purchase: PurchaseReceipt,
+) -> Result<()> {
+    let purchase_receipt_bump = assert_derivation(
+        &id(),
+        purchase_receipt_info,
+        &[
+            PURCHASE_RECEIPT_PREFIX.as_ref(),
+            seller_trade_state.as_ref(),
+            buyer_trade_state.as_ref(),
+        ],
+    )?;
+
+    if purchase_receipt_info.data_is_empty() {
+        let purchase_receipt_seeds = [
+            PURCHASE_RECEIPT_PREFIX.as_bytes(),
+            seller_trade_state.as_ref(),
+            buyer_trade_state.as_ref(),
+            &[purchase_receipt_bump],
+        ];
+
+        create_or_allocate_account_raw(
+            id(),
+            purchase_receipt_info,
+            rent,
+            system_program,
+            auction_house_fee_account,
+            PURCHASE_RECEIPT_SIZE,
+            fee_payer_seeds,
+            &purchase_receipt_seeds,
+        )?;
+    }
+
+    purchase.bump = purchase_receipt_bump;
+    purchase.try_serialize(&mut *purchase_receipt_info.try_borrow_mut_data()?)?;
+
     Ok(())
 }
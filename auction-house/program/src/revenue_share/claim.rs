@@ -0,0 +1,190 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{keccak, program::invoke_signed},
+};
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+use crate::{
+    constants::*, errors::AuctionHouseError, utils::*, AhEventKind, AuctionHouse, RevenueShare,
+    RevenueShareSnapshot,
+};
+
+use super::verify_revenue_share_proof;
+
+/// Accounts for the [`claim_revenue_share` handler](auction_house/fn.claim_revenue_share.html).
+#[derive(Accounts)]
+#[instruction(snapshot_index: u64)]
+pub struct ClaimRevenueShare<'info> {
+    /// Holder of the governance token account at the snapshot slot.
+    #[account(mut)]
+    pub claimant: Signer<'info>,
+
+    /// Governance token account presented by the holder.
+    #[account(
+        constraint = governance_token_account.owner == claimant.key(),
+        constraint = governance_token_account.mint == revenue_share.governance_mint
+    )]
+    pub governance_token_account: Account<'info, TokenAccount>,
+
+    /// Treasury mint account, either native SOL mint or a SPL token mint.
+    pub treasury_mint: Account<'info, Mint>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            treasury_mint.key().as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=treasury_mint
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+
+    /// The revenue share PDA.
+    #[account(
+        mut,
+        seeds = [REVENUE_SHARE.as_bytes(), auction_house.key().as_ref()],
+        bump=revenue_share.bump,
+        has_one=auction_house
+    )]
+    pub revenue_share: Account<'info, RevenueShare>,
+
+    /// The snapshot PDA being claimed from.
+    #[account(
+        mut,
+        seeds = [
+            REVENUE_SHARE.as_bytes(),
+            auction_house.key().as_ref(),
+            &snapshot_index.to_le_bytes()
+        ],
+        bump=snapshot.bump,
+        has_one=revenue_share
+    )]
+    pub snapshot: Box<Account<'info, RevenueShareSnapshot>>,
+
+    /// CHECK: Validated in claim_revenue_share.
+    /// Associated token account of the revenue share PDA for SPL treasuries, unused for native SOL.
+    #[account(mut)]
+    pub revenue_share_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in claim_revenue_share.
+    /// Claimant wallet for native SOL, otherwise the claimant associated token account of the treasury mint.
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Claim the pro-rata part of a revenue share snapshot for the governance token account `balance` at the snapshot slot.
+pub fn claim_revenue_share<'info>(
+    ctx: Context<'_, '_, '_, 'info, ClaimRevenueShare<'info>>,
+    _snapshot_index: u64,
+    index: u64,
+    balance: u64,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    let claimant = &ctx.accounts.claimant;
+    let governance_token_account = &ctx.accounts.governance_token_account;
+    let treasury_mint = &ctx.accounts.treasury_mint;
+    let auction_house = &ctx.accounts.auction_house;
+    let revenue_share = &ctx.accounts.revenue_share;
+    let snapshot = &mut ctx.accounts.snapshot;
+    let revenue_share_token_account = &ctx.accounts.revenue_share_token_account;
+    let destination = &ctx.accounts.destination;
+    let token_program = &ctx.accounts.token_program;
+
+    let byte_index = (index / 8) as usize;
+    let bit_mask = 1u8 << (index % 8);
+    if byte_index >= snapshot.claimed_bitmap.len() {
+        return Err(AuctionHouseError::RevenueShareClaimIndexOutOfRange.into());
+    }
+    if snapshot.claimed_bitmap[byte_index] & bit_mask != 0 {
+        return Err(AuctionHouseError::RevenueShareAlreadyClaimed.into());
+    }
+
+    let leaf = keccak::hashv(&[
+        &index.to_le_bytes(),
+        governance_token_account.key().as_ref(),
+        &balance.to_le_bytes(),
+    ])
+    .0;
+    if !verify_revenue_share_proof(&proof, snapshot.merkle_root, leaf) {
+        return Err(AuctionHouseError::InvalidRevenueShareProof.into());
+    }
+
+    snapshot.claimed_bitmap[byte_index] |= bit_mask;
+
+    let amount = (balance as u128)
+        .checked_mul(snapshot.amount as u128)
+        .ok_or(AuctionHouseError::NumericalOverflow)?
+        .checked_div(snapshot.supply as u128)
+        .ok_or(AuctionHouseError::NumericalOverflow)? as u64;
+    snapshot.claimed = snapshot
+        .claimed
+        .checked_add(amount)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+    if snapshot.claimed > snapshot.amount {
+        return Err(AuctionHouseError::NumericalOverflow.into());
+    }
+
+    let is_native = treasury_mint.key() == spl_token::native_mint::id();
+    if is_native {
+        assert_keys_equal(claimant.key(), destination.key())?;
+        let revenue_share_info = revenue_share.to_account_info();
+        **revenue_share_info.lamports.borrow_mut() = revenue_share_info
+            .lamports()
+            .checked_sub(amount)
+            .ok_or(AuctionHouseError::NumericalOverflow)?;
+        **destination.lamports.borrow_mut() = destination
+            .lamports()
+            .checked_add(amount)
+            .ok_or(AuctionHouseError::NumericalOverflow)?;
+    } else {
+        assert_is_ata(
+            &revenue_share_token_account.to_account_info(),
+            &revenue_share.key(),
+            &treasury_mint.key(),
+        )?;
+        assert_is_ata(
+            &destination.to_account_info(),
+            &claimant.key(),
+            &treasury_mint.key(),
+        )?;
+
+        let ah_key = auction_house.key();
+        let revenue_share_seeds = [
+            REVENUE_SHARE.as_bytes(),
+            ah_key.as_ref(),
+            &[revenue_share.bump],
+        ];
+        invoke_signed(
+            &spl_token::instruction::transfer(
+                token_program.key,
+                &revenue_share_token_account.key(),
+                &destination.key(),
+                &revenue_share.key(),
+                &[],
+                amount,
+            )?,
+            &[
+                revenue_share_token_account.to_account_info(),
+                destination.to_account_info(),
+                token_program.to_account_info(),
+                revenue_share.to_account_info(),
+            ],
+            &[&revenue_share_seeds],
+        )?;
+    }
+
+    emit_ah_event(
+        AhEventKind::ClaimRevenueShare,
+        auction_house.key(),
+        Some(revenue_share.governance_mint),
+        amount,
+        vec![claimant.key()],
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,116 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{Mint, Token},
+};
+
+use crate::{
+    constants::*, errors::AuctionHouseError, utils::*, AhEventKind, AuctionHouse, RevenueShare,
+};
+
+/// Accounts for the [`create_revenue_share` handler](auction_house/fn.create_revenue_share.html).
+#[derive(Accounts)]
+pub struct CreateRevenueShare<'info> {
+    /// Treasury mint account, either native SOL mint or a SPL token mint.
+    pub treasury_mint: Account<'info, Mint>,
+
+    /// Authority key for the Auction House.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            treasury_mint.key().as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority,
+        has_one=treasury_mint
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+
+    /// Mint of the governance token whose holders share the house fees.
+    pub governance_mint: Account<'info, Mint>,
+
+    /// The revenue share PDA, also holding the accrued SOL for native treasuries.
+    #[account(
+        init,
+        payer = authority,
+        space = REVENUE_SHARE_SIZE,
+        seeds = [REVENUE_SHARE.as_bytes(), auction_house.key().as_ref()],
+        bump
+    )]
+    pub revenue_share: Account<'info, RevenueShare>,
+
+    /// CHECK: Validated in create_revenue_share.
+    /// Associated token account of the revenue share PDA for SPL treasuries, unused for native SOL.
+    #[account(mut)]
+    pub revenue_share_token_account: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub ata_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Route `basis_points` of every house fee into the revenue share PDA for holders of `governance_mint`.
+pub fn create_revenue_share<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreateRevenueShare<'info>>,
+    basis_points: u16,
+) -> Result<()> {
+    if basis_points > 10000 {
+        return Err(AuctionHouseError::InvalidBasisPoints.into());
+    }
+
+    let treasury_mint = &ctx.accounts.treasury_mint;
+    let is_native = treasury_mint.key() == spl_token::native_mint::id();
+
+    if !is_native {
+        let revenue_share_token_account = &ctx.accounts.revenue_share_token_account;
+        if revenue_share_token_account.data_is_empty() {
+            make_ata(
+                revenue_share_token_account.to_account_info(),
+                ctx.accounts.revenue_share.to_account_info(),
+                treasury_mint.to_account_info(),
+                ctx.accounts.authority.to_account_info(),
+                ctx.accounts.ata_program.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+                ctx.accounts.rent.to_account_info(),
+                &[],
+            )?;
+        }
+
+        assert_is_ata(
+            &revenue_share_token_account.to_account_info(),
+            &ctx.accounts.revenue_share.key(),
+            &treasury_mint.key(),
+        )?;
+    }
+
+    let revenue_share = &mut ctx.accounts.revenue_share;
+    revenue_share.auction_house = ctx.accounts.auction_house.key();
+    revenue_share.governance_mint = ctx.accounts.governance_mint.key();
+    revenue_share.basis_points = basis_points;
+    revenue_share.accrued = 0;
+    revenue_share.snapshot_count = 0;
+    revenue_share.bump = *ctx
+        .bumps
+        .get("revenue_share")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+
+    ctx.accounts.auction_house.has_revenue_share = true;
+
+    emit_ah_event(
+        AhEventKind::CreateRevenueShare,
+        ctx.accounts.auction_house.key(),
+        Some(ctx.accounts.governance_mint.key()),
+        0,
+        vec![ctx.accounts.authority.key()],
+    )?;
+
+    Ok(())
+}
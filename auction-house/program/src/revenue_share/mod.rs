@@ -0,0 +1,115 @@
+pub mod claim;
+pub mod create;
+pub mod snapshot;
+pub use claim::*;
+pub use create::*;
+pub use snapshot::*;
+
+use anchor_lang::{
+    prelude::*,
+    solana_program::{keccak, program::invoke_signed, system_instruction},
+};
+
+use crate::{
+    constants::*, errors::AuctionHouseError, pda::find_revenue_share_address, utils::*,
+    AuctionHouse, RevenueShare,
+};
+
+/// Move the revenue share part of `house_fee` from the treasury to the revenue share vault and add it to the accrued amount.
+/// For native SOL the revenue share account is its own vault, for SPL tokens `revenue_share_vault` is its associated token account.
+pub fn accrue_revenue_share<'a>(
+    auction_house: &Account<'a, AuctionHouse>,
+    auction_house_treasury: &AccountInfo<'a>,
+    revenue_share_info: &AccountInfo<'a>,
+    revenue_share_vault: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    house_fee: u64,
+    is_native: bool,
+) -> Result<()> {
+    let ah_key = auction_house.key();
+    let (revenue_share_key, _) = find_revenue_share_address(&ah_key);
+    assert_keys_equal(revenue_share_key, *revenue_share_info.key)?;
+
+    let mut revenue_share: Account<RevenueShare> = Account::try_from(revenue_share_info)?;
+    let share = (house_fee as u128)
+        .checked_mul(revenue_share.basis_points as u128)
+        .ok_or(AuctionHouseError::NumericalOverflow)?
+        .checked_div(10000)
+        .ok_or(AuctionHouseError::NumericalOverflow)? as u64;
+
+    if share == 0 {
+        return Ok(());
+    }
+
+    if is_native {
+        assert_keys_equal(revenue_share_key, *revenue_share_vault.key)?;
+        let auction_house_treasury_seeds = [
+            PREFIX.as_bytes(),
+            ah_key.as_ref(),
+            TREASURY.as_bytes(),
+            &[auction_house.treasury_bump],
+        ];
+        invoke_signed(
+            &system_instruction::transfer(
+                auction_house_treasury.key,
+                revenue_share_vault.key,
+                share,
+            ),
+            &[
+                auction_house_treasury.clone(),
+                revenue_share_vault.clone(),
+                system_program.clone(),
+            ],
+            &[&auction_house_treasury_seeds],
+        )?;
+    } else {
+        assert_is_ata(
+            revenue_share_vault,
+            &revenue_share_key,
+            &auction_house.treasury_mint,
+        )?;
+        let auction_house_seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            &[auction_house.bump],
+        ];
+        invoke_signed(
+            &spl_token::instruction::transfer(
+                token_program.key,
+                auction_house_treasury.key,
+                revenue_share_vault.key,
+                &ah_key,
+                &[],
+                share,
+            )?,
+            &[
+                auction_house_treasury.clone(),
+                revenue_share_vault.clone(),
+                token_program.clone(),
+                auction_house.to_account_info(),
+            ],
+            &[&auction_house_seeds],
+        )?;
+    }
+
+    revenue_share.accrued = revenue_share
+        .accrued
+        .checked_add(share)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+    revenue_share.exit(&crate::id())
+}
+
+/// Verify a merkle proof for `leaf` against `root`, hashing sorted pairs.
+pub fn verify_revenue_share_proof(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
+    let mut computed_hash = leaf;
+    for proof_element in proof.iter() {
+        computed_hash = if computed_hash <= *proof_element {
+            keccak::hashv(&[&computed_hash, proof_element]).0
+        } else {
+            keccak::hashv(&[proof_element, &computed_hash]).0
+        };
+    }
+    computed_hash == root
+}
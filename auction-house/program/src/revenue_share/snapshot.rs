@@ -0,0 +1,96 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::*, errors::AuctionHouseError, utils::emit_ah_event, AhEventKind, AuctionHouse,
+    RevenueShare, RevenueShareSnapshot,
+};
+
+/// Accounts for the [`snapshot_revenue_share` handler](auction_house/fn.snapshot_revenue_share.html).
+#[derive(Accounts)]
+pub struct SnapshotRevenueShare<'info> {
+    /// Authority key for the Auction House.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+
+    /// The revenue share PDA.
+    #[account(
+        mut,
+        seeds = [REVENUE_SHARE.as_bytes(), auction_house.key().as_ref()],
+        bump=revenue_share.bump,
+        has_one=auction_house
+    )]
+    pub revenue_share: Account<'info, RevenueShare>,
+
+    /// The snapshot PDA distributing the amount accrued since the previous snapshot.
+    #[account(
+        init,
+        payer = authority,
+        space = REVENUE_SHARE_SNAPSHOT_SIZE,
+        seeds = [
+            REVENUE_SHARE.as_bytes(),
+            auction_house.key().as_ref(),
+            &revenue_share.snapshot_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub snapshot: Box<Account<'info, RevenueShareSnapshot>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Snapshot the accrued revenue share for distribution to the governance token holders at `slot`.
+/// `merkle_root` commits to `(index, token_account, balance)` leaves of the holders and `supply` is the governance mint supply at `slot`.
+pub fn snapshot_revenue_share<'info>(
+    ctx: Context<'_, '_, '_, 'info, SnapshotRevenueShare<'info>>,
+    merkle_root: [u8; 32],
+    supply: u64,
+    slot: u64,
+) -> Result<()> {
+    if supply == 0 || slot > Clock::get()?.slot {
+        return Err(AuctionHouseError::InvalidRevenueShareSnapshot.into());
+    }
+
+    let revenue_share = &mut ctx.accounts.revenue_share;
+    let snapshot = &mut ctx.accounts.snapshot;
+
+    snapshot.revenue_share = revenue_share.key();
+    snapshot.index = revenue_share.snapshot_count;
+    snapshot.slot = slot;
+    snapshot.merkle_root = merkle_root;
+    snapshot.supply = supply;
+    snapshot.amount = revenue_share.accrued;
+    snapshot.claimed = 0;
+    snapshot.bump = *ctx
+        .bumps
+        .get("snapshot")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+    snapshot.claimed_bitmap = vec![0; REVENUE_SHARE_BITMAP_LEN];
+
+    revenue_share.accrued = 0;
+    revenue_share.snapshot_count = revenue_share
+        .snapshot_count
+        .checked_add(1)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+    emit_ah_event(
+        AhEventKind::SnapshotRevenueShare,
+        ctx.accounts.auction_house.key(),
+        Some(ctx.accounts.revenue_share.governance_mint),
+        snapshot.amount,
+        vec![ctx.accounts.authority.key()],
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::*, errors::AuctionHouseError, RoyaltyMandate};
+
+/// Accounts for the [`set_royalty_mandate` handler](auction_house/fn.set_royalty_mandate.html).
+#[derive(Accounts)]
+pub struct SetRoyaltyMandate<'info> {
+    /// Creator whose royalties are redirected, pays for the mandate.
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// CHECK: Any account chosen by the creator can receive its royalties.
+    /// Destination of the creator royalties, e.g. a DAO treasury or split contract.
+    pub destination: UncheckedAccount<'info>,
+
+    /// Royalty mandate PDA of the creator.
+    #[account(
+        init,
+        payer = creator,
+        space = ROYALTY_MANDATE_SIZE,
+        seeds = [
+            PREFIX.as_bytes(),
+            ROYALTY_MANDATE.as_bytes(),
+            creator.key().as_ref()
+        ],
+        bump
+    )]
+    pub royalty_mandate: Account<'info, RoyaltyMandate>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Redirect royalties of the signing creator to `destination` in every Auction House sale that passes the mandate.
+pub fn set_royalty_mandate<'info>(
+    ctx: Context<'_, '_, '_, 'info, SetRoyaltyMandate<'info>>,
+) -> Result<()> {
+    let royalty_mandate = &mut ctx.accounts.royalty_mandate;
+
+    royalty_mandate.creator = ctx.accounts.creator.key();
+    royalty_mandate.destination = ctx.accounts.destination.key();
+    royalty_mandate.bump = *ctx
+        .bumps
+        .get("royalty_mandate")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+
+    Ok(())
+}
+
+/// Accounts for the [`revoke_royalty_mandate` handler](auction_house/fn.revoke_royalty_mandate.html).
+#[derive(Accounts)]
+pub struct RevokeRoyaltyMandate<'info> {
+    /// Creator that signed the mandate, receives its lamports back.
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// Royalty mandate PDA of the creator, closed back to the creator.
+    #[account(
+        mut,
+        close = creator,
+        seeds = [
+            PREFIX.as_bytes(),
+            ROYALTY_MANDATE.as_bytes(),
+            creator.key().as_ref()
+        ],
+        bump=royalty_mandate.bump,
+        has_one=creator
+    )]
+    pub royalty_mandate: Account<'info, RoyaltyMandate>,
+}
+
+/// Close the royalty mandate of the signing creator so royalties are paid to its own wallet again.
+pub fn revoke_royalty_mandate<'info>(
+    _ctx: Context<'_, '_, '_, 'info, RevokeRoyaltyMandate<'info>>,
+) -> Result<()> {
+    Ok(())
+}
@@ -1,7 +1,9 @@
 use anchor_lang::{prelude::*, solana_program::program::invoke, AnchorDeserialize};
 use spl_token::instruction::approve;
 
-use crate::{constants::*, errors::*, utils::*, AuctionHouse, AuthorityScope, *};
+use crate::{
+    constants::*, errors::*, order_book::*, utils::*, AhEventKind, AuctionHouse, AuthorityScope, *,
+};
 
 /// Accounts for the [`sell` handler](auction_house/fn.sell.html).
 #[derive(Accounts)]
@@ -251,11 +253,13 @@ pub fn sell<'info>(
     sell_logic(
         ctx.accounts,
         ctx.program_id,
+        ctx.remaining_accounts,
         trade_state_bump,
         free_trade_state_bump,
         program_as_signer_bump,
         buyer_price,
         token_size,
+        None,
     )
 }
 
@@ -287,23 +291,177 @@ pub fn auctioneer_sell<'info>(
     sell_logic(
         &mut accounts,
         ctx.program_id,
+        ctx.remaining_accounts,
         trade_state_bump,
         free_trade_state_bump,
         program_as_signer_bump,
         u64::MAX,
         token_size,
+        None,
+    )
+}
+
+/// Accounts for the [`sell_v2` handler](auction_house/fn.sell_v2.html).
+#[derive(Accounts, Clone)]
+#[instruction(buyer_price: u64, token_size: u64)]
+pub struct SellV2<'info> {
+    /// CHECK: Verified through CPI
+    /// User wallet account.
+    pub wallet: UncheckedAccount<'info>,
+
+    /// SPL token account containing token for sale.
+    #[account(mut)]
+    pub token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Verified through CPI
+    /// Metaplex metadata account decorating SPL mint account.
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Verified through CPI
+    /// Auction House authority account.
+    pub authority: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority,
+        has_one=auction_house_fee_account
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance fee account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump=auction_house.fee_payer_bump
+    )]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint with the canonical bump.
+    /// Seller trade state PDA account encoding the sell order.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_account.mint.as_ref(),
+            &buyer_price.to_le_bytes(),
+            &token_size.to_le_bytes()
+        ],
+        bump
+    )]
+    pub seller_trade_state: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint with the canonical bump.
+    /// Free seller trade state PDA account encoding a free sell order.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_account.mint.as_ref(),
+            &0u64.to_le_bytes(),
+            &token_size.to_le_bytes()
+        ],
+        bump
+    )]
+    pub free_seller_trade_state: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint with the bump stored on the auction house.
+    #[account(seeds=[PREFIX.as_bytes(), SIGNER.as_bytes()], bump=get_program_as_signer_bump(&auction_house))]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> From<SellV2<'info>> for Sell<'info> {
+    fn from(a: SellV2<'info>) -> Sell<'info> {
+        Sell {
+            wallet: a.wallet,
+            token_account: a.token_account,
+            metadata: a.metadata,
+            authority: a.authority,
+            auction_house: *a.auction_house,
+            auction_house_fee_account: a.auction_house_fee_account,
+            seller_trade_state: a.seller_trade_state,
+            free_seller_trade_state: a.free_seller_trade_state,
+            token_program: a.token_program,
+            system_program: a.system_program,
+            program_as_signer: a.program_as_signer,
+            rent: a.rent,
+        }
+    }
+}
+
+/// Sell, with the trade state bumps derived on chain and the program as signer bump read from the auction house
+/// instead of passed by the client.
+pub fn sell_v2<'info>(
+    ctx: Context<'_, '_, '_, 'info, SellV2<'info>>,
+    buyer_price: u64,
+    token_size: u64,
+) -> Result<()> {
+    let auction_house = &ctx.accounts.auction_house;
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    let trade_state_bump = *ctx
+        .bumps
+        .get("seller_trade_state")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+    let free_trade_state_bump = *ctx
+        .bumps
+        .get("free_seller_trade_state")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+    let program_as_signer_bump = get_program_as_signer_bump(auction_house);
+
+    let mut accounts: Sell<'info> = (*ctx.accounts).clone().into();
+
+    sell_logic(
+        &mut accounts,
+        ctx.program_id,
+        ctx.remaining_accounts,
+        trade_state_bump,
+        free_trade_state_bump,
+        program_as_signer_bump,
+        buyer_price,
+        token_size,
+        None,
     )
 }
 
 /// Create a sell bid by creating a `seller_trade_state` account and approving the program as the token delegate.
-fn sell_logic<'info>(
+pub(crate) fn sell_logic<'info>(
     accounts: &mut Sell<'info>,
     program_id: &Pubkey,
+    remaining_accounts: &[AccountInfo<'info>],
     trade_state_bump: u8,
     _free_trade_state_bump: u8,
     _program_as_signer_bump: u8,
     buyer_price: u64,
     token_size: u64,
+    custodial_identity: Option<[u8; 32]>,
 ) -> Result<()> {
     let wallet = &accounts.wallet;
     let token_account = &accounts.token_account;
@@ -318,6 +476,8 @@ fn sell_logic<'info>(
     let program_as_signer = &accounts.program_as_signer;
     let rent = &accounts.rent;
 
+    assert_not_paused(auction_house, PAUSE_SELL)?;
+
     // 1. The wallet being a signer is the only condition in which an NFT can sell at a price of 0.
     //    If the user does list at 0 then auction house can change the sale price if the 'can_change_sale_price' option is true.
     // 2. If the trade is not priced at 0, the wallet holder has to be a signer since auction house cannot sign if listing over 0.
@@ -332,8 +492,23 @@ fn sell_logic<'info>(
         return Err(AuctionHouseError::SaleRequiresSigner.into());
     }
 
+    // Auctioneer listings are placed at u64::MAX and priced by their bids instead.
+    if buyer_price != u64::MAX {
+        assert_price_on_tick(auction_house, buyer_price)?;
+    }
+
     let auction_house_key = auction_house.key();
 
+    assert_listing_cooldown(
+        auction_house,
+        &auction_house_key,
+        &token_account.mint,
+        &wallet.key(),
+        remaining_accounts,
+    )?;
+    let order_book_summary =
+        find_order_book_summary(remaining_accounts, auction_house, &token_account.mint)?;
+
     let seeds = [
         PREFIX.as_bytes(),
         auction_house_key.as_ref(),
@@ -401,14 +576,42 @@ fn sell_logic<'info>(
             &rent.to_account_info(),
             system_program,
             &fee_payer,
-            TRADE_STATE_SIZE,
+            match custodial_identity {
+                Some(_) => CUSTODIAL_TRADE_STATE_SIZE,
+                None => TRADE_STATE_WITH_RENT_PAYER_SIZE,
+            },
             fee_seeds,
             &ts_seeds,
         )?;
+        write_trade_state(&ts_info, trade_state_bump, fee_payer.key)?;
+        if let Some(identity) = &custodial_identity {
+            write_custodial_identity(&ts_info, identity)?;
+        }
+
+        record_order_opened(
+            order_book_summary,
+            &auction_house_key,
+            &token_account.mint,
+            OrderSide::Ask,
+            buyer_price,
+        )?;
+    } else if custodial_identity.is_some()
+        && read_custodial_identity(&ts_info.data.borrow()) != custodial_identity
+    {
+        // Relisting must not change whose approval the sale requires.
+        return Err(AuctionHouseError::CustodialIdentityMismatch.into());
     }
 
     let data = &mut ts_info.data.borrow_mut();
     data[0] = trade_state_bump;
 
+    emit_ah_event(
+        AhEventKind::Sell,
+        auction_house.key(),
+        Some(token_account.mint),
+        buyer_price,
+        vec![wallet.key()],
+    )?;
+
     Ok(())
 }
@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::*, errors::AuctionHouseError, utils::emit_ah_event, AhEventKind, AuctionHouse,
+    SessionKey,
+};
+
+/// Accounts for the [`approve_session_key` handler](auction_house/fn.approve_session_key.html).
+#[derive(Accounts)]
+pub struct ApproveSessionKey<'info> {
+    /// User wallet account approving the session key.
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    /// CHECK: The wallet can approve whatever key they wish.
+    /// Key allowed to bid and cancel on behalf of the wallet.
+    pub session_key: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+
+    /// The session key PDA storing the spend limit and expiry.
+    #[account(
+        init,
+        payer = wallet,
+        space = SESSION_KEY_SIZE,
+        seeds = [
+            SESSION_KEY.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref(),
+            session_key.key().as_ref()
+        ],
+        bump
+    )]
+    pub session_key_account: Account<'info, SessionKey>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Approve a session key to bid on behalf of the wallet up to `max_spend` until `expiry`.
+pub fn approve_session_key<'info>(
+    ctx: Context<'_, '_, '_, 'info, ApproveSessionKey<'info>>,
+    max_spend: u64,
+    expiry: i64,
+) -> Result<()> {
+    if expiry <= Clock::get()?.unix_timestamp {
+        return Err(AuctionHouseError::SessionKeyExpired.into());
+    }
+
+    let session_key_account = &mut ctx.accounts.session_key_account;
+    session_key_account.wallet = ctx.accounts.wallet.key();
+    session_key_account.auction_house = ctx.accounts.auction_house.key();
+    session_key_account.session_key = ctx.accounts.session_key.key();
+    session_key_account.max_spend = max_spend;
+    session_key_account.spent = 0;
+    session_key_account.expiry = expiry;
+    session_key_account.bump = *ctx
+        .bumps
+        .get("session_key_account")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+
+    emit_ah_event(
+        AhEventKind::ApproveSessionKey,
+        ctx.accounts.auction_house.key(),
+        None,
+        max_spend,
+        vec![ctx.accounts.wallet.key(), ctx.accounts.session_key.key()],
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,4 @@
+pub mod approve;
+pub mod revoke;
+pub use approve::*;
+pub use revoke::*;
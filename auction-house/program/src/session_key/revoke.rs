@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::*, utils::emit_ah_event, AhEventKind, AuctionHouse, SessionKey};
+
+/// Accounts for the [`revoke_session_key` handler](auction_house/fn.revoke_session_key.html).
+#[derive(Accounts)]
+pub struct RevokeSessionKey<'info> {
+    /// User wallet account that approved the session key.
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    /// CHECK: Validated through the session key PDA seeds.
+    /// Key being revoked.
+    pub session_key: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+
+    /// The session key PDA, closed back to the wallet.
+    #[account(
+        mut,
+        close = wallet,
+        seeds = [
+            SESSION_KEY.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref(),
+            session_key.key().as_ref()
+        ],
+        bump=session_key_account.bump,
+        has_one=wallet
+    )]
+    pub session_key_account: Account<'info, SessionKey>,
+}
+
+/// Revoke a session key, returning the rent of the session key PDA to the wallet.
+pub fn revoke_session_key<'info>(
+    ctx: Context<'_, '_, '_, 'info, RevokeSessionKey<'info>>,
+) -> Result<()> {
+    emit_ah_event(
+        AhEventKind::RevokeSessionKey,
+        ctx.accounts.auction_house.key(),
+        None,
+        0,
+        vec![ctx.accounts.wallet.key(), ctx.accounts.session_key.key()],
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,288 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program_option::COption, sysvar},
+};
+use anchor_spl::token::TokenAccount;
+
+use crate::{
+    constants::*, errors::AuctionHouseError, order_book::*, utils::*, AhEventKind, AuctionHouse,
+    SignedListingNonce,
+};
+
+/// Accounts for the [`post_signed_listing` handler](auction_house/fn.post_signed_listing.html).
+#[derive(Accounts)]
+#[instruction(
+    trade_state_bump: u8,
+    program_as_signer_bump: u8,
+    buyer_price: u64,
+    token_size: u64,
+    expiry: i64,
+    nonce: u64
+)]
+pub struct PostSignedListing<'info> {
+    /// CHECK: Validated against the ed25519 verification instruction in post_signed_listing.
+    /// Seller wallet account, does not sign the transaction.
+    pub wallet: UncheckedAccount<'info>,
+
+    /// SPL token account containing the token for sale.
+    pub token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Validated by assert_metadata_valid.
+    /// Metaplex metadata account decorating SPL mint account.
+    pub metadata: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Seller trade state PDA account encoding the sell order.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_account.mint.as_ref(),
+            &buyer_price.to_le_bytes(),
+            &token_size.to_le_bytes()
+        ],
+        bump=trade_state_bump
+    )]
+    pub seller_trade_state: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(seeds=[PREFIX.as_bytes(), SIGNER.as_bytes()], bump=program_as_signer_bump)]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    /// Account submitting the signed listing, pays for the seller trade state and is refunded its rent when it closes.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Signed listing nonce PDA, created to mark the nonce as used so the signature can't be posted again.
+    #[account(
+        init,
+        payer = payer,
+        space = SIGNED_LISTING_NONCE_SIZE,
+        seeds = [
+            PREFIX.as_bytes(),
+            SIGNED_LISTING.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref(),
+            &nonce.to_le_bytes()
+        ],
+        bump
+    )]
+    pub signed_listing_nonce: Box<Account<'info, SignedListingNonce>>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+
+    /// CHECK: Validated by the address constraint.
+    #[account(address = sysvar::instructions::id())]
+    pub instruction: UncheckedAccount<'info>,
+}
+
+/// Create a `seller_trade_state` from a listing the seller signed off-chain. The previous instruction must be an
+/// ed25519 program verification of the [`signed_listing_message`] and the token account must already delegate
+/// `token_size` to the program as signer. The nonce of the listing is marked as used, so the same signature can't be
+/// posted again once the listing is canceled or sold.
+pub fn post_signed_listing<'info>(
+    ctx: Context<'_, '_, '_, 'info, PostSignedListing<'info>>,
+    trade_state_bump: u8,
+    _program_as_signer_bump: u8,
+    buyer_price: u64,
+    token_size: u64,
+    expiry: i64,
+    nonce: u64,
+) -> Result<()> {
+    let wallet = &ctx.accounts.wallet;
+    let token_account = &ctx.accounts.token_account;
+    let metadata = &ctx.accounts.metadata;
+    let auction_house = &ctx.accounts.auction_house;
+    let seller_trade_state = &ctx.accounts.seller_trade_state;
+    let program_as_signer = &ctx.accounts.program_as_signer;
+    let payer = &ctx.accounts.payer;
+    let system_program = &ctx.accounts.system_program;
+    let rent = &ctx.accounts.rent;
+
+    assert_not_paused(auction_house, PAUSE_SELL)?;
+    assert_price_on_tick(auction_house, buyer_price)?;
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    // Only the wallet signing the transaction can list at a price of 0.
+    if buyer_price == 0 {
+        return Err(AuctionHouseError::SaleRequiresSigner.into());
+    }
+
+    if Clock::get()?.unix_timestamp > expiry {
+        return Err(AuctionHouseError::SignedListingExpired.into());
+    }
+
+    let auction_house_key = auction_house.key();
+    let token_account_key = token_account.key();
+
+    assert_listing_cooldown(
+        auction_house,
+        &auction_house_key,
+        &token_account.mint,
+        &wallet.key(),
+        ctx.remaining_accounts,
+    )?;
+
+    assert_ed25519_signature(
+        &ctx.accounts.instruction.to_account_info(),
+        &wallet.key(),
+        &signed_listing_message(
+            &auction_house_key,
+            &token_account_key,
+            buyer_price,
+            token_size,
+            expiry,
+            nonce,
+        ),
+    )?;
+
+    assert_is_ata(
+        &token_account.to_account_info(),
+        &wallet.key(),
+        &token_account.mint,
+    )?;
+
+    assert_metadata_valid(metadata, token_account)?;
+
+    if token_size > token_account.amount {
+        return Err(AuctionHouseError::InvalidTokenAmount.into());
+    }
+
+    // The seller can't approve the delegate without sending a transaction, so it must be in place already.
+    if token_account.delegate != COption::Some(program_as_signer.key())
+        || token_account.delegated_amount != token_size
+    {
+        return Err(AuctionHouseError::SignedListingNotDelegated.into());
+    }
+
+    let ts_info = seller_trade_state.to_account_info();
+    if ts_info.data_is_empty() {
+        let wallet_key = wallet.key();
+        let ts_seeds = [
+            PREFIX.as_bytes(),
+            wallet_key.as_ref(),
+            auction_house_key.as_ref(),
+            token_account_key.as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_account.mint.as_ref(),
+            &buyer_price.to_le_bytes(),
+            &token_size.to_le_bytes(),
+            &[trade_state_bump],
+        ];
+        create_or_allocate_account_raw(
+            *ctx.program_id,
+            &ts_info,
+            &rent.to_account_info(),
+            system_program,
+            &payer.to_account_info(),
+            TRADE_STATE_WITH_RENT_PAYER_SIZE,
+            &[],
+            &ts_seeds,
+        )?;
+        write_trade_state(&ts_info, trade_state_bump, payer.key)?;
+
+        record_order_opened(
+            find_order_book_summary(ctx.remaining_accounts, auction_house, &token_account.mint)?,
+            &auction_house_key,
+            &token_account.mint,
+            OrderSide::Ask,
+            buyer_price,
+        )?;
+    }
+
+    let signed_listing_nonce = &mut ctx.accounts.signed_listing_nonce;
+    signed_listing_nonce.auction_house = auction_house_key;
+    signed_listing_nonce.wallet = wallet.key();
+    signed_listing_nonce.nonce = nonce;
+    signed_listing_nonce.canceled = false;
+    signed_listing_nonce.bump = *ctx
+        .bumps
+        .get("signed_listing_nonce")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+
+    emit_ah_event(
+        AhEventKind::Sell,
+        auction_house_key,
+        Some(token_account.mint),
+        buyer_price,
+        vec![wallet.key(), payer.key()],
+    )?;
+
+    Ok(())
+}
+
+/// Accounts for the [`cancel_signed_listing` handler](auction_house/fn.cancel_signed_listing.html).
+#[derive(Accounts)]
+#[instruction(nonce: u64)]
+pub struct CancelSignedListing<'info> {
+    /// Seller wallet which signed the listing off-chain, pays for the signed listing nonce.
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// Signed listing nonce PDA, created to mark the nonce as canceled.
+    #[account(
+        init,
+        payer = wallet,
+        space = SIGNED_LISTING_NONCE_SIZE,
+        seeds = [
+            PREFIX.as_bytes(),
+            SIGNED_LISTING.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref(),
+            &nonce.to_le_bytes()
+        ],
+        bump
+    )]
+    pub signed_listing_nonce: Box<Account<'info, SignedListingNonce>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Cancel a listing signed off-chain before it is posted by marking its `nonce` as used.
+pub fn cancel_signed_listing<'info>(
+    ctx: Context<'_, '_, '_, 'info, CancelSignedListing<'info>>,
+    nonce: u64,
+) -> Result<()> {
+    let signed_listing_nonce = &mut ctx.accounts.signed_listing_nonce;
+    signed_listing_nonce.auction_house = ctx.accounts.auction_house.key();
+    signed_listing_nonce.wallet = ctx.accounts.wallet.key();
+    signed_listing_nonce.nonce = nonce;
+    signed_listing_nonce.canceled = true;
+    signed_listing_nonce.bump = *ctx
+        .bumps
+        .get("signed_listing_nonce")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+
+    Ok(())
+}
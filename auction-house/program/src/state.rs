@@ -20,15 +20,397 @@ pub struct AuctionHouse {
     pub escrow_payment_bump: u8,
     pub has_auctioneer: bool,
     pub auctioneer_pda_bump: u8,
+    pub auto_receipts: bool,
+    pub fee_account_target_balance: u64,
+    pub fee_account_low_balance_threshold: u64,
+    pub has_revenue_share: bool,
+    pub wash_trading_guard: bool,
+    pub enforce_royalties: bool,
+    pub has_compliance: bool,
+    pub tick_size: u64,
+    pub free_sales_require_sign_off: bool,
+    pub max_free_sales_per_epoch: u16,
+    pub program_as_signer_bump: u8,
+    pub paused_operations: u8,
+    pub epoch_report_retention: u16,
+    /// Seconds the seller proceeds of a sale are held back for disputes, zero pays sellers at the sale.
+    pub dispute_window: i64,
+    /// Slots after a sale during which neither of its parties may list the mint again, zero disables the cooldown.
+    pub listing_cooldown_slots: u64,
+    /// Set once an order book summary is created, from then on orders must be sent with the summary of their mint.
+    pub has_order_book_summaries: bool,
 }
 
 #[account]
 pub struct Auctioneer {
     pub auctioneer_authority: Pubkey,
     pub auction_house: Pubkey,
+    /// Scopes added since the auctioneer was delegated are read from its zeroed padding, so they stay
+    /// ungranted until the next `update_auctioneer`.
     pub scopes: [bool; MAX_NUM_SCOPES],
 }
 
+#[account]
+pub struct SessionKey {
+    pub wallet: Pubkey,
+    pub auction_house: Pubkey,
+    pub session_key: Pubkey,
+    pub max_spend: u64,
+    pub spent: u64,
+    pub expiry: i64,
+    pub bump: u8,
+}
+
+#[account]
+pub struct RevenueShare {
+    pub auction_house: Pubkey,
+    pub governance_mint: Pubkey,
+    pub basis_points: u16,
+    pub accrued: u64,
+    pub snapshot_count: u64,
+    pub bump: u8,
+}
+
+#[account]
+pub struct RevenueShareSnapshot {
+    pub revenue_share: Pubkey,
+    pub index: u64,
+    pub slot: u64,
+    pub merkle_root: [u8; 32],
+    pub supply: u64,
+    pub amount: u64,
+    pub claimed: u64,
+    pub bump: u8,
+    pub claimed_bitmap: Vec<u8>,
+}
+
+#[account]
+pub struct PendingListing {
+    pub auction_house: Pubkey,
+    pub wallet: Pubkey,
+    pub token_account: Pubkey,
+    pub buyer_price: u64,
+    pub token_size: u64,
+    pub bump: u8,
+}
+
+/// Marks a signed listing `nonce` of `wallet` as used, whether it was posted or canceled by the wallet before it was.
+#[account]
+pub struct SignedListingNonce {
+    pub auction_house: Pubkey,
+    pub wallet: Pubkey,
+    pub nonce: u64,
+    pub canceled: bool,
+    pub bump: u8,
+}
+
+/// Creator-signed redirection of the royalties paid to `creator` by `pay_creator_fees` to `destination`.
+#[account]
+pub struct RoyaltyMandate {
+    pub creator: Pubkey,
+    pub destination: Pubkey,
+    pub bump: u8,
+}
+
+/// Seller-signed redirection of the proceeds of the listing under `seller_trade_state` to `destination`.
+#[account]
+pub struct ProceedsRoute {
+    pub seller: Pubkey,
+    pub seller_trade_state: Pubkey,
+    pub destination: Pubkey,
+    pub bump: u8,
+}
+
+/// Seller proceeds of a sale held back for the dispute window of the Auction House. Native proceeds are held by
+/// the account itself, SPL proceeds by its associated token account.
+#[account]
+pub struct DeferredSettlement {
+    pub auction_house: Pubkey,
+    pub purchase_receipt: Pubkey,
+    pub buyer: Pubkey,
+    pub seller: Pubkey,
+    /// Seller wallet or associated token account, or the destination of the seller's proceeds route.
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub settle_after: i64,
+    pub disputed: bool,
+    pub bump: u8,
+}
+
+/// Parties and slot of the last sale of a mint on an Auction House, checked by `sell` against the listing cooldown.
+#[account]
+pub struct LastSale {
+    pub auction_house: Pubkey,
+    pub token_mint: Pubkey,
+    pub buyer: Pubkey,
+    pub seller: Pubkey,
+    pub slot: u64,
+    pub bump: u8,
+}
+
+/// Item of a [`BundleListing`], listed on its own by `sell` at `price`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Debug)]
+pub struct BundleItem {
+    pub trade_state: Pubkey,
+    pub token_account: Pubkey,
+    pub token_mint: Pubkey,
+    pub price: u64,
+    pub token_size: u64,
+}
+
+/// Lot of seller listings bought together by `execute_bundle_sale` for the sum of their prices.
+#[account]
+pub struct BundleListing {
+    pub auction_house: Pubkey,
+    pub seller: Pubkey,
+    pub bundle_id: u64,
+    pub price: u64,
+    pub items: Vec<BundleItem>,
+    pub bump: u8,
+}
+
+/// Listing taken off the market by `begin_layaway` and paid for in `installments` due every `installment_interval`
+/// seconds from `started_at`. The listed tokens are held by the program until the layaway is completed or canceled.
+#[account]
+pub struct Layaway {
+    pub auction_house: Pubkey,
+    pub seller: Pubkey,
+    pub buyer: Pubkey,
+    pub seller_trade_state: Pubkey,
+    pub token_mint: Pubkey,
+    pub token_size: u64,
+    pub price: u64,
+    pub installments: u8,
+    pub installment_interval: i64,
+    pub started_at: i64,
+    pub amount_paid: u64,
+    pub penalty_basis_points: u16,
+    pub bump: u8,
+    pub escrow_bump: u8,
+}
+
+impl Layaway {
+    /// Amount the buyer must have paid by `now`, the price split evenly over the installments with deadlines passed.
+    pub fn amount_due(&self, now: i64) -> Option<u64> {
+        let deadlines_passed = now.checked_sub(self.started_at)? / self.installment_interval;
+        let deadlines_passed =
+            std::cmp::min(deadlines_passed.max(0) as u128, self.installments as u128);
+
+        (self.price as u128)
+            .checked_mul(deadlines_passed)?
+            .checked_div(self.installments as u128)
+            .map(|amount_due| amount_due as u64)
+    }
+
+    pub fn is_defaulted(&self, now: i64) -> Option<bool> {
+        Some(self.amount_paid < self.amount_due(now)?)
+    }
+
+    /// Part of the amount paid kept by the seller when the layaway is canceled.
+    pub fn penalty(&self) -> Option<u64> {
+        let penalty = (self.price as u128)
+            .checked_mul(self.penalty_basis_points as u128)?
+            .checked_div(10000)? as u64;
+
+        Some(std::cmp::min(penalty, self.amount_paid))
+    }
+}
+
+/// NFT-for-NFT trade proposed by `proposer`: its offered tokens, delegated to the program as signer, in exchange for
+/// `target_size` tokens of `target_mint` from whoever accepts the swap.
+#[account]
+pub struct SwapProposal {
+    pub auction_house: Pubkey,
+    pub proposer: Pubkey,
+    pub offered_token_account: Pubkey,
+    pub offered_mint: Pubkey,
+    pub offered_size: u64,
+    pub target_mint: Pubkey,
+    pub target_size: u64,
+    pub bump: u8,
+    pub max_offered_valuation: u64,
+}
+
+/// Per-wallet volume cap and freeze list enforced on every sale of the Auction House.
+#[account]
+pub struct ComplianceConfig {
+    pub auction_house: Pubkey,
+    pub volume_cap_per_epoch: u64,
+    pub frozen_wallets: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+/// Volume traded by `wallet` during `epoch`, reset on the first sale of a later epoch.
+#[account]
+pub struct WalletVolume {
+    pub auction_house: Pubkey,
+    pub wallet: Pubkey,
+    pub epoch: u64,
+    pub volume: u64,
+    pub bump: u8,
+}
+
+#[account]
+pub struct FreeSaleCounter {
+    pub auction_house: Pubkey,
+    pub seller: Pubkey,
+    pub epoch: u64,
+    pub count: u16,
+    pub bump: u8,
+}
+
+/// Totals of the sales of an Auction House in one epoch, for accounting exports.
+#[account]
+pub struct EpochReport {
+    pub auction_house: Pubkey,
+    pub epoch: u64,
+    pub house_fees: u64,
+    pub royalties_paid: u64,
+    pub volume: u64,
+    pub sale_count: u64,
+    pub bump: u8,
+}
+
+#[account]
+pub struct LinkedWallet {
+    pub auction_house: Pubkey,
+    pub wallet: Pubkey,
+    pub group: Pubkey,
+    pub bump: u8,
+}
+
+#[account]
+pub struct PendingUpdate {
+    pub auction_house: Pubkey,
+    pub payer: Pubkey,
+    pub authority: Pubkey,
+    pub treasury_withdrawal_destination: Pubkey,
+    pub executable_slot: u64,
+    pub bump: u8,
+}
+
+#[account]
+pub struct OrderBookSummary {
+    pub auction_house: Pubkey,
+    pub token_mint: Pubkey,
+    pub best_ask: Option<u64>,
+    pub best_bid: Option<u64>,
+    pub open_asks: u64,
+    pub open_bids: u64,
+    pub bump: u8,
+    /// Open asks at the best ask price, read from zeroed padding on summaries created before it was counted.
+    pub best_ask_count: u64,
+    /// Open bids at the best bid price, read from zeroed padding on summaries created before it was counted.
+    pub best_bid_count: u64,
+}
+
+/// Emitted when the Auction House fee account balance is below the configured threshold.
+#[event]
+pub struct FeeAccountLowBalance {
+    pub auction_house: Pubkey,
+    pub auction_house_fee_account: Pubkey,
+    pub balance: u64,
+    pub threshold: u64,
+}
+
+/// Validation relaxed for accounts created by older versions of the program.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Debug)]
+pub enum LenientPath {
+    /// Native escrow below rent exemption, the fee payer made up the shortfall.
+    EscrowRentShortfall,
+    /// Trade state without a recorded rent payer, its rent went to the fee payer.
+    LegacyTradeState,
+}
+
+/// Emitted when a handler takes a [`LenientPath`].
+#[event]
+pub struct LenientPathTaken {
+    pub auction_house: Pubkey,
+    pub account: Pubkey,
+    pub path: LenientPath,
+}
+
+/// Emitted when a custodial listing is sold with the approval of the user it was made for.
+#[event]
+pub struct CustodialSaleApproved {
+    pub auction_house: Pubkey,
+    pub seller_trade_state: Pubkey,
+    /// Key which signed the approval, hashed into the identity recorded in the trade state.
+    pub user: Pubkey,
+    pub buyer: Pubkey,
+    pub price: u64,
+    pub token_size: u64,
+}
+
+/// Emitted when a swap proposal is accepted, with the royalties each side paid on the valuation of the authority.
+#[event]
+pub struct SwapExecuted {
+    pub auction_house: Pubkey,
+    pub proposer: Pubkey,
+    pub acceptor: Pubkey,
+    pub offered_mint: Pubkey,
+    pub offered_size: u64,
+    pub offered_valuation: u64,
+    pub offered_royalties: u64,
+    pub target_mint: Pubkey,
+    pub target_size: u64,
+    pub target_valuation: u64,
+    pub target_royalties: u64,
+}
+
+/// State change reported by an [`AhEventV1`].
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Debug)]
+pub enum AhEventKind {
+    CreateAuctionHouse,
+    UpdateAuctionHouse,
+    WithdrawFromFee,
+    WithdrawFromTreasury,
+    TopUpFeeAccount,
+    Deposit,
+    Withdraw,
+    CloseEscrowAccount,
+    Bid,
+    PublicBid,
+    Sell,
+    Cancel,
+    PrepareSaleAccounts,
+    ExecuteSale,
+    DelegateAuctioneer,
+    UpdateAuctioneer,
+    ApproveSessionKey,
+    RevokeSessionKey,
+    CreateRevenueShare,
+    SnapshotRevenueShare,
+    ClaimRevenueShare,
+    ApplyPendingUpdate,
+    CancelPendingUpdate,
+    RegisterLinkedWallet,
+    RemoveLinkedWallet,
+    CreateOrderBookSummary,
+    MigrateListing,
+    InitiateListing,
+    PrintListingReceipt,
+    CancelListingReceipt,
+    PrintBidReceipt,
+    CancelBidReceipt,
+    PrintPurchaseReceipt,
+}
+
+/// Versioned event logged by every state-changing handler, see [`emit_ah_event`](crate::utils::emit_ah_event).
+/// New fields or kinds are only ever added under a new schema version.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Debug)]
+pub struct AhEventV1 {
+    pub kind: AhEventKind,
+    pub ah: Pubkey,
+    /// Token mint the event is about, if any.
+    pub mint: Option<Pubkey>,
+    /// Order price, or the amount moved for deposits, withdrawals and claims.
+    pub price: u64,
+    /// Wallets taking part, the buyer comes before the seller for sales.
+    pub wallets: Vec<Pubkey>,
+    pub slot: u64,
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Debug)]
 #[repr(u32)]
 pub enum AuthorityScope {
@@ -39,4 +421,7 @@ pub enum AuthorityScope {
     Sell = 4,
     Cancel = 5,
     Withdraw = 6,
+    PrintReceipt = 7,
+    WithdrawTreasury = 8,
+    UpdateHouse = 9,
 }
@@ -0,0 +1,447 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::program::{invoke, invoke_signed},
+};
+use anchor_spl::{associated_token::AssociatedToken, token::Token};
+
+use crate::{
+    constants::*, errors::AuctionHouseError, token_interface::*, utils::*, AuctionHouse,
+    SwapExecuted, SwapProposal,
+};
+
+/// Accounts for the [`accept_swap` handler](auction_house/fn.accept_swap.html).
+#[derive(Accounts)]
+#[instruction(proposer_escrow_bump: u8, acceptor_escrow_bump: u8, program_as_signer_bump: u8)]
+pub struct AcceptSwap<'info> {
+    /// Wallet holding the target tokens, accepting the swap.
+    #[account(mut)]
+    pub acceptor: Signer<'info>,
+
+    /// CHECK: Checked in has_one constraint of the swap proposal.
+    /// Wallet that proposed the swap, receives the swap proposal lamports back.
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+
+    /// CHECK: Checked in has_one constraint, must sign in accept_swap.
+    /// Auction House instance authority, valuing both sides of the swap for royalties.
+    pub authority: UncheckedAccount<'info>,
+
+    /// CHECK: Checked in has_one constraint.
+    /// Auction House treasury mint account, royalties are paid in it.
+    pub treasury_mint: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority,
+        has_one=treasury_mint,
+        has_one=auction_house_fee_account
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance fee account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump=auction_house.fee_payer_bump
+    )]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// Swap proposal PDA, closed back to the proposer.
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [
+            PREFIX.as_bytes(),
+            proposer.key().as_ref(),
+            auction_house.key().as_ref(),
+            SWAP_PROPOSAL.as_bytes(),
+            offered_mint.key().as_ref(),
+            target_mint.key().as_ref()
+        ],
+        bump=swap_proposal.bump,
+        has_one=proposer,
+        has_one=auction_house,
+        has_one=offered_token_account,
+        has_one=offered_mint,
+        has_one=target_mint
+    )]
+    pub swap_proposal: Box<Account<'info, SwapProposal>>,
+
+    /// CHECK: Checked in has_one constraint of the swap proposal.
+    /// Proposer SPL token account holding the offered tokens.
+    #[account(mut)]
+    pub offered_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Checked in has_one constraint of the swap proposal.
+    /// Mint of the offered tokens.
+    pub offered_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in accept_swap.
+    /// Metadata of the offered tokens.
+    pub offered_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in accept_swap.
+    /// Acceptor SPL token account holding the target tokens.
+    #[account(mut)]
+    pub target_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Checked in has_one constraint of the swap proposal.
+    /// Mint of the target tokens.
+    pub target_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in accept_swap.
+    /// Metadata of the target tokens.
+    pub target_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in accept_swap.
+    /// Proposer SPL token account to receive the target tokens at.
+    #[account(mut)]
+    pub proposer_receipt_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in accept_swap.
+    /// Acceptor SPL token account to receive the offered tokens at.
+    #[account(mut)]
+    pub acceptor_receipt_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Proposer escrow payment account, pays the royalties of the offered tokens.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            proposer.key().as_ref()
+        ],
+        bump=proposer_escrow_bump
+    )]
+    pub proposer_escrow_payment_account: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Acceptor escrow payment account, pays the royalties of the target tokens.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            acceptor.key().as_ref()
+        ],
+        bump=acceptor_escrow_bump
+    )]
+    pub acceptor_escrow_payment_account: UncheckedAccount<'info>,
+
+    /// Token program of the swapped tokens and, unless Token-2022 owning it leads the remaining accounts, of the treasury mint.
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub ata_program: Program<'info, AssociatedToken>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(seeds=[PREFIX.as_bytes(), SIGNER.as_bytes()], bump=program_as_signer_bump)]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Exchange the offered tokens of a swap proposal for the target tokens of the acceptor. Each side pays the royalties of
+/// the tokens it gives away from its escrow, computed on the valuation supplied by the Auction House authority, which
+/// for the offered tokens can't exceed the maximum set by the proposer. The
+/// remaining accounts hold the creators of the offered tokens followed by the creators of the target tokens, as in
+/// `execute_sale`.
+pub fn accept_swap<'info>(
+    ctx: Context<'_, '_, '_, 'info, AcceptSwap<'info>>,
+    proposer_escrow_bump: u8,
+    acceptor_escrow_bump: u8,
+    program_as_signer_bump: u8,
+    offered_valuation: u64,
+    target_valuation: u64,
+) -> Result<()> {
+    let acceptor = &ctx.accounts.acceptor;
+    let proposer = &ctx.accounts.proposer;
+    let authority = &ctx.accounts.authority;
+    let treasury_mint = &ctx.accounts.treasury_mint;
+    let auction_house = &ctx.accounts.auction_house;
+    let auction_house_fee_account = &ctx.accounts.auction_house_fee_account;
+    let swap_proposal = &ctx.accounts.swap_proposal;
+    let offered_token_account = &ctx.accounts.offered_token_account;
+    let offered_metadata = &ctx.accounts.offered_metadata;
+    let target_token_account = &ctx.accounts.target_token_account;
+    let target_metadata = &ctx.accounts.target_metadata;
+    let proposer_receipt_token_account = &ctx.accounts.proposer_receipt_token_account;
+    let acceptor_receipt_token_account = &ctx.accounts.acceptor_receipt_token_account;
+    let proposer_escrow_payment_account = &ctx.accounts.proposer_escrow_payment_account;
+    let acceptor_escrow_payment_account = &ctx.accounts.acceptor_escrow_payment_account;
+    let token_program = &ctx.accounts.token_program;
+    let system_program = &ctx.accounts.system_program;
+    let ata_program = &ctx.accounts.ata_program;
+    let program_as_signer = &ctx.accounts.program_as_signer;
+    let rent = &ctx.accounts.rent;
+
+    assert_not_paused(auction_house, PAUSE_EXECUTE_SALE)?;
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    // Royalties are computed on the valuations, so they are only taken from the authority.
+    if !authority.is_signer {
+        return Err(AuctionHouseError::CannotTakeThisActionWithoutAuctionHouseSignOff.into());
+    }
+
+    // The proposer escrow only pays royalties on a valuation the proposer signed off on.
+    if offered_valuation > swap_proposal.max_offered_valuation {
+        return Err(AuctionHouseError::SwapValuationTooHigh.into());
+    }
+
+    let offered_token_account_data = assert_is_ata(
+        offered_token_account,
+        &proposer.key(),
+        &swap_proposal.offered_mint,
+    )?;
+    if get_delegate_from_token_account(offered_token_account)? != Some(program_as_signer.key()) {
+        msg!("No delegate detected on token account.");
+        return Err(AuctionHouseError::BothPartiesNeedToAgreeToSale.into());
+    }
+    if offered_token_account_data.amount < swap_proposal.offered_size {
+        return Err(AuctionHouseError::NotEnoughTokensAvailableForPurchase.into());
+    }
+
+    let target_token_account_data = assert_is_ata(
+        target_token_account,
+        &acceptor.key(),
+        &swap_proposal.target_mint,
+    )?;
+    if target_token_account_data.amount < swap_proposal.target_size {
+        return Err(AuctionHouseError::NotEnoughTokensAvailableForPurchase.into());
+    }
+
+    for (metadata, mint) in [
+        (offered_metadata, &swap_proposal.offered_mint),
+        (target_metadata, &swap_proposal.target_mint),
+    ] {
+        assert_derivation(
+            &mpl_token_metadata::id(),
+            metadata,
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                mint.as_ref(),
+            ],
+        )?;
+        if metadata.data_is_empty() {
+            return Err(AuctionHouseError::MetadataDoesntExist.into());
+        }
+    }
+
+    let is_native = treasury_mint.key() == spl_token::native_mint::id();
+
+    let auction_house_key = auction_house.key();
+    let seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        FEE_PAYER.as_bytes(),
+        &[auction_house.fee_payer_bump],
+    ];
+
+    let (fee_payer, fee_payer_seeds) = get_fee_payer(
+        authority,
+        auction_house,
+        acceptor.to_account_info(),
+        auction_house_fee_account.to_account_info(),
+        &seeds,
+    )?;
+    let fee_payer_clone = fee_payer.to_account_info();
+
+    let ah_seeds = [
+        PREFIX.as_bytes(),
+        auction_house.creator.as_ref(),
+        auction_house.treasury_mint.as_ref(),
+        &[auction_house.bump],
+    ];
+
+    let proposer_key = proposer.key();
+    let proposer_escrow_seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        proposer_key.as_ref(),
+        &[proposer_escrow_bump],
+    ];
+    let acceptor_key = acceptor.key();
+    let acceptor_escrow_seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        acceptor_key.as_ref(),
+        &[acceptor_escrow_bump],
+    ];
+
+    // with the native account, the escrow is its own owner,
+    // whereas with token, it is the auction house that is owner.
+    let (proposer_royalty_seeds, acceptor_royalty_seeds) = if is_native {
+        (proposer_escrow_seeds, acceptor_escrow_seeds)
+    } else {
+        (ah_seeds, ah_seeds)
+    };
+
+    let auction_house_clone = auction_house.to_account_info();
+    let ata_clone = ata_program.to_account_info();
+    let token_clone = token_program.to_account_info();
+    let sys_clone = system_program.to_account_info();
+    let rent_clone = rent.to_account_info();
+
+    let remaining_accounts = &mut ctx.remaining_accounts.iter();
+    let treasury_token_program =
+        next_treasury_token_program(remaining_accounts, treasury_mint, &token_clone, is_native)?;
+
+    // Each side pays the royalties of the tokens it gives away, as a seller would.
+    let mut royalties_paid = [0u64; 2];
+    for (i, (metadata, escrow_payment_account, royalty_seeds, valuation)) in [
+        (
+            offered_metadata,
+            proposer_escrow_payment_account,
+            &proposer_royalty_seeds,
+            offered_valuation,
+        ),
+        (
+            target_metadata,
+            acceptor_escrow_payment_account,
+            &acceptor_royalty_seeds,
+            target_valuation,
+        ),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        if auction_house.enforce_royalties {
+            assert_royalties_enforced(
+                remaining_accounts.as_slice(),
+                metadata,
+                valuation,
+                is_native,
+            )?;
+        }
+
+        let leftover_after_royalties = pay_creator_fees(
+            remaining_accounts,
+            metadata,
+            escrow_payment_account,
+            &auction_house_clone,
+            &fee_payer_clone,
+            treasury_mint,
+            &ata_clone,
+            &treasury_token_program,
+            &sys_clone,
+            &rent_clone,
+            royalty_seeds,
+            fee_payer_seeds,
+            valuation,
+            is_native,
+        )?;
+        royalties_paid[i] = valuation
+            .checked_sub(leftover_after_royalties)
+            .ok_or(AuctionHouseError::NumericalOverflow)?;
+    }
+
+    for (receipt_token_account, wallet, mint) in [
+        (
+            acceptor_receipt_token_account,
+            acceptor.to_account_info(),
+            &ctx.accounts.offered_mint,
+        ),
+        (
+            proposer_receipt_token_account,
+            proposer.to_account_info(),
+            &ctx.accounts.target_mint,
+        ),
+    ] {
+        if receipt_token_account.data_is_empty() {
+            make_ata(
+                receipt_token_account.to_account_info(),
+                wallet.clone(),
+                mint.to_account_info(),
+                fee_payer.to_account_info(),
+                ata_program.to_account_info(),
+                token_program.to_account_info(),
+                system_program.to_account_info(),
+                rent.to_account_info(),
+                fee_payer_seeds,
+            )?;
+        }
+
+        let receipt_token_account_data =
+            assert_is_ata(receipt_token_account, wallet.key, &mint.key())?;
+
+        // make sure you cant get rugged
+        if receipt_token_account_data.delegate.is_some() {
+            return Err(AuctionHouseError::BuyerATACannotHaveDelegate.into());
+        }
+    }
+
+    let program_as_signer_seeds = [
+        PREFIX.as_bytes(),
+        SIGNER.as_bytes(),
+        &[program_as_signer_bump],
+    ];
+
+    invoke_signed(
+        &spl_token::instruction::transfer(
+            token_program.key,
+            &offered_token_account.key(),
+            &acceptor_receipt_token_account.key(),
+            &program_as_signer.key(),
+            &[],
+            swap_proposal.offered_size,
+        )?,
+        &[
+            offered_token_account.to_account_info(),
+            acceptor_receipt_token_account.to_account_info(),
+            program_as_signer.to_account_info(),
+            token_clone.clone(),
+        ],
+        &[&program_as_signer_seeds],
+    )?;
+
+    invoke(
+        &spl_token::instruction::transfer(
+            token_program.key,
+            &target_token_account.key(),
+            &proposer_receipt_token_account.key(),
+            &acceptor.key(),
+            &[],
+            swap_proposal.target_size,
+        )?,
+        &[
+            target_token_account.to_account_info(),
+            proposer_receipt_token_account.to_account_info(),
+            acceptor.to_account_info(),
+            token_clone,
+        ],
+    )?;
+
+    emit!(SwapExecuted {
+        auction_house: auction_house_key,
+        proposer: proposer_key,
+        acceptor: acceptor_key,
+        offered_mint: swap_proposal.offered_mint,
+        offered_size: swap_proposal.offered_size,
+        offered_valuation,
+        offered_royalties: royalties_paid[0],
+        target_mint: swap_proposal.target_mint,
+        target_size: swap_proposal.target_size,
+        target_valuation,
+        target_royalties: royalties_paid[1],
+    });
+
+    emit_if_fee_account_low(auction_house, &auction_house_fee_account.to_account_info());
+
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use anchor_lang::{prelude::*, solana_program::program::invoke};
+use anchor_spl::token::Token;
+use spl_token::instruction::revoke;
+
+use crate::{constants::*, AuctionHouse, SwapProposal};
+
+/// Accounts for the [`cancel_swap` handler](auction_house/fn.cancel_swap.html).
+#[derive(Accounts)]
+pub struct CancelSwap<'info> {
+    /// Wallet that proposed the swap, receives the swap proposal lamports back.
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Checked in has_one constraint of the swap proposal.
+    /// Proposer SPL token account holding the offered tokens.
+    #[account(mut)]
+    pub offered_token_account: UncheckedAccount<'info>,
+
+    /// Swap proposal PDA, closed back to the proposer.
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [
+            PREFIX.as_bytes(),
+            proposer.key().as_ref(),
+            auction_house.key().as_ref(),
+            SWAP_PROPOSAL.as_bytes(),
+            swap_proposal.offered_mint.as_ref(),
+            swap_proposal.target_mint.as_ref()
+        ],
+        bump=swap_proposal.bump,
+        has_one=proposer,
+        has_one=auction_house,
+        has_one=offered_token_account
+    )]
+    pub swap_proposal: Box<Account<'info, SwapProposal>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Close the swap proposal and revoke the delegation of the offered tokens, as `cancel` does for listings.
+pub fn cancel_swap<'info>(ctx: Context<'_, '_, '_, 'info, CancelSwap<'info>>) -> Result<()> {
+    let proposer = &ctx.accounts.proposer;
+    let offered_token_account = &ctx.accounts.offered_token_account;
+    let token_program = &ctx.accounts.token_program;
+
+    // The token account may have been closed or emptied since the proposal.
+    if !offered_token_account.data_is_empty() {
+        invoke(
+            &revoke(
+                &token_program.key(),
+                &offered_token_account.key(),
+                &proposer.key(),
+                &[],
+            )?,
+            &[
+                token_program.to_account_info(),
+                offered_token_account.to_account_info(),
+                proposer.to_account_info(),
+            ],
+        )?;
+    }
+
+    Ok(())
+}
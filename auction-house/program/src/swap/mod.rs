@@ -0,0 +1,6 @@
+pub mod accept;
+pub mod cancel;
+pub mod propose;
+pub use accept::*;
+pub use cancel::*;
+pub use propose::*;
@@ -0,0 +1,129 @@
+use anchor_lang::{prelude::*, solana_program::program::invoke};
+use anchor_spl::token::{Mint, Token};
+use spl_token::instruction::approve;
+
+use crate::{constants::*, errors::AuctionHouseError, utils::*, AuctionHouse, SwapProposal};
+
+/// Accounts for the [`propose_swap` handler](auction_house/fn.propose_swap.html).
+#[derive(Accounts)]
+#[instruction(program_as_signer_bump: u8)]
+pub struct ProposeSwap<'info> {
+    /// Wallet offering its tokens in exchange for the target tokens, pays for the swap proposal.
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Validated in propose_swap.
+    /// Proposer SPL token account holding the offered tokens, delegated to the program as signer.
+    #[account(mut)]
+    pub offered_token_account: UncheckedAccount<'info>,
+
+    /// Mint of the offered tokens.
+    pub offered_mint: Box<Account<'info, Mint>>,
+
+    /// Mint of the tokens asked for in exchange.
+    pub target_mint: Box<Account<'info, Mint>>,
+
+    /// Swap proposal PDA recording the exchange.
+    #[account(
+        init,
+        payer = proposer,
+        space = SWAP_PROPOSAL_SIZE,
+        seeds = [
+            PREFIX.as_bytes(),
+            proposer.key().as_ref(),
+            auction_house.key().as_ref(),
+            SWAP_PROPOSAL.as_bytes(),
+            offered_mint.key().as_ref(),
+            target_mint.key().as_ref()
+        ],
+        bump
+    )]
+    pub swap_proposal: Box<Account<'info, SwapProposal>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(seeds=[PREFIX.as_bytes(), SIGNER.as_bytes()], bump=program_as_signer_bump)]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Offer `offered_size` tokens of the offered mint for `target_size` tokens of the target mint. The offered tokens stay
+/// in the proposer token account, delegated to the program as signer until the swap is accepted or canceled. The
+/// royalties the proposer pays on acceptance are computed on a valuation of at most `max_offered_valuation`.
+pub fn propose_swap<'info>(
+    ctx: Context<'_, '_, '_, 'info, ProposeSwap<'info>>,
+    _program_as_signer_bump: u8,
+    offered_size: u64,
+    target_size: u64,
+    max_offered_valuation: u64,
+) -> Result<()> {
+    let proposer = &ctx.accounts.proposer;
+    let auction_house = &ctx.accounts.auction_house;
+    let offered_token_account = &ctx.accounts.offered_token_account;
+    let offered_mint = &ctx.accounts.offered_mint;
+    let target_mint = &ctx.accounts.target_mint;
+    let program_as_signer = &ctx.accounts.program_as_signer;
+    let token_program = &ctx.accounts.token_program;
+
+    assert_not_paused(auction_house, PAUSE_BUY)?;
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    if offered_size == 0 || target_size == 0 || offered_mint.key() == target_mint.key() {
+        return Err(AuctionHouseError::InvalidSwap.into());
+    }
+
+    let offered_token_account_data =
+        assert_is_ata(offered_token_account, &proposer.key(), &offered_mint.key())?;
+    if offered_token_account_data.amount < offered_size {
+        return Err(AuctionHouseError::NotEnoughTokensAvailableForPurchase.into());
+    }
+
+    invoke(
+        &approve(
+            &token_program.key(),
+            &offered_token_account.key(),
+            &program_as_signer.key(),
+            &proposer.key(),
+            &[],
+            offered_size,
+        )?,
+        &[
+            token_program.to_account_info(),
+            offered_token_account.to_account_info(),
+            program_as_signer.to_account_info(),
+            proposer.to_account_info(),
+        ],
+    )?;
+
+    let swap_proposal = &mut ctx.accounts.swap_proposal;
+    swap_proposal.auction_house = auction_house.key();
+    swap_proposal.proposer = proposer.key();
+    swap_proposal.offered_token_account = offered_token_account.key();
+    swap_proposal.offered_mint = offered_mint.key();
+    swap_proposal.offered_size = offered_size;
+    swap_proposal.target_mint = target_mint.key();
+    swap_proposal.target_size = target_size;
+    swap_proposal.max_offered_valuation = max_offered_valuation;
+    swap_proposal.bump = *ctx
+        .bumps
+        .get("swap_proposal")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+
+    Ok(())
+}
@@ -0,0 +1,497 @@
+//! Program-test harness for integration tests of the Auction House and the programs built on it.
+//!
+//! ```ignore
+//! let mut ah = AuctionHouseTest::new().seller_fee_basis_points(200).start().await?;
+//! let seller = ah.funded_wallet(ONE_SOL).await?;
+//! let nft = ah.mint_nft(&seller, creators, 500).await?;
+//! let listing = ah.sell(&nft, ONE_SOL, 1).await?;
+//! let buyer = ah.funded_wallet(2 * ONE_SOL).await?;
+//! let bid = ah.bid(&nft, &buyer, ONE_SOL, 1).await?;
+//! ah.execute_sale(&nft, &listing, &bid).await?;
+//! ```
+
+use std::io;
+
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use mpl_testing_utils::{solana::airdrop, utils::Metadata};
+use mpl_token_metadata::state::Creator;
+use solana_program::{instruction::AccountMeta, system_program, sysvar};
+use solana_program_test::{ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+    transport::TransportError,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+use crate::{
+    pda::{
+        find_auction_house_address, find_auction_house_fee_account_address,
+        find_auction_house_treasury_address, find_escrow_payment_address,
+        find_proceeds_route_address, find_program_as_signer_address, find_royalty_mandate_address,
+        find_trade_state_address,
+    },
+    AuctionHouse,
+};
+
+pub const ONE_SOL: u64 = 1_000_000_000;
+
+/// Program test with the Auction House and Token Metadata programs loaded.
+pub fn auction_house_program_test() -> ProgramTest {
+    let mut program = ProgramTest::new("mpl_auction_house", crate::id(), None);
+    program.add_program("mpl_token_metadata", mpl_token_metadata::id(), None);
+    program
+}
+
+async fn get_auction_house(
+    context: &mut ProgramTestContext,
+    auction_house_key: &Pubkey,
+) -> Result<AuctionHouse, TransportError> {
+    let account = context
+        .banks_client
+        .get_account(*auction_house_key)
+        .await?
+        .expect("account empty");
+
+    AuctionHouse::try_deserialize(&mut account.data.as_ref())
+        .map_err(|e| TransportError::IoError(io::Error::new(io::ErrorKind::InvalidData, e)))
+}
+
+/// Builder of an Auction House trading in native SOL, started on a fresh program test.
+pub struct AuctionHouseTest {
+    program_test: ProgramTest,
+    seller_fee_basis_points: u16,
+    requires_sign_off: bool,
+    can_change_sale_price: bool,
+}
+
+impl Default for AuctionHouseTest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AuctionHouseTest {
+    pub fn new() -> Self {
+        Self::with_program_test(auction_house_program_test())
+    }
+
+    /// Start from a program test with additional programs or accounts loaded.
+    pub fn with_program_test(program_test: ProgramTest) -> Self {
+        Self {
+            program_test,
+            seller_fee_basis_points: 100,
+            requires_sign_off: false,
+            can_change_sale_price: false,
+        }
+    }
+
+    pub fn seller_fee_basis_points(mut self, seller_fee_basis_points: u16) -> Self {
+        self.seller_fee_basis_points = seller_fee_basis_points;
+        self
+    }
+
+    pub fn requires_sign_off(mut self, requires_sign_off: bool) -> Self {
+        self.requires_sign_off = requires_sign_off;
+        self
+    }
+
+    pub fn can_change_sale_price(mut self, can_change_sale_price: bool) -> Self {
+        self.can_change_sale_price = can_change_sale_price;
+        self
+    }
+
+    /// Start the program test and create the Auction House with a funded authority.
+    pub async fn start(self) -> Result<AuctionHouseContext, TransportError> {
+        let mut context = self.program_test.start_with_context().await;
+        let authority = Keypair::new();
+        airdrop(&mut context, &authority.pubkey(), 10 * ONE_SOL).await?;
+
+        let treasury_mint = spl_token::native_mint::id();
+        let (auction_house_key, bump) =
+            find_auction_house_address(&authority.pubkey(), &treasury_mint);
+        let (auction_house_fee_account, fee_payer_bump) =
+            find_auction_house_fee_account_address(&auction_house_key);
+        let (auction_house_treasury, treasury_bump) =
+            find_auction_house_treasury_address(&auction_house_key);
+
+        let accounts = crate::accounts::CreateAuctionHouse {
+            treasury_mint,
+            payer: authority.pubkey(),
+            authority: authority.pubkey(),
+            fee_withdrawal_destination: authority.pubkey(),
+            treasury_withdrawal_destination: authority.pubkey(),
+            treasury_withdrawal_destination_owner: authority.pubkey(),
+            auction_house: auction_house_key,
+            auction_house_fee_account,
+            auction_house_treasury,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            ata_program: spl_associated_token_account::id(),
+            rent: sysvar::rent::id(),
+        }
+        .to_account_metas(None);
+        let data = crate::instruction::CreateAuctionHouse {
+            _bump: bump,
+            fee_payer_bump,
+            treasury_bump,
+            seller_fee_basis_points: self.seller_fee_basis_points,
+            requires_sign_off: self.requires_sign_off,
+            can_change_sale_price: self.can_change_sale_price,
+        }
+        .data();
+        let tx = Transaction::new_signed_with_payer(
+            &[Instruction {
+                program_id: crate::id(),
+                data,
+                accounts,
+            }],
+            Some(&authority.pubkey()),
+            &[&authority],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await?;
+
+        // Fee account pays for trade states and receipts of sales the authority does not sign.
+        airdrop(&mut context, &auction_house_fee_account, 10 * ONE_SOL).await?;
+
+        let auction_house = get_auction_house(&mut context, &auction_house_key).await?;
+
+        Ok(AuctionHouseContext {
+            context,
+            authority,
+            auction_house_key,
+            auction_house,
+        })
+    }
+}
+
+/// Listing created by [`AuctionHouseContext::sell`].
+#[derive(Clone, Debug)]
+pub struct TestListing {
+    pub seller: Pubkey,
+    pub token_account: Pubkey,
+    pub seller_trade_state: Pubkey,
+    pub price: u64,
+    pub token_size: u64,
+}
+
+/// Bid created by [`AuctionHouseContext::bid`].
+#[derive(Clone, Debug)]
+pub struct TestBid {
+    pub buyer: Pubkey,
+    pub buyer_trade_state: Pubkey,
+    pub escrow_payment_account: Pubkey,
+    pub price: u64,
+    pub token_size: u64,
+}
+
+/// Started program test with a live Auction House.
+pub struct AuctionHouseContext {
+    pub context: ProgramTestContext,
+    pub authority: Keypair,
+    pub auction_house_key: Pubkey,
+    pub auction_house: AuctionHouse,
+}
+
+impl AuctionHouseContext {
+    /// Refresh `auction_house` from the chain, e.g. after `update_auction_house`.
+    pub async fn reload(&mut self) -> Result<(), TransportError> {
+        self.auction_house = get_auction_house(&mut self.context, &self.auction_house_key).await?;
+
+        Ok(())
+    }
+
+    pub async fn airdrop(&mut self, receiver: &Pubkey, amount: u64) -> Result<(), TransportError> {
+        airdrop(&mut self.context, receiver, amount).await
+    }
+
+    /// New wallet funded with `amount` lamports.
+    pub async fn funded_wallet(&mut self, amount: u64) -> Result<Keypair, TransportError> {
+        let wallet = Keypair::new();
+        self.airdrop(&wallet.pubkey(), amount).await?;
+
+        Ok(wallet)
+    }
+
+    /// Sign and send `instructions` paid by the first of `signers`.
+    pub async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), TransportError> {
+        let tx = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&signers[0].pubkey()),
+            &signers.to_vec(),
+            self.context.last_blockhash,
+        );
+
+        self.context.banks_client.process_transaction(tx).await
+    }
+
+    /// Mint a single NFT held by `owner` in its associated token account. Creators other than the
+    /// program test payer, which is the update authority, must be unverified.
+    pub async fn mint_nft(
+        &mut self,
+        owner: &Keypair,
+        creators: Vec<Creator>,
+        seller_fee_basis_points: u16,
+    ) -> Result<Metadata, TransportError> {
+        let nft = Metadata {
+            token: Keypair::from_bytes(&owner.to_bytes()).unwrap(),
+            ..Metadata::new()
+        };
+        nft.create(
+            &mut self.context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            (!creators.is_empty()).then_some(creators),
+            seller_fee_basis_points,
+            false,
+            1,
+        )
+        .await?;
+
+        Ok(nft)
+    }
+
+    /// List `token_size` tokens of `nft` by its owner at `price`.
+    pub async fn sell(
+        &mut self,
+        nft: &Metadata,
+        price: u64,
+        token_size: u64,
+    ) -> Result<TestListing, TransportError> {
+        let ah = &self.auction_house;
+        let seller = nft.token.pubkey();
+        let token_account = get_associated_token_address(&seller, &nft.mint.pubkey());
+        let (seller_trade_state, trade_state_bump) = find_trade_state_address(
+            &seller,
+            &self.auction_house_key,
+            &token_account,
+            &ah.treasury_mint,
+            &nft.mint.pubkey(),
+            price,
+            token_size,
+        );
+        let (free_seller_trade_state, free_trade_state_bump) = find_trade_state_address(
+            &seller,
+            &self.auction_house_key,
+            &token_account,
+            &ah.treasury_mint,
+            &nft.mint.pubkey(),
+            0,
+            token_size,
+        );
+        let (program_as_signer, program_as_signer_bump) = find_program_as_signer_address();
+
+        let accounts = crate::accounts::Sell {
+            wallet: seller,
+            token_account,
+            metadata: nft.pubkey,
+            authority: ah.authority,
+            auction_house: self.auction_house_key,
+            auction_house_fee_account: ah.auction_house_fee_account,
+            seller_trade_state,
+            free_seller_trade_state,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            program_as_signer,
+            rent: sysvar::rent::id(),
+        }
+        .to_account_metas(None);
+        let data = crate::instruction::Sell {
+            trade_state_bump,
+            free_trade_state_bump,
+            program_as_signer_bump,
+            token_size,
+            buyer_price: price,
+        }
+        .data();
+
+        self.process(
+            &[Instruction {
+                program_id: crate::id(),
+                data,
+                accounts,
+            }],
+            &[&nft.token],
+        )
+        .await?;
+
+        Ok(TestListing {
+            seller,
+            token_account,
+            seller_trade_state,
+            price,
+            token_size,
+        })
+    }
+
+    /// Bid `price` for `token_size` tokens of `nft` held by its owner, escrowing the price from `buyer`.
+    pub async fn bid(
+        &mut self,
+        nft: &Metadata,
+        buyer: &Keypair,
+        price: u64,
+        token_size: u64,
+    ) -> Result<TestBid, TransportError> {
+        let ah = &self.auction_house;
+        let token_account = get_associated_token_address(&nft.token.pubkey(), &nft.mint.pubkey());
+        let (buyer_trade_state, trade_state_bump) = find_trade_state_address(
+            &buyer.pubkey(),
+            &self.auction_house_key,
+            &token_account,
+            &ah.treasury_mint,
+            &nft.mint.pubkey(),
+            price,
+            token_size,
+        );
+        let (escrow_payment_account, escrow_payment_bump) =
+            find_escrow_payment_address(&self.auction_house_key, &buyer.pubkey());
+
+        let accounts = crate::accounts::Buy {
+            wallet: buyer.pubkey(),
+            payment_account: buyer.pubkey(),
+            transfer_authority: buyer.pubkey(),
+            treasury_mint: ah.treasury_mint,
+            token_account,
+            metadata: nft.pubkey,
+            escrow_payment_account,
+            authority: ah.authority,
+            auction_house: self.auction_house_key,
+            auction_house_fee_account: ah.auction_house_fee_account,
+            buyer_trade_state,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            rent: sysvar::rent::id(),
+        }
+        .to_account_metas(None);
+        let data = crate::instruction::Buy {
+            trade_state_bump,
+            escrow_payment_bump,
+            buyer_price: price,
+            token_size,
+        }
+        .data();
+
+        self.process(
+            &[Instruction {
+                program_id: crate::id(),
+                data,
+                accounts,
+            }],
+            &[buyer],
+        )
+        .await?;
+
+        Ok(TestBid {
+            buyer: buyer.pubkey(),
+            buyer_trade_state,
+            escrow_payment_account,
+            price,
+            token_size,
+        })
+    }
+
+    /// Match `listing` and `bid` of `nft` signed by the authority, paying royalties to every creator
+    /// of the NFT.
+    pub async fn execute_sale(
+        &mut self,
+        nft: &Metadata,
+        listing: &TestListing,
+        bid: &TestBid,
+    ) -> Result<(), TransportError> {
+        self.execute_sale_with_remaining_accounts(nft, listing, bid, vec![])
+            .await
+    }
+
+    /// [`Self::execute_sale`] with `remaining_accounts` passed after the creators and the proceeds route,
+    /// for the optional accounts of the Auction House features enabled.
+    pub async fn execute_sale_with_remaining_accounts(
+        &mut self,
+        nft: &Metadata,
+        listing: &TestListing,
+        bid: &TestBid,
+        remaining_accounts: Vec<AccountMeta>,
+    ) -> Result<(), TransportError> {
+        let ah = &self.auction_house;
+        let (free_trade_state, free_trade_state_bump) = find_trade_state_address(
+            &listing.seller,
+            &self.auction_house_key,
+            &listing.token_account,
+            &ah.treasury_mint,
+            &nft.mint.pubkey(),
+            0,
+            listing.token_size,
+        );
+        let (_, escrow_payment_bump) =
+            find_escrow_payment_address(&self.auction_house_key, &bid.buyer);
+        let (program_as_signer, program_as_signer_bump) = find_program_as_signer_address();
+
+        let mut accounts = crate::accounts::ExecuteSale {
+            buyer: bid.buyer,
+            seller: listing.seller,
+            token_account: listing.token_account,
+            token_mint: nft.mint.pubkey(),
+            metadata: nft.pubkey,
+            treasury_mint: ah.treasury_mint,
+            escrow_payment_account: bid.escrow_payment_account,
+            seller_payment_receipt_account: listing.seller,
+            buyer_receipt_token_account: get_associated_token_address(
+                &bid.buyer,
+                &nft.mint.pubkey(),
+            ),
+            authority: ah.authority,
+            auction_house: self.auction_house_key,
+            auction_house_fee_account: ah.auction_house_fee_account,
+            auction_house_treasury: ah.auction_house_treasury,
+            buyer_trade_state: bid.buyer_trade_state,
+            seller_trade_state: listing.seller_trade_state,
+            free_trade_state,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            ata_program: spl_associated_token_account::id(),
+            program_as_signer,
+            rent: sysvar::rent::id(),
+        }
+        .to_account_metas(None);
+        let creators = nft.get_data(&mut self.context).await.data.creators;
+        accounts.extend(creators.unwrap_or_default().iter().flat_map(|creator| {
+            [
+                AccountMeta::new(creator.address, false),
+                AccountMeta::new_readonly(find_royalty_mandate_address(&creator.address).0, false),
+            ]
+        }));
+        accounts.push(AccountMeta::new_readonly(
+            find_proceeds_route_address(&listing.seller, &listing.seller_trade_state).0,
+            false,
+        ));
+        accounts.extend(remaining_accounts);
+        let data = crate::instruction::ExecuteSale {
+            escrow_payment_bump,
+            _free_trade_state_bump: free_trade_state_bump,
+            program_as_signer_bump,
+            token_size: listing.token_size,
+            buyer_price: bid.price,
+            partial_order_size: None,
+            partial_order_price: None,
+        }
+        .data();
+
+        let authority = Keypair::from_bytes(&self.authority.to_bytes()).unwrap();
+        self.process(
+            &[Instruction {
+                program_id: crate::id(),
+                data,
+                accounts,
+            }],
+            &[&authority],
+        )
+        .await
+    }
+}
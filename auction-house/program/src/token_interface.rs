@@ -0,0 +1,364 @@
+//! Token program interface shared by SPL Token and Token-2022 treasuries.
+//!
+//! Token-2022 keeps the SPL Token layout for the base mint and account state and appends its extensions as
+//! type-length-value entries after an account type byte, so only the extensions Auction House accounts for are
+//! parsed here.
+use crate::errors::AuctionHouseError;
+
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        instruction::Instruction,
+        program::invoke_signed,
+        program_pack::{IsInitialized, Pack},
+    },
+};
+use spl_token::state::{Account as SplAccount, Mint as SplMint};
+use std::slice::Iter;
+
+pub mod spl_token_2022 {
+    anchor_lang::declare_id!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+}
+
+/// Offset of the Token-2022 account type byte, extended mints are padded to the size of a token account.
+const ACCOUNT_TYPE_OFFSET: usize = SplAccount::LEN;
+const ACCOUNT_TYPE_MINT: u8 = 1;
+const TLV_HEADER_LEN: usize = 4;
+const EXTENSION_TYPE_UNINITIALIZED: u16 = 0;
+const EXTENSION_TYPE_TRANSFER_FEE_CONFIG: u16 = 1;
+const EXTENSION_TYPE_NON_TRANSFERABLE: u16 = 9;
+const EXTENSION_TYPE_TRANSFER_HOOK: u16 = 14;
+const EXTENSION_TYPE_CONFIDENTIAL_TRANSFER_FEE_CONFIG: u16 = 16;
+const EXTENSION_TYPE_PAUSABLE: u16 = 26;
+/// Token accounts of a mint with a transfer fee hold the withheld fee amount.
+const TRANSFER_FEE_AMOUNT_LEN: usize = 8;
+/// Token accounts of a non-transferable or pausable mint are flagged by an empty extension.
+const NON_TRANSFERABLE_ACCOUNT_LEN: usize = 0;
+const PAUSABLE_ACCOUNT_LEN: usize = 0;
+/// Token accounts of a mint with a transfer hook track whether a transfer is in progress.
+const TRANSFER_HOOK_ACCOUNT_LEN: usize = 1;
+/// Token accounts of a mint with a confidential transfer fee hold the encrypted withheld fee amount.
+const CONFIDENTIAL_TRANSFER_FEE_AMOUNT_LEN: usize = 64;
+/// Two authorities and the withheld amount precede the older and newer transfer fees.
+const TRANSFER_FEE_CONFIG_FEES_OFFSET: usize = 72;
+const TRANSFER_FEE_LEN: usize = 18;
+const ONE_IN_BASIS_POINTS: u128 = 10000;
+
+/// Transfer fee of a Token-2022 mint for an epoch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TransferFee {
+    pub epoch: u64,
+    pub maximum_fee: u64,
+    pub transfer_fee_basis_points: u16,
+}
+
+impl TransferFee {
+    fn unpack(data: &[u8]) -> Self {
+        TransferFee {
+            epoch: u64::from_le_bytes(data[0..8].try_into().unwrap()),
+            maximum_fee: u64::from_le_bytes(data[8..16].try_into().unwrap()),
+            transfer_fee_basis_points: u16::from_le_bytes(data[16..18].try_into().unwrap()),
+        }
+    }
+
+    /// Fee withheld from a transfer of `amount`, rounded up and capped at the maximum fee like Token-2022 does.
+    pub fn calculate_fee(&self, amount: u64) -> Option<u64> {
+        if self.transfer_fee_basis_points == 0 || amount == 0 {
+            return Some(0);
+        }
+        let numerator = (amount as u128).checked_mul(self.transfer_fee_basis_points as u128)?;
+        let fee = numerator
+            .checked_add(ONE_IN_BASIS_POINTS)?
+            .checked_sub(1)?
+            .checked_div(ONE_IN_BASIS_POINTS)?;
+        Some(std::cmp::min(fee, self.maximum_fee as u128) as u64)
+    }
+
+    /// Smallest amount to transfer so the destination receives at least `net_amount`.
+    pub fn calculate_gross_amount(&self, net_amount: u64) -> Option<u64> {
+        if self.transfer_fee_basis_points == 0 || net_amount == 0 {
+            return Some(net_amount);
+        }
+        let capped = net_amount.checked_add(self.maximum_fee)?;
+        let uncapped = if self.transfer_fee_basis_points as u128 >= ONE_IN_BASIS_POINTS {
+            capped
+        } else {
+            let denominator = ONE_IN_BASIS_POINTS - self.transfer_fee_basis_points as u128;
+            let gross = (net_amount as u128)
+                .checked_mul(ONE_IN_BASIS_POINTS)?
+                .checked_add(denominator - 1)?
+                .checked_div(denominator)?;
+            u64::try_from(gross).ok()?
+        };
+        let mut gross_amount = std::cmp::min(capped, uncapped);
+        // Rounding the fee up can leave the destination one unit short.
+        while gross_amount.checked_sub(self.calculate_fee(gross_amount)?)? < net_amount {
+            gross_amount = gross_amount.checked_add(1)?;
+        }
+        Some(gross_amount)
+    }
+}
+
+/// Return true if `key` is the SPL Token or the Token-2022 program.
+pub fn is_token_program(key: &Pubkey) -> bool {
+    *key == spl_token::id() || *key == spl_token_2022::id()
+}
+
+/// Return true if `token_program` is a token program owning the treasury mint, the native mint is owned by SPL Token.
+pub fn is_treasury_token_program(token_program: &Pubkey, treasury_mint: &AccountInfo) -> bool {
+    is_token_program(token_program) && treasury_mint.owner == token_program
+}
+
+pub fn assert_token_program(token_program: &AccountInfo) -> Result<()> {
+    if !is_token_program(token_program.key) {
+        return Err(AuctionHouseError::InvalidTokenProgram.into());
+    }
+    Ok(())
+}
+
+/// Unpack the base mint state of a mint owned by either token program.
+pub fn unpack_mint(mint_info: &AccountInfo) -> Result<SplMint> {
+    if !is_token_program(mint_info.owner) {
+        return Err(AuctionHouseError::IncorrectOwner.into());
+    }
+    let data = mint_info.try_borrow_data()?;
+    if data.len() < SplMint::LEN {
+        return Err(AuctionHouseError::UninitializedAccount.into());
+    }
+    let mint = SplMint::unpack_from_slice(&data[..SplMint::LEN])?;
+    if !mint.is_initialized() {
+        return Err(AuctionHouseError::UninitializedAccount.into());
+    }
+    Ok(mint)
+}
+
+/// Unpack the base account state of a token account owned by either token program.
+pub fn unpack_token_account(account_info: &AccountInfo) -> Result<SplAccount> {
+    if !is_token_program(account_info.owner) {
+        return Err(AuctionHouseError::IncorrectOwner.into());
+    }
+    let data = account_info.try_borrow_data()?;
+    if data.len() < SplAccount::LEN {
+        return Err(AuctionHouseError::UninitializedAccount.into());
+    }
+    let account = SplAccount::unpack_from_slice(&data[..SplAccount::LEN])?;
+    if !account.is_initialized() {
+        return Err(AuctionHouseError::UninitializedAccount.into());
+    }
+    Ok(account)
+}
+
+/// Type and value of the mint extensions in the type-length-value entries following the account type byte.
+fn mint_extensions(data: &[u8]) -> impl Iterator<Item = (u16, &[u8])> {
+    let mut offset = match data.get(ACCOUNT_TYPE_OFFSET) {
+        Some(&ACCOUNT_TYPE_MINT) => ACCOUNT_TYPE_OFFSET + 1,
+        _ => data.len(),
+    };
+    std::iter::from_fn(move || {
+        let header = data.get(offset..offset + TLV_HEADER_LEN)?;
+        let entry_type = u16::from_le_bytes([header[0], header[1]]);
+        let entry_len = u16::from_le_bytes([header[2], header[3]]) as usize;
+        if entry_type == EXTENSION_TYPE_UNINITIALIZED {
+            return None;
+        }
+        let value_start = offset + TLV_HEADER_LEN;
+        let value_end = value_start.checked_add(entry_len)?;
+        let value = data.get(value_start..value_end)?;
+        offset = value_end;
+        Some((entry_type, value))
+    })
+}
+
+/// Find the value of a mint extension in the type-length-value entries following the account type byte.
+fn find_mint_extension(data: &[u8], extension_type: u16) -> Option<&[u8]> {
+    mint_extensions(data)
+        .find(|(entry_type, _)| *entry_type == extension_type)
+        .map(|(_, value)| value)
+}
+
+/// Length of the account extension Token-2022 requires on token accounts of a mint with `mint_extension_type`.
+fn required_account_extension_len(mint_extension_type: u16) -> Option<usize> {
+    match mint_extension_type {
+        EXTENSION_TYPE_TRANSFER_FEE_CONFIG => Some(TRANSFER_FEE_AMOUNT_LEN),
+        EXTENSION_TYPE_NON_TRANSFERABLE => Some(NON_TRANSFERABLE_ACCOUNT_LEN),
+        EXTENSION_TYPE_TRANSFER_HOOK => Some(TRANSFER_HOOK_ACCOUNT_LEN),
+        EXTENSION_TYPE_CONFIDENTIAL_TRANSFER_FEE_CONFIG => {
+            Some(CONFIDENTIAL_TRANSFER_FEE_AMOUNT_LEN)
+        }
+        EXTENSION_TYPE_PAUSABLE => Some(PAUSABLE_ACCOUNT_LEN),
+        _ => None,
+    }
+}
+
+/// Transfer fee in effect for `epoch` if the mint is a Token-2022 mint with the transfer fee extension.
+pub fn get_transfer_fee(mint_info: &AccountInfo, epoch: u64) -> Result<Option<TransferFee>> {
+    if *mint_info.owner != spl_token_2022::id() {
+        return Ok(None);
+    }
+    let data = mint_info.try_borrow_data()?;
+    let config = match find_mint_extension(&data, EXTENSION_TYPE_TRANSFER_FEE_CONFIG) {
+        Some(config) if config.len() >= TRANSFER_FEE_CONFIG_FEES_OFFSET + 2 * TRANSFER_FEE_LEN => {
+            config
+        }
+        _ => return Ok(None),
+    };
+    let fees = &config[TRANSFER_FEE_CONFIG_FEES_OFFSET..];
+    let older_transfer_fee = TransferFee::unpack(&fees[..TRANSFER_FEE_LEN]);
+    let newer_transfer_fee = TransferFee::unpack(&fees[TRANSFER_FEE_LEN..2 * TRANSFER_FEE_LEN]);
+
+    Ok(Some(if epoch >= newer_transfer_fee.epoch {
+        newer_transfer_fee
+    } else {
+        older_transfer_fee
+    }))
+}
+
+/// Amount the destination receives when `amount` of the mint is transferred in the current epoch.
+pub fn amount_after_transfer_fee(mint_info: &AccountInfo, amount: u64) -> Result<u64> {
+    match get_transfer_fee(mint_info, Clock::get()?.epoch)? {
+        Some(transfer_fee) => transfer_fee
+            .calculate_fee(amount)
+            .and_then(|fee| amount.checked_sub(fee))
+            .ok_or_else(|| AuctionHouseError::NumericalOverflow.into()),
+        None => Ok(amount),
+    }
+}
+
+/// Amount to transfer in the current epoch so the destination receives `net_amount` of the mint.
+pub fn amount_before_transfer_fee(mint_info: &AccountInfo, net_amount: u64) -> Result<u64> {
+    match get_transfer_fee(mint_info, Clock::get()?.epoch)? {
+        Some(transfer_fee) => transfer_fee
+            .calculate_gross_amount(net_amount)
+            .ok_or_else(|| AuctionHouseError::NumericalOverflow.into()),
+        None => Ok(net_amount),
+    }
+}
+
+/// Space of a token account for the mint, including the extensions Token-2022 requires for it.
+pub fn token_account_len(mint_info: &AccountInfo) -> Result<usize> {
+    if *mint_info.owner != spl_token_2022::id() {
+        return Ok(SplAccount::LEN);
+    }
+    let data = mint_info.try_borrow_data()?;
+    let mut required_extensions = mint_extensions(&data)
+        .filter_map(|(entry_type, _)| required_account_extension_len(entry_type))
+        .peekable();
+    if required_extensions.peek().is_none() {
+        return Ok(SplAccount::LEN);
+    }
+    let extensions_len: usize = required_extensions
+        .map(|extension_len| TLV_HEADER_LEN + extension_len)
+        .sum();
+    Ok(ACCOUNT_TYPE_OFFSET + 1 + extensions_len)
+}
+
+pub fn get_associated_token_address_with_program_id(
+    wallet: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &[wallet.as_ref(), token_program.as_ref(), mint.as_ref()],
+        &spl_associated_token_account::id(),
+    )
+    .0
+}
+
+/// Instruction creating the associated token account of `wallet` under either token program.
+pub fn create_associated_token_account(
+    payer: &Pubkey,
+    wallet: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Instruction {
+    let mut instruction =
+        spl_associated_token_account::create_associated_token_account(payer, wallet, mint);
+    instruction.accounts[1].pubkey =
+        get_associated_token_address_with_program_id(wallet, mint, token_program);
+    instruction.accounts[5].pubkey = *token_program;
+    instruction
+}
+
+/// Transfer `amount` of the mint with the token program owning it. Token-2022 transfers are checked transfers, which
+/// the transfer fee extension requires.
+#[allow(clippy::too_many_arguments)]
+pub fn transfer<'a>(
+    token_program: &AccountInfo<'a>,
+    source: &AccountInfo<'a>,
+    mint: &AccountInfo<'a>,
+    destination: &AccountInfo<'a>,
+    authority: &AccountInfo<'a>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    assert_token_program(token_program)?;
+    if *token_program.key == spl_token::id() {
+        invoke_signed(
+            &spl_token::instruction::transfer(
+                token_program.key,
+                source.key,
+                destination.key,
+                authority.key,
+                &[],
+                amount,
+            )?,
+            &[
+                source.clone(),
+                destination.clone(),
+                token_program.clone(),
+                authority.clone(),
+            ],
+            signer_seeds,
+        )?;
+    } else {
+        let decimals = unpack_mint(mint)?.decimals;
+        // The SPL Token builder only accepts its own program id, the instruction layout is shared.
+        let mut instruction = spl_token::instruction::transfer_checked(
+            &spl_token::id(),
+            source.key,
+            mint.key,
+            destination.key,
+            authority.key,
+            &[],
+            amount,
+            decimals,
+        )?;
+        instruction.program_id = *token_program.key;
+        invoke_signed(
+            &instruction,
+            &[
+                source.clone(),
+                mint.clone(),
+                destination.clone(),
+                token_program.clone(),
+                authority.clone(),
+            ],
+            signer_seeds,
+        )?;
+    }
+    Ok(())
+}
+
+/// Token program moving the treasury mint in handlers whose `token_program` moves the SPL Token being sold. A
+/// treasury mint owned by another token program must be followed by that program as the leading remaining account.
+pub fn next_treasury_token_program<'a>(
+    remaining_accounts: &mut Iter<AccountInfo<'a>>,
+    treasury_mint: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    is_native: bool,
+) -> Result<AccountInfo<'a>> {
+    if is_native || treasury_mint.owner == token_program.key {
+        return Ok(token_program.clone());
+    }
+
+    match remaining_accounts.as_slice().first() {
+        Some(treasury_token_program)
+            if treasury_token_program.key == treasury_mint.owner
+                && is_token_program(treasury_token_program.key) =>
+        {
+            remaining_accounts.next();
+            Ok(treasury_token_program.clone())
+        }
+        _ => Err(AuctionHouseError::InvalidTokenProgram.into()),
+    }
+}
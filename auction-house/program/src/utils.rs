@@ -1,30 +1,198 @@
 use crate::{
-    constants::*, errors::AuctionHouseError, AuctionHouse, Auctioneer, AuthorityScope, PREFIX,
+    constants::*, errors::AuctionHouseError, metadata_reader::MetadataRoyalties,
+    token_interface::*, AhEventKind, AhEventV1, AuctionHouse, Auctioneer, AuthorityScope,
+    FeeAccountLowBalance, FreeSaleCounter, LastSale, LinkedWallet, ProceedsRoute, RoyaltyMandate,
+    PREFIX,
 };
 
 use anchor_lang::{
     prelude::*,
     solana_program::{
+        ed25519_program, keccak,
+        log::sol_log_data,
         program::invoke_signed,
         program_memory::sol_memcmp,
         program_option::COption,
         program_pack::{IsInitialized, Pack},
         pubkey::PUBKEY_BYTES,
         system_instruction,
+        sysvar::{self, instructions::get_instruction_relative},
     },
 };
-use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_spl::token::{Mint, TokenAccount};
 use arrayref::array_ref;
-use metaplex_token_metadata::state::Metadata;
-use spl_associated_token_account::get_associated_token_address;
 use spl_token::{instruction::initialize_account2, state::Account as SplAccount};
 use std::{convert::TryInto, slice::Iter};
+/// Emit a [`FeeAccountLowBalance`] event if the fee account balance is below the threshold configured for the Auction House.
+pub fn emit_if_fee_account_low(auction_house: &Account<AuctionHouse>, fee_account: &AccountInfo) {
+    let threshold = auction_house.fee_account_low_balance_threshold;
+    let balance = fee_account.lamports();
+    if balance < threshold {
+        emit!(FeeAccountLowBalance {
+            auction_house: auction_house.key(),
+            auction_house_fee_account: fee_account.key(),
+            balance,
+            threshold,
+        });
+    }
+}
+
+/// Log an [`AhEventV1`] with `sol_log_data`, as the fields [`AH_EVENT_TAG`], [`AH_EVENT_SCHEMA_VERSION`] and the borsh encoded event.
+pub fn emit_ah_event(
+    kind: AhEventKind,
+    auction_house: Pubkey,
+    mint: Option<Pubkey>,
+    price: u64,
+    wallets: Vec<Pubkey>,
+) -> Result<()> {
+    let event = AhEventV1 {
+        kind,
+        ah: auction_house,
+        mint,
+        price,
+        wallets,
+        slot: Clock::get()?.slot,
+    };
+    sol_log_data(&[
+        AH_EVENT_TAG.as_bytes(),
+        &[AH_EVENT_SCHEMA_VERSION],
+        &event.try_to_vec()?,
+    ]);
+
+    Ok(())
+}
+
+/// Reject the sale if the buyer and seller are the same wallet or share a group in the linked-wallet registry.
+/// Registry entries that were never created are passed as empty accounts.
+pub fn assert_not_self_trade(
+    auction_house: &Pubkey,
+    buyer: &Pubkey,
+    seller: &Pubkey,
+    buyer_linked_wallet: &AccountInfo,
+    seller_linked_wallet: &AccountInfo,
+) -> Result<()> {
+    if buyer == seller {
+        return Err(AuctionHouseError::SelfTradeNotAllowed.into());
+    }
+
+    let buyer_group = get_linked_wallet_group(auction_house, buyer, buyer_linked_wallet)?;
+    let seller_group = get_linked_wallet_group(auction_house, seller, seller_linked_wallet)?;
+
+    if let (Some(buyer_group), Some(seller_group)) = (buyer_group, seller_group) {
+        if buyer_group == seller_group {
+            return Err(AuctionHouseError::SelfTradeNotAllowed.into());
+        }
+    }
+
+    Ok(())
+}
+
+fn get_linked_wallet_group(
+    auction_house: &Pubkey,
+    wallet: &Pubkey,
+    linked_wallet: &AccountInfo,
+) -> Result<Option<Pubkey>> {
+    assert_derivation(
+        &crate::id(),
+        linked_wallet,
+        &[
+            PREFIX.as_bytes(),
+            auction_house.as_ref(),
+            LINKED_WALLET.as_bytes(),
+            wallet.as_ref(),
+        ],
+    )?;
+
+    if linked_wallet.data_is_empty() {
+        return Ok(None);
+    }
+
+    assert_owned_by(linked_wallet, &crate::id())?;
+    let entry = LinkedWallet::try_deserialize(&mut linked_wallet.try_borrow_data()?.as_ref())?;
+
+    Ok(Some(entry.group))
+}
+
+/// Return the account royalties of `creator_info` are paid to. The creator is followed in the remaining accounts by
+/// its [`RoyaltyMandate`] address and, if the creator set a mandate, the mandated destination. Otherwise royalties go
+/// to the creator itself.
+pub fn next_royalty_payee<'a, 'b>(
+    remaining_accounts: &mut Iter<'b, AccountInfo<'a>>,
+    creator_info: &'b AccountInfo<'a>,
+) -> Result<&'b AccountInfo<'a>> {
+    let mandate_info = next_account_info(remaining_accounts)?;
+    if mandate_info.owner != &crate::id() || mandate_info.data_is_empty() {
+        // The mandate address is required even when unset, so an existing mandate can't be left out.
+        if crate::pda::find_royalty_mandate_address(creator_info.key).0 != *mandate_info.key {
+            return Err(AuctionHouseError::InvalidRoyaltyMandate.into());
+        }
+        return Ok(creator_info);
+    }
+    let destination_info = next_account_info(remaining_accounts)?;
+
+    let mandate = RoyaltyMandate::try_deserialize(&mut mandate_info.try_borrow_data()?.as_ref())?;
+    let mandate_key = Pubkey::create_program_address(
+        &[
+            PREFIX.as_bytes(),
+            ROYALTY_MANDATE.as_bytes(),
+            creator_info.key.as_ref(),
+            &[mandate.bump],
+        ],
+        &crate::id(),
+    )
+    .map_err(|_| AuctionHouseError::InvalidRoyaltyMandate)?;
+    if mandate_key != *mandate_info.key || mandate.destination != *destination_info.key {
+        return Err(AuctionHouseError::InvalidRoyaltyMandate.into());
+    }
+
+    Ok(destination_info)
+}
+
+/// Return the destination the seller routed the proceeds of the listing under `seller_trade_state` to. The listing
+/// [`ProceedsRoute`] address follows the creator accounts in the remaining accounts. Without a route, proceeds go to
+/// the seller.
+pub fn next_proceeds_destination(
+    remaining_accounts: &mut Iter<AccountInfo>,
+    seller: &Pubkey,
+    seller_trade_state: &Pubkey,
+) -> Result<Option<Pubkey>> {
+    let route_info = next_account_info(remaining_accounts)?;
+    if route_info.owner != &crate::id() || route_info.data_is_empty() {
+        // The route address is required even when unset, so an existing route can't be left out.
+        if crate::pda::find_proceeds_route_address(seller, seller_trade_state).0 != *route_info.key
+        {
+            return Err(AuctionHouseError::InvalidProceedsRoute.into());
+        }
+        return Ok(None);
+    }
+
+    let route = ProceedsRoute::try_deserialize(&mut route_info.try_borrow_data()?.as_ref())?;
+    let route_key = Pubkey::create_program_address(
+        &[
+            PREFIX.as_bytes(),
+            PROCEEDS_ROUTE.as_bytes(),
+            seller.as_ref(),
+            seller_trade_state.as_ref(),
+            &[route.bump],
+        ],
+        &crate::id(),
+    )
+    .map_err(|_| AuctionHouseError::InvalidProceedsRoute)?;
+    if route_key != *route_info.key {
+        return Err(AuctionHouseError::InvalidProceedsRoute.into());
+    }
+
+    Ok(Some(route.destination))
+}
+
 pub fn assert_is_ata(ata: &AccountInfo, wallet: &Pubkey, mint: &Pubkey) -> Result<SplAccount> {
-    assert_owned_by(ata, &spl_token::id())?;
-    let ata_account: SplAccount = assert_initialized(ata)?;
+    let ata_account = unpack_token_account(ata)?;
     assert_keys_equal(ata_account.owner, *wallet)?;
     assert_keys_equal(ata_account.mint, *mint)?;
-    assert_keys_equal(get_associated_token_address(wallet, mint), *ata.key)?;
+    assert_keys_equal(
+        get_associated_token_address_with_program_id(wallet, mint, ata.owner),
+        *ata.key,
+    )?;
     Ok(ata_account)
 }
 
@@ -48,11 +216,7 @@ pub fn make_ata<'a>(
     };
 
     invoke_signed(
-        &spl_associated_token_account::create_associated_token_account(
-            fee_payer.key,
-            wallet.key,
-            mint.key,
-        ),
+        &create_associated_token_account(fee_payer.key, wallet.key, mint.key, token_program.key),
         &[
             ata,
             wallet,
@@ -169,6 +333,247 @@ pub fn assert_keys_equal(key1: Pubkey, key2: Pubkey) -> Result<()> {
     }
 }
 
+/// Return whether `wallet` is a verified creator of the NFT described by `metadata_info`.
+pub fn is_verified_creator(metadata_info: &AccountInfo, wallet: &Pubkey) -> Result<bool> {
+    let data = metadata_info.try_borrow_data()?;
+    let metadata = MetadataRoyalties::from_bytes(&data)?;
+
+    Ok(metadata
+        .creators()
+        .any(|creator| creator.verified && creator.address == *wallet))
+}
+
+/// Assert that the Auction House authority has not paused `operation`, one of the `PAUSE_*` bits.
+pub fn assert_not_paused(auction_house: &AuctionHouse, operation: u8) -> Result<()> {
+    if auction_house.paused_operations & operation != 0 {
+        return Err(AuctionHouseError::OperationPaused.into());
+    }
+
+    Ok(())
+}
+
+/// Bump of the program as signer PDA, derived for Auction Houses created before it was stored.
+pub fn get_program_as_signer_bump(auction_house: &AuctionHouse) -> u8 {
+    match auction_house.program_as_signer_bump {
+        0 => crate::pda::find_program_as_signer_address().1,
+        bump => bump,
+    }
+}
+
+/// Assert that `price` is a multiple of the Auction House tick size, a tick size of 0 accepts any price.
+pub fn assert_price_on_tick(auction_house: &AuctionHouse, price: u64) -> Result<()> {
+    if auction_house.tick_size > 0 && price % auction_house.tick_size != 0 {
+        return Err(AuctionHouseError::PriceNotOnTick.into());
+    }
+
+    Ok(())
+}
+
+/// Count a free sale against the seller's cap for the current epoch, creating the counter PDA on the first free sale.
+#[allow(clippy::too_many_arguments)]
+pub fn record_free_sale<'a>(
+    auction_house: &AuctionHouse,
+    auction_house_key: &Pubkey,
+    seller: &Pubkey,
+    free_sale_counter_info: &AccountInfo<'a>,
+    fee_payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    rent: &AccountInfo<'a>,
+    fee_payer_seeds: &[&[u8]],
+) -> Result<()> {
+    let (free_sale_counter_key, free_sale_counter_bump) = Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            auction_house_key.as_ref(),
+            FREE_SALE_COUNTER.as_bytes(),
+            seller.as_ref(),
+        ],
+        &crate::id(),
+    );
+    if free_sale_counter_info.key() != free_sale_counter_key {
+        return Err(AuctionHouseError::InvalidFreeSaleCounter.into());
+    }
+
+    let epoch = Clock::get()?.epoch;
+    let mut free_sale_counter = if free_sale_counter_info.data_is_empty() {
+        create_or_allocate_account_raw(
+            crate::id(),
+            free_sale_counter_info,
+            rent,
+            system_program,
+            fee_payer,
+            FREE_SALE_COUNTER_SIZE,
+            fee_payer_seeds,
+            &[
+                PREFIX.as_bytes(),
+                auction_house_key.as_ref(),
+                FREE_SALE_COUNTER.as_bytes(),
+                seller.as_ref(),
+                &[free_sale_counter_bump],
+            ],
+        )?;
+
+        FreeSaleCounter {
+            auction_house: *auction_house_key,
+            seller: *seller,
+            epoch,
+            count: 0,
+            bump: free_sale_counter_bump,
+        }
+    } else {
+        if free_sale_counter_info.owner != &crate::id() {
+            return Err(AuctionHouseError::InvalidFreeSaleCounter.into());
+        }
+        FreeSaleCounter::try_deserialize(&mut free_sale_counter_info.try_borrow_data()?.as_ref())?
+    };
+
+    // The cap is rolling per epoch, the first free sale of a new epoch starts from zero.
+    if free_sale_counter.epoch != epoch {
+        free_sale_counter.epoch = epoch;
+        free_sale_counter.count = 0;
+    }
+    free_sale_counter.count = free_sale_counter
+        .count
+        .checked_add(1)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+    if free_sale_counter.count > auction_house.max_free_sales_per_epoch {
+        return Err(AuctionHouseError::FreeSaleCapExceeded.into());
+    }
+
+    free_sale_counter.try_serialize(&mut *free_sale_counter_info.try_borrow_mut_data()?)?;
+
+    Ok(())
+}
+
+/// Assert that `wallet` was neither party of the last sale of `token_mint` within the listing cooldown. A last sale
+/// account not created yet means the mint never sold on the Auction House.
+pub fn assert_listing_cooldown_passed(
+    auction_house: &AuctionHouse,
+    auction_house_key: &Pubkey,
+    token_mint: &Pubkey,
+    wallet: &Pubkey,
+    last_sale_info: &AccountInfo,
+) -> Result<()> {
+    let (last_sale_key, _) = Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            auction_house_key.as_ref(),
+            LAST_SALE.as_bytes(),
+            token_mint.as_ref(),
+        ],
+        &crate::id(),
+    );
+    if last_sale_info.key() != last_sale_key {
+        return Err(AuctionHouseError::InvalidLastSale.into());
+    }
+    if last_sale_info.data_is_empty() {
+        return Ok(());
+    }
+    if last_sale_info.owner != &crate::id() {
+        return Err(AuctionHouseError::InvalidLastSale.into());
+    }
+
+    let last_sale = LastSale::try_deserialize(&mut last_sale_info.try_borrow_data()?.as_ref())?;
+    let cooldown_end = last_sale
+        .slot
+        .checked_add(auction_house.listing_cooldown_slots)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+    if (*wallet == last_sale.buyer || *wallet == last_sale.seller)
+        && Clock::get()?.slot < cooldown_end
+    {
+        return Err(AuctionHouseError::ListingCooldownActive.into());
+    }
+
+    Ok(())
+}
+
+/// Assert that `wallet` may list `token_mint` while the listing cooldown is on, the last sale PDA of the mint must be
+/// one of the remaining accounts of the listing instruction then.
+pub fn assert_listing_cooldown(
+    auction_house: &AuctionHouse,
+    auction_house_key: &Pubkey,
+    token_mint: &Pubkey,
+    wallet: &Pubkey,
+    remaining_accounts: &[AccountInfo],
+) -> Result<()> {
+    if auction_house.listing_cooldown_slots == 0 {
+        return Ok(());
+    }
+
+    let (last_sale_key, _) = crate::pda::find_last_sale_address(auction_house_key, token_mint);
+    let last_sale = remaining_accounts
+        .iter()
+        .find(|info| *info.key == last_sale_key)
+        .ok_or(AuctionHouseError::InvalidLastSale)?;
+    assert_listing_cooldown_passed(
+        auction_house,
+        auction_house_key,
+        token_mint,
+        wallet,
+        last_sale,
+    )
+}
+
+/// Record the parties and slot of a sale of `token_mint`, creating the last sale PDA on the first sale of the mint.
+#[allow(clippy::too_many_arguments)]
+pub fn record_last_sale<'a>(
+    auction_house_key: &Pubkey,
+    token_mint: &Pubkey,
+    buyer: &Pubkey,
+    seller: &Pubkey,
+    last_sale_info: &AccountInfo<'a>,
+    fee_payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    rent: &AccountInfo<'a>,
+    fee_payer_seeds: &[&[u8]],
+) -> Result<()> {
+    let (last_sale_key, last_sale_bump) = Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            auction_house_key.as_ref(),
+            LAST_SALE.as_bytes(),
+            token_mint.as_ref(),
+        ],
+        &crate::id(),
+    );
+    if last_sale_info.key() != last_sale_key {
+        return Err(AuctionHouseError::InvalidLastSale.into());
+    }
+
+    if last_sale_info.data_is_empty() {
+        create_or_allocate_account_raw(
+            crate::id(),
+            last_sale_info,
+            rent,
+            system_program,
+            fee_payer,
+            LAST_SALE_SIZE,
+            fee_payer_seeds,
+            &[
+                PREFIX.as_bytes(),
+                auction_house_key.as_ref(),
+                LAST_SALE.as_bytes(),
+                token_mint.as_ref(),
+                &[last_sale_bump],
+            ],
+        )?;
+    } else if last_sale_info.owner != &crate::id() {
+        return Err(AuctionHouseError::InvalidLastSale.into());
+    }
+
+    LastSale {
+        auction_house: *auction_house_key,
+        token_mint: *token_mint,
+        buyer: *buyer,
+        seller: *seller,
+        slot: Clock::get()?.slot,
+        bump: last_sale_bump,
+    }
+    .try_serialize(&mut *last_sale_info.try_borrow_mut_data()?)?;
+
+    Ok(())
+}
+
 #[derive(Debug, Clone)]
 pub enum BidType {
     PublicSale,
@@ -259,6 +664,7 @@ pub fn pay_auction_house_fees<'a>(
     auction_house: &anchor_lang::prelude::Account<'a, AuctionHouse>,
     auction_house_treasury: &AccountInfo<'a>,
     escrow_payment_account: &AccountInfo<'a>,
+    treasury_mint: &AccountInfo<'a>,
     token_program: &AccountInfo<'a>,
     system_program: &AccountInfo<'a>,
     signer_seeds: &[&[u8]],
@@ -272,21 +678,13 @@ pub fn pay_auction_house_fees<'a>(
         .checked_div(10000)
         .ok_or(AuctionHouseError::NumericalOverflow)? as u64;
     if !is_native {
-        invoke_signed(
-            &spl_token::instruction::transfer(
-                token_program.key,
-                escrow_payment_account.key,
-                auction_house_treasury.key,
-                &auction_house.key(),
-                &[],
-                total_fee,
-            )?,
-            &[
-                escrow_payment_account.clone(),
-                auction_house_treasury.clone(),
-                token_program.clone(),
-                auction_house.to_account_info(),
-            ],
+        transfer(
+            token_program,
+            escrow_payment_account,
+            treasury_mint,
+            auction_house_treasury,
+            &auction_house.to_account_info(),
+            total_fee,
             &[signer_seeds],
         )?;
     } else {
@@ -311,8 +709,8 @@ pub fn create_program_token_account_if_not_present<'a>(
     payment_account: &UncheckedAccount<'a>,
     system_program: &Program<'a, System>,
     fee_payer: &AccountInfo<'a>,
-    token_program: &Program<'a, Token>,
-    treasury_mint: &anchor_lang::prelude::Account<'a, Mint>,
+    token_program: &AccountInfo<'a>,
+    treasury_mint: &AccountInfo<'a>,
     owner: &AccountInfo<'a>,
     rent: &Sysvar<'a, Rent>,
     signer_seeds: &[&[u8]],
@@ -320,28 +718,32 @@ pub fn create_program_token_account_if_not_present<'a>(
     is_native: bool,
 ) -> Result<()> {
     if !is_native && payment_account.data_is_empty() {
+        assert_token_program(token_program)?;
         create_or_allocate_account_raw(
             *token_program.key,
             &payment_account.to_account_info(),
             &rent.to_account_info(),
             system_program,
             fee_payer,
-            spl_token::state::Account::LEN,
+            token_account_len(treasury_mint)?,
             fee_seeds,
             signer_seeds,
         )?;
         msg!("This.");
+        // Token-2022 shares the SPL Token instruction layout.
+        let mut instruction = initialize_account2(
+            &spl_token::id(),
+            &payment_account.key(),
+            &treasury_mint.key(),
+            &owner.key(),
+        )
+        .unwrap();
+        instruction.program_id = *token_program.key;
         invoke_signed(
-            &initialize_account2(
-                token_program.key,
-                &payment_account.key(),
-                &treasury_mint.key(),
-                &owner.key(),
-            )
-            .unwrap(),
+            &instruction,
             &[
-                token_program.to_account_info(),
-                treasury_mint.to_account_info(),
+                token_program.clone(),
+                treasury_mint.clone(),
                 payment_account.to_account_info(),
                 rent.to_account_info(),
                 owner.clone(),
@@ -370,8 +772,9 @@ pub fn pay_creator_fees<'a>(
     size: u64,
     is_native: bool,
 ) -> Result<u64> {
-    let metadata = Metadata::from_account_info(metadata_info)?;
-    let fees = metadata.data.seller_fee_basis_points;
+    let data = metadata_info.try_borrow_data()?;
+    let metadata = MetadataRoyalties::from_bytes(&data)?;
+    let fees = metadata.seller_fee_basis_points;
     let total_fee = (fees as u128)
         .checked_mul(size as u128)
         .ok_or(AuctionHouseError::NumericalOverflow)?
@@ -381,79 +784,70 @@ pub fn pay_creator_fees<'a>(
     let remaining_size = size
         .checked_sub(total_fee)
         .ok_or(AuctionHouseError::NumericalOverflow)?;
-    match metadata.data.creators {
-        Some(creators) => {
-            for creator in creators {
-                let pct = creator.share as u128;
-                let creator_fee =
-                    pct.checked_mul(total_fee as u128)
-                        .ok_or(AuctionHouseError::NumericalOverflow)?
-                        .checked_div(100)
-                        .ok_or(AuctionHouseError::NumericalOverflow)? as u64;
-                remaining_fee = remaining_fee
-                    .checked_sub(creator_fee)
-                    .ok_or(AuctionHouseError::NumericalOverflow)?;
-                let current_creator_info = next_account_info(remaining_accounts)?;
-                assert_keys_equal(creator.address, *current_creator_info.key)?;
-                if !is_native {
-                    let current_creator_token_account_info = next_account_info(remaining_accounts)?;
-                    if current_creator_token_account_info.data_is_empty() {
-                        make_ata(
-                            current_creator_token_account_info.to_account_info(),
-                            current_creator_info.to_account_info(),
-                            treasury_mint.to_account_info(),
-                            fee_payer.to_account_info(),
-                            ata_program.to_account_info(),
-                            token_program.to_account_info(),
-                            system_program.to_account_info(),
-                            rent.to_account_info(),
-                            fee_payer_seeds,
-                        )?;
-                    }
-                    assert_is_ata(
-                        current_creator_token_account_info,
-                        current_creator_info.key,
-                        &treasury_mint.key(),
+    if metadata.has_creators() {
+        for creator in metadata.creators() {
+            let pct = creator.share as u128;
+            let creator_fee = pct
+                .checked_mul(total_fee as u128)
+                .ok_or(AuctionHouseError::NumericalOverflow)?
+                .checked_div(100)
+                .ok_or(AuctionHouseError::NumericalOverflow)? as u64;
+            remaining_fee = remaining_fee
+                .checked_sub(creator_fee)
+                .ok_or(AuctionHouseError::NumericalOverflow)?;
+            let current_creator_info = next_account_info(remaining_accounts)?;
+            assert_keys_equal(creator.address, *current_creator_info.key)?;
+            let current_creator_info =
+                next_royalty_payee(remaining_accounts, current_creator_info)?;
+            if !is_native {
+                let current_creator_token_account_info = next_account_info(remaining_accounts)?;
+                if current_creator_token_account_info.data_is_empty() {
+                    make_ata(
+                        current_creator_token_account_info.to_account_info(),
+                        current_creator_info.to_account_info(),
+                        treasury_mint.to_account_info(),
+                        fee_payer.to_account_info(),
+                        ata_program.to_account_info(),
+                        token_program.to_account_info(),
+                        system_program.to_account_info(),
+                        rent.to_account_info(),
+                        fee_payer_seeds,
                     )?;
-                    if creator_fee > 0 {
-                        invoke_signed(
-                            &spl_token::instruction::transfer(
-                                token_program.key,
-                                escrow_payment_account.key,
-                                current_creator_token_account_info.key,
-                                payment_account_owner.key,
-                                &[],
-                                creator_fee,
-                            )?,
-                            &[
-                                escrow_payment_account.clone(),
-                                current_creator_token_account_info.clone(),
-                                token_program.clone(),
-                                payment_account_owner.clone(),
-                            ],
-                            &[signer_seeds],
-                        )?;
-                    }
-                } else if creator_fee > 0 {
-                    invoke_signed(
-                        &system_instruction::transfer(
-                            escrow_payment_account.key,
-                            current_creator_info.key,
-                            creator_fee,
-                        ),
-                        &[
-                            escrow_payment_account.clone(),
-                            current_creator_info.clone(),
-                            system_program.clone(),
-                        ],
+                }
+                assert_is_ata(
+                    current_creator_token_account_info,
+                    current_creator_info.key,
+                    &treasury_mint.key(),
+                )?;
+                if creator_fee > 0 {
+                    transfer(
+                        token_program,
+                        escrow_payment_account,
+                        treasury_mint,
+                        current_creator_token_account_info,
+                        payment_account_owner,
+                        creator_fee,
                         &[signer_seeds],
                     )?;
                 }
+            } else if creator_fee > 0 {
+                invoke_signed(
+                    &system_instruction::transfer(
+                        escrow_payment_account.key,
+                        current_creator_info.key,
+                        creator_fee,
+                    ),
+                    &[
+                        escrow_payment_account.clone(),
+                        current_creator_info.clone(),
+                        system_program.clone(),
+                    ],
+                    &[signer_seeds],
+                )?;
             }
         }
-        None => {
-            msg!("No creators found in metadata");
-        }
+    } else {
+        msg!("No creators found in metadata");
     }
     // Any dust is returned to the party posting the NFT
     Ok(remaining_size
@@ -461,6 +855,167 @@ pub fn pay_creator_fees<'a>(
         .ok_or(AuctionHouseError::NumericalOverflow)?)
 }
 
+/// Assert that a sale pays the full royalties when the Auction House enforces them. The leading remaining accounts
+/// must be exactly the metadata creators, in order, each followed by its [`RoyaltyMandate`] address, the mandated
+/// destination if set and then by the payee token account for SPL sales.
+pub fn assert_royalties_enforced(
+    remaining_accounts: &[AccountInfo],
+    metadata_info: &AccountInfo,
+    size: u64,
+    is_native: bool,
+) -> Result<()> {
+    let data = metadata_info.try_borrow_data()?;
+    let metadata = MetadataRoyalties::from_bytes(&data)?;
+    let fees = metadata.seller_fee_basis_points as u128;
+    let fee_product = fees
+        .checked_mul(size as u128)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+    // Free sales and sales too small to pay any royalty would leave creators with nothing.
+    if fees > 0 && fee_product < 10000 {
+        return Err(AuctionHouseError::RoyaltiesNotEnforced.into());
+    }
+
+    let remaining_accounts = &mut remaining_accounts.iter();
+    for creator in metadata.creators() {
+        let creator_info = remaining_accounts
+            .next()
+            .ok_or(AuctionHouseError::RoyaltiesNotEnforced)?;
+        if creator_info.key() != creator.address {
+            return Err(AuctionHouseError::RoyaltiesNotEnforced.into());
+        }
+        next_royalty_payee(remaining_accounts, creator_info)?;
+        if !is_native && remaining_accounts.next().is_none() {
+            return Err(AuctionHouseError::RoyaltiesNotEnforced.into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Message signed off-chain by a seller to authorize `post_signed_listing`. The `nonce` can only be posted once.
+pub fn signed_listing_message(
+    auction_house: &Pubkey,
+    token_account: &Pubkey,
+    buyer_price: u64,
+    token_size: u64,
+    expiry: i64,
+    nonce: u64,
+) -> Vec<u8> {
+    [
+        SIGNED_LISTING.as_bytes(),
+        auction_house.as_ref(),
+        token_account.as_ref(),
+        &buyer_price.to_le_bytes(),
+        &token_size.to_le_bytes(),
+        &expiry.to_le_bytes(),
+        &nonce.to_le_bytes(),
+    ]
+    .concat()
+}
+
+/// Assert that the previous instruction is an ed25519 program verification of a single `message` signature by
+/// `signer`, with the public key, signature and message all stored in that instruction.
+pub fn assert_ed25519_signature(
+    instruction_account: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+) -> Result<()> {
+    if ed25519_signer(instruction_account, message)? != *signer {
+        return Err(AuctionHouseError::InvalidListingSignature.into());
+    }
+
+    Ok(())
+}
+
+/// Key whose signature of `message` the previous instruction verifies through the ed25519 program, with the public
+/// key, signature and message all stored in that instruction.
+pub fn ed25519_signer(instruction_account: &AccountInfo, message: &[u8]) -> Result<Pubkey> {
+    let ed25519_instruction = get_instruction_relative(-1, instruction_account)
+        .map_err(|_| AuctionHouseError::InvalidListingSignature)?;
+    if ed25519_instruction.program_id != ed25519_program::id() {
+        return Err(AuctionHouseError::InvalidListingSignature.into());
+    }
+
+    // Layout: num_signatures(1), padding(1), signature_offset(2), signature_instruction_index(2),
+    // public_key_offset(2), public_key_instruction_index(2), message_data_offset(2), message_data_size(2),
+    // message_instruction_index(2), ...
+    let data = &ed25519_instruction.data;
+    if data.len() < 16 || data[0] != 1 {
+        return Err(AuctionHouseError::InvalidListingSignature.into());
+    }
+
+    let read_u16 = |offset: usize| u16::from_le_bytes(*array_ref![data, offset, 2]);
+    if read_u16(4) != u16::MAX || read_u16(8) != u16::MAX || read_u16(14) != u16::MAX {
+        return Err(AuctionHouseError::InvalidListingSignature.into());
+    }
+
+    let public_key_offset = read_u16(6) as usize;
+    let message_offset = read_u16(10) as usize;
+    let message_size = read_u16(12) as usize;
+    let public_key = data
+        .get(public_key_offset..public_key_offset + PUBKEY_BYTES)
+        .ok_or(AuctionHouseError::InvalidListingSignature)?;
+    let signed_message = data.get(message_offset..message_offset + message_size);
+
+    if signed_message != Some(message) {
+        return Err(AuctionHouseError::InvalidListingSignature.into());
+    }
+
+    Ok(Pubkey::new_from_array(*array_ref![public_key, 0, 32]))
+}
+
+/// Identity recorded in a custodial trade state for the user holding `user_key`, hashed so the key itself is only
+/// revealed by the approval of a sale.
+pub fn custodial_identity_hash(user_key: &Pubkey) -> [u8; 32] {
+    keccak::hashv(&[CUSTODIAL_IDENTITY.as_bytes(), user_key.as_ref()]).0
+}
+
+/// Message signed off-chain by the user of a custodial listing to approve its sale to `buyer`.
+pub fn custodial_approval_message(
+    auction_house: &Pubkey,
+    seller_trade_state: &Pubkey,
+    buyer: &Pubkey,
+    price: u64,
+    token_size: u64,
+) -> Vec<u8> {
+    [
+        CUSTODIAL_APPROVAL.as_bytes(),
+        auction_house.as_ref(),
+        seller_trade_state.as_ref(),
+        buyer.as_ref(),
+        &price.to_le_bytes(),
+        &token_size.to_le_bytes(),
+    ]
+    .concat()
+}
+
+/// User identity hash recorded in a trade state created by `custodial_sell`, if it was.
+pub fn read_custodial_identity(trade_state_data: &[u8]) -> Option<[u8; 32]> {
+    trade_state_data
+        .get(TRADE_STATE_WITH_RENT_PAYER_SIZE..CUSTODIAL_TRADE_STATE_SIZE)
+        .map(|identity| *array_ref![identity, 0, 32])
+}
+
+/// Check the previous instruction verifies the user of a custodial listing signed `message`, returning the user key.
+pub fn verify_custodial_approval(
+    instruction_account: &AccountInfo,
+    identity: &[u8; 32],
+    message: &[u8],
+) -> Result<Pubkey> {
+    if instruction_account.key() != sysvar::instructions::id() {
+        return Err(AuctionHouseError::InvalidCustodialApproval.into());
+    }
+
+    let user = ed25519_signer(instruction_account, message)
+        .map_err(|_| AuctionHouseError::InvalidCustodialApproval)?;
+    if custodial_identity_hash(&user) != *identity {
+        return Err(AuctionHouseError::InvalidCustodialApproval.into());
+    }
+
+    Ok(user)
+}
+
 /// Cheap method to just grab mint Pubkey from token account, instead of deserializing entire thing
 pub fn get_mint_from_token_account(token_account_info: &AccountInfo) -> Result<Pubkey> {
     // TokeAccount layout:   mint(32), owner(32), ...
@@ -608,6 +1163,59 @@ pub fn assert_valid_trade_state(
     }
 }
 
+/// Write the bump of a trade state created by `sell` or `buy` followed by the account which paid its rent.
+pub fn write_trade_state(trade_state: &AccountInfo, bump: u8, rent_payer: &Pubkey) -> Result<()> {
+    let mut data = trade_state.try_borrow_mut_data()?;
+    data[0] = bump;
+    data[TRADE_STATE_SIZE..TRADE_STATE_WITH_RENT_PAYER_SIZE].copy_from_slice(rent_payer.as_ref());
+    Ok(())
+}
+
+/// Record the user `identity` hash in a trade state allocated with [`CUSTODIAL_TRADE_STATE_SIZE`].
+pub fn write_custodial_identity(trade_state: &AccountInfo, identity: &[u8; 32]) -> Result<()> {
+    let mut data = trade_state.try_borrow_mut_data()?;
+    data[TRADE_STATE_WITH_RENT_PAYER_SIZE..CUSTODIAL_TRADE_STATE_SIZE].copy_from_slice(identity);
+    Ok(())
+}
+
+/// Record the `session_key` placing a bid in a trade state allocated with [`SESSION_TRADE_STATE_SIZE`].
+pub fn write_session_key(trade_state: &AccountInfo, session_key: &Pubkey) -> Result<()> {
+    let mut data = trade_state.try_borrow_mut_data()?;
+    data[TRADE_STATE_WITH_RENT_PAYER_SIZE..SESSION_TRADE_STATE_SIZE]
+        .copy_from_slice(session_key.as_ref());
+    Ok(())
+}
+
+/// Session key recorded in a trade state created by `session_private_bid`, if it was.
+pub fn read_session_key(trade_state_data: &[u8]) -> Option<Pubkey> {
+    trade_state_data
+        .get(TRADE_STATE_WITH_RENT_PAYER_SIZE..SESSION_TRADE_STATE_SIZE)
+        .map(|session_key| Pubkey::new_from_array(*array_ref![session_key, 0, 32]))
+}
+
+/// Account refunded the rent of a closed trade state: the rent payer recorded in it, which has to be one of
+/// `candidates`, or `fallback` for trade states created before rent payers were recorded.
+pub fn trade_state_rent_recipient<'a>(
+    trade_state_data: &[u8],
+    candidates: &[&AccountInfo<'a>],
+    fallback: &AccountInfo<'a>,
+) -> Result<AccountInfo<'a>> {
+    if trade_state_data.len() < TRADE_STATE_WITH_RENT_PAYER_SIZE {
+        return Ok(fallback.clone());
+    }
+
+    let rent_payer = Pubkey::new_from_array(*array_ref![
+        trade_state_data,
+        TRADE_STATE_SIZE,
+        PUBKEY_BYTES
+    ]);
+    candidates
+        .iter()
+        .find(|candidate| *candidate.key == rent_payer)
+        .map(|candidate| (*candidate).clone())
+        .ok_or_else(|| AuctionHouseError::RentPayerNotProvided.into())
+}
+
 pub fn rent_checked_sub(escrow_account: AccountInfo, diff: u64) -> Result<u64> {
     let rent_minimum: u64 = (Rent::get()?).minimum_balance(escrow_account.data_len());
     let account_lamports: u64 = escrow_account
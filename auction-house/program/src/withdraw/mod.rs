@@ -1,6 +1,9 @@
 use anchor_lang::{prelude::*, AnchorDeserialize};
 
-use crate::{constants::*, errors::*, utils::*, AuctionHouse, AuthorityScope, *};
+use crate::{
+    constants::*, errors::*, token_interface::*, utils::*, AhEventKind, AuctionHouse,
+    AuthorityScope, *,
+};
 
 /// Accounts for the [`withdraw` handler](auction_house/fn.withdraw.html).
 #[derive(Accounts)]
@@ -28,8 +31,9 @@ pub struct Withdraw<'info> {
     )]
     pub escrow_payment_account: UncheckedAccount<'info>,
 
+    /// CHECK: Checked in has_one constraint of the auction house.
     /// Auction House instance treasury mint account.
-    pub treasury_mint: Box<Account<'info, Mint>>,
+    pub treasury_mint: UncheckedAccount<'info>,
 
     /// CHECK: Validated in withdraw_logic.
     /// Auction House instance authority account.
@@ -62,7 +66,10 @@ pub struct Withdraw<'info> {
     )]
     pub auction_house_fee_account: UncheckedAccount<'info>,
 
-    pub token_program: Program<'info, Token>,
+    /// CHECK: Checked in constraint to be SPL Token or Token-2022.
+    /// Token program of the treasury mint.
+    #[account(constraint = is_treasury_token_program(token_program.key, &treasury_mint.to_account_info()) @ AuctionHouseError::InvalidTokenProgram)]
+    pub token_program: UncheckedAccount<'info>,
     pub system_program: Program<'info, System>,
     pub ata_program: Program<'info, AssociatedToken>,
     pub rent: Sysvar<'info, Rent>,
@@ -128,8 +135,9 @@ pub struct AuctioneerWithdraw<'info> {
     )]
     pub escrow_payment_account: UncheckedAccount<'info>,
 
+    /// CHECK: Checked in has_one constraint of the auction house.
     /// Auction House instance treasury mint account.
-    pub treasury_mint: Box<Account<'info, Mint>>,
+    pub treasury_mint: UncheckedAccount<'info>,
 
     /// CHECK: Validated in withdraw_logic.
     /// Auction House instance authority account.
@@ -178,7 +186,10 @@ pub struct AuctioneerWithdraw<'info> {
     )]
     pub ah_auctioneer_pda: UncheckedAccount<'info>,
 
-    pub token_program: Program<'info, Token>,
+    /// CHECK: Checked in constraint to be SPL Token or Token-2022.
+    /// Token program of the treasury mint.
+    #[account(constraint = is_treasury_token_program(token_program.key, &treasury_mint.to_account_info()) @ AuctionHouseError::InvalidTokenProgram)]
+    pub token_program: UncheckedAccount<'info>,
     pub system_program: Program<'info, System>,
     pub ata_program: Program<'info, AssociatedToken>,
     pub rent: Sysvar<'info, Rent>,
@@ -210,6 +221,115 @@ pub fn auctioneer_withdraw<'info>(
     withdraw_logic(&mut accounts, escrow_payment_bump, amount)
 }
 
+/// Accounts for the [`withdraw_v2` handler](auction_house/fn.withdraw_v2.html).
+#[derive(Accounts, Clone)]
+pub struct WithdrawV2<'info> {
+    /// CHECK: Validated in withdraw_logic.
+    /// User wallet account.
+    pub wallet: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in withdraw_logic.
+    /// SPL token account or native SOL account to transfer funds to. If the account is a native SOL account, this is the same as the wallet address.
+    #[account(mut)]
+    pub receipt_account: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint with the canonical bump.
+    /// Buyer escrow payment account PDA.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref()
+        ],
+        bump
+    )]
+    pub escrow_payment_account: UncheckedAccount<'info>,
+
+    /// CHECK: Checked in has_one constraint of the auction house.
+    /// Auction House instance treasury mint account.
+    pub treasury_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in withdraw_logic.
+    /// Auction House instance authority account.
+    pub authority: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority,
+        has_one=treasury_mint,
+        has_one=auction_house_fee_account
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance fee account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump=auction_house.fee_payer_bump
+    )]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Checked in constraint to be SPL Token or Token-2022.
+    /// Token program of the treasury mint.
+    #[account(constraint = is_treasury_token_program(token_program.key, &treasury_mint.to_account_info()) @ AuctionHouseError::InvalidTokenProgram)]
+    pub token_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    pub ata_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> From<WithdrawV2<'info>> for Withdraw<'info> {
+    fn from(a: WithdrawV2<'info>) -> Withdraw<'info> {
+        Withdraw {
+            wallet: a.wallet,
+            receipt_account: a.receipt_account,
+            escrow_payment_account: a.escrow_payment_account,
+            treasury_mint: a.treasury_mint,
+            authority: a.authority,
+            auction_house: a.auction_house,
+            auction_house_fee_account: a.auction_house_fee_account,
+            token_program: a.token_program,
+            system_program: a.system_program,
+            ata_program: a.ata_program,
+            rent: a.rent,
+        }
+    }
+}
+
+/// Withdraw, with the escrow payment bump derived on chain instead of passed by the client.
+pub fn withdraw_v2<'info>(
+    ctx: Context<'_, '_, '_, 'info, WithdrawV2<'info>>,
+    amount: u64,
+) -> Result<()> {
+    let auction_house = &ctx.accounts.auction_house;
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    let escrow_payment_bump = *ctx
+        .bumps
+        .get("escrow_payment_account")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+
+    let mut accounts: Withdraw<'info> = (*ctx.accounts).clone().into();
+
+    withdraw_logic(&mut accounts, escrow_payment_bump, amount)
+}
+
 #[allow(clippy::needless_lifetimes)]
 fn withdraw_logic<'info>(
     accounts: &mut Withdraw<'info>,
@@ -294,21 +414,14 @@ fn withdraw_logic<'info>(
         }
 
         assert_is_ata(receipt_account, &wallet.key(), &treasury_mint.key())?;
-        invoke_signed(
-            &spl_token::instruction::transfer(
-                token_program.key,
-                &escrow_payment_account.key(),
-                &receipt_account.key(),
-                &auction_house.key(),
-                &[],
-                amount,
-            )?,
-            &[
-                escrow_payment_account.to_account_info(),
-                receipt_account.to_account_info(),
-                token_program.to_account_info(),
-                auction_house.to_account_info(),
-            ],
+        // The escrow is debited `amount`, the receipt account gets it net of any transfer fee.
+        transfer(
+            token_program,
+            escrow_payment_account,
+            treasury_mint,
+            receipt_account,
+            &auction_house.to_account_info(),
+            amount,
             &[&ah_seeds],
         )?;
     } else {
@@ -330,5 +443,13 @@ fn withdraw_logic<'info>(
         )?;
     }
 
+    emit_ah_event(
+        AhEventKind::Withdraw,
+        auction_house_key,
+        None,
+        amount,
+        vec![wallet.key()],
+    )?;
+
     Ok(())
 }
@@ -0,0 +1,224 @@
+#![cfg(feature = "test-bpf")]
+
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use mpl_auction_house::{
+    assertions::*, constants::TRADE_STATE_WITH_RENT_PAYER_SIZE, errors::AuctionHouseError,
+    LenientPath, LenientPathTaken,
+};
+use solana_program::{
+    account_info::AccountInfo,
+    program_pack::Pack,
+    program_stubs::{set_syscall_stubs, SyscallStubs},
+    pubkey::Pubkey,
+    system_program,
+};
+use spl_token::state::{Account as SplAccount, AccountState, Mint as SplMint};
+use std::sync::{Mutex, Once};
+
+/// Data logged by every test of this file, the stubs are shared by all of them.
+static EVENTS: Mutex<Vec<Vec<u8>>> = Mutex::new(Vec::new());
+static CAPTURE_EVENTS: Once = Once::new();
+
+/// Syscall stubs recording the data events are logged with.
+struct EventLog;
+
+impl SyscallStubs for EventLog {
+    fn sol_log_data(&self, data: &[&[u8]]) {
+        EVENTS
+            .lock()
+            .unwrap()
+            .extend(data.iter().map(|field| field.to_vec()));
+    }
+}
+
+fn capture_events() {
+    CAPTURE_EVENTS.call_once(|| {
+        set_syscall_stubs(Box::new(EventLog));
+    });
+}
+
+/// `LenientPathTaken` events logged for `account`.
+fn lenient_paths_taken(account: &Pubkey) -> Vec<LenientPathTaken> {
+    EVENTS
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|data| data.starts_with(&LenientPathTaken::discriminator()))
+        .map(|data| LenientPathTaken::try_from_slice(&data[8..]).unwrap())
+        .filter(|event| event.account == *account)
+        .collect()
+}
+
+fn token_account_bytes(mint: &Pubkey, state: AccountState) -> Vec<u8> {
+    let mut data = vec![0; SplAccount::LEN];
+    SplAccount {
+        mint: *mint,
+        owner: Pubkey::new_unique(),
+        amount: 1,
+        state,
+        ..Default::default()
+    }
+    .pack_into_slice(&mut data);
+    data
+}
+
+fn mint_bytes(is_initialized: bool) -> Vec<u8> {
+    let mut data = vec![0; SplMint::LEN];
+    SplMint {
+        is_initialized,
+        ..Default::default()
+    }
+    .pack_into_slice(&mut data);
+    data
+}
+
+#[test]
+fn strict_path_accepts_initialized_accounts() {
+    let (key, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mut lamports = 0;
+
+    let mut data = token_account_bytes(&mint, AccountState::Initialized);
+    let token_account = AccountInfo::new(
+        &key,
+        false,
+        false,
+        &mut lamports,
+        &mut data,
+        &spl_token::id(),
+        false,
+        0,
+    );
+    assert_token_account_basic(&token_account, &mint).unwrap();
+
+    let mut lamports = 0;
+    let mut data = mint_bytes(true);
+    let mint_account = AccountInfo::new(
+        &mint,
+        false,
+        false,
+        &mut lamports,
+        &mut data,
+        &spl_token::id(),
+        false,
+        0,
+    );
+    assert_mint_basic(&mint_account).unwrap();
+}
+
+#[test]
+fn strict_path_rejects_token_account_of_other_mint() {
+    let (key, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mut lamports = 0;
+    let mut data = token_account_bytes(&Pubkey::new_unique(), AccountState::Initialized);
+    let token_account = AccountInfo::new(
+        &key,
+        false,
+        false,
+        &mut lamports,
+        &mut data,
+        &spl_token::id(),
+        false,
+        0,
+    );
+
+    let err = assert_token_account_basic(&token_account, &mint).unwrap_err();
+
+    assert_eq!(err, AuctionHouseError::PublicKeyMismatch.into());
+}
+
+#[test]
+fn strict_path_rejects_uninitialized_token_account() {
+    let (key, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mut lamports = 0;
+    let mut data = token_account_bytes(&mint, AccountState::Uninitialized);
+    let token_account = AccountInfo::new(
+        &key,
+        false,
+        false,
+        &mut lamports,
+        &mut data,
+        &spl_token::id(),
+        false,
+        0,
+    );
+
+    let err = assert_token_account_basic(&token_account, &mint).unwrap_err();
+
+    assert_eq!(err, AuctionHouseError::UninitializedAccount.into());
+}
+
+#[test]
+fn strict_path_rejects_accounts_not_owned_by_a_token_program() {
+    let (key, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mut lamports = 0;
+    let mut data = token_account_bytes(&mint, AccountState::Initialized);
+    let token_account = AccountInfo::new(
+        &key,
+        false,
+        false,
+        &mut lamports,
+        &mut data,
+        &system_program::id(),
+        false,
+        0,
+    );
+
+    let err = assert_token_account_basic(&token_account, &mint).unwrap_err();
+
+    assert_eq!(err, AuctionHouseError::IncorrectOwner.into());
+}
+
+#[test]
+fn strict_path_rejects_uninitialized_mint() {
+    let mint = Pubkey::new_unique();
+    let mut lamports = 0;
+    let mut data = mint_bytes(false);
+    let mint_account = AccountInfo::new(
+        &mint,
+        false,
+        false,
+        &mut lamports,
+        &mut data,
+        &spl_token::id(),
+        false,
+        0,
+    );
+
+    let err = assert_mint_basic(&mint_account).unwrap_err();
+
+    assert_eq!(err, AuctionHouseError::UninitializedAccount.into());
+}
+
+#[test]
+fn lenient_path_is_reported_for_legacy_trade_states_only() {
+    capture_events();
+    let auction_house = Pubkey::new_unique();
+    let (legacy_trade_state, trade_state) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+    emit_if_legacy_trade_state(auction_house, legacy_trade_state, &[255]);
+    emit_if_legacy_trade_state(
+        auction_house,
+        trade_state,
+        &[255; TRADE_STATE_WITH_RENT_PAYER_SIZE],
+    );
+
+    let taken = lenient_paths_taken(&legacy_trade_state);
+    assert_eq!(taken.len(), 1);
+    assert_eq!(taken[0].auction_house, auction_house);
+    assert_eq!(taken[0].path, LenientPath::LegacyTradeState);
+    assert!(lenient_paths_taken(&trade_state).is_empty());
+}
+
+#[test]
+fn lenient_path_taken_event_carries_the_path() {
+    capture_events();
+    let (auction_house, escrow) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+    emit_lenient_path(auction_house, escrow, LenientPath::EscrowRentShortfall);
+
+    let taken = lenient_paths_taken(&escrow);
+    assert_eq!(taken.len(), 1);
+    assert_eq!(taken[0].auction_house, auction_house);
+    assert_eq!(taken[0].account, escrow);
+    assert_eq!(taken[0].path, LenientPath::EscrowRentShortfall);
+}
@@ -0,0 +1,160 @@
+#![cfg(feature = "test-bpf")]
+pub mod common;
+pub mod utils;
+
+use common::*;
+use utils::{helpers::default_scopes, setup_functions::*};
+
+const ONE_SOL: u64 = 1_000_000_000;
+
+async fn delegated_auctioneer(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah_authority: &Keypair,
+    scopes: Vec<AuthorityScope>,
+) -> Keypair {
+    let auctioneer_authority = Keypair::new();
+    airdrop(context, &auctioneer_authority.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+    let (auctioneer_pda, _) = find_auctioneer_pda(ahkey, &auctioneer_authority.pubkey());
+    delegate_auctioneer(
+        context,
+        *ahkey,
+        ah_authority,
+        auctioneer_authority.pubkey(),
+        auctioneer_pda,
+        scopes,
+    )
+    .await
+    .unwrap();
+
+    auctioneer_authority
+}
+
+#[tokio::test]
+async fn auctioneer_update_auction_house_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (_, ahkey, ah_authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let auctioneer_authority =
+        delegated_auctioneer(&mut context, &ahkey, &ah_authority, default_scopes()).await;
+
+    let (_, tx) = auctioneer_update_auction_house(
+        &mut context,
+        &ahkey,
+        &auctioneer_authority,
+        Some(250),
+        None,
+        Some(1000),
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let ah_account = context
+        .banks_client
+        .get_account(ahkey)
+        .await
+        .expect("Error getting auction house account")
+        .expect("Auction House empty");
+    let ah = AuctionHouse::try_deserialize(&mut ah_account.data.as_ref()).unwrap();
+    assert_eq!(ah.seller_fee_basis_points, 250);
+    assert_eq!(ah.tick_size, 1000);
+}
+
+#[tokio::test]
+async fn auctioneer_update_auction_house_missing_scope_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (_, ahkey, ah_authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let scopes = default_scopes()
+        .into_iter()
+        .filter(|scope| *scope != AuthorityScope::UpdateHouse)
+        .collect();
+    let auctioneer_authority =
+        delegated_auctioneer(&mut context, &ahkey, &ah_authority, scopes).await;
+
+    let (_, tx) = auctioneer_update_auction_house(
+        &mut context,
+        &ahkey,
+        &auctioneer_authority,
+        Some(250),
+        None,
+        None,
+    );
+    let error = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, MISSING_AUCTIONEER_SCOPE);
+}
+
+#[tokio::test]
+async fn auctioneer_withdraw_from_treasury_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, ah_authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let auctioneer_authority =
+        delegated_auctioneer(&mut context, &ahkey, &ah_authority, default_scopes()).await;
+    airdrop(&mut context, &ah.auction_house_treasury, ONE_SOL)
+        .await
+        .unwrap();
+    let destination_balance = context
+        .banks_client
+        .get_balance(ah.treasury_withdrawal_destination)
+        .await
+        .unwrap();
+
+    let (_, tx) = auctioneer_withdraw_from_treasury(
+        &mut context,
+        &ahkey,
+        &ah,
+        &auctioneer_authority,
+        ONE_SOL / 2,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    assert_eq!(
+        context
+            .banks_client
+            .get_balance(ah.treasury_withdrawal_destination)
+            .await
+            .unwrap(),
+        destination_balance + ONE_SOL / 2
+    );
+}
+
+#[tokio::test]
+async fn auctioneer_withdraw_from_treasury_missing_scope_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, ah_authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let auctioneer_authority = delegated_auctioneer(
+        &mut context,
+        &ahkey,
+        &ah_authority,
+        vec![AuthorityScope::Withdraw],
+    )
+    .await;
+    airdrop(&mut context, &ah.auction_house_treasury, ONE_SOL)
+        .await
+        .unwrap();
+
+    let (_, tx) = auctioneer_withdraw_from_treasury(
+        &mut context,
+        &ahkey,
+        &ah,
+        &auctioneer_authority,
+        ONE_SOL / 2,
+    );
+    let error = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, MISSING_AUCTIONEER_SCOPE);
+}
@@ -0,0 +1,365 @@
+#![cfg(feature = "test-bpf")]
+pub mod common;
+pub mod utils;
+
+use common::*;
+use mpl_auction_house::{
+    pda::{
+        find_bundle_listing_address, find_escrow_payment_address, find_program_as_signer_address,
+        find_royalty_mandate_address, find_trade_state_address,
+    },
+    BundleItem,
+};
+use mpl_token_metadata::state::Creator;
+use solana_program::{instruction::AccountMeta, program_pack::Pack, system_program, sysvar};
+use utils::setup_functions::*;
+
+const PRICES: [u64; 3] = [100_000_000, 200_000_000, 300_000_000];
+
+/// List three NFTs of the same seller, all created by `creator`, and bundle them.
+async fn setup_bundle(
+    context: &mut ProgramTestContext,
+    creator: &Pubkey,
+) -> (
+    AuctionHouse,
+    Pubkey,
+    Keypair,
+    Vec<Metadata>,
+    Vec<BundleItem>,
+) {
+    let (ah, ahkey, _) = existing_auction_house_test_context(context).await.unwrap();
+    let seller = Keypair::new();
+    airdrop(context, &seller.pubkey(), TEN_SOL).await.unwrap();
+
+    let mut metadatas = Vec::new();
+    let mut items = Vec::new();
+    for price in PRICES {
+        let mut test_metadata = Metadata::new();
+        test_metadata.token = Keypair::from_bytes(&seller.to_bytes()).unwrap();
+        test_metadata
+            .create(
+                context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                Some(vec![Creator {
+                    address: *creator,
+                    verified: false,
+                    share: 100,
+                }]),
+                500,
+                false,
+                1,
+            )
+            .await
+            .unwrap();
+        let ((sell_acc, _), sell_tx) = sell(context, &ahkey, &ah, &test_metadata, price, 1);
+        context
+            .banks_client
+            .process_transaction(sell_tx)
+            .await
+            .unwrap();
+
+        items.push(BundleItem {
+            trade_state: sell_acc.seller_trade_state,
+            token_account: sell_acc.token_account,
+            token_mint: test_metadata.mint.pubkey(),
+            price,
+            token_size: 1,
+        });
+        metadatas.push(test_metadata);
+    }
+
+    create_bundle_listing(context, &ahkey, &seller, 0, items.clone())
+        .await
+        .unwrap();
+
+    (ah, ahkey, seller, metadatas, items)
+}
+
+fn execute_bundle_sale(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    seller: &Pubkey,
+    buyer: &Keypair,
+    metadatas: &[Metadata],
+    items: &[BundleItem],
+    creator: &Pubkey,
+    buyer_price: u64,
+) -> Transaction {
+    let (escrow, escrow_bump) = find_escrow_payment_address(ahkey, &buyer.pubkey());
+    let (pas, pas_bump) = find_program_as_signer_address();
+    let mut accounts = mpl_auction_house::accounts::ExecuteBundleSale {
+        buyer: buyer.pubkey(),
+        seller: *seller,
+        treasury_mint: ah.treasury_mint,
+        escrow_payment_account: escrow,
+        seller_payment_receipt_account: *seller,
+        authority: ah.authority,
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        auction_house_treasury: ah.auction_house_treasury,
+        bundle_listing: find_bundle_listing_address(seller, ahkey, 0).0,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        ata_program: spl_associated_token_account::id(),
+        program_as_signer: pas,
+        rent: sysvar::rent::id(),
+    }
+    .to_account_metas(None);
+    for (item, test_metadata) in items.iter().zip(metadatas) {
+        accounts.push(AccountMeta::new(item.trade_state, false));
+        accounts.push(AccountMeta::new(item.token_account, false));
+        accounts.push(AccountMeta::new_readonly(item.token_mint, false));
+        accounts.push(AccountMeta::new_readonly(test_metadata.pubkey, false));
+        accounts.push(AccountMeta::new(
+            get_associated_token_address(&buyer.pubkey(), &item.token_mint),
+            false,
+        ));
+        accounts.push(AccountMeta::new(*creator, false));
+        accounts.push(AccountMeta::new_readonly(
+            find_royalty_mandate_address(creator).0,
+            false,
+        ));
+    }
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::ExecuteBundleSale {
+            escrow_payment_bump: escrow_bump,
+            program_as_signer_bump: pas_bump,
+            buyer_price,
+        }
+        .data(),
+        accounts,
+    };
+
+    Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&buyer.pubkey()),
+        &[buyer],
+        context.last_blockhash,
+    )
+}
+
+async fn setup_buyer(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+) -> Keypair {
+    let buyer = Keypair::new();
+    airdrop(context, &buyer.pubkey(), TEN_SOL).await.unwrap();
+    let (_, deposit_tx) = deposit(
+        context,
+        ahkey,
+        ah,
+        test_metadata,
+        &buyer,
+        PRICES.iter().sum(),
+    );
+    context
+        .banks_client
+        .process_transaction(deposit_tx)
+        .await
+        .unwrap();
+
+    buyer
+}
+
+#[tokio::test]
+async fn execute_bundle_sale_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let creator = Keypair::new();
+    airdrop(&mut context, &creator.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+    let (ah, ahkey, seller, metadatas, items) = setup_bundle(&mut context, &creator.pubkey()).await;
+    let buyer = setup_buyer(&mut context, &ahkey, &ah, &metadatas[0]).await;
+    let seller_lamports = context
+        .banks_client
+        .get_account(seller.pubkey())
+        .await
+        .unwrap()
+        .unwrap()
+        .lamports;
+    let bundle_listing_lamports = context
+        .banks_client
+        .get_account(find_bundle_listing_address(&seller.pubkey(), &ahkey, 0).0)
+        .await
+        .unwrap()
+        .unwrap()
+        .lamports;
+    // The seller paid for its listings, so it gets their rent back.
+    let mut trade_state_lamports = 0;
+    for item in items.iter() {
+        trade_state_lamports += context
+            .banks_client
+            .get_account(item.trade_state)
+            .await
+            .unwrap()
+            .unwrap()
+            .lamports;
+    }
+
+    let tx = execute_bundle_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &seller.pubkey(),
+        &buyer,
+        &metadatas,
+        &items,
+        &creator.pubkey(),
+        PRICES.iter().sum(),
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    for item in items.iter() {
+        let buyer_token_account = context
+            .banks_client
+            .get_account(get_associated_token_address(
+                &buyer.pubkey(),
+                &item.token_mint,
+            ))
+            .await
+            .unwrap()
+            .unwrap();
+        let buyer_token_account =
+            spl_token::state::Account::unpack(&buyer_token_account.data).unwrap();
+        assert_eq!(buyer_token_account.amount, 1);
+
+        let seller_trade_state = context
+            .banks_client
+            .get_account(item.trade_state)
+            .await
+            .unwrap();
+        assert!(seller_trade_state.is_none());
+    }
+
+    // Royalties of 5% and the house fee of 1% are taken from every item.
+    let creator_account = context
+        .banks_client
+        .get_account(creator.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(creator_account.lamports, ONE_SOL + 30_000_000);
+    let seller_account = context
+        .banks_client
+        .get_account(seller.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        seller_account.lamports,
+        seller_lamports + bundle_listing_lamports + trade_state_lamports + 564_000_000
+    );
+    let bundle_listing = context
+        .banks_client
+        .get_account(find_bundle_listing_address(&seller.pubkey(), &ahkey, 0).0)
+        .await
+        .unwrap();
+    assert!(bundle_listing.is_none());
+}
+
+#[tokio::test]
+async fn execute_bundle_sale_price_mismatch_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let creator = Keypair::new();
+    airdrop(&mut context, &creator.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+    let (ah, ahkey, seller, metadatas, items) = setup_bundle(&mut context, &creator.pubkey()).await;
+    let buyer = setup_buyer(&mut context, &ahkey, &ah, &metadatas[0]).await;
+
+    let tx = execute_bundle_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &seller.pubkey(),
+        &buyer,
+        &metadatas,
+        &items,
+        &creator.pubkey(),
+        PRICES[0],
+    );
+    let result = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(result, BUNDLE_PRICE_MISMATCH);
+}
+
+#[tokio::test]
+async fn execute_bundle_sale_missing_item_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let creator = Keypair::new();
+    airdrop(&mut context, &creator.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+    let (ah, ahkey, seller, metadatas, items) = setup_bundle(&mut context, &creator.pubkey()).await;
+    let buyer = setup_buyer(&mut context, &ahkey, &ah, &metadatas[0]).await;
+
+    // The first item is passed twice in place of the second one.
+    let items = vec![items[0].clone(), items[0].clone(), items[2].clone()];
+    let tx = execute_bundle_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &seller.pubkey(),
+        &buyer,
+        &metadatas,
+        &items,
+        &creator.pubkey(),
+        PRICES.iter().sum(),
+    );
+    let result = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(result, INVALID_BUNDLE_LISTING);
+}
+
+#[tokio::test]
+async fn create_bundle_listing_unlisted_item_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let seller = Keypair::new();
+    airdrop(&mut context, &seller.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+
+    // A trade state that does not exist cannot be bundled.
+    let test_metadata = Metadata::new();
+    let token_account =
+        get_associated_token_address(&seller.pubkey(), &test_metadata.mint.pubkey());
+    let items = (0..2)
+        .map(|i| BundleItem {
+            trade_state: find_trade_state_address(
+                &seller.pubkey(),
+                &ahkey,
+                &token_account,
+                &ah.treasury_mint,
+                &test_metadata.mint.pubkey(),
+                PRICES[i],
+                1,
+            )
+            .0,
+            token_account,
+            token_mint: test_metadata.mint.pubkey(),
+            price: PRICES[i],
+            token_size: 1,
+        })
+        .collect();
+
+    let result = create_bundle_listing(&mut context, &ahkey, &seller, 0, items)
+        .await
+        .unwrap_err();
+    assert_error!(result, INVALID_BUNDLE_LISTING);
+}
@@ -3,6 +3,7 @@ pub mod common;
 pub mod utils;
 
 use common::*;
+use mpl_auction_house::constants::TRADE_STATE_WITH_RENT_PAYER_SIZE;
 use solana_sdk::sysvar;
 use utils::{helpers::default_scopes, setup_functions::*};
 
@@ -843,3 +844,97 @@ async fn auction_cancel_bid_no_delegate_fails() {
 
     assert_error!(error, INVALID_SEEDS);
 }
+
+#[tokio::test]
+async fn cancel_listing_by_authority_refunds_seller() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = Metadata::new();
+    airdrop(&mut context, &test_metadata.token.pubkey(), 10_000_000_000)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            &mut context,
+            "Tests".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+    let ((acc, _), sell_tx) = sell(&mut context, &ahkey, &ah, &test_metadata, 10, 1);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let accounts = mpl_auction_house::accounts::Cancel {
+        auction_house: ahkey,
+        wallet: test_metadata.token.pubkey(),
+        token_account: acc.token_account,
+        authority: ah.authority,
+        trade_state: acc.seller_trade_state,
+        token_program: spl_token::id(),
+        token_mint: test_metadata.mint.pubkey(),
+        auction_house_fee_account: ah.auction_house_fee_account,
+    }
+    .to_account_metas(None);
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::Cancel {
+            buyer_price: 10,
+            token_size: 1,
+        }
+        .data(),
+        accounts,
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&authority.pubkey()),
+        &[&authority],
+        context.last_blockhash,
+    );
+
+    let seller_before = context
+        .banks_client
+        .get_balance(test_metadata.token.pubkey())
+        .await
+        .unwrap();
+    let fee_account_before = context
+        .banks_client
+        .get_balance(ah.auction_house_fee_account)
+        .await
+        .unwrap();
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    // The seller paid for the listing, so the rent goes back to them rather than the fee account.
+    let trade_state_rent = context
+        .banks_client
+        .get_rent()
+        .await
+        .unwrap()
+        .minimum_balance(TRADE_STATE_WITH_RENT_PAYER_SIZE);
+    assert_eq!(
+        context
+            .banks_client
+            .get_balance(test_metadata.token.pubkey())
+            .await
+            .unwrap(),
+        seller_before + trade_state_rent
+    );
+    assert_eq!(
+        context
+            .banks_client
+            .get_balance(ah.auction_house_fee_account)
+            .await
+            .unwrap(),
+        fee_account_before
+    );
+}
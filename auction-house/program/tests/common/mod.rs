@@ -31,6 +31,46 @@ pub const BUYER_TRADE_STATE_NOT_VALID: u32 = 6037;
 pub const NOT_ENOUGH_TOKENS_AVAIL_FOR_PURCHASE: u32 = 6039;
 pub const PARTIAL_BUY_PRICE_MISMATCH: u32 = 6040;
 pub const MISSING_ELEMENTS_NEEDED_FOR_PARTIAL_BUY: u32 = 6038;
+pub const SESSION_KEY_SPEND_LIMIT_EXCEEDED: u32 = 6042;
+pub const REVENUE_SHARE_ALREADY_CLAIMED: u32 = 6046;
+pub const PENDING_UPDATE_ALREADY_QUEUED: u32 = 6049;
+pub const PENDING_UPDATE_NOT_EXECUTABLE: u32 = 6050;
+pub const SELF_TRADE_NOT_ALLOWED: u32 = 6051;
+pub const INVALID_ORDER_BOOK_SUMMARY: u32 = 6052;
+pub const INVALID_LISTING_MIGRATION: u32 = 6053;
+pub const ROYALTIES_NOT_ENFORCED: u32 = 6054;
+pub const SALE_ACCOUNTS_NOT_PREPARED: u32 = 6055;
+pub const INVALID_LISTING_SIGNATURE: u32 = 6056;
+pub const SIGNED_LISTING_NOT_DELEGATED: u32 = 6058;
+pub const INVALID_ROYALTY_MANDATE: u32 = 6059;
+pub const INVALID_TOKEN_PROGRAM: u32 = 6060;
+pub const INVALID_BUNDLE_LISTING: u32 = 6061;
+pub const BUNDLE_PRICE_MISMATCH: u32 = 6062;
+pub const WALLET_FROZEN: u32 = 6064;
+pub const WALLET_VOLUME_CAP_EXCEEDED: u32 = 6065;
+pub const PRICE_NOT_ON_TICK: u32 = 6067;
+pub const LAYAWAY_DEFAULTED: u32 = 6070;
+pub const LAYAWAY_NOT_DEFAULTED: u32 = 6071;
+pub const LAYAWAY_NOT_PAID: u32 = 6072;
+pub const FREE_SALE_REQUIRES_AUCTION_HOUSE_SIGNOFF: u32 = 6073;
+pub const FREE_SALE_CAP_EXCEEDED: u32 = 6074;
+pub const OPERATION_PAUSED: u32 = 6077;
+pub const INVALID_PAUSED_OPERATIONS: u32 = 6078;
+pub const BID_BELOW_ASK: u32 = 6079;
+pub const INVALID_PROCEEDS_ROUTE: u32 = 6081;
+pub const EPOCH_REPORT_RETAINED: u32 = 6083;
+pub const DISPUTE_WINDOW_CLOSED: u32 = 6085;
+pub const DISPUTE_WINDOW_OPEN: u32 = 6086;
+pub const PURCHASE_DISPUTED: u32 = 6087;
+pub const PURCHASE_NOT_DISPUTED: u32 = 6088;
+pub const INVALID_DISPUTE_WINDOW: u32 = 6089;
+pub const INVALID_LAST_SALE: u32 = 6091;
+pub const LISTING_COOLDOWN_ACTIVE: u32 = 6092;
+pub const INVALID_CUSTODIAL_APPROVAL: u32 = 6093;
+pub const CUSTODIAL_IDENTITY_MISMATCH: u32 = 6094;
+pub const INVALID_SWAP: u32 = 6095;
+pub const ORDER_BOOK_SUMMARY_REQUIRED: u32 = 6096;
+pub const SWAP_VALUATION_TOO_HIGH: u32 = 6097;
 
 pub const TEN_SOL: u64 = 10_000_000_000;
 pub const ONE_SOL: u64 = 1_000_000_000;
@@ -0,0 +1,115 @@
+#![cfg(feature = "test-bpf")]
+pub mod common;
+pub mod utils;
+
+use common::*;
+use mpl_auction_house::{
+    pda::{find_compliance_config_address, find_wallet_volume_address},
+    test_utils::{AuctionHouseContext, AuctionHouseTest, TestBid, TestListing},
+    WalletVolume,
+};
+use solana_program::instruction::AccountMeta;
+use utils::setup_functions::*;
+
+/// Auction House with a compliance config, an NFT listed at one SOL and a matching bid.
+async fn setup_sale(
+    volume_cap_per_epoch: u64,
+    frozen_seller: bool,
+) -> (AuctionHouseContext, Metadata, TestListing, TestBid) {
+    let mut ah = AuctionHouseTest::new().start().await.unwrap();
+    let seller = ah.funded_wallet(ONE_SOL).await.unwrap();
+    let buyer = ah.funded_wallet(TEN_SOL).await.unwrap();
+
+    let frozen_wallets = if frozen_seller {
+        vec![seller.pubkey()]
+    } else {
+        vec![]
+    };
+    let authority = Keypair::from_bytes(&ah.authority.to_bytes()).unwrap();
+    create_compliance_config(
+        &mut ah.context,
+        &ah.auction_house_key,
+        &authority,
+        volume_cap_per_epoch,
+        frozen_wallets,
+    )
+    .await
+    .unwrap();
+    ah.reload().await.unwrap();
+    assert!(ah.auction_house.has_compliance);
+
+    let nft = ah.mint_nft(&seller, vec![], 0).await.unwrap();
+    let listing = ah.sell(&nft, ONE_SOL, 1).await.unwrap();
+    let bid = ah.bid(&nft, &buyer, ONE_SOL, 1).await.unwrap();
+
+    (ah, nft, listing, bid)
+}
+
+fn compliance_accounts(
+    ah: &AuctionHouseContext,
+    buyer: &Pubkey,
+    seller: &Pubkey,
+) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new_readonly(
+            find_compliance_config_address(&ah.auction_house_key).0,
+            false,
+        ),
+        AccountMeta::new(
+            find_wallet_volume_address(&ah.auction_house_key, buyer).0,
+            false,
+        ),
+        AccountMeta::new(
+            find_wallet_volume_address(&ah.auction_house_key, seller).0,
+            false,
+        ),
+    ]
+}
+
+#[tokio::test]
+async fn execute_sale_records_wallet_volume() {
+    let (mut ah, nft, listing, bid) = setup_sale(2 * ONE_SOL, false).await;
+
+    let accounts = compliance_accounts(&ah, &bid.buyer, &listing.seller);
+    ah.execute_sale_with_remaining_accounts(&nft, &listing, &bid, accounts)
+        .await
+        .unwrap();
+
+    for wallet in [bid.buyer, listing.seller] {
+        let wallet_volume = ah
+            .context
+            .banks_client
+            .get_account(find_wallet_volume_address(&ah.auction_house_key, &wallet).0)
+            .await
+            .unwrap()
+            .unwrap();
+        let wallet_volume =
+            WalletVolume::try_deserialize(&mut wallet_volume.data.as_ref()).unwrap();
+        assert_eq!(wallet_volume.wallet, wallet);
+        assert_eq!(wallet_volume.volume, ONE_SOL);
+    }
+}
+
+#[tokio::test]
+async fn execute_sale_over_volume_cap_fails() {
+    let (mut ah, nft, listing, bid) = setup_sale(ONE_SOL / 2, false).await;
+
+    let accounts = compliance_accounts(&ah, &bid.buyer, &listing.seller);
+    let result = ah
+        .execute_sale_with_remaining_accounts(&nft, &listing, &bid, accounts)
+        .await
+        .unwrap_err();
+    assert_error!(result, WALLET_VOLUME_CAP_EXCEEDED);
+}
+
+#[tokio::test]
+async fn execute_sale_frozen_wallet_fails() {
+    let (mut ah, nft, listing, bid) = setup_sale(2 * ONE_SOL, true).await;
+
+    let accounts = compliance_accounts(&ah, &bid.buyer, &listing.seller);
+    let result = ah
+        .execute_sale_with_remaining_accounts(&nft, &listing, &bid, accounts)
+        .await
+        .unwrap_err();
+    assert_error!(result, WALLET_FROZEN);
+}
@@ -0,0 +1,269 @@
+#![cfg(feature = "test-bpf")]
+pub mod common;
+pub mod utils;
+
+use common::*;
+use mpl_auction_house::{
+    constants::{CUSTODIAL_TRADE_STATE_SIZE, TRADE_STATE_WITH_RENT_PAYER_SIZE},
+    pda::{
+        find_escrow_payment_address, find_proceeds_route_address, find_program_as_signer_address,
+        find_trade_state_address,
+    },
+    utils::{custodial_approval_message, custodial_identity_hash},
+};
+use solana_program::{instruction::AccountMeta, program_pack::Pack, system_program, sysvar};
+use utils::setup_functions::*;
+
+const PRICE: u64 = 100_000_000;
+
+struct CustodialSale {
+    ah: AuctionHouse,
+    ahkey: Pubkey,
+    authority: Keypair,
+    test_metadata: Metadata,
+    sell_acc: mpl_auction_house::accounts::CustodialSell,
+    buyer: Keypair,
+    buyer_trade_state: Pubkey,
+    escrow_payment_account: Pubkey,
+}
+
+/// List an NFT through a custodial platform for `user` and bid on it at the listing price.
+async fn list_and_bid(context: &mut ProgramTestContext, user: &Keypair) -> CustodialSale {
+    let (ah, ahkey, authority) = existing_auction_house_test_context(context).await.unwrap();
+    let test_metadata = Metadata::new();
+    airdrop(context, &test_metadata.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+
+    // The platform wallet holds the token and signs, the user never does.
+    let (sell_acc, sell_tx) = custodial_sell(
+        context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        PRICE,
+        1,
+        custodial_identity_hash(&user.pubkey()),
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let buyer = Keypair::new();
+    airdrop(context, &buyer.pubkey(), TEN_SOL).await.unwrap();
+    let ((bid_acc, _), buy_tx) = buy(
+        context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &test_metadata.token.pubkey(),
+        &buyer,
+        PRICE,
+        1,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+    airdrop(context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+
+    CustodialSale {
+        ah,
+        ahkey,
+        authority,
+        test_metadata,
+        sell_acc,
+        buyer,
+        buyer_trade_state: bid_acc.buyer_trade_state,
+        escrow_payment_account: bid_acc.escrow_payment_account,
+    }
+}
+
+/// Execute the sale, preceded by an ed25519 verification of the approval message signed by `approver`.
+async fn execute_approved_sale(
+    context: &mut ProgramTestContext,
+    sale: &CustodialSale,
+    approver: &Keypair,
+) -> std::result::Result<(), TransportError> {
+    let buyer = sale.buyer.pubkey();
+    let seller = sale.test_metadata.token.pubkey();
+    let mint = sale.test_metadata.mint.pubkey();
+    let mut accounts = mpl_auction_house::accounts::ExecuteSale {
+        buyer,
+        seller,
+        auction_house: sale.ahkey,
+        metadata: sale.test_metadata.pubkey,
+        token_account: sale.sell_acc.token_account,
+        authority: sale.ah.authority,
+        seller_trade_state: sale.sell_acc.seller_trade_state,
+        buyer_trade_state: sale.buyer_trade_state,
+        token_program: spl_token::id(),
+        free_trade_state: sale.sell_acc.free_seller_trade_state,
+        seller_payment_receipt_account: seller,
+        buyer_receipt_token_account: get_associated_token_address(&buyer, &mint),
+        escrow_payment_account: sale.escrow_payment_account,
+        token_mint: mint,
+        auction_house_fee_account: sale.ah.auction_house_fee_account,
+        auction_house_treasury: sale.ah.auction_house_treasury,
+        treasury_mint: sale.ah.treasury_mint,
+        program_as_signer: sale.sell_acc.program_as_signer,
+        system_program: system_program::id(),
+        ata_program: spl_associated_token_account::id(),
+        rent: sysvar::rent::id(),
+    }
+    .to_account_metas(None);
+    accounts.push(AccountMeta::new_readonly(
+        find_proceeds_route_address(&seller, &sale.sell_acc.seller_trade_state).0,
+        false,
+    ));
+    accounts.push(AccountMeta::new_readonly(sysvar::instructions::id(), false));
+
+    let (_, free_sts_bump) = find_trade_state_address(
+        &seller,
+        &sale.ahkey,
+        &sale.sell_acc.token_account,
+        &sale.ah.treasury_mint,
+        &mint,
+        0,
+        1,
+    );
+    let (_, escrow_bump) = find_escrow_payment_address(&sale.ahkey, &buyer);
+    let (_, pas_bump) = find_program_as_signer_address();
+    let execute_sale_ix = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::ExecuteSale {
+            escrow_payment_bump: escrow_bump,
+            _free_trade_state_bump: free_sts_bump,
+            program_as_signer_bump: pas_bump,
+            token_size: 1,
+            buyer_price: PRICE,
+            partial_order_size: None,
+            partial_order_price: None,
+        }
+        .data(),
+        accounts,
+    };
+
+    let message = custodial_approval_message(
+        &sale.ahkey,
+        &sale.sell_acc.seller_trade_state,
+        &buyer,
+        PRICE,
+        1,
+    );
+    let ed25519_ix = signed_listing_ed25519_instruction(approver, &message);
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ed25519_ix, execute_sale_ix],
+        Some(&sale.authority.pubkey()),
+        &[&sale.authority],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await
+}
+
+#[tokio::test]
+async fn custodial_sell_records_user_identity() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let user = Keypair::new();
+    let sale = list_and_bid(&mut context, &user).await;
+
+    let seller_trade_state = context
+        .banks_client
+        .get_account(sale.sell_acc.seller_trade_state)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(seller_trade_state.data.len(), CUSTODIAL_TRADE_STATE_SIZE);
+    assert_eq!(
+        seller_trade_state.data[TRADE_STATE_WITH_RENT_PAYER_SIZE..],
+        custodial_identity_hash(&user.pubkey())
+    );
+}
+
+#[tokio::test]
+async fn execute_sale_with_user_approval_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let user = Keypair::new();
+    let sale = list_and_bid(&mut context, &user).await;
+
+    execute_approved_sale(&mut context, &sale, &user)
+        .await
+        .unwrap();
+
+    let buyer_token_account =
+        get_associated_token_address(&sale.buyer.pubkey(), &sale.test_metadata.mint.pubkey());
+    let token_account = context
+        .banks_client
+        .get_account(buyer_token_account)
+        .await
+        .unwrap()
+        .unwrap();
+    let token_account = spl_token::state::Account::unpack(&token_account.data).unwrap();
+    assert_eq!(token_account.amount, 1);
+    assert!(context
+        .banks_client
+        .get_account(sale.sell_acc.seller_trade_state)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn execute_sale_approved_by_other_key_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let user = Keypair::new();
+    let sale = list_and_bid(&mut context, &user).await;
+
+    let error = execute_approved_sale(&mut context, &sale, &Keypair::new())
+        .await
+        .unwrap_err();
+
+    match error {
+        TransportError::TransactionError(TransactionError::InstructionError(
+            1,
+            InstructionError::Custom(code),
+        )) => assert_eq!(code, INVALID_CUSTODIAL_APPROVAL),
+        _ => panic!("Unexpected error {:?}", error),
+    }
+}
+
+#[tokio::test]
+async fn custodial_sell_relist_for_other_user_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let sale = list_and_bid(&mut context, &Keypair::new()).await;
+
+    let (_, sell_tx) = custodial_sell(
+        &mut context,
+        &sale.ahkey,
+        &sale.ah,
+        &sale.test_metadata,
+        PRICE,
+        1,
+        custodial_identity_hash(&Keypair::new().pubkey()),
+    );
+    let error = context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, CUSTODIAL_IDENTITY_MISMATCH);
+}
@@ -3,8 +3,12 @@ pub mod common;
 pub mod utils;
 
 use common::*;
-use mpl_auction_house::pda::find_auctioneer_pda;
+use mpl_auction_house::{
+    pda::{find_auctioneer_pda, find_escrow_payment_address},
+    token_interface::spl_token_2022,
+};
 use mpl_testing_utils::{solana::airdrop, utils::Metadata};
+use solana_program::{system_program, sysvar};
 use solana_sdk::{signature::Keypair, signer::Signer};
 use std::assert_eq;
 use utils::{helpers::default_scopes, setup_functions::*};
@@ -248,3 +252,107 @@ async fn auctioneer_deposit_no_delegate_fails() {
 
     assert_error!(error, INVALID_SEEDS);
 }
+
+#[tokio::test]
+async fn deposit_invalid_token_program_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), ONE_SOL * 2)
+        .await
+        .unwrap();
+
+    // Only SPL Token and Token-2022 can move treasury funds.
+    let (escrow, escrow_bump) = find_escrow_payment_address(&ahkey, &buyer.pubkey());
+    let accounts = mpl_auction_house::accounts::Deposit {
+        wallet: buyer.pubkey(),
+        authority: ah.authority,
+        auction_house: ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        token_program: system_program::id(),
+        treasury_mint: ah.treasury_mint,
+        payment_account: buyer.pubkey(),
+        transfer_authority: buyer.pubkey(),
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+        escrow_payment_account: escrow,
+    }
+    .to_account_metas(None);
+    let data = mpl_auction_house::instruction::Deposit {
+        amount: ONE_SOL,
+        escrow_payment_bump: escrow_bump,
+    }
+    .data();
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data,
+        accounts,
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        context.last_blockhash,
+    );
+
+    let error = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, INVALID_TOKEN_PROGRAM);
+}
+
+#[tokio::test]
+async fn deposit_token_program_not_owning_treasury_mint_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), ONE_SOL * 2)
+        .await
+        .unwrap();
+
+    // The native treasury mint is owned by SPL Token, not Token-2022.
+    let (escrow, escrow_bump) = find_escrow_payment_address(&ahkey, &buyer.pubkey());
+    let accounts = mpl_auction_house::accounts::Deposit {
+        wallet: buyer.pubkey(),
+        authority: ah.authority,
+        auction_house: ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        token_program: spl_token_2022::id(),
+        treasury_mint: ah.treasury_mint,
+        payment_account: buyer.pubkey(),
+        transfer_authority: buyer.pubkey(),
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+        escrow_payment_account: escrow,
+    }
+    .to_account_metas(None);
+    let data = mpl_auction_house::instruction::Deposit {
+        amount: ONE_SOL,
+        escrow_payment_bump: escrow_bump,
+    }
+    .data();
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data,
+        accounts,
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        context.last_blockhash,
+    );
+
+    let error = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, INVALID_TOKEN_PROGRAM);
+}
@@ -0,0 +1,412 @@
+#![cfg(feature = "test-bpf")]
+pub mod common;
+pub mod utils;
+
+use common::*;
+use mpl_auction_house::{
+    pda::{
+        find_deferred_settlement_address, find_escrow_payment_address, find_proceeds_route_address,
+        find_program_as_signer_address, find_purchase_receipt_address,
+        find_royalty_mandate_address, find_trade_state_address,
+    },
+    receipt::PurchaseReceipt,
+    DeferredSettlement,
+};
+use mpl_token_metadata::state::Creator;
+use solana_program::{instruction::AccountMeta, system_program, sysvar};
+use solana_sdk::sysvar::clock::Clock;
+use utils::setup_functions::*;
+
+const DAY: i64 = 86_400;
+const PRICE: u64 = 100_000_000;
+
+struct DisputedSale {
+    ah: AuctionHouse,
+    ahkey: Pubkey,
+    authority: Keypair,
+    seller: Pubkey,
+    buyer: Keypair,
+    purchase_receipt: Pubkey,
+}
+
+/// Sell an NFT on an Auction House holding proceeds back for a day.
+async fn execute_held_sale(context: &mut ProgramTestContext) -> DisputedSale {
+    let (ah, ahkey, authority) = existing_auction_house_test_context(context).await.unwrap();
+    update_auction_house(
+        context,
+        &ahkey,
+        &ah,
+        &authority,
+        None,
+        None,
+        None,
+        Some(true),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some(DAY),
+        None,
+    )
+    .await
+    .unwrap();
+
+    let creator = Keypair::new();
+    airdrop(context, &creator.pubkey(), ONE_SOL).await.unwrap();
+    let test_metadata = Metadata::new();
+    airdrop(context, &test_metadata.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            Some(vec![Creator {
+                address: creator.pubkey(),
+                verified: false,
+                share: 100,
+            }]),
+            500,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+    let ((sell_acc, _), sell_tx) = sell(context, &ahkey, &ah, &test_metadata, PRICE, 1);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+    let buyer = Keypair::new();
+    airdrop(context, &buyer.pubkey(), TEN_SOL).await.unwrap();
+    let ((bid_acc, _), buy_tx) = buy(
+        context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &test_metadata.token.pubkey(),
+        &buyer,
+        PRICE,
+        1,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+    airdrop(context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+
+    let purchase_receipt =
+        find_purchase_receipt_address(&sell_acc.seller_trade_state, &bid_acc.buyer_trade_state).0;
+    let mut accounts = mpl_auction_house::accounts::ExecuteSale {
+        buyer: buyer.pubkey(),
+        seller: test_metadata.token.pubkey(),
+        auction_house: ahkey,
+        metadata: test_metadata.pubkey,
+        token_account: sell_acc.token_account,
+        authority: ah.authority,
+        seller_trade_state: sell_acc.seller_trade_state,
+        buyer_trade_state: bid_acc.buyer_trade_state,
+        token_program: spl_token::id(),
+        free_trade_state: sell_acc.free_seller_trade_state,
+        seller_payment_receipt_account: test_metadata.token.pubkey(),
+        buyer_receipt_token_account: get_associated_token_address(
+            &buyer.pubkey(),
+            &test_metadata.mint.pubkey(),
+        ),
+        escrow_payment_account: bid_acc.escrow_payment_account,
+        token_mint: test_metadata.mint.pubkey(),
+        auction_house_fee_account: ah.auction_house_fee_account,
+        auction_house_treasury: ah.auction_house_treasury,
+        treasury_mint: ah.treasury_mint,
+        program_as_signer: sell_acc.program_as_signer,
+        system_program: system_program::id(),
+        ata_program: spl_associated_token_account::id(),
+        rent: sysvar::rent::id(),
+    }
+    .to_account_metas(None);
+    accounts.push(AccountMeta::new(creator.pubkey(), false));
+    accounts.push(AccountMeta::new_readonly(
+        find_royalty_mandate_address(&creator.pubkey()).0,
+        false,
+    ));
+    accounts.push(AccountMeta::new_readonly(
+        find_proceeds_route_address(&test_metadata.token.pubkey(), &sell_acc.seller_trade_state).0,
+        false,
+    ));
+    accounts.push(AccountMeta::new(
+        find_deferred_settlement_address(&ahkey, &purchase_receipt).0,
+        false,
+    ));
+    accounts.push(AccountMeta::new(purchase_receipt, false));
+    let (_, free_sts_bump) = find_trade_state_address(
+        &test_metadata.token.pubkey(),
+        &ahkey,
+        &sell_acc.token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        0,
+        1,
+    );
+    let (_, escrow_bump) = find_escrow_payment_address(&ahkey, &buyer.pubkey());
+    let (_, pas_bump) = find_program_as_signer_address();
+    let data = mpl_auction_house::instruction::ExecuteSale {
+        escrow_payment_bump: escrow_bump,
+        _free_trade_state_bump: free_sts_bump,
+        program_as_signer_bump: pas_bump,
+        token_size: 1,
+        buyer_price: PRICE,
+        partial_order_size: None,
+        partial_order_price: None,
+    }
+    .data();
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data,
+        accounts,
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&authority.pubkey()),
+        &[&authority],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    DisputedSale {
+        ah,
+        ahkey,
+        authority,
+        seller: test_metadata.token.pubkey(),
+        buyer,
+        purchase_receipt,
+    }
+}
+
+fn seller_proceeds(ah: &AuctionHouse) -> u64 {
+    PRICE - PRICE * 500 / 10000 - PRICE * ah.seller_fee_basis_points as u64 / 10000
+}
+
+async fn pass_dispute_window(context: &mut ProgramTestContext) {
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp += DAY;
+    context.set_sysvar(&clock);
+}
+
+#[tokio::test]
+async fn execute_sale_holds_proceeds_in_deferred_settlement() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let sale = execute_held_sale(&mut context).await;
+
+    let deferred_settlement =
+        find_deferred_settlement_address(&sale.ahkey, &sale.purchase_receipt).0;
+    let settlement_account = context
+        .banks_client
+        .get_account(deferred_settlement)
+        .await
+        .unwrap()
+        .unwrap();
+    let settlement =
+        DeferredSettlement::try_deserialize(&mut settlement_account.data.as_ref()).unwrap();
+    assert_eq!(settlement.buyer, sale.buyer.pubkey());
+    assert_eq!(settlement.seller, sale.seller);
+    assert_eq!(settlement.destination, sale.seller);
+    assert_eq!(settlement.amount, seller_proceeds(&sale.ah));
+    assert!(!settlement.disputed);
+
+    let rent = context.banks_client.get_rent().await.unwrap();
+    assert_eq!(
+        settlement_account.lamports,
+        rent.minimum_balance(settlement_account.data.len()) + seller_proceeds(&sale.ah)
+    );
+}
+
+#[tokio::test]
+async fn release_settlement_after_window_pays_seller() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let sale = execute_held_sale(&mut context).await;
+    let error = release_settlement(
+        &mut context,
+        &sale.ahkey,
+        &sale.ah,
+        &sale.authority,
+        &sale.purchase_receipt,
+        &sale.seller,
+    )
+    .await
+    .unwrap_err();
+    assert_error!(error, DISPUTE_WINDOW_OPEN);
+
+    // Anyone may release once the window passed.
+    pass_dispute_window(&mut context).await;
+    let payer = Keypair::new();
+    airdrop(&mut context, &payer.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+    let seller_balance = context.banks_client.get_balance(sale.seller).await.unwrap();
+    release_settlement(
+        &mut context,
+        &sale.ahkey,
+        &sale.ah,
+        &payer,
+        &sale.purchase_receipt,
+        &sale.seller,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        context.banks_client.get_balance(sale.seller).await.unwrap(),
+        seller_balance + seller_proceeds(&sale.ah)
+    );
+    assert!(context
+        .banks_client
+        .get_account(find_deferred_settlement_address(&sale.ahkey, &sale.purchase_receipt).0)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn resolve_dispute_refunds_buyer() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let sale = execute_held_sale(&mut context).await;
+
+    flag_purchase(
+        &mut context,
+        &sale.ahkey,
+        &sale.authority,
+        &sale.purchase_receipt,
+    )
+    .await
+    .unwrap();
+    let receipt_account = context
+        .banks_client
+        .get_account(sale.purchase_receipt)
+        .await
+        .unwrap()
+        .unwrap();
+    let receipt = PurchaseReceipt::try_deserialize(&mut receipt_account.data.as_ref()).unwrap();
+    assert!(receipt.disputed);
+
+    // A disputed settlement stays frozen past the dispute window.
+    pass_dispute_window(&mut context).await;
+    let error = release_settlement(
+        &mut context,
+        &sale.ahkey,
+        &sale.ah,
+        &sale.authority,
+        &sale.purchase_receipt,
+        &sale.seller,
+    )
+    .await
+    .unwrap_err();
+    assert_error!(error, PURCHASE_DISPUTED);
+
+    let buyer_balance = context
+        .banks_client
+        .get_balance(sale.buyer.pubkey())
+        .await
+        .unwrap();
+    resolve_dispute(
+        &mut context,
+        &sale.ahkey,
+        &sale.ah,
+        &sale.authority,
+        &sale.purchase_receipt,
+        &sale.buyer.pubkey(),
+        &sale.seller,
+        false,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        context
+            .banks_client
+            .get_balance(sale.buyer.pubkey())
+            .await
+            .unwrap(),
+        buyer_balance + seller_proceeds(&sale.ah)
+    );
+}
+
+#[tokio::test]
+async fn flag_purchase_after_window_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let sale = execute_held_sale(&mut context).await;
+
+    pass_dispute_window(&mut context).await;
+    let error = flag_purchase(
+        &mut context,
+        &sale.ahkey,
+        &sale.authority,
+        &sale.purchase_receipt,
+    )
+    .await
+    .unwrap_err();
+    assert_error!(error, DISPUTE_WINDOW_CLOSED);
+}
+
+#[tokio::test]
+async fn resolve_undisputed_purchase_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let sale = execute_held_sale(&mut context).await;
+
+    let error = resolve_dispute(
+        &mut context,
+        &sale.ahkey,
+        &sale.ah,
+        &sale.authority,
+        &sale.purchase_receipt,
+        &sale.buyer.pubkey(),
+        &sale.seller,
+        true,
+    )
+    .await
+    .unwrap_err();
+    assert_error!(error, PURCHASE_NOT_DISPUTED);
+}
+
+#[tokio::test]
+async fn negative_dispute_window_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+
+    let error = update_auction_house(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some(-1),
+        None,
+    )
+    .await
+    .unwrap_err();
+    assert_error!(error, INVALID_DISPUTE_WINDOW);
+}
@@ -0,0 +1,267 @@
+#![cfg(feature = "test-bpf")]
+pub mod common;
+pub mod utils;
+
+use common::*;
+use mpl_auction_house::{
+    pda::{
+        find_epoch_report_address, find_escrow_payment_address, find_proceeds_route_address,
+        find_program_as_signer_address, find_trade_state_address,
+    },
+    EpochReport,
+};
+use solana_program::{clock::Clock, instruction::AccountMeta, system_program, sysvar};
+use utils::setup_functions::*;
+
+const PRICE: u64 = 100_000_000;
+
+fn execute_sale_instruction(
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    buyer: &Pubkey,
+    epoch_report: &Pubkey,
+) -> Instruction {
+    let seller = test_metadata.token.pubkey();
+    let token_account = get_associated_token_address(&seller, &test_metadata.mint.pubkey());
+    let (seller_trade_state, _) = find_trade_state_address(
+        &seller,
+        ahkey,
+        &token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        PRICE,
+        1,
+    );
+    let (free_trade_state, free_sts_bump) = find_trade_state_address(
+        &seller,
+        ahkey,
+        &token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        0,
+        1,
+    );
+    let (buyer_trade_state, _) = find_trade_state_address(
+        buyer,
+        ahkey,
+        &token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        PRICE,
+        1,
+    );
+    let (escrow_payment_account, escrow_bump) = find_escrow_payment_address(ahkey, buyer);
+    let (program_as_signer, pas_bump) = find_program_as_signer_address();
+    let mut accounts = mpl_auction_house::accounts::ExecuteSale {
+        buyer: *buyer,
+        seller,
+        auction_house: *ahkey,
+        metadata: test_metadata.pubkey,
+        token_account,
+        authority: ah.authority,
+        seller_trade_state,
+        buyer_trade_state,
+        token_program: spl_token::id(),
+        free_trade_state,
+        seller_payment_receipt_account: seller,
+        buyer_receipt_token_account: get_associated_token_address(
+            buyer,
+            &test_metadata.mint.pubkey(),
+        ),
+        escrow_payment_account,
+        token_mint: test_metadata.mint.pubkey(),
+        auction_house_fee_account: ah.auction_house_fee_account,
+        auction_house_treasury: ah.auction_house_treasury,
+        treasury_mint: ah.treasury_mint,
+        program_as_signer,
+        system_program: system_program::id(),
+        ata_program: spl_associated_token_account::id(),
+        rent: sysvar::rent::id(),
+    }
+    .to_account_metas(None);
+    accounts.push(AccountMeta::new_readonly(
+        find_proceeds_route_address(&seller, &seller_trade_state).0,
+        false,
+    ));
+    accounts.push(AccountMeta::new(*epoch_report, false));
+
+    Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::ExecuteSale {
+            escrow_payment_bump: escrow_bump,
+            _free_trade_state_bump: free_sts_bump,
+            program_as_signer_bump: pas_bump,
+            token_size: 1,
+            buyer_price: PRICE,
+            partial_order_size: None,
+            partial_order_price: None,
+        }
+        .data(),
+        accounts,
+    }
+}
+
+fn close_epoch_report_instruction(
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    epoch_report: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::CloseEpochReport {}.data(),
+        accounts: mpl_auction_house::accounts::CloseEpochReport {
+            authority: ah.authority,
+            auction_house: *ahkey,
+            auction_house_fee_account: ah.auction_house_fee_account,
+            epoch_report: *epoch_report,
+        }
+        .to_account_metas(None),
+    }
+}
+
+#[tokio::test]
+async fn execute_sale_records_epoch_report() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    update_auction_house(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some(1),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    let test_metadata = Metadata::new();
+    airdrop(&mut context, &test_metadata.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            &mut context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+    let (_, sell_tx) = sell(&mut context, &ahkey, &ah, &test_metadata, PRICE, 1);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let (_, buy_tx) = buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &test_metadata.token.pubkey(),
+        &buyer,
+        PRICE,
+        1,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+    airdrop(&mut context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+
+    let epoch = context
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap()
+        .epoch;
+    let (epoch_report, _) = find_epoch_report_address(&ahkey, epoch);
+    let tx = Transaction::new_signed_with_payer(
+        &[execute_sale_instruction(
+            &ahkey,
+            &ah,
+            &test_metadata,
+            &buyer.pubkey(),
+            &epoch_report,
+        )],
+        Some(&authority.pubkey()),
+        &[&authority],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let epoch_report_account = context
+        .banks_client
+        .get_account(epoch_report)
+        .await
+        .unwrap()
+        .unwrap();
+    let report = EpochReport::try_deserialize(&mut epoch_report_account.data.as_ref()).unwrap();
+    assert_eq!(report.epoch, epoch);
+    assert_eq!(report.volume, PRICE);
+    assert_eq!(report.sale_count, 1);
+    assert_eq!(report.royalties_paid, 0);
+    assert_eq!(
+        report.house_fees,
+        PRICE * ah.seller_fee_basis_points as u64 / 10000
+    );
+
+    // The report is kept for one more epoch after its own.
+    let tx = Transaction::new_signed_with_payer(
+        &[close_epoch_report_instruction(&ahkey, &ah, &epoch_report)],
+        Some(&authority.pubkey()),
+        &[&authority],
+        context.last_blockhash,
+    );
+    let result = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(result, EPOCH_REPORT_RETAINED);
+
+    let first_closable_slot = context
+        .genesis_config()
+        .epoch_schedule
+        .get_first_slot_in_epoch(epoch + 2);
+    context.warp_to_slot(first_closable_slot).unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[close_epoch_report_instruction(&ahkey, &ah, &epoch_report)],
+        Some(&authority.pubkey()),
+        &[&authority],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+    assert!(context
+        .banks_client
+        .get_account(epoch_report)
+        .await
+        .unwrap()
+        .is_none());
+}
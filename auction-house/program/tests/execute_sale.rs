@@ -16,12 +16,16 @@ use solana_sdk::signer::Signer;
 use std::assert_eq;
 
 use solana_program::{
-    instruction::{Instruction, InstructionError},
+    instruction::{AccountMeta, Instruction, InstructionError},
     system_program, sysvar,
 };
 
 use mpl_auction_house::{
-    pda::{find_escrow_payment_address, find_program_as_signer_address, find_trade_state_address},
+    constants::TRADE_STATE_WITH_RENT_PAYER_SIZE,
+    pda::{
+        find_escrow_payment_address, find_proceeds_route_address, find_program_as_signer_address,
+        find_purchase_receipt_address, find_trade_state_address,
+    },
     receipt::{BidReceipt, ListingReceipt, PurchaseReceipt},
 };
 use solana_program::program_pack::Pack;
@@ -87,7 +91,7 @@ async fn execute_sale_existing_token_account_success() {
     create_associated_token_account(&mut context, &buyer, &test_metadata.mint.pubkey())
         .await
         .unwrap();
-    let accounts = mpl_auction_house::accounts::ExecuteSale {
+    let mut accounts = mpl_auction_house::accounts::ExecuteSale {
         buyer: buyer.pubkey(),
         seller: test_metadata.token.pubkey(),
         auction_house: ahkey,
@@ -111,6 +115,10 @@ async fn execute_sale_existing_token_account_success() {
         rent: sysvar::rent::id(),
     }
     .to_account_metas(None);
+    accounts.push(AccountMeta::new_readonly(
+        find_proceeds_route_address(&test_metadata.token.pubkey(), &sell_acc.seller_trade_state).0,
+        false,
+    ));
     let (_, free_sts_bump) = find_trade_state_address(
         &test_metadata.token.pubkey(),
         &ahkey,
@@ -179,7 +187,17 @@ async fn execute_sale_existing_token_account_success() {
     )
     .unwrap();
     let fee_minus: u64 = 100_000_000 - ((ah.seller_fee_basis_points as u64 * 100_000_000) / 10000);
-    assert_eq!(seller_before.lamports + fee_minus, seller_after.lamports);
+    // The seller funded its trade state, so its rent comes back with the proceeds.
+    let trade_state_rent = context
+        .banks_client
+        .get_rent()
+        .await
+        .unwrap()
+        .minimum_balance(TRADE_STATE_WITH_RENT_PAYER_SIZE);
+    assert_eq!(
+        seller_before.lamports + fee_minus + trade_state_rent,
+        seller_after.lamports
+    );
     assert!(seller_before.lamports < seller_after.lamports);
     assert_eq!(buyer_token_after.amount, 1);
 }
@@ -249,7 +267,7 @@ async fn execute_sale_wrong_token_account_owner_success() {
         .await
         .unwrap();
 
-    let accounts = mpl_auction_house::accounts::ExecuteSale {
+    let mut accounts = mpl_auction_house::accounts::ExecuteSale {
         buyer: buyer.pubkey(),
         seller: test_metadata.token.pubkey(),
         auction_house: ahkey,
@@ -273,6 +291,10 @@ async fn execute_sale_wrong_token_account_owner_success() {
         rent: sysvar::rent::id(),
     }
     .to_account_metas(None);
+    accounts.push(AccountMeta::new_readonly(
+        find_proceeds_route_address(&test_metadata.token.pubkey(), &sell_acc.seller_trade_state).0,
+        false,
+    ));
     let (_, free_sts_bump) = find_trade_state_address(
         &test_metadata.token.pubkey(),
         &ahkey,
@@ -379,7 +401,7 @@ async fn execute_sale_success() {
     let buyer_token_account =
         get_associated_token_address(&buyer.pubkey(), &test_metadata.mint.pubkey());
 
-    let accounts = mpl_auction_house::accounts::ExecuteSale {
+    let mut accounts = mpl_auction_house::accounts::ExecuteSale {
         buyer: buyer.pubkey(),
         seller: test_metadata.token.pubkey(),
         auction_house: ahkey,
@@ -403,6 +425,10 @@ async fn execute_sale_success() {
         rent: sysvar::rent::id(),
     }
     .to_account_metas(None);
+    accounts.push(AccountMeta::new_readonly(
+        find_proceeds_route_address(&test_metadata.token.pubkey(), &sell_acc.seller_trade_state).0,
+        false,
+    ));
     let (_, free_sts_bump) = find_trade_state_address(
         &test_metadata.token.pubkey(),
         &ahkey,
@@ -471,11 +497,185 @@ async fn execute_sale_success() {
     )
     .unwrap();
     let fee_minus: u64 = 100_000_000 - ((ah.seller_fee_basis_points as u64 * 100_000_000) / 10000);
-    assert_eq!(seller_before.lamports + fee_minus, seller_after.lamports);
+    // The seller funded its trade state, so its rent comes back with the proceeds.
+    let trade_state_rent = context
+        .banks_client
+        .get_rent()
+        .await
+        .unwrap()
+        .minimum_balance(TRADE_STATE_WITH_RENT_PAYER_SIZE);
+    assert_eq!(
+        seller_before.lamports + fee_minus + trade_state_rent,
+        seller_after.lamports
+    );
     assert!(seller_before.lamports < seller_after.lamports);
     assert_eq!(buyer_token_after.amount, 1);
 }
 
+#[tokio::test]
+async fn execute_sale_auto_receipts_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    // Payer Wallet
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    update_auction_house(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        None,
+        None,
+        None,
+        Some(true),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    let test_metadata = Metadata::new();
+    airdrop(&mut context, &test_metadata.token.pubkey(), 10_000_000_000)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            &mut context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+    let ((sell_acc, _), sell_tx) = sell(&mut context, &ahkey, &ah, &test_metadata, 100_000_000, 1);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), 10_000_000_000)
+        .await
+        .unwrap();
+    let ((bid_acc, _), buy_tx) = buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &test_metadata.token.pubkey(),
+        &buyer,
+        100_000_000,
+        1,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+    let buyer_token_account =
+        get_associated_token_address(&buyer.pubkey(), &test_metadata.mint.pubkey());
+    let (purchase_receipt, _) =
+        find_purchase_receipt_address(&sell_acc.seller_trade_state, &bid_acc.buyer_trade_state);
+
+    let mut accounts = mpl_auction_house::accounts::ExecuteSale {
+        buyer: buyer.pubkey(),
+        seller: test_metadata.token.pubkey(),
+        auction_house: ahkey,
+        metadata: test_metadata.pubkey,
+        token_account: sell_acc.token_account,
+        authority: ah.authority,
+        seller_trade_state: sell_acc.seller_trade_state,
+        buyer_trade_state: bid_acc.buyer_trade_state,
+        token_program: spl_token::id(),
+        free_trade_state: sell_acc.free_seller_trade_state,
+        seller_payment_receipt_account: test_metadata.token.pubkey(),
+        buyer_receipt_token_account: buyer_token_account,
+        escrow_payment_account: bid_acc.escrow_payment_account,
+        token_mint: test_metadata.mint.pubkey(),
+        auction_house_fee_account: ah.auction_house_fee_account,
+        auction_house_treasury: ah.auction_house_treasury,
+        treasury_mint: ah.treasury_mint,
+        program_as_signer: sell_acc.program_as_signer,
+        system_program: system_program::id(),
+        ata_program: spl_associated_token_account::id(),
+        rent: sysvar::rent::id(),
+    }
+    .to_account_metas(None);
+    accounts.push(AccountMeta::new_readonly(
+        find_proceeds_route_address(&test_metadata.token.pubkey(), &sell_acc.seller_trade_state).0,
+        false,
+    ));
+    accounts.push(AccountMeta::new(purchase_receipt, false));
+    let (_, free_sts_bump) = find_trade_state_address(
+        &test_metadata.token.pubkey(),
+        &ahkey,
+        &sell_acc.token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        0,
+        1,
+    );
+    let (_, escrow_bump) = find_escrow_payment_address(&ahkey, &buyer.pubkey());
+    let (_, pas_bump) = find_program_as_signer_address();
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::ExecuteSale {
+            escrow_payment_bump: escrow_bump,
+            _free_trade_state_bump: free_sts_bump,
+            program_as_signer_bump: pas_bump,
+            token_size: 1,
+            buyer_price: 100_000_000,
+            partial_order_size: None,
+            partial_order_price: None,
+        }
+        .data(),
+        accounts,
+    };
+    airdrop(&mut context, &ah.auction_house_fee_account, 10_000_000_000)
+        .await
+        .unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&authority.pubkey()),
+        &[&authority],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let purchase_receipt_account = context
+        .banks_client
+        .get_account(purchase_receipt)
+        .await
+        .expect("getting purchase receipt")
+        .expect("empty purchase receipt data");
+    let purchase_receipt =
+        PurchaseReceipt::try_deserialize(&mut purchase_receipt_account.data.as_ref()).unwrap();
+
+    assert_eq!(purchase_receipt.auction_house, ahkey);
+    assert_eq!(purchase_receipt.buyer, buyer.pubkey());
+    assert_eq!(purchase_receipt.seller, test_metadata.token.pubkey());
+    assert_eq!(purchase_receipt.metadata, test_metadata.pubkey);
+    assert_eq!(purchase_receipt.bookkeeper, ah.auction_house_fee_account);
+    assert_eq!(purchase_receipt.price, 100_000_000);
+    assert_eq!(purchase_receipt.token_size, 1);
+    assert!(!purchase_receipt.self_trade);
+    assert!(!purchase_receipt.sold_by_creator);
+}
+
 #[tokio::test]
 async fn auctioneer_execute_sale_success() {
     let mut context = auction_house_program_test().start_with_context().await;
@@ -648,7 +848,17 @@ async fn auctioneer_execute_sale_success() {
     )
     .unwrap();
     let fee_minus: u64 = 100_000_000 - ((ah.seller_fee_basis_points as u64 * 100_000_000) / 10000);
-    assert_eq!(seller_before.lamports + fee_minus, seller_after.lamports);
+    // The seller funded its trade state, so its rent comes back with the proceeds.
+    let trade_state_rent = context
+        .banks_client
+        .get_rent()
+        .await
+        .unwrap()
+        .minimum_balance(TRADE_STATE_WITH_RENT_PAYER_SIZE);
+    assert_eq!(
+        seller_before.lamports + fee_minus + trade_state_rent,
+        seller_after.lamports
+    );
     assert!(seller_before.lamports < seller_after.lamports);
     assert_eq!(buyer_token_after.amount, 1);
 }
@@ -1058,7 +1268,17 @@ async fn execute_public_sale_success() {
     )
     .unwrap();
 
-    assert_eq!(seller_before.lamports + fee_minus, seller_after.lamports);
+    // The seller funded its trade state, so its rent comes back with the proceeds.
+    let trade_state_rent = context
+        .banks_client
+        .get_rent()
+        .await
+        .unwrap()
+        .minimum_balance(TRADE_STATE_WITH_RENT_PAYER_SIZE);
+    assert_eq!(
+        seller_before.lamports + fee_minus + trade_state_rent,
+        seller_after.lamports
+    );
     assert!(seller_before.lamports < seller_after.lamports);
     assert_eq!(buyer_token_after.amount, 1);
     let new_seller = buyer;
@@ -1352,7 +1572,17 @@ async fn auctioneer_execute_public_sale_success() {
     )
     .unwrap();
 
-    assert_eq!(seller_before.lamports + fee_minus, seller_after.lamports);
+    // The seller funded its trade state, so its rent comes back with the proceeds.
+    let trade_state_rent = context
+        .banks_client
+        .get_rent()
+        .await
+        .unwrap()
+        .minimum_balance(TRADE_STATE_WITH_RENT_PAYER_SIZE);
+    assert_eq!(
+        seller_before.lamports + fee_minus + trade_state_rent,
+        seller_after.lamports
+    );
     assert!(seller_before.lamports < seller_after.lamports);
     assert_eq!(buyer_token_after.amount, 1);
     let new_seller = buyer;
@@ -1724,7 +1954,7 @@ async fn execute_sale_partial_order_success() {
         .await
         .unwrap();
 
-    let accounts = mpl_auction_house::accounts::ExecuteSale {
+    let mut accounts = mpl_auction_house::accounts::ExecuteSale {
         buyer: buyer.pubkey(),
         seller: test_metadata.token.pubkey(),
         auction_house: ahkey,
@@ -1748,6 +1978,10 @@ async fn execute_sale_partial_order_success() {
         rent: sysvar::rent::id(),
     }
     .to_account_metas(None);
+    accounts.push(AccountMeta::new_readonly(
+        find_proceeds_route_address(&test_metadata.token.pubkey(), &sell_acc.seller_trade_state).0,
+        false,
+    ));
     let (_, free_sts_bump) = find_trade_state_address(
         &test_metadata.token.pubkey(),
         &ahkey,
@@ -1860,7 +2094,7 @@ async fn execute_sale_partial_order_success() {
         .await
         .unwrap();
 
-    let accounts = mpl_auction_house::accounts::ExecuteSale {
+    let mut accounts = mpl_auction_house::accounts::ExecuteSale {
         buyer: buyer.pubkey(),
         seller: test_metadata.token.pubkey(),
         auction_house: ahkey,
@@ -1884,6 +2118,10 @@ async fn execute_sale_partial_order_success() {
         rent: sysvar::rent::id(),
     }
     .to_account_metas(None);
+    accounts.push(AccountMeta::new_readonly(
+        find_proceeds_route_address(&test_metadata.token.pubkey(), &sell_acc.seller_trade_state).0,
+        false,
+    ));
 
     let (_, escrow_bump) = find_escrow_payment_address(&ahkey, &buyer.pubkey());
     let (_, pas_bump) = find_program_as_signer_address();
@@ -1958,7 +2196,17 @@ async fn execute_sale_partial_order_success() {
     .unwrap();
 
     let fee_minus: u64 = 300_000_000 - ((ah.seller_fee_basis_points as u64 * 300_000_000) / 10000);
-    assert_eq!(seller_before.lamports + fee_minus, seller_after.lamports);
+    // The seller funded its trade state, so its rent comes back with the proceeds.
+    let trade_state_rent = context
+        .banks_client
+        .get_rent()
+        .await
+        .unwrap()
+        .minimum_balance(TRADE_STATE_WITH_RENT_PAYER_SIZE);
+    assert_eq!(
+        seller_before.lamports + fee_minus + trade_state_rent,
+        seller_after.lamports
+    );
     assert!(seller_before.lamports < seller_after.lamports);
     assert_eq!(buyer_token_after.amount, 3);
     assert_eq!(seller_token_after.amount, 0);
@@ -2053,7 +2301,7 @@ async fn execute_sale_fail_buyer_trade_state_does_not_exist() {
         1,
     );
 
-    let accounts = mpl_auction_house::accounts::ExecuteSale {
+    let mut accounts = mpl_auction_house::accounts::ExecuteSale {
         buyer: buyer.pubkey(),
         seller: test_metadata.token.pubkey(),
         auction_house: ahkey,
@@ -2077,6 +2325,10 @@ async fn execute_sale_fail_buyer_trade_state_does_not_exist() {
         rent: sysvar::rent::id(),
     }
     .to_account_metas(None);
+    accounts.push(AccountMeta::new_readonly(
+        find_proceeds_route_address(&test_metadata.token.pubkey(), &sell_acc.seller_trade_state).0,
+        false,
+    ));
 
     let (_, free_sts_bump) = find_trade_state_address(
         &test_metadata.token.pubkey(),
@@ -2182,7 +2434,7 @@ async fn execute_sale_partial_order_order_exceeds_tokens() {
         .await
         .unwrap();
 
-    let accounts = mpl_auction_house::accounts::ExecuteSale {
+    let mut accounts = mpl_auction_house::accounts::ExecuteSale {
         buyer: buyer.pubkey(),
         seller: test_metadata.token.pubkey(),
         auction_house: ahkey,
@@ -2206,6 +2458,10 @@ async fn execute_sale_partial_order_order_exceeds_tokens() {
         rent: sysvar::rent::id(),
     }
     .to_account_metas(None);
+    accounts.push(AccountMeta::new_readonly(
+        find_proceeds_route_address(&test_metadata.token.pubkey(), &sell_acc.seller_trade_state).0,
+        false,
+    ));
     let (_, free_sts_bump) = find_trade_state_address(
         &test_metadata.token.pubkey(),
         &ahkey,
@@ -2318,7 +2574,7 @@ async fn execute_sale_partial_order_order_exceeds_tokens() {
         .await
         .unwrap();
 
-    let accounts = mpl_auction_house::accounts::ExecuteSale {
+    let mut accounts = mpl_auction_house::accounts::ExecuteSale {
         buyer: buyer.pubkey(),
         seller: test_metadata.token.pubkey(),
         auction_house: ahkey,
@@ -2342,6 +2598,10 @@ async fn execute_sale_partial_order_order_exceeds_tokens() {
         rent: sysvar::rent::id(),
     }
     .to_account_metas(None);
+    accounts.push(AccountMeta::new_readonly(
+        find_proceeds_route_address(&test_metadata.token.pubkey(), &sell_acc.seller_trade_state).0,
+        false,
+    ));
 
     let (_, escrow_bump) = find_escrow_payment_address(&ahkey, &buyer.pubkey());
     let (_, pas_bump) = find_program_as_signer_address();
@@ -2435,7 +2695,7 @@ async fn execute_sale_partial_order_fail_price_mismatch() {
         .await
         .unwrap();
 
-    let accounts = mpl_auction_house::accounts::ExecuteSale {
+    let mut accounts = mpl_auction_house::accounts::ExecuteSale {
         buyer: buyer.pubkey(),
         seller: test_metadata.token.pubkey(),
         auction_house: ahkey,
@@ -2459,6 +2719,10 @@ async fn execute_sale_partial_order_fail_price_mismatch() {
         rent: sysvar::rent::id(),
     }
     .to_account_metas(None);
+    accounts.push(AccountMeta::new_readonly(
+        find_proceeds_route_address(&test_metadata.token.pubkey(), &sell_acc.seller_trade_state).0,
+        false,
+    ));
     let (_, free_sts_bump) = find_trade_state_address(
         &test_metadata.token.pubkey(),
         &ahkey,
@@ -2571,7 +2835,7 @@ async fn execute_sale_partial_order_fail_price_mismatch() {
         .await
         .unwrap();
 
-    let accounts = mpl_auction_house::accounts::ExecuteSale {
+    let mut accounts = mpl_auction_house::accounts::ExecuteSale {
         buyer: buyer.pubkey(),
         seller: test_metadata.token.pubkey(),
         auction_house: ahkey,
@@ -2595,6 +2859,10 @@ async fn execute_sale_partial_order_fail_price_mismatch() {
         rent: sysvar::rent::id(),
     }
     .to_account_metas(None);
+    accounts.push(AccountMeta::new_readonly(
+        find_proceeds_route_address(&test_metadata.token.pubkey(), &sell_acc.seller_trade_state).0,
+        false,
+    ));
 
     let (_, escrow_bump) = find_escrow_payment_address(&ahkey, &buyer.pubkey());
     let (_, pas_bump) = find_program_as_signer_address();
@@ -2688,7 +2956,7 @@ async fn execute_sale_partial_order_fail_missing_elements() {
         .await
         .unwrap();
 
-    let accounts = mpl_auction_house::accounts::ExecuteSale {
+    let mut accounts = mpl_auction_house::accounts::ExecuteSale {
         buyer: buyer.pubkey(),
         seller: test_metadata.token.pubkey(),
         auction_house: ahkey,
@@ -2712,6 +2980,10 @@ async fn execute_sale_partial_order_fail_missing_elements() {
         rent: sysvar::rent::id(),
     }
     .to_account_metas(None);
+    accounts.push(AccountMeta::new_readonly(
+        find_proceeds_route_address(&test_metadata.token.pubkey(), &sell_acc.seller_trade_state).0,
+        false,
+    ));
     let (_, free_sts_bump) = find_trade_state_address(
         &test_metadata.token.pubkey(),
         &ahkey,
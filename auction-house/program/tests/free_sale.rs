@@ -0,0 +1,319 @@
+#![cfg(feature = "test-bpf")]
+pub mod common;
+pub mod utils;
+
+use common::*;
+use mpl_auction_house::{
+    pda::{
+        find_escrow_payment_address, find_free_sale_counter_address, find_proceeds_route_address,
+        find_program_as_signer_address, find_trade_state_address,
+    },
+    FreeSaleCounter,
+};
+use solana_program::{instruction::AccountMeta, system_program, sysvar};
+use utils::setup_functions::*;
+
+fn free_execute_sale_instruction(
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    buyer: &Pubkey,
+    token_size: u64,
+    free_sale_counter: Option<Pubkey>,
+) -> Instruction {
+    let seller = test_metadata.token.pubkey();
+    let token_account = get_associated_token_address(&seller, &test_metadata.mint.pubkey());
+    let (seller_trade_state, free_sts_bump) = find_trade_state_address(
+        &seller,
+        ahkey,
+        &token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        0,
+        token_size,
+    );
+    let (buyer_trade_state, _) = find_trade_state_address(
+        buyer,
+        ahkey,
+        &token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        0,
+        token_size,
+    );
+    let (escrow_payment_account, escrow_bump) = find_escrow_payment_address(ahkey, buyer);
+    let (program_as_signer, pas_bump) = find_program_as_signer_address();
+    let mut accounts = mpl_auction_house::accounts::ExecuteSale {
+        buyer: *buyer,
+        seller,
+        auction_house: *ahkey,
+        metadata: test_metadata.pubkey,
+        token_account,
+        authority: ah.authority,
+        seller_trade_state,
+        buyer_trade_state,
+        token_program: spl_token::id(),
+        free_trade_state: seller_trade_state,
+        seller_payment_receipt_account: seller,
+        buyer_receipt_token_account: get_associated_token_address(
+            buyer,
+            &test_metadata.mint.pubkey(),
+        ),
+        escrow_payment_account,
+        token_mint: test_metadata.mint.pubkey(),
+        auction_house_fee_account: ah.auction_house_fee_account,
+        auction_house_treasury: ah.auction_house_treasury,
+        treasury_mint: ah.treasury_mint,
+        program_as_signer,
+        system_program: system_program::id(),
+        ata_program: spl_associated_token_account::id(),
+        rent: sysvar::rent::id(),
+    }
+    .to_account_metas(None);
+    accounts.push(AccountMeta::new_readonly(
+        find_proceeds_route_address(&seller, &seller_trade_state).0,
+        false,
+    ));
+    if let Some(free_sale_counter) = free_sale_counter {
+        accounts.push(AccountMeta::new(free_sale_counter, false));
+    }
+
+    Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::ExecuteSale {
+            escrow_payment_bump: escrow_bump,
+            _free_trade_state_bump: free_sts_bump,
+            program_as_signer_bump: pas_bump,
+            token_size,
+            buyer_price: 0,
+            partial_order_size: None,
+            partial_order_price: None,
+        }
+        .data(),
+        accounts,
+    }
+}
+
+async fn list_and_bid_free(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    token_size: u64,
+) -> Keypair {
+    let (_, sell_tx) = sell(context, ahkey, ah, test_metadata, 0, token_size);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let buyer = Keypair::new();
+    airdrop(context, &buyer.pubkey(), TEN_SOL).await.unwrap();
+    let (_, buy_tx) = buy(
+        context,
+        ahkey,
+        ah,
+        test_metadata,
+        &test_metadata.token.pubkey(),
+        &buyer,
+        0,
+        token_size,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    buyer
+}
+
+#[tokio::test]
+async fn free_sale_requires_auction_house_sign_off() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    update_auction_house(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some(true),
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    let test_metadata = Metadata::new();
+    airdrop(&mut context, &test_metadata.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            &mut context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+    let buyer = list_and_bid_free(&mut context, &ahkey, &ah, &test_metadata, 1).await;
+
+    // The seller sign off is not enough.
+    let tx = Transaction::new_signed_with_payer(
+        &[free_execute_sale_instruction(
+            &ahkey,
+            &ah,
+            &test_metadata,
+            &buyer.pubkey(),
+            1,
+            None,
+        )],
+        Some(&test_metadata.token.pubkey()),
+        &[&test_metadata.token],
+        context.last_blockhash,
+    );
+    let result = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(result, FREE_SALE_REQUIRES_AUCTION_HOUSE_SIGNOFF);
+
+    airdrop(&mut context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[free_execute_sale_instruction(
+            &ahkey,
+            &ah,
+            &test_metadata,
+            &buyer.pubkey(),
+            1,
+            None,
+        )],
+        Some(&authority.pubkey()),
+        &[&authority],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+}
+
+#[tokio::test]
+async fn free_sales_capped_per_epoch() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    update_auction_house(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some(1),
+        None,
+        None,
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    let test_metadata = Metadata::new();
+    airdrop(&mut context, &test_metadata.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            &mut context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            3,
+        )
+        .await
+        .unwrap();
+    airdrop(&mut context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+    let (free_sale_counter, _) =
+        find_free_sale_counter_address(&ahkey, &test_metadata.token.pubkey());
+
+    let buyer = list_and_bid_free(&mut context, &ahkey, &ah, &test_metadata, 1).await;
+    let tx = Transaction::new_signed_with_payer(
+        &[free_execute_sale_instruction(
+            &ahkey,
+            &ah,
+            &test_metadata,
+            &buyer.pubkey(),
+            1,
+            Some(free_sale_counter),
+        )],
+        Some(&authority.pubkey()),
+        &[&authority],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let counter_account = context
+        .banks_client
+        .get_account(free_sale_counter)
+        .await
+        .unwrap()
+        .unwrap();
+    let counter = FreeSaleCounter::try_deserialize(&mut counter_account.data.as_ref()).unwrap();
+    assert_eq!(counter.seller, test_metadata.token.pubkey());
+    assert_eq!(counter.count, 1);
+
+    // A second free sale by the same seller in the epoch goes over the cap.
+    let buyer = list_and_bid_free(&mut context, &ahkey, &ah, &test_metadata, 2).await;
+    let tx = Transaction::new_signed_with_payer(
+        &[free_execute_sale_instruction(
+            &ahkey,
+            &ah,
+            &test_metadata,
+            &buyer.pubkey(),
+            2,
+            Some(free_sale_counter),
+        )],
+        Some(&authority.pubkey()),
+        &[&authority],
+        context.last_blockhash,
+    );
+    let result = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(result, FREE_SALE_CAP_EXCEEDED);
+}
@@ -0,0 +1,332 @@
+#![cfg(feature = "test-bpf")]
+pub mod common;
+pub mod utils;
+
+use common::*;
+use mpl_auction_house::{
+    pda::find_layaway_escrow_address, utils::custodial_identity_hash, Layaway,
+};
+use solana_program::program_pack::Pack;
+use solana_sdk::sysvar::clock::Clock;
+use spl_token::state::Account;
+use utils::setup_functions::*;
+
+const DAY: i64 = 86_400;
+
+async fn setup_layaway(
+    context: &mut ProgramTestContext,
+    installment_interval: i64,
+) -> (AuctionHouse, Pubkey, Metadata, Keypair, Pubkey) {
+    let (ah, ahkey, _) = existing_auction_house_test_context(context).await.unwrap();
+    let test_metadata = Metadata::new();
+    airdrop(context, &test_metadata.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+    let (_, sell_tx) = sell(context, &ahkey, &ah, &test_metadata, ONE_SOL, 1);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let buyer = Keypair::new();
+    airdrop(context, &buyer.pubkey(), TEN_SOL).await.unwrap();
+    let layaway = begin_layaway(
+        context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &buyer,
+        ONE_SOL,
+        1,
+        2,
+        installment_interval,
+        1000,
+    )
+    .await
+    .unwrap();
+
+    (ah, ahkey, test_metadata, buyer, layaway)
+}
+
+#[tokio::test]
+async fn layaway_paid_in_installments_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, test_metadata, buyer, layaway) = setup_layaway(&mut context, DAY).await;
+
+    let layaway_token_account = context
+        .banks_client
+        .get_account(mpl_auction_house::pda::find_layaway_token_account_address(&layaway).0)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        Account::unpack_from_slice(&layaway_token_account.data)
+            .unwrap()
+            .amount,
+        1
+    );
+
+    pay_installment(&mut context, &ahkey, &ah, &buyer, &layaway, ONE_SOL / 2)
+        .await
+        .unwrap();
+    let result = complete_layaway(&mut context, &ahkey, &ah, &test_metadata, &buyer, &layaway)
+        .await
+        .unwrap_err();
+    assert_error!(result, LAYAWAY_NOT_PAID);
+
+    pay_installment(&mut context, &ahkey, &ah, &buyer, &layaway, ONE_SOL / 2 + 1)
+        .await
+        .unwrap_err();
+    pay_installment(&mut context, &ahkey, &ah, &buyer, &layaway, ONE_SOL - ONE_SOL / 2)
+        .await
+        .unwrap();
+    let layaway_account = context
+        .banks_client
+        .get_account(layaway)
+        .await
+        .unwrap()
+        .unwrap();
+    let layaway_data = Layaway::try_deserialize(&mut layaway_account.data.as_ref()).unwrap();
+    assert_eq!(layaway_data.amount_paid, ONE_SOL);
+
+    context.warp_to_slot(100).unwrap();
+    let seller_before = context
+        .banks_client
+        .get_account(test_metadata.token.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    complete_layaway(&mut context, &ahkey, &ah, &test_metadata, &buyer, &layaway)
+        .await
+        .unwrap();
+
+    let buyer_token_account = context
+        .banks_client
+        .get_account(get_associated_token_address(
+            &buyer.pubkey(),
+            &test_metadata.mint.pubkey(),
+        ))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        Account::unpack_from_slice(&buyer_token_account.data)
+            .unwrap()
+            .amount,
+        1
+    );
+    let seller_after = context
+        .banks_client
+        .get_account(test_metadata.token.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(seller_after.lamports > seller_before.lamports);
+    assert!(context
+        .banks_client
+        .get_account(layaway)
+        .await
+        .unwrap()
+        .is_none());
+    assert!(context
+        .banks_client
+        .get_account(find_layaway_escrow_address(&ahkey, &layaway).0)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn layaway_defaulted_cancel_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, test_metadata, buyer, layaway) = setup_layaway(&mut context, DAY).await;
+
+    pay_installment(&mut context, &ahkey, &ah, &buyer, &layaway, ONE_SOL / 2)
+        .await
+        .unwrap();
+    let result = cancel_layaway(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &buyer.pubkey(),
+        &layaway,
+    )
+    .await
+    .unwrap_err();
+    assert_error!(result, LAYAWAY_NOT_DEFAULTED);
+
+    // Miss the second installment.
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp += 2 * DAY;
+    context.set_sysvar(&clock);
+
+    let result = pay_installment(&mut context, &ahkey, &ah, &buyer, &layaway, ONE_SOL / 4)
+        .await
+        .unwrap_err();
+    assert_error!(result, LAYAWAY_DEFAULTED);
+
+    let seller_before = context
+        .banks_client
+        .get_account(test_metadata.token.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    let buyer_before = context
+        .banks_client
+        .get_account(buyer.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    let escrow_before = context
+        .banks_client
+        .get_account(find_layaway_escrow_address(&ahkey, &layaway).0)
+        .await
+        .unwrap()
+        .unwrap();
+    let layaway_rent = context
+        .banks_client
+        .get_account(layaway)
+        .await
+        .unwrap()
+        .unwrap()
+        .lamports;
+    let layaway_token_rent = context
+        .banks_client
+        .get_account(mpl_auction_house::pda::find_layaway_token_account_address(&layaway).0)
+        .await
+        .unwrap()
+        .unwrap()
+        .lamports;
+    cancel_layaway(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &buyer.pubkey(),
+        &layaway,
+    )
+    .await
+    .unwrap();
+
+    // The seller keeps 10% of the price and pays the transaction fee.
+    let seller_after = context
+        .banks_client
+        .get_account(test_metadata.token.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        seller_after.lamports,
+        seller_before.lamports + ONE_SOL / 10 - 5000
+    );
+    let buyer_after = context
+        .banks_client
+        .get_account(buyer.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        buyer_after.lamports,
+        buyer_before.lamports + escrow_before.lamports - ONE_SOL / 10
+            + layaway_rent
+            + layaway_token_rent
+    );
+
+    let seller_token_account = context
+        .banks_client
+        .get_account(get_associated_token_address(
+            &test_metadata.token.pubkey(),
+            &test_metadata.mint.pubkey(),
+        ))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        Account::unpack_from_slice(&seller_token_account.data)
+            .unwrap()
+            .amount,
+        1
+    );
+    assert!(context
+        .banks_client
+        .get_account(layaway)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn begin_layaway_custodial_listing_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = Metadata::new();
+    airdrop(&mut context, &test_metadata.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            &mut context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+    let user = Keypair::new();
+    let (_, sell_tx) = custodial_sell(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        ONE_SOL,
+        1,
+        custodial_identity_hash(&user.pubkey()),
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    // The platform wallet signs for the seller, but the user never approved the layaway.
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let error = begin_layaway(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &buyer,
+        ONE_SOL,
+        1,
+        2,
+        DAY,
+        1000,
+    )
+    .await
+    .unwrap_err();
+    assert_error!(error, INVALID_CUSTODIAL_APPROVAL);
+}
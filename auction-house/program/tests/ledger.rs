@@ -0,0 +1,129 @@
+#![cfg(all(feature = "test-bpf", feature = "ledger-checks"))]
+
+use mpl_auction_house::{errors::AuctionHouseError, ledger::SaleLedger};
+use solana_program::{account_info::AccountInfo, pubkey::Pubkey, system_program};
+
+const PRICE: u64 = 1_000_000;
+
+fn transfer(source: &AccountInfo, destination: &AccountInfo, amount: u64) {
+    **source.lamports.borrow_mut() -= amount;
+    **destination.lamports.borrow_mut() += amount;
+}
+
+#[test]
+fn verifies_balances_matching_postings() {
+    let (escrow_key, seller_key) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let (mut escrow_lamports, mut seller_lamports) = (PRICE, 0);
+    let escrow = AccountInfo::new(
+        &escrow_key,
+        false,
+        true,
+        &mut escrow_lamports,
+        &mut [],
+        &system_program::ID,
+        false,
+        0,
+    );
+    let seller = AccountInfo::new(
+        &seller_key,
+        false,
+        true,
+        &mut seller_lamports,
+        &mut [],
+        &system_program::ID,
+        false,
+        0,
+    );
+
+    let mut ledger = SaleLedger::default();
+    ledger.post(&escrow, &seller, PRICE, PRICE, false).unwrap();
+    ledger.assert_debited(&escrow, PRICE).unwrap();
+    transfer(&escrow, &seller, PRICE);
+
+    ledger.verify(&Pubkey::new_unique()).unwrap();
+}
+
+#[test]
+fn rejects_balance_not_matching_postings() {
+    let (escrow_key, seller_key, treasury_key) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let (mut escrow_lamports, mut seller_lamports, mut treasury_lamports) = (PRICE, 0, 0);
+    let escrow = AccountInfo::new(
+        &escrow_key,
+        false,
+        true,
+        &mut escrow_lamports,
+        &mut [],
+        &system_program::ID,
+        false,
+        0,
+    );
+    let seller = AccountInfo::new(
+        &seller_key,
+        false,
+        true,
+        &mut seller_lamports,
+        &mut [],
+        &system_program::ID,
+        false,
+        0,
+    );
+    let treasury = AccountInfo::new(
+        &treasury_key,
+        false,
+        true,
+        &mut treasury_lamports,
+        &mut [],
+        &system_program::ID,
+        false,
+        0,
+    );
+
+    let mut ledger = SaleLedger::default();
+    ledger.post(&escrow, &seller, PRICE, PRICE, false).unwrap();
+    // A mis-split sale sends part of the seller proceeds to the treasury
+    transfer(&escrow, &seller, PRICE / 2);
+    transfer(&escrow, &treasury, PRICE / 2);
+
+    let err = ledger.verify(&Pubkey::new_unique()).unwrap_err();
+
+    assert_eq!(err, AuctionHouseError::LedgerMismatch.into());
+}
+
+#[test]
+fn rejects_postings_not_adding_up_to_price() {
+    let (escrow_key, seller_key) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let (mut escrow_lamports, mut seller_lamports) = (PRICE, 0);
+    let escrow = AccountInfo::new(
+        &escrow_key,
+        false,
+        true,
+        &mut escrow_lamports,
+        &mut [],
+        &system_program::ID,
+        false,
+        0,
+    );
+    let seller = AccountInfo::new(
+        &seller_key,
+        false,
+        true,
+        &mut seller_lamports,
+        &mut [],
+        &system_program::ID,
+        false,
+        0,
+    );
+
+    let mut ledger = SaleLedger::default();
+    ledger
+        .post(&escrow, &seller, PRICE - 1, PRICE - 1, false)
+        .unwrap();
+
+    let err = ledger.assert_debited(&escrow, PRICE).unwrap_err();
+
+    assert_eq!(err, AuctionHouseError::LedgerMismatch.into());
+}
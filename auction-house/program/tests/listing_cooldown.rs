@@ -0,0 +1,521 @@
+#![cfg(feature = "test-bpf")]
+pub mod common;
+pub mod utils;
+
+use common::*;
+use mpl_auction_house::{
+    pda::{
+        find_escrow_payment_address, find_last_sale_address, find_proceeds_route_address,
+        find_program_as_signer_address, find_trade_state_address,
+    },
+    utils::signed_listing_message,
+    LastSale,
+};
+use solana_program::{clock::Clock, instruction::AccountMeta, system_program, sysvar};
+use utils::setup_functions::*;
+
+const PRICE: u64 = 100_000_000;
+const COOLDOWN_SLOTS: u64 = 1_000;
+
+fn sell_instruction(
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    wallet: &Pubkey,
+    last_sale: Option<Pubkey>,
+) -> Instruction {
+    let token_account = get_associated_token_address(wallet, &test_metadata.mint.pubkey());
+    let (seller_trade_state, sts_bump) = find_trade_state_address(
+        wallet,
+        ahkey,
+        &token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        PRICE,
+        1,
+    );
+    let (free_seller_trade_state, free_sts_bump) = find_trade_state_address(
+        wallet,
+        ahkey,
+        &token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        0,
+        1,
+    );
+    let (program_as_signer, pas_bump) = find_program_as_signer_address();
+    let mut accounts = mpl_auction_house::accounts::Sell {
+        wallet: *wallet,
+        token_account,
+        metadata: test_metadata.pubkey,
+        authority: ah.authority,
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        seller_trade_state,
+        free_seller_trade_state,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        program_as_signer,
+        rent: sysvar::rent::id(),
+    }
+    .to_account_metas(None);
+    if let Some(last_sale) = last_sale {
+        accounts.push(AccountMeta::new_readonly(last_sale, false));
+    }
+
+    Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::Sell {
+            trade_state_bump: sts_bump,
+            free_trade_state_bump: free_sts_bump,
+            program_as_signer_bump: pas_bump,
+            buyer_price: PRICE,
+            token_size: 1,
+        }
+        .data(),
+        accounts,
+    }
+}
+
+fn execute_sale_instruction(
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    buyer: &Pubkey,
+    last_sale: &Pubkey,
+) -> Instruction {
+    let seller = test_metadata.token.pubkey();
+    let token_account = get_associated_token_address(&seller, &test_metadata.mint.pubkey());
+    let (seller_trade_state, _) = find_trade_state_address(
+        &seller,
+        ahkey,
+        &token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        PRICE,
+        1,
+    );
+    let (free_trade_state, free_sts_bump) = find_trade_state_address(
+        &seller,
+        ahkey,
+        &token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        0,
+        1,
+    );
+    let (buyer_trade_state, _) = find_trade_state_address(
+        buyer,
+        ahkey,
+        &token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        PRICE,
+        1,
+    );
+    let (escrow_payment_account, escrow_bump) = find_escrow_payment_address(ahkey, buyer);
+    let (program_as_signer, pas_bump) = find_program_as_signer_address();
+    let mut accounts = mpl_auction_house::accounts::ExecuteSale {
+        buyer: *buyer,
+        seller,
+        auction_house: *ahkey,
+        metadata: test_metadata.pubkey,
+        token_account,
+        authority: ah.authority,
+        seller_trade_state,
+        buyer_trade_state,
+        token_program: spl_token::id(),
+        free_trade_state,
+        seller_payment_receipt_account: seller,
+        buyer_receipt_token_account: get_associated_token_address(
+            buyer,
+            &test_metadata.mint.pubkey(),
+        ),
+        escrow_payment_account,
+        token_mint: test_metadata.mint.pubkey(),
+        auction_house_fee_account: ah.auction_house_fee_account,
+        auction_house_treasury: ah.auction_house_treasury,
+        treasury_mint: ah.treasury_mint,
+        program_as_signer,
+        system_program: system_program::id(),
+        ata_program: spl_associated_token_account::id(),
+        rent: sysvar::rent::id(),
+    }
+    .to_account_metas(None);
+    accounts.push(AccountMeta::new_readonly(
+        find_proceeds_route_address(&seller, &seller_trade_state).0,
+        false,
+    ));
+    accounts.push(AccountMeta::new(*last_sale, false));
+
+    Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::ExecuteSale {
+            escrow_payment_bump: escrow_bump,
+            _free_trade_state_bump: free_sts_bump,
+            program_as_signer_bump: pas_bump,
+            token_size: 1,
+            buyer_price: PRICE,
+            partial_order_size: None,
+            partial_order_price: None,
+        }
+        .data(),
+        accounts,
+    }
+}
+
+/// Auction House with the listing cooldown on and a freshly minted NFT.
+async fn cooldown_auction_house(
+    context: &mut ProgramTestContext,
+) -> (AuctionHouse, Pubkey, Keypair, Metadata) {
+    let (ah, ahkey, authority) = existing_auction_house_test_context(context).await.unwrap();
+    update_auction_house(
+        context,
+        &ahkey,
+        &ah,
+        &authority,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some(COOLDOWN_SLOTS),
+    )
+    .await
+    .unwrap();
+
+    let test_metadata = Metadata::new();
+    airdrop(context, &test_metadata.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+
+    (ah, ahkey, authority, test_metadata)
+}
+
+#[tokio::test]
+async fn relist_within_cooldown_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority, test_metadata) = cooldown_auction_house(&mut context).await;
+    let (last_sale, _) = find_last_sale_address(&ahkey, &test_metadata.mint.pubkey());
+
+    // A mint which never sold has no last sale yet.
+    let seller = test_metadata.token.pubkey();
+    let tx = Transaction::new_signed_with_payer(
+        &[sell_instruction(
+            &ahkey,
+            &ah,
+            &test_metadata,
+            &seller,
+            Some(last_sale),
+        )],
+        Some(&seller),
+        &[&test_metadata.token],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let (_, buy_tx) = buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &seller,
+        &buyer,
+        PRICE,
+        1,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+    airdrop(&mut context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[execute_sale_instruction(
+            &ahkey,
+            &ah,
+            &test_metadata,
+            &buyer.pubkey(),
+            &last_sale,
+        )],
+        Some(&authority.pubkey()),
+        &[&authority],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let last_sale_account = context
+        .banks_client
+        .get_account(last_sale)
+        .await
+        .unwrap()
+        .unwrap();
+    let last_sale_data = LastSale::try_deserialize(&mut last_sale_account.data.as_ref()).unwrap();
+    assert_eq!(last_sale_data.token_mint, test_metadata.mint.pubkey());
+    assert_eq!(last_sale_data.buyer, buyer.pubkey());
+    assert_eq!(last_sale_data.seller, seller);
+
+    // The buyer cannot flip the NFT right away.
+    let tx = Transaction::new_signed_with_payer(
+        &[sell_instruction(
+            &ahkey,
+            &ah,
+            &test_metadata,
+            &buyer.pubkey(),
+            Some(last_sale),
+        )],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        context.last_blockhash,
+    );
+    let error = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, LISTING_COOLDOWN_ACTIVE);
+
+    let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    context
+        .warp_to_slot(last_sale_data.slot.max(clock.slot) + COOLDOWN_SLOTS)
+        .unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[sell_instruction(
+            &ahkey,
+            &ah,
+            &test_metadata,
+            &buyer.pubkey(),
+            Some(last_sale),
+        )],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+}
+
+#[tokio::test]
+async fn sell_without_last_sale_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _, test_metadata) = cooldown_auction_house(&mut context).await;
+
+    let seller = test_metadata.token.pubkey();
+    let tx = Transaction::new_signed_with_payer(
+        &[sell_instruction(&ahkey, &ah, &test_metadata, &seller, None)],
+        Some(&seller),
+        &[&test_metadata.token],
+        context.last_blockhash,
+    );
+    let error = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, INVALID_LAST_SALE);
+
+    // The last sale of another mint does not stand in for this one.
+    let (other_last_sale, _) = find_last_sale_address(&ahkey, &Pubkey::new_unique());
+    let tx = Transaction::new_signed_with_payer(
+        &[sell_instruction(
+            &ahkey,
+            &ah,
+            &test_metadata,
+            &seller,
+            Some(other_last_sale),
+        )],
+        Some(&seller),
+        &[&test_metadata.token],
+        context.last_blockhash,
+    );
+    let error = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, INVALID_LAST_SALE);
+}
+
+#[tokio::test]
+async fn post_signed_listing_without_last_sale_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _, test_metadata) = cooldown_auction_house(&mut context).await;
+    let (last_sale, _) = find_last_sale_address(&ahkey, &test_metadata.mint.pubkey());
+
+    let seller = test_metadata.token.pubkey();
+    let token_account = get_associated_token_address(&seller, &test_metadata.mint.pubkey());
+    let (program_as_signer, _) = find_program_as_signer_address();
+    let approve_tx = Transaction::new_signed_with_payer(
+        &[spl_token::instruction::approve(
+            &spl_token::id(),
+            &token_account,
+            &program_as_signer,
+            &seller,
+            &[],
+            1,
+        )
+        .unwrap()],
+        Some(&seller),
+        &[&test_metadata.token],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(approve_tx)
+        .await
+        .unwrap();
+
+    let expiry = context
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap()
+        .unix_timestamp
+        + 60;
+    let message = signed_listing_message(&ahkey, &token_account, PRICE, 1, expiry, 0);
+
+    let (_, post_ix) = post_signed_listing(
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &context.payer.pubkey(),
+        PRICE,
+        1,
+        expiry,
+        0,
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            signed_listing_ed25519_instruction(&test_metadata.token, &message),
+            post_ix,
+        ],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    let error = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    match error {
+        TransportError::TransactionError(TransactionError::InstructionError(
+            1,
+            InstructionError::Custom(code),
+        )) => assert_eq!(code, INVALID_LAST_SALE),
+        _ => panic!("Unexpected error {:?}", error),
+    }
+
+    let (_, mut post_ix) = post_signed_listing(
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &context.payer.pubkey(),
+        PRICE,
+        1,
+        expiry,
+        0,
+    );
+    post_ix
+        .accounts
+        .push(AccountMeta::new_readonly(last_sale, false));
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            signed_listing_ed25519_instruction(&test_metadata.token, &message),
+            post_ix,
+        ],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+}
+
+#[tokio::test]
+async fn migrate_listing_without_last_sale_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (source_ah, source_ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let (ah, ahkey, _, test_metadata) = cooldown_auction_house(&mut context).await;
+    let (last_sale, _) = find_last_sale_address(&ahkey, &test_metadata.mint.pubkey());
+
+    let (_, sell_tx) = sell(
+        &mut context,
+        &source_ahkey,
+        &source_ah,
+        &test_metadata,
+        PRICE,
+        1,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let (_, migrate_tx) = migrate_listing(
+        &mut context,
+        &source_ahkey,
+        &source_ah,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        PRICE,
+        1,
+        None,
+        &[],
+    );
+    let error = context
+        .banks_client
+        .process_transaction(migrate_tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, INVALID_LAST_SALE);
+
+    let (_, migrate_tx) = migrate_listing(
+        &mut context,
+        &source_ahkey,
+        &source_ah,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        PRICE,
+        1,
+        None,
+        &[AccountMeta::new_readonly(last_sale, false)],
+    );
+    context
+        .banks_client
+        .process_transaction(migrate_tx)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,301 @@
+#![cfg(feature = "test-bpf")]
+pub mod common;
+pub mod utils;
+
+use common::*;
+use mpl_auction_house::{
+    constants::MATCH_ORDERS_BOUNTY_BASIS_POINTS,
+    pda::{find_proceeds_route_address, find_program_as_signer_address},
+};
+use solana_program::{instruction::AccountMeta, system_program, sysvar};
+use spl_token::state::Account;
+use utils::setup_functions::*;
+
+use solana_program::program_pack::Pack;
+
+fn match_orders(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    cranker: &Keypair,
+    sell_accounts: &mpl_auction_house::accounts::Sell,
+    buy_accounts: &mpl_auction_house::accounts::Buy,
+    ask_price: u64,
+    bid_price: u64,
+) -> Transaction {
+    let (program_as_signer, _) = find_program_as_signer_address();
+    let buyer_token_account =
+        get_associated_token_address(&buy_accounts.wallet, &test_metadata.mint.pubkey());
+
+    let mut accounts = mpl_auction_house::accounts::MatchOrders {
+        cranker: cranker.pubkey(),
+        cranker_payment_account: cranker.pubkey(),
+        buyer: buy_accounts.wallet,
+        seller: sell_accounts.wallet,
+        token_account: sell_accounts.token_account,
+        token_mint: test_metadata.mint.pubkey(),
+        metadata: test_metadata.pubkey,
+        treasury_mint: ah.treasury_mint,
+        escrow_payment_account: buy_accounts.escrow_payment_account,
+        seller_payment_receipt_account: sell_accounts.wallet,
+        buyer_receipt_token_account: buyer_token_account,
+        authority: ah.authority,
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        auction_house_treasury: ah.auction_house_treasury,
+        buyer_trade_state: buy_accounts.buyer_trade_state,
+        seller_trade_state: sell_accounts.seller_trade_state,
+        free_trade_state: sell_accounts.free_seller_trade_state,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        ata_program: spl_associated_token_account::id(),
+        program_as_signer,
+        rent: sysvar::rent::id(),
+    }
+    .to_account_metas(None);
+    accounts.push(AccountMeta::new_readonly(
+        find_proceeds_route_address(&sell_accounts.wallet, &sell_accounts.seller_trade_state).0,
+        false,
+    ));
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::MatchOrders {
+            ask_price,
+            bid_price,
+            token_size: 1,
+        }
+        .data(),
+        accounts,
+    };
+
+    Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&cranker.pubkey()),
+        &[cranker],
+        context.last_blockhash,
+    )
+}
+
+async fn list_and_bid(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    ask_price: u64,
+    bid_price: u64,
+) -> (
+    mpl_auction_house::accounts::Sell,
+    mpl_auction_house::accounts::Buy,
+) {
+    airdrop(context, &test_metadata.token.pubkey(), 10_000_000_000)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+
+    let ((sell_accounts, _), sell_tx) = sell(context, ahkey, ah, test_metadata, ask_price, 1);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let buyer = Keypair::new();
+    airdrop(context, &buyer.pubkey(), 10_000_000_000)
+        .await
+        .unwrap();
+    let ((buy_accounts, _), buy_tx) = buy(
+        context,
+        ahkey,
+        ah,
+        test_metadata,
+        &test_metadata.token.pubkey(),
+        &buyer,
+        bid_price,
+        1,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+    airdrop(context, &ah.auction_house_fee_account, 10_000_000_000)
+        .await
+        .unwrap();
+
+    (sell_accounts, buy_accounts)
+}
+
+#[tokio::test]
+async fn match_crossing_orders_pays_bounty_from_spread() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = Metadata::new();
+    let (sell_accounts, buy_accounts) = list_and_bid(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        100_000_000,
+        110_000_000,
+    )
+    .await;
+
+    let cranker = Keypair::new();
+    airdrop(&mut context, &cranker.pubkey(), 1_000_000_000)
+        .await
+        .unwrap();
+    let escrow_before = context
+        .banks_client
+        .get_balance(buy_accounts.escrow_payment_account)
+        .await
+        .unwrap();
+
+    let tx = match_orders(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &cranker,
+        &sell_accounts,
+        &buy_accounts,
+        100_000_000,
+        110_000_000,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let bounty = 100_000_000 * MATCH_ORDERS_BOUNTY_BASIS_POINTS as u64 / 10000;
+    let escrow_after = context
+        .banks_client
+        .get_balance(buy_accounts.escrow_payment_account)
+        .await
+        .unwrap();
+    assert_eq!(escrow_before - escrow_after, 100_000_000 + bounty);
+
+    let buyer_token_account =
+        get_associated_token_address(&buy_accounts.wallet, &test_metadata.mint.pubkey());
+    let buyer_token = Account::unpack_from_slice(
+        context
+            .banks_client
+            .get_account(buyer_token_account)
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+            .as_slice(),
+    )
+    .unwrap();
+    assert_eq!(buyer_token.amount, 1);
+    assert!(context
+        .banks_client
+        .get_account(buy_accounts.buyer_trade_state)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn match_orders_at_same_price_pays_bounty_from_house_fee() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = Metadata::new();
+    let (sell_accounts, buy_accounts) = list_and_bid(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        100_000_000,
+        100_000_000,
+    )
+    .await;
+
+    let cranker = Keypair::new();
+    airdrop(&mut context, &cranker.pubkey(), 1_000_000_000)
+        .await
+        .unwrap();
+    let treasury_before = context
+        .banks_client
+        .get_balance(ah.auction_house_treasury)
+        .await
+        .unwrap();
+
+    let tx = match_orders(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &cranker,
+        &sell_accounts,
+        &buy_accounts,
+        100_000_000,
+        100_000_000,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let house_fee = 100_000_000 * ah.seller_fee_basis_points as u64 / 10000;
+    let bounty = 100_000_000 * MATCH_ORDERS_BOUNTY_BASIS_POINTS as u64 / 10000;
+    let treasury_after = context
+        .banks_client
+        .get_balance(ah.auction_house_treasury)
+        .await
+        .unwrap();
+    assert_eq!(treasury_after - treasury_before, house_fee - bounty);
+}
+
+#[tokio::test]
+async fn match_bid_below_ask_fail() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = Metadata::new();
+    let (sell_accounts, buy_accounts) = list_and_bid(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        100_000_000,
+        90_000_000,
+    )
+    .await;
+
+    let cranker = Keypair::new();
+    airdrop(&mut context, &cranker.pubkey(), 1_000_000_000)
+        .await
+        .unwrap();
+
+    let tx = match_orders(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &cranker,
+        &sell_accounts,
+        &buy_accounts,
+        100_000_000,
+        90_000_000,
+    );
+    let error = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, BID_BELOW_ASK);
+}
@@ -0,0 +1,97 @@
+#![cfg(feature = "test-bpf")]
+
+use anchor_lang::AnchorSerialize;
+use mpl_auction_house::{
+    errors::AuctionHouseError,
+    metadata_reader::{MetadataCreator, MetadataRoyalties},
+};
+use mpl_token_metadata::state::{Creator, Data, Key, Metadata};
+use solana_program::pubkey::Pubkey;
+
+fn metadata_bytes(creators: Option<Vec<Creator>>, primary_sale_happened: bool) -> Vec<u8> {
+    let metadata = Metadata {
+        key: Key::MetadataV1,
+        update_authority: Pubkey::new_unique(),
+        mint: Pubkey::new_unique(),
+        data: Data {
+            name: "Test NFT".to_string(),
+            symbol: "TEST".to_string(),
+            uri: "https://example.com/nft.json".to_string(),
+            seller_fee_basis_points: 250,
+            creators,
+        },
+        primary_sale_happened,
+        is_mutable: true,
+        edition_nonce: Some(255),
+        token_standard: None,
+        collection: None,
+        uses: None,
+    };
+
+    metadata.try_to_vec().unwrap()
+}
+
+#[test]
+fn reads_royalty_fields() {
+    let creators = vec![
+        Creator {
+            address: Pubkey::new_unique(),
+            verified: true,
+            share: 60,
+        },
+        Creator {
+            address: Pubkey::new_unique(),
+            verified: false,
+            share: 40,
+        },
+    ];
+    let data = metadata_bytes(Some(creators.clone()), true);
+
+    let metadata = MetadataRoyalties::from_bytes(&data).unwrap();
+
+    assert_eq!(metadata.seller_fee_basis_points, 250);
+    assert!(metadata.primary_sale_happened);
+    assert!(metadata.has_creators());
+    assert_eq!(
+        metadata.creators().collect::<Vec<_>>(),
+        creators
+            .iter()
+            .map(|creator| MetadataCreator {
+                address: creator.address,
+                verified: creator.verified,
+                share: creator.share,
+            })
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn reads_metadata_without_creators() {
+    let data = metadata_bytes(None, false);
+
+    let metadata = MetadataRoyalties::from_bytes(&data).unwrap();
+
+    assert_eq!(metadata.seller_fee_basis_points, 250);
+    assert!(!metadata.primary_sale_happened);
+    assert!(!metadata.has_creators());
+    assert_eq!(metadata.creators().count(), 0);
+}
+
+#[test]
+fn truncated_metadata_fail() {
+    let data = metadata_bytes(None, false);
+
+    let err = MetadataRoyalties::from_bytes(&data[..100]).unwrap_err();
+
+    assert_eq!(err, AuctionHouseError::InvalidMetadata.into());
+}
+
+#[test]
+fn not_metadata_account_fail() {
+    let mut data = metadata_bytes(None, false);
+    data[0] = Key::MasterEditionV2 as u8;
+
+    let err = MetadataRoyalties::from_bytes(&data).unwrap_err();
+
+    assert_eq!(err, AuctionHouseError::InvalidMetadata.into());
+}
@@ -0,0 +1,166 @@
+#![cfg(feature = "test-bpf")]
+pub mod common;
+pub mod utils;
+
+use common::*;
+use mpl_auction_house::{
+    constants::TRADE_STATE_WITH_RENT_PAYER_SIZE, pda::find_listing_receipt_address,
+    receipt::ListingReceipt,
+};
+use utils::setup_functions::*;
+
+#[tokio::test]
+async fn migrate_listing_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (source_ah, source_ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let (destination_ah, destination_ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = Metadata::new();
+    airdrop(&mut context, &test_metadata.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            &mut context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+    let ((sell_acc, _), sell_tx) = sell(
+        &mut context,
+        &source_ahkey,
+        &source_ah,
+        &test_metadata,
+        ONE_SOL,
+        1,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+    let (source_listing_receipt, _) = find_listing_receipt_address(&sell_acc.seller_trade_state);
+
+    let (acc, migrate_tx) = migrate_listing(
+        &mut context,
+        &source_ahkey,
+        &source_ah,
+        &destination_ahkey,
+        &destination_ah,
+        &test_metadata,
+        ONE_SOL,
+        1,
+        Some(source_listing_receipt),
+        &[],
+    );
+    context
+        .banks_client
+        .process_transaction(migrate_tx)
+        .await
+        .unwrap();
+
+    let source_trade_state = context
+        .banks_client
+        .get_account(acc.source_trade_state)
+        .await
+        .expect("Error Getting Trade State");
+    assert!(source_trade_state.is_none());
+
+    let destination_trade_state = context
+        .banks_client
+        .get_account(acc.destination_trade_state)
+        .await
+        .expect("Error Getting Trade State")
+        .expect("Trade State Empty");
+    assert_eq!(
+        destination_trade_state.data.len(),
+        TRADE_STATE_WITH_RENT_PAYER_SIZE
+    );
+    assert_eq!(
+        destination_trade_state.data[1..TRADE_STATE_WITH_RENT_PAYER_SIZE],
+        test_metadata.token.pubkey().to_bytes()
+    );
+
+    let receipt_account = context
+        .banks_client
+        .get_account(acc.listing_receipt)
+        .await
+        .expect("Error Getting Listing Receipt")
+        .expect("Listing Receipt Empty");
+    let receipt = ListingReceipt::try_deserialize(&mut receipt_account.data.as_ref()).unwrap();
+    assert_eq!(receipt.auction_house, destination_ahkey);
+    assert_eq!(receipt.trade_state, acc.destination_trade_state);
+    assert_eq!(receipt.seller, test_metadata.token.pubkey());
+    assert_eq!(receipt.price, ONE_SOL);
+    assert_eq!(receipt.token_size, 1);
+    assert_eq!(receipt.canceled_at, None);
+
+    let source_receipt_account = context
+        .banks_client
+        .get_account(source_listing_receipt)
+        .await
+        .expect("Error Getting Listing Receipt")
+        .expect("Listing Receipt Empty");
+    let source_receipt =
+        ListingReceipt::try_deserialize(&mut source_receipt_account.data.as_ref()).unwrap();
+    assert!(source_receipt.canceled_at.is_some());
+}
+
+#[tokio::test]
+async fn migrate_listing_same_auction_house_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = Metadata::new();
+    airdrop(&mut context, &test_metadata.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            &mut context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+    let (_, sell_tx) = sell(&mut context, &ahkey, &ah, &test_metadata, ONE_SOL, 1);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let (_, migrate_tx) = migrate_listing(
+        &mut context,
+        &ahkey,
+        &ah,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        ONE_SOL,
+        1,
+        None,
+        &[],
+    );
+    let result = context
+        .banks_client
+        .process_transaction(migrate_tx)
+        .await
+        .unwrap_err();
+    assert_error!(result, INVALID_LISTING_MIGRATION);
+}
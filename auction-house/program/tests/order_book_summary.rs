@@ -0,0 +1,529 @@
+#![cfg(feature = "test-bpf")]
+pub mod common;
+pub mod utils;
+
+use common::*;
+use mpl_auction_house::{
+    pda::{find_escrow_payment_address, find_program_as_signer_address, find_trade_state_address},
+    OrderBookSummary,
+};
+use solana_program::instruction::AccountMeta;
+use utils::setup_functions::*;
+
+async fn get_order_book_summary(
+    context: &mut ProgramTestContext,
+    order_book_summary: &Pubkey,
+) -> OrderBookSummary {
+    let account = context
+        .banks_client
+        .get_account(*order_book_summary)
+        .await
+        .expect("Error Getting Order Book Summary")
+        .expect("Order Book Summary Empty");
+
+    OrderBookSummary::try_deserialize(&mut account.data.as_ref()).unwrap()
+}
+
+#[tokio::test]
+async fn order_book_summary_tracks_sell_buy_and_cancel() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = Metadata::new();
+    airdrop(&mut context, &test_metadata.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            &mut context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+
+    let order_book_summary = create_order_book_summary(
+        &mut context,
+        &ahkey,
+        &test_metadata.token,
+        &test_metadata.mint.pubkey(),
+    )
+    .await
+    .unwrap();
+
+    let summary = get_order_book_summary(&mut context, &order_book_summary).await;
+    assert_eq!(summary.auction_house, ahkey);
+    assert_eq!(summary.token_mint, test_metadata.mint.pubkey());
+    assert_eq!(summary.open_asks, 0);
+    assert_eq!(summary.open_bids, 0);
+
+    // List with the summary passed as the last account.
+    let ((sell_acc, _), _) = sell(&mut context, &ahkey, &ah, &test_metadata, ONE_SOL, 1);
+    let mut accounts = sell_acc.to_account_metas(None);
+    accounts.push(AccountMeta::new(order_book_summary, false));
+    let (_, sts_bump) = find_trade_state_address(
+        &test_metadata.token.pubkey(),
+        &ahkey,
+        &sell_acc.token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        ONE_SOL,
+        1,
+    );
+    let (_, free_sts_bump) = find_trade_state_address(
+        &test_metadata.token.pubkey(),
+        &ahkey,
+        &sell_acc.token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        0,
+        1,
+    );
+    let (_, pas_bump) = find_program_as_signer_address();
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::Sell {
+            trade_state_bump: sts_bump,
+            free_trade_state_bump: free_sts_bump,
+            program_as_signer_bump: pas_bump,
+            buyer_price: ONE_SOL,
+            token_size: 1,
+        }
+        .data(),
+        accounts,
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&test_metadata.token.pubkey()),
+        &[&test_metadata.token],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let summary = get_order_book_summary(&mut context, &order_book_summary).await;
+    assert_eq!(summary.open_asks, 1);
+    assert_eq!(summary.best_ask, Some(ONE_SOL));
+
+    // Bid below the ask.
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let bid_price = ONE_SOL / 2;
+    let ((bid_acc, _), _) = buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &test_metadata.token.pubkey(),
+        &buyer,
+        bid_price,
+        1,
+    );
+    let mut accounts = bid_acc.to_account_metas(None);
+    accounts.push(AccountMeta::new(order_book_summary, false));
+    let (_, bts_bump) = find_trade_state_address(
+        &buyer.pubkey(),
+        &ahkey,
+        &sell_acc.token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        bid_price,
+        1,
+    );
+    let (_, escrow_bump) = find_escrow_payment_address(&ahkey, &buyer.pubkey());
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::Buy {
+            trade_state_bump: bts_bump,
+            escrow_payment_bump: escrow_bump,
+            buyer_price: bid_price,
+            token_size: 1,
+        }
+        .data(),
+        accounts,
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let summary = get_order_book_summary(&mut context, &order_book_summary).await;
+    assert_eq!(summary.open_bids, 1);
+    assert_eq!(summary.best_bid, Some(bid_price));
+
+    // Cancelling the only listing clears the best ask.
+    let mut accounts = mpl_auction_house::accounts::Cancel {
+        auction_house: ahkey,
+        wallet: test_metadata.token.pubkey(),
+        token_account: sell_acc.token_account,
+        authority: ah.authority,
+        trade_state: sell_acc.seller_trade_state,
+        token_program: spl_token::id(),
+        token_mint: test_metadata.mint.pubkey(),
+        auction_house_fee_account: ah.auction_house_fee_account,
+    }
+    .to_account_metas(None);
+    accounts.push(AccountMeta::new(order_book_summary, false));
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::Cancel {
+            buyer_price: ONE_SOL,
+            token_size: 1,
+        }
+        .data(),
+        accounts,
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&test_metadata.token.pubkey()),
+        &[&test_metadata.token],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let summary = get_order_book_summary(&mut context, &order_book_summary).await;
+    assert_eq!(summary.open_asks, 0);
+    assert_eq!(summary.best_ask, None);
+    assert_eq!(summary.open_bids, 1);
+    assert_eq!(summary.best_bid, Some(bid_price));
+}
+
+#[tokio::test]
+async fn sell_without_order_book_summary_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = Metadata::new();
+    let other_metadata = Metadata::new();
+    airdrop(&mut context, &test_metadata.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    airdrop(&mut context, &other_metadata.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    for metadata in [&test_metadata, &other_metadata] {
+        metadata
+            .create(
+                &mut context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                false,
+                1,
+            )
+            .await
+            .unwrap();
+    }
+
+    let other_summary = create_order_book_summary(
+        &mut context,
+        &ahkey,
+        &other_metadata.token,
+        &other_metadata.mint.pubkey(),
+    )
+    .await
+    .unwrap();
+
+    let ((sell_acc, _), _) = sell(&mut context, &ahkey, &ah, &test_metadata, ONE_SOL, 1);
+    let mut accounts = sell_acc.to_account_metas(None);
+    accounts.push(AccountMeta::new(other_summary, false));
+    let (_, sts_bump) = find_trade_state_address(
+        &test_metadata.token.pubkey(),
+        &ahkey,
+        &sell_acc.token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        ONE_SOL,
+        1,
+    );
+    let (_, free_sts_bump) = find_trade_state_address(
+        &test_metadata.token.pubkey(),
+        &ahkey,
+        &sell_acc.token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        0,
+        1,
+    );
+    let (_, pas_bump) = find_program_as_signer_address();
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::Sell {
+            trade_state_bump: sts_bump,
+            free_trade_state_bump: free_sts_bump,
+            program_as_signer_bump: pas_bump,
+            buyer_price: ONE_SOL,
+            token_size: 1,
+        }
+        .data(),
+        accounts,
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&test_metadata.token.pubkey()),
+        &[&test_metadata.token],
+        context.last_blockhash,
+    );
+    let result = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(result, ORDER_BOOK_SUMMARY_REQUIRED);
+}
+
+#[tokio::test]
+async fn migrate_listing_opens_ask_on_destination_summary() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (source_ah, source_ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = Metadata::new();
+    airdrop(&mut context, &test_metadata.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            &mut context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+
+    let (_, sell_tx) = sell(
+        &mut context,
+        &source_ahkey,
+        &source_ah,
+        &test_metadata,
+        ONE_SOL,
+        1,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let order_book_summary = create_order_book_summary(
+        &mut context,
+        &ahkey,
+        &test_metadata.token,
+        &test_metadata.mint.pubkey(),
+    )
+    .await
+    .unwrap();
+
+    // The destination keeps summaries, so the migrated ask must be counted in it.
+    let (_, migrate_tx) = migrate_listing(
+        &mut context,
+        &source_ahkey,
+        &source_ah,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        ONE_SOL,
+        1,
+        None,
+        &[],
+    );
+    let result = context
+        .banks_client
+        .process_transaction(migrate_tx)
+        .await
+        .unwrap_err();
+    assert_error!(result, ORDER_BOOK_SUMMARY_REQUIRED);
+
+    let (_, migrate_tx) = migrate_listing(
+        &mut context,
+        &source_ahkey,
+        &source_ah,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        ONE_SOL,
+        1,
+        None,
+        &[AccountMeta::new(order_book_summary, false)],
+    );
+    context
+        .banks_client
+        .process_transaction(migrate_tx)
+        .await
+        .unwrap();
+
+    let summary = get_order_book_summary(&mut context, &order_book_summary).await;
+    assert_eq!(summary.open_asks, 1);
+    assert_eq!(summary.best_ask, Some(ONE_SOL));
+}
+
+async fn bid_with_summary(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    buyer: &Keypair,
+    bid_price: u64,
+    order_book_summary: &Pubkey,
+) -> mpl_auction_house::accounts::Buy {
+    airdrop(context, &buyer.pubkey(), TEN_SOL).await.unwrap();
+    let ((bid_acc, _), _) = buy(
+        context,
+        ahkey,
+        ah,
+        test_metadata,
+        &test_metadata.token.pubkey(),
+        buyer,
+        bid_price,
+        1,
+    );
+    let mut accounts = bid_acc.to_account_metas(None);
+    accounts.push(AccountMeta::new(*order_book_summary, false));
+    let (_, bts_bump) = find_trade_state_address(
+        &buyer.pubkey(),
+        ahkey,
+        &bid_acc.token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        bid_price,
+        1,
+    );
+    let (_, escrow_bump) = find_escrow_payment_address(ahkey, &buyer.pubkey());
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::Buy {
+            trade_state_bump: bts_bump,
+            escrow_payment_bump: escrow_bump,
+            buyer_price: bid_price,
+            token_size: 1,
+        }
+        .data(),
+        accounts,
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&buyer.pubkey()),
+        &[buyer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    bid_acc
+}
+
+#[tokio::test]
+async fn best_bid_kept_while_orders_remain_at_its_price() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = Metadata::new();
+    airdrop(&mut context, &test_metadata.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            &mut context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+    let order_book_summary = create_order_book_summary(
+        &mut context,
+        &ahkey,
+        &test_metadata.token,
+        &test_metadata.mint.pubkey(),
+    )
+    .await
+    .unwrap();
+
+    // Two bids at the same price.
+    let first_buyer = Keypair::new();
+    let bid_acc = bid_with_summary(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &first_buyer,
+        ONE_SOL,
+        &order_book_summary,
+    )
+    .await;
+    bid_with_summary(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &Keypair::new(),
+        ONE_SOL,
+        &order_book_summary,
+    )
+    .await;
+
+    let summary = get_order_book_summary(&mut context, &order_book_summary).await;
+    assert_eq!(summary.open_bids, 2);
+    assert_eq!(summary.best_bid, Some(ONE_SOL));
+    assert_eq!(summary.best_bid_count, 2);
+
+    // Canceling one of them leaves the other at the best bid.
+    let mut accounts = mpl_auction_house::accounts::Cancel {
+        auction_house: ahkey,
+        wallet: first_buyer.pubkey(),
+        token_account: bid_acc.token_account,
+        authority: ah.authority,
+        trade_state: bid_acc.buyer_trade_state,
+        token_program: spl_token::id(),
+        token_mint: test_metadata.mint.pubkey(),
+        auction_house_fee_account: ah.auction_house_fee_account,
+    }
+    .to_account_metas(None);
+    accounts.push(AccountMeta::new(order_book_summary, false));
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::Cancel {
+            buyer_price: ONE_SOL,
+            token_size: 1,
+        }
+        .data(),
+        accounts,
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&first_buyer.pubkey()),
+        &[&first_buyer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let summary = get_order_book_summary(&mut context, &order_book_summary).await;
+    assert_eq!(summary.open_bids, 1);
+    assert_eq!(summary.best_bid, Some(ONE_SOL));
+    assert_eq!(summary.best_bid_count, 1);
+}
@@ -0,0 +1,140 @@
+#![cfg(feature = "test-bpf")]
+pub mod common;
+pub mod utils;
+
+use common::*;
+use mpl_auction_house::constants::{PAUSE_DEPOSIT, PAUSE_SELL};
+use utils::setup_functions::*;
+
+#[tokio::test]
+async fn paused_operations_fail_while_withdraw_succeeds() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = Metadata::new();
+    airdrop(&mut context, &test_metadata.token.pubkey(), 10_000_000_000)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            &mut context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+
+    let sale_price = 1_000_000_000;
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), 10_000_000_000)
+        .await
+        .unwrap();
+    let (_, deposit_tx) = deposit(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &buyer,
+        sale_price,
+    );
+    context
+        .banks_client
+        .process_transaction(deposit_tx)
+        .await
+        .unwrap();
+
+    update_auction_house(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some(PAUSE_SELL | PAUSE_DEPOSIT),
+        None,
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+
+    let (_, sell_tx) = sell(&mut context, &ahkey, &ah, &test_metadata, sale_price, 1);
+    let error = context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, OPERATION_PAUSED);
+
+    let (_, deposit_tx) = deposit(&mut context, &ahkey, &ah, &test_metadata, &buyer, 1);
+    let error = context
+        .banks_client
+        .process_transaction(deposit_tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, OPERATION_PAUSED);
+
+    // Users can always take their funds out of escrow.
+    let (_, withdraw_tx) = withdraw(
+        &mut context,
+        &buyer,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        sale_price,
+        sale_price,
+    );
+    context
+        .banks_client
+        .process_transaction(withdraw_tx)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn unknown_paused_operations_fail() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+
+    let error = update_auction_house(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some(1 << 7),
+        None,
+        None,
+        None,
+    )
+    .await
+    .unwrap_err();
+    assert_error!(error, INVALID_PAUSED_OPERATIONS);
+}
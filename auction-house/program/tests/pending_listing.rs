@@ -0,0 +1,134 @@
+#![cfg(feature = "test-bpf")]
+pub mod common;
+pub mod utils;
+
+use common::*;
+use mpl_auction_house::{pda::find_program_as_signer_address, PendingListing};
+use solana_program::{program_option::COption, program_pack::Pack};
+use spl_token::state::Account;
+use utils::setup_functions::*;
+
+#[tokio::test]
+async fn finalize_listing_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = Metadata::new();
+    airdrop(&mut context, &test_metadata.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            &mut context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+
+    let (initiate_acc, initiate_tx) =
+        initiate_listing(&mut context, &ahkey, &test_metadata, ONE_SOL, 1);
+    context
+        .banks_client
+        .process_transaction(initiate_tx)
+        .await
+        .unwrap();
+
+    let pending_listing_account = context
+        .banks_client
+        .get_account(initiate_acc.pending_listing)
+        .await
+        .expect("Error Getting Pending Listing")
+        .expect("Pending Listing Empty");
+    let pending_listing =
+        PendingListing::try_deserialize(&mut pending_listing_account.data.as_ref()).unwrap();
+    assert_eq!(pending_listing.auction_house, ahkey);
+    assert_eq!(pending_listing.wallet, test_metadata.token.pubkey());
+    assert_eq!(pending_listing.buyer_price, ONE_SOL);
+    assert_eq!(pending_listing.token_size, 1);
+
+    let (finalize_acc, finalize_tx) =
+        finalize_listing(&mut context, &ahkey, &ah, &test_metadata, ONE_SOL, 1);
+    context
+        .banks_client
+        .process_transaction(finalize_tx)
+        .await
+        .unwrap();
+
+    let seller_trade_state = context
+        .banks_client
+        .get_account(finalize_acc.seller_trade_state)
+        .await
+        .expect("Error Getting Trade State")
+        .expect("Trade State Empty");
+    assert_eq!(seller_trade_state.data.len(), 1);
+
+    let pending_listing_account = context
+        .banks_client
+        .get_account(finalize_acc.pending_listing)
+        .await
+        .expect("Error Getting Pending Listing");
+    assert!(pending_listing_account.is_none());
+
+    let token_account = Account::unpack_from_slice(
+        context
+            .banks_client
+            .get_account(finalize_acc.token_account)
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+            .as_slice(),
+    )
+    .unwrap();
+    let (pas, _) = find_program_as_signer_address();
+    assert_eq!(token_account.delegate, COption::Some(pas));
+    assert_eq!(token_account.delegated_amount, 1);
+}
+
+#[tokio::test]
+async fn finalize_listing_with_different_price_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = Metadata::new();
+    airdrop(&mut context, &test_metadata.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            &mut context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+
+    let (_, initiate_tx) = initiate_listing(&mut context, &ahkey, &test_metadata, ONE_SOL, 1);
+    context
+        .banks_client
+        .process_transaction(initiate_tx)
+        .await
+        .unwrap();
+
+    let (_, finalize_tx) =
+        finalize_listing(&mut context, &ahkey, &ah, &test_metadata, ONE_SOL * 2, 1);
+    let error = context
+        .banks_client
+        .process_transaction(finalize_tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, INVALID_SEEDS);
+}
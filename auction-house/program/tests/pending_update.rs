@@ -0,0 +1,121 @@
+#![cfg(feature = "test-bpf")]
+pub mod common;
+pub mod utils;
+
+use common::*;
+use mpl_auction_house::{constants::PENDING_UPDATE_DELAY_SLOTS, pda::find_pending_update_address};
+use solana_sdk::sysvar::clock::Clock;
+use utils::setup_functions::*;
+
+#[tokio::test]
+async fn apply_pending_update_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let new_authority = Keypair::new();
+
+    queue_authority_update(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &new_authority.pubkey(),
+    )
+    .await
+    .unwrap();
+
+    // The authority is unchanged until the timelock elapsed.
+    let ah_account = context
+        .banks_client
+        .get_account(ahkey)
+        .await
+        .unwrap()
+        .unwrap();
+    let ah_data = AuctionHouse::try_deserialize(&mut ah_account.data.as_ref()).unwrap();
+    assert_eq!(ah_data.authority, authority.pubkey());
+
+    // Anyone can apply the update once the timelock elapsed.
+    let cranker = Keypair::new();
+    airdrop(&mut context, &cranker.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+    let result = apply_pending_update(&mut context, &ahkey, &authority.pubkey(), &cranker)
+        .await
+        .unwrap_err();
+    assert_error!(result, PENDING_UPDATE_NOT_EXECUTABLE);
+
+    let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    context
+        .warp_to_slot(clock.slot + PENDING_UPDATE_DELAY_SLOTS + 1)
+        .unwrap();
+    apply_pending_update(&mut context, &ahkey, &authority.pubkey(), &cranker)
+        .await
+        .unwrap();
+
+    let ah_account = context
+        .banks_client
+        .get_account(ahkey)
+        .await
+        .unwrap()
+        .unwrap();
+    let ah_data = AuctionHouse::try_deserialize(&mut ah_account.data.as_ref()).unwrap();
+    assert_eq!(ah_data.authority, new_authority.pubkey());
+    assert!(context
+        .banks_client
+        .get_account(find_pending_update_address(&ahkey).0)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn cancel_pending_update_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let new_authority = Keypair::new();
+
+    queue_authority_update(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &new_authority.pubkey(),
+    )
+    .await
+    .unwrap();
+
+    // Only one change can be queued at a time.
+    context.warp_to_slot(100).unwrap();
+    let result = queue_authority_update(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &Keypair::new().pubkey(),
+    )
+    .await
+    .unwrap_err();
+    assert_error!(result, PENDING_UPDATE_ALREADY_QUEUED);
+
+    cancel_pending_update(&mut context, &ahkey, &authority, &authority.pubkey())
+        .await
+        .unwrap();
+    assert!(context
+        .banks_client
+        .get_account(find_pending_update_address(&ahkey).0)
+        .await
+        .unwrap()
+        .is_none());
+
+    let ah_account = context
+        .banks_client
+        .get_account(ahkey)
+        .await
+        .unwrap()
+        .unwrap();
+    let ah_data = AuctionHouse::try_deserialize(&mut ah_account.data.as_ref()).unwrap();
+    assert_eq!(ah_data.authority, authority.pubkey());
+}
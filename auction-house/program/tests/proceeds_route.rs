@@ -0,0 +1,246 @@
+#![cfg(feature = "test-bpf")]
+pub mod common;
+pub mod utils;
+
+use common::*;
+use mpl_auction_house::pda::{
+    find_escrow_payment_address, find_proceeds_route_address, find_program_as_signer_address,
+    find_royalty_mandate_address, find_trade_state_address,
+};
+use mpl_token_metadata::state::Creator;
+use solana_program::{instruction::AccountMeta, system_program, sysvar};
+use utils::setup_functions::*;
+
+async fn setup_routed_sale(
+    context: &mut ProgramTestContext,
+    creator: &Keypair,
+    destination: &Pubkey,
+) -> (Vec<AccountMeta>, Vec<u8>, Keypair, AuctionHouse, Pubkey) {
+    let (ah, ahkey, authority) = existing_auction_house_test_context(context).await.unwrap();
+    let test_metadata = Metadata::new();
+    airdrop(context, &test_metadata.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            Some(vec![Creator {
+                address: creator.pubkey(),
+                verified: false,
+                share: 100,
+            }]),
+            500,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+    let ((sell_acc, _), sell_tx) = sell(context, &ahkey, &ah, &test_metadata, 100_000_000, 1);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+    set_proceeds_route(
+        context,
+        &test_metadata.token,
+        &sell_acc.seller_trade_state,
+        destination,
+    )
+    .await
+    .unwrap();
+    let buyer = Keypair::new();
+    airdrop(context, &buyer.pubkey(), TEN_SOL).await.unwrap();
+    let ((bid_acc, _), buy_tx) = buy(
+        context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &test_metadata.token.pubkey(),
+        &buyer,
+        100_000_000,
+        1,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+    airdrop(context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+
+    let accounts = mpl_auction_house::accounts::ExecuteSale {
+        buyer: buyer.pubkey(),
+        seller: test_metadata.token.pubkey(),
+        auction_house: ahkey,
+        metadata: test_metadata.pubkey,
+        token_account: sell_acc.token_account,
+        authority: ah.authority,
+        seller_trade_state: sell_acc.seller_trade_state,
+        buyer_trade_state: bid_acc.buyer_trade_state,
+        token_program: spl_token::id(),
+        free_trade_state: sell_acc.free_seller_trade_state,
+        seller_payment_receipt_account: *destination,
+        buyer_receipt_token_account: get_associated_token_address(
+            &buyer.pubkey(),
+            &test_metadata.mint.pubkey(),
+        ),
+        escrow_payment_account: bid_acc.escrow_payment_account,
+        token_mint: test_metadata.mint.pubkey(),
+        auction_house_fee_account: ah.auction_house_fee_account,
+        auction_house_treasury: ah.auction_house_treasury,
+        treasury_mint: ah.treasury_mint,
+        program_as_signer: sell_acc.program_as_signer,
+        system_program: system_program::id(),
+        ata_program: spl_associated_token_account::id(),
+        rent: sysvar::rent::id(),
+    }
+    .to_account_metas(None);
+    let (_, free_sts_bump) = find_trade_state_address(
+        &test_metadata.token.pubkey(),
+        &ahkey,
+        &sell_acc.token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        0,
+        1,
+    );
+    let (_, escrow_bump) = find_escrow_payment_address(&ahkey, &buyer.pubkey());
+    let (_, pas_bump) = find_program_as_signer_address();
+    let data = mpl_auction_house::instruction::ExecuteSale {
+        escrow_payment_bump: escrow_bump,
+        _free_trade_state_bump: free_sts_bump,
+        program_as_signer_bump: pas_bump,
+        token_size: 1,
+        buyer_price: 100_000_000,
+        partial_order_size: None,
+        partial_order_price: None,
+    }
+    .data();
+
+    let proceeds_route =
+        find_proceeds_route_address(&test_metadata.token.pubkey(), &sell_acc.seller_trade_state).0;
+
+    (accounts, data, authority, ah, proceeds_route)
+}
+
+#[tokio::test]
+async fn execute_sale_pays_routed_destination() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let creator = Keypair::new();
+    airdrop(&mut context, &creator.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+    let destination = Keypair::new();
+
+    let (mut accounts, data, authority, ah, proceeds_route) =
+        setup_routed_sale(&mut context, &creator, &destination.pubkey()).await;
+    accounts.push(AccountMeta::new(creator.pubkey(), false));
+    accounts.push(AccountMeta::new_readonly(
+        find_royalty_mandate_address(&creator.pubkey()).0,
+        false,
+    ));
+    accounts.push(AccountMeta::new_readonly(proceeds_route, false));
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data,
+        accounts,
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&authority.pubkey()),
+        &[&authority],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let auction_house_fee = 100_000_000 * ah.seller_fee_basis_points as u64 / 10000;
+    let destination_account = context
+        .banks_client
+        .get_account(destination.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        destination_account.lamports,
+        100_000_000 - 5_000_000 - auction_house_fee
+    );
+}
+
+#[tokio::test]
+async fn execute_sale_wrong_routed_destination_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let creator = Keypair::new();
+    airdrop(&mut context, &creator.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+
+    let (mut accounts, data, authority, _, proceeds_route) =
+        setup_routed_sale(&mut context, &creator, &Keypair::new().pubkey()).await;
+    // Pay the seller directly despite the route.
+    accounts[7].pubkey = accounts[1].pubkey;
+    accounts.push(AccountMeta::new(creator.pubkey(), false));
+    accounts.push(AccountMeta::new_readonly(
+        find_royalty_mandate_address(&creator.pubkey()).0,
+        false,
+    ));
+    accounts.push(AccountMeta::new_readonly(proceeds_route, false));
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data,
+        accounts,
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&authority.pubkey()),
+        &[&authority],
+        context.last_blockhash,
+    );
+    let result = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(result, INVALID_PROCEEDS_ROUTE);
+}
+
+#[tokio::test]
+async fn execute_sale_without_proceeds_route_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let creator = Keypair::new();
+    airdrop(&mut context, &creator.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+
+    let (mut accounts, data, authority, _, _) =
+        setup_routed_sale(&mut context, &creator, &Keypair::new().pubkey()).await;
+    // Leaving the route out would pay the seller directly.
+    accounts[7].pubkey = accounts[1].pubkey;
+    accounts.push(AccountMeta::new(creator.pubkey(), false));
+    accounts.push(AccountMeta::new_readonly(
+        find_royalty_mandate_address(&creator.pubkey()).0,
+        false,
+    ));
+    accounts.push(AccountMeta::new_readonly(Keypair::new().pubkey(), false));
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data,
+        accounts,
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&authority.pubkey()),
+        &[&authority],
+        context.last_blockhash,
+    );
+    let result = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+    assert_error!(result, INVALID_PROCEEDS_ROUTE);
+}
@@ -0,0 +1,262 @@
+#![cfg(feature = "test-bpf")]
+pub mod common;
+pub mod utils;
+
+use common::*;
+use mpl_auction_house::{
+    pda::{
+        find_escrow_payment_address, find_proceeds_route_address, find_program_as_signer_address,
+        find_trade_state_address,
+    },
+    RevenueShare,
+};
+use mpl_testing_utils::solana::{create_mint, create_token_account, mint_tokens};
+use solana_program::{instruction::AccountMeta, keccak, system_program, sysvar};
+use solana_sdk::sysvar::clock::Clock;
+use utils::setup_functions::*;
+
+#[tokio::test]
+async fn revenue_share_accrue_and_claim_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+
+    // Governance token held by a single holder.
+    let governance_mint = Keypair::new();
+    create_mint(&mut context, &governance_mint, &authority.pubkey(), None)
+        .await
+        .unwrap();
+    let holder = Keypair::new();
+    airdrop(&mut context, &holder.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+    let governance_token_account = Keypair::new();
+    create_token_account(
+        &mut context,
+        &governance_token_account,
+        &governance_mint.pubkey(),
+        &holder.pubkey(),
+    )
+    .await
+    .unwrap();
+    mint_tokens(
+        &mut context,
+        &governance_mint.pubkey(),
+        &governance_token_account.pubkey(),
+        100,
+        &authority.pubkey(),
+        Some(&authority),
+    )
+    .await
+    .unwrap();
+
+    let revenue_share = create_revenue_share(
+        &mut context,
+        ahkey,
+        &ah,
+        &authority,
+        governance_mint.pubkey(),
+        5000,
+    )
+    .await
+    .unwrap();
+    let ah_acc = context
+        .banks_client
+        .get_account(ahkey)
+        .await
+        .unwrap()
+        .unwrap();
+    let ah = AuctionHouse::try_deserialize(&mut ah_acc.data.as_ref()).unwrap();
+    assert!(ah.has_revenue_share);
+
+    // Sell and buy so house fees accrue into the revenue share.
+    let test_metadata = Metadata::new();
+    airdrop(&mut context, &test_metadata.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            &mut context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+    let ((sell_acc, _), sell_tx) = sell(&mut context, &ahkey, &ah, &test_metadata, ONE_SOL, 1);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let ((bid_acc, _), buy_tx) = buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &test_metadata.token.pubkey(),
+        &buyer,
+        ONE_SOL,
+        1,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    let mut accounts = mpl_auction_house::accounts::ExecuteSale {
+        buyer: buyer.pubkey(),
+        seller: test_metadata.token.pubkey(),
+        auction_house: ahkey,
+        metadata: test_metadata.pubkey,
+        token_account: sell_acc.token_account,
+        authority: ah.authority,
+        seller_trade_state: sell_acc.seller_trade_state,
+        buyer_trade_state: bid_acc.buyer_trade_state,
+        token_program: spl_token::id(),
+        free_trade_state: sell_acc.free_seller_trade_state,
+        seller_payment_receipt_account: test_metadata.token.pubkey(),
+        buyer_receipt_token_account: get_associated_token_address(
+            &buyer.pubkey(),
+            &test_metadata.mint.pubkey(),
+        ),
+        escrow_payment_account: bid_acc.escrow_payment_account,
+        token_mint: test_metadata.mint.pubkey(),
+        auction_house_fee_account: ah.auction_house_fee_account,
+        auction_house_treasury: ah.auction_house_treasury,
+        treasury_mint: ah.treasury_mint,
+        program_as_signer: sell_acc.program_as_signer,
+        system_program: system_program::id(),
+        ata_program: spl_associated_token_account::id(),
+        rent: sysvar::rent::id(),
+    }
+    .to_account_metas(None);
+    accounts.push(AccountMeta::new_readonly(
+        find_proceeds_route_address(&test_metadata.token.pubkey(), &sell_acc.seller_trade_state).0,
+        false,
+    ));
+    accounts.push(AccountMeta::new(revenue_share, false));
+    let (_, free_sts_bump) = find_trade_state_address(
+        &test_metadata.token.pubkey(),
+        &ahkey,
+        &sell_acc.token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        0,
+        1,
+    );
+    let (_, escrow_bump) = find_escrow_payment_address(&ahkey, &buyer.pubkey());
+    let (_, pas_bump) = find_program_as_signer_address();
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::ExecuteSale {
+            escrow_payment_bump: escrow_bump,
+            _free_trade_state_bump: free_sts_bump,
+            program_as_signer_bump: pas_bump,
+            token_size: 1,
+            buyer_price: ONE_SOL,
+            partial_order_size: None,
+            partial_order_price: None,
+        }
+        .data(),
+        accounts,
+    };
+    airdrop(&mut context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&authority.pubkey()),
+        &[&authority],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    // House fee is 1% of the price, half of it goes to the revenue share.
+    let revenue_share_acc = context
+        .banks_client
+        .get_account(revenue_share)
+        .await
+        .unwrap()
+        .unwrap();
+    let revenue_share_data =
+        RevenueShare::try_deserialize(&mut revenue_share_acc.data.as_ref()).unwrap();
+    let accrued = ONE_SOL / 100 / 2;
+    assert_eq!(revenue_share_data.accrued, accrued);
+
+    // A single holder tree, the root is the leaf itself.
+    let balance: u64 = 100;
+    let leaf = keccak::hashv(&[
+        &0u64.to_le_bytes(),
+        governance_token_account.pubkey().as_ref(),
+        &balance.to_le_bytes(),
+    ])
+    .0;
+    let slot = context
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap()
+        .slot;
+    snapshot_revenue_share(&mut context, ahkey, &authority, 0, leaf, balance, slot)
+        .await
+        .unwrap();
+
+    let holder_before = context
+        .banks_client
+        .get_balance(holder.pubkey())
+        .await
+        .unwrap();
+    let claim_tx = claim_revenue_share(
+        &mut context,
+        ahkey,
+        &ah,
+        &holder,
+        governance_token_account.pubkey(),
+        0,
+        0,
+        balance,
+        vec![],
+    );
+    context
+        .banks_client
+        .process_transaction(claim_tx)
+        .await
+        .unwrap();
+    let holder_after = context
+        .banks_client
+        .get_balance(holder.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(holder_after - holder_before + 5000, accrued);
+
+    // Claiming the same index again is rejected by the bitmap.
+    context.warp_to_slot(slot + 2).unwrap();
+    let claim_tx = claim_revenue_share(
+        &mut context,
+        ahkey,
+        &ah,
+        &holder,
+        governance_token_account.pubkey(),
+        0,
+        0,
+        balance,
+        vec![],
+    );
+    let error = context
+        .banks_client
+        .process_transaction(claim_tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, REVENUE_SHARE_ALREADY_CLAIMED);
+}
@@ -8,6 +8,7 @@ use utils::{helpers::default_scopes, setup_functions::*};
 
 use mpl_auction_house::receipt::ListingReceipt;
 use mpl_testing_utils::{solana::airdrop, utils::Metadata};
+use mpl_token_metadata::{instruction::sign_metadata, state::Creator};
 use solana_sdk::{signer::Signer, sysvar::clock::Clock};
 use std::assert_eq;
 
@@ -77,6 +78,68 @@ async fn sell_success() {
     assert_eq!(listing_receipt.seller, *owner_pubkey);
     assert_eq!(listing_receipt.price, 1);
     assert_eq!(listing_receipt.token_size, 1);
+    assert!(!listing_receipt.sold_by_creator);
+}
+
+#[tokio::test]
+async fn sell_by_verified_creator_flags_receipt() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = Metadata::new();
+    let owner_pubkey = &test_metadata.token.pubkey();
+    airdrop(&mut context, owner_pubkey, TEN_SOL).await.unwrap();
+    test_metadata
+        .create(
+            &mut context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            Some(vec![Creator {
+                address: *owner_pubkey,
+                verified: false,
+                share: 100,
+            }]),
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+    let sign_tx = Transaction::new_signed_with_payer(
+        &[sign_metadata(
+            mpl_token_metadata::id(),
+            test_metadata.pubkey,
+            *owner_pubkey,
+        )],
+        Some(owner_pubkey),
+        &[&test_metadata.token],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(sign_tx)
+        .await
+        .unwrap();
+
+    let ((_, listing_receipt_acc), sell_tx) = sell(&mut context, &ahkey, &ah, &test_metadata, 1, 1);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let listing_receipt_account = context
+        .banks_client
+        .get_account(listing_receipt_acc.receipt)
+        .await
+        .expect("getting listing receipt")
+        .expect("empty listing receipt data");
+    let listing_receipt =
+        ListingReceipt::try_deserialize(&mut listing_receipt_account.data.as_ref()).unwrap();
+
+    assert!(listing_receipt.sold_by_creator);
 }
 
 #[tokio::test]
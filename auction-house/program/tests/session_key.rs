@@ -0,0 +1,419 @@
+#![cfg(feature = "test-bpf")]
+pub mod common;
+pub mod utils;
+
+use common::*;
+use mpl_auction_house::{
+    constants::{SESSION_TRADE_STATE_SIZE, TRADE_STATE_WITH_RENT_PAYER_SIZE},
+    SessionKey,
+};
+use solana_sdk::sysvar::clock::Clock;
+use utils::setup_functions::*;
+
+#[tokio::test]
+async fn session_buy_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    // Payer Wallet
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = Metadata::new();
+
+    airdrop(&mut context, &test_metadata.token.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            &mut context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), ONE_SOL * 10)
+        .await
+        .unwrap();
+    let (_, deposit_tx) = deposit(&mut context, &ahkey, &ah, &test_metadata, &buyer, ONE_SOL);
+    context
+        .banks_client
+        .process_transaction(deposit_tx)
+        .await
+        .unwrap();
+
+    let session_key = Keypair::new();
+    airdrop(&mut context, &session_key.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+    let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    let session_key_account = approve_session_key(
+        &mut context,
+        ahkey,
+        &buyer,
+        session_key.pubkey(),
+        ONE_SOL,
+        clock.unix_timestamp + 3600,
+    )
+    .await
+    .unwrap();
+
+    let (acc, buy_tx) = session_buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &test_metadata.token.pubkey(),
+        &buyer.pubkey(),
+        &session_key,
+        ONE_SOL,
+        1,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    let bts = context
+        .banks_client
+        .get_account(acc.buyer_trade_state)
+        .await
+        .expect("Error Getting Trade State")
+        .expect("Trade State Empty");
+    assert_eq!(bts.data.len(), SESSION_TRADE_STATE_SIZE);
+    assert_eq!(
+        bts.data[TRADE_STATE_WITH_RENT_PAYER_SIZE..],
+        session_key.pubkey().to_bytes()
+    );
+
+    let session_key_acc = context
+        .banks_client
+        .get_account(session_key_account)
+        .await
+        .expect("Error Getting Session Key")
+        .expect("Session Key Empty");
+    let session = SessionKey::try_deserialize(&mut session_key_acc.data.as_ref()).unwrap();
+
+    assert_eq!(session.wallet, buyer.pubkey());
+    assert_eq!(session.session_key, session_key.pubkey());
+    assert_eq!(session.auction_house, ahkey);
+    assert_eq!(session.max_spend, ONE_SOL);
+    assert_eq!(session.spent, ONE_SOL);
+}
+
+#[tokio::test]
+async fn session_buy_over_spend_limit_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    // Payer Wallet
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = Metadata::new();
+
+    airdrop(&mut context, &test_metadata.token.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            &mut context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), ONE_SOL * 10)
+        .await
+        .unwrap();
+    let (_, deposit_tx) = deposit(&mut context, &ahkey, &ah, &test_metadata, &buyer, ONE_SOL);
+    context
+        .banks_client
+        .process_transaction(deposit_tx)
+        .await
+        .unwrap();
+
+    let session_key = Keypair::new();
+    airdrop(&mut context, &session_key.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+    let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    approve_session_key(
+        &mut context,
+        ahkey,
+        &buyer,
+        session_key.pubkey(),
+        ONE_SOL / 2,
+        clock.unix_timestamp + 3600,
+    )
+    .await
+    .unwrap();
+
+    let (_, buy_tx) = session_buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &test_metadata.token.pubkey(),
+        &buyer.pubkey(),
+        &session_key,
+        ONE_SOL,
+        1,
+    );
+    let error = context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap_err();
+
+    assert_error!(error, SESSION_KEY_SPEND_LIMIT_EXCEEDED);
+}
+
+#[tokio::test]
+async fn session_cancel_credits_spend_limit() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = Metadata::new();
+
+    airdrop(&mut context, &test_metadata.token.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            &mut context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), ONE_SOL * 10)
+        .await
+        .unwrap();
+    let (_, deposit_tx) = deposit(&mut context, &ahkey, &ah, &test_metadata, &buyer, ONE_SOL);
+    context
+        .banks_client
+        .process_transaction(deposit_tx)
+        .await
+        .unwrap();
+
+    let session_key = Keypair::new();
+    airdrop(&mut context, &session_key.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+    let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    let session_key_account = approve_session_key(
+        &mut context,
+        ahkey,
+        &buyer,
+        session_key.pubkey(),
+        ONE_SOL,
+        clock.unix_timestamp + 3600,
+    )
+    .await
+    .unwrap();
+
+    let (_, buy_tx) = session_buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &test_metadata.token.pubkey(),
+        &buyer.pubkey(),
+        &session_key,
+        ONE_SOL,
+        1,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    let (cancel_acc, cancel_tx) = session_cancel(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &test_metadata.token.pubkey(),
+        &buyer.pubkey(),
+        &session_key,
+        ONE_SOL,
+        1,
+    );
+    context
+        .banks_client
+        .process_transaction(cancel_tx)
+        .await
+        .unwrap();
+
+    let bts = context
+        .banks_client
+        .get_account(cancel_acc.trade_state)
+        .await
+        .expect("Error Getting Trade State");
+    assert!(bts.is_none());
+
+    let session_key_acc = context
+        .banks_client
+        .get_account(session_key_account)
+        .await
+        .expect("Error Getting Session Key")
+        .expect("Session Key Empty");
+    let session = SessionKey::try_deserialize(&mut session_key_acc.data.as_ref()).unwrap();
+    assert_eq!(session.spent, 0);
+
+    // The credited budget covers bidding the same amount again.
+    context.warp_to_slot(100).unwrap();
+    let (_, buy_tx) = session_buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &test_metadata.token.pubkey(),
+        &buyer.pubkey(),
+        &session_key,
+        ONE_SOL,
+        1,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn session_cancel_of_wallet_bid_keeps_spend_limit() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = Metadata::new();
+
+    airdrop(&mut context, &test_metadata.token.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            &mut context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), ONE_SOL * 10)
+        .await
+        .unwrap();
+    let (_, deposit_tx) = deposit(&mut context, &ahkey, &ah, &test_metadata, &buyer, ONE_SOL);
+    context
+        .banks_client
+        .process_transaction(deposit_tx)
+        .await
+        .unwrap();
+
+    let session_key = Keypair::new();
+    airdrop(&mut context, &session_key.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+    let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    let session_key_account = approve_session_key(
+        &mut context,
+        ahkey,
+        &buyer,
+        session_key.pubkey(),
+        ONE_SOL,
+        clock.unix_timestamp + 3600,
+    )
+    .await
+    .unwrap();
+
+    let (_, buy_tx) = session_buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &test_metadata.token.pubkey(),
+        &buyer.pubkey(),
+        &session_key,
+        ONE_SOL / 2,
+        1,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    // The wallet bids on its own, the session key never spent anything on this bid.
+    let (_, buy_tx) = buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &test_metadata.token.pubkey(),
+        &buyer,
+        ONE_SOL / 4,
+        1,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    let (cancel_acc, cancel_tx) = session_cancel(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &test_metadata.token.pubkey(),
+        &buyer.pubkey(),
+        &session_key,
+        ONE_SOL / 4,
+        1,
+    );
+    context
+        .banks_client
+        .process_transaction(cancel_tx)
+        .await
+        .unwrap();
+
+    let bts = context
+        .banks_client
+        .get_account(cancel_acc.trade_state)
+        .await
+        .expect("Error Getting Trade State");
+    assert!(bts.is_none());
+
+    let session_key_acc = context
+        .banks_client
+        .get_account(session_key_account)
+        .await
+        .expect("Error Getting Session Key")
+        .expect("Session Key Empty");
+    let session = SessionKey::try_deserialize(&mut session_key_acc.data.as_ref()).unwrap();
+    assert_eq!(session.spent, ONE_SOL / 2);
+}
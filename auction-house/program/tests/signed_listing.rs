@@ -0,0 +1,428 @@
+#![cfg(feature = "test-bpf")]
+pub mod common;
+pub mod utils;
+
+use common::*;
+use mpl_auction_house::{
+    constants::TRADE_STATE_WITH_RENT_PAYER_SIZE,
+    pda::{find_program_as_signer_address, find_signed_listing_nonce_address},
+    utils::signed_listing_message,
+};
+use solana_program::{clock::Clock, instruction::AccountMeta};
+use utils::setup_functions::*;
+
+async fn setup_seller(context: &mut ProgramTestContext, approve: bool) -> Metadata {
+    let test_metadata = Metadata::new();
+    airdrop(context, &test_metadata.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+
+    if approve {
+        let token = get_associated_token_address(
+            &test_metadata.token.pubkey(),
+            &test_metadata.mint.pubkey(),
+        );
+        let (pas, _) = find_program_as_signer_address();
+        let approve_tx = Transaction::new_signed_with_payer(
+            &[spl_token::instruction::approve(
+                &spl_token::id(),
+                &token,
+                &pas,
+                &test_metadata.token.pubkey(),
+                &[],
+                1,
+            )
+            .unwrap()],
+            Some(&test_metadata.token.pubkey()),
+            &[&test_metadata.token],
+            context.last_blockhash,
+        );
+        context
+            .banks_client
+            .process_transaction(approve_tx)
+            .await
+            .unwrap();
+    }
+
+    test_metadata
+}
+
+#[tokio::test]
+async fn post_signed_listing_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = setup_seller(&mut context, true).await;
+    let expiry = context
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap()
+        .unix_timestamp
+        + 60;
+
+    let (acc, post_ix) = post_signed_listing(
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &context.payer.pubkey(),
+        ONE_SOL,
+        1,
+        expiry,
+        0,
+    );
+    let message = signed_listing_message(&ahkey, &acc.token_account, ONE_SOL, 1, expiry, 0);
+    let ed25519_ix = signed_listing_ed25519_instruction(&test_metadata.token, &message);
+
+    // Submitted and paid by a relayer, the seller only signed the message.
+    let tx = Transaction::new_signed_with_payer(
+        &[ed25519_ix, post_ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let seller_trade_state = context
+        .banks_client
+        .get_account(acc.seller_trade_state)
+        .await
+        .expect("Error Getting Trade State")
+        .expect("Trade State Empty");
+    assert_eq!(
+        seller_trade_state.data.len(),
+        TRADE_STATE_WITH_RENT_PAYER_SIZE
+    );
+    assert_eq!(
+        seller_trade_state.data[1..TRADE_STATE_WITH_RENT_PAYER_SIZE],
+        context.payer.pubkey().to_bytes()
+    );
+}
+
+#[tokio::test]
+async fn cancel_signed_listing_refunds_relayer() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = setup_seller(&mut context, true).await;
+    let relayer = Keypair::new();
+    airdrop(&mut context, &relayer.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+    let expiry = context
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap()
+        .unix_timestamp
+        + 60;
+
+    let (acc, post_ix) = post_signed_listing(
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &relayer.pubkey(),
+        ONE_SOL,
+        1,
+        expiry,
+        0,
+    );
+    let message = signed_listing_message(&ahkey, &acc.token_account, ONE_SOL, 1, expiry, 0);
+    let ed25519_ix = signed_listing_ed25519_instruction(&test_metadata.token, &message);
+    let tx = Transaction::new_signed_with_payer(
+        &[ed25519_ix, post_ix],
+        Some(&relayer.pubkey()),
+        &[&relayer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+    let relayer_balance = context
+        .banks_client
+        .get_balance(relayer.pubkey())
+        .await
+        .unwrap();
+    let trade_state_rent = context
+        .banks_client
+        .get_balance(acc.seller_trade_state)
+        .await
+        .unwrap();
+
+    // The seller cancels, the relayer gets the trade state rent back.
+    let mut accounts = mpl_auction_house::accounts::Cancel {
+        auction_house: ahkey,
+        wallet: test_metadata.token.pubkey(),
+        token_account: acc.token_account,
+        authority: ah.authority,
+        trade_state: acc.seller_trade_state,
+        token_program: spl_token::id(),
+        token_mint: test_metadata.mint.pubkey(),
+        auction_house_fee_account: ah.auction_house_fee_account,
+    }
+    .to_account_metas(None);
+    accounts.push(AccountMeta::new(relayer.pubkey(), false));
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::Cancel {
+            buyer_price: ONE_SOL,
+            token_size: 1,
+        }
+        .data(),
+        accounts,
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&test_metadata.token.pubkey()),
+        &[&test_metadata.token],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let seller_trade_state = context
+        .banks_client
+        .get_account(acc.seller_trade_state)
+        .await
+        .unwrap();
+    assert!(seller_trade_state.is_none());
+    let relayer_balance_after = context
+        .banks_client
+        .get_balance(relayer.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(relayer_balance_after, relayer_balance + trade_state_rent);
+
+    // Delegating again doesn't let anyone replay the signature.
+    let approve_tx = Transaction::new_signed_with_payer(
+        &[spl_token::instruction::approve(
+            &spl_token::id(),
+            &acc.token_account,
+            &acc.program_as_signer,
+            &test_metadata.token.pubkey(),
+            &[],
+            1,
+        )
+        .unwrap()],
+        Some(&test_metadata.token.pubkey()),
+        &[&test_metadata.token],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(approve_tx)
+        .await
+        .unwrap();
+    let (_, post_ix) = post_signed_listing(
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &relayer.pubkey(),
+        ONE_SOL,
+        1,
+        expiry,
+        0,
+    );
+    let ed25519_ix = signed_listing_ed25519_instruction(&test_metadata.token, &message);
+    let tx = Transaction::new_signed_with_payer(
+        &[ed25519_ix, post_ix],
+        Some(&relayer.pubkey()),
+        &[&relayer],
+        context.last_blockhash,
+    );
+    let error = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+
+    match error {
+        TransportError::TransactionError(TransactionError::InstructionError(
+            1,
+            InstructionError::Custom(0),
+        )) => (),
+        _ => panic!("Unexpected error {:?}", error),
+    }
+}
+
+#[tokio::test]
+async fn post_canceled_signed_listing_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = setup_seller(&mut context, true).await;
+    let expiry = context
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap()
+        .unix_timestamp
+        + 60;
+
+    let cancel_ix = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::CancelSignedListing { nonce: 7 }.data(),
+        accounts: mpl_auction_house::accounts::CancelSignedListing {
+            wallet: test_metadata.token.pubkey(),
+            auction_house: ahkey,
+            signed_listing_nonce: find_signed_listing_nonce_address(
+                &ahkey,
+                &test_metadata.token.pubkey(),
+                7,
+            )
+            .0,
+            system_program: solana_program::system_program::id(),
+        }
+        .to_account_metas(None),
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[cancel_ix],
+        Some(&test_metadata.token.pubkey()),
+        &[&test_metadata.token],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let (acc, post_ix) = post_signed_listing(
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &context.payer.pubkey(),
+        ONE_SOL,
+        1,
+        expiry,
+        7,
+    );
+    let message = signed_listing_message(&ahkey, &acc.token_account, ONE_SOL, 1, expiry, 7);
+    let ed25519_ix = signed_listing_ed25519_instruction(&test_metadata.token, &message);
+    let tx = Transaction::new_signed_with_payer(
+        &[ed25519_ix, post_ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    let error = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+
+    match error {
+        TransportError::TransactionError(TransactionError::InstructionError(
+            1,
+            InstructionError::Custom(0),
+        )) => (),
+        _ => panic!("Unexpected error {:?}", error),
+    }
+}
+
+#[tokio::test]
+async fn post_signed_listing_with_different_price_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = setup_seller(&mut context, true).await;
+    let expiry = context
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap()
+        .unix_timestamp
+        + 60;
+
+    let (acc, post_ix) = post_signed_listing(
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &context.payer.pubkey(),
+        ONE_SOL / 2,
+        1,
+        expiry,
+        0,
+    );
+    let message = signed_listing_message(&ahkey, &acc.token_account, ONE_SOL, 1, expiry, 0);
+    let ed25519_ix = signed_listing_ed25519_instruction(&test_metadata.token, &message);
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ed25519_ix, post_ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    let error = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+
+    match error {
+        TransportError::TransactionError(TransactionError::InstructionError(
+            1,
+            InstructionError::Custom(code),
+        )) => assert_eq!(code, INVALID_LISTING_SIGNATURE),
+        _ => panic!("Unexpected error {:?}", error),
+    }
+}
+
+#[tokio::test]
+async fn post_signed_listing_without_delegate_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = setup_seller(&mut context, false).await;
+    let expiry = context
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap()
+        .unix_timestamp
+        + 60;
+
+    let (acc, post_ix) = post_signed_listing(
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &context.payer.pubkey(),
+        ONE_SOL,
+        1,
+        expiry,
+        0,
+    );
+    let message = signed_listing_message(&ahkey, &acc.token_account, ONE_SOL, 1, expiry, 0);
+    let ed25519_ix = signed_listing_ed25519_instruction(&test_metadata.token, &message);
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ed25519_ix, post_ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    let error = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+
+    match error {
+        TransportError::TransactionError(TransactionError::InstructionError(
+            1,
+            InstructionError::Custom(code),
+        )) => assert_eq!(code, SIGNED_LISTING_NOT_DELEGATED),
+        _ => panic!("Unexpected error {:?}", error),
+    }
+}
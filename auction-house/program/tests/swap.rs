@@ -0,0 +1,299 @@
+#![cfg(feature = "test-bpf")]
+pub mod common;
+pub mod utils;
+
+use common::*;
+use mpl_token_metadata::state::Creator;
+use solana_program::program_pack::Pack;
+use spl_token::state::Account;
+use utils::setup_functions::*;
+
+const VALUATION: u64 = 1_000_000_000;
+const ROYALTIES: u64 = 50_000_000;
+
+/// Mint an NFT paying 5% royalties to `creator` to a fresh wallet.
+async fn create_nft(context: &mut ProgramTestContext, creator: &Pubkey) -> Metadata {
+    let test_metadata = Metadata::new();
+    airdrop(context, &test_metadata.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            Some(vec![Creator {
+                address: *creator,
+                verified: false,
+                share: 100,
+            }]),
+            500,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+
+    test_metadata
+}
+
+async fn token_amount(context: &mut ProgramTestContext, owner: &Pubkey, mint: &Pubkey) -> u64 {
+    let account = context
+        .banks_client
+        .get_account(get_associated_token_address(owner, mint))
+        .await
+        .unwrap()
+        .unwrap();
+    Account::unpack(&account.data).unwrap().amount
+}
+
+#[tokio::test]
+async fn accept_swap_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let offered_creator = Keypair::new();
+    let target_creator = Keypair::new();
+    airdrop(&mut context, &offered_creator.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+    airdrop(&mut context, &target_creator.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+    let offered = create_nft(&mut context, &offered_creator.pubkey()).await;
+    let target = create_nft(&mut context, &target_creator.pubkey()).await;
+
+    // Both sides fund their escrow with the royalties of the NFT they give away.
+    let (_, deposit_tx) = deposit(
+        &mut context,
+        &ahkey,
+        &ah,
+        &offered,
+        &offered.token,
+        ROYALTIES,
+    );
+    context
+        .banks_client
+        .process_transaction(deposit_tx)
+        .await
+        .unwrap();
+    let (_, deposit_tx) = deposit(&mut context, &ahkey, &ah, &target, &target.token, ROYALTIES);
+    context
+        .banks_client
+        .process_transaction(deposit_tx)
+        .await
+        .unwrap();
+
+    let (propose_acc, propose_tx) = propose_swap(
+        &mut context,
+        &ahkey,
+        &offered.token,
+        &offered.mint.pubkey(),
+        &target.mint.pubkey(),
+        1,
+        1,
+        VALUATION,
+    );
+    context
+        .banks_client
+        .process_transaction(propose_tx)
+        .await
+        .unwrap();
+    let proposal_account = context
+        .banks_client
+        .get_account(propose_acc.swap_proposal)
+        .await
+        .unwrap();
+    assert!(proposal_account.is_some());
+
+    airdrop(&mut context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+    let (_, accept_tx) = accept_swap(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &target.token,
+        &propose_acc,
+        VALUATION,
+        VALUATION,
+        &[offered_creator.pubkey(), target_creator.pubkey()],
+    );
+    context
+        .banks_client
+        .process_transaction(accept_tx)
+        .await
+        .unwrap();
+
+    let proposer = offered.token.pubkey();
+    let acceptor = target.token.pubkey();
+    assert_eq!(
+        token_amount(&mut context, &proposer, &offered.mint.pubkey()).await,
+        0
+    );
+    assert_eq!(
+        token_amount(&mut context, &proposer, &target.mint.pubkey()).await,
+        1
+    );
+    assert_eq!(
+        token_amount(&mut context, &acceptor, &target.mint.pubkey()).await,
+        0
+    );
+    assert_eq!(
+        token_amount(&mut context, &acceptor, &offered.mint.pubkey()).await,
+        1
+    );
+
+    let offered_creator_balance = context
+        .banks_client
+        .get_balance(offered_creator.pubkey())
+        .await
+        .unwrap();
+    let target_creator_balance = context
+        .banks_client
+        .get_balance(target_creator.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(offered_creator_balance, ONE_SOL + ROYALTIES);
+    assert_eq!(target_creator_balance, ONE_SOL + ROYALTIES);
+
+    let proposal_account = context
+        .banks_client
+        .get_account(propose_acc.swap_proposal)
+        .await
+        .unwrap();
+    assert!(proposal_account.is_none());
+}
+
+#[tokio::test]
+async fn propose_swap_same_mint_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (_, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let creator = Keypair::new();
+    let offered = create_nft(&mut context, &creator.pubkey()).await;
+
+    let (_, propose_tx) = propose_swap(
+        &mut context,
+        &ahkey,
+        &offered.token,
+        &offered.mint.pubkey(),
+        &offered.mint.pubkey(),
+        1,
+        1,
+        VALUATION,
+    );
+    let error = context
+        .banks_client
+        .process_transaction(propose_tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, INVALID_SWAP);
+}
+
+#[tokio::test]
+async fn cancel_swap_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (_, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let creator = Keypair::new();
+    let offered = create_nft(&mut context, &creator.pubkey()).await;
+    let target = create_nft(&mut context, &creator.pubkey()).await;
+
+    let (propose_acc, propose_tx) = propose_swap(
+        &mut context,
+        &ahkey,
+        &offered.token,
+        &offered.mint.pubkey(),
+        &target.mint.pubkey(),
+        1,
+        1,
+        VALUATION,
+    );
+    context
+        .banks_client
+        .process_transaction(propose_tx)
+        .await
+        .unwrap();
+
+    let (_, cancel_tx) = cancel_swap(&mut context, &offered.token, &propose_acc);
+    context
+        .banks_client
+        .process_transaction(cancel_tx)
+        .await
+        .unwrap();
+
+    let proposal_account = context
+        .banks_client
+        .get_account(propose_acc.swap_proposal)
+        .await
+        .unwrap();
+    assert!(proposal_account.is_none());
+    let offered_token_account = context
+        .banks_client
+        .get_account(propose_acc.offered_token_account)
+        .await
+        .unwrap()
+        .unwrap();
+    let offered_token_account = Account::unpack(&offered_token_account.data).unwrap();
+    assert!(offered_token_account.delegate.is_none());
+}
+
+#[tokio::test]
+async fn accept_swap_valuation_too_high_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let offered_creator = Keypair::new();
+    let target_creator = Keypair::new();
+    let offered = create_nft(&mut context, &offered_creator.pubkey()).await;
+    let target = create_nft(&mut context, &target_creator.pubkey()).await;
+
+    let (_, deposit_tx) = deposit(&mut context, &ahkey, &ah, &offered, &offered.token, ONE_SOL);
+    context
+        .banks_client
+        .process_transaction(deposit_tx)
+        .await
+        .unwrap();
+
+    let (propose_acc, propose_tx) = propose_swap(
+        &mut context,
+        &ahkey,
+        &offered.token,
+        &offered.mint.pubkey(),
+        &target.mint.pubkey(),
+        1,
+        1,
+        VALUATION,
+    );
+    context
+        .banks_client
+        .process_transaction(propose_tx)
+        .await
+        .unwrap();
+
+    // The authority values the offered NFT above what the proposer agreed to, draining its escrow into royalties.
+    let (_, accept_tx) = accept_swap(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &target.token,
+        &propose_acc,
+        VALUATION * 2,
+        VALUATION,
+        &[offered_creator.pubkey(), target_creator.pubkey()],
+    );
+    let error = context
+        .banks_client
+        .process_transaction(accept_tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, SWAP_VALUATION_TOO_HIGH);
+}
@@ -0,0 +1,83 @@
+#![cfg(feature = "test-bpf")]
+
+use mpl_auction_house::test_utils::*;
+use mpl_token_metadata::state::Creator;
+use solana_program::program_pack::Pack;
+use solana_program_test::tokio;
+use solana_sdk::signature::{Keypair, Signer};
+use spl_associated_token_account::get_associated_token_address;
+
+#[tokio::test]
+async fn list_bid_and_execute_sale_pays_every_creator() {
+    let mut ah = AuctionHouseTest::new()
+        .seller_fee_basis_points(200)
+        .start()
+        .await
+        .unwrap();
+    let seller = ah.funded_wallet(ONE_SOL).await.unwrap();
+    let creators = [Keypair::new(), Keypair::new()];
+    for creator in creators.iter() {
+        ah.airdrop(&creator.pubkey(), ONE_SOL).await.unwrap();
+    }
+    let nft = ah
+        .mint_nft(
+            &seller,
+            vec![
+                Creator {
+                    address: creators[0].pubkey(),
+                    verified: false,
+                    share: 70,
+                },
+                Creator {
+                    address: creators[1].pubkey(),
+                    verified: false,
+                    share: 30,
+                },
+            ],
+            1000,
+        )
+        .await
+        .unwrap();
+
+    let listing = ah.sell(&nft, ONE_SOL, 1).await.unwrap();
+    let buyer = ah.funded_wallet(2 * ONE_SOL).await.unwrap();
+    let bid = ah.bid(&nft, &buyer, ONE_SOL, 1).await.unwrap();
+    let seller_before = ah
+        .context
+        .banks_client
+        .get_balance(seller.pubkey())
+        .await
+        .unwrap();
+    ah.execute_sale(&nft, &listing, &bid).await.unwrap();
+
+    let buyer_token_account = ah
+        .context
+        .banks_client
+        .get_account(get_associated_token_address(
+            &buyer.pubkey(),
+            &nft.mint.pubkey(),
+        ))
+        .await
+        .unwrap()
+        .unwrap();
+    let buyer_token_account = spl_token::state::Account::unpack(&buyer_token_account.data).unwrap();
+    assert_eq!(buyer_token_account.amount, 1);
+
+    // Royalties of 10% are split 70/30, the house keeps 2%.
+    for (creator, royalty) in creators.iter().zip([70_000_000, 30_000_000]) {
+        let balance = ah
+            .context
+            .banks_client
+            .get_balance(creator.pubkey())
+            .await
+            .unwrap();
+        assert_eq!(balance, ONE_SOL + royalty);
+    }
+    let seller_after = ah
+        .context
+        .banks_client
+        .get_balance(seller.pubkey())
+        .await
+        .unwrap();
+    assert!(seller_after >= seller_before + 880_000_000);
+}
@@ -0,0 +1,275 @@
+#![cfg(feature = "test-bpf")]
+pub mod common;
+pub mod utils;
+
+use common::*;
+use mpl_auction_house::{pda::find_program_as_signer_address, utils::signed_listing_message};
+use solana_program::clock::Clock;
+use utils::setup_functions::*;
+
+const TICK_SIZE: u64 = 1_000_000;
+
+/// Auction House with a tick size and a freshly minted NFT.
+async fn tick_size_auction_house(
+    context: &mut ProgramTestContext,
+) -> (AuctionHouse, Pubkey, Metadata) {
+    let (ah, ahkey, authority) = existing_auction_house_test_context(context).await.unwrap();
+    update_auction_house(
+        context,
+        &ahkey,
+        &ah,
+        &authority,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some(TICK_SIZE),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+
+    let test_metadata = Metadata::new();
+    airdrop(context, &test_metadata.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+
+    (ah, ahkey, test_metadata)
+}
+
+#[tokio::test]
+async fn sell_and_buy_off_tick_fail() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    update_auction_house(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some(1_000_000),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    let test_metadata = Metadata::new();
+    airdrop(&mut context, &test_metadata.token.pubkey(), 10_000_000_000)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            &mut context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+
+    let (_, sell_tx) = sell(&mut context, &ahkey, &ah, &test_metadata, 100_000_001, 1);
+    let error = context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, PRICE_NOT_ON_TICK);
+
+    let (_, sell_tx) = sell(&mut context, &ahkey, &ah, &test_metadata, 100_000_000, 1);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), 10_000_000_000)
+        .await
+        .unwrap();
+    let (_, buy_tx) = buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &test_metadata.token.pubkey(),
+        &buyer,
+        99_999_999,
+        1,
+    );
+    let error = context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, PRICE_NOT_ON_TICK);
+
+    let (_, buy_tx) = buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &test_metadata.token.pubkey(),
+        &buyer,
+        100_000_000,
+        1,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn post_signed_listing_off_tick_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, test_metadata) = tick_size_auction_house(&mut context).await;
+
+    let seller = test_metadata.token.pubkey();
+    let token_account = get_associated_token_address(&seller, &test_metadata.mint.pubkey());
+    let (program_as_signer, _) = find_program_as_signer_address();
+    let approve_tx = Transaction::new_signed_with_payer(
+        &[spl_token::instruction::approve(
+            &spl_token::id(),
+            &token_account,
+            &program_as_signer,
+            &seller,
+            &[],
+            1,
+        )
+        .unwrap()],
+        Some(&seller),
+        &[&test_metadata.token],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(approve_tx)
+        .await
+        .unwrap();
+
+    let expiry = context
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap()
+        .unix_timestamp
+        + 60;
+    for (price, nonce) in [(100 * TICK_SIZE + 1, 0), (100 * TICK_SIZE, 1)] {
+        let message = signed_listing_message(&ahkey, &token_account, price, 1, expiry, nonce);
+        let (_, post_ix) = post_signed_listing(
+            &ahkey,
+            &ah,
+            &test_metadata,
+            &context.payer.pubkey(),
+            price,
+            1,
+            expiry,
+            nonce,
+        );
+        let tx = Transaction::new_signed_with_payer(
+            &[
+                signed_listing_ed25519_instruction(&test_metadata.token, &message),
+                post_ix,
+            ],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        let result = context.banks_client.process_transaction(tx).await;
+
+        if price % TICK_SIZE == 0 {
+            result.unwrap();
+            continue;
+        }
+        match result.unwrap_err() {
+            TransportError::TransactionError(TransactionError::InstructionError(
+                1,
+                InstructionError::Custom(code),
+            )) => assert_eq!(code, PRICE_NOT_ON_TICK),
+            error => panic!("Unexpected error {:?}", error),
+        }
+    }
+}
+
+#[tokio::test]
+async fn migrate_listing_off_tick_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (source_ah, source_ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let (ah, ahkey, test_metadata) = tick_size_auction_house(&mut context).await;
+
+    let price = 100 * TICK_SIZE + 1;
+    let (_, sell_tx) = sell(
+        &mut context,
+        &source_ahkey,
+        &source_ah,
+        &test_metadata,
+        price,
+        1,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let (_, migrate_tx) = migrate_listing(
+        &mut context,
+        &source_ahkey,
+        &source_ah,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        price,
+        1,
+        None,
+        &[],
+    );
+    let error = context
+        .banks_client
+        .process_transaction(migrate_tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, PRICE_NOT_ON_TICK);
+}
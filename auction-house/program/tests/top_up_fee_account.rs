@@ -0,0 +1,81 @@
+#![cfg(feature = "test-bpf")]
+pub mod common;
+pub mod utils;
+
+use common::*;
+use utils::setup_functions::*;
+
+#[tokio::test]
+async fn top_up_fee_account_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let target_balance = ONE_SOL * 2;
+    update_auction_house(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        None,
+        None,
+        None,
+        None,
+        Some(target_balance),
+        Some(ONE_SOL),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    airdrop(&mut context, &ah.auction_house_treasury, ONE_SOL * 5)
+        .await
+        .unwrap();
+
+    // Anyone can crank the top up.
+    let cranker = Keypair::new();
+    airdrop(&mut context, &cranker.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+    let treasury_before = context
+        .banks_client
+        .get_account(ah.auction_house_treasury)
+        .await
+        .unwrap()
+        .unwrap()
+        .lamports;
+    let fee_before = context
+        .banks_client
+        .get_balance(ah.auction_house_fee_account)
+        .await
+        .unwrap();
+
+    top_up_fee_account(&mut context, &ahkey, &ah, &cranker)
+        .await
+        .unwrap();
+
+    let treasury_after = context
+        .banks_client
+        .get_account(ah.auction_house_treasury)
+        .await
+        .unwrap()
+        .unwrap()
+        .lamports;
+    let fee_after = context
+        .banks_client
+        .get_balance(ah.auction_house_fee_account)
+        .await
+        .unwrap();
+    assert_eq!(fee_after, target_balance);
+    assert_eq!(
+        treasury_before - treasury_after,
+        target_balance - fee_before
+    );
+}
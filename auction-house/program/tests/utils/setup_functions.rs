@@ -10,12 +10,19 @@ use mpl_auction_house::{
     pda::{
         find_auction_house_address, find_auction_house_fee_account_address,
         find_auction_house_treasury_address, find_auctioneer_pda,
-        find_auctioneer_trade_state_address, find_bid_receipt_address, find_escrow_payment_address,
-        find_listing_receipt_address, find_program_as_signer_address,
-        find_public_bid_trade_state_address, find_purchase_receipt_address,
+        find_auctioneer_trade_state_address, find_bid_receipt_address, find_bundle_listing_address,
+        find_compliance_config_address, find_deferred_settlement_address,
+        find_escrow_payment_address, find_layaway_address, find_layaway_escrow_address,
+        find_layaway_token_account_address, find_linked_wallet_address,
+        find_listing_receipt_address, find_order_book_summary_address,
+        find_pending_listing_address, find_pending_update_address, find_proceeds_route_address,
+        find_program_as_signer_address, find_public_bid_trade_state_address,
+        find_purchase_receipt_address, find_revenue_share_address,
+        find_revenue_share_snapshot_address, find_royalty_mandate_address,
+        find_session_key_address, find_signed_listing_nonce_address, find_swap_proposal_address,
         find_trade_state_address,
     },
-    AuctionHouse, AuthorityScope,
+    AuctionHouse, AuthorityScope, BundleItem,
 };
 
 use mpl_testing_utils::{solana::airdrop, utils::Metadata};
@@ -23,7 +30,11 @@ use std::result::Result as StdResult;
 
 use mpl_token_metadata::pda::find_metadata_account;
 use solana_program_test::*;
-use solana_sdk::{instruction::Instruction, transaction::Transaction, transport::TransportError};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    transaction::Transaction,
+    transport::TransportError,
+};
 use spl_associated_token_account::get_associated_token_address;
 
 pub fn auction_house_program_test() -> ProgramTest {
@@ -96,274 +107,1799 @@ pub async fn create_auction_house(
         .map(|_| *auction_house_key)
 }
 
-pub fn deposit(
+pub async fn update_auction_house(
     context: &mut ProgramTestContext,
     ahkey: &Pubkey,
     ah: &AuctionHouse,
-    test_metadata: &Metadata,
-    buyer: &Keypair,
-    sale_price: u64,
-) -> (mpl_auction_house::accounts::Deposit, Transaction) {
-    let seller_token_account =
-        get_associated_token_address(&test_metadata.token.pubkey(), &test_metadata.mint.pubkey());
-    let (_buyer_trade_state, _sts_bump) = find_trade_state_address(
-        &buyer.pubkey(),
-        ahkey,
-        &seller_token_account,
-        &ah.treasury_mint,
-        &test_metadata.mint.pubkey(),
-        sale_price,
-        1,
-    );
-    let (escrow, escrow_bump) = find_escrow_payment_address(ahkey, &buyer.pubkey());
-    let accounts = mpl_auction_house::accounts::Deposit {
-        wallet: buyer.pubkey(),
-        authority: ah.authority,
+    authority: &Keypair,
+    seller_fee_basis_points: Option<u16>,
+    requires_sign_off: Option<bool>,
+    can_change_sale_price: Option<bool>,
+    auto_receipts: Option<bool>,
+    fee_account_target_balance: Option<u64>,
+    fee_account_low_balance_threshold: Option<u64>,
+    wash_trading_guard: Option<bool>,
+    enforce_royalties: Option<bool>,
+    tick_size: Option<u64>,
+    free_sales_require_sign_off: Option<bool>,
+    max_free_sales_per_epoch: Option<u16>,
+    paused_operations: Option<u8>,
+    epoch_report_retention: Option<u16>,
+    dispute_window: Option<i64>,
+    listing_cooldown_slots: Option<u64>,
+) -> StdResult<(), TransportError> {
+    let accounts = mpl_auction_house::accounts::UpdateAuctionHouse {
+        treasury_mint: ah.treasury_mint,
+        payer: authority.pubkey(),
+        authority: authority.pubkey(),
+        new_authority: authority.pubkey(),
+        fee_withdrawal_destination: ah.fee_withdrawal_destination,
+        treasury_withdrawal_destination: ah.treasury_withdrawal_destination,
+        treasury_withdrawal_destination_owner: ah.treasury_withdrawal_destination,
         auction_house: *ahkey,
-        auction_house_fee_account: ah.auction_house_fee_account,
+        pending_update: find_pending_update_address(ahkey).0,
         token_program: spl_token::id(),
-        treasury_mint: ah.treasury_mint,
-        payment_account: buyer.pubkey(),
-        transfer_authority: buyer.pubkey(),
-        system_program: solana_program::system_program::id(),
+        system_program: system_program::id(),
+        ata_program: spl_associated_token_account::id(),
         rent: sysvar::rent::id(),
-        escrow_payment_account: escrow,
-    };
-    let account_metas = accounts.to_account_metas(None);
+    }
+    .to_account_metas(None);
 
-    let data = mpl_auction_house::instruction::Deposit {
-        amount: sale_price,
-        escrow_payment_bump: escrow_bump,
+    let data = mpl_auction_house::instruction::UpdateAuctionHouse {
+        seller_fee_basis_points,
+        requires_sign_off,
+        can_change_sale_price,
+        auto_receipts,
+        fee_account_target_balance,
+        fee_account_low_balance_threshold,
+        wash_trading_guard,
+        enforce_royalties,
+        tick_size,
+        free_sales_require_sign_off,
+        max_free_sales_per_epoch,
+        paused_operations,
+        epoch_report_retention,
+        dispute_window,
+        listing_cooldown_slots,
     }
     .data();
 
     let instruction = Instruction {
         program_id: mpl_auction_house::id(),
         data,
-        accounts: account_metas,
+        accounts,
     };
 
-    (
-        accounts,
-        Transaction::new_signed_with_payer(
-            &[instruction],
-            Some(&buyer.pubkey()),
-            &[buyer],
-            context.last_blockhash,
-        ),
-    )
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&authority.pubkey()),
+        &[authority],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await
 }
 
-pub fn auctioneer_deposit(
+pub async fn queue_authority_update(
     context: &mut ProgramTestContext,
     ahkey: &Pubkey,
     ah: &AuctionHouse,
-    test_metadata: &Metadata,
-    buyer: &Keypair,
-    auctioneer_authority: &Keypair,
-    sale_price: u64,
-) -> (mpl_auction_house::accounts::AuctioneerDeposit, Transaction) {
-    let seller_token_account =
-        get_associated_token_address(&test_metadata.token.pubkey(), &test_metadata.mint.pubkey());
-    let (_buyer_trade_state, _sts_bump) = find_trade_state_address(
-        &buyer.pubkey(),
-        ahkey,
-        &seller_token_account,
-        &ah.treasury_mint,
-        &test_metadata.mint.pubkey(),
-        sale_price,
-        1,
-    );
-    let (auctioneer_pda, _) = find_auctioneer_pda(ahkey, &auctioneer_authority.pubkey());
-    let (escrow, escrow_bump) = find_escrow_payment_address(ahkey, &buyer.pubkey());
-
-    let accounts = mpl_auction_house::accounts::AuctioneerDeposit {
-        wallet: buyer.pubkey(),
-        payment_account: buyer.pubkey(),
-        transfer_authority: test_metadata.token.pubkey(),
-        escrow_payment_account: escrow,
+    authority: &Keypair,
+    new_authority: &Pubkey,
+) -> StdResult<(), TransportError> {
+    let accounts = mpl_auction_house::accounts::UpdateAuctionHouse {
         treasury_mint: ah.treasury_mint,
-        authority: ah.authority,
-        auctioneer_authority: auctioneer_authority.pubkey(),
+        payer: authority.pubkey(),
+        authority: authority.pubkey(),
+        new_authority: *new_authority,
+        fee_withdrawal_destination: ah.fee_withdrawal_destination,
+        treasury_withdrawal_destination: ah.treasury_withdrawal_destination,
+        treasury_withdrawal_destination_owner: ah.treasury_withdrawal_destination,
         auction_house: *ahkey,
-        auction_house_fee_account: ah.auction_house_fee_account,
-        ah_auctioneer_pda: auctioneer_pda,
+        pending_update: find_pending_update_address(ahkey).0,
         token_program: spl_token::id(),
-        system_program: solana_program::system_program::id(),
+        system_program: system_program::id(),
+        ata_program: spl_associated_token_account::id(),
         rent: sysvar::rent::id(),
-    };
-    let account_metas = accounts.to_account_metas(None);
+    }
+    .to_account_metas(None);
 
-    let data = mpl_auction_house::instruction::AuctioneerDeposit {
-        escrow_payment_bump: escrow_bump,
-        amount: sale_price,
+    let data = mpl_auction_house::instruction::UpdateAuctionHouse {
+        seller_fee_basis_points: None,
+        requires_sign_off: None,
+        can_change_sale_price: None,
+        auto_receipts: None,
+        fee_account_target_balance: None,
+        fee_account_low_balance_threshold: None,
+        wash_trading_guard: None,
+        enforce_royalties: None,
+        tick_size: None,
+        free_sales_require_sign_off: None,
+        max_free_sales_per_epoch: None,
+        paused_operations: None,
+        epoch_report_retention: None,
+        dispute_window: None,
+        listing_cooldown_slots: None,
     }
     .data();
 
     let instruction = Instruction {
         program_id: mpl_auction_house::id(),
         data,
-        accounts: account_metas,
+        accounts,
     };
 
-    (
-        accounts,
-        Transaction::new_signed_with_payer(
-            &[instruction],
-            Some(&buyer.pubkey()),
-            &[buyer, auctioneer_authority],
-            context.last_blockhash,
-        ),
-    )
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&authority.pubkey()),
+        &[authority],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await
 }
 
-pub fn buy(
+pub async fn apply_pending_update(
     context: &mut ProgramTestContext,
     ahkey: &Pubkey,
-    ah: &AuctionHouse,
-    test_metadata: &Metadata,
-    owner: &Pubkey,
-    buyer: &Keypair,
-    sale_price: u64,
-    token_size: u64,
-) -> (
-    (
-        mpl_auction_house::accounts::Buy,
-        mpl_auction_house::accounts::PrintBidReceipt,
-    ),
-    Transaction,
-) {
-    let seller_token_account = get_associated_token_address(owner, &test_metadata.mint.pubkey());
-    let trade_state = find_trade_state_address(
-        &buyer.pubkey(),
-        ahkey,
-        &seller_token_account,
-        &ah.treasury_mint,
-        &test_metadata.mint.pubkey(),
-        sale_price,
-        token_size,
-    );
-
-    let (escrow, escrow_bump) = find_escrow_payment_address(ahkey, &buyer.pubkey());
-    let (bts, bts_bump) = trade_state;
-    let accounts = mpl_auction_house::accounts::Buy {
-        wallet: buyer.pubkey(),
-        token_account: seller_token_account,
-        metadata: test_metadata.pubkey,
-        authority: ah.authority,
+    payer: &Pubkey,
+    cranker: &Keypair,
+) -> StdResult<(), TransportError> {
+    let accounts = mpl_auction_house::accounts::ApplyPendingUpdate {
         auction_house: *ahkey,
-        auction_house_fee_account: ah.auction_house_fee_account,
-        buyer_trade_state: bts,
-        token_program: spl_token::id(),
-        treasury_mint: ah.treasury_mint,
-        payment_account: buyer.pubkey(),
-        transfer_authority: buyer.pubkey(),
-        system_program: solana_program::system_program::id(),
-        rent: sysvar::rent::id(),
-        escrow_payment_account: escrow,
+        pending_update: find_pending_update_address(ahkey).0,
+        payer: *payer,
+    }
+    .to_account_metas(None);
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::ApplyPendingUpdate {}.data(),
+        accounts,
     };
 
-    let account_metas = accounts.to_account_metas(None);
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&cranker.pubkey()),
+        &[cranker],
+        context.last_blockhash,
+    );
 
-    let buy_ix = mpl_auction_house::instruction::Buy {
-        trade_state_bump: bts_bump,
-        escrow_payment_bump: escrow_bump,
-        token_size,
-        buyer_price: sale_price,
-    };
-    let data = buy_ix.data();
+    context.banks_client.process_transaction(tx).await
+}
+
+pub async fn cancel_pending_update(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    authority: &Keypair,
+    payer: &Pubkey,
+) -> StdResult<(), TransportError> {
+    let accounts = mpl_auction_house::accounts::CancelPendingUpdate {
+        authority: authority.pubkey(),
+        auction_house: *ahkey,
+        pending_update: find_pending_update_address(ahkey).0,
+        payer: *payer,
+    }
+    .to_account_metas(None);
 
     let instruction = Instruction {
         program_id: mpl_auction_house::id(),
-        data,
-        accounts: account_metas,
+        data: mpl_auction_house::instruction::CancelPendingUpdate {}.data(),
+        accounts,
     };
 
-    let (bid_receipt, bid_receipt_bump) = find_bid_receipt_address(&bts);
-    let print_receipt_accounts = mpl_auction_house::accounts::PrintBidReceipt {
-        receipt: bid_receipt,
-        bookkeeper: buyer.pubkey(),
-        system_program: solana_program::system_program::id(),
-        rent: sysvar::rent::id(),
-        instruction: sysvar::instructions::id(),
-    };
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&authority.pubkey()),
+        &[authority],
+        context.last_blockhash,
+    );
 
-    let account_metas = print_receipt_accounts.to_account_metas(None);
+    context.banks_client.process_transaction(tx).await
+}
 
-    let print_bid_receipt_ix = mpl_auction_house::instruction::PrintBidReceipt {
-        receipt_bump: bid_receipt_bump,
-    };
-    let data = print_bid_receipt_ix.data();
+pub async fn register_linked_wallet(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    authority: &Keypair,
+    wallet: &Pubkey,
+    group: Pubkey,
+) -> StdResult<(), TransportError> {
+    let accounts = mpl_auction_house::accounts::RegisterLinkedWallet {
+        authority: authority.pubkey(),
+        auction_house: *ahkey,
+        wallet: *wallet,
+        linked_wallet: find_linked_wallet_address(ahkey, wallet).0,
+        system_program: system_program::id(),
+    }
+    .to_account_metas(None);
 
-    let print_bid_receipt_instruction = Instruction {
+    let instruction = Instruction {
         program_id: mpl_auction_house::id(),
-        data,
-        accounts: account_metas,
+        data: mpl_auction_house::instruction::RegisterLinkedWallet { group }.data(),
+        accounts,
     };
 
-    (
-        (accounts, print_receipt_accounts),
-        Transaction::new_signed_with_payer(
-            &[instruction, print_bid_receipt_instruction],
-            Some(&buyer.pubkey()),
-            &[buyer],
-            context.last_blockhash,
-        ),
-    )
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&authority.pubkey()),
+        &[authority],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await
 }
 
-pub fn auctioneer_buy(
+pub async fn remove_linked_wallet(
     context: &mut ProgramTestContext,
     ahkey: &Pubkey,
-    ah: &AuctionHouse,
-    test_metadata: &Metadata,
-    owner: &Pubkey,
-    buyer: &Keypair,
-    auctioneer_authority: &Keypair,
-    sale_price: u64,
+    authority: &Keypair,
+    wallet: &Pubkey,
+) -> StdResult<(), TransportError> {
+    let accounts = mpl_auction_house::accounts::RemoveLinkedWallet {
+        authority: authority.pubkey(),
+        auction_house: *ahkey,
+        linked_wallet: find_linked_wallet_address(ahkey, wallet).0,
+    }
+    .to_account_metas(None);
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::RemoveLinkedWallet {}.data(),
+        accounts,
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&authority.pubkey()),
+        &[authority],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await
+}
+
+pub async fn set_royalty_mandate(
+    context: &mut ProgramTestContext,
+    creator: &Keypair,
+    destination: &Pubkey,
+) -> StdResult<(), TransportError> {
+    let accounts = mpl_auction_house::accounts::SetRoyaltyMandate {
+        creator: creator.pubkey(),
+        destination: *destination,
+        royalty_mandate: find_royalty_mandate_address(&creator.pubkey()).0,
+        system_program: system_program::id(),
+    }
+    .to_account_metas(None);
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::SetRoyaltyMandate {}.data(),
+        accounts,
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&creator.pubkey()),
+        &[creator],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await
+}
+
+pub async fn set_proceeds_route(
+    context: &mut ProgramTestContext,
+    seller: &Keypair,
+    seller_trade_state: &Pubkey,
+    destination: &Pubkey,
+) -> StdResult<(), TransportError> {
+    let accounts = mpl_auction_house::accounts::SetProceedsRoute {
+        seller: seller.pubkey(),
+        seller_trade_state: *seller_trade_state,
+        destination: *destination,
+        proceeds_route: find_proceeds_route_address(&seller.pubkey(), seller_trade_state).0,
+        system_program: system_program::id(),
+    }
+    .to_account_metas(None);
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::SetProceedsRoute {}.data(),
+        accounts,
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&seller.pubkey()),
+        &[seller],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await
+}
+
+pub async fn flag_purchase(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    authority: &Keypair,
+    purchase_receipt: &Pubkey,
+) -> StdResult<(), TransportError> {
+    let accounts = mpl_auction_house::accounts::FlagPurchase {
+        authority: authority.pubkey(),
+        auction_house: *ahkey,
+        purchase_receipt: *purchase_receipt,
+        deferred_settlement: find_deferred_settlement_address(ahkey, purchase_receipt).0,
+    }
+    .to_account_metas(None);
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::FlagPurchase {}.data(),
+        accounts,
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&authority.pubkey()),
+        &[authority],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await
+}
+
+pub async fn release_settlement(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    payer: &Keypair,
+    purchase_receipt: &Pubkey,
+    destination: &Pubkey,
+) -> StdResult<(), TransportError> {
+    let deferred_settlement = find_deferred_settlement_address(ahkey, purchase_receipt).0;
+    let accounts = mpl_auction_house::accounts::ReleaseSettlement {
+        destination: *destination,
+        treasury_mint: ah.treasury_mint,
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        deferred_settlement,
+        settlement_token_account: get_associated_token_address(
+            &deferred_settlement,
+            &ah.treasury_mint,
+        ),
+        token_program: spl_token::id(),
+    }
+    .to_account_metas(None);
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::ReleaseSettlement {}.data(),
+        accounts,
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[payer],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn resolve_dispute(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    authority: &Keypair,
+    purchase_receipt: &Pubkey,
+    buyer: &Pubkey,
+    destination: &Pubkey,
+    release_to_seller: bool,
+) -> StdResult<(), TransportError> {
+    let deferred_settlement = find_deferred_settlement_address(ahkey, purchase_receipt).0;
+    let accounts = mpl_auction_house::accounts::ResolveDispute {
+        authority: authority.pubkey(),
+        buyer: *buyer,
+        buyer_refund_account: *buyer,
+        destination: *destination,
+        treasury_mint: ah.treasury_mint,
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        deferred_settlement,
+        settlement_token_account: get_associated_token_address(
+            &deferred_settlement,
+            &ah.treasury_mint,
+        ),
+        token_program: spl_token::id(),
+    }
+    .to_account_metas(None);
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::ResolveDispute { release_to_seller }.data(),
+        accounts,
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&authority.pubkey()),
+        &[authority],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await
+}
+
+pub async fn create_bundle_listing(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    seller: &Keypair,
+    bundle_id: u64,
+    items: Vec<BundleItem>,
+) -> StdResult<(), TransportError> {
+    let mut accounts = mpl_auction_house::accounts::CreateBundleListing {
+        seller: seller.pubkey(),
+        auction_house: *ahkey,
+        bundle_listing: find_bundle_listing_address(&seller.pubkey(), ahkey, bundle_id).0,
+        system_program: system_program::id(),
+    }
+    .to_account_metas(None);
+    accounts.extend(
+        items
+            .iter()
+            .map(|item| AccountMeta::new_readonly(item.trade_state, false)),
+    );
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::CreateBundleListing { bundle_id, items }.data(),
+        accounts,
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&seller.pubkey()),
+        &[seller],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await
+}
+
+pub async fn create_compliance_config(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    authority: &Keypair,
+    volume_cap_per_epoch: u64,
+    frozen_wallets: Vec<Pubkey>,
+) -> StdResult<(), TransportError> {
+    let accounts = mpl_auction_house::accounts::CreateComplianceConfig {
+        authority: authority.pubkey(),
+        auction_house: *ahkey,
+        compliance_config: find_compliance_config_address(ahkey).0,
+        system_program: system_program::id(),
+    }
+    .to_account_metas(None);
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::CreateComplianceConfig {
+            volume_cap_per_epoch,
+            frozen_wallets,
+        }
+        .data(),
+        accounts,
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&authority.pubkey()),
+        &[authority],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await
+}
+
+pub async fn create_order_book_summary(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    payer: &Keypair,
+    token_mint: &Pubkey,
+) -> StdResult<Pubkey, TransportError> {
+    let (order_book_summary, _) = find_order_book_summary_address(ahkey, token_mint);
+    let accounts = mpl_auction_house::accounts::CreateOrderBookSummary {
+        payer: payer.pubkey(),
+        auction_house: *ahkey,
+        token_mint: *token_mint,
+        order_book_summary,
+        system_program: system_program::id(),
+    }
+    .to_account_metas(None);
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::CreateOrderBookSummary {}.data(),
+        accounts,
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[payer],
+        context.last_blockhash,
+    );
+
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map(|_| order_book_summary)
+}
+
+pub async fn prepare_sale_accounts(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    authority: &Keypair,
+    test_metadata: &Metadata,
+    buyer: &Pubkey,
+    seller: &Pubkey,
+) -> StdResult<(), TransportError> {
+    let seller_payment_receipt_account = if ah.treasury_mint == spl_token::native_mint::id() {
+        *seller
+    } else {
+        get_associated_token_address(seller, &ah.treasury_mint)
+    };
+    let accounts = mpl_auction_house::accounts::PrepareSaleAccounts {
+        buyer: *buyer,
+        seller: *seller,
+        token_mint: test_metadata.mint.pubkey(),
+        metadata: test_metadata.pubkey,
+        treasury_mint: ah.treasury_mint,
+        seller_payment_receipt_account,
+        buyer_receipt_token_account: get_associated_token_address(
+            buyer,
+            &test_metadata.mint.pubkey(),
+        ),
+        authority: authority.pubkey(),
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        ata_program: spl_associated_token_account::id(),
+        rent: sysvar::rent::id(),
+    }
+    .to_account_metas(None);
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::PrepareSaleAccounts {}.data(),
+        accounts,
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&authority.pubkey()),
+        &[authority],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await
+}
+
+pub async fn top_up_fee_account(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    payer: &Keypair,
+) -> StdResult<(), TransportError> {
+    let accounts = mpl_auction_house::accounts::TopUpFeeAccount {
+        treasury_mint: ah.treasury_mint,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        auction_house_treasury: ah.auction_house_treasury,
+        auction_house: *ahkey,
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+    }
+    .to_account_metas(None);
+
+    let data = mpl_auction_house::instruction::TopUpFeeAccount {}.data();
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data,
+        accounts,
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[payer],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn begin_layaway(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    buyer: &Keypair,
+    price: u64,
+    token_size: u64,
+    installments: u8,
+    installment_interval: i64,
+    penalty_basis_points: u16,
+) -> StdResult<Pubkey, TransportError> {
+    let seller = &test_metadata.token;
+    let token_account =
+        get_associated_token_address(&seller.pubkey(), &test_metadata.mint.pubkey());
+    let (seller_trade_state, _) = find_trade_state_address(
+        &seller.pubkey(),
+        ahkey,
+        &token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        price,
+        token_size,
+    );
+    let (layaway, _) = find_layaway_address(ahkey, &seller_trade_state);
+    let (program_as_signer, program_as_signer_bump) = find_program_as_signer_address();
+    let accounts = mpl_auction_house::accounts::BeginLayaway {
+        buyer: buyer.pubkey(),
+        seller: seller.pubkey(),
+        treasury_mint: ah.treasury_mint,
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        token_account,
+        token_mint: test_metadata.mint.pubkey(),
+        seller_trade_state,
+        layaway,
+        layaway_token_account: find_layaway_token_account_address(&layaway).0,
+        program_as_signer,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+    }
+    .to_account_metas(None);
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::BeginLayaway {
+            program_as_signer_bump,
+            price,
+            token_size,
+            installments,
+            installment_interval,
+            penalty_basis_points,
+        }
+        .data(),
+        accounts,
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&buyer.pubkey()),
+        &[buyer, seller],
+        context.last_blockhash,
+    );
+
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map(|_| layaway)
+}
+
+pub async fn pay_installment(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    buyer: &Keypair,
+    layaway: &Pubkey,
+    amount: u64,
+) -> StdResult<(), TransportError> {
+    let accounts = mpl_auction_house::accounts::PayInstallment {
+        buyer: buyer.pubkey(),
+        payment_account: buyer.pubkey(),
+        treasury_mint: ah.treasury_mint,
+        auction_house: *ahkey,
+        layaway: *layaway,
+        layaway_escrow: find_layaway_escrow_address(ahkey, layaway).0,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+    }
+    .to_account_metas(None);
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::PayInstallment { amount }.data(),
+        accounts,
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&buyer.pubkey()),
+        &[buyer],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await
+}
+
+pub async fn complete_layaway(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    buyer: &Keypair,
+    layaway: &Pubkey,
+) -> StdResult<(), TransportError> {
+    let seller = test_metadata.token.pubkey();
+    let (program_as_signer, program_as_signer_bump) = find_program_as_signer_address();
+    let accounts = mpl_auction_house::accounts::CompleteLayaway {
+        buyer: buyer.pubkey(),
+        seller,
+        token_mint: test_metadata.mint.pubkey(),
+        metadata: test_metadata.pubkey,
+        treasury_mint: ah.treasury_mint,
+        layaway_escrow: find_layaway_escrow_address(ahkey, layaway).0,
+        seller_payment_receipt_account: seller,
+        buyer_receipt_token_account: get_associated_token_address(
+            &buyer.pubkey(),
+            &test_metadata.mint.pubkey(),
+        ),
+        authority: ah.authority,
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        auction_house_treasury: ah.auction_house_treasury,
+        layaway: *layaway,
+        layaway_token_account: find_layaway_token_account_address(layaway).0,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        ata_program: spl_associated_token_account::id(),
+        program_as_signer,
+        rent: sysvar::rent::id(),
+    }
+    .to_account_metas(None);
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::CompleteLayaway {
+            program_as_signer_bump,
+        }
+        .data(),
+        accounts,
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&buyer.pubkey()),
+        &[buyer],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await
+}
+
+pub async fn cancel_layaway(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    buyer: &Pubkey,
+    layaway: &Pubkey,
+) -> StdResult<(), TransportError> {
+    let seller = &test_metadata.token;
+    let (program_as_signer, program_as_signer_bump) = find_program_as_signer_address();
+    let accounts = mpl_auction_house::accounts::CancelLayaway {
+        seller: seller.pubkey(),
+        buyer: *buyer,
+        token_mint: test_metadata.mint.pubkey(),
+        seller_token_account: get_associated_token_address(
+            &seller.pubkey(),
+            &test_metadata.mint.pubkey(),
+        ),
+        treasury_mint: ah.treasury_mint,
+        layaway_escrow: find_layaway_escrow_address(ahkey, layaway).0,
+        seller_payment_receipt_account: seller.pubkey(),
+        buyer_refund_account: *buyer,
+        auction_house: *ahkey,
+        layaway: *layaway,
+        layaway_token_account: find_layaway_token_account_address(layaway).0,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        ata_program: spl_associated_token_account::id(),
+        program_as_signer,
+        rent: sysvar::rent::id(),
+    }
+    .to_account_metas(None);
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::CancelLayaway {
+            program_as_signer_bump,
+        }
+        .data(),
+        accounts,
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&seller.pubkey()),
+        &[seller],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await
+}
+
+pub fn propose_swap(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    proposer: &Keypair,
+    offered_mint: &Pubkey,
+    target_mint: &Pubkey,
+    offered_size: u64,
+    target_size: u64,
+    max_offered_valuation: u64,
+) -> (mpl_auction_house::accounts::ProposeSwap, Transaction) {
+    let (program_as_signer, program_as_signer_bump) = find_program_as_signer_address();
+    let accounts = mpl_auction_house::accounts::ProposeSwap {
+        proposer: proposer.pubkey(),
+        auction_house: *ahkey,
+        offered_token_account: get_associated_token_address(&proposer.pubkey(), offered_mint),
+        offered_mint: *offered_mint,
+        target_mint: *target_mint,
+        swap_proposal: find_swap_proposal_address(
+            &proposer.pubkey(),
+            ahkey,
+            offered_mint,
+            target_mint,
+        )
+        .0,
+        program_as_signer,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+    };
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::ProposeSwap {
+            program_as_signer_bump,
+            offered_size,
+            target_size,
+            max_offered_valuation,
+        }
+        .data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&proposer.pubkey()),
+        &[proposer],
+        context.last_blockhash,
+    );
+
+    (accounts, tx)
+}
+
+pub fn accept_swap(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    authority: &Keypair,
+    acceptor: &Keypair,
+    propose_accounts: &mpl_auction_house::accounts::ProposeSwap,
+    offered_valuation: u64,
+    target_valuation: u64,
+    creators: &[Pubkey],
+) -> (mpl_auction_house::accounts::AcceptSwap, Transaction) {
+    let proposer = propose_accounts.proposer;
+    let offered_mint = propose_accounts.offered_mint;
+    let target_mint = propose_accounts.target_mint;
+    let (program_as_signer, program_as_signer_bump) = find_program_as_signer_address();
+    let (proposer_escrow_payment_account, proposer_escrow_bump) =
+        find_escrow_payment_address(ahkey, &proposer);
+    let (acceptor_escrow_payment_account, acceptor_escrow_bump) =
+        find_escrow_payment_address(ahkey, &acceptor.pubkey());
+    let accounts = mpl_auction_house::accounts::AcceptSwap {
+        acceptor: acceptor.pubkey(),
+        proposer,
+        authority: authority.pubkey(),
+        treasury_mint: ah.treasury_mint,
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        swap_proposal: propose_accounts.swap_proposal,
+        offered_token_account: propose_accounts.offered_token_account,
+        offered_mint,
+        offered_metadata: find_metadata_account(&offered_mint).0,
+        target_token_account: get_associated_token_address(&acceptor.pubkey(), &target_mint),
+        target_mint,
+        target_metadata: find_metadata_account(&target_mint).0,
+        proposer_receipt_token_account: get_associated_token_address(&proposer, &target_mint),
+        acceptor_receipt_token_account: get_associated_token_address(
+            &acceptor.pubkey(),
+            &offered_mint,
+        ),
+        proposer_escrow_payment_account,
+        acceptor_escrow_payment_account,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        ata_program: spl_associated_token_account::id(),
+        program_as_signer,
+        rent: sysvar::rent::id(),
+    };
+    let mut account_metas = accounts.to_account_metas(None);
+    account_metas.extend(creators.iter().flat_map(|creator| {
+        [
+            AccountMeta::new(*creator, false),
+            AccountMeta::new_readonly(find_royalty_mandate_address(creator).0, false),
+        ]
+    }));
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::AcceptSwap {
+            proposer_escrow_bump,
+            acceptor_escrow_bump,
+            program_as_signer_bump,
+            offered_valuation,
+            target_valuation,
+        }
+        .data(),
+        accounts: account_metas,
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&acceptor.pubkey()),
+        &[acceptor, authority],
+        context.last_blockhash,
+    );
+
+    (accounts, tx)
+}
+
+pub fn cancel_swap(
+    context: &mut ProgramTestContext,
+    proposer: &Keypair,
+    propose_accounts: &mpl_auction_house::accounts::ProposeSwap,
+) -> (mpl_auction_house::accounts::CancelSwap, Transaction) {
+    let accounts = mpl_auction_house::accounts::CancelSwap {
+        proposer: proposer.pubkey(),
+        auction_house: propose_accounts.auction_house,
+        offered_token_account: propose_accounts.offered_token_account,
+        swap_proposal: propose_accounts.swap_proposal,
+        token_program: spl_token::id(),
+    };
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::CancelSwap {}.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&proposer.pubkey()),
+        &[proposer],
+        context.last_blockhash,
+    );
+
+    (accounts, tx)
+}
+
+pub fn deposit(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    buyer: &Keypair,
+    sale_price: u64,
+) -> (mpl_auction_house::accounts::Deposit, Transaction) {
+    let seller_token_account =
+        get_associated_token_address(&test_metadata.token.pubkey(), &test_metadata.mint.pubkey());
+    let (_buyer_trade_state, _sts_bump) = find_trade_state_address(
+        &buyer.pubkey(),
+        ahkey,
+        &seller_token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        sale_price,
+        1,
+    );
+    let (escrow, escrow_bump) = find_escrow_payment_address(ahkey, &buyer.pubkey());
+    let accounts = mpl_auction_house::accounts::Deposit {
+        wallet: buyer.pubkey(),
+        authority: ah.authority,
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        token_program: spl_token::id(),
+        treasury_mint: ah.treasury_mint,
+        payment_account: buyer.pubkey(),
+        transfer_authority: buyer.pubkey(),
+        system_program: solana_program::system_program::id(),
+        rent: sysvar::rent::id(),
+        escrow_payment_account: escrow,
+    };
+    let account_metas = accounts.to_account_metas(None);
+
+    let data = mpl_auction_house::instruction::Deposit {
+        amount: sale_price,
+        escrow_payment_bump: escrow_bump,
+    }
+    .data();
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data,
+        accounts: account_metas,
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&buyer.pubkey()),
+            &[buyer],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub fn auctioneer_deposit(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    buyer: &Keypair,
+    auctioneer_authority: &Keypair,
+    sale_price: u64,
+) -> (mpl_auction_house::accounts::AuctioneerDeposit, Transaction) {
+    let seller_token_account =
+        get_associated_token_address(&test_metadata.token.pubkey(), &test_metadata.mint.pubkey());
+    let (_buyer_trade_state, _sts_bump) = find_trade_state_address(
+        &buyer.pubkey(),
+        ahkey,
+        &seller_token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        sale_price,
+        1,
+    );
+    let (auctioneer_pda, _) = find_auctioneer_pda(ahkey, &auctioneer_authority.pubkey());
+    let (escrow, escrow_bump) = find_escrow_payment_address(ahkey, &buyer.pubkey());
+
+    let accounts = mpl_auction_house::accounts::AuctioneerDeposit {
+        wallet: buyer.pubkey(),
+        payment_account: buyer.pubkey(),
+        transfer_authority: test_metadata.token.pubkey(),
+        escrow_payment_account: escrow,
+        treasury_mint: ah.treasury_mint,
+        authority: ah.authority,
+        auctioneer_authority: auctioneer_authority.pubkey(),
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        ah_auctioneer_pda: auctioneer_pda,
+        token_program: spl_token::id(),
+        system_program: solana_program::system_program::id(),
+        rent: sysvar::rent::id(),
+    };
+    let account_metas = accounts.to_account_metas(None);
+
+    let data = mpl_auction_house::instruction::AuctioneerDeposit {
+        escrow_payment_bump: escrow_bump,
+        amount: sale_price,
+    }
+    .data();
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data,
+        accounts: account_metas,
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&buyer.pubkey()),
+            &[buyer, auctioneer_authority],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub fn buy(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    owner: &Pubkey,
+    buyer: &Keypair,
+    sale_price: u64,
+    token_size: u64,
+) -> (
+    (
+        mpl_auction_house::accounts::Buy,
+        mpl_auction_house::accounts::PrintBidReceipt,
+    ),
+    Transaction,
+) {
+    let seller_token_account = get_associated_token_address(owner, &test_metadata.mint.pubkey());
+    let trade_state = find_trade_state_address(
+        &buyer.pubkey(),
+        ahkey,
+        &seller_token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        sale_price,
+        token_size,
+    );
+
+    let (escrow, escrow_bump) = find_escrow_payment_address(ahkey, &buyer.pubkey());
+    let (bts, bts_bump) = trade_state;
+    let accounts = mpl_auction_house::accounts::Buy {
+        wallet: buyer.pubkey(),
+        token_account: seller_token_account,
+        metadata: test_metadata.pubkey,
+        authority: ah.authority,
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        buyer_trade_state: bts,
+        token_program: spl_token::id(),
+        treasury_mint: ah.treasury_mint,
+        payment_account: buyer.pubkey(),
+        transfer_authority: buyer.pubkey(),
+        system_program: solana_program::system_program::id(),
+        rent: sysvar::rent::id(),
+        escrow_payment_account: escrow,
+    };
+
+    let account_metas = accounts.to_account_metas(None);
+
+    let buy_ix = mpl_auction_house::instruction::Buy {
+        trade_state_bump: bts_bump,
+        escrow_payment_bump: escrow_bump,
+        token_size,
+        buyer_price: sale_price,
+    };
+    let data = buy_ix.data();
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data,
+        accounts: account_metas,
+    };
+
+    let (bid_receipt, bid_receipt_bump) = find_bid_receipt_address(&bts);
+    let print_receipt_accounts = mpl_auction_house::accounts::PrintBidReceipt {
+        receipt: bid_receipt,
+        bookkeeper: buyer.pubkey(),
+        system_program: solana_program::system_program::id(),
+        rent: sysvar::rent::id(),
+        instruction: sysvar::instructions::id(),
+    };
+
+    let account_metas = print_receipt_accounts.to_account_metas(None);
+
+    let print_bid_receipt_ix = mpl_auction_house::instruction::PrintBidReceipt {
+        receipt_bump: bid_receipt_bump,
+    };
+    let data = print_bid_receipt_ix.data();
+
+    let print_bid_receipt_instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data,
+        accounts: account_metas,
+    };
+
+    (
+        (accounts, print_receipt_accounts),
+        Transaction::new_signed_with_payer(
+            &[instruction, print_bid_receipt_instruction],
+            Some(&buyer.pubkey()),
+            &[buyer],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub fn auctioneer_buy(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    owner: &Pubkey,
+    buyer: &Keypair,
+    auctioneer_authority: &Keypair,
+    sale_price: u64,
 ) -> (mpl_auction_house::accounts::AuctioneerBuy, Transaction) {
     let seller_token_account = get_associated_token_address(owner, &test_metadata.mint.pubkey());
     let trade_state = find_trade_state_address(
         &buyer.pubkey(),
         ahkey,
-        &seller_token_account,
+        &seller_token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        sale_price,
+        1,
+    );
+    let (auctioneer_pda, _) = find_auctioneer_pda(ahkey, &auctioneer_authority.pubkey());
+    let (escrow, escrow_bump) = find_escrow_payment_address(ahkey, &buyer.pubkey());
+    let (bts, bts_bump) = trade_state;
+
+    let accounts = mpl_auction_house::accounts::AuctioneerBuy {
+        wallet: buyer.pubkey(),
+        token_account: seller_token_account,
+        metadata: test_metadata.pubkey,
+        authority: ah.authority,
+        auctioneer_authority: auctioneer_authority.pubkey(),
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        buyer_trade_state: bts,
+        ah_auctioneer_pda: auctioneer_pda,
+        token_program: spl_token::id(),
+        treasury_mint: ah.treasury_mint,
+        payment_account: buyer.pubkey(),
+        transfer_authority: buyer.pubkey(),
+        system_program: solana_program::system_program::id(),
+        rent: sysvar::rent::id(),
+        escrow_payment_account: escrow,
+    };
+
+    let account_metas = accounts.to_account_metas(None);
+
+    let buy_ix = mpl_auction_house::instruction::AuctioneerBuy {
+        trade_state_bump: bts_bump,
+        escrow_payment_bump: escrow_bump,
+        token_size: 1,
+        buyer_price: sale_price,
+    };
+    let data = buy_ix.data();
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data,
+        accounts: account_metas,
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&buyer.pubkey()),
+            &[buyer, auctioneer_authority],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub fn public_buy(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    owner: &Pubkey,
+    buyer: &Keypair,
+    sale_price: u64,
+) -> (
+    (
+        mpl_auction_house::accounts::PublicBuy,
+        mpl_auction_house::accounts::PrintBidReceipt,
+    ),
+    Transaction,
+) {
+    let seller_token_account = get_associated_token_address(owner, &test_metadata.mint.pubkey());
+    let trade_state = find_public_bid_trade_state_address(
+        &buyer.pubkey(),
+        ahkey,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        sale_price,
+        1,
+    );
+    let (escrow, escrow_bump) = find_escrow_payment_address(ahkey, &buyer.pubkey());
+    let (bts, bts_bump) = trade_state;
+
+    let accounts = mpl_auction_house::accounts::PublicBuy {
+        wallet: buyer.pubkey(),
+        token_account: seller_token_account,
+        metadata: test_metadata.pubkey,
+        authority: ah.authority,
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        buyer_trade_state: bts,
+        token_program: spl_token::id(),
+        treasury_mint: ah.treasury_mint,
+        payment_account: buyer.pubkey(),
+        transfer_authority: buyer.pubkey(),
+        system_program: solana_program::system_program::id(),
+        rent: sysvar::rent::id(),
+        escrow_payment_account: escrow,
+    };
+    let account_metas = accounts.to_account_metas(None);
+
+    let buy_ix = mpl_auction_house::instruction::PublicBuy {
+        trade_state_bump: bts_bump,
+        escrow_payment_bump: escrow_bump,
+        token_size: 1,
+        buyer_price: sale_price,
+    };
+    let data = buy_ix.data();
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data,
+        accounts: account_metas,
+    };
+
+    let (bid_receipt, bid_receipt_bump) = find_bid_receipt_address(&bts);
+    let print_receipt_accounts = mpl_auction_house::accounts::PrintBidReceipt {
+        receipt: bid_receipt,
+        bookkeeper: buyer.pubkey(),
+        system_program: solana_program::system_program::id(),
+        rent: sysvar::rent::id(),
+        instruction: sysvar::instructions::id(),
+    };
+
+    let account_metas = print_receipt_accounts.to_account_metas(None);
+
+    let print_bid_receipt_ix = mpl_auction_house::instruction::PrintBidReceipt {
+        receipt_bump: bid_receipt_bump,
+    };
+    let data = print_bid_receipt_ix.data();
+
+    let print_bid_receipt_instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data,
+        accounts: account_metas,
+    };
+
+    (
+        (accounts, print_receipt_accounts),
+        Transaction::new_signed_with_payer(
+            &[instruction, print_bid_receipt_instruction],
+            Some(&buyer.pubkey()),
+            &[buyer],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub fn auctioneer_public_buy(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    owner: &Pubkey,
+    buyer: &Keypair,
+    auctioneer_authority: &Keypair,
+    sale_price: u64,
+) -> (
+    mpl_auction_house::accounts::AuctioneerPublicBuy,
+    Transaction,
+) {
+    let seller_token_account = get_associated_token_address(owner, &test_metadata.mint.pubkey());
+    let trade_state = find_public_bid_trade_state_address(
+        &buyer.pubkey(),
+        ahkey,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        sale_price,
+        1,
+    );
+
+    let (auctioneer_pda, _) = find_auctioneer_pda(ahkey, &auctioneer_authority.pubkey());
+    let (escrow, escrow_bump) = find_escrow_payment_address(ahkey, &buyer.pubkey());
+    let (bts, bts_bump) = trade_state;
+
+    let accounts = mpl_auction_house::accounts::AuctioneerPublicBuy {
+        wallet: buyer.pubkey(),
+        token_account: seller_token_account,
+        metadata: test_metadata.pubkey,
+        authority: ah.authority,
+        auctioneer_authority: auctioneer_authority.pubkey(),
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        buyer_trade_state: bts,
+        token_program: spl_token::id(),
+        treasury_mint: ah.treasury_mint,
+        payment_account: buyer.pubkey(),
+        transfer_authority: buyer.pubkey(),
+        ah_auctioneer_pda: auctioneer_pda,
+        system_program: solana_program::system_program::id(),
+        rent: sysvar::rent::id(),
+        escrow_payment_account: escrow,
+    };
+    let account_metas = accounts.to_account_metas(None);
+
+    let buy_ix = mpl_auction_house::instruction::AuctioneerPublicBuy {
+        trade_state_bump: bts_bump,
+        escrow_payment_bump: escrow_bump,
+        token_size: 1,
+        buyer_price: sale_price,
+    };
+    let data = buy_ix.data();
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data,
+        accounts: account_metas,
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&buyer.pubkey()),
+            &[buyer, auctioneer_authority],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub fn execute_sale(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    authority: &Keypair,
+    test_metadata: &Metadata,
+    buyer: &Pubkey,
+    seller: &Pubkey,
+    token_account: &Pubkey,
+    seller_trade_state: &Pubkey,
+    buyer_trade_state: &Pubkey,
+    token_size: u64,
+    buyer_price: u64,
+    partial_order_size: Option<u64>,
+    partial_order_price: Option<u64>,
+) -> (
+    (
+        mpl_auction_house::accounts::ExecuteSale,
+        mpl_auction_house::accounts::PrintPurchaseReceipt,
+    ),
+    Transaction,
+) {
+    let program_id = mpl_auction_house::id();
+    let buyer_token_account = get_associated_token_address(buyer, &test_metadata.mint.pubkey());
+
+    let (program_as_signer, pas_bump) = find_program_as_signer_address();
+
+    let (free_trade_state, free_sts_bump) = find_trade_state_address(
+        seller,
+        ahkey,
+        token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        0,
+        token_size,
+    );
+    let (escrow_payment_account, escrow_bump) = find_escrow_payment_address(ahkey, buyer);
+    let (purchase_receipt, purchase_receipt_bump) =
+        find_purchase_receipt_address(seller_trade_state, buyer_trade_state);
+    let (listing_receipt, _listing_receipt_bump) = find_listing_receipt_address(seller_trade_state);
+    let (bid_receipt, _public_bid_receipt_bump) = find_bid_receipt_address(buyer_trade_state);
+    let execute_sale_accounts = mpl_auction_house::accounts::ExecuteSale {
+        buyer: *buyer,
+        seller: *seller,
+        auction_house: *ahkey,
+        token_account: *token_account,
+        token_mint: test_metadata.mint.pubkey(),
+        treasury_mint: ah.treasury_mint,
+        metadata: test_metadata.pubkey,
+        authority: ah.authority,
+        seller_trade_state: *seller_trade_state,
+        buyer_trade_state: *buyer_trade_state,
+        free_trade_state,
+        seller_payment_receipt_account: *seller,
+        buyer_receipt_token_account: buyer_token_account,
+        escrow_payment_account,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        auction_house_treasury: ah.auction_house_treasury,
+        program_as_signer,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        ata_program: spl_associated_token_account::id(),
+        rent: sysvar::rent::id(),
+    };
+
+    let mut execute_sale_account_metas = execute_sale_accounts.to_account_metas(None);
+    execute_sale_account_metas.push(AccountMeta::new_readonly(
+        find_proceeds_route_address(seller, seller_trade_state).0,
+        false,
+    ));
+
+    let execute_sale_instruction = Instruction {
+        program_id,
+        data: mpl_auction_house::instruction::ExecuteSale {
+            escrow_payment_bump: escrow_bump,
+            _free_trade_state_bump: free_sts_bump,
+            program_as_signer_bump: pas_bump,
+            token_size,
+            buyer_price,
+            partial_order_size,
+            partial_order_price,
+        }
+        .data(),
+        accounts: execute_sale_account_metas,
+    };
+
+    let print_purchase_receipt_accounts = mpl_auction_house::accounts::PrintPurchaseReceipt {
+        purchase_receipt,
+        listing_receipt,
+        bid_receipt,
+        bookkeeper: authority.pubkey(),
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+        instruction: sysvar::instructions::id(),
+    };
+
+    let print_purchase_receipt_instruction = Instruction {
+        program_id,
+        data: mpl_auction_house::instruction::PrintPurchaseReceipt {
+            purchase_receipt_bump,
+        }
+        .data(),
+        accounts: print_purchase_receipt_accounts.to_account_metas(None),
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[execute_sale_instruction, print_purchase_receipt_instruction],
+        Some(&authority.pubkey()),
+        &[authority],
+        context.last_blockhash,
+    );
+
+    ((execute_sale_accounts, print_purchase_receipt_accounts), tx)
+}
+
+pub fn auctioneer_execute_sale(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    authority: &Keypair,
+    auctioneer_authority: &Keypair,
+    test_metadata: &Metadata,
+    buyer: &Pubkey,
+    seller: &Pubkey,
+    token_account: &Pubkey,
+    seller_trade_state: &Pubkey,
+    buyer_trade_state: &Pubkey,
+    token_size: u64,
+    buyer_price: u64,
+) -> (
+    mpl_auction_house::accounts::AuctioneerExecuteSale,
+    Transaction,
+) {
+    let program_id = mpl_auction_house::id();
+    let buyer_token_account = get_associated_token_address(buyer, &test_metadata.mint.pubkey());
+
+    let (program_as_signer, pas_bump) = find_program_as_signer_address();
+
+    let (free_trade_state, free_sts_bump) = find_trade_state_address(
+        seller,
+        ahkey,
+        token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        0,
+        token_size,
+    );
+
+    let (auctioneer_pda, _) = find_auctioneer_pda(ahkey, &auctioneer_authority.pubkey());
+    let (escrow_payment_account, escrow_bump) = find_escrow_payment_address(ahkey, buyer);
+
+    let execute_sale_accounts = mpl_auction_house::accounts::AuctioneerExecuteSale {
+        buyer: *buyer,
+        seller: *seller,
+        auction_house: *ahkey,
+        token_account: *token_account,
+        token_mint: test_metadata.mint.pubkey(),
+        treasury_mint: ah.treasury_mint,
+        metadata: test_metadata.pubkey,
+        authority: ah.authority,
+        auctioneer_authority: auctioneer_authority.pubkey(),
+        seller_trade_state: *seller_trade_state,
+        buyer_trade_state: *buyer_trade_state,
+        free_trade_state,
+        seller_payment_receipt_account: *seller,
+        buyer_receipt_token_account: buyer_token_account,
+        escrow_payment_account,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        auction_house_treasury: ah.auction_house_treasury,
+        program_as_signer,
+        ah_auctioneer_pda: auctioneer_pda,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        ata_program: spl_associated_token_account::id(),
+        rent: sysvar::rent::id(),
+    };
+
+    let execute_sale_account_metas = execute_sale_accounts.to_account_metas(None);
+
+    let execute_sale_instruction = Instruction {
+        program_id,
+        data: mpl_auction_house::instruction::AuctioneerExecuteSale {
+            escrow_payment_bump: escrow_bump,
+            _free_trade_state_bump: free_sts_bump,
+            program_as_signer_bump: pas_bump,
+            token_size,
+            buyer_price,
+        }
+        .data(),
+        accounts: execute_sale_account_metas,
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[execute_sale_instruction],
+        Some(&authority.pubkey()),
+        &[authority, auctioneer_authority],
+        context.last_blockhash,
+    );
+
+    (execute_sale_accounts, tx)
+}
+
+pub fn sell_mint(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata_mint: &Pubkey,
+    seller: &Keypair,
+    sale_price: u64,
+) -> (
+    (
+        mpl_auction_house::accounts::Sell,
+        mpl_auction_house::accounts::PrintListingReceipt,
+    ),
+    Transaction,
+) {
+    let token = get_associated_token_address(&seller.pubkey(), test_metadata_mint);
+    let (metadata, _) = find_metadata_account(test_metadata_mint);
+    let (seller_trade_state, sts_bump) = find_trade_state_address(
+        &seller.pubkey(),
+        ahkey,
+        &token,
+        &ah.treasury_mint,
+        test_metadata_mint,
+        sale_price,
+        1,
+    );
+    let (free_seller_trade_state, free_sts_bump) = find_trade_state_address(
+        &seller.pubkey(),
+        ahkey,
+        &token,
+        &ah.treasury_mint,
+        test_metadata_mint,
+        0,
+        1,
+    );
+    let (pas, pas_bump) = find_program_as_signer_address();
+    let (listing_receipt, receipt_bump) = find_listing_receipt_address(&seller_trade_state);
+
+    let accounts = mpl_auction_house::accounts::Sell {
+        wallet: seller.pubkey(),
+        token_account: token,
+        metadata,
+        authority: ah.authority,
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        seller_trade_state,
+        free_seller_trade_state,
+        token_program: spl_token::id(),
+        system_program: solana_program::system_program::id(),
+        program_as_signer: pas,
+        rent: sysvar::rent::id(),
+    };
+    let account_metas = accounts.to_account_metas(None);
+
+    let data = mpl_auction_house::instruction::Sell {
+        trade_state_bump: sts_bump,
+        free_trade_state_bump: free_sts_bump,
+        program_as_signer_bump: pas_bump,
+        token_size: 1,
+        buyer_price: sale_price,
+    }
+    .data();
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data,
+        accounts: account_metas,
+    };
+
+    let listing_receipt_accounts = mpl_auction_house::accounts::PrintListingReceipt {
+        receipt: listing_receipt,
+        bookkeeper: seller.pubkey(),
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+        instruction: sysvar::instructions::id(),
+    };
+
+    let print_receipt_instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::PrintListingReceipt { receipt_bump }.data(),
+        accounts: listing_receipt_accounts.to_account_metas(None),
+    };
+
+    (
+        (accounts, listing_receipt_accounts),
+        Transaction::new_signed_with_payer(
+            &[instruction, print_receipt_instruction],
+            Some(&seller.pubkey()),
+            &[seller],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub fn auctioneer_sell_mint(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata_mint: &Pubkey,
+    seller: &Keypair,
+    auctioneer_authority: &Keypair,
+) -> (mpl_auction_house::accounts::AuctioneerSell, Transaction) {
+    let token = get_associated_token_address(&seller.pubkey(), test_metadata_mint);
+    let (metadata, _) = find_metadata_account(test_metadata_mint);
+    let (seller_trade_state, sts_bump) = find_auctioneer_trade_state_address(
+        &seller.pubkey(),
+        ahkey,
+        &token,
         &ah.treasury_mint,
-        &test_metadata.mint.pubkey(),
-        sale_price,
+        test_metadata_mint,
+        1,
+    );
+    let (free_seller_trade_state, free_sts_bump) = find_trade_state_address(
+        &seller.pubkey(),
+        ahkey,
+        &token,
+        &ah.treasury_mint,
+        test_metadata_mint,
+        0,
         1,
     );
+
     let (auctioneer_pda, _) = find_auctioneer_pda(ahkey, &auctioneer_authority.pubkey());
-    let (escrow, escrow_bump) = find_escrow_payment_address(ahkey, &buyer.pubkey());
-    let (bts, bts_bump) = trade_state;
+    let (pas, pas_bump) = find_program_as_signer_address();
 
-    let accounts = mpl_auction_house::accounts::AuctioneerBuy {
-        wallet: buyer.pubkey(),
-        token_account: seller_token_account,
-        metadata: test_metadata.pubkey,
+    let accounts = mpl_auction_house::accounts::AuctioneerSell {
+        wallet: seller.pubkey(),
+        token_account: token,
+        metadata,
         authority: ah.authority,
         auctioneer_authority: auctioneer_authority.pubkey(),
         auction_house: *ahkey,
         auction_house_fee_account: ah.auction_house_fee_account,
-        buyer_trade_state: bts,
+        seller_trade_state,
+        free_seller_trade_state,
         ah_auctioneer_pda: auctioneer_pda,
         token_program: spl_token::id(),
-        treasury_mint: ah.treasury_mint,
-        payment_account: buyer.pubkey(),
-        transfer_authority: buyer.pubkey(),
         system_program: solana_program::system_program::id(),
+        program_as_signer: pas,
         rent: sysvar::rent::id(),
-        escrow_payment_account: escrow,
     };
-
     let account_metas = accounts.to_account_metas(None);
 
-    let buy_ix = mpl_auction_house::instruction::AuctioneerBuy {
-        trade_state_bump: bts_bump,
-        escrow_payment_bump: escrow_bump,
+    let data = mpl_auction_house::instruction::AuctioneerSell {
+        trade_state_bump: sts_bump,
+        free_trade_state_bump: free_sts_bump,
+        program_as_signer_bump: pas_bump,
         token_size: 1,
-        buyer_price: sale_price,
-    };
-    let data = buy_ix.data();
+    }
+    .data();
 
     let instruction = Instruction {
         program_id: mpl_auction_house::id(),
@@ -375,421 +1911,501 @@ pub fn auctioneer_buy(
         accounts,
         Transaction::new_signed_with_payer(
             &[instruction],
-            Some(&buyer.pubkey()),
-            &[buyer, auctioneer_authority],
+            Some(&seller.pubkey()),
+            &[seller, auctioneer_authority],
             context.last_blockhash,
         ),
     )
 }
 
-pub fn public_buy(
+pub fn sell(
     context: &mut ProgramTestContext,
     ahkey: &Pubkey,
     ah: &AuctionHouse,
     test_metadata: &Metadata,
-    owner: &Pubkey,
-    buyer: &Keypair,
     sale_price: u64,
+    token_size: u64,
 ) -> (
     (
-        mpl_auction_house::accounts::PublicBuy,
-        mpl_auction_house::accounts::PrintBidReceipt,
+        mpl_auction_house::accounts::Sell,
+        mpl_auction_house::accounts::PrintListingReceipt,
     ),
     Transaction,
 ) {
-    let seller_token_account = get_associated_token_address(owner, &test_metadata.mint.pubkey());
-    let trade_state = find_public_bid_trade_state_address(
-        &buyer.pubkey(),
+    let program_id = mpl_auction_house::id();
+    let token =
+        get_associated_token_address(&test_metadata.token.pubkey(), &test_metadata.mint.pubkey());
+    let (seller_trade_state, sts_bump) = find_trade_state_address(
+        &test_metadata.token.pubkey(),
         ahkey,
+        &token,
         &ah.treasury_mint,
         &test_metadata.mint.pubkey(),
         sale_price,
-        1,
+        token_size,
     );
-    let (escrow, escrow_bump) = find_escrow_payment_address(ahkey, &buyer.pubkey());
-    let (bts, bts_bump) = trade_state;
+    let (listing_receipt, receipt_bump) = find_listing_receipt_address(&seller_trade_state);
 
-    let accounts = mpl_auction_house::accounts::PublicBuy {
-        wallet: buyer.pubkey(),
-        token_account: seller_token_account,
+    let (free_seller_trade_state, free_sts_bump) = find_trade_state_address(
+        &test_metadata.token.pubkey(),
+        ahkey,
+        &token,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        0,
+        token_size,
+    );
+    let (pas, pas_bump) = find_program_as_signer_address();
+
+    let accounts = mpl_auction_house::accounts::Sell {
+        wallet: test_metadata.token.pubkey(),
+        token_account: token,
         metadata: test_metadata.pubkey,
         authority: ah.authority,
         auction_house: *ahkey,
         auction_house_fee_account: ah.auction_house_fee_account,
-        buyer_trade_state: bts,
+        seller_trade_state,
+        free_seller_trade_state,
         token_program: spl_token::id(),
-        treasury_mint: ah.treasury_mint,
-        payment_account: buyer.pubkey(),
-        transfer_authority: buyer.pubkey(),
         system_program: solana_program::system_program::id(),
+        program_as_signer: pas,
         rent: sysvar::rent::id(),
-        escrow_payment_account: escrow,
     };
     let account_metas = accounts.to_account_metas(None);
 
-    let buy_ix = mpl_auction_house::instruction::PublicBuy {
-        trade_state_bump: bts_bump,
-        escrow_payment_bump: escrow_bump,
-        token_size: 1,
+    let data = mpl_auction_house::instruction::Sell {
+        trade_state_bump: sts_bump,
+        free_trade_state_bump: free_sts_bump,
+        program_as_signer_bump: pas_bump,
+        token_size,
         buyer_price: sale_price,
-    };
-    let data = buy_ix.data();
+    }
+    .data();
 
     let instruction = Instruction {
-        program_id: mpl_auction_house::id(),
+        program_id,
         data,
         accounts: account_metas,
     };
 
-    let (bid_receipt, bid_receipt_bump) = find_bid_receipt_address(&bts);
-    let print_receipt_accounts = mpl_auction_house::accounts::PrintBidReceipt {
-        receipt: bid_receipt,
-        bookkeeper: buyer.pubkey(),
-        system_program: solana_program::system_program::id(),
+    let listing_receipt_accounts = mpl_auction_house::accounts::PrintListingReceipt {
+        receipt: listing_receipt,
+        bookkeeper: test_metadata.token.pubkey(),
+        system_program: system_program::id(),
         rent: sysvar::rent::id(),
         instruction: sysvar::instructions::id(),
     };
 
-    let account_metas = print_receipt_accounts.to_account_metas(None);
-
-    let print_bid_receipt_ix = mpl_auction_house::instruction::PrintBidReceipt {
-        receipt_bump: bid_receipt_bump,
-    };
-    let data = print_bid_receipt_ix.data();
+    let mut print_receipt_account_metas = listing_receipt_accounts.to_account_metas(None);
+    print_receipt_account_metas.push(AccountMeta::new_readonly(test_metadata.pubkey, false));
 
-    let print_bid_receipt_instruction = Instruction {
-        program_id: mpl_auction_house::id(),
-        data,
-        accounts: account_metas,
+    let print_receipt_instruction = Instruction {
+        program_id,
+        data: mpl_auction_house::instruction::PrintListingReceipt { receipt_bump }.data(),
+        accounts: print_receipt_account_metas,
     };
 
     (
-        (accounts, print_receipt_accounts),
+        (accounts, listing_receipt_accounts),
         Transaction::new_signed_with_payer(
-            &[instruction, print_bid_receipt_instruction],
-            Some(&buyer.pubkey()),
-            &[buyer],
+            &[instruction, print_receipt_instruction],
+            Some(&test_metadata.token.pubkey()),
+            &[&test_metadata.token],
             context.last_blockhash,
         ),
     )
 }
 
-pub fn auctioneer_public_buy(
+pub fn initiate_listing(
     context: &mut ProgramTestContext,
     ahkey: &Pubkey,
-    ah: &AuctionHouse,
     test_metadata: &Metadata,
-    owner: &Pubkey,
-    buyer: &Keypair,
-    auctioneer_authority: &Keypair,
     sale_price: u64,
-) -> (
-    mpl_auction_house::accounts::AuctioneerPublicBuy,
-    Transaction,
-) {
-    let seller_token_account = get_associated_token_address(owner, &test_metadata.mint.pubkey());
-    let trade_state = find_public_bid_trade_state_address(
-        &buyer.pubkey(),
-        ahkey,
-        &ah.treasury_mint,
-        &test_metadata.mint.pubkey(),
-        sale_price,
-        1,
-    );
-
-    let (auctioneer_pda, _) = find_auctioneer_pda(ahkey, &auctioneer_authority.pubkey());
-    let (escrow, escrow_bump) = find_escrow_payment_address(ahkey, &buyer.pubkey());
-    let (bts, bts_bump) = trade_state;
-
-    let accounts = mpl_auction_house::accounts::AuctioneerPublicBuy {
-        wallet: buyer.pubkey(),
-        token_account: seller_token_account,
-        metadata: test_metadata.pubkey,
-        authority: ah.authority,
-        auctioneer_authority: auctioneer_authority.pubkey(),
-        auction_house: *ahkey,
-        auction_house_fee_account: ah.auction_house_fee_account,
-        buyer_trade_state: bts,
-        token_program: spl_token::id(),
-        treasury_mint: ah.treasury_mint,
-        payment_account: buyer.pubkey(),
-        transfer_authority: buyer.pubkey(),
-        ah_auctioneer_pda: auctioneer_pda,
-        system_program: solana_program::system_program::id(),
-        rent: sysvar::rent::id(),
-        escrow_payment_account: escrow,
-    };
-    let account_metas = accounts.to_account_metas(None);
-
-    let buy_ix = mpl_auction_house::instruction::AuctioneerPublicBuy {
-        trade_state_bump: bts_bump,
-        escrow_payment_bump: escrow_bump,
-        token_size: 1,
-        buyer_price: sale_price,
+    token_size: u64,
+) -> (mpl_auction_house::accounts::InitiateListing, Transaction) {
+    let program_id = mpl_auction_house::id();
+    let token =
+        get_associated_token_address(&test_metadata.token.pubkey(), &test_metadata.mint.pubkey());
+    let (pending_listing, _) =
+        find_pending_listing_address(&test_metadata.token.pubkey(), ahkey, &token);
+
+    let accounts = mpl_auction_house::accounts::InitiateListing {
+        wallet: test_metadata.token.pubkey(),
+        token_account: token,
+        auction_house: *ahkey,
+        pending_listing,
+        system_program: system_program::id(),
     };
-    let data = buy_ix.data();
 
     let instruction = Instruction {
-        program_id: mpl_auction_house::id(),
-        data,
-        accounts: account_metas,
+        program_id,
+        data: mpl_auction_house::instruction::InitiateListing {
+            buyer_price: sale_price,
+            token_size,
+        }
+        .data(),
+        accounts: accounts.to_account_metas(None),
     };
 
     (
         accounts,
         Transaction::new_signed_with_payer(
             &[instruction],
-            Some(&buyer.pubkey()),
-            &[buyer, auctioneer_authority],
+            Some(&test_metadata.token.pubkey()),
+            &[&test_metadata.token],
             context.last_blockhash,
         ),
     )
 }
 
-pub fn execute_sale(
+pub fn finalize_listing(
     context: &mut ProgramTestContext,
     ahkey: &Pubkey,
     ah: &AuctionHouse,
-    authority: &Keypair,
     test_metadata: &Metadata,
-    buyer: &Pubkey,
-    seller: &Pubkey,
-    token_account: &Pubkey,
-    seller_trade_state: &Pubkey,
-    buyer_trade_state: &Pubkey,
+    sale_price: u64,
     token_size: u64,
-    buyer_price: u64,
-    partial_order_size: Option<u64>,
-    partial_order_price: Option<u64>,
-) -> (
-    (
-        mpl_auction_house::accounts::ExecuteSale,
-        mpl_auction_house::accounts::PrintPurchaseReceipt,
-    ),
-    Transaction,
-) {
+) -> (mpl_auction_house::accounts::FinalizeListing, Transaction) {
     let program_id = mpl_auction_house::id();
-    let buyer_token_account = get_associated_token_address(buyer, &test_metadata.mint.pubkey());
-
-    let (program_as_signer, pas_bump) = find_program_as_signer_address();
-
-    let (free_trade_state, free_sts_bump) = find_trade_state_address(
-        seller,
+    let token =
+        get_associated_token_address(&test_metadata.token.pubkey(), &test_metadata.mint.pubkey());
+    let (pending_listing, _) =
+        find_pending_listing_address(&test_metadata.token.pubkey(), ahkey, &token);
+    let (seller_trade_state, sts_bump) = find_trade_state_address(
+        &test_metadata.token.pubkey(),
         ahkey,
-        token_account,
+        &token,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        sale_price,
+        token_size,
+    );
+    let (free_seller_trade_state, free_sts_bump) = find_trade_state_address(
+        &test_metadata.token.pubkey(),
+        ahkey,
+        &token,
         &ah.treasury_mint,
         &test_metadata.mint.pubkey(),
         0,
         token_size,
     );
-    let (escrow_payment_account, escrow_bump) = find_escrow_payment_address(ahkey, buyer);
-    let (purchase_receipt, purchase_receipt_bump) =
-        find_purchase_receipt_address(seller_trade_state, buyer_trade_state);
-    let (listing_receipt, _listing_receipt_bump) = find_listing_receipt_address(seller_trade_state);
-    let (bid_receipt, _public_bid_receipt_bump) = find_bid_receipt_address(buyer_trade_state);
-    let execute_sale_accounts = mpl_auction_house::accounts::ExecuteSale {
-        buyer: *buyer,
-        seller: *seller,
-        auction_house: *ahkey,
-        token_account: *token_account,
-        token_mint: test_metadata.mint.pubkey(),
-        treasury_mint: ah.treasury_mint,
+    let (pas, pas_bump) = find_program_as_signer_address();
+
+    let accounts = mpl_auction_house::accounts::FinalizeListing {
+        wallet: test_metadata.token.pubkey(),
+        token_account: token,
         metadata: test_metadata.pubkey,
         authority: ah.authority,
-        seller_trade_state: *seller_trade_state,
-        buyer_trade_state: *buyer_trade_state,
-        free_trade_state,
-        seller_payment_receipt_account: *seller,
-        buyer_receipt_token_account: buyer_token_account,
-        escrow_payment_account,
+        auction_house: *ahkey,
         auction_house_fee_account: ah.auction_house_fee_account,
-        auction_house_treasury: ah.auction_house_treasury,
-        program_as_signer,
+        pending_listing,
+        seller_trade_state,
+        free_seller_trade_state,
         token_program: spl_token::id(),
         system_program: system_program::id(),
-        ata_program: spl_associated_token_account::id(),
+        program_as_signer: pas,
         rent: sysvar::rent::id(),
     };
-
-    let execute_sale_account_metas = execute_sale_accounts.to_account_metas(None);
-
-    let execute_sale_instruction = Instruction {
+    let instruction = Instruction {
         program_id,
-        data: mpl_auction_house::instruction::ExecuteSale {
-            escrow_payment_bump: escrow_bump,
-            _free_trade_state_bump: free_sts_bump,
+        data: mpl_auction_house::instruction::FinalizeListing {
+            trade_state_bump: sts_bump,
+            free_trade_state_bump: free_sts_bump,
             program_as_signer_bump: pas_bump,
-            token_size,
-            buyer_price,
-            partial_order_size,
-            partial_order_price,
         }
         .data(),
-        accounts: execute_sale_account_metas,
+        accounts: accounts.to_account_metas(None),
     };
 
-    let print_purchase_receipt_accounts = mpl_auction_house::accounts::PrintPurchaseReceipt {
-        purchase_receipt,
-        listing_receipt,
-        bid_receipt,
-        bookkeeper: authority.pubkey(),
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&test_metadata.token.pubkey()),
+            &[&test_metadata.token],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub fn signed_listing_ed25519_instruction(seller: &Keypair, message: &[u8]) -> Instruction {
+    let signature = seller.sign_message(message);
+
+    // num_signatures, padding, then offsets of the public key, signature and message stored after them.
+    let public_key_offset: u16 = 16;
+    let signature_offset = public_key_offset + 32;
+    let message_offset = signature_offset + 64;
+    let mut data = vec![1u8, 0];
+    for value in [
+        signature_offset,
+        u16::MAX,
+        public_key_offset,
+        u16::MAX,
+        message_offset,
+        message.len() as u16,
+        u16::MAX,
+    ] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    data.extend_from_slice(seller.pubkey().as_ref());
+    data.extend_from_slice(signature.as_ref());
+    data.extend_from_slice(message);
+
+    Instruction {
+        program_id: solana_sdk::ed25519_program::id(),
+        accounts: vec![],
+        data,
+    }
+}
+
+pub fn post_signed_listing(
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    payer: &Pubkey,
+    sale_price: u64,
+    token_size: u64,
+    expiry: i64,
+    nonce: u64,
+) -> (mpl_auction_house::accounts::PostSignedListing, Instruction) {
+    let program_id = mpl_auction_house::id();
+    let token =
+        get_associated_token_address(&test_metadata.token.pubkey(), &test_metadata.mint.pubkey());
+    let (seller_trade_state, sts_bump) = find_trade_state_address(
+        &test_metadata.token.pubkey(),
+        ahkey,
+        &token,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        sale_price,
+        token_size,
+    );
+    let (pas, pas_bump) = find_program_as_signer_address();
+
+    let accounts = mpl_auction_house::accounts::PostSignedListing {
+        wallet: test_metadata.token.pubkey(),
+        token_account: token,
+        metadata: test_metadata.pubkey,
+        auction_house: *ahkey,
+        seller_trade_state,
+        program_as_signer: pas,
+        payer: *payer,
+        signed_listing_nonce: find_signed_listing_nonce_address(
+            ahkey,
+            &test_metadata.token.pubkey(),
+            nonce,
+        )
+        .0,
         system_program: system_program::id(),
         rent: sysvar::rent::id(),
         instruction: sysvar::instructions::id(),
     };
 
-    let print_purchase_receipt_instruction = Instruction {
+    let instruction = Instruction {
         program_id,
-        data: mpl_auction_house::instruction::PrintPurchaseReceipt {
-            purchase_receipt_bump,
+        data: mpl_auction_house::instruction::PostSignedListing {
+            trade_state_bump: sts_bump,
+            program_as_signer_bump: pas_bump,
+            buyer_price: sale_price,
+            token_size,
+            expiry,
+            nonce,
         }
         .data(),
-        accounts: print_purchase_receipt_accounts.to_account_metas(None),
+        accounts: accounts.to_account_metas(None),
     };
 
-    let tx = Transaction::new_signed_with_payer(
-        &[execute_sale_instruction, print_purchase_receipt_instruction],
-        Some(&authority.pubkey()),
-        &[authority],
-        context.last_blockhash,
-    );
-
-    ((execute_sale_accounts, print_purchase_receipt_accounts), tx)
+    (accounts, instruction)
 }
 
-pub fn auctioneer_execute_sale(
+pub fn custodial_sell(
     context: &mut ProgramTestContext,
     ahkey: &Pubkey,
     ah: &AuctionHouse,
-    authority: &Keypair,
-    auctioneer_authority: &Keypair,
     test_metadata: &Metadata,
-    buyer: &Pubkey,
-    seller: &Pubkey,
-    token_account: &Pubkey,
-    seller_trade_state: &Pubkey,
-    buyer_trade_state: &Pubkey,
+    sale_price: u64,
     token_size: u64,
-    buyer_price: u64,
-) -> (
-    mpl_auction_house::accounts::AuctioneerExecuteSale,
-    Transaction,
-) {
+    user_identity_hash: [u8; 32],
+) -> (mpl_auction_house::accounts::CustodialSell, Transaction) {
     let program_id = mpl_auction_house::id();
-    let buyer_token_account = get_associated_token_address(buyer, &test_metadata.mint.pubkey());
-
-    let (program_as_signer, pas_bump) = find_program_as_signer_address();
-
-    let (free_trade_state, free_sts_bump) = find_trade_state_address(
-        seller,
+    let token =
+        get_associated_token_address(&test_metadata.token.pubkey(), &test_metadata.mint.pubkey());
+    let (seller_trade_state, _) = find_trade_state_address(
+        &test_metadata.token.pubkey(),
         ahkey,
-        token_account,
+        &token,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        sale_price,
+        token_size,
+    );
+    let (free_seller_trade_state, _) = find_trade_state_address(
+        &test_metadata.token.pubkey(),
+        ahkey,
+        &token,
         &ah.treasury_mint,
         &test_metadata.mint.pubkey(),
         0,
         token_size,
     );
+    let (pas, _) = find_program_as_signer_address();
 
-    let (auctioneer_pda, _) = find_auctioneer_pda(ahkey, &auctioneer_authority.pubkey());
-    let (escrow_payment_account, escrow_bump) = find_escrow_payment_address(ahkey, buyer);
-
-    let execute_sale_accounts = mpl_auction_house::accounts::AuctioneerExecuteSale {
-        buyer: *buyer,
-        seller: *seller,
-        auction_house: *ahkey,
-        token_account: *token_account,
-        token_mint: test_metadata.mint.pubkey(),
-        treasury_mint: ah.treasury_mint,
+    let accounts = mpl_auction_house::accounts::CustodialSell {
+        wallet: test_metadata.token.pubkey(),
+        token_account: token,
         metadata: test_metadata.pubkey,
         authority: ah.authority,
-        auctioneer_authority: auctioneer_authority.pubkey(),
-        seller_trade_state: *seller_trade_state,
-        buyer_trade_state: *buyer_trade_state,
-        free_trade_state,
-        seller_payment_receipt_account: *seller,
-        buyer_receipt_token_account: buyer_token_account,
-        escrow_payment_account,
+        auction_house: *ahkey,
         auction_house_fee_account: ah.auction_house_fee_account,
-        auction_house_treasury: ah.auction_house_treasury,
-        program_as_signer,
-        ah_auctioneer_pda: auctioneer_pda,
+        seller_trade_state,
+        free_seller_trade_state,
         token_program: spl_token::id(),
+        system_program: solana_program::system_program::id(),
+        program_as_signer: pas,
+        rent: sysvar::rent::id(),
+    };
+
+    let instruction = Instruction {
+        program_id,
+        data: mpl_auction_house::instruction::CustodialSell {
+            buyer_price: sale_price,
+            token_size,
+            user_identity_hash,
+        }
+        .data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&test_metadata.token.pubkey()),
+            &[&test_metadata.token],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub fn migrate_listing(
+    context: &mut ProgramTestContext,
+    source_ahkey: &Pubkey,
+    source_ah: &AuctionHouse,
+    destination_ahkey: &Pubkey,
+    destination_ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    sale_price: u64,
+    token_size: u64,
+    source_listing_receipt: Option<Pubkey>,
+    remaining_accounts: &[AccountMeta],
+) -> (mpl_auction_house::accounts::MigrateListing, Transaction) {
+    let token =
+        get_associated_token_address(&test_metadata.token.pubkey(), &test_metadata.mint.pubkey());
+    let (source_trade_state, _) = find_trade_state_address(
+        &test_metadata.token.pubkey(),
+        source_ahkey,
+        &token,
+        &source_ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        sale_price,
+        token_size,
+    );
+    let (destination_trade_state, trade_state_bump) = find_trade_state_address(
+        &test_metadata.token.pubkey(),
+        destination_ahkey,
+        &token,
+        &destination_ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        sale_price,
+        token_size,
+    );
+    let (listing_receipt, receipt_bump) = find_listing_receipt_address(&destination_trade_state);
+
+    let accounts = mpl_auction_house::accounts::MigrateListing {
+        wallet: test_metadata.token.pubkey(),
+        token_account: token,
+        metadata: test_metadata.pubkey,
+        source_auction_house: *source_ahkey,
+        source_auction_house_fee_account: source_ah.auction_house_fee_account,
+        source_trade_state,
+        destination_auction_house: *destination_ahkey,
+        destination_trade_state,
+        listing_receipt,
         system_program: system_program::id(),
-        ata_program: spl_associated_token_account::id(),
         rent: sysvar::rent::id(),
     };
+    let mut account_metas = accounts.to_account_metas(None);
+    if let Some(source_listing_receipt) = source_listing_receipt {
+        account_metas.push(AccountMeta::new(source_listing_receipt, false));
+    }
+    account_metas.extend_from_slice(remaining_accounts);
 
-    let execute_sale_account_metas = execute_sale_accounts.to_account_metas(None);
-
-    let execute_sale_instruction = Instruction {
-        program_id,
-        data: mpl_auction_house::instruction::AuctioneerExecuteSale {
-            escrow_payment_bump: escrow_bump,
-            _free_trade_state_bump: free_sts_bump,
-            program_as_signer_bump: pas_bump,
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::MigrateListing {
+            trade_state_bump,
+            receipt_bump,
+            buyer_price: sale_price,
             token_size,
-            buyer_price,
         }
         .data(),
-        accounts: execute_sale_account_metas,
+        accounts: account_metas,
     };
 
-    let tx = Transaction::new_signed_with_payer(
-        &[execute_sale_instruction],
-        Some(&authority.pubkey()),
-        &[authority, auctioneer_authority],
-        context.last_blockhash,
-    );
-
-    (execute_sale_accounts, tx)
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&test_metadata.token.pubkey()),
+            &[&test_metadata.token],
+            context.last_blockhash,
+        ),
+    )
 }
 
-pub fn sell_mint(
+pub fn auctioneer_sell(
     context: &mut ProgramTestContext,
     ahkey: &Pubkey,
     ah: &AuctionHouse,
-    test_metadata_mint: &Pubkey,
-    seller: &Keypair,
-    sale_price: u64,
-) -> (
-    (
-        mpl_auction_house::accounts::Sell,
-        mpl_auction_house::accounts::PrintListingReceipt,
-    ),
-    Transaction,
-) {
-    let token = get_associated_token_address(&seller.pubkey(), test_metadata_mint);
-    let (metadata, _) = find_metadata_account(test_metadata_mint);
-    let (seller_trade_state, sts_bump) = find_trade_state_address(
-        &seller.pubkey(),
+    test_metadata: &Metadata,
+    auctioneer_authority: &Keypair,
+) -> (mpl_auction_house::accounts::AuctioneerSell, Transaction) {
+    let program_id = mpl_auction_house::id();
+    let token =
+        get_associated_token_address(&test_metadata.token.pubkey(), &test_metadata.mint.pubkey());
+    let (seller_trade_state, sts_bump) = find_auctioneer_trade_state_address(
+        &test_metadata.token.pubkey(),
         ahkey,
         &token,
         &ah.treasury_mint,
-        test_metadata_mint,
-        sale_price,
+        &test_metadata.mint.pubkey(),
         1,
     );
+
     let (free_seller_trade_state, free_sts_bump) = find_trade_state_address(
-        &seller.pubkey(),
+        &test_metadata.token.pubkey(),
         ahkey,
         &token,
         &ah.treasury_mint,
-        test_metadata_mint,
+        &test_metadata.mint.pubkey(),
         0,
         1,
     );
     let (pas, pas_bump) = find_program_as_signer_address();
-    let (listing_receipt, receipt_bump) = find_listing_receipt_address(&seller_trade_state);
 
-    let accounts = mpl_auction_house::accounts::Sell {
-        wallet: seller.pubkey(),
+    let (auctioneer_pda, _) = find_auctioneer_pda(ahkey, &auctioneer_authority.pubkey());
+
+    let accounts = mpl_auction_house::accounts::AuctioneerSell {
+        wallet: test_metadata.token.pubkey(),
         token_account: token,
-        metadata,
+        metadata: test_metadata.pubkey,
         authority: ah.authority,
+        auctioneer_authority: auctioneer_authority.pubkey(),
         auction_house: *ahkey,
         auction_house_fee_account: ah.auction_house_fee_account,
         seller_trade_state,
         free_seller_trade_state,
+        ah_auctioneer_pda: auctioneer_pda,
         token_program: spl_token::id(),
         system_program: solana_program::system_program::id(),
         program_as_signer: pas,
@@ -797,275 +2413,361 @@ pub fn sell_mint(
     };
     let account_metas = accounts.to_account_metas(None);
 
-    let data = mpl_auction_house::instruction::Sell {
+    let data = mpl_auction_house::instruction::AuctioneerSell {
         trade_state_bump: sts_bump,
         free_trade_state_bump: free_sts_bump,
         program_as_signer_bump: pas_bump,
         token_size: 1,
-        buyer_price: sale_price,
     }
     .data();
 
     let instruction = Instruction {
-        program_id: mpl_auction_house::id(),
+        program_id,
         data,
         accounts: account_metas,
     };
 
-    let listing_receipt_accounts = mpl_auction_house::accounts::PrintListingReceipt {
-        receipt: listing_receipt,
-        bookkeeper: seller.pubkey(),
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&test_metadata.token.pubkey()),
+            &[&test_metadata.token, auctioneer_authority],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub async fn delegate_auctioneer(
+    context: &mut ProgramTestContext,
+    auction_house: Pubkey,
+    authority: &Keypair,
+    auctioneer_authority: Pubkey,
+    ah_auctioneer_pda: Pubkey,
+    scopes: Vec<AuthorityScope>,
+) -> StdResult<(), TransportError> {
+    let accounts = mpl_auction_house::accounts::DelegateAuctioneer {
+        auction_house,
+        authority: authority.pubkey(),
+        auctioneer_authority,
+        ah_auctioneer_pda,
         system_program: system_program::id(),
-        rent: sysvar::rent::id(),
-        instruction: sysvar::instructions::id(),
+    }
+    .to_account_metas(None);
+
+    let data = mpl_auction_house::instruction::DelegateAuctioneer { scopes }.data();
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data,
+        accounts,
     };
 
-    let print_receipt_instruction = Instruction {
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&authority.pubkey()),
+        &[authority],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await
+}
+
+pub async fn approve_session_key(
+    context: &mut ProgramTestContext,
+    auction_house: Pubkey,
+    wallet: &Keypair,
+    session_key: Pubkey,
+    max_spend: u64,
+    expiry: i64,
+) -> StdResult<Pubkey, TransportError> {
+    let (session_key_account, _) =
+        find_session_key_address(&auction_house, &wallet.pubkey(), &session_key);
+    let accounts = mpl_auction_house::accounts::ApproveSessionKey {
+        wallet: wallet.pubkey(),
+        session_key,
+        auction_house,
+        session_key_account,
+        system_program: system_program::id(),
+    }
+    .to_account_metas(None);
+
+    let data = mpl_auction_house::instruction::ApproveSessionKey { max_spend, expiry }.data();
+
+    let instruction = Instruction {
         program_id: mpl_auction_house::id(),
-        data: mpl_auction_house::instruction::PrintListingReceipt { receipt_bump }.data(),
-        accounts: listing_receipt_accounts.to_account_metas(None),
+        data,
+        accounts,
     };
 
-    (
-        (accounts, listing_receipt_accounts),
-        Transaction::new_signed_with_payer(
-            &[instruction, print_receipt_instruction],
-            Some(&seller.pubkey()),
-            &[seller],
-            context.last_blockhash,
-        ),
-    )
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&wallet.pubkey()),
+        &[wallet],
+        context.last_blockhash,
+    );
+
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map(|_| session_key_account)
 }
 
-pub fn auctioneer_sell_mint(
+pub async fn create_revenue_share(
     context: &mut ProgramTestContext,
-    ahkey: &Pubkey,
+    auction_house: Pubkey,
     ah: &AuctionHouse,
-    test_metadata_mint: &Pubkey,
-    seller: &Keypair,
-    auctioneer_authority: &Keypair,
-) -> (mpl_auction_house::accounts::AuctioneerSell, Transaction) {
-    let token = get_associated_token_address(&seller.pubkey(), test_metadata_mint);
-    let (metadata, _) = find_metadata_account(test_metadata_mint);
-    let (seller_trade_state, sts_bump) = find_auctioneer_trade_state_address(
-        &seller.pubkey(),
-        ahkey,
-        &token,
-        &ah.treasury_mint,
-        test_metadata_mint,
-        1,
+    authority: &Keypair,
+    governance_mint: Pubkey,
+    basis_points: u16,
+) -> StdResult<Pubkey, TransportError> {
+    let (revenue_share, _) = find_revenue_share_address(&auction_house);
+    let accounts = mpl_auction_house::accounts::CreateRevenueShare {
+        treasury_mint: ah.treasury_mint,
+        authority: authority.pubkey(),
+        auction_house,
+        governance_mint,
+        revenue_share,
+        revenue_share_token_account: get_associated_token_address(
+            &revenue_share,
+            &ah.treasury_mint,
+        ),
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        ata_program: spl_associated_token_account::id(),
+        rent: sysvar::rent::id(),
+    }
+    .to_account_metas(None);
+
+    let data = mpl_auction_house::instruction::CreateRevenueShare { basis_points }.data();
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data,
+        accounts,
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&authority.pubkey()),
+        &[authority],
+        context.last_blockhash,
     );
-    let (free_seller_trade_state, free_sts_bump) = find_trade_state_address(
-        &seller.pubkey(),
-        ahkey,
-        &token,
-        &ah.treasury_mint,
-        test_metadata_mint,
-        0,
-        1,
+
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map(|_| revenue_share)
+}
+
+pub async fn snapshot_revenue_share(
+    context: &mut ProgramTestContext,
+    auction_house: Pubkey,
+    authority: &Keypair,
+    snapshot_index: u64,
+    merkle_root: [u8; 32],
+    supply: u64,
+    slot: u64,
+) -> StdResult<Pubkey, TransportError> {
+    let (revenue_share, _) = find_revenue_share_address(&auction_house);
+    let (snapshot, _) = find_revenue_share_snapshot_address(&auction_house, snapshot_index);
+    let accounts = mpl_auction_house::accounts::SnapshotRevenueShare {
+        authority: authority.pubkey(),
+        auction_house,
+        revenue_share,
+        snapshot,
+        system_program: system_program::id(),
+    }
+    .to_account_metas(None);
+
+    let data = mpl_auction_house::instruction::SnapshotRevenueShare {
+        merkle_root,
+        supply,
+        slot,
+    }
+    .data();
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data,
+        accounts,
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&authority.pubkey()),
+        &[authority],
+        context.last_blockhash,
     );
 
-    let (auctioneer_pda, _) = find_auctioneer_pda(ahkey, &auctioneer_authority.pubkey());
-    let (pas, pas_bump) = find_program_as_signer_address();
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map(|_| snapshot)
+}
 
-    let accounts = mpl_auction_house::accounts::AuctioneerSell {
-        wallet: seller.pubkey(),
-        token_account: token,
-        metadata,
-        authority: ah.authority,
-        auctioneer_authority: auctioneer_authority.pubkey(),
-        auction_house: *ahkey,
-        auction_house_fee_account: ah.auction_house_fee_account,
-        seller_trade_state,
-        free_seller_trade_state,
-        ah_auctioneer_pda: auctioneer_pda,
+pub fn claim_revenue_share(
+    context: &mut ProgramTestContext,
+    auction_house: Pubkey,
+    ah: &AuctionHouse,
+    claimant: &Keypair,
+    governance_token_account: Pubkey,
+    snapshot_index: u64,
+    index: u64,
+    balance: u64,
+    proof: Vec<[u8; 32]>,
+) -> Transaction {
+    let (revenue_share, _) = find_revenue_share_address(&auction_house);
+    let (snapshot, _) = find_revenue_share_snapshot_address(&auction_house, snapshot_index);
+    let accounts = mpl_auction_house::accounts::ClaimRevenueShare {
+        claimant: claimant.pubkey(),
+        governance_token_account,
+        treasury_mint: ah.treasury_mint,
+        auction_house,
+        revenue_share,
+        snapshot,
+        revenue_share_token_account: get_associated_token_address(
+            &revenue_share,
+            &ah.treasury_mint,
+        ),
+        destination: claimant.pubkey(),
         token_program: spl_token::id(),
-        system_program: solana_program::system_program::id(),
-        program_as_signer: pas,
-        rent: sysvar::rent::id(),
-    };
-    let account_metas = accounts.to_account_metas(None);
+        system_program: system_program::id(),
+    }
+    .to_account_metas(None);
 
-    let data = mpl_auction_house::instruction::AuctioneerSell {
-        trade_state_bump: sts_bump,
-        free_trade_state_bump: free_sts_bump,
-        program_as_signer_bump: pas_bump,
-        token_size: 1,
+    let data = mpl_auction_house::instruction::ClaimRevenueShare {
+        snapshot_index,
+        index,
+        balance,
+        proof,
     }
     .data();
 
     let instruction = Instruction {
         program_id: mpl_auction_house::id(),
         data,
-        accounts: account_metas,
+        accounts,
     };
 
-    (
-        accounts,
-        Transaction::new_signed_with_payer(
-            &[instruction],
-            Some(&seller.pubkey()),
-            &[seller, auctioneer_authority],
-            context.last_blockhash,
-        ),
+    Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&claimant.pubkey()),
+        &[claimant],
+        context.last_blockhash,
     )
 }
 
-pub fn sell(
+pub fn session_buy(
     context: &mut ProgramTestContext,
     ahkey: &Pubkey,
     ah: &AuctionHouse,
     test_metadata: &Metadata,
+    owner: &Pubkey,
+    wallet: &Pubkey,
+    session_key: &Keypair,
     sale_price: u64,
     token_size: u64,
-) -> (
-    (
-        mpl_auction_house::accounts::Sell,
-        mpl_auction_house::accounts::PrintListingReceipt,
-    ),
-    Transaction,
-) {
-    let program_id = mpl_auction_house::id();
-    let token =
-        get_associated_token_address(&test_metadata.token.pubkey(), &test_metadata.mint.pubkey());
-    let (seller_trade_state, sts_bump) = find_trade_state_address(
-        &test_metadata.token.pubkey(),
+) -> (mpl_auction_house::accounts::SessionBuy, Transaction) {
+    let seller_token_account = get_associated_token_address(owner, &test_metadata.mint.pubkey());
+    let (buyer_trade_state, trade_state_bump) = find_trade_state_address(
+        wallet,
         ahkey,
-        &token,
+        &seller_token_account,
         &ah.treasury_mint,
         &test_metadata.mint.pubkey(),
         sale_price,
         token_size,
     );
-    let (listing_receipt, receipt_bump) = find_listing_receipt_address(&seller_trade_state);
-
-    let (free_seller_trade_state, free_sts_bump) = find_trade_state_address(
-        &test_metadata.token.pubkey(),
-        ahkey,
-        &token,
-        &ah.treasury_mint,
-        &test_metadata.mint.pubkey(),
-        0,
-        token_size,
-    );
-    let (pas, pas_bump) = find_program_as_signer_address();
-
-    let accounts = mpl_auction_house::accounts::Sell {
-        wallet: test_metadata.token.pubkey(),
-        token_account: token,
+    let (escrow, escrow_bump) = find_escrow_payment_address(ahkey, wallet);
+    let (session_key_account, _) = find_session_key_address(ahkey, wallet, &session_key.pubkey());
+    let accounts = mpl_auction_house::accounts::SessionBuy {
+        session_key: session_key.pubkey(),
+        wallet: *wallet,
+        session_key_account,
+        treasury_mint: ah.treasury_mint,
+        token_account: seller_token_account,
         metadata: test_metadata.pubkey,
+        escrow_payment_account: escrow,
         authority: ah.authority,
         auction_house: *ahkey,
         auction_house_fee_account: ah.auction_house_fee_account,
-        seller_trade_state,
-        free_seller_trade_state,
-        token_program: spl_token::id(),
-        system_program: solana_program::system_program::id(),
-        program_as_signer: pas,
+        buyer_trade_state,
+        system_program: system_program::id(),
         rent: sysvar::rent::id(),
     };
     let account_metas = accounts.to_account_metas(None);
 
-    let data = mpl_auction_house::instruction::Sell {
-        trade_state_bump: sts_bump,
-        free_trade_state_bump: free_sts_bump,
-        program_as_signer_bump: pas_bump,
-        token_size,
+    let data = mpl_auction_house::instruction::SessionBuy {
+        trade_state_bump,
+        escrow_payment_bump: escrow_bump,
         buyer_price: sale_price,
+        token_size,
     }
     .data();
 
     let instruction = Instruction {
-        program_id,
+        program_id: mpl_auction_house::id(),
         data,
         accounts: account_metas,
     };
 
-    let listing_receipt_accounts = mpl_auction_house::accounts::PrintListingReceipt {
-        receipt: listing_receipt,
-        bookkeeper: test_metadata.token.pubkey(),
-        system_program: system_program::id(),
-        rent: sysvar::rent::id(),
-        instruction: sysvar::instructions::id(),
-    };
-
-    let print_receipt_instruction = Instruction {
-        program_id,
-        data: mpl_auction_house::instruction::PrintListingReceipt { receipt_bump }.data(),
-        accounts: listing_receipt_accounts.to_account_metas(None),
-    };
-
     (
-        (accounts, listing_receipt_accounts),
+        accounts,
         Transaction::new_signed_with_payer(
-            &[instruction, print_receipt_instruction],
-            Some(&test_metadata.token.pubkey()),
-            &[&test_metadata.token],
+            &[instruction],
+            Some(&session_key.pubkey()),
+            &[session_key],
             context.last_blockhash,
         ),
     )
 }
 
-pub fn auctioneer_sell(
+pub fn session_cancel(
     context: &mut ProgramTestContext,
     ahkey: &Pubkey,
     ah: &AuctionHouse,
     test_metadata: &Metadata,
-    auctioneer_authority: &Keypair,
-) -> (mpl_auction_house::accounts::AuctioneerSell, Transaction) {
-    let program_id = mpl_auction_house::id();
-    let token =
-        get_associated_token_address(&test_metadata.token.pubkey(), &test_metadata.mint.pubkey());
-    let (seller_trade_state, sts_bump) = find_auctioneer_trade_state_address(
-        &test_metadata.token.pubkey(),
-        ahkey,
-        &token,
-        &ah.treasury_mint,
-        &test_metadata.mint.pubkey(),
-        1,
-    );
-
-    let (free_seller_trade_state, free_sts_bump) = find_trade_state_address(
-        &test_metadata.token.pubkey(),
+    owner: &Pubkey,
+    wallet: &Pubkey,
+    session_key: &Keypair,
+    buyer_price: u64,
+    token_size: u64,
+) -> (mpl_auction_house::accounts::SessionCancel, Transaction) {
+    let token_account = get_associated_token_address(owner, &test_metadata.mint.pubkey());
+    let (trade_state, _) = find_trade_state_address(
+        wallet,
         ahkey,
-        &token,
+        &token_account,
         &ah.treasury_mint,
         &test_metadata.mint.pubkey(),
-        0,
-        1,
+        buyer_price,
+        token_size,
     );
-    let (pas, pas_bump) = find_program_as_signer_address();
-
-    let (auctioneer_pda, _) = find_auctioneer_pda(ahkey, &auctioneer_authority.pubkey());
-
-    let accounts = mpl_auction_house::accounts::AuctioneerSell {
-        wallet: test_metadata.token.pubkey(),
-        token_account: token,
-        metadata: test_metadata.pubkey,
-        authority: ah.authority,
-        auctioneer_authority: auctioneer_authority.pubkey(),
+    let (session_key_account, _) = find_session_key_address(ahkey, wallet, &session_key.pubkey());
+    let accounts = mpl_auction_house::accounts::SessionCancel {
+        session_key: session_key.pubkey(),
+        wallet: *wallet,
+        session_key_account,
+        token_account,
+        token_mint: test_metadata.mint.pubkey(),
         auction_house: *ahkey,
         auction_house_fee_account: ah.auction_house_fee_account,
-        seller_trade_state,
-        free_seller_trade_state,
-        ah_auctioneer_pda: auctioneer_pda,
-        token_program: spl_token::id(),
-        system_program: solana_program::system_program::id(),
-        program_as_signer: pas,
-        rent: sysvar::rent::id(),
+        trade_state,
     };
     let account_metas = accounts.to_account_metas(None);
 
-    let data = mpl_auction_house::instruction::AuctioneerSell {
-        trade_state_bump: sts_bump,
-        free_trade_state_bump: free_sts_bump,
-        program_as_signer_bump: pas_bump,
-        token_size: 1,
+    let data = mpl_auction_house::instruction::SessionCancel {
+        buyer_price,
+        token_size,
     }
     .data();
 
     let instruction = Instruction {
-        program_id,
+        program_id: mpl_auction_house::id(),
         data,
         accounts: account_metas,
     };
@@ -1074,48 +2776,13 @@ pub fn auctioneer_sell(
         accounts,
         Transaction::new_signed_with_payer(
             &[instruction],
-            Some(&test_metadata.token.pubkey()),
-            &[&test_metadata.token, auctioneer_authority],
+            Some(&session_key.pubkey()),
+            &[session_key],
             context.last_blockhash,
         ),
     )
 }
 
-pub async fn delegate_auctioneer(
-    context: &mut ProgramTestContext,
-    auction_house: Pubkey,
-    authority: &Keypair,
-    auctioneer_authority: Pubkey,
-    ah_auctioneer_pda: Pubkey,
-    scopes: Vec<AuthorityScope>,
-) -> StdResult<(), TransportError> {
-    let accounts = mpl_auction_house::accounts::DelegateAuctioneer {
-        auction_house,
-        authority: authority.pubkey(),
-        auctioneer_authority,
-        ah_auctioneer_pda,
-        system_program: system_program::id(),
-    }
-    .to_account_metas(None);
-
-    let data = mpl_auction_house::instruction::DelegateAuctioneer { scopes }.data();
-
-    let instruction = Instruction {
-        program_id: mpl_auction_house::id(),
-        data,
-        accounts,
-    };
-
-    let tx = Transaction::new_signed_with_payer(
-        &[instruction],
-        Some(&authority.pubkey()),
-        &[authority],
-        context.last_blockhash,
-    );
-
-    context.banks_client.process_transaction(tx).await
-}
-
 pub fn withdraw(
     context: &mut ProgramTestContext,
     buyer: &Keypair,
@@ -1238,6 +2905,83 @@ pub fn auctioneer_withdraw(
     (accounts, tx)
 }
 
+pub fn auctioneer_withdraw_from_treasury(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    auctioneer_authority: &Keypair,
+    amount: u64,
+) -> (
+    mpl_auction_house::accounts::AuctioneerWithdrawFromTreasury,
+    Transaction,
+) {
+    let (auctioneer_pda, _) = find_auctioneer_pda(ahkey, &auctioneer_authority.pubkey());
+
+    let accounts = mpl_auction_house::accounts::AuctioneerWithdrawFromTreasury {
+        treasury_mint: ah.treasury_mint,
+        auctioneer_authority: auctioneer_authority.pubkey(),
+        treasury_withdrawal_destination: ah.treasury_withdrawal_destination,
+        auction_house_treasury: ah.auction_house_treasury,
+        auction_house: *ahkey,
+        ah_auctioneer_pda: auctioneer_pda,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+    };
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::AuctioneerWithdrawFromTreasury { amount }.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&auctioneer_authority.pubkey()),
+        &[auctioneer_authority],
+        context.last_blockhash,
+    );
+
+    (accounts, tx)
+}
+
+pub fn auctioneer_update_auction_house(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    auctioneer_authority: &Keypair,
+    seller_fee_basis_points: Option<u16>,
+    can_change_sale_price: Option<bool>,
+    tick_size: Option<u64>,
+) -> (
+    mpl_auction_house::accounts::AuctioneerUpdateAuctionHouse,
+    Transaction,
+) {
+    let (auctioneer_pda, _) = find_auctioneer_pda(ahkey, &auctioneer_authority.pubkey());
+
+    let accounts = mpl_auction_house::accounts::AuctioneerUpdateAuctionHouse {
+        auctioneer_authority: auctioneer_authority.pubkey(),
+        auction_house: *ahkey,
+        ah_auctioneer_pda: auctioneer_pda,
+    };
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::AuctioneerUpdateAuctionHouse {
+            seller_fee_basis_points,
+            can_change_sale_price,
+            tick_size,
+        }
+        .data(),
+        accounts: accounts.to_account_metas(None),
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&auctioneer_authority.pubkey()),
+        &[auctioneer_authority],
+        context.last_blockhash,
+    );
+
+    (accounts, tx)
+}
+
 pub async fn existing_auction_house_test_context(
     context: &mut ProgramTestContext,
 ) -> StdResult<(AuctionHouse, Pubkey, Keypair), TransportError> {
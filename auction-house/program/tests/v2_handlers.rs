@@ -0,0 +1,386 @@
+#![cfg(feature = "test-bpf")]
+pub mod common;
+pub mod utils;
+
+use common::*;
+use mpl_auction_house::pda::{
+    find_escrow_payment_address, find_proceeds_route_address, find_program_as_signer_address,
+    find_trade_state_address,
+};
+use solana_program::{instruction::AccountMeta, program_option::COption, system_program, sysvar};
+use solana_sdk::account::AccountSharedData;
+use spl_token::state::Account;
+use utils::setup_functions::*;
+
+use solana_program::program_pack::Pack;
+
+#[tokio::test]
+async fn deposit_and_withdraw_v2() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    assert_eq!(
+        ah.program_as_signer_bump,
+        find_program_as_signer_address().1
+    );
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), 10_000_000_000)
+        .await
+        .unwrap();
+    let (escrow, _) = find_escrow_payment_address(&ahkey, &buyer.pubkey());
+
+    let deposit_instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::DepositV2 {
+            amount: 1_000_000_000,
+        }
+        .data(),
+        accounts: mpl_auction_house::accounts::DepositV2 {
+            wallet: buyer.pubkey(),
+            payment_account: buyer.pubkey(),
+            transfer_authority: buyer.pubkey(),
+            escrow_payment_account: escrow,
+            treasury_mint: ah.treasury_mint,
+            authority: ah.authority,
+            auction_house: ahkey,
+            auction_house_fee_account: ah.auction_house_fee_account,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            rent: sysvar::rent::id(),
+        }
+        .to_account_metas(None),
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[deposit_instruction],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+    let escrow_after_deposit = context.banks_client.get_balance(escrow).await.unwrap();
+    assert_eq!(escrow_after_deposit, 1_000_000_000);
+
+    let withdraw_instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::WithdrawV2 {
+            amount: 400_000_000,
+        }
+        .data(),
+        accounts: mpl_auction_house::accounts::WithdrawV2 {
+            wallet: buyer.pubkey(),
+            receipt_account: buyer.pubkey(),
+            escrow_payment_account: escrow,
+            treasury_mint: ah.treasury_mint,
+            authority: ah.authority,
+            auction_house: ahkey,
+            auction_house_fee_account: ah.auction_house_fee_account,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            ata_program: spl_associated_token_account::id(),
+            rent: sysvar::rent::id(),
+        }
+        .to_account_metas(None),
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[withdraw_instruction],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+    let escrow_after_withdraw = context.banks_client.get_balance(escrow).await.unwrap();
+    assert_eq!(escrow_after_withdraw, 600_000_000);
+}
+
+#[tokio::test]
+async fn sell_buy_execute_sale_v2() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = Metadata::new();
+    airdrop(&mut context, &test_metadata.token.pubkey(), 10_000_000_000)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            &mut context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+    let seller = test_metadata.token.pubkey();
+    let token_account = get_associated_token_address(&seller, &test_metadata.mint.pubkey());
+    let (seller_trade_state, _) = find_trade_state_address(
+        &seller,
+        &ahkey,
+        &token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        100_000_000,
+        1,
+    );
+    let (free_trade_state, _) = find_trade_state_address(
+        &seller,
+        &ahkey,
+        &token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        0,
+        1,
+    );
+    let (program_as_signer, _) = find_program_as_signer_address();
+
+    let sell_instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::SellV2 {
+            buyer_price: 100_000_000,
+            token_size: 1,
+        }
+        .data(),
+        accounts: mpl_auction_house::accounts::SellV2 {
+            wallet: seller,
+            token_account,
+            metadata: test_metadata.pubkey,
+            authority: ah.authority,
+            auction_house: ahkey,
+            auction_house_fee_account: ah.auction_house_fee_account,
+            seller_trade_state,
+            free_seller_trade_state: free_trade_state,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            program_as_signer,
+            rent: sysvar::rent::id(),
+        }
+        .to_account_metas(None),
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[sell_instruction],
+        Some(&seller),
+        &[&test_metadata.token],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), 10_000_000_000)
+        .await
+        .unwrap();
+    let (buyer_trade_state, _) = find_trade_state_address(
+        &buyer.pubkey(),
+        &ahkey,
+        &token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        100_000_000,
+        1,
+    );
+    let (escrow, _) = find_escrow_payment_address(&ahkey, &buyer.pubkey());
+
+    let buy_instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::BuyV2 {
+            buyer_price: 100_000_000,
+            token_size: 1,
+        }
+        .data(),
+        accounts: mpl_auction_house::accounts::BuyV2 {
+            wallet: buyer.pubkey(),
+            payment_account: buyer.pubkey(),
+            transfer_authority: buyer.pubkey(),
+            treasury_mint: ah.treasury_mint,
+            token_account,
+            metadata: test_metadata.pubkey,
+            escrow_payment_account: escrow,
+            authority: ah.authority,
+            auction_house: ahkey,
+            auction_house_fee_account: ah.auction_house_fee_account,
+            buyer_trade_state,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            rent: sysvar::rent::id(),
+        }
+        .to_account_metas(None),
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[buy_instruction],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+    airdrop(&mut context, &ah.auction_house_fee_account, 10_000_000_000)
+        .await
+        .unwrap();
+
+    let buyer_token_account =
+        get_associated_token_address(&buyer.pubkey(), &test_metadata.mint.pubkey());
+    let mut accounts = mpl_auction_house::accounts::ExecuteSaleV2 {
+        buyer: buyer.pubkey(),
+        seller,
+        token_account,
+        token_mint: test_metadata.mint.pubkey(),
+        metadata: test_metadata.pubkey,
+        treasury_mint: ah.treasury_mint,
+        escrow_payment_account: escrow,
+        seller_payment_receipt_account: seller,
+        buyer_receipt_token_account: buyer_token_account,
+        authority: ah.authority,
+        auction_house: ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        auction_house_treasury: ah.auction_house_treasury,
+        buyer_trade_state,
+        seller_trade_state,
+        free_trade_state,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        ata_program: spl_associated_token_account::id(),
+        program_as_signer,
+        rent: sysvar::rent::id(),
+    }
+    .to_account_metas(None);
+    accounts.push(AccountMeta::new_readonly(
+        find_proceeds_route_address(&seller, &seller_trade_state).0,
+        false,
+    ));
+    let execute_sale_instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::ExecuteSaleV2 {
+            buyer_price: 100_000_000,
+            token_size: 1,
+            partial_order_size: None,
+            partial_order_price: None,
+        }
+        .data(),
+        accounts,
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[execute_sale_instruction],
+        Some(&authority.pubkey()),
+        &[&authority],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let buyer_token_after = Account::unpack_from_slice(
+        context
+            .banks_client
+            .get_account(buyer_token_account)
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+            .as_slice(),
+    )
+    .unwrap();
+    assert_eq!(buyer_token_after.amount, 1);
+}
+
+#[tokio::test]
+async fn sell_v2_without_stored_program_as_signer_bump() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+
+    // Houses created before the bump was stored hold 0 until their next update.
+    let mut ah_account = context
+        .banks_client
+        .get_account(ahkey)
+        .await
+        .unwrap()
+        .unwrap();
+    let mut legacy_ah = AuctionHouse::try_deserialize(&mut ah_account.data.as_ref()).unwrap();
+    legacy_ah.program_as_signer_bump = 0;
+    legacy_ah
+        .try_serialize(&mut ah_account.data.as_mut_slice())
+        .unwrap();
+    context.set_account(&ahkey, &AccountSharedData::from(ah_account));
+
+    let test_metadata = Metadata::new();
+    airdrop(&mut context, &test_metadata.token.pubkey(), 10_000_000_000)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            &mut context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+    let seller = test_metadata.token.pubkey();
+    let token_account = get_associated_token_address(&seller, &test_metadata.mint.pubkey());
+    let (seller_trade_state, _) = find_trade_state_address(
+        &seller,
+        &ahkey,
+        &token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        100_000_000,
+        1,
+    );
+    let (free_trade_state, _) = find_trade_state_address(
+        &seller,
+        &ahkey,
+        &token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        0,
+        1,
+    );
+    let (program_as_signer, _) = find_program_as_signer_address();
+
+    let sell_instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::SellV2 {
+            buyer_price: 100_000_000,
+            token_size: 1,
+        }
+        .data(),
+        accounts: mpl_auction_house::accounts::SellV2 {
+            wallet: seller,
+            token_account,
+            metadata: test_metadata.pubkey,
+            authority: ah.authority,
+            auction_house: ahkey,
+            auction_house_fee_account: ah.auction_house_fee_account,
+            seller_trade_state,
+            free_seller_trade_state: free_trade_state,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            program_as_signer,
+            rent: sysvar::rent::id(),
+        }
+        .to_account_metas(None),
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[sell_instruction],
+        Some(&seller),
+        &[&test_metadata.token],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let token = context
+        .banks_client
+        .get_account(token_account)
+        .await
+        .unwrap()
+        .unwrap();
+    let token = Account::unpack(&token.data).unwrap();
+    assert_eq!(token.delegate, COption::Some(program_as_signer));
+}
@@ -11,7 +11,7 @@ use mpl_auction_house::{
     AuctionHouse,
 };
 
-use crate::{constants::*, sell::config::*, utils::*};
+use crate::{bid_history::*, constants::*, errors::*, operator::*, sell::config::*, utils::*};
 
 /// Accounts for the [`private_bid_with_auctioneer` handler](fn.private_bid_with_auctioneer.html).
 #[derive(Accounts)]
@@ -43,6 +43,7 @@ pub struct AuctioneerBuy<'info> {
 
     // Accounts passed into Auction House CPI call
     /// User wallet account.
+    #[account(mut)]
     wallet: Signer<'info>,
 
     /// CHECK: Verified through CPI
@@ -111,6 +112,24 @@ pub struct AuctioneerBuy<'info> {
     )]
     pub ah_auctioneer_pda: UncheckedAccount<'info>,
 
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Current bid history page of the listing, created with the first bid of the page.
+    #[account(
+        mut,
+        seeds = [
+            BID_HISTORY.as_bytes(),
+            listing_config.key().as_ref(),
+            &(listing_config.bid_count / BID_HISTORY_PAGE_LEN as u64).to_le_bytes()
+        ],
+        bump
+    )]
+    bid_history: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Operator control PDA, bids are refused while it is paused.
+    #[account(seeds = [OPERATOR_CONTROL.as_bytes(), auction_house.key().as_ref()], bump)]
+    operator_control: UncheckedAccount<'info>,
+
     token_program: Program<'info, Token>,
     system_program: Program<'info, System>,
     rent: Sysvar<'info, Rent>,
@@ -125,13 +144,59 @@ pub fn auctioneer_buy<'info>(
     buyer_price: u64,
     token_size: u64,
 ) -> Result<()> {
+    assert_not_paused(&ctx.accounts.operator_control)?;
     assert_auction_active(&ctx.accounts.listing_config)?;
     assert_higher_bid(&ctx.accounts.listing_config, buyer_price)?;
-    assert_exceeds_reserve_price(&ctx.accounts.listing_config, buyer_price)?;
+    // Auto-relisted listings take bids below reserve, the listing is reopened when none reaches it.
+    if ctx.accounts.listing_config.auto_relist.is_none() {
+        assert_exceeds_reserve_price(&ctx.accounts.listing_config, buyer_price)?;
+    }
     process_time_extension(&mut ctx.accounts.listing_config)?;
+    // Accrue the outbid highest bid before it is replaced.
+    accrue_listing(&mut ctx.accounts.listing_config)?;
+    // The outbid highest bid becomes the runner-up, kept as a fallback at settlement.
+    ctx.accounts.listing_config.runner_up_bid = ctx.accounts.listing_config.highest_bid.clone();
     ctx.accounts.listing_config.highest_bid.amount = buyer_price;
     ctx.accounts.listing_config.highest_bid.buyer_trade_state =
         ctx.accounts.buyer_trade_state.key();
+    // A bid at the buy-now price ends the auction, it can be settled right away.
+    if ctx.accounts.listing_config.buy_now_price > 0
+        && buyer_price >= ctx.accounts.listing_config.buy_now_price
+    {
+        ctx.accounts.listing_config.bought_now_at = Clock::get()?.unix_timestamp;
+    }
+
+    // The price feed of listings recording oracle prices is the first of the remaining accounts.
+    let price_snapshot = match &ctx.accounts.listing_config.price_snapshot {
+        Some(config) => {
+            let price_feed = ctx
+                .remaining_accounts
+                .first()
+                .ok_or(AuctioneerError::InvalidPriceFeed)?;
+            Some(snapshot_price(config, price_feed)?)
+        }
+        None => None,
+    };
+
+    let bid_history_bump = *ctx
+        .bumps
+        .get("bid_history")
+        .ok_or(AuctioneerError::BumpSeedNotInHashMap)?;
+    record_bid(
+        &mut ctx.accounts.listing_config,
+        &ctx.accounts.bid_history.to_account_info(),
+        &ctx.accounts.wallet.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.rent,
+        bid_history_bump,
+        buyer_price,
+        price_snapshot,
+    )?;
+    collect_bid_fee(
+        &mut ctx.accounts.listing_config,
+        &ctx.accounts.wallet.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )?;
 
     let cpi_program = ctx.accounts.auction_house_program.to_account_info();
     let cpi_accounts = AHBuy {
@@ -0,0 +1,205 @@
+//! Record accepted bids of a listing for dispute evidence.
+
+use anchor_lang::{prelude::*, AnchorDeserialize};
+
+use solana_program::{
+    program::{invoke, invoke_signed},
+    system_instruction,
+};
+
+use crate::{constants::*, errors::*, sell::config::*, utils::*};
+
+pub const PRICE_SNAPSHOT_SIZE: usize = 8 + 8 + 4 + 8;
+pub const BID_RECORD_SIZE: usize = 8 + 32 + 8 + 1 + PRICE_SNAPSHOT_SIZE;
+pub const BID_HISTORY_SIZE: usize =
+    8 + 32 + 8 + 32 + 1 + 4 + BID_RECORD_SIZE * BID_HISTORY_PAGE_LEN;
+pub const LEGACY_BID_RECORD_SIZE: usize = 8 + 32 + 8;
+pub const LEGACY_BID_HISTORY_SIZE: usize =
+    8 + 32 + 8 + 32 + 1 + 4 + LEGACY_BID_RECORD_SIZE * BID_HISTORY_PAGE_LEN;
+
+/// Aggregate oracle price of the treasury mint at the time of a bid.
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy)]
+pub struct PriceSnapshot {
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    /// Slot the aggregate price was published at.
+    pub publish_slot: u64,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Clone)]
+pub struct BidRecord {
+    pub amount: u64,
+    pub bidder: Pubkey,
+    pub slot: u64,
+    /// Oracle price at the bid, for listings with a price snapshot config.
+    pub price_snapshot: Option<PriceSnapshot>,
+}
+
+/// Append-only page of the accepted bids of a listing, `BID_HISTORY_PAGE_LEN` bids per page.
+#[account]
+pub struct BidHistory {
+    pub listing_config: Pubkey,
+    pub page: u64,
+    pub payer: Pubkey,
+    pub bump: u8,
+    pub bids: Vec<BidRecord>,
+}
+
+/// [`BidRecord`] as written before price snapshots were recorded.
+#[derive(AnchorDeserialize)]
+struct LegacyBidRecord {
+    amount: u64,
+    bidder: Pubkey,
+    slot: u64,
+}
+
+/// [`BidHistory`] page of [`LEGACY_BID_HISTORY_SIZE`], holding [`LegacyBidRecord`]s behind the same header.
+#[derive(AnchorDeserialize)]
+struct LegacyBidHistory {
+    listing_config: Pubkey,
+    page: u64,
+    payer: Pubkey,
+    bump: u8,
+    bids: Vec<LegacyBidRecord>,
+}
+
+/// Rewrite a bid history page created before price snapshots were recorded with the current layout, growing it to
+/// [`BID_HISTORY_SIZE`] with `payer` covering the rent. The rewritten records have no price snapshot.
+pub fn grow_legacy_bid_history<'info>(
+    bid_history: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let legacy = LegacyBidHistory::deserialize(&mut &bid_history.try_borrow_data()?[8..])?;
+    let history = BidHistory {
+        listing_config: legacy.listing_config,
+        page: legacy.page,
+        payer: legacy.payer,
+        bump: legacy.bump,
+        bids: legacy
+            .bids
+            .into_iter()
+            .map(|bid| BidRecord {
+                amount: bid.amount,
+                bidder: bid.bidder,
+                slot: bid.slot,
+                price_snapshot: None,
+            })
+            .collect(),
+    };
+
+    let rent = Rent::get()?
+        .minimum_balance(BID_HISTORY_SIZE)
+        .saturating_sub(bid_history.lamports());
+    if rent > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, bid_history.key, rent),
+            &[payer.clone(), bid_history.clone(), system_program.clone()],
+        )?;
+    }
+    bid_history.realloc(BID_HISTORY_SIZE, false)?;
+
+    let mut data = bid_history.try_borrow_mut_data()?;
+    history.try_serialize(&mut *data)?;
+
+    Ok(())
+}
+
+/// Append an accepted bid to the current bid history page of the listing, creating the page paid by `payer` when it is the first bid of the page.
+pub fn record_bid<'info>(
+    listing_config: &mut Account<'info, ListingConfig>,
+    bid_history: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    rent: &Sysvar<'info, Rent>,
+    bid_history_bump: u8,
+    amount: u64,
+    price_snapshot: Option<PriceSnapshot>,
+) -> Result<()> {
+    let listing_config_key = listing_config.key();
+    let page = listing_config.bid_count / BID_HISTORY_PAGE_LEN as u64;
+    let record = BidRecord {
+        amount,
+        bidder: payer.key(),
+        slot: Clock::get()?.slot,
+        price_snapshot,
+    };
+
+    if bid_history.data_is_empty() {
+        let bid_history_seeds = [
+            BID_HISTORY.as_bytes(),
+            listing_config_key.as_ref(),
+            &page.to_le_bytes(),
+            &[bid_history_bump],
+        ];
+        invoke_signed(
+            &system_instruction::create_account(
+                payer.key,
+                bid_history.key,
+                rent.minimum_balance(BID_HISTORY_SIZE),
+                BID_HISTORY_SIZE as u64,
+                &crate::id(),
+            ),
+            &[payer.clone(), bid_history.clone(), system_program.clone()],
+            &[&bid_history_seeds],
+        )?;
+
+        let history = BidHistory {
+            listing_config: listing_config_key,
+            page,
+            payer: payer.key(),
+            bump: bid_history_bump,
+            bids: vec![record],
+        };
+        let mut data = bid_history.try_borrow_mut_data()?;
+        history.try_serialize(&mut *data)?;
+    } else {
+        // Pages started before price snapshots were recorded are rewritten before the record is appended.
+        if bid_history.data_len() < BID_HISTORY_SIZE {
+            grow_legacy_bid_history(bid_history, payer, system_program)?;
+        }
+
+        let mut history: Account<BidHistory> = Account::try_from(bid_history)?;
+        history.bids.push(record);
+        history.exit(&crate::id())?;
+    }
+
+    listing_config.bid_count += 1;
+
+    Ok(())
+}
+
+/// Accounts for the [`close_bid_history` handler](fn.close_bid_history.html).
+#[derive(Accounts)]
+pub struct CloseBidHistory<'info> {
+    /// The Listing Config the bid history was recorded for.
+    pub listing_config: Account<'info, ListingConfig>,
+
+    /// CHECK: Verified with address constraint against the listing highest bid.
+    /// Trade state of the highest bid, emptied once the sale is executed.
+    #[account(address = listing_config.highest_bid.buyer_trade_state)]
+    pub buyer_trade_state: UncheckedAccount<'info>,
+
+    /// Bid history page to close.
+    #[account(mut, has_one = listing_config, has_one = payer, close = payer)]
+    pub bid_history: Account<'info, BidHistory>,
+
+    /// CHECK: Verified with has_one constraint on bid history account.
+    /// Account that paid for the bid history page, receiving the rent back.
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+}
+
+/// Close a bid history page once the auction is over and the highest bid is settled, returning the rent to the payer of the page.
+pub fn close_bid_history<'info>(
+    ctx: Context<'_, '_, '_, 'info, CloseBidHistory<'info>>,
+) -> Result<()> {
+    assert_auction_over(&ctx.accounts.listing_config)?;
+
+    if ctx.accounts.buyer_trade_state.lamports() > 0 {
+        return err!(AuctioneerError::BidHistoryNotSettled);
+    }
+
+    Ok(())
+}
@@ -5,6 +5,7 @@ use mpl_auction_house::{
     self,
     constants::{AUCTIONEER, FEE_PAYER, PREFIX},
     cpi::accounts::AuctioneerCancel as AHCancel,
+    pda::find_trade_state_address,
     program::AuctionHouse as AuctionHouseProgram,
     AuctionHouse,
 };
@@ -90,13 +91,45 @@ pub fn auctioneer_cancel<'info>(
     buyer_price: u64,
     token_size: u64,
 ) -> Result<()> {
+    // A highest bid below reserve can be canceled once the auction ended, as it will never be settled.
     if !ctx.accounts.listing_config.allow_high_bid_cancel
         && (ctx.accounts.trade_state.key()
             == ctx.accounts.listing_config.highest_bid.buyer_trade_state)
+        && !ctx
+            .accounts
+            .listing_config
+            .reserve_failed(Clock::get()?.unix_timestamp)
     {
         return err!(AuctioneerError::CannotCancelHighestBid);
     }
 
+    // Canceling a co-listed sale takes the co-lister signature, passed as the first remaining account.
+    if ctx.accounts.listing_config.has_co_lister() {
+        let (seller_trade_state, _) = find_trade_state_address(
+            &ctx.accounts.seller.key(),
+            &ctx.accounts.auction_house.key(),
+            &ctx.accounts.token_account.key(),
+            &ctx.accounts.auction_house.treasury_mint,
+            &ctx.accounts.token_mint.key(),
+            AUCTIONEER_BUYER_PRICE,
+            token_size,
+        );
+        if ctx.accounts.trade_state.key() == seller_trade_state {
+            match ctx.remaining_accounts.first() {
+                Some(co_lister)
+                    if co_lister.key() == ctx.accounts.listing_config.co_lister
+                        && co_lister.is_signer => {}
+                _ => return err!(AuctioneerError::MissingCoListerSignature),
+            }
+        }
+    }
+
+    if ctx.accounts.trade_state.key() == ctx.accounts.listing_config.runner_up_bid.buyer_trade_state
+    {
+        ctx.accounts.listing_config.runner_up_bid.amount = 0;
+        ctx.accounts.listing_config.runner_up_bid.buyer_trade_state = Pubkey::default();
+    }
+
     let cpi_program = ctx.accounts.auction_house_program.to_account_info();
     let cpi_accounts = AHCancel {
         wallet: ctx.accounts.wallet.to_account_info(),
@@ -0,0 +1,70 @@
+//! Let a second principal, such as the artist of a gallery consignment, co-list an auction and share its proceeds.
+
+use anchor_lang::{prelude::*, AnchorDeserialize};
+use anchor_spl::token::TokenAccount;
+
+use mpl_auction_house::{
+    self, constants::PREFIX, program::AuctionHouse as AuctionHouseProgram, AuctionHouse,
+};
+
+use crate::{constants::*, errors::*, sell::config::*};
+
+/// Accounts for the [`set_co_lister` handler](fn.set_co_lister.html).
+#[derive(Accounts)]
+#[instruction(token_size: u64)]
+pub struct SetCoLister<'info> {
+    /// Auction House Program
+    pub auction_house_program: Program<'info, AuctionHouseProgram>,
+
+    /// The Listing Config used for listing settings
+    #[account(
+        mut,
+        seeds=[
+            LISTING_CONFIG.as_bytes(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_account.mint.as_ref(),
+            &token_size.to_le_bytes()
+        ],
+        bump=listing_config.bump,
+    )]
+    pub listing_config: Box<Account<'info, ListingConfig>>,
+
+    /// Seller wallet account, the principal holding the token.
+    pub wallet: Signer<'info>,
+
+    /// Second principal of the listing.
+    pub co_lister: Signer<'info>,
+
+    /// SPL token account containing the listed token.
+    pub token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Auction House instance PDA account.
+    #[account(seeds=[PREFIX.as_bytes(), auction_house.creator.as_ref(), auction_house.treasury_mint.as_ref()], seeds::program=auction_house_program, bump=auction_house.bump)]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+}
+
+/// Record the co-lister of a listing and its split of the seller proceeds. Bidders have to see the principals before
+/// bidding, so the split is frozen once the first bid comes in.
+pub fn set_co_lister<'info>(
+    ctx: Context<'_, '_, '_, 'info, SetCoLister<'info>>,
+    _token_size: u64,
+    co_lister_basis_points: u16,
+) -> Result<()> {
+    let listing_config = &mut ctx.accounts.listing_config;
+
+    if co_lister_basis_points == 0
+        || co_lister_basis_points > 10000
+        || listing_config.bid_count > 0
+        || ctx.accounts.co_lister.key() == ctx.accounts.wallet.key()
+    {
+        return err!(AuctioneerError::InvalidCoListerSplit);
+    }
+
+    listing_config.co_lister = ctx.accounts.co_lister.key();
+    listing_config.co_lister_basis_points = co_lister_basis_points;
+
+    Ok(())
+}
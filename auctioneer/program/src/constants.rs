@@ -1,2 +1,10 @@
 pub const LISTING_CONFIG: &str = "listing_config";
+pub const BID_HISTORY: &str = "bid_history";
+pub const OPERATOR_CONTROL: &str = "operator_control";
+pub const BID_HISTORY_PAGE_LEN: usize = 32;
 pub const AUCTIONEER_BUYER_PRICE: u64 = u64::MAX;
+pub const PYTH_MAGIC: u32 = 0xa1b2c3d4;
+pub const PYTH_PRICE_ACCOUNT_TYPE: u32 = 3;
+pub const PYTH_STATUS_TRADING: u32 = 1;
+// Up to the end of the aggregate price info
+pub const PYTH_PRICE_ACCOUNT_LEN: usize = 240;
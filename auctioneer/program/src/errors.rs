@@ -41,4 +41,76 @@ pub enum AuctioneerError {
     // 6009
     #[msg("The highest bidder is not allowed to cancel")]
     CannotCancelHighestBid,
+
+    // 6010
+    #[msg("The highest bid must be settled before closing the bid history")]
+    BidHistoryNotSettled,
+
+    // 6011
+    #[msg("The marketplace operator has paused bids and sales")]
+    OperatorPaused,
+
+    // 6012
+    #[msg("Numerical overflow")]
+    NumericalOverflow,
+
+    // 6013
+    #[msg("Fallback settlement must be run on the runner-up bidder")]
+    NotRunnerUpBidder,
+
+    // 6014
+    #[msg("The highest bid can still be settled")]
+    HighestBidSettleable,
+
+    // 6015
+    #[msg("The highest bid accounts do not match the listing")]
+    InvalidHighestBidAccounts,
+
+    // 6016
+    #[msg("The co-lister split must be between 1 and 10000 basis points and set before any bid")]
+    InvalidCoListerSplit,
+
+    // 6017
+    #[msg("Both listing principals must sign to cancel a co-listed sale")]
+    MissingCoListerSignature,
+
+    // 6018
+    #[msg("The seller must sign the settlement of a co-listed sale")]
+    SellerMustSignCoListedSettlement,
+
+    // 6019
+    #[msg("The co-lister accounts do not match the listing")]
+    InvalidCoListerAccount,
+
+    // 6020
+    #[msg("Auto-relist rounds must have a positive duration and take at most 10000 basis points off the reserve")]
+    InvalidAutoRelist,
+
+    // 6021
+    #[msg("The reserve price was met, the auction must be settled instead")]
+    ReserveMet,
+
+    // 6022
+    #[msg("The listing has no auto-relist round left")]
+    NoRelistRoundLeft,
+
+    // 6023
+    #[msg("The listing was canceled")]
+    ListingCanceled,
+
+    // 6024
+    #[msg("The price feed is missing, does not match the listing or is not a trading Pyth price account")]
+    InvalidPriceFeed,
+
+    // 6025
+    #[msg("The price feed was not updated recently enough to snapshot")]
+    StalePriceFeed,
+
+    // 6026
+    #[msg("The listing config already has the current size")]
+    ListingConfigAlreadyMigrated,
+
+    // 6027
+    #[msg("The bid history page already has the current layout")]
+    BidHistoryAlreadyMigrated,
 }
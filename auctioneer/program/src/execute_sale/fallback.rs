@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::spl_token;
+
+use mpl_auction_house::pda::{find_escrow_payment_address, find_trade_state_address};
+
+use solana_program::program_pack::Pack;
+
+use crate::{errors::*, execute_sale::*, operator::*, utils::*};
+
+/// Emitted when a listing is settled with the runner-up bid because the highest bid could no longer be executed.
+#[event]
+pub struct FallbackSettlement {
+    pub listing_config: Pubkey,
+    pub highest_bid_trade_state: Pubkey,
+    pub runner_up_trade_state: Pubkey,
+    pub amount: u64,
+}
+
+/// Settle an ended auction with the runner-up bid when the highest bid cannot be executed anymore, because its
+/// trade state was canceled or its escrow no longer holds the bid amount. The remaining accounts are the highest
+/// bidder wallet, its buyer trade state and its escrow payment account.
+pub fn settle_with_fallback<'info>(
+    ctx: Context<'_, '_, '_, 'info, AuctioneerExecuteSale<'info>>,
+    escrow_payment_bump: u8,
+    free_trade_state_bump: u8,
+    program_as_signer_bump: u8,
+    auctioneer_authority_bump: u8,
+    buyer_price: u64,
+    token_size: u64,
+) -> Result<()> {
+    assert_not_paused(&ctx.accounts.operator_control)?;
+    assert_auction_over(&ctx.accounts.listing_config)?;
+
+    let listing_config = &ctx.accounts.listing_config;
+    if ctx.accounts.buyer_trade_state.key() != listing_config.runner_up_bid.buyer_trade_state
+        || buyer_price != listing_config.runner_up_bid.amount
+        || listing_config.runner_up_bid.amount == 0
+    {
+        return err!(AuctioneerError::NotRunnerUpBidder);
+    }
+
+    let highest_bid_trade_state = listing_config.highest_bid.buyer_trade_state;
+    assert_highest_bid_unsettleable(&ctx, token_size)?;
+
+    emit!(FallbackSettlement {
+        listing_config: ctx.accounts.listing_config.key(),
+        highest_bid_trade_state,
+        runner_up_trade_state: ctx.accounts.buyer_trade_state.key(),
+        amount: buyer_price,
+    });
+
+    // The runner-up takes over the highest bid so the bid history can be closed once it is settled.
+    let listing_config = &mut ctx.accounts.listing_config;
+    listing_config.highest_bid = listing_config.runner_up_bid.clone();
+    listing_config.runner_up_bid.amount = 0;
+    listing_config.runner_up_bid.buyer_trade_state = Pubkey::default();
+
+    execute_sale_cpi(
+        ctx,
+        escrow_payment_bump,
+        free_trade_state_bump,
+        program_as_signer_bump,
+        auctioneer_authority_bump,
+        buyer_price,
+        token_size,
+    )
+}
+
+fn assert_highest_bid_unsettleable(
+    ctx: &Context<'_, '_, '_, '_, AuctioneerExecuteSale<'_>>,
+    token_size: u64,
+) -> Result<()> {
+    let highest_bid = &ctx.accounts.listing_config.highest_bid;
+    let auction_house = &ctx.accounts.auction_house;
+    let ah_key = auction_house.key();
+
+    let (wallet, trade_state, escrow_payment_account) = match ctx.remaining_accounts {
+        [wallet, trade_state, escrow_payment_account] => {
+            (wallet, trade_state, escrow_payment_account)
+        }
+        _ => return err!(AuctioneerError::InvalidHighestBidAccounts),
+    };
+
+    let (trade_state_key, _) = find_trade_state_address(
+        wallet.key,
+        &ah_key,
+        &ctx.accounts.token_account.key(),
+        &auction_house.treasury_mint,
+        &ctx.accounts.token_mint.key(),
+        highest_bid.amount,
+        token_size,
+    );
+    let (escrow_payment_key, _) = find_escrow_payment_address(&ah_key, wallet.key);
+    if trade_state.key() != highest_bid.buyer_trade_state
+        || trade_state.key() != trade_state_key
+        || escrow_payment_account.key() != escrow_payment_key
+    {
+        return err!(AuctioneerError::InvalidHighestBidAccounts);
+    }
+
+    if trade_state.data_is_empty() {
+        return Ok(());
+    }
+
+    let escrowed = if auction_house.treasury_mint == spl_token::native_mint::id() {
+        escrow_payment_account.lamports()
+    } else if escrow_payment_account.data_is_empty() {
+        0
+    } else {
+        spl_token::state::Account::unpack(&escrow_payment_account.try_borrow_data()?)?.amount
+    };
+    if escrowed < highest_bid.amount {
+        return Ok(());
+    }
+
+    err!(AuctioneerError::HighestBidSettleable)
+}
@@ -1,5 +1,12 @@
+pub mod fallback;
+
+pub use fallback::*;
+
 use anchor_lang::{prelude::*, AnchorDeserialize, InstructionData};
-use anchor_spl::{associated_token::AssociatedToken, token::Token};
+use anchor_spl::{
+    associated_token::{get_associated_token_address, AssociatedToken},
+    token::{self, spl_token, Token},
+};
 
 use mpl_auction_house::{
     self,
@@ -9,9 +16,12 @@ use mpl_auction_house::{
     AuctionHouse,
 };
 
-use crate::{constants::*, sell::config::*, utils::*};
+use crate::{constants::*, errors::*, operator::*, sell::config::*, utils::*};
 
-use solana_program::program::invoke_signed;
+use solana_program::{
+    program::{invoke, invoke_signed},
+    system_instruction,
+};
 
 #[derive(Accounts)]
 #[instruction(escrow_payment_bump: u8, free_trade_state_bump: u8, program_as_signer_bump: u8, auctioneer_authority_bump: u8, buyer_price: u64, token_size: u64)]
@@ -22,6 +32,7 @@ pub struct AuctioneerExecuteSale<'info> {
     // Accounts used for Auctioneer
     /// The Listing Config used for listing settings
     #[account(
+        mut,
         seeds=[
             LISTING_CONFIG.as_bytes(),
             seller.key().as_ref(),
@@ -123,6 +134,11 @@ pub struct AuctioneerExecuteSale<'info> {
     #[account(seeds = [AUCTIONEER.as_bytes(), auction_house.key().as_ref(), auctioneer_authority.key().as_ref()], seeds::program=auction_house_program, bump = auction_house.auctioneer_pda_bump)]
     pub ah_auctioneer_pda: UncheckedAccount<'info>,
 
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Operator control PDA, sales are refused while it is paused.
+    #[account(seeds = [OPERATOR_CONTROL.as_bytes(), auction_house.key().as_ref()], bump)]
+    pub operator_control: UncheckedAccount<'info>,
+
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
     pub ata_program: Program<'info, AssociatedToken>,
@@ -143,12 +159,85 @@ pub fn auctioneer_execute_sale<'info>(
     buyer_price: u64,
     token_size: u64,
 ) -> Result<()> {
+    assert_not_paused(&ctx.accounts.operator_control)?;
     assert_auction_over(&ctx.accounts.listing_config)?;
     assert_highest_bidder(
         &ctx.accounts.listing_config,
         ctx.accounts.buyer_trade_state.key(),
     )?;
 
+    execute_sale_cpi(
+        ctx,
+        escrow_payment_bump,
+        free_trade_state_bump,
+        program_as_signer_bump,
+        auctioneer_authority_bump,
+        buyer_price,
+        token_size,
+    )
+}
+
+/// Run the Auction House execute sale for the provided buyer trade state and settle the escrowed bid fees.
+pub(crate) fn execute_sale_cpi<'info>(
+    ctx: Context<'_, '_, '_, 'info, AuctioneerExecuteSale<'info>>,
+    escrow_payment_bump: u8,
+    free_trade_state_bump: u8,
+    program_as_signer_bump: u8,
+    auctioneer_authority_bump: u8,
+    buyer_price: u64,
+    token_size: u64,
+) -> Result<()> {
+    // Auto-relisted listings take bids below reserve, which never settle.
+    assert_exceeds_reserve_price(&ctx.accounts.listing_config, buyer_price)?;
+
+    let is_native = ctx.accounts.treasury_mint.key() == spl_token::native_mint::id();
+    let seller = ctx.accounts.seller.to_account_info();
+    let seller_payment_receipt_account = ctx.accounts.seller_payment_receipt_account.to_account_info();
+
+    // The co-lister wallet and, for SPL treasuries, its payment token account follow the named accounts.
+    let remaining_accounts = &mut ctx.remaining_accounts.iter();
+    let co_lister_accounts = if ctx.accounts.listing_config.has_co_lister() {
+        if !seller.is_signer {
+            return err!(AuctioneerError::SellerMustSignCoListedSettlement);
+        }
+        let co_lister = next_account_info(remaining_accounts)?;
+        let co_lister_payment_account = if is_native {
+            co_lister
+        } else {
+            next_account_info(remaining_accounts)?
+        };
+        if co_lister.key() != ctx.accounts.listing_config.co_lister
+            || (!is_native
+                && co_lister_payment_account.key()
+                    != get_associated_token_address(
+                        &co_lister.key(),
+                        &ctx.accounts.treasury_mint.key(),
+                    ))
+        {
+            return err!(AuctioneerError::InvalidCoListerAccount);
+        }
+        Some((co_lister, co_lister_payment_account))
+    } else {
+        None
+    };
+
+    // Trade state rent goes back to the fee payer of the sale, which also pays for a missing buyer token account.
+    let seller_pays_fees =
+        !ctx.accounts.authority.is_signer && !ctx.accounts.buyer.is_signer && is_native;
+    let trade_state_rent = [
+        &ctx.accounts.seller_trade_state,
+        &ctx.accounts.buyer_trade_state,
+        &ctx.accounts.free_trade_state,
+    ]
+    .iter()
+    .try_fold(0u64, |rent, trade_state| {
+        rent.checked_add(trade_state.lamports())
+    })
+    .ok_or(AuctioneerError::NumericalOverflow)?;
+    let buyer_receipt_token_account_existed =
+        !ctx.accounts.buyer_receipt_token_account.data_is_empty();
+    let proceeds_before = payment_account_balance(&seller_payment_receipt_account, is_native)?;
+
     let cpi_program = ctx.accounts.auction_house_program.to_account_info();
     let cpi_accounts = AHExecuteSale {
         buyer: ctx.accounts.buyer.to_account_info(),
@@ -206,8 +295,6 @@ pub fn auctioneer_execute_sale<'info>(
 
     let auction_house = &ctx.accounts.auction_house;
     let ah_key = auction_house.key();
-    let auctioneer_authority = &ctx.accounts.auctioneer_authority;
-    let _aa_key = auctioneer_authority.key();
 
     let auctioneer_seeds = [
         AUCTIONEER.as_bytes(),
@@ -217,5 +304,64 @@ pub fn auctioneer_execute_sale<'info>(
 
     invoke_signed(&ix, &cpi_accounts.to_account_infos(), &[&auctioneer_seeds])?;
 
+    if let Some((co_lister, co_lister_payment_account)) = co_lister_accounts {
+        let mut proceeds_after = payment_account_balance(&seller_payment_receipt_account, is_native)?;
+        if seller_pays_fees {
+            proceeds_after = proceeds_after
+                .checked_sub(trade_state_rent)
+                .ok_or(AuctioneerError::NumericalOverflow)?;
+            if !buyer_receipt_token_account_existed {
+                proceeds_after = proceeds_after
+                    .checked_add(ctx.accounts.buyer_receipt_token_account.lamports())
+                    .ok_or(AuctioneerError::NumericalOverflow)?;
+            }
+        }
+        let proceeds = proceeds_after
+            .checked_sub(proceeds_before)
+            .ok_or(AuctioneerError::NumericalOverflow)?;
+        let co_lister_share = ctx
+            .accounts
+            .listing_config
+            .co_lister_share(proceeds)
+            .ok_or(AuctioneerError::NumericalOverflow)?;
+
+        if co_lister_share > 0 {
+            if is_native {
+                invoke(
+                    &system_instruction::transfer(&seller.key(), &co_lister.key(), co_lister_share),
+                    &[
+                        seller.clone(),
+                        co_lister.clone(),
+                        ctx.accounts.system_program.to_account_info(),
+                    ],
+                )?;
+            } else {
+                token::transfer(
+                    CpiContext::new(
+                        ctx.accounts.token_program.to_account_info(),
+                        token::Transfer {
+                            from: seller_payment_receipt_account.clone(),
+                            to: co_lister_payment_account.clone(),
+                            authority: seller.clone(),
+                        },
+                    ),
+                    co_lister_share,
+                )?;
+            }
+        }
+    }
+
+    settle_bid_fees(
+        &mut ctx.accounts.listing_config,
+        &ctx.accounts.buyer.to_account_info(),
+        &seller,
+        co_lister_accounts.map(|(co_lister, _)| co_lister),
+    )?;
+    settle_listing_fee(
+        &mut ctx.accounts.listing_config,
+        &seller,
+        &ctx.accounts.auction_house_fee_account.to_account_info(),
+    )?;
+
     Ok(())
 }
@@ -1,16 +1,35 @@
 pub mod authorize;
 pub mod bid;
+pub mod bid_history;
 pub mod cancel;
+pub mod co_lister;
 pub mod constants;
 pub mod deposit;
 pub mod errors;
 pub mod execute_sale;
+pub mod migrate;
+pub mod operator;
 pub mod pda;
+pub mod relist;
 pub mod sell;
 pub mod utils;
 pub mod withdraw;
 
-use crate::{authorize::*, bid::*, cancel::*, deposit::*, execute_sale::*, sell::*, withdraw::*};
+use crate::{
+    authorize::*,
+    bid::*,
+    bid_history::*,
+    cancel::*,
+    co_lister::*,
+    deposit::*,
+    execute_sale::*,
+    migrate::*,
+    operator::*,
+    relist::*,
+    sell::config::{AutoRelist, PriceSnapshotConfig},
+    sell::*,
+    withdraw::*,
+};
 
 use anchor_lang::prelude::*;
 
@@ -81,6 +100,28 @@ pub mod auctioneer {
         )
     }
 
+    /// Settle an ended auction with the runner-up bid when the highest bid can no longer be executed.
+    #[inline(never)]
+    pub fn settle_with_fallback<'info>(
+        ctx: Context<'_, '_, '_, 'info, AuctioneerExecuteSale<'info>>,
+        escrow_payment_bump: u8,
+        free_trade_state_bump: u8,
+        program_as_signer_bump: u8,
+        auctioneer_authority_bump: u8,
+        buyer_price: u64,
+        token_size: u64,
+    ) -> Result<()> {
+        execute_sale::settle_with_fallback(
+            ctx,
+            escrow_payment_bump,
+            free_trade_state_bump,
+            program_as_signer_bump,
+            auctioneer_authority_bump,
+            buyer_price,
+            token_size,
+        )
+    }
+
     /// Create a sell bid by creating a `seller_trade_state` account and approving the program as the token delegate.
     pub fn sell<'info>(
         ctx: Context<'_, '_, '_, 'info, AuctioneerSell<'info>>,
@@ -96,6 +137,11 @@ pub mod auctioneer {
         time_ext_period: Option<u32>,
         time_ext_delta: Option<u32>,
         allow_high_bid_cancel: Option<bool>,
+        bid_fee: Option<u64>,
+        listing_fee: Option<u64>,
+        buy_now_price: Option<u64>,
+        auto_relist: Option<AutoRelist>,
+        price_snapshot: Option<PriceSnapshotConfig>,
     ) -> Result<()> {
         auctioneer_sell(
             ctx,
@@ -111,9 +157,23 @@ pub mod auctioneer {
             time_ext_period,
             time_ext_delta,
             allow_high_bid_cancel,
+            bid_fee,
+            listing_fee,
+            buy_now_price,
+            auto_relist,
+            price_snapshot,
         )
     }
 
+    /// Reopen a listing which ended below its reserve price for its next auto-relist round, refunding the bid fee of
+    /// the highest bidder and releasing its bid. Anyone may run it.
+    pub fn relist_after_reserve_failure<'info>(
+        ctx: Context<'_, '_, '_, 'info, RelistAfterReserveFailure<'info>>,
+        token_size: u64,
+    ) -> Result<()> {
+        relist::relist_after_reserve_failure(ctx, token_size)
+    }
+
     /// Create a private buy bid by creating a `buyer_trade_state` account and an `escrow_payment` account and funding the escrow with the necessary SOL or SPL token amount.
     pub fn buy<'info>(
         ctx: Context<'_, '_, '_, 'info, AuctioneerBuy<'info>>,
@@ -132,4 +192,42 @@ pub mod auctioneer {
             token_size,
         )
     }
+
+    /// Add a co-lister to a listing before any bid, both principals sign and the co-lister gets `co_lister_basis_points` of the seller proceeds and forfeited bid fees.
+    pub fn set_co_lister<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetCoLister<'info>>,
+        token_size: u64,
+        co_lister_basis_points: u16,
+    ) -> Result<()> {
+        co_lister::set_co_lister(ctx, token_size, co_lister_basis_points)
+    }
+
+    /// Close a bid history page after the auction is settled, returning the rent to the payer of the page.
+    pub fn close_bid_history<'info>(
+        ctx: Context<'_, '_, '_, 'info, CloseBidHistory<'info>>,
+    ) -> Result<()> {
+        bid_history::close_bid_history(ctx)
+    }
+
+    /// Pause or resume bids and sales of the Auction House as its operator, cancels and withdrawals stay open.
+    pub fn set_operator_paused<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetOperatorPaused<'info>>,
+        paused: bool,
+    ) -> Result<()> {
+        operator::set_operator_paused(ctx, paused)
+    }
+
+    /// Grow a listing config created before its later fields were added to the current size, the payer covering the rent.
+    pub fn migrate_listing_config<'info>(
+        ctx: Context<'_, '_, '_, 'info, MigrateListingConfig<'info>>,
+    ) -> Result<()> {
+        migrate::migrate_listing_config(ctx)
+    }
+
+    /// Rewrite a bid history page started before price snapshots were recorded with the current layout, the payer covering the rent.
+    pub fn migrate_bid_history<'info>(
+        ctx: Context<'_, '_, '_, 'info, MigrateBidHistory<'info>>,
+    ) -> Result<()> {
+        migrate::migrate_bid_history(ctx)
+    }
 }
@@ -0,0 +1,101 @@
+//! Grow listing configs and bid history pages created before their later fields were added to the current size.
+
+use anchor_lang::{prelude::*, AnchorDeserialize, Discriminator};
+
+use solana_program::{program::invoke, system_instruction};
+
+use crate::{bid_history::*, errors::*, sell::config::*};
+
+/// Accounts for the [`migrate_listing_config` handler](fn.migrate_listing_config.html).
+#[derive(Accounts)]
+pub struct MigrateListingConfig<'info> {
+    /// Account paying the rent of the added space, anyone may migrate a listing config.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Not dangerous. Owner checked in constraint and discriminator checked in the handler.
+    /// Listing config to migrate, too short to deserialize as a [`ListingConfig`].
+    #[account(mut, owner = crate::id())]
+    pub listing_config: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Reallocate a listing config to [`LISTING_CONFIG_SIZE`]. The added fields start zeroed, which reads as no bid
+/// count, fee, co-lister, auto-relist or price snapshot, and the accrual starts at the current slot.
+pub fn migrate_listing_config<'info>(
+    ctx: Context<'_, '_, '_, 'info, MigrateListingConfig<'info>>,
+) -> Result<()> {
+    let listing_config = ctx.accounts.listing_config.to_account_info();
+    {
+        let data = listing_config.try_borrow_data()?;
+        if data.len() < 8 || data[..8] != ListingConfig::discriminator() {
+            return Err(ErrorCode::AccountDiscriminatorMismatch.into());
+        }
+        if data.len() >= LISTING_CONFIG_SIZE {
+            return err!(AuctioneerError::ListingConfigAlreadyMigrated);
+        }
+    }
+
+    let rent = Rent::get()?
+        .minimum_balance(LISTING_CONFIG_SIZE)
+        .saturating_sub(listing_config.lamports());
+    if rent > 0 {
+        invoke(
+            &system_instruction::transfer(ctx.accounts.payer.key, listing_config.key, rent),
+            &[
+                ctx.accounts.payer.to_account_info(),
+                listing_config.clone(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+    }
+    listing_config.realloc(LISTING_CONFIG_SIZE, true)?;
+
+    let mut config = ListingConfig::try_deserialize(&mut &listing_config.try_borrow_data()?[..])?;
+    config.version = ListingConfigVersion::V1;
+    if config.last_accrual_slot == 0 {
+        config.last_accrual_slot = Clock::get()?.slot;
+    }
+    config.try_serialize(&mut &mut listing_config.try_borrow_mut_data()?[..])?;
+
+    Ok(())
+}
+
+/// Accounts for the [`migrate_bid_history` handler](fn.migrate_bid_history.html).
+#[derive(Accounts)]
+pub struct MigrateBidHistory<'info> {
+    /// Account paying the rent of the added space, anyone may migrate a bid history page.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Not dangerous. Owner checked in constraint and discriminator checked in the handler.
+    /// Bid history page to migrate, holding records without a price snapshot.
+    #[account(mut, owner = crate::id())]
+    pub bid_history: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Rewrite a bid history page with the current [`BidRecord`] layout, so it can be read and closed as a
+/// [`BidHistory`]. Pages still receiving bids are migrated by the bid itself.
+pub fn migrate_bid_history<'info>(
+    ctx: Context<'_, '_, '_, 'info, MigrateBidHistory<'info>>,
+) -> Result<()> {
+    let bid_history = ctx.accounts.bid_history.to_account_info();
+    {
+        let data = bid_history.try_borrow_data()?;
+        if data.len() < 8 || data[..8] != BidHistory::discriminator() {
+            return Err(ErrorCode::AccountDiscriminatorMismatch.into());
+        }
+        if data.len() >= BID_HISTORY_SIZE {
+            return err!(AuctioneerError::BidHistoryAlreadyMigrated);
+        }
+    }
+
+    grow_legacy_bid_history(
+        &bid_history,
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )
+}
@@ -0,0 +1,104 @@
+//! Let the marketplace operator pause bidding and settlement on an Auction House.
+
+use anchor_lang::{prelude::*, AnchorDeserialize};
+
+use solana_program::{program::invoke_signed, system_instruction};
+
+use mpl_auction_house::{
+    self, constants::PREFIX, program::AuctionHouse as AuctionHouseProgram, AuctionHouse,
+};
+
+use crate::{constants::*, errors::*};
+
+pub const OPERATOR_CONTROL_SIZE: usize = 8 + 32 + 1 + 1;
+
+/// Operator switches of an Auction House, missing until the operator first sets them.
+#[account]
+pub struct OperatorControl {
+    pub auction_house: Pubkey,
+    pub paused: bool,
+    pub bump: u8,
+}
+
+/// Accounts for the [`set_operator_paused` handler](fn.set_operator_paused.html).
+#[derive(Accounts)]
+pub struct SetOperatorPaused<'info> {
+    /// Auction House Program
+    pub auction_house_program: Program<'info, AuctionHouseProgram>,
+
+    /// Auction House authority, acting as the marketplace operator.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(seeds = [PREFIX.as_bytes(), auction_house.creator.as_ref(), auction_house.treasury_mint.as_ref()], seeds::program=auction_house_program, bump = auction_house.bump, has_one = authority)]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Operator control PDA, created on first use.
+    #[account(mut, seeds = [OPERATOR_CONTROL.as_bytes(), auction_house.key().as_ref()], bump)]
+    pub operator_control: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Pause or resume bids and settlements of the Auction House. Cancels and withdrawals are never paused so users can always get their funds back.
+pub fn set_operator_paused<'info>(
+    ctx: Context<'_, '_, '_, 'info, SetOperatorPaused<'info>>,
+    paused: bool,
+) -> Result<()> {
+    let operator_control = &ctx.accounts.operator_control;
+    let auction_house_key = ctx.accounts.auction_house.key();
+    let bump = *ctx
+        .bumps
+        .get("operator_control")
+        .ok_or(AuctioneerError::BumpSeedNotInHashMap)?;
+
+    if operator_control.data_is_empty() {
+        let operator_control_seeds = [
+            OPERATOR_CONTROL.as_bytes(),
+            auction_house_key.as_ref(),
+            &[bump],
+        ];
+        invoke_signed(
+            &system_instruction::create_account(
+                ctx.accounts.authority.key,
+                operator_control.key,
+                ctx.accounts.rent.minimum_balance(OPERATOR_CONTROL_SIZE),
+                OPERATOR_CONTROL_SIZE as u64,
+                &crate::id(),
+            ),
+            &[
+                ctx.accounts.authority.to_account_info(),
+                operator_control.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+            &[&operator_control_seeds],
+        )?;
+    }
+
+    let control = OperatorControl {
+        auction_house: auction_house_key,
+        paused,
+        bump,
+    };
+    let mut data = operator_control.try_borrow_mut_data()?;
+    control.try_serialize(&mut *data)?;
+
+    Ok(())
+}
+
+/// Reject new activity while the operator has paused the Auction House.
+pub fn assert_not_paused(operator_control: &AccountInfo) -> Result<()> {
+    if operator_control.data_is_empty() {
+        return Ok(());
+    }
+
+    let control: Account<OperatorControl> = Account::try_from(operator_control)?;
+    if control.paused {
+        return err!(AuctioneerError::OperatorPaused);
+    }
+
+    Ok(())
+}
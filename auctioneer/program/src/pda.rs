@@ -25,6 +25,24 @@ pub fn find_listing_config_address(
     )
 }
 
+pub fn find_bid_history_address(listing_config: &Pubkey, page: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            BID_HISTORY.as_bytes(),
+            listing_config.as_ref(),
+            &page.to_le_bytes(),
+        ],
+        &id(),
+    )
+}
+
+pub fn find_operator_control_address(auction_house: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[OPERATOR_CONTROL.as_bytes(), auction_house.as_ref()],
+        &id(),
+    )
+}
+
 pub fn find_auctioneer_authority_seeds(auction_house: &Pubkey) -> (Pubkey, u8) {
     Pubkey::find_program_address(&[AUCTIONEER.as_bytes(), auction_house.as_ref()], &id())
 }
@@ -0,0 +1,158 @@
+use anchor_lang::prelude::*;
+
+use mpl_auction_house::{
+    constants::{FEE_PAYER, PREFIX},
+    pda::find_trade_state_address,
+    program::AuctionHouse as AuctionHouseProgram,
+    AuctionHouse,
+};
+
+use solana_program::clock::UnixTimestamp;
+
+use crate::{constants::*, errors::*, sell::config::*, utils::*};
+
+/// Accounts for the [`relist_after_reserve_failure` handler](auctioneer/fn.relist_after_reserve_failure.html).
+#[derive(Accounts)]
+#[instruction(token_size: u64)]
+pub struct RelistAfterReserveFailure<'info> {
+    /// Auction House Program
+    pub auction_house_program: Program<'info, AuctionHouseProgram>,
+
+    /// The Listing Config used for listing settings
+    #[account(
+        mut,
+        seeds=[
+            LISTING_CONFIG.as_bytes(),
+            seller.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_mint.key().as_ref(),
+            &token_size.to_le_bytes()
+        ],
+        bump=listing_config.bump,
+    )]
+    pub listing_config: Account<'info, ListingConfig>,
+
+    /// CHECK: Checked via listing config seeds
+    /// Seller user wallet account, refunded the listing fee not earned.
+    #[account(mut)]
+    pub seller: UncheckedAccount<'info>,
+
+    /// CHECK: Checked via listing config seeds
+    /// Token account where the SPL token is stored.
+    pub token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Checked via listing config seeds
+    /// Token mint account for the SPL token.
+    pub token_mint: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(seeds=[PREFIX.as_bytes(), auction_house.creator.as_ref(), auction_house.treasury_mint.as_ref()], seeds::program=auction_house_program, bump=auction_house.bump, has_one=auction_house_fee_account)]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance fee account.
+    #[account(mut, seeds=[PREFIX.as_bytes(), auction_house.key().as_ref(), FEE_PAYER.as_bytes()], seeds::program=auction_house_program, bump=auction_house.fee_payer_bump)]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account address checked in constraint.
+    /// Seller trade state PDA account encoding the sell order, closed when the listing was canceled.
+    #[account(address=find_trade_state_address(&seller.key(), &auction_house.key(), &token_account.key(), &auction_house.treasury_mint, &token_mint.key(), AUCTIONEER_BUYER_PRICE, token_size).0)]
+    pub seller_trade_state: UncheckedAccount<'info>,
+}
+
+/// Emitted when a listing which ended below its reserve price is reopened for its next round.
+#[event]
+pub struct ListingRelisted {
+    pub listing_config: Pubkey,
+    pub highest_bid_trade_state: Pubkey,
+    pub highest_bid_amount: u64,
+    pub reserve_price: u64,
+    pub start_time: UnixTimestamp,
+    pub end_time: UnixTimestamp,
+    pub rounds_remaining: u8,
+}
+
+/// Reopen a listing which ended below its reserve price for the next round of its auto-relist schedule. The bid fee
+/// of the highest bidder is refunded and its bid released, the bid amount staying in its Auction House escrow until
+/// withdrawn, and the listing fee of the ended round is settled. The remaining accounts are the highest bidder wallet
+/// and its buyer trade state, when the round had a bid.
+pub fn relist_after_reserve_failure<'info>(
+    ctx: Context<'_, '_, '_, 'info, RelistAfterReserveFailure<'info>>,
+    token_size: u64,
+) -> Result<()> {
+    assert_auction_over(&ctx.accounts.listing_config)?;
+
+    let auto_relist = match ctx.accounts.listing_config.auto_relist {
+        Some(auto_relist) if auto_relist.rounds_remaining > 0 => auto_relist,
+        _ => return err!(AuctioneerError::NoRelistRoundLeft),
+    };
+    if ctx.accounts.listing_config.reserve_met() {
+        return err!(AuctioneerError::ReserveMet);
+    }
+    if ctx.accounts.seller_trade_state.data_is_empty() {
+        return err!(AuctioneerError::ListingCanceled);
+    }
+
+    let highest_bid = ctx.accounts.listing_config.highest_bid.clone();
+    if highest_bid.amount > 0 {
+        let (wallet, trade_state) = match ctx.remaining_accounts {
+            [wallet, trade_state] => (wallet, trade_state),
+            _ => return err!(AuctioneerError::InvalidHighestBidAccounts),
+        };
+        let (trade_state_key, _) = find_trade_state_address(
+            wallet.key,
+            &ctx.accounts.auction_house.key(),
+            &ctx.accounts.token_account.key(),
+            &ctx.accounts.auction_house.treasury_mint,
+            &ctx.accounts.token_mint.key(),
+            highest_bid.amount,
+            token_size,
+        );
+        if trade_state.key() != highest_bid.buyer_trade_state
+            || trade_state.key() != trade_state_key
+        {
+            return err!(AuctioneerError::InvalidHighestBidAccounts);
+        }
+
+        refund_bid_fee(&mut ctx.accounts.listing_config, wallet)?;
+    }
+
+    settle_listing_fee(
+        &mut ctx.accounts.listing_config,
+        &ctx.accounts.seller.to_account_info(),
+        &ctx.accounts.auction_house_fee_account.to_account_info(),
+    )?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let listing_config = &mut ctx.accounts.listing_config;
+    listing_config.reserve_price = listing_config
+        .next_reserve_price(&auto_relist)
+        .ok_or(AuctioneerError::NumericalOverflow)?;
+    listing_config.start_time = now;
+    listing_config.end_time = now
+        .checked_add(auto_relist.duration)
+        .ok_or(AuctioneerError::NumericalOverflow)?;
+    listing_config.highest_bid.amount = 0;
+    listing_config.highest_bid.buyer_trade_state = Pubkey::default();
+    listing_config.runner_up_bid.amount = 0;
+    listing_config.runner_up_bid.buyer_trade_state = Pubkey::default();
+    listing_config.bought_now_at = 0;
+    listing_config.auto_relist = Some(AutoRelist {
+        rounds_remaining: auto_relist.rounds_remaining - 1,
+        ..auto_relist
+    });
+
+    emit!(ListingRelisted {
+        listing_config: listing_config.key(),
+        highest_bid_trade_state: highest_bid.buyer_trade_state,
+        highest_bid_amount: highest_bid.amount,
+        reserve_price: listing_config.reserve_price,
+        start_time: listing_config.start_time,
+        end_time: listing_config.end_time,
+        rounds_remaining: auto_relist.rounds_remaining - 1,
+    });
+
+    Ok(())
+}
@@ -2,11 +2,45 @@ use anchor_lang::prelude::*;
 use solana_program::clock::UnixTimestamp;
 
 pub const BID_SIZE: usize = 8 + 1 + 32;
-pub const LISTING_CONFIG_SIZE: usize = 8 + 1 + 8 + 8 + BID_SIZE + 1 + 8 + 8 + 4 + 4 + 1;
+pub const AUTO_RELIST_SIZE: usize = 8 + 2 + 1;
+pub const PRICE_SNAPSHOT_CONFIG_SIZE: usize = 32 + 8;
+pub const LISTING_CONFIG_SIZE: usize = 8
+    + 1
+    + 8
+    + 8
+    + BID_SIZE
+    + 1
+    + 8
+    + 8
+    + 4
+    + 4
+    + 1
+    + 8
+    + 8
+    + 8
+    + BID_SIZE
+    + 32
+    + 2
+    + 8
+    + 8
+    + 8
+    + 16
+    + 8
+    + 8
+    + 8
+    + 1
+    + AUTO_RELIST_SIZE
+    + 1
+    + PRICE_SNAPSHOT_CONFIG_SIZE
+    + 64; // padding
+/// Size of the listing configs created before the fields following `allow_high_bid_cancel` were added.
+pub const LEGACY_LISTING_CONFIG_SIZE: usize = 8 + 1 + 8 + 8 + BID_SIZE + 1 + 8 + 8 + 4 + 4 + 1;
 
 #[derive(AnchorDeserialize, AnchorSerialize, Clone)]
 pub enum ListingConfigVersion {
     V0,
+    /// Sized for every field of the listing config, with room left for later ones.
+    V1,
 }
 
 #[derive(AnchorDeserialize, AnchorSerialize, Clone)]
@@ -16,6 +50,26 @@ pub struct Bid {
     pub buyer_trade_state: Pubkey,
 }
 
+/// Schedule of the rounds a listing is reopened for when it ends below its reserve price.
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy)]
+pub struct AutoRelist {
+    /// Duration of each next round, starting when it is opened.
+    pub duration: i64,
+    /// Part of the reserve price taken off at each next round.
+    pub reserve_decrement_basis_points: u16,
+    pub rounds_remaining: u8,
+}
+
+/// Oracle feed whose price each accepted bid of a listing records, so the bid can later be valued in another
+/// currency from on-chain data alone.
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy)]
+pub struct PriceSnapshotConfig {
+    /// Pyth v2 price account of the treasury mint.
+    pub price_feed: Pubkey,
+    /// Slots the aggregate price may lag behind the bid before the bid is refused.
+    pub max_staleness_slots: u64,
+}
+
 #[account]
 pub struct ListingConfig {
     pub version: ListingConfigVersion,
@@ -28,4 +82,90 @@ pub struct ListingConfig {
     pub time_ext_period: u32,
     pub time_ext_delta: u32,
     pub allow_high_bid_cancel: bool,
+    pub bid_count: u64,
+    pub bid_fee: u64,
+    pub bid_fees_escrowed: u64,
+    pub runner_up_bid: Bid,
+    pub co_lister: Pubkey,
+    pub co_lister_basis_points: u16,
+    /// Lamports charged to the seller for the listing, escrowed until settlement.
+    pub listing_fee: u64,
+    pub listing_fee_escrowed: u64,
+    /// Part of the listing fee earned by the time listed, as of the last accrual.
+    pub listing_fee_accrued: u64,
+    /// Sum of the highest bid amount over the slots it was escrowed, for display.
+    pub escrow_slot_time: u128,
+    pub last_accrual_slot: u64,
+    /// Bid amount ending the auction at once, zero when the listing has no buy-now price.
+    pub buy_now_price: u64,
+    pub bought_now_at: UnixTimestamp,
+    /// Rounds to reopen the listing for when it ends below reserve, in which case bids below reserve are taken.
+    pub auto_relist: Option<AutoRelist>,
+    /// Oracle price recorded with each bid in the bid history, when set.
+    pub price_snapshot: Option<PriceSnapshotConfig>,
+}
+
+impl ListingConfig {
+    pub fn has_co_lister(&self) -> bool {
+        self.co_lister != Pubkey::default()
+    }
+
+    pub fn bought_now(&self) -> bool {
+        self.bought_now_at != 0
+    }
+
+    /// Whether a bid reached the reserve price, no bid at all falling short of it.
+    pub fn reserve_met(&self) -> bool {
+        self.highest_bid.amount > 0 && self.highest_bid.amount >= self.reserve_price
+    }
+
+    /// Whether the auction ended without a bid reaching the reserve price.
+    pub fn reserve_failed(&self, now: UnixTimestamp) -> bool {
+        (now >= self.end_time || self.bought_now()) && !self.reserve_met()
+    }
+
+    /// Accrue the highest bid over the slots since the last accrual and the listing fee earned by the time listed,
+    /// which stops at the buy-now bid when one ended the auction early.
+    pub fn accrue(&mut self, slot: u64, now: UnixTimestamp) -> Option<()> {
+        let slots = slot.saturating_sub(self.last_accrual_slot);
+        self.escrow_slot_time = self
+            .escrow_slot_time
+            .checked_add((self.highest_bid.amount as u128).checked_mul(slots as u128)?)?;
+        self.last_accrual_slot = self.last_accrual_slot.max(slot);
+
+        let listed_until = if self.bought_now() {
+            self.bought_now_at.min(now)
+        } else {
+            now
+        };
+        let duration = self.end_time.checked_sub(self.start_time)?;
+        self.listing_fee_accrued = if duration <= 0 {
+            self.listing_fee
+        } else {
+            let listed = listed_until
+                .checked_sub(self.start_time)?
+                .clamp(0, duration);
+            (self.listing_fee as u128)
+                .checked_mul(listed as u128)?
+                .checked_div(duration as u128)? as u64
+        };
+
+        Some(())
+    }
+
+    /// Reserve price of the next round of an auto-relisted listing.
+    pub fn next_reserve_price(&self, auto_relist: &AutoRelist) -> Option<u64> {
+        let decrement = (self.reserve_price as u128)
+            .checked_mul(auto_relist.reserve_decrement_basis_points as u128)?
+            .checked_div(10000)? as u64;
+        self.reserve_price.checked_sub(decrement)
+    }
+
+    /// Part of `amount` owed to the co-lister.
+    pub fn co_lister_share(&self, amount: u64) -> Option<u64> {
+        (amount as u128)
+            .checked_mul(self.co_lister_basis_points as u128)?
+            .checked_div(10000)
+            .map(|share| share as u64)
+    }
 }
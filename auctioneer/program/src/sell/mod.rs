@@ -1,6 +1,6 @@
 pub mod config;
 
-use crate::{constants::*, errors::*, sell::config::*};
+use crate::{constants::*, errors::*, sell::config::*, utils::*};
 
 use anchor_lang::{prelude::*, AnchorDeserialize, InstructionData};
 use anchor_spl::token::{Token, TokenAccount};
@@ -111,9 +111,21 @@ pub fn auctioneer_sell<'info>(
     time_ext_period: Option<u32>,
     time_ext_delta: Option<u32>,
     allow_high_bid_cancel: Option<bool>,
+    bid_fee: Option<u64>,
+    listing_fee: Option<u64>,
+    buy_now_price: Option<u64>,
+    auto_relist: Option<AutoRelist>,
+    price_snapshot: Option<PriceSnapshotConfig>,
 ) -> Result<()> {
-    ctx.accounts.listing_config.version = ListingConfigVersion::V0;
+    if let Some(auto_relist) = &auto_relist {
+        if auto_relist.duration <= 0 || auto_relist.reserve_decrement_basis_points > 10000 {
+            return err!(AuctioneerError::InvalidAutoRelist);
+        }
+    }
+
+    ctx.accounts.listing_config.version = ListingConfigVersion::V1;
     ctx.accounts.listing_config.highest_bid.version = ListingConfigVersion::V0;
+    ctx.accounts.listing_config.runner_up_bid.version = ListingConfigVersion::V0;
     ctx.accounts.listing_config.start_time = start_time;
     ctx.accounts.listing_config.end_time = end_time;
     ctx.accounts.listing_config.reserve_price = reserve_price.unwrap_or(0);
@@ -121,11 +133,31 @@ pub fn auctioneer_sell<'info>(
     ctx.accounts.listing_config.time_ext_period = time_ext_period.unwrap_or(0);
     ctx.accounts.listing_config.time_ext_delta = time_ext_delta.unwrap_or(0);
     ctx.accounts.listing_config.allow_high_bid_cancel = allow_high_bid_cancel.unwrap_or(false);
+    ctx.accounts.listing_config.bid_count = 0;
+    ctx.accounts.listing_config.bid_fee = bid_fee.unwrap_or(0);
+    ctx.accounts.listing_config.bid_fees_escrowed = 0;
+    ctx.accounts.listing_config.co_lister = Pubkey::default();
+    ctx.accounts.listing_config.co_lister_basis_points = 0;
+    ctx.accounts.listing_config.listing_fee = listing_fee.unwrap_or(0);
+    ctx.accounts.listing_config.listing_fee_escrowed = 0;
+    ctx.accounts.listing_config.listing_fee_accrued = 0;
+    ctx.accounts.listing_config.escrow_slot_time = 0;
+    ctx.accounts.listing_config.last_accrual_slot = Clock::get()?.slot;
+    ctx.accounts.listing_config.buy_now_price = buy_now_price.unwrap_or(0);
+    ctx.accounts.listing_config.bought_now_at = 0;
+    ctx.accounts.listing_config.auto_relist = auto_relist;
+    ctx.accounts.listing_config.price_snapshot = price_snapshot;
     ctx.accounts.listing_config.bump = *ctx
         .bumps
         .get("listing_config")
         .ok_or(AuctioneerError::BumpSeedNotInHashMap)?;
 
+    collect_listing_fee(
+        &mut ctx.accounts.listing_config,
+        &ctx.accounts.wallet.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )?;
+
     let cpi_program = ctx.accounts.auction_house_program.to_account_info();
     let cpi_accounts = AHSell {
         wallet: ctx.accounts.wallet.to_account_info(),
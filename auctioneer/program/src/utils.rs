@@ -1,6 +1,9 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token::accessor;
 
-use crate::{errors::*, sell::config::*};
+use solana_program::{program::invoke, system_instruction};
+
+use crate::{bid_history::PriceSnapshot, constants::*, errors::*, sell::config::*};
 
 pub fn assert_auction_active(listing_config: &Account<ListingConfig>) -> Result<()> {
     let clock = Clock::get()?;
@@ -8,7 +11,7 @@ pub fn assert_auction_active(listing_config: &Account<ListingConfig>) -> Result<
 
     if current_timestamp < listing_config.start_time {
         return err!(AuctioneerError::AuctionNotStarted);
-    } else if current_timestamp > listing_config.end_time {
+    } else if current_timestamp > listing_config.end_time || listing_config.bought_now() {
         return err!(AuctioneerError::AuctionEnded);
     }
 
@@ -19,7 +22,7 @@ pub fn assert_auction_over(listing_config: &Account<ListingConfig>) -> Result<()
     let clock = Clock::get()?;
     let current_timestamp = clock.unix_timestamp;
 
-    if current_timestamp < listing_config.end_time {
+    if current_timestamp < listing_config.end_time && !listing_config.bought_now() {
         return err!(AuctioneerError::AuctionActive);
     }
 
@@ -73,3 +76,221 @@ pub fn process_time_extension(listing_config: &mut Account<ListingConfig>) -> Re
 
     Ok(())
 }
+
+/// Escrow the listing's `bid_fee` from the bidder into the listing config account.
+pub fn collect_bid_fee<'info>(
+    listing_config: &mut Account<'info, ListingConfig>,
+    bidder: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let bid_fee = listing_config.bid_fee;
+    if bid_fee == 0 {
+        return Ok(());
+    }
+
+    invoke(
+        &system_instruction::transfer(bidder.key, &listing_config.key(), bid_fee),
+        &[
+            bidder.clone(),
+            listing_config.to_account_info(),
+            system_program.clone(),
+        ],
+    )?;
+
+    listing_config.bid_fees_escrowed = listing_config
+        .bid_fees_escrowed
+        .checked_add(bid_fee)
+        .ok_or(AuctioneerError::NumericalOverflow)?;
+
+    Ok(())
+}
+
+/// Refund the winning bid's fee to the buyer and pay the fees forfeited by losing bids to the seller, and to the
+/// co-lister for its share when there is one.
+pub fn settle_bid_fees<'info>(
+    listing_config: &mut Account<'info, ListingConfig>,
+    buyer: &AccountInfo<'info>,
+    seller: &AccountInfo<'info>,
+    co_lister: Option<&AccountInfo<'info>>,
+) -> Result<()> {
+    let escrowed = listing_config.bid_fees_escrowed;
+    if escrowed == 0 {
+        return Ok(());
+    }
+
+    let refund = listing_config.bid_fee.min(escrowed);
+    let forfeited = escrowed - refund;
+    let co_lister_share = match co_lister {
+        Some(_) => listing_config
+            .co_lister_share(forfeited)
+            .ok_or(AuctioneerError::NumericalOverflow)?,
+        None => 0,
+    };
+    let listing_config_info = listing_config.to_account_info();
+
+    **listing_config_info.try_borrow_mut_lamports()? = listing_config_info
+        .lamports()
+        .checked_sub(escrowed)
+        .ok_or(AuctioneerError::NumericalOverflow)?;
+    **buyer.try_borrow_mut_lamports()? = buyer
+        .lamports()
+        .checked_add(refund)
+        .ok_or(AuctioneerError::NumericalOverflow)?;
+    **seller.try_borrow_mut_lamports()? = seller
+        .lamports()
+        .checked_add(forfeited - co_lister_share)
+        .ok_or(AuctioneerError::NumericalOverflow)?;
+    if let Some(co_lister) = co_lister {
+        **co_lister.try_borrow_mut_lamports()? = co_lister
+            .lamports()
+            .checked_add(co_lister_share)
+            .ok_or(AuctioneerError::NumericalOverflow)?;
+    }
+
+    listing_config.bid_fees_escrowed = 0;
+
+    Ok(())
+}
+
+/// Refund the bid fee of the highest bid of a round ended below reserve to its bidder. Fees forfeited by outbid bids
+/// stay escrowed until the listing is settled.
+pub fn refund_bid_fee<'info>(
+    listing_config: &mut Account<'info, ListingConfig>,
+    bidder: &AccountInfo<'info>,
+) -> Result<()> {
+    let refund = listing_config.bid_fee.min(listing_config.bid_fees_escrowed);
+    if refund == 0 {
+        return Ok(());
+    }
+
+    let listing_config_info = listing_config.to_account_info();
+    **listing_config_info.try_borrow_mut_lamports()? = listing_config_info
+        .lamports()
+        .checked_sub(refund)
+        .ok_or(AuctioneerError::NumericalOverflow)?;
+    **bidder.try_borrow_mut_lamports()? = bidder
+        .lamports()
+        .checked_add(refund)
+        .ok_or(AuctioneerError::NumericalOverflow)?;
+
+    listing_config.bid_fees_escrowed -= refund;
+
+    Ok(())
+}
+
+/// Escrow the listing's `listing_fee` from the seller into the listing config account.
+pub fn collect_listing_fee<'info>(
+    listing_config: &mut Account<'info, ListingConfig>,
+    seller: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let listing_fee = listing_config.listing_fee;
+    if listing_fee == 0 {
+        return Ok(());
+    }
+
+    invoke(
+        &system_instruction::transfer(seller.key, &listing_config.key(), listing_fee),
+        &[
+            seller.clone(),
+            listing_config.to_account_info(),
+            system_program.clone(),
+        ],
+    )?;
+
+    listing_config.listing_fee_escrowed = listing_fee;
+
+    Ok(())
+}
+
+/// Update the escrow and listing fee bookkeeping of the listing up to the current slot.
+pub fn accrue_listing(listing_config: &mut Account<ListingConfig>) -> Result<()> {
+    let clock = Clock::get()?;
+    listing_config
+        .accrue(clock.slot, clock.unix_timestamp)
+        .ok_or(AuctioneerError::NumericalOverflow)?;
+
+    Ok(())
+}
+
+/// Pay the listing fee earned over the time listed to the Auction House fee account and refund the rest to the
+/// seller, which only remains when a buy-now bid ended the auction early.
+pub fn settle_listing_fee<'info>(
+    listing_config: &mut Account<'info, ListingConfig>,
+    seller: &AccountInfo<'info>,
+    auction_house_fee_account: &AccountInfo<'info>,
+) -> Result<()> {
+    accrue_listing(listing_config)?;
+
+    let escrowed = listing_config.listing_fee_escrowed;
+    if escrowed == 0 {
+        return Ok(());
+    }
+
+    let earned = listing_config.listing_fee_accrued.min(escrowed);
+    let listing_config_info = listing_config.to_account_info();
+
+    **listing_config_info.try_borrow_mut_lamports()? = listing_config_info
+        .lamports()
+        .checked_sub(escrowed)
+        .ok_or(AuctioneerError::NumericalOverflow)?;
+    **auction_house_fee_account.try_borrow_mut_lamports()? = auction_house_fee_account
+        .lamports()
+        .checked_add(earned)
+        .ok_or(AuctioneerError::NumericalOverflow)?;
+    **seller.try_borrow_mut_lamports()? = seller
+        .lamports()
+        .checked_add(escrowed - earned)
+        .ok_or(AuctioneerError::NumericalOverflow)?;
+
+    listing_config.listing_fee_escrowed = 0;
+
+    Ok(())
+}
+
+/// Balance of the account the seller is paid at, lamports for native treasuries and tokens otherwise.
+pub fn payment_account_balance(payment_account: &AccountInfo, is_native: bool) -> Result<u64> {
+    if is_native {
+        Ok(payment_account.lamports())
+    } else if payment_account.data_is_empty() {
+        Ok(0)
+    } else {
+        accessor::amount(payment_account)
+    }
+}
+
+/// Snapshot the aggregate price of the Pyth v2 price account configured for the listing, which must be trading and
+/// published within `max_staleness_slots` of the current slot.
+pub fn snapshot_price(
+    config: &PriceSnapshotConfig,
+    price_feed: &AccountInfo,
+) -> Result<PriceSnapshot> {
+    if price_feed.key() != config.price_feed {
+        return err!(AuctioneerError::InvalidPriceFeed);
+    }
+
+    let data = price_feed.try_borrow_data()?;
+    if data.len() < PYTH_PRICE_ACCOUNT_LEN {
+        return err!(AuctioneerError::InvalidPriceFeed);
+    }
+
+    let read_u32 = |offset: usize| u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap());
+    if read_u32(0) != PYTH_MAGIC
+        || read_u32(8) != PYTH_PRICE_ACCOUNT_TYPE
+        || read_u32(224) != PYTH_STATUS_TRADING
+    {
+        return err!(AuctioneerError::InvalidPriceFeed);
+    }
+
+    let snapshot = PriceSnapshot {
+        price: i64::from_le_bytes(data[208..216].try_into().unwrap()),
+        conf: u64::from_le_bytes(data[216..224].try_into().unwrap()),
+        expo: i32::from_le_bytes(data[20..24].try_into().unwrap()),
+        publish_slot: u64::from_le_bytes(data[232..240].try_into().unwrap()),
+    };
+    if Clock::get()?.slot.saturating_sub(snapshot.publish_slot) > config.max_staleness_slots {
+        return err!(AuctioneerError::StalePriceFeed);
+    }
+
+    Ok(snapshot)
+}
@@ -0,0 +1,184 @@
+#![cfg(feature = "test-bpf")]
+pub mod common;
+pub mod utils;
+
+use common::*;
+use utils::setup_functions::*;
+
+use mpl_auctioneer::{
+    bid_history::BidHistory, pda::find_bid_history_address, sell::config::ListingConfig,
+};
+use std::time::SystemTime;
+
+/// Auction House with a listing of a fresh NFT running for the next minute.
+async fn setup_listing(
+    context: &mut ProgramTestContext,
+) -> (AuctionHouse, Pubkey, Keypair, Metadata, Pubkey, Pubkey) {
+    let (ah, ahkey, authority) = existing_auction_house_test_context(context).await.unwrap();
+    let test_metadata = Metadata::new();
+    airdrop(context, &test_metadata.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs();
+    let ((sell_acc, listing_config_address), sell_tx) = sell(
+        context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        (now - 60) as i64,
+        (now + 60) as i64,
+        None,
+        None,
+        None,
+        None,
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    (
+        ah,
+        ahkey,
+        authority,
+        test_metadata,
+        sell_acc.wallet,
+        listing_config_address,
+    )
+}
+
+#[tokio::test]
+async fn bid_history_records_every_bid() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+    let (ah, ahkey, _, test_metadata, seller, listing_config_address) =
+        setup_listing(&mut context).await;
+
+    let bidders = [Keypair::new(), Keypair::new()];
+    let amounts = [100_000_000, 100_000_001];
+    for (bidder, amount) in bidders.iter().zip(amounts) {
+        airdrop(&mut context, &bidder.pubkey(), TEN_SOL)
+            .await
+            .unwrap();
+        let (_, buy_tx) = buy(
+            &mut context,
+            &ahkey,
+            &ah,
+            &test_metadata,
+            &test_metadata.token.pubkey(),
+            bidder,
+            &seller,
+            &listing_config_address,
+            amount,
+        );
+        context
+            .banks_client
+            .process_transaction(buy_tx)
+            .await
+            .unwrap();
+    }
+
+    let listing = context
+        .banks_client
+        .get_account(listing_config_address)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let config = ListingConfig::try_deserialize(&mut listing.as_ref()).unwrap();
+    assert_eq!(config.bid_count, 2);
+
+    let (bid_history, _) = find_bid_history_address(&listing_config_address, 0);
+    let bid_history_account = context
+        .banks_client
+        .get_account(bid_history)
+        .await
+        .unwrap()
+        .unwrap();
+    let history = BidHistory::try_deserialize(&mut bid_history_account.data.as_ref()).unwrap();
+    assert_eq!(history.listing_config, listing_config_address);
+    assert_eq!(history.page, 0);
+    assert_eq!(history.payer, bidders[0].pubkey());
+    assert_eq!(history.bids.len(), 2);
+    for ((record, bidder), amount) in history.bids.iter().zip(bidders.iter()).zip(amounts) {
+        assert_eq!(record.bidder, bidder.pubkey());
+        assert_eq!(record.amount, amount);
+    }
+    assert!(history.bids[0].slot <= history.bids[1].slot);
+}
+
+#[tokio::test]
+async fn close_bid_history_during_auction_failure() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+    let (ah, ahkey, authority, test_metadata, seller, listing_config_address) =
+        setup_listing(&mut context).await;
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let (bid_acc, buy_tx) = buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &test_metadata.token.pubkey(),
+        &buyer,
+        &seller,
+        &listing_config_address,
+        100_000_000,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    let (_, close_tx) = close_bid_history(
+        &mut context,
+        &listing_config_address,
+        &bid_acc.buyer_trade_state,
+        &buyer.pubkey(),
+        0,
+        &authority,
+    );
+    let result = context
+        .banks_client
+        .process_transaction(close_tx)
+        .await
+        .unwrap_err();
+    assert_error!(result, AUCTION_ACTIVE);
+
+    // Rent of the page only ever goes back to the bidder which paid for it.
+    let (_, close_tx) = close_bid_history(
+        &mut context,
+        &listing_config_address,
+        &bid_acc.buyer_trade_state,
+        &authority.pubkey(),
+        0,
+        &authority,
+    );
+    let result = context
+        .banks_client
+        .process_transaction(close_tx)
+        .await
+        .unwrap_err();
+    assert_error!(result, HAS_ONE_CONSTRAINT_VIOLATION);
+}
@@ -0,0 +1,230 @@
+#![cfg(feature = "test-bpf")]
+pub mod common;
+pub mod utils;
+
+use common::*;
+use mpl_auctioneer::{pda::*, sell::config::ListingConfig};
+use solana_sdk::{instruction::AccountMeta, signature::Keypair};
+use std::time::SystemTime;
+use utils::setup_functions::*;
+
+#[tokio::test]
+async fn set_co_lister_success() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+    // Payer Wallet
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = Metadata::new();
+    airdrop(&mut context, &test_metadata.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            &mut context,
+            "Tests".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+    let ((_, listing_config_address), sell_tx) = sell(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        (SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_secs()) as i64,
+        (SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_secs()
+            + 60) as i64,
+        None,
+        None,
+        None,
+        None,
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let co_lister = Keypair::new();
+    let (_, bad_split_tx) = set_co_lister(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &co_lister,
+        1,
+        10_001,
+    );
+    let result = context
+        .banks_client
+        .process_transaction(bad_split_tx)
+        .await
+        .unwrap_err();
+    assert_error!(result, INVALID_CO_LISTER_SPLIT);
+
+    let (_, co_lister_tx) = set_co_lister(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &co_lister,
+        1,
+        2_500,
+    );
+    context
+        .banks_client
+        .process_transaction(co_lister_tx)
+        .await
+        .unwrap();
+
+    let listing_config_account = context
+        .banks_client
+        .get_account(listing_config_address)
+        .await
+        .expect("Error getting listing config")
+        .expect("Listing config empty");
+    let listing_config =
+        ListingConfig::try_deserialize(&mut listing_config_account.data.as_ref()).unwrap();
+    assert_eq!(listing_config.co_lister, co_lister.pubkey());
+    assert_eq!(listing_config.co_lister_basis_points, 2_500);
+}
+
+#[tokio::test]
+async fn cancel_co_listed_listing_requires_co_lister() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+    // Payer Wallet
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = Metadata::new();
+    airdrop(&mut context, &test_metadata.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            &mut context,
+            "Tests".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+    let ((acc, listing_config_address), sell_tx) = sell(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        (SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_secs()) as i64,
+        (SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_secs()
+            + 60) as i64,
+        None,
+        None,
+        None,
+        None,
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let co_lister = Keypair::new();
+    let (_, co_lister_tx) = set_co_lister(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &co_lister,
+        1,
+        5_000,
+    );
+    context
+        .banks_client
+        .process_transaction(co_lister_tx)
+        .await
+        .unwrap();
+
+    let (auctioneer_authority, aa_bump) = find_auctioneer_authority_seeds(&ahkey);
+    let (auctioneer_pda, _) = find_auctioneer_pda(&ahkey, &auctioneer_authority);
+    let accounts = mpl_auctioneer::accounts::AuctioneerCancel {
+        auction_house_program: mpl_auction_house::id(),
+        listing_config: listing_config_address,
+        seller: acc.wallet,
+        auction_house: ahkey,
+        wallet: test_metadata.token.pubkey(),
+        token_account: acc.token_account,
+        authority: ah.authority,
+        trade_state: acc.seller_trade_state,
+        token_program: spl_token::id(),
+        token_mint: test_metadata.mint.pubkey(),
+        auction_house_fee_account: ah.auction_house_fee_account,
+        auctioneer_authority: auctioneer_authority,
+        ah_auctioneer_pda: auctioneer_pda,
+    }
+    .to_account_metas(None);
+    let data = mpl_auctioneer::instruction::Cancel {
+        auctioneer_authority_bump: aa_bump,
+        buyer_price: u64::MAX,
+        token_size: 1,
+    }
+    .data();
+
+    let seller_only_tx = Transaction::new_signed_with_payer(
+        &[Instruction {
+            program_id: mpl_auctioneer::id(),
+            data: data.clone(),
+            accounts: accounts.clone(),
+        }],
+        Some(&test_metadata.token.pubkey()),
+        &[&test_metadata.token],
+        context.last_blockhash,
+    );
+    let result = context
+        .banks_client
+        .process_transaction(seller_only_tx)
+        .await
+        .unwrap_err();
+    assert_error!(result, MISSING_CO_LISTER_SIGNATURE);
+
+    let mut co_signed_accounts = accounts;
+    co_signed_accounts.push(AccountMeta::new_readonly(co_lister.pubkey(), true));
+    let co_signed_tx = Transaction::new_signed_with_payer(
+        &[Instruction {
+            program_id: mpl_auctioneer::id(),
+            data,
+            accounts: co_signed_accounts,
+        }],
+        Some(&test_metadata.token.pubkey()),
+        &[&test_metadata.token, &co_lister],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(co_signed_tx)
+        .await
+        .unwrap();
+}
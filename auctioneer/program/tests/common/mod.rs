@@ -38,3 +38,16 @@ pub const NOT_HIGH_BIDDER: u32 = 6006;
 pub const BELOW_RESERVE_PRICE: u32 = 6007;
 pub const BELOW_BID_INCREMENT: u32 = 6008;
 pub const CANNOT_CANCEL_HIGHEST_BID: u32 = 6009;
+pub const BID_HISTORY_NOT_SETTLED: u32 = 6010;
+pub const OPERATOR_PAUSED: u32 = 6011;
+pub const NOT_RUNNER_UP_BIDDER: u32 = 6013;
+pub const HIGHEST_BID_SETTLEABLE: u32 = 6014;
+pub const INVALID_CO_LISTER_SPLIT: u32 = 6016;
+pub const MISSING_CO_LISTER_SIGNATURE: u32 = 6017;
+pub const INVALID_AUTO_RELIST: u32 = 6020;
+pub const RESERVE_MET: u32 = 6021;
+pub const NO_RELIST_ROUND_LEFT: u32 = 6022;
+pub const INVALID_PRICE_FEED: u32 = 6024;
+pub const STALE_PRICE_FEED: u32 = 6025;
+pub const LISTING_CONFIG_ALREADY_MIGRATED: u32 = 6026;
+pub const BID_HISTORY_ALREADY_MIGRATED: u32 = 6027;
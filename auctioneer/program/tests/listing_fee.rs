@@ -0,0 +1,229 @@
+#![cfg(feature = "test-bpf")]
+pub mod common;
+pub mod utils;
+
+use common::*;
+use mpl_auction_house::constants::TRADE_STATE_WITH_RENT_PAYER_SIZE;
+use mpl_auctioneer::sell::config::ListingConfig;
+use std::{assert_eq, time::SystemTime};
+use utils::setup_functions::*;
+
+const LISTING_FEE: u64 = 100_000_000;
+const BUY_NOW_PRICE: u64 = 200_000_000;
+
+async fn list_with_buy_now(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+) -> (mpl_auctioneer::accounts::AuctioneerSell, Pubkey) {
+    airdrop(context, &test_metadata.token.pubkey(), 10_000_000_000)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs();
+    let ((sell_acc, listing_config_address), sell_tx) = sell_with_fees(
+        context,
+        ahkey,
+        ah,
+        test_metadata,
+        (now - 60) as i64,
+        (now + 3600) as i64,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some(LISTING_FEE),
+        Some(BUY_NOW_PRICE),
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    (sell_acc, listing_config_address)
+}
+
+async fn listing_config(context: &mut ProgramTestContext, address: Pubkey) -> ListingConfig {
+    let listing = context
+        .banks_client
+        .get_account(address)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    ListingConfig::try_deserialize(&mut listing.as_ref()).unwrap()
+}
+
+#[tokio::test]
+async fn buy_now_settles_early_and_refunds_listing_fee() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = Metadata::new();
+    let (sell_acc, listing_config_address) =
+        list_with_buy_now(&mut context, &ahkey, &ah, &test_metadata).await;
+
+    let config = listing_config(&mut context, listing_config_address).await;
+    assert_eq!(config.listing_fee, LISTING_FEE);
+    assert_eq!(config.listing_fee_escrowed, LISTING_FEE);
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), 10_000_000_000)
+        .await
+        .unwrap();
+    let (bid_acc, buy_tx) = buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &test_metadata.token.pubkey(),
+        &buyer,
+        &sell_acc.wallet,
+        &listing_config_address,
+        BUY_NOW_PRICE,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    let config = listing_config(&mut context, listing_config_address).await;
+    assert!(config.bought_now());
+
+    // The auction is over before its end time, so it can be settled right away.
+    let buyer_token_account =
+        get_associated_token_address(&buyer.pubkey(), &test_metadata.mint.pubkey());
+    let (_, execute_sale_tx) = execute_sale(
+        &mut context,
+        &listing_config_address,
+        &ahkey,
+        &ah,
+        &authority,
+        &test_metadata,
+        &buyer.pubkey(),
+        &test_metadata.token.pubkey(),
+        &sell_acc.token_account,
+        &sell_acc.seller_trade_state,
+        &bid_acc.buyer_trade_state,
+        1,
+        BUY_NOW_PRICE,
+    );
+    airdrop(&mut context, &ah.auction_house_fee_account, 10_000_000_000)
+        .await
+        .unwrap();
+    let seller_before = context
+        .banks_client
+        .get_balance(test_metadata.token.pubkey())
+        .await
+        .unwrap();
+    context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap();
+    let seller_after = context
+        .banks_client
+        .get_balance(test_metadata.token.pubkey())
+        .await
+        .unwrap();
+
+    let config = listing_config(&mut context, listing_config_address).await;
+    assert_eq!(config.listing_fee_escrowed, 0);
+    assert!(config.listing_fee_accrued < LISTING_FEE);
+    assert!(config.escrow_slot_time >= BUY_NOW_PRICE as u128);
+
+    let proceeds = BUY_NOW_PRICE - (ah.seller_fee_basis_points as u64 * BUY_NOW_PRICE) / 10000;
+    let refund = LISTING_FEE - config.listing_fee_accrued;
+    let trade_state_rent = context
+        .banks_client
+        .get_rent()
+        .await
+        .unwrap()
+        .minimum_balance(TRADE_STATE_WITH_RENT_PAYER_SIZE);
+    assert_eq!(
+        seller_before + proceeds + refund + trade_state_rent,
+        seller_after
+    );
+    assert!(context
+        .banks_client
+        .get_account(buyer_token_account)
+        .await
+        .unwrap()
+        .is_some());
+}
+
+#[tokio::test]
+async fn bid_after_buy_now_fails() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = Metadata::new();
+    let (sell_acc, listing_config_address) =
+        list_with_buy_now(&mut context, &ahkey, &ah, &test_metadata).await;
+
+    let buyer0 = Keypair::new();
+    airdrop(&mut context, &buyer0.pubkey(), 10_000_000_000)
+        .await
+        .unwrap();
+    let (_, buy_tx) = buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &test_metadata.token.pubkey(),
+        &buyer0,
+        &sell_acc.wallet,
+        &listing_config_address,
+        BUY_NOW_PRICE,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    let buyer1 = Keypair::new();
+    airdrop(&mut context, &buyer1.pubkey(), 10_000_000_000)
+        .await
+        .unwrap();
+    let (_, buy_tx) = buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &test_metadata.token.pubkey(),
+        &buyer1,
+        &sell_acc.wallet,
+        &listing_config_address,
+        BUY_NOW_PRICE + 1,
+    );
+    let error = context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, AUCTION_ENDED);
+}
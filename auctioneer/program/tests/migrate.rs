@@ -0,0 +1,324 @@
+#![cfg(feature = "test-bpf")]
+pub mod common;
+pub mod utils;
+
+use anchor_lang::Discriminator;
+use common::*;
+use mpl_auctioneer::{
+    bid_history::{BidHistory, BID_HISTORY_SIZE, LEGACY_BID_HISTORY_SIZE},
+    pda::find_bid_history_address,
+    sell::config::{
+        ListingConfig, ListingConfigVersion, LEGACY_LISTING_CONFIG_SIZE, LISTING_CONFIG_SIZE,
+    },
+};
+use solana_sdk::account::{Account, AccountSharedData};
+use std::time::SystemTime;
+use utils::setup_functions::*;
+
+const LEGACY_BID_AMOUNT: u64 = 50_000_000;
+const LEGACY_BID_SLOT: u64 = 7;
+
+/// Bid history page as written before price snapshots were recorded, holding a single bid of `bidder`.
+fn legacy_bid_history_account(
+    rent: &Rent,
+    listing_config: &Pubkey,
+    payer: &Pubkey,
+    bidder: &Pubkey,
+) -> Account {
+    let mut data = BidHistory::discriminator().to_vec();
+    data.extend_from_slice(listing_config.as_ref());
+    data.extend_from_slice(&0u64.to_le_bytes());
+    data.extend_from_slice(payer.as_ref());
+    data.push(255);
+    data.extend_from_slice(&1u32.to_le_bytes());
+    data.extend_from_slice(&LEGACY_BID_AMOUNT.to_le_bytes());
+    data.extend_from_slice(bidder.as_ref());
+    data.extend_from_slice(&LEGACY_BID_SLOT.to_le_bytes());
+    data.resize(LEGACY_BID_HISTORY_SIZE, 0);
+
+    Account {
+        lamports: rent.minimum_balance(LEGACY_BID_HISTORY_SIZE),
+        data,
+        owner: mpl_auctioneer::id(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+#[tokio::test]
+async fn migrate_legacy_listing_config_success() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = Metadata::new();
+    airdrop(&mut context, &test_metadata.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            &mut context,
+            "Tests".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+    let ((_, listing_config_address), sell_tx) = sell(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        (SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_secs()) as i64,
+        (SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_secs()
+            + 60) as i64,
+        Some(100_000_000),
+        None,
+        None,
+        None,
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    // Shrink the listing config back to the size it had before its later fields were added.
+    let mut listing_config_account = context
+        .banks_client
+        .get_account(listing_config_address)
+        .await
+        .expect("Error getting listing config")
+        .expect("Listing config empty");
+    let rent = context.banks_client.get_rent().await.unwrap();
+    listing_config_account
+        .data
+        .truncate(LEGACY_LISTING_CONFIG_SIZE);
+    listing_config_account.lamports = rent.minimum_balance(LEGACY_LISTING_CONFIG_SIZE);
+    context.set_account(
+        &listing_config_address,
+        &AccountSharedData::from(listing_config_account),
+    );
+
+    let payer = Keypair::new();
+    airdrop(&mut context, &payer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let (_, migrate_tx) = migrate_listing_config(&mut context, &listing_config_address, &payer);
+    context
+        .banks_client
+        .process_transaction(migrate_tx)
+        .await
+        .unwrap();
+
+    let listing_config_account = context
+        .banks_client
+        .get_account(listing_config_address)
+        .await
+        .expect("Error getting listing config")
+        .expect("Listing config empty");
+    assert_eq!(listing_config_account.data.len(), LISTING_CONFIG_SIZE);
+    assert_eq!(
+        listing_config_account.lamports,
+        rent.minimum_balance(LISTING_CONFIG_SIZE)
+    );
+    let listing_config =
+        ListingConfig::try_deserialize(&mut listing_config_account.data.as_ref()).unwrap();
+    assert!(matches!(listing_config.version, ListingConfigVersion::V1));
+    assert_eq!(listing_config.reserve_price, 100_000_000);
+    assert_eq!(listing_config.bid_count, 0);
+    assert!(!listing_config.has_co_lister());
+    assert!(listing_config.auto_relist.is_none());
+    assert!(listing_config.price_snapshot.is_none());
+    assert!(listing_config.last_accrual_slot > 0);
+
+    let second_payer = Keypair::new();
+    airdrop(&mut context, &second_payer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let (_, migrate_tx) =
+        migrate_listing_config(&mut context, &listing_config_address, &second_payer);
+    let result = context
+        .banks_client
+        .process_transaction(migrate_tx)
+        .await
+        .unwrap_err();
+    assert_error!(result, LISTING_CONFIG_ALREADY_MIGRATED);
+}
+
+#[tokio::test]
+async fn migrate_legacy_bid_history_success() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let listing_config = Pubkey::new_unique();
+    let page_payer = Pubkey::new_unique();
+    let bidder = Pubkey::new_unique();
+    let (bid_history_address, _) = find_bid_history_address(&listing_config, 0);
+    context.set_account(
+        &bid_history_address,
+        &AccountSharedData::from(legacy_bid_history_account(
+            &rent,
+            &listing_config,
+            &page_payer,
+            &bidder,
+        )),
+    );
+
+    let payer = Keypair::new();
+    airdrop(&mut context, &payer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let (_, migrate_tx) = migrate_bid_history(&mut context, &bid_history_address, &payer);
+    context
+        .banks_client
+        .process_transaction(migrate_tx)
+        .await
+        .unwrap();
+
+    let bid_history_account = context
+        .banks_client
+        .get_account(bid_history_address)
+        .await
+        .expect("Error getting bid history")
+        .expect("Bid history empty");
+    assert_eq!(bid_history_account.data.len(), BID_HISTORY_SIZE);
+    assert_eq!(
+        bid_history_account.lamports,
+        rent.minimum_balance(BID_HISTORY_SIZE)
+    );
+    let history = BidHistory::try_deserialize(&mut bid_history_account.data.as_ref()).unwrap();
+    assert_eq!(history.listing_config, listing_config);
+    assert_eq!(history.payer, page_payer);
+    assert_eq!(history.bump, 255);
+    assert_eq!(history.bids.len(), 1);
+    assert_eq!(history.bids[0].amount, LEGACY_BID_AMOUNT);
+    assert_eq!(history.bids[0].bidder, bidder);
+    assert_eq!(history.bids[0].slot, LEGACY_BID_SLOT);
+    assert!(history.bids[0].price_snapshot.is_none());
+
+    let second_payer = Keypair::new();
+    airdrop(&mut context, &second_payer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let (_, migrate_tx) = migrate_bid_history(&mut context, &bid_history_address, &second_payer);
+    let result = context
+        .banks_client
+        .process_transaction(migrate_tx)
+        .await
+        .unwrap_err();
+    assert_error!(result, BID_HISTORY_ALREADY_MIGRATED);
+}
+
+#[tokio::test]
+async fn bid_on_legacy_bid_history_success() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = Metadata::new();
+    airdrop(&mut context, &test_metadata.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            &mut context,
+            "Tests".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+    let ((sell_acc, listing_config_address), sell_tx) = sell(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        (SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_secs()
+            - 60) as i64,
+        (SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_secs()
+            + 60) as i64,
+        None,
+        None,
+        None,
+        None,
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    // The first page of the listing was started before price snapshots were recorded.
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let page_payer = Pubkey::new_unique();
+    let legacy_bidder = Pubkey::new_unique();
+    let (bid_history_address, _) = find_bid_history_address(&listing_config_address, 0);
+    context.set_account(
+        &bid_history_address,
+        &AccountSharedData::from(legacy_bid_history_account(
+            &rent,
+            &listing_config_address,
+            &page_payer,
+            &legacy_bidder,
+        )),
+    );
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let (_, buy_tx) = buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &test_metadata.token.pubkey(),
+        &buyer,
+        &sell_acc.wallet,
+        &listing_config_address,
+        100_000_000,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    let bid_history_account = context
+        .banks_client
+        .get_account(bid_history_address)
+        .await
+        .expect("Error getting bid history")
+        .expect("Bid history empty");
+    assert_eq!(bid_history_account.data.len(), BID_HISTORY_SIZE);
+    let history = BidHistory::try_deserialize(&mut bid_history_account.data.as_ref()).unwrap();
+    assert_eq!(history.payer, page_payer);
+    assert_eq!(history.bids.len(), 2);
+    assert_eq!(history.bids[0].amount, LEGACY_BID_AMOUNT);
+    assert_eq!(history.bids[0].bidder, legacy_bidder);
+    assert!(history.bids[0].price_snapshot.is_none());
+    assert_eq!(history.bids[1].amount, 100_000_000);
+    assert_eq!(history.bids[1].bidder, buyer.pubkey());
+}
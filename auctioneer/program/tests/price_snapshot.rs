@@ -0,0 +1,218 @@
+#![cfg(feature = "test-bpf")]
+pub mod common;
+pub mod utils;
+
+use common::*;
+use mpl_auctioneer::{
+    bid_history::BidHistory, pda::find_bid_history_address, sell::config::PriceSnapshotConfig,
+};
+use solana_sdk::account::Account;
+use std::time::SystemTime;
+use utils::setup_functions::*;
+
+const PRICE: i64 = 2_150_000_000;
+const CONF: u64 = 1_500_000;
+const MAX_STALENESS_SLOTS: u64 = 25;
+
+/// Pyth v2 price account of a trading SOL/USD feed quoted with 8 decimals
+fn price_feed_account(publish_slot: u64) -> Account {
+    let mut data = vec![0; 3312];
+    data[0..4].copy_from_slice(&0xa1b2c3d4u32.to_le_bytes());
+    data[4..8].copy_from_slice(&2u32.to_le_bytes());
+    data[8..12].copy_from_slice(&3u32.to_le_bytes());
+    data[20..24].copy_from_slice(&(-8i32).to_le_bytes());
+    data[208..216].copy_from_slice(&PRICE.to_le_bytes());
+    data[216..224].copy_from_slice(&CONF.to_le_bytes());
+    data[224..228].copy_from_slice(&1u32.to_le_bytes());
+    data[232..240].copy_from_slice(&publish_slot.to_le_bytes());
+
+    Account {
+        lamports: 1_000_000_000,
+        data,
+        owner: Pubkey::new_unique(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+async fn list_with_price_snapshot(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    price_feed: &Pubkey,
+) -> (mpl_auctioneer::accounts::AuctioneerSell, Pubkey) {
+    airdrop(context, &test_metadata.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs();
+    let ((sell_acc, listing_config_address), sell_tx) = sell_with_price_snapshot(
+        context,
+        ahkey,
+        ah,
+        test_metadata,
+        (now - 60) as i64,
+        (now + 3600) as i64,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some(PriceSnapshotConfig {
+            price_feed: *price_feed,
+            max_staleness_slots: MAX_STALENESS_SLOTS,
+        }),
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    (sell_acc, listing_config_address)
+}
+
+async fn bid(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    sell_acc: &mpl_auctioneer::accounts::AuctioneerSell,
+    listing_config_address: &Pubkey,
+    price_feed: Option<&Pubkey>,
+) -> std::result::Result<Keypair, TransportError> {
+    let buyer = Keypair::new();
+    airdrop(context, &buyer.pubkey(), TEN_SOL).await.unwrap();
+    let (_, buy_tx) = buy_with_price_feed(
+        context,
+        ahkey,
+        ah,
+        test_metadata,
+        &test_metadata.token.pubkey(),
+        &buyer,
+        &sell_acc.wallet,
+        listing_config_address,
+        100_000_000,
+        price_feed,
+    );
+    context.banks_client.process_transaction(buy_tx).await?;
+
+    Ok(buyer)
+}
+
+#[tokio::test]
+async fn bid_records_price_snapshot() {
+    let price_feed = Pubkey::new_unique();
+    let mut program_test = auctioneer_program_test();
+    program_test.add_account(price_feed, price_feed_account(0));
+    let mut context = program_test.start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = Metadata::new();
+    let (sell_acc, listing_config_address) =
+        list_with_price_snapshot(&mut context, &ahkey, &ah, &test_metadata, &price_feed).await;
+
+    let buyer = bid(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &sell_acc,
+        &listing_config_address,
+        Some(&price_feed),
+    )
+    .await
+    .unwrap();
+
+    let bid_history_account = context
+        .banks_client
+        .get_account(find_bid_history_address(&listing_config_address, 0).0)
+        .await
+        .unwrap()
+        .unwrap();
+    let history = BidHistory::try_deserialize(&mut bid_history_account.data.as_ref()).unwrap();
+    assert_eq!(history.bids.len(), 1);
+    assert_eq!(history.bids[0].bidder, buyer.pubkey());
+    let snapshot = history.bids[0].price_snapshot.unwrap();
+    assert_eq!(snapshot.price, PRICE);
+    assert_eq!(snapshot.conf, CONF);
+    assert_eq!(snapshot.expo, -8);
+    assert_eq!(snapshot.publish_slot, 0);
+}
+
+#[tokio::test]
+async fn bid_without_price_feed_fails() {
+    let price_feed = Pubkey::new_unique();
+    let mut program_test = auctioneer_program_test();
+    program_test.add_account(price_feed, price_feed_account(0));
+    let mut context = program_test.start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = Metadata::new();
+    let (sell_acc, listing_config_address) =
+        list_with_price_snapshot(&mut context, &ahkey, &ah, &test_metadata, &price_feed).await;
+
+    let error = bid(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &sell_acc,
+        &listing_config_address,
+        None,
+    )
+    .await
+    .unwrap_err();
+    assert_error!(error, INVALID_PRICE_FEED);
+}
+
+#[tokio::test]
+async fn bid_with_stale_price_feed_fails() {
+    let price_feed = Pubkey::new_unique();
+    let mut program_test = auctioneer_program_test();
+    program_test.add_account(price_feed, price_feed_account(0));
+    let mut context = program_test.start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = Metadata::new();
+    let (sell_acc, listing_config_address) =
+        list_with_price_snapshot(&mut context, &ahkey, &ah, &test_metadata, &price_feed).await;
+
+    context.warp_to_slot(MAX_STALENESS_SLOTS + 100).unwrap();
+    let error = bid(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &sell_acc,
+        &listing_config_address,
+        Some(&price_feed),
+    )
+    .await
+    .unwrap_err();
+    assert_error!(error, STALE_PRICE_FEED);
+}
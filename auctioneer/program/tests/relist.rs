@@ -0,0 +1,288 @@
+#![cfg(feature = "test-bpf")]
+pub mod common;
+pub mod utils;
+
+use common::*;
+use mpl_auctioneer::sell::config::{AutoRelist, ListingConfig};
+use std::time::SystemTime;
+use utils::setup_functions::*;
+
+const RESERVE_PRICE: u64 = 200_000_000;
+const BID_FEE: u64 = 10_000_000;
+
+async fn list_with_auto_relist(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    reserve_decrement_basis_points: u16,
+) -> std::result::Result<(mpl_auctioneer::accounts::AuctioneerSell, Pubkey), TransportError> {
+    airdrop(context, &test_metadata.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs();
+    let ((sell_acc, listing_config_address), sell_tx) = sell_with_auto_relist(
+        context,
+        ahkey,
+        ah,
+        test_metadata,
+        (now - 60) as i64,
+        (now + 60) as i64,
+        Some(RESERVE_PRICE),
+        None,
+        None,
+        None,
+        None,
+        Some(BID_FEE),
+        None,
+        None,
+        Some(AutoRelist {
+            duration: 3600,
+            reserve_decrement_basis_points,
+            rounds_remaining: 1,
+        }),
+    );
+    context.banks_client.process_transaction(sell_tx).await?;
+
+    Ok((sell_acc, listing_config_address))
+}
+
+async fn bid(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    sell_acc: &mpl_auctioneer::accounts::AuctioneerSell,
+    listing_config_address: &Pubkey,
+    price: u64,
+) -> (Keypair, mpl_auctioneer::accounts::AuctioneerBuy) {
+    let buyer = Keypair::new();
+    airdrop(context, &buyer.pubkey(), TEN_SOL).await.unwrap();
+    let (bid_acc, buy_tx) = buy(
+        context,
+        ahkey,
+        ah,
+        test_metadata,
+        &test_metadata.token.pubkey(),
+        &buyer,
+        &sell_acc.wallet,
+        listing_config_address,
+        price,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    (buyer, bid_acc)
+}
+
+async fn listing_config(context: &mut ProgramTestContext, address: Pubkey) -> ListingConfig {
+    let listing = context
+        .banks_client
+        .get_account(address)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    ListingConfig::try_deserialize(&mut listing.as_ref()).unwrap()
+}
+
+#[tokio::test]
+async fn relist_after_reserve_failure_success() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = Metadata::new();
+    let (sell_acc, listing_config_address) =
+        list_with_auto_relist(&mut context, &ahkey, &ah, &test_metadata, 5000)
+            .await
+            .unwrap();
+
+    // Bids below reserve are taken, but never settle.
+    let (buyer, bid_acc) = bid(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &sell_acc,
+        &listing_config_address,
+        RESERVE_PRICE / 2,
+    )
+    .await;
+    context.warp_to_slot(120 * 400).unwrap();
+    let (_, execute_sale_tx) = execute_sale(
+        &mut context,
+        &listing_config_address,
+        &ahkey,
+        &ah,
+        &authority,
+        &test_metadata,
+        &buyer.pubkey(),
+        &test_metadata.token.pubkey(),
+        &sell_acc.token_account,
+        &sell_acc.seller_trade_state,
+        &bid_acc.buyer_trade_state,
+        1,
+        RESERVE_PRICE / 2,
+    );
+    let error = context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, BELOW_RESERVE_PRICE);
+
+    // Anyone may open the next round.
+    let payer = Keypair::new();
+    airdrop(&mut context, &payer.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+    let buyer_before = context
+        .banks_client
+        .get_balance(buyer.pubkey())
+        .await
+        .unwrap();
+    let (_, relist_tx) = relist_after_reserve_failure(
+        &mut context,
+        &listing_config_address,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &sell_acc.token_account,
+        1,
+        Some((&buyer.pubkey(), &bid_acc.buyer_trade_state)),
+        &payer,
+    );
+    context
+        .banks_client
+        .process_transaction(relist_tx)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        context
+            .banks_client
+            .get_balance(buyer.pubkey())
+            .await
+            .unwrap(),
+        buyer_before + BID_FEE
+    );
+    let config = listing_config(&mut context, listing_config_address).await;
+    assert_eq!(config.reserve_price, RESERVE_PRICE / 2);
+    assert_eq!(config.highest_bid.amount, 0);
+    assert_eq!(config.bid_fees_escrowed, 0);
+    assert_eq!(config.end_time - config.start_time, 3600);
+    assert_eq!(config.auto_relist.unwrap().rounds_remaining, 0);
+
+    // The next round is open for bids at the reduced reserve.
+    bid(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &sell_acc,
+        &listing_config_address,
+        RESERVE_PRICE / 2 + 1,
+    )
+    .await;
+    let config = listing_config(&mut context, listing_config_address).await;
+    assert!(config.reserve_met());
+
+    let (_, relist_tx) = relist_after_reserve_failure(
+        &mut context,
+        &listing_config_address,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &sell_acc.token_account,
+        1,
+        None,
+        &payer,
+    );
+    let error = context
+        .banks_client
+        .process_transaction(relist_tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, AUCTION_ACTIVE);
+}
+
+#[tokio::test]
+async fn relist_after_reserve_met_fails() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = Metadata::new();
+    let (sell_acc, listing_config_address) =
+        list_with_auto_relist(&mut context, &ahkey, &ah, &test_metadata, 5000)
+            .await
+            .unwrap();
+    let (buyer, bid_acc) = bid(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &sell_acc,
+        &listing_config_address,
+        RESERVE_PRICE,
+    )
+    .await;
+    context.warp_to_slot(120 * 400).unwrap();
+
+    let payer = Keypair::new();
+    airdrop(&mut context, &payer.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+    let (_, relist_tx) = relist_after_reserve_failure(
+        &mut context,
+        &listing_config_address,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &sell_acc.token_account,
+        1,
+        Some((&buyer.pubkey(), &bid_acc.buyer_trade_state)),
+        &payer,
+    );
+    let error = context
+        .banks_client
+        .process_transaction(relist_tx)
+        .await
+        .unwrap_err();
+    assert_error!(error, RESERVE_MET);
+}
+
+#[tokio::test]
+async fn sell_invalid_auto_relist_fails() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = Metadata::new();
+
+    let error = list_with_auto_relist(&mut context, &ahkey, &ah, &test_metadata, 10001)
+        .await
+        .unwrap_err();
+    assert_error!(error, INVALID_AUTO_RELIST);
+}
@@ -0,0 +1,123 @@
+#![cfg(feature = "test-bpf")]
+pub mod common;
+pub mod utils;
+
+use common::*;
+use utils::{helpers::default_scopes, setup_functions::*};
+
+use mpl_testing_utils::{solana::airdrop, utils::Metadata};
+use solana_sdk::signer::Signer;
+use std::time::SystemTime;
+
+async fn create_listed_metadata(context: &mut ProgramTestContext) -> Metadata {
+    let test_metadata = Metadata::new();
+    airdrop(context, &test_metadata.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+    test_metadata
+}
+
+fn sell_window() -> (i64, i64) {
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs() as i64;
+    (now, now + 60)
+}
+
+#[tokio::test]
+async fn sell_requires_sell_scope() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let scopes = default_scopes()
+        .into_iter()
+        .filter(|scope| *scope != AuthorityScope::Sell)
+        .collect();
+    update_auctioneer_scopes(&mut context, &ahkey, &authority, scopes)
+        .await
+        .unwrap();
+    let test_metadata = create_listed_metadata(&mut context).await;
+
+    let (start_time, end_time) = sell_window();
+    let (_, sell_tx) = sell(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        start_time,
+        end_time,
+        None,
+        None,
+        None,
+        None,
+        None,
+    );
+    let result = context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap_err();
+    assert_error!(result, MISSING_AUCTIONEER_SCOPE);
+}
+
+#[tokio::test]
+async fn sell_succeeds_once_sell_scope_restored() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    update_auctioneer_scopes(
+        &mut context,
+        &ahkey,
+        &authority,
+        vec![AuthorityScope::Deposit],
+    )
+    .await
+    .unwrap();
+    update_auctioneer_scopes(&mut context, &ahkey, &authority, default_scopes())
+        .await
+        .unwrap();
+    let test_metadata = create_listed_metadata(&mut context).await;
+
+    let (start_time, end_time) = sell_window();
+    let ((acc, _), sell_tx) = sell(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        start_time,
+        end_time,
+        None,
+        None,
+        None,
+        None,
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+    let sts = context
+        .banks_client
+        .get_account(acc.seller_trade_state)
+        .await
+        .expect("Error Getting Trade State")
+        .expect("Trade State Empty");
+    assert_eq!(sts.data.len(), 1);
+}
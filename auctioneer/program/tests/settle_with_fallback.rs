@@ -0,0 +1,309 @@
+#![cfg(feature = "test-bpf")]
+pub mod common;
+pub mod utils;
+
+use common::*;
+use mpl_auctioneer::sell::config::ListingConfig;
+use solana_sdk::program_pack::Pack;
+use std::time::SystemTime;
+use utils::setup_functions::*;
+
+use mpl_auctioneer::accounts::{AuctioneerBuy, AuctioneerSell};
+
+async fn two_bid_auction() -> (
+    ProgramTestContext,
+    AuctionHouse,
+    Pubkey,
+    Keypair,
+    Metadata,
+    AuctioneerSell,
+    Pubkey,
+    (Keypair, AuctioneerBuy),
+    (Keypair, AuctioneerBuy),
+) {
+    let mut context = auctioneer_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = Metadata::new();
+    airdrop(&mut context, &test_metadata.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            &mut context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs();
+    let ((sell_acc, listing_config_address), sell_tx) = sell(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        (now - 60) as i64,
+        (now + 60) as i64,
+        None,
+        None,
+        None,
+        None,
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let runner_up = Keypair::new();
+    airdrop(&mut context, &runner_up.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let (runner_up_bid, buy_tx) = buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &test_metadata.token.pubkey(),
+        &runner_up,
+        &sell_acc.wallet,
+        &listing_config_address,
+        100_000_000,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    let winner = Keypair::new();
+    airdrop(&mut context, &winner.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let (winner_bid, buy_tx) = buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &test_metadata.token.pubkey(),
+        &winner,
+        &sell_acc.wallet,
+        &listing_config_address,
+        100_000_001,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+    airdrop(&mut context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+
+    (
+        context,
+        ah,
+        ahkey,
+        authority,
+        test_metadata,
+        sell_acc,
+        listing_config_address,
+        (winner, winner_bid),
+        (runner_up, runner_up_bid),
+    )
+}
+
+#[tokio::test]
+async fn settle_with_fallback_success() {
+    let (
+        mut context,
+        ah,
+        ahkey,
+        authority,
+        test_metadata,
+        sell_acc,
+        listing_config_address,
+        (winner, winner_bid),
+        (runner_up, runner_up_bid),
+    ) = two_bid_auction().await;
+
+    let listing_config_account = context
+        .banks_client
+        .get_account(listing_config_address)
+        .await
+        .unwrap()
+        .unwrap();
+    let listing_config =
+        ListingConfig::try_deserialize(&mut listing_config_account.data.as_ref()).unwrap();
+    assert_eq!(listing_config.highest_bid.amount, 100_000_001);
+    assert_eq!(listing_config.runner_up_bid.amount, 100_000_000);
+    assert_eq!(
+        listing_config.runner_up_bid.buyer_trade_state,
+        runner_up_bid.buyer_trade_state
+    );
+
+    // The winner pulls part of the escrowed funds before the auction is settled.
+    let (_, withdraw_tx) = withdraw(
+        &mut context,
+        &winner,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        100_000_001,
+        1_000,
+    );
+    context
+        .banks_client
+        .process_transaction(withdraw_tx)
+        .await
+        .unwrap();
+
+    context.warp_to_slot(120 * 400).unwrap();
+
+    let (_, execute_sale_tx) = execute_sale(
+        &mut context,
+        &listing_config_address,
+        &ahkey,
+        &ah,
+        &authority,
+        &test_metadata,
+        &winner.pubkey(),
+        &test_metadata.token.pubkey(),
+        &sell_acc.token_account,
+        &sell_acc.seller_trade_state,
+        &winner_bid.buyer_trade_state,
+        1,
+        100_000_001,
+    );
+    assert!(context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .is_err());
+
+    let (_, fallback_tx) = settle_with_fallback(
+        &mut context,
+        &listing_config_address,
+        &ahkey,
+        &ah,
+        &authority,
+        &test_metadata,
+        &runner_up.pubkey(),
+        &test_metadata.token.pubkey(),
+        &sell_acc.token_account,
+        &sell_acc.seller_trade_state,
+        &runner_up_bid.buyer_trade_state,
+        1,
+        100_000_000,
+        &winner.pubkey(),
+        &winner_bid.buyer_trade_state,
+    );
+    context
+        .banks_client
+        .process_transaction(fallback_tx)
+        .await
+        .unwrap();
+
+    let runner_up_token_account =
+        get_associated_token_address(&runner_up.pubkey(), &test_metadata.mint.pubkey());
+    let runner_up_token = spl_token::state::Account::unpack_from_slice(
+        &context
+            .banks_client
+            .get_account(runner_up_token_account)
+            .await
+            .unwrap()
+            .unwrap()
+            .data,
+    )
+    .unwrap();
+    assert_eq!(runner_up_token.amount, 1);
+
+    let listing_config_account = context
+        .banks_client
+        .get_account(listing_config_address)
+        .await
+        .unwrap()
+        .unwrap();
+    let listing_config =
+        ListingConfig::try_deserialize(&mut listing_config_account.data.as_ref()).unwrap();
+    assert_eq!(
+        listing_config.highest_bid.buyer_trade_state,
+        runner_up_bid.buyer_trade_state
+    );
+    assert_eq!(listing_config.runner_up_bid.amount, 0);
+}
+
+#[tokio::test]
+async fn settle_with_fallback_highest_bid_settleable() {
+    let (
+        mut context,
+        ah,
+        ahkey,
+        authority,
+        test_metadata,
+        sell_acc,
+        listing_config_address,
+        (winner, winner_bid),
+        (runner_up, runner_up_bid),
+    ) = two_bid_auction().await;
+
+    context.warp_to_slot(120 * 400).unwrap();
+
+    let (_, fallback_tx) = settle_with_fallback(
+        &mut context,
+        &listing_config_address,
+        &ahkey,
+        &ah,
+        &authority,
+        &test_metadata,
+        &runner_up.pubkey(),
+        &test_metadata.token.pubkey(),
+        &sell_acc.token_account,
+        &sell_acc.seller_trade_state,
+        &runner_up_bid.buyer_trade_state,
+        1,
+        100_000_000,
+        &winner.pubkey(),
+        &winner_bid.buyer_trade_state,
+    );
+    let result = context
+        .banks_client
+        .process_transaction(fallback_tx)
+        .await
+        .unwrap_err();
+    assert_error!(result, HIGHEST_BID_SETTLEABLE);
+
+    let (_, fallback_tx) = settle_with_fallback(
+        &mut context,
+        &listing_config_address,
+        &ahkey,
+        &ah,
+        &authority,
+        &test_metadata,
+        &winner.pubkey(),
+        &test_metadata.token.pubkey(),
+        &sell_acc.token_account,
+        &sell_acc.seller_trade_state,
+        &winner_bid.buyer_trade_state,
+        1,
+        100_000_001,
+        &winner.pubkey(),
+        &winner_bid.buyer_trade_state,
+    );
+    let result = context
+        .banks_client
+        .process_transaction(fallback_tx)
+        .await
+        .unwrap_err();
+    assert_error!(result, NOT_RUNNER_UP_BIDDER);
+}
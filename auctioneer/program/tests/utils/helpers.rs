@@ -9,6 +9,9 @@ pub fn default_scopes() -> Vec<AuthorityScope> {
         AuthorityScope::Sell,
         AuthorityScope::Cancel,
         AuthorityScope::Withdraw,
+        AuthorityScope::PrintReceipt,
+        AuthorityScope::WithdrawTreasury,
+        AuthorityScope::UpdateHouse,
     ]
 }
 
@@ -13,16 +13,21 @@ use mpl_auction_house::{
         find_auctioneer_trade_state_address, find_escrow_payment_address,
         find_program_as_signer_address, find_trade_state_address,
     },
-    AuctionHouse,
+    AuctionHouse, AuthorityScope,
+};
+use mpl_auctioneer::{
+    pda::*,
+    sell::config::{AutoRelist, PriceSnapshotConfig},
 };
-use mpl_auctioneer::pda::*;
 use mpl_testing_utils::{solana::airdrop, utils::Metadata};
 use std::result::Result as StdResult;
 
 use mpl_token_metadata::pda::find_metadata_account;
 use solana_program_test::*;
 use solana_sdk::{
-    clock::UnixTimestamp, instruction::Instruction, transaction::Transaction,
+    clock::UnixTimestamp,
+    instruction::{AccountMeta, Instruction},
+    transaction::Transaction,
     transport::TransportError,
 };
 use spl_associated_token_account::get_associated_token_address;
@@ -141,6 +146,40 @@ pub async fn create_auction_house(
         .map(|_| auction_house_key.clone())
 }
 
+pub async fn update_auctioneer_scopes(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    authority: &Keypair,
+    scopes: Vec<AuthorityScope>,
+) -> StdResult<(), TransportError> {
+    let (auctioneer_authority, _) = find_auctioneer_authority_seeds(ahkey);
+    let (auctioneer_pda, _) = find_auctioneer_pda(ahkey, &auctioneer_authority);
+
+    let accounts = mpl_auction_house::accounts::UpdateAuctioneer {
+        auction_house: *ahkey,
+        authority: authority.pubkey(),
+        auctioneer_authority,
+        ah_auctioneer_pda: auctioneer_pda,
+        system_program: system_program::id(),
+    }
+    .to_account_metas(None);
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::UpdateAuctioneer { scopes }.data(),
+        accounts,
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&authority.pubkey()),
+        &[authority],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await
+}
+
 pub fn deposit(
     context: &mut ProgramTestContext,
     ahkey: &Pubkey,
@@ -215,6 +254,32 @@ pub fn buy(
     seller: &Pubkey,
     listing_config: &Pubkey,
     sale_price: u64,
+) -> (mpl_auctioneer::accounts::AuctioneerBuy, Transaction) {
+    buy_with_price_feed(
+        context,
+        ahkey,
+        ah,
+        test_metadata,
+        owner,
+        buyer,
+        seller,
+        listing_config,
+        sale_price,
+        None,
+    )
+}
+
+pub fn buy_with_price_feed(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    owner: &Pubkey,
+    buyer: &Keypair,
+    seller: &Pubkey,
+    listing_config: &Pubkey,
+    sale_price: u64,
+    price_feed: Option<&Pubkey>,
 ) -> (mpl_auctioneer::accounts::AuctioneerBuy, Transaction) {
     let seller_token_account = get_associated_token_address(&owner, &test_metadata.mint.pubkey());
     let trade_state = find_trade_state_address(
@@ -241,6 +306,7 @@ pub fn buy(
         auction_house: *ahkey,
         auction_house_fee_account: ah.auction_house_fee_account,
         buyer_trade_state: bts,
+        bid_history: find_bid_history_address(listing_config, 0).0,
         token_program: spl_token::id(),
         treasury_mint: ah.treasury_mint,
         payment_account: buyer.pubkey(),
@@ -250,9 +316,13 @@ pub fn buy(
         escrow_payment_account: escrow,
         auctioneer_authority: auctioneer_authority,
         ah_auctioneer_pda: auctioneer_pda,
+        operator_control: find_operator_control_address(ahkey).0,
     };
 
-    let account_metas = accounts.to_account_metas(None);
+    let mut account_metas = accounts.to_account_metas(None);
+    if let Some(price_feed) = price_feed {
+        account_metas.push(AccountMeta::new_readonly(*price_feed, false));
+    }
 
     let buy_ix = mpl_auctioneer::instruction::Buy {
         trade_state_bump: bts_bump,
@@ -338,6 +408,7 @@ pub fn execute_sale(
         authority: authority.pubkey(),
         auctioneer_authority: auctioneer_authority,
         ah_auctioneer_pda: auctioneer_pda,
+        operator_control: find_operator_control_address(ahkey).0,
     };
 
     let execute_sale_account_metas = execute_sale_accounts.to_account_metas(None);
@@ -366,6 +437,303 @@ pub fn execute_sale(
     (execute_sale_accounts, tx)
 }
 
+pub fn settle_with_fallback(
+    context: &mut ProgramTestContext,
+    listing_config: &Pubkey,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    authority: &Keypair,
+    test_metadata: &Metadata,
+    buyer: &Pubkey,
+    seller: &Pubkey,
+    token_account: &Pubkey,
+    seller_trade_state: &Pubkey,
+    buyer_trade_state: &Pubkey,
+    token_size: u64,
+    buyer_price: u64,
+    highest_bidder: &Pubkey,
+    highest_bid_trade_state: &Pubkey,
+) -> (mpl_auctioneer::accounts::AuctioneerExecuteSale, Transaction) {
+    let (accounts, _) = execute_sale(
+        context,
+        listing_config,
+        ahkey,
+        ah,
+        authority,
+        test_metadata,
+        buyer,
+        seller,
+        token_account,
+        seller_trade_state,
+        buyer_trade_state,
+        token_size,
+        buyer_price,
+    );
+
+    let (_, free_sts_bump) = find_trade_state_address(
+        &seller,
+        &ahkey,
+        &token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        0,
+        token_size,
+    );
+    let (_, escrow_bump) = find_escrow_payment_address(&ahkey, &buyer);
+    let (_, pas_bump) = find_program_as_signer_address();
+    let (_, aa_bump) = find_auctioneer_authority_seeds(ahkey);
+    let (highest_bid_escrow, _) = find_escrow_payment_address(&ahkey, &highest_bidder);
+
+    let mut account_metas = accounts.to_account_metas(None);
+    account_metas.push(AccountMeta::new_readonly(*highest_bidder, false));
+    account_metas.push(AccountMeta::new_readonly(*highest_bid_trade_state, false));
+    account_metas.push(AccountMeta::new_readonly(highest_bid_escrow, false));
+
+    let instruction = Instruction {
+        program_id: mpl_auctioneer::id(),
+        data: mpl_auctioneer::instruction::SettleWithFallback {
+            escrow_payment_bump: escrow_bump,
+            free_trade_state_bump: free_sts_bump,
+            program_as_signer_bump: pas_bump,
+            auctioneer_authority_bump: aa_bump,
+            token_size,
+            buyer_price,
+        }
+        .data(),
+        accounts: account_metas,
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&authority.pubkey()),
+        &[authority],
+        context.last_blockhash,
+    );
+
+    (accounts, tx)
+}
+
+pub fn relist_after_reserve_failure(
+    context: &mut ProgramTestContext,
+    listing_config: &Pubkey,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    token_account: &Pubkey,
+    token_size: u64,
+    highest_bid: Option<(&Pubkey, &Pubkey)>,
+    payer: &Keypair,
+) -> (
+    mpl_auctioneer::accounts::RelistAfterReserveFailure,
+    Transaction,
+) {
+    let seller = test_metadata.token.pubkey();
+    let (seller_trade_state, _) = find_auctioneer_trade_state_address(
+        &seller,
+        ahkey,
+        token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        token_size,
+    );
+    let accounts = mpl_auctioneer::accounts::RelistAfterReserveFailure {
+        auction_house_program: mpl_auction_house::id(),
+        listing_config: *listing_config,
+        seller,
+        token_account: *token_account,
+        token_mint: test_metadata.mint.pubkey(),
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        seller_trade_state,
+    };
+
+    let mut account_metas = accounts.to_account_metas(None);
+    if let Some((highest_bidder, highest_bid_trade_state)) = highest_bid {
+        account_metas.push(AccountMeta::new(*highest_bidder, false));
+        account_metas.push(AccountMeta::new_readonly(*highest_bid_trade_state, false));
+    }
+
+    let instruction = Instruction {
+        program_id: mpl_auctioneer::id(),
+        data: mpl_auctioneer::instruction::RelistAfterReserveFailure { token_size }.data(),
+        accounts: account_metas,
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[payer],
+        context.last_blockhash,
+    );
+
+    (accounts, tx)
+}
+
+pub fn close_bid_history(
+    context: &mut ProgramTestContext,
+    listing_config: &Pubkey,
+    buyer_trade_state: &Pubkey,
+    payer: &Pubkey,
+    page: u64,
+    fee_payer: &Keypair,
+) -> (mpl_auctioneer::accounts::CloseBidHistory, Transaction) {
+    let (bid_history, _) = find_bid_history_address(listing_config, page);
+    let accounts = mpl_auctioneer::accounts::CloseBidHistory {
+        listing_config: *listing_config,
+        buyer_trade_state: *buyer_trade_state,
+        bid_history,
+        payer: *payer,
+    };
+
+    let instruction = Instruction {
+        program_id: mpl_auctioneer::id(),
+        data: mpl_auctioneer::instruction::CloseBidHistory {}.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&fee_payer.pubkey()),
+        &[fee_payer],
+        context.last_blockhash,
+    );
+
+    (accounts, tx)
+}
+
+pub fn set_operator_paused(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    authority: &Keypair,
+    paused: bool,
+) -> (mpl_auctioneer::accounts::SetOperatorPaused, Transaction) {
+    let accounts = mpl_auctioneer::accounts::SetOperatorPaused {
+        auction_house_program: mpl_auction_house::id(),
+        authority: authority.pubkey(),
+        auction_house: *ahkey,
+        operator_control: find_operator_control_address(ahkey).0,
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+    };
+
+    let instruction = Instruction {
+        program_id: mpl_auctioneer::id(),
+        data: mpl_auctioneer::instruction::SetOperatorPaused { paused }.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&authority.pubkey()),
+        &[authority],
+        context.last_blockhash,
+    );
+
+    (accounts, tx)
+}
+
+pub fn migrate_listing_config(
+    context: &mut ProgramTestContext,
+    listing_config: &Pubkey,
+    payer: &Keypair,
+) -> (mpl_auctioneer::accounts::MigrateListingConfig, Transaction) {
+    let accounts = mpl_auctioneer::accounts::MigrateListingConfig {
+        payer: payer.pubkey(),
+        listing_config: *listing_config,
+        system_program: system_program::id(),
+    };
+
+    let instruction = Instruction {
+        program_id: mpl_auctioneer::id(),
+        data: mpl_auctioneer::instruction::MigrateListingConfig {}.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[payer],
+        context.last_blockhash,
+    );
+
+    (accounts, tx)
+}
+
+pub fn migrate_bid_history(
+    context: &mut ProgramTestContext,
+    bid_history: &Pubkey,
+    payer: &Keypair,
+) -> (mpl_auctioneer::accounts::MigrateBidHistory, Transaction) {
+    let accounts = mpl_auctioneer::accounts::MigrateBidHistory {
+        payer: payer.pubkey(),
+        bid_history: *bid_history,
+        system_program: system_program::id(),
+    };
+
+    let instruction = Instruction {
+        program_id: mpl_auctioneer::id(),
+        data: mpl_auctioneer::instruction::MigrateBidHistory {}.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[payer],
+        context.last_blockhash,
+    );
+
+    (accounts, tx)
+}
+
+pub fn set_co_lister(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    co_lister: &Keypair,
+    token_size: u64,
+    co_lister_basis_points: u16,
+) -> (mpl_auctioneer::accounts::SetCoLister, Transaction) {
+    let token =
+        get_associated_token_address(&test_metadata.token.pubkey(), &test_metadata.mint.pubkey());
+    let (listing_config, _) = find_listing_config_address(
+        &test_metadata.token.pubkey(),
+        ahkey,
+        &token,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        token_size,
+    );
+    let accounts = mpl_auctioneer::accounts::SetCoLister {
+        auction_house_program: mpl_auction_house::id(),
+        listing_config,
+        wallet: test_metadata.token.pubkey(),
+        co_lister: co_lister.pubkey(),
+        token_account: token,
+        auction_house: *ahkey,
+    };
+
+    let instruction = Instruction {
+        program_id: mpl_auctioneer::id(),
+        data: mpl_auctioneer::instruction::SetCoLister {
+            token_size,
+            co_lister_basis_points,
+        }
+        .data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&test_metadata.token.pubkey()),
+        &[&test_metadata.token, co_lister],
+        context.last_blockhash,
+    );
+
+    (accounts, tx)
+}
+
 pub fn sell_mint(
     context: &mut ProgramTestContext,
     ahkey: &Pubkey,
@@ -449,6 +817,11 @@ pub fn sell_mint(
         time_ext_period,
         time_ext_delta,
         allow_high_bid_cancel,
+        bid_fee: None,
+        listing_fee: None,
+        buy_now_price: None,
+        auto_relist: None,
+        price_snapshot: None,
     }
     .data();
 
@@ -484,6 +857,156 @@ pub fn sell(
 ) -> (
     (mpl_auctioneer::accounts::AuctioneerSell, Pubkey),
     Transaction,
+) {
+    sell_with_bid_fee(
+        context,
+        ahkey,
+        ah,
+        test_metadata,
+        start_time,
+        end_time,
+        reserve_price,
+        min_bid_increment,
+        time_ext_period,
+        time_ext_delta,
+        allow_high_bid_cancel,
+        None,
+    )
+}
+
+pub fn sell_with_bid_fee(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    start_time: UnixTimestamp,
+    end_time: UnixTimestamp,
+    reserve_price: Option<u64>,
+    min_bid_increment: Option<u64>,
+    time_ext_period: Option<u32>,
+    time_ext_delta: Option<u32>,
+    allow_high_bid_cancel: Option<bool>,
+    bid_fee: Option<u64>,
+) -> (
+    (mpl_auctioneer::accounts::AuctioneerSell, Pubkey),
+    Transaction,
+) {
+    sell_with_fees(
+        context,
+        ahkey,
+        ah,
+        test_metadata,
+        start_time,
+        end_time,
+        reserve_price,
+        min_bid_increment,
+        time_ext_period,
+        time_ext_delta,
+        allow_high_bid_cancel,
+        bid_fee,
+        None,
+        None,
+    )
+}
+
+pub fn sell_with_fees(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    start_time: UnixTimestamp,
+    end_time: UnixTimestamp,
+    reserve_price: Option<u64>,
+    min_bid_increment: Option<u64>,
+    time_ext_period: Option<u32>,
+    time_ext_delta: Option<u32>,
+    allow_high_bid_cancel: Option<bool>,
+    bid_fee: Option<u64>,
+    listing_fee: Option<u64>,
+    buy_now_price: Option<u64>,
+) -> (
+    (mpl_auctioneer::accounts::AuctioneerSell, Pubkey),
+    Transaction,
+) {
+    sell_with_auto_relist(
+        context,
+        ahkey,
+        ah,
+        test_metadata,
+        start_time,
+        end_time,
+        reserve_price,
+        min_bid_increment,
+        time_ext_period,
+        time_ext_delta,
+        allow_high_bid_cancel,
+        bid_fee,
+        listing_fee,
+        buy_now_price,
+        None,
+    )
+}
+
+pub fn sell_with_auto_relist(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    start_time: UnixTimestamp,
+    end_time: UnixTimestamp,
+    reserve_price: Option<u64>,
+    min_bid_increment: Option<u64>,
+    time_ext_period: Option<u32>,
+    time_ext_delta: Option<u32>,
+    allow_high_bid_cancel: Option<bool>,
+    bid_fee: Option<u64>,
+    listing_fee: Option<u64>,
+    buy_now_price: Option<u64>,
+    auto_relist: Option<AutoRelist>,
+) -> (
+    (mpl_auctioneer::accounts::AuctioneerSell, Pubkey),
+    Transaction,
+) {
+    sell_with_price_snapshot(
+        context,
+        ahkey,
+        ah,
+        test_metadata,
+        start_time,
+        end_time,
+        reserve_price,
+        min_bid_increment,
+        time_ext_period,
+        time_ext_delta,
+        allow_high_bid_cancel,
+        bid_fee,
+        listing_fee,
+        buy_now_price,
+        auto_relist,
+        None,
+    )
+}
+
+pub fn sell_with_price_snapshot(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    start_time: UnixTimestamp,
+    end_time: UnixTimestamp,
+    reserve_price: Option<u64>,
+    min_bid_increment: Option<u64>,
+    time_ext_period: Option<u32>,
+    time_ext_delta: Option<u32>,
+    allow_high_bid_cancel: Option<bool>,
+    bid_fee: Option<u64>,
+    listing_fee: Option<u64>,
+    buy_now_price: Option<u64>,
+    auto_relist: Option<AutoRelist>,
+    price_snapshot: Option<PriceSnapshotConfig>,
+) -> (
+    (mpl_auctioneer::accounts::AuctioneerSell, Pubkey),
+    Transaction,
 ) {
     let token =
         get_associated_token_address(&test_metadata.token.pubkey(), &test_metadata.mint.pubkey());
@@ -552,6 +1075,11 @@ pub fn sell(
         time_ext_period,
         time_ext_delta,
         allow_high_bid_cancel,
+        bid_fee,
+        listing_fee,
+        buy_now_price,
+        auto_relist,
+        price_snapshot,
     }
     .data();
 
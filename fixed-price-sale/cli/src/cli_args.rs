@@ -85,6 +85,10 @@ pub enum Commands {
 
         #[clap(long, value_name = "U64")]
         max_supply: Option<u64>,
+
+        /// Sell the master edition token itself instead of printing editions.
+        #[clap(long)]
+        master_edition_as_is: bool,
     },
     /// Perform `CreateMarket` instruction of `mpl_fixed_price_sale` program.
     CreateMarket {
@@ -59,6 +59,8 @@ fn main() -> Result<(), error::Error> {
                 println!("Store::admin - {}", store.admin);
                 println!("Store::name - {}", store.name);
                 println!("Store::description - {}", store.description);
+                println!("Store::pending_admin - {:?}", store.pending_admin);
+                println!("Store::authority_version - {}", store.authority_version);
 
                 None
             }
@@ -101,6 +103,26 @@ fn main() -> Result<(), error::Error> {
                     }
                 );
                 println!("Market::state - {:?}", market.state);
+                println!(
+                    "Market::processor_fee - {}",
+                    if let Some(x) = &market.processor_fee {
+                        format!("{} bps to {}", x.basis_points, x.destination)
+                    } else {
+                        String::from("<none>")
+                    }
+                );
+                println!(
+                    "Market::sweep_basis_points - {}",
+                    if let Some(x) = market.sweep_basis_points {
+                        x.to_string()
+                    } else {
+                        String::from("<none>")
+                    }
+                );
+                println!(
+                    "Market::funds_swept - {}",
+                    spl_token::amount_to_ui_amount(market.funds_swept, decimals)
+                );
 
                 None
             }
@@ -115,6 +137,30 @@ fn main() -> Result<(), error::Error> {
                     "TradeHistory::already_bought - {}",
                     trade_history.already_bought
                 );
+                println!(
+                    "TradeHistory::already_redeemed - {}",
+                    trade_history.already_redeemed
+                );
+                println!(
+                    "TradeHistory::processor_fees_paid - {}",
+                    trade_history.processor_fees_paid
+                );
+                println!(
+                    "TradeHistory::last_client_nonce - {:?}",
+                    trade_history.last_client_nonce
+                );
+                println!(
+                    "TradeHistory::discounted_bought - {}",
+                    trade_history.discounted_bought
+                );
+                println!(
+                    "TradeHistory::discounts_received - {}",
+                    trade_history.discounts_received
+                );
+                println!(
+                    "TradeHistory::sponsored - {}",
+                    trade_history.sponsored
+                );
 
                 None
             }
@@ -146,6 +192,7 @@ fn main() -> Result<(), error::Error> {
                 resource_mint,
                 resource_token,
                 max_supply,
+                master_edition_as_is,
             } => {
                 let admin_keypair = if let Some(keypair) = admin_keypair {
                     read_keypair_file(keypair)?
@@ -168,6 +215,11 @@ fn main() -> Result<(), error::Error> {
                     &Pubkey::from_str(&resource_mint)?,
                     &Pubkey::from_str(&resource_token)?,
                     max_supply,
+                    if master_edition_as_is {
+                        mpl_fixed_price_sale::state::SellingResourceType::MasterEditionAsIs
+                    } else {
+                        mpl_fixed_price_sale::state::SellingResourceType::Editions
+                    },
                 )?;
 
                 Some(vec![(tx, ui_info)])
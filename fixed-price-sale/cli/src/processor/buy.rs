@@ -3,7 +3,9 @@
 use super::{get_account_state, UiTransactionInfo};
 use crate::{error, utils};
 use anchor_lang::{InstructionData, ToAccountMetas};
-use mpl_fixed_price_sale::utils::{find_trade_history_address, find_vault_owner_address};
+use mpl_fixed_price_sale::utils::{
+    find_provenance_record_address, find_trade_history_address, find_versioned_vault_owner_address,
+};
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::{
     instruction::Instruction,
@@ -24,6 +26,7 @@ pub struct BuyUiInfo {
     new_metadata: Pubkey,
     new_edition: Pubkey,
     new_mint: Pubkey,
+    provenance_record: Pubkey,
 }
 
 impl UiTransactionInfo for BuyUiInfo {
@@ -34,6 +37,7 @@ impl UiTransactionInfo for BuyUiInfo {
         println!("Buy::new_metadata - {}", self.new_metadata);
         println!("Buy::new_edition - {}", self.new_edition);
         println!("Buy::new_mint - {}", self.new_mint);
+        println!("Buy::provenance_record - {}", self.provenance_record);
     }
 }
 
@@ -71,7 +75,11 @@ pub fn buy(
         1,
     )?;
 
-    let (owner, vault_owner_bump) = find_vault_owner_address(&resource_mint, &store);
+    let (owner, vault_owner_bump) = find_versioned_vault_owner_address(
+        &resource_mint,
+        &store,
+        selling_resource_state.vault_owner_version,
+    );
     let (trade_history, trade_history_bump) =
         find_trade_history_address(&user_wallet.pubkey(), market);
 
@@ -140,6 +148,7 @@ pub fn buy(
         new_token_account: new_mint_token_account.pubkey(),
         edition_marker,
         vault: selling_resource_state.vault,
+        store: selling_resource_state.store,
         owner,
         master_edition_metadata,
         clock: clock::id(),
@@ -147,12 +156,15 @@ pub fn buy(
         token_metadata_program: mpl_token_metadata::id(),
         token_program: spl_token::id(),
         system_program: system_program::id(),
+        associated_token_program: spl_associated_token_account::id(),
+        provenance_record: find_provenance_record_address(&new_mint.pubkey()).0,
     }
     .to_account_metas(None);
 
     let data = mpl_fixed_price_sale::instruction::Buy {
         _trade_history_bump: trade_history_bump,
         vault_owner_bump,
+        client_nonce: None,
     }
     .data();
 
@@ -178,6 +190,7 @@ pub fn buy(
             new_metadata,
             trade_history,
             new_mint: new_mint.pubkey(),
+            provenance_record: find_provenance_record_address(&new_mint.pubkey()).0,
         }),
     ))
 }
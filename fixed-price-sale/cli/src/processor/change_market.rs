@@ -1,11 +1,16 @@
 //! Module provide handler for `ChangeMarket` command.
 
-use super::UiTransactionInfo;
+use super::{get_account_state, UiTransactionInfo};
 use crate::error;
 use anchor_lang::{InstructionData, ToAccountMetas};
+use mpl_fixed_price_sale::utils::{find_market_manager_address, find_market_name_registry_address};
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::{
-    instruction::Instruction, pubkey::Pubkey, signature::Signer, signer::keypair::Keypair, sysvar,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::Signer,
+    signer::keypair::Keypair,
+    system_program, sysvar,
     transaction::Transaction,
 };
 
@@ -31,13 +36,32 @@ pub fn change_market(
     new_price: Option<u64>,
     new_pieces_in_one_wallet: Option<u64>,
 ) -> Result<(Transaction, Box<dyn UiTransactionInfo>), error::Error> {
-    let accounts = mpl_fixed_price_sale::accounts::ChangeMarket {
+    let market_state = get_account_state::<mpl_fixed_price_sale::state::Market>(client, market)?;
+
+    let mut accounts = mpl_fixed_price_sale::accounts::ChangeMarket {
         market: *market,
+        store: market_state.store,
         owner: owner.pubkey(),
+        market_manager: find_market_manager_address(market, &owner.pubkey()).0,
         clock: sysvar::id(),
+        system_program: system_program::id(),
     }
     .to_account_metas(None);
 
+    // Registered name moves along with the market
+    if let Some(new_name) = &new_name {
+        let (new_market_name_registry, _) =
+            find_market_name_registry_address(&market_state.store, new_name);
+        accounts.push(AccountMeta::new(new_market_name_registry, false));
+
+        // Markets created before names were registered have no registry
+        let (market_name_registry, _) =
+            find_market_name_registry_address(&market_state.store, &market_state.name);
+        if client.get_account(&market_name_registry).is_ok() {
+            accounts.push(AccountMeta::new(market_name_registry, false));
+        }
+    }
+
     let data = mpl_fixed_price_sale::instruction::ChangeMarket {
         new_name,
         new_description,
@@ -46,13 +46,16 @@ pub fn claim_resource(
         &mpl_token_metadata::id(),
     );
 
-    let (_owner, vault_owner_bump) = mpl_fixed_price_sale::utils::find_vault_owner_address(
-        &selling_resource_state.resource,
-        &selling_resource_state.store,
-    );
+    let (_owner, vault_owner_bump) =
+        mpl_fixed_price_sale::utils::find_versioned_vault_owner_address(
+            &selling_resource_state.resource,
+            &selling_resource_state.store,
+            selling_resource_state.vault_owner_version,
+        );
 
     let accounts = mpl_fixed_price_sale::accounts::ClaimResource {
         market: *market,
+        store: market_state.store,
         selling_resource: market_state.selling_resource,
         treasury_holder: market_state.treasury_holder,
         metadata: master_edition_metadata,
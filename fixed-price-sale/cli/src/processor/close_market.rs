@@ -1,11 +1,16 @@
 //! Module provide handler for `CloseMarket` command.
 
-use super::UiTransactionInfo;
+use super::{get_account_state, UiTransactionInfo};
 use crate::error;
 use anchor_lang::{InstructionData, ToAccountMetas};
+use mpl_fixed_price_sale::utils::find_market_name_registry_address;
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::{
-    instruction::Instruction, pubkey::Pubkey, signature::Signer, signer::keypair::Keypair, sysvar,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::Signer,
+    signer::keypair::Keypair,
+    sysvar,
     transaction::Transaction,
 };
 
@@ -26,13 +31,23 @@ pub fn close_market(
     owner: &Keypair,
     market: &Pubkey,
 ) -> Result<(Transaction, Box<dyn UiTransactionInfo>), error::Error> {
-    let accounts = mpl_fixed_price_sale::accounts::CloseMarket {
+    let market_state = get_account_state::<mpl_fixed_price_sale::state::Market>(client, market)?;
+
+    let mut accounts = mpl_fixed_price_sale::accounts::CloseMarket {
         market: *market,
+        store: market_state.store,
         owner: owner.pubkey(),
         clock: sysvar::id(),
     }
     .to_account_metas(None);
 
+    // Markets created before names were registered have no registry
+    let (market_name_registry, _) =
+        find_market_name_registry_address(&market_state.store, &market_state.name);
+    if client.get_account(&market_name_registry).is_ok() {
+        accounts.push(AccountMeta::new(market_name_registry, false));
+    }
+
     let data = mpl_fixed_price_sale::instruction::CloseMarket {}.data();
 
     let instruction = Instruction {
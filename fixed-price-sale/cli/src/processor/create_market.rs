@@ -3,7 +3,10 @@
 use super::{get_account_state, UiTransactionInfo};
 use crate::{error, utils};
 use anchor_lang::{InstructionData, ToAccountMetas};
-use mpl_fixed_price_sale::utils::find_treasury_owner_address;
+use mpl_fixed_price_sale::utils::{
+    find_global_config_address, find_market_name_registry_address,
+    find_store_payment_mints_address, find_treasury_owner_address,
+};
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::{
     instruction::Instruction, pubkey::Pubkey, signature::Signer, signer::keypair::Keypair,
@@ -61,6 +64,13 @@ pub fn create_market(
         mint: *mint,
         treasury_holder: treasury_holder.pubkey(),
         owner: treasury_owner,
+        market_name_registry: find_market_name_registry_address(
+            &selling_resource_state.store,
+            &name,
+        )
+        .0,
+        store_payment_mints: find_store_payment_mints_address(&selling_resource_state.store).0,
+        global_config: find_global_config_address().0,
         system_program: system_program::id(),
     }
     .to_account_metas(None);
@@ -75,6 +85,10 @@ pub fn create_market(
         start_date,
         end_date,
         gating_config,
+        metadata_overrides: None,
+        pda_mints: false,
+        processor_fee: None,
+        sweep_basis_points: None,
     }
     .data();
 
@@ -1,9 +1,9 @@
 //! Module provide handler for `InitSellingResource` command.
 
-use super::UiTransactionInfo;
+use super::{get_account_state, UiTransactionInfo};
 use crate::{error, utils};
 use anchor_lang::{InstructionData, ToAccountMetas};
-use mpl_fixed_price_sale::utils::find_vault_owner_address;
+use mpl_fixed_price_sale::{state::SellingResourceType, utils::find_versioned_vault_owner_address};
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::{
     instruction::Instruction, pubkey::Pubkey, signature::Signer, signer::keypair::Keypair,
@@ -38,8 +38,11 @@ pub fn init_selling_resource(
     resource_mint: &Pubkey,
     resource_token: &Pubkey,
     max_supply: Option<u64>,
+    resource_type: SellingResourceType,
 ) -> Result<(Transaction, Box<dyn UiTransactionInfo>), error::Error> {
-    let (vault_owner, vault_owner_bump) = find_vault_owner_address(resource_mint, store);
+    let store_state = get_account_state::<mpl_fixed_price_sale::state::Store>(client, store)?;
+    let (vault_owner, vault_owner_bump) =
+        find_versioned_vault_owner_address(resource_mint, store, store_state.authority_version);
     let selling_resource = Keypair::new();
 
     // Should be created
@@ -86,6 +89,7 @@ pub fn init_selling_resource(
         master_edition_bump: master_edition_bump,
         vault_owner_bump: vault_owner_bump,
         max_supply,
+        resource_type,
     }
     .data();
 
@@ -1,8 +1,9 @@
 //! Module provide handler for `ResumeMarket` command.
 
-use super::UiTransactionInfo;
+use super::{get_account_state, UiTransactionInfo};
 use crate::error;
 use anchor_lang::{InstructionData, ToAccountMetas};
+use mpl_fixed_price_sale::utils::find_market_manager_address;
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::{
     instruction::Instruction, pubkey::Pubkey, signature::Signer, signer::keypair::Keypair, sysvar,
@@ -26,9 +27,13 @@ pub fn resume_market(
     owner: &Keypair,
     market: &Pubkey,
 ) -> Result<(Transaction, Box<dyn UiTransactionInfo>), error::Error> {
+    let market_state = get_account_state::<mpl_fixed_price_sale::state::Market>(client, market)?;
+
     let accounts = mpl_fixed_price_sale::accounts::ResumeMarket {
         market: *market,
+        store: market_state.store,
         owner: owner.pubkey(),
+        market_manager: find_market_manager_address(market, &owner.pubkey()).0,
         clock: sysvar::id(),
     }
     .to_account_metas(None);
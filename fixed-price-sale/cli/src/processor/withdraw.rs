@@ -88,8 +88,11 @@ pub fn withdraw(
                 &primary_royalties_holder,
             );
 
+        let (claim_tally, _) = mpl_fixed_price_sale::utils::find_claim_tally_address(&market);
+
         let mut accounts = mpl_fixed_price_sale::accounts::Withdraw {
             market: *market,
+            store: market_state.store,
             selling_resource: market_state.selling_resource,
             treasury_holder: market_state.treasury_holder,
             metadata: master_edition_metadata,
@@ -98,6 +101,7 @@ pub fn withdraw(
             funder: primary_royalties_holder,
             payer: payer.pubkey(),
             payout_ticket,
+            claim_tally,
             destination,
             clock: clock::id(),
             rent: rent::id(),
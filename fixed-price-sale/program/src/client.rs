@@ -0,0 +1,140 @@
+//! Off-chain helpers for frontends, not compiled into the program
+use crate::{
+    error::ErrorCode,
+    state::{Market, MarketState, SellingResource, SellingResourceState, TradeHistory},
+};
+use anchor_lang::{prelude::*, system_program::System};
+
+/// Accounts fetched by a client before submitting `buy`
+pub struct BuyRpcState {
+    pub market: Market,
+    pub selling_resource_key: Pubkey,
+    pub selling_resource: SellingResource,
+    /// `None` before the wallet's first purchase on the market
+    pub trade_history: Option<TradeHistory>,
+    pub user_wallet: Pubkey,
+    pub user_token_account: Pubkey,
+    /// mint of `user_token_account`, `None` if it does not exist or for native markets
+    pub user_token_account_mint: Option<Pubkey>,
+    pub client_nonce: Option<u64>,
+    /// cluster time the transaction is expected to land at
+    pub unix_timestamp: i64,
+}
+
+/// Check of `buy` which would fail with the fetched accounts
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PreconditionError {
+    SellingResourceMismatch,
+    MarketIsSuspended,
+    SoldOut,
+    MarketIsNotStarted { start_date: u64 },
+    MarketIsEnded { end_date: Option<u64> },
+    UserReachBuyLimit { pieces_in_one_wallet: u64 },
+    NoActivePhase,
+    DuplicatePurchaseNonce,
+    UserWalletMustMatchUserTokenAccount,
+    TreasuryMismatch { treasury_mint: Pubkey },
+}
+
+impl PreconditionError {
+    /// Error code `buy` fails with on-chain
+    pub fn error_code(&self) -> u32 {
+        match self {
+            PreconditionError::SellingResourceMismatch => {
+                anchor_lang::error::ErrorCode::ConstraintHasOne.into()
+            }
+            PreconditionError::MarketIsSuspended => ErrorCode::MarketIsSuspended.into(),
+            PreconditionError::SoldOut => ErrorCode::SoldOut.into(),
+            PreconditionError::MarketIsNotStarted { .. } => ErrorCode::MarketIsNotStarted.into(),
+            PreconditionError::MarketIsEnded { .. } => ErrorCode::MarketIsEnded.into(),
+            PreconditionError::UserReachBuyLimit { .. } => ErrorCode::UserReachBuyLimit.into(),
+            PreconditionError::NoActivePhase => ErrorCode::NoActivePhase.into(),
+            PreconditionError::DuplicatePurchaseNonce => ErrorCode::DuplicatePurchaseNonce.into(),
+            PreconditionError::UserWalletMustMatchUserTokenAccount => {
+                ErrorCode::UserWalletMustMatchUserTokenAccount.into()
+            }
+            PreconditionError::TreasuryMismatch { .. } => ErrorCode::TreasuryMismatch.into(),
+        }
+    }
+}
+
+/// Reproduce the checks `buy` makes of the market dates and phases, supply, wallet limits and treasury mint,
+/// reporting every failing one instead of the first. Gating, gatekeeper program and price oracle checks depend on
+/// further accounts and are not covered.
+pub fn validate_buy_preconditions(rpc_state: &BuyRpcState) -> Vec<PreconditionError> {
+    let market = &rpc_state.market;
+    let selling_resource = &rpc_state.selling_resource;
+    let now = rpc_state.unix_timestamp as u64;
+    let mut errors = Vec::new();
+
+    if market.selling_resource != rpc_state.selling_resource_key {
+        errors.push(PreconditionError::SellingResourceMismatch);
+    }
+
+    if market.state == MarketState::Suspended {
+        errors.push(PreconditionError::MarketIsSuspended);
+    }
+
+    let sold_out = selling_resource.state == SellingResourceState::Exhausted
+        || selling_resource
+            .max_supply
+            .map_or(false, |max_supply| selling_resource.supply >= max_supply);
+    if sold_out {
+        errors.push(PreconditionError::SoldOut);
+    }
+
+    if market.start_date > now {
+        errors.push(PreconditionError::MarketIsNotStarted {
+            start_date: market.start_date,
+        });
+    }
+
+    let ended = match market.end_date {
+        Some(end_date) => now > end_date,
+        None => market.state == MarketState::Ended,
+    };
+    if ended {
+        errors.push(PreconditionError::MarketIsEnded {
+            end_date: market.end_date,
+        });
+    }
+
+    let phase = market.active_phase(now);
+    if !market.phases.is_empty() && phase.is_none() {
+        errors.push(PreconditionError::NoActivePhase);
+    }
+
+    if let Some(trade_history) = &rpc_state.trade_history {
+        if let Some(pieces_in_one_wallet) = market.pieces_in_one_wallet {
+            if trade_history.pieces_acquired() == Some(pieces_in_one_wallet) {
+                errors.push(PreconditionError::UserReachBuyLimit {
+                    pieces_in_one_wallet,
+                });
+            }
+        }
+
+        if let Some(wallet_limit) = phase.and_then(|phase| phase.wallet_limit) {
+            if trade_history.pieces_acquired() >= Some(wallet_limit) {
+                errors.push(PreconditionError::UserReachBuyLimit {
+                    pieces_in_one_wallet: wallet_limit,
+                });
+            }
+        }
+
+        if trade_history.is_client_nonce_used(rpc_state.client_nonce) {
+            errors.push(PreconditionError::DuplicatePurchaseNonce);
+        }
+    }
+
+    if market.treasury_mint == System::id() {
+        if rpc_state.user_token_account != rpc_state.user_wallet {
+            errors.push(PreconditionError::UserWalletMustMatchUserTokenAccount);
+        }
+    } else if rpc_state.user_token_account_mint != Some(market.treasury_mint) {
+        errors.push(PreconditionError::TreasuryMismatch {
+            treasury_mint: market.treasury_mint,
+        });
+    }
+
+    errors
+}
@@ -0,0 +1,111 @@
+//! Helpers for programs composing fixed price sale instructions, available with the `cpi` feature.
+//!
+//! Typed contexts for every instruction are generated by Anchor in `crate::cpi::accounts`.
+//! The helpers below cover the PDA-signer variants, where the buyer or the selling resource
+//! owner is an authority of the calling program signing with `signer_seeds`, and forward
+//! gating / voucher accounts as remaining accounts.
+
+use crate::{
+    cpi::{self, accounts},
+    state::{GatingConfig, MetadataOverrides, ProcessorFee, SellingResourceType},
+};
+use anchor_lang::prelude::*;
+
+/// Buy an edition for a buyer signing with `signer_seeds`.
+///
+/// The buyer PDA pays for the trade history and provenance record, so it should hold enough lamports.
+/// Gating or voucher accounts are passed in `remaining_accounts` in the same order as for a regular `buy`.
+pub fn buy_with_signer<'info>(
+    program: AccountInfo<'info>,
+    accounts: accounts::Buy<'info>,
+    remaining_accounts: Vec<AccountInfo<'info>>,
+    signer_seeds: &[&[&[u8]]],
+    trade_history_bump: u8,
+    vault_owner_bump: u8,
+    client_nonce: Option<u64>,
+) -> Result<()> {
+    let ctx = CpiContext::new_with_signer(program, accounts, signer_seeds)
+        .with_remaining_accounts(remaining_accounts);
+
+    cpi::buy(ctx, trade_history_bump, vault_owner_bump, client_nonce)
+}
+
+/// Redeem a voucher for an edition for a buyer signing with `signer_seeds`.
+pub fn redeem_voucher_buy_with_signer<'info>(
+    program: AccountInfo<'info>,
+    accounts: accounts::Buy<'info>,
+    remaining_accounts: Vec<AccountInfo<'info>>,
+    signer_seeds: &[&[&[u8]]],
+    trade_history_bump: u8,
+    vault_owner_bump: u8,
+) -> Result<()> {
+    let ctx = CpiContext::new_with_signer(program, accounts, signer_seeds)
+        .with_remaining_accounts(remaining_accounts);
+
+    cpi::redeem_voucher_buy(ctx, trade_history_bump, vault_owner_bump)
+}
+
+/// Create a market for a selling resource owned by a program authority signing with `signer_seeds`.
+///
+/// The market account is created by `create_market`, so its keypair should sign the outer transaction.
+#[allow(clippy::too_many_arguments)]
+pub fn create_market_with_signer<'info>(
+    program: AccountInfo<'info>,
+    accounts: accounts::CreateMarket<'info>,
+    remaining_accounts: Vec<AccountInfo<'info>>,
+    signer_seeds: &[&[&[u8]]],
+    treasury_owner_bump: u8,
+    name: String,
+    description: String,
+    mutable: bool,
+    price: u64,
+    pieces_in_one_wallet: Option<u64>,
+    start_date: u64,
+    end_date: Option<u64>,
+    gating_config: Option<GatingConfig>,
+    metadata_overrides: Option<MetadataOverrides>,
+    pda_mints: bool,
+    processor_fee: Option<ProcessorFee>,
+    sweep_basis_points: Option<u16>,
+) -> Result<()> {
+    let ctx = CpiContext::new_with_signer(program, accounts, signer_seeds)
+        .with_remaining_accounts(remaining_accounts);
+
+    cpi::create_market(
+        ctx,
+        treasury_owner_bump,
+        name,
+        description,
+        mutable,
+        price,
+        pieces_in_one_wallet,
+        start_date,
+        end_date,
+        gating_config,
+        metadata_overrides,
+        pda_mints,
+        processor_fee,
+        sweep_basis_points,
+    )
+}
+
+/// Init a selling resource for a store admin or resource owner signing with `signer_seeds`.
+pub fn init_selling_resource_with_signer<'info>(
+    program: AccountInfo<'info>,
+    accounts: accounts::InitSellingResource<'info>,
+    signer_seeds: &[&[&[u8]]],
+    master_edition_bump: u8,
+    vault_owner_bump: u8,
+    max_supply: Option<u64>,
+    resource_type: SellingResourceType,
+) -> Result<()> {
+    let ctx = CpiContext::new_with_signer(program, accounts, signer_seeds);
+
+    cpi::init_selling_resource(
+        ctx,
+        master_edition_bump,
+        vault_owner_bump,
+        max_supply,
+        resource_type,
+    )
+}
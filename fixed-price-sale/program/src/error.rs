@@ -136,4 +136,175 @@ pub enum ErrorCode {
     // 6043
     #[msg("Wrong gating token")]
     WrongGatingToken,
+    // 6044
+    #[msg("Name suffix is longer than allowed")]
+    NameSuffixIsTooLong,
+    // 6045
+    #[msg("Uri is longer than allowed")]
+    UriIsTooLong,
+    // 6046
+    #[msg("Vault owner should be the update authority to apply metadata overrides")]
+    VaultOwnerIsNotUpdateAuthority,
+    // 6047
+    #[msg("New token account should be owned by user wallet and hold new mint")]
+    InvalidNewTokenAccount,
+    // 6048
+    #[msg("Market is already counted in store stats")]
+    MarketStatsAlreadySynced,
+    // 6049
+    #[msg("Market does not accept vouchers")]
+    VouchersNotAccepted,
+    // 6050
+    #[msg("Voucher should be owned by user wallet and verified in the voucher collection")]
+    InvalidVoucher,
+    // 6051
+    #[msg("Processor fee basis points should not be greater than 10000")]
+    InvalidProcessorFee,
+    // 6052
+    #[msg("Processor fee destination account is missing or does not match market")]
+    InvalidProcessorAccount,
+    // 6053
+    #[msg("Client nonce should be greater than the one of the last purchase")]
+    DuplicatePurchaseNonce,
+    // 6054
+    #[msg("Market with this name already exists in the store")]
+    MarketNameTaken,
+    // 6055
+    #[msg("Market name registry is missing or does not belong to market")]
+    InvalidMarketNameRegistry,
+    // 6056
+    #[msg("Sweep basis points should not be greater than 10000")]
+    InvalidSweepBasisPoints,
+    // 6057
+    #[msg("Market does not allow partial sweeps")]
+    PartialSweepDisabled,
+    // 6058
+    #[msg("Treasury was already swept in this epoch")]
+    PartialSweepRateLimited,
+    // 6059
+    #[msg("Store has no pending admin")]
+    NoPendingStoreAdmin,
+    // 6060
+    #[msg("Signer is not the pending store admin")]
+    InvalidPendingStoreAdmin,
+    // 6061
+    #[msg("Market is not ended")]
+    MarketIsNotEnded,
+    // 6062
+    #[msg("Gatekeeper program account is missing or does not match market")]
+    InvalidGatekeeperProgram,
+    // 6063
+    #[msg("Price feed account is missing, does not match market or is not trading")]
+    InvalidPriceFeed,
+    // 6064
+    #[msg("Treasury mint price deviates from its peg beyond market tolerance")]
+    TreasuryDepegged,
+    // 6065
+    #[msg("All editions of the selling resource are sold")]
+    SoldOut,
+    // 6066
+    #[msg("Treasury holder or user token account does not match market treasury")]
+    TreasuryMismatch,
+    // 6067
+    #[msg("Next edition number could not be derived from master edition")]
+    EditionDerivationFailed,
+    // 6068
+    #[msg("New mint must be the selling resource mint when selling master edition as is")]
+    ResourceMintMismatch,
+    // 6069
+    #[msg("Funder has nothing to withdraw")]
+    NothingToWithdraw,
+    // 6070
+    #[msg("Quantity discounts must be sorted by quantity from 2 up with discounts up to 100%")]
+    InvalidQuantityDiscounts,
+    // 6071
+    #[msg("Sponsor vault account is missing or does not match market")]
+    InvalidSponsorVault,
+    // 6072
+    #[msg("Treasury mint is not in the store payment mints")]
+    PaymentMintNotAllowed,
+    // 6073
+    #[msg("Too many store payment mints")]
+    TooManyPaymentMints,
+    // 6074
+    #[msg("Trade history account does not match market")]
+    InvalidTradeHistory,
+    // 6075
+    #[msg("Phases must be sorted by start, not overlap and end after they start")]
+    InvalidPhases,
+    // 6076
+    #[msg("No phase of the market is active")]
+    NoActivePhase,
+    // 6077
+    #[msg("Signer is not the upgrade authority of the program")]
+    InvalidGlobalConfigAuthority,
+    // 6078
+    #[msg("Feature is disabled in the global config")]
+    FeatureDisabled,
+    // 6079
+    #[msg("Store reached the maximum number of markets")]
+    TooManyMarkets,
+    // 6080
+    #[msg("Market reached the maximum number of managers")]
+    TooManyMarketManagers,
+    // 6081
+    #[msg("Signer is neither the market owner nor one of its managers")]
+    InvalidMarketManager,
+    // 6082
+    #[msg("Physical redemption delivery window must be positive")]
+    InvalidPhysicalRedemption,
+    // 6083
+    #[msg("Delivery of the purchase is not pending")]
+    DeliveryNotPending,
+    // 6084
+    #[msg("Signer is neither the buyer nor the arbiter of the delivery")]
+    InvalidDeliverySigner,
+    // 6085
+    #[msg("Delivery deadline of the purchase has not passed yet")]
+    DeliveryDeadlineNotPassed,
+    // 6086
+    #[msg("Refund destination is not a treasury token account of the buyer")]
+    InvalidRefundDestination,
+    // 6087
+    #[msg("Action queue account is missing or does not match market")]
+    InvalidActionQueue,
+    // 6088
+    #[msg("Action queue is full until its worker consumes actions")]
+    ActionQueueFull,
+    // 6089
+    #[msg("Action is not in the queue")]
+    ActionNotQueued,
+    // 6090
+    #[msg("New mint is not owned by the token program")]
+    NewMintOwnerInvalid,
+    // 6091
+    #[msg("Mint account size does not match a token program mint")]
+    MintAccountSizeInvalid,
+    // 6092
+    #[msg("Mint of an edition must have zero decimals")]
+    MintDecimalsInvalid,
+    // 6093
+    #[msg("Metadata account is too small to hold token metadata")]
+    MetadataAccountTooSmall,
+    // 6094
+    #[msg("Master edition account is too small to hold a master edition")]
+    MasterEditionAccountTooSmall,
+    // 6095
+    #[msg("Token account is not owned by the token program")]
+    TokenAccountOwnerInvalid,
+    // 6096
+    #[msg("Token account size does not match a token program account")]
+    TokenAccountSizeInvalid,
+    // 6097
+    #[msg("Account already has the current size")]
+    AccountAlreadyMigrated,
+    // 6098
+    #[msg("Account is not of a type with a legacy layout")]
+    AccountNotMigratable,
+    // 6099
+    #[msg("Master edition metadata is missing")]
+    MasterMetadataMissing,
+    // 6100
+    #[msg("Edition of the purchase is not held by the buyer")]
+    EditionNotHeld,
 }
@@ -1,3 +1,7 @@
+#[cfg(not(target_arch = "bpf"))]
+pub mod client;
+#[cfg(feature = "cpi")]
+pub mod cpi_utils;
 pub mod error;
 pub mod processor;
 pub mod state;
@@ -6,12 +10,18 @@ pub mod utils;
 use crate::{
     error::ErrorCode,
     state::{
-        Creator, GatingConfig, Market, PayoutTicket, PrimaryMetadataCreators, SellingResource,
-        Store, TradeHistory,
+        ActionQueue, ClaimTally, Creator, DustPolicy, GatekeeperProgram, GatingConfig,
+        GlobalConfig, Market, MarketManager, MetadataOverrides, PayoutTicket, Phase,
+        PhysicalRedemption, PriceOracle, PrimaryMetadataCreators, ProcessorFee, ProvenanceRecord,
+        QuantityDiscount, SellingResource, SellingResourceType, SponsorVault, Store,
+        StorePaymentMints, TradeHistory,
     },
     utils::*,
 };
-use anchor_lang::{prelude::*, system_program::System, AnchorDeserialize, AnchorSerialize};
+use anchor_lang::{
+    prelude::*, solana_program::bpf_loader_upgradeable, system_program::System, AnchorDeserialize,
+    AnchorSerialize,
+};
 use anchor_spl::{
     associated_token::AssociatedToken,
     token::{Mint, Token, TokenAccount},
@@ -28,9 +38,14 @@ pub mod fixed_price_sale {
         master_edition_bump: u8,
         vault_owner_bump: u8,
         max_supply: Option<u64>,
+        resource_type: SellingResourceType,
     ) -> Result<()> {
-        ctx.accounts
-            .process(master_edition_bump, vault_owner_bump, max_supply)
+        ctx.accounts.process(
+            master_edition_bump,
+            vault_owner_bump,
+            max_supply,
+            resource_type,
+        )
     }
 
     pub fn create_store<'info>(
@@ -45,18 +60,154 @@ pub mod fixed_price_sale {
         ctx: Context<'_, '_, '_, 'info, Buy<'info>>,
         _trade_history_bump: u8,
         vault_owner_bump: u8,
+        client_nonce: Option<u64>,
     ) -> Result<()> {
-        ctx.accounts.process(
-            _trade_history_bump,
-            vault_owner_bump,
-            ctx.remaining_accounts,
-        )
+        ctx.accounts
+            .process(
+                _trade_history_bump,
+                vault_owner_bump,
+                client_nonce,
+                ctx.remaining_accounts,
+            )
+            .map_err(|error| {
+                ctx.accounts.set_buy_diagnostics();
+                error
+            })
     }
 
-    pub fn close_market<'info>(ctx: Context<'_, '_, '_, 'info, CloseMarket<'info>>) -> Result<()> {
+    pub fn buy_sponsored<'info>(
+        ctx: Context<'_, '_, '_, 'info, Buy<'info>>,
+        _trade_history_bump: u8,
+        vault_owner_bump: u8,
+        client_nonce: Option<u64>,
+    ) -> Result<()> {
+        ctx.accounts
+            .process_sponsored(
+                _trade_history_bump,
+                vault_owner_bump,
+                client_nonce,
+                ctx.remaining_accounts,
+            )
+            .map_err(|error| {
+                ctx.accounts.set_buy_diagnostics();
+                error
+            })
+    }
+
+    pub fn fund_sponsor_vault<'info>(
+        ctx: Context<'_, '_, '_, 'info, FundSponsorVault<'info>>,
+        amount: u64,
+        per_wallet_cap: u64,
+    ) -> Result<()> {
+        ctx.accounts.process(amount, per_wallet_cap)
+    }
+
+    pub fn redeem_voucher_buy<'info>(
+        ctx: Context<'_, '_, '_, 'info, Buy<'info>>,
+        _trade_history_bump: u8,
+        vault_owner_bump: u8,
+    ) -> Result<()> {
+        ctx.accounts
+            .process_redeem_voucher(vault_owner_bump, ctx.remaining_accounts)
+            .map_err(|error| {
+                ctx.accounts.set_buy_diagnostics();
+                error
+            })
+    }
+
+    pub fn set_voucher_collection<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetVoucherCollection<'info>>,
+        voucher_collection: Option<Pubkey>,
+    ) -> Result<()> {
+        ctx.accounts.process(voucher_collection)
+    }
+
+    pub fn set_dust_policy<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetDustPolicy<'info>>,
+        dust_policy: DustPolicy,
+    ) -> Result<()> {
+        ctx.accounts.process(dust_policy)
+    }
+
+    pub fn set_gatekeeper_program<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetGatekeeperProgram<'info>>,
+        gatekeeper_program: Option<GatekeeperProgram>,
+    ) -> Result<()> {
+        ctx.accounts.process(gatekeeper_program)
+    }
+
+    pub fn set_price_oracle<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetPriceOracle<'info>>,
+        price_oracle: Option<PriceOracle>,
+        oracle_override: bool,
+    ) -> Result<()> {
+        ctx.accounts.process(price_oracle, oracle_override)
+    }
+
+    pub fn set_physical_redemption<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetPhysicalRedemption<'info>>,
+        physical_redemption: Option<PhysicalRedemption>,
+    ) -> Result<()> {
+        ctx.accounts.process(physical_redemption)
+    }
+
+    pub fn confirm_delivery<'info>(
+        ctx: Context<'_, '_, '_, 'info, ConfirmDelivery<'info>>,
+    ) -> Result<()> {
+        ctx.accounts.process()
+    }
+
+    pub fn refund_undelivered<'info>(
+        ctx: Context<'_, '_, '_, 'info, RefundUndelivered<'info>>,
+        treasury_owner_bump: u8,
+    ) -> Result<()> {
+        ctx.accounts.process(treasury_owner_bump)
+    }
+
+    pub fn create_action_queue<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateActionQueue<'info>>,
+        worker: Pubkey,
+    ) -> Result<()> {
+        ctx.accounts.process(worker)
+    }
+
+    pub fn consume_action<'info>(
+        ctx: Context<'_, '_, '_, 'info, ConsumeAction<'info>>,
+        sequence: u64,
+    ) -> Result<()> {
+        ctx.accounts.process(sequence)
+    }
+
+    pub fn set_quantity_discounts<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetQuantityDiscounts<'info>>,
+        quantity_discounts: Vec<QuantityDiscount>,
+    ) -> Result<()> {
+        ctx.accounts.process(quantity_discounts)
+    }
+
+    pub fn set_phases<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetPhases<'info>>,
+        phases: Vec<Phase>,
+    ) -> Result<()> {
+        ctx.accounts.process(phases)
+    }
+
+    pub fn add_market_manager<'info>(
+        ctx: Context<'_, '_, '_, 'info, AddMarketManager<'info>>,
+    ) -> Result<()> {
         ctx.accounts.process()
     }
 
+    pub fn remove_market_manager<'info>(
+        ctx: Context<'_, '_, '_, 'info, RemoveMarketManager<'info>>,
+    ) -> Result<()> {
+        ctx.accounts.process()
+    }
+
+    pub fn close_market<'info>(ctx: Context<'_, '_, '_, 'info, CloseMarket<'info>>) -> Result<()> {
+        ctx.accounts.process(ctx.remaining_accounts)
+    }
+
     pub fn suspend_market<'info>(
         ctx: Context<'_, '_, '_, 'info, SuspendMarket<'info>>,
     ) -> Result<()> {
@@ -77,6 +228,7 @@ pub mod fixed_price_sale {
             mutable,
             new_price,
             new_pieces_in_one_wallet,
+            ctx.remaining_accounts,
         )
     }
 
@@ -98,6 +250,14 @@ pub mod fixed_price_sale {
         )
     }
 
+    pub fn partial_sweep<'info>(
+        ctx: Context<'_, '_, '_, 'info, PartialSweep<'info>>,
+        treasury_owner_bump: u8,
+    ) -> Result<()> {
+        ctx.accounts
+            .process(treasury_owner_bump, ctx.remaining_accounts)
+    }
+
     pub fn create_market<'info>(
         ctx: Context<'_, '_, '_, 'info, CreateMarket<'info>>,
         _treasury_owner_bump: u8,
@@ -109,6 +269,78 @@ pub mod fixed_price_sale {
         start_date: u64,
         end_date: Option<u64>,
         gating_config: Option<GatingConfig>,
+        metadata_overrides: Option<MetadataOverrides>,
+        pda_mints: bool,
+        processor_fee: Option<ProcessorFee>,
+        sweep_basis_points: Option<u16>,
+    ) -> Result<()> {
+        ctx.accounts.process(
+            _treasury_owner_bump,
+            name,
+            description,
+            mutable,
+            price,
+            pieces_in_one_wallet,
+            start_date,
+            end_date,
+            gating_config,
+            metadata_overrides,
+            pda_mints,
+            processor_fee,
+            sweep_basis_points,
+            ctx.remaining_accounts,
+        )
+    }
+
+    pub fn create_market_v2<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateMarketV2<'info>>,
+        _treasury_owner_bump: u8,
+        name: String,
+        description: String,
+        mutable: bool,
+        price: u64,
+        pieces_in_one_wallet: Option<u64>,
+        start_date: u64,
+        end_date: Option<u64>,
+        gating_config: Option<GatingConfig>,
+        metadata_overrides: Option<MetadataOverrides>,
+        pda_mints: bool,
+        processor_fee: Option<ProcessorFee>,
+        sweep_basis_points: Option<u16>,
+    ) -> Result<()> {
+        ctx.accounts.process(
+            _treasury_owner_bump,
+            name,
+            description,
+            mutable,
+            price,
+            pieces_in_one_wallet,
+            start_date,
+            end_date,
+            gating_config,
+            metadata_overrides,
+            pda_mints,
+            processor_fee,
+            sweep_basis_points,
+            ctx.remaining_accounts,
+        )
+    }
+
+    pub fn create_market_pda<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateMarketPda<'info>>,
+        _treasury_owner_bump: u8,
+        name: String,
+        description: String,
+        mutable: bool,
+        price: u64,
+        pieces_in_one_wallet: Option<u64>,
+        start_date: u64,
+        end_date: Option<u64>,
+        gating_config: Option<GatingConfig>,
+        metadata_overrides: Option<MetadataOverrides>,
+        pda_mints: bool,
+        processor_fee: Option<ProcessorFee>,
+        sweep_basis_points: Option<u16>,
     ) -> Result<()> {
         ctx.accounts.process(
             _treasury_owner_bump,
@@ -120,10 +352,24 @@ pub mod fixed_price_sale {
             start_date,
             end_date,
             gating_config,
+            metadata_overrides,
+            pda_mints,
+            processor_fee,
+            sweep_basis_points,
             ctx.remaining_accounts,
         )
     }
 
+    pub fn clone_market<'info>(
+        ctx: Context<'_, '_, '_, 'info, CloneMarket<'info>>,
+        treasury_owner_bump: u8,
+        start_date: u64,
+        end_date: Option<u64>,
+    ) -> Result<()> {
+        ctx.accounts
+            .process(treasury_owner_bump, start_date, end_date)
+    }
+
     pub fn claim_resource<'info>(
         ctx: Context<'_, '_, '_, 'info, ClaimResource<'info>>,
         vault_owner_bump: u8,
@@ -131,6 +377,18 @@ pub mod fixed_price_sale {
         ctx.accounts.process(vault_owner_bump)
     }
 
+    pub fn sync_store_stats<'info>(
+        ctx: Context<'_, '_, '_, 'info, SyncStoreStats<'info>>,
+    ) -> Result<()> {
+        ctx.accounts.process()
+    }
+
+    pub fn migrate_account<'info>(
+        ctx: Context<'_, '_, '_, 'info, MigrateAccount<'info>>,
+    ) -> Result<()> {
+        ctx.accounts.process()
+    }
+
     pub fn save_primary_metadata_creators<'info>(
         ctx: Context<'_, '_, '_, 'info, SavePrimaryMetadataCreators<'info>>,
         primary_metadata_creators_bump: u8,
@@ -139,6 +397,52 @@ pub mod fixed_price_sale {
         ctx.accounts
             .process(primary_metadata_creators_bump, creators)
     }
+
+    pub fn rotate_store_authority<'info>(
+        ctx: Context<'_, '_, '_, 'info, RotateStoreAuthority<'info>>,
+        new_admin: Pubkey,
+    ) -> Result<()> {
+        ctx.accounts.process(new_admin)
+    }
+
+    pub fn accept_store_authority<'info>(
+        ctx: Context<'_, '_, '_, 'info, AcceptStoreAuthority<'info>>,
+    ) -> Result<()> {
+        ctx.accounts.process()
+    }
+
+    pub fn set_store_payment_mints<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetStorePaymentMints<'info>>,
+        mints: Vec<Pubkey>,
+        admin_override: bool,
+    ) -> Result<()> {
+        ctx.accounts.process(mints, admin_override)
+    }
+
+    pub fn set_global_config<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetGlobalConfig<'info>>,
+        raffle_mode_enabled: bool,
+        oracle_pricing_enabled: bool,
+        max_markets_per_store: Option<u64>,
+        max_processor_fee_basis_points: u16,
+    ) -> Result<()> {
+        ctx.accounts.process(
+            raffle_mode_enabled,
+            oracle_pricing_enabled,
+            max_markets_per_store,
+            max_processor_fee_basis_points,
+        )
+    }
+
+    pub fn view_market<'info>(ctx: Context<'_, '_, '_, 'info, ViewMarket<'info>>) -> Result<()> {
+        ctx.accounts.process(ctx.remaining_accounts)
+    }
+
+    pub fn view_selling_resource<'info>(
+        ctx: Context<'_, '_, '_, 'info, ViewSellingResource<'info>>,
+    ) -> Result<()> {
+        ctx.accounts.process()
+    }
 }
 
 #[derive(Accounts)]
@@ -154,7 +458,7 @@ pub struct CreateStore<'info> {
 #[derive(Accounts)]
 #[instruction(master_edition_bump:u8, vault_owner_bump: u8, max_supply: Option<u64>)]
 pub struct InitSellingResource<'info> {
-    #[account(has_one=admin)]
+    #[account(mut, has_one=admin)]
     store: Box<Account<'info, Store>>,
     #[account(mut)]
     admin: Signer<'info>,
@@ -171,7 +475,7 @@ pub struct InitSellingResource<'info> {
     metadata: UncheckedAccount<'info>,
     #[account(mut, has_one=owner)]
     vault: Box<Account<'info, TokenAccount>>,
-    #[account(seeds=[VAULT_OWNER_PREFIX.as_bytes(), resource_mint.key().as_ref(), store.key().as_ref()], bump=vault_owner_bump)]
+    #[account(seeds=[VAULT_OWNER_PREFIX.as_bytes(), resource_mint.key().as_ref(), store.key().as_ref(), store.vault_owner_version_seed()], bump=vault_owner_bump)]
     /// CHECK: checked in program
     owner: UncheckedAccount<'info>,
     #[account(mut)]
@@ -187,6 +491,59 @@ pub struct InitSellingResource<'info> {
 pub struct CreateMarket<'info> {
     #[account(init, space=Market::LEN, payer=selling_resource_owner)]
     market: Box<Account<'info, Market>>,
+    #[account(mut)]
+    store: Box<Account<'info, Store>>,
+    #[account(mut)]
+    selling_resource_owner: Signer<'info>,
+    #[account(mut, has_one=store)]
+    selling_resource: Box<Account<'info, SellingResource>>,
+    /// CHECK: checked in program
+    mint: UncheckedAccount<'info>,
+    #[account(mut)]
+    /// CHECK: checked in program
+    treasury_holder: UncheckedAccount<'info>,
+    #[account(seeds=[HOLDER_PREFIX.as_bytes(), mint.key().as_ref(), selling_resource.key().as_ref()], bump=treasury_owner_bump)]
+    /// CHECK: checked in program
+    owner: UncheckedAccount<'info>,
+    // Will be created by program
+    #[account(mut)]
+    /// CHECK: checked in program
+    market_name_registry: UncheckedAccount<'info>,
+    // May be empty, if the store admin never restricted treasury mints
+    #[account(seeds=[STORE_PAYMENT_MINTS_PREFIX.as_bytes(), store.key().as_ref()], bump)]
+    /// CHECK: checked in program
+    store_payment_mints: UncheckedAccount<'info>,
+    // May be empty, if the upgrade authority never set a global config
+    #[account(seeds=[GLOBAL_CONFIG_PREFIX.as_bytes()], bump)]
+    /// CHECK: checked in program
+    global_config: UncheckedAccount<'info>,
+    system_program: Program<'info, System>,
+    // if gating config is set collection mint key should be passed
+    // collection_mint: Account<'info, Mint>
+    // if metadata overrides are set master edition metadata should be passed after it
+    // master_metadata: UncheckedAccount<'info>
+}
+
+#[derive(Accounts)]
+pub struct CreateMarketV2<'info> {
+    // `treasury_holder` will be created by program as ATA of the treasury owner, unless treasury mint is native
+    create_market: CreateMarket<'info>,
+    token_program: Program<'info, Token>,
+    associated_token_program: Program<'info, AssociatedToken>,
+    rent: Sysvar<'info, Rent>,
+    // if gating config is set collection mint key should be passed
+    // collection_mint: Account<'info, Mint>
+    // if metadata overrides are set master edition metadata should be passed after it
+    // master_metadata: UncheckedAccount<'info>
+}
+
+#[derive(Accounts)]
+#[instruction(treasury_owner_bump: u8)]
+pub struct CreateMarketPda<'info> {
+    // Derived from `selling_resource.market_index`, which is bumped so the next market gets a new address
+    #[account(init, seeds=[MARKET_PREFIX.as_bytes(), store.key().as_ref(), selling_resource.key().as_ref(), &selling_resource.market_index.to_le_bytes()], bump, space=Market::LEN, payer=selling_resource_owner)]
+    market: Box<Account<'info, Market>>,
+    #[account(mut)]
     store: Box<Account<'info, Store>>,
     #[account(mut)]
     selling_resource_owner: Signer<'info>,
@@ -200,15 +557,56 @@ pub struct CreateMarket<'info> {
     #[account(seeds=[HOLDER_PREFIX.as_bytes(), mint.key().as_ref(), selling_resource.key().as_ref()], bump=treasury_owner_bump)]
     /// CHECK: checked in program
     owner: UncheckedAccount<'info>,
+    // Will be created by program
+    #[account(mut)]
+    /// CHECK: checked in program
+    market_name_registry: UncheckedAccount<'info>,
+    // May be empty, if the store admin never restricted treasury mints
+    #[account(seeds=[STORE_PAYMENT_MINTS_PREFIX.as_bytes(), store.key().as_ref()], bump)]
+    /// CHECK: checked in program
+    store_payment_mints: UncheckedAccount<'info>,
+    // May be empty, if the upgrade authority never set a global config
+    #[account(seeds=[GLOBAL_CONFIG_PREFIX.as_bytes()], bump)]
+    /// CHECK: checked in program
+    global_config: UncheckedAccount<'info>,
     system_program: Program<'info, System>,
     // if gating config is set collection mint key should be passed
     // collection_mint: Account<'info, Mint>
+    // if metadata overrides are set master edition metadata should be passed after it
+    // master_metadata: UncheckedAccount<'info>
+}
+
+#[derive(Accounts)]
+#[instruction(treasury_owner_bump: u8, start_date: u64, end_date: Option<u64>)]
+pub struct CloneMarket<'info> {
+    #[account(init, space=Market::LEN, payer=owner)]
+    market: Box<Account<'info, Market>>,
+    #[account(has_one=owner, has_one=store)]
+    source_market: Box<Account<'info, Market>>,
+    #[account(mut)]
+    store: Box<Account<'info, Store>>,
+    #[account(mut)]
+    owner: Signer<'info>,
+    #[account(mut, has_one=store, has_one=owner)]
+    selling_resource: Box<Account<'info, SellingResource>>,
+    #[account(mut)]
+    /// CHECK: checked in program
+    treasury_holder: UncheckedAccount<'info>,
+    #[account(seeds=[HOLDER_PREFIX.as_bytes(), source_market.treasury_mint.as_ref(), selling_resource.key().as_ref()], bump=treasury_owner_bump)]
+    /// CHECK: checked in program
+    treasury_owner: UncheckedAccount<'info>,
+    // Will be created by program, or taken over from `source_market` if its name is still registered
+    #[account(mut)]
+    /// CHECK: checked in program
+    market_name_registry: UncheckedAccount<'info>,
+    clock: Sysvar<'info, Clock>,
+    system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
 #[instruction(trade_history:u8, vault_owner_bump: u8)]
 pub struct Buy<'info> {
-    #[account(mut, has_one=treasury_holder, has_one=selling_resource)]
+    #[account(mut, has_one=treasury_holder @ ErrorCode::TreasuryMismatch, has_one=selling_resource, has_one=store)]
     market: Box<Account<'info, Market>>,
     #[account(mut)]
     selling_resource: Box<Account<'info, SellingResource>>,
@@ -233,19 +631,23 @@ pub struct Buy<'info> {
     #[account(mut, owner=mpl_token_metadata::id())]
     /// CHECK: checked in program
     master_edition: UncheckedAccount<'info>,
+    // Will be created by program if `Market::pda_mints` is set
     #[account(mut)]
-    new_mint: Box<Account<'info, Mint>>,
+    /// CHECK: checked in program
+    new_mint: UncheckedAccount<'info>,
     // Will be created by `mpl_token_metadata`
     #[account(mut)]
     /// CHECK: checked in program
     edition_marker: UncheckedAccount<'info>,
     #[account(mut, has_one=owner)]
     vault: Box<Account<'info, TokenAccount>>,
-    #[account(seeds=[VAULT_OWNER_PREFIX.as_bytes(), selling_resource.resource.as_ref(), selling_resource.store.as_ref()], bump=vault_owner_bump)]
+    #[account(seeds=[VAULT_OWNER_PREFIX.as_bytes(), selling_resource.resource.as_ref(), selling_resource.store.as_ref(), selling_resource.vault_owner_version_seed()], bump=vault_owner_bump)]
     /// CHECK: checked in program
     owner: UncheckedAccount<'info>,
-    #[account(mut, constraint = new_token_account.owner == user_wallet.key())]
-    new_token_account: Box<Account<'info, TokenAccount>>,
+    // Will be created by program if `Market::pda_mints` is set
+    #[account(mut)]
+    /// CHECK: checked in program
+    new_token_account: UncheckedAccount<'info>,
     #[account(mut, owner=mpl_token_metadata::id())]
     /// CHECK: checked in program
     master_edition_metadata: UncheckedAccount<'info>,
@@ -255,18 +657,45 @@ pub struct Buy<'info> {
     token_metadata_program: UncheckedAccount<'info>,
     token_program: Program<'info, Token>,
     system_program: Program<'info, System>,
+    associated_token_program: Program<'info, AssociatedToken>,
+    #[account(mut)]
+    store: Box<Account<'info, Store>>,
+    #[account(init, seeds=[PROVENANCE_RECORD_PREFIX.as_bytes(), new_mint.key().as_ref()], bump, payer=user_wallet, space=ProvenanceRecord::LEN)]
+    provenance_record: Box<Account<'info, ProvenanceRecord>>,
     // if gatekeeper set for the collection these accounts also should be passed
     // IMPORTANT: accounts should be passed strictly in this order
     // user_collection_token_account: Account<'info, TokenAccount>
     // token_account_mint: Account<'info, Mint>
     // metadata_account: UncheckedAccount<'info>
+    // for `redeem_voucher_buy` voucher accounts are passed instead, strictly in this order
+    // voucher_token_account: Account<'info, TokenAccount>
+    // voucher_mint: Account<'info, Mint>
+    // voucher_metadata: UncheckedAccount<'info>
+    // followed by the gating accounts if the market or its active phase is gated
+    // for `buy_sponsored` the market `SponsorVault` precedes all of them
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64, per_wallet_cap: u64)]
+pub struct FundSponsorVault<'info> {
+    #[account(has_one=owner, has_one=store)]
+    market: Box<Account<'info, Market>>,
+    #[account(mut)]
+    store: Box<Account<'info, Store>>,
+    #[account(mut)]
+    owner: Signer<'info>,
+    #[account(init_if_needed, seeds=[SPONSOR_VAULT_PREFIX.as_bytes(), market.key().as_ref()], bump, payer=owner, space=SponsorVault::LEN)]
+    sponsor_vault: Box<Account<'info, SponsorVault>>,
+    system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
 #[instruction(treasury_owner_bump: u8, payout_ticket_bump: u8)]
 pub struct Withdraw<'info> {
-    #[account(has_one=treasury_holder, has_one=selling_resource, has_one=treasury_mint)]
+    #[account(mut, has_one=treasury_holder, has_one=selling_resource, has_one=treasury_mint, has_one=store)]
     market: Box<Account<'info, Market>>,
+    #[account(mut)]
+    store: Box<Account<'info, Store>>,
     selling_resource: Box<Account<'info, SellingResource>>,
     #[account(owner=mpl_token_metadata::id())]
     /// CHECK: checked in program
@@ -288,6 +717,8 @@ pub struct Withdraw<'info> {
     payer: Signer<'info>,
     #[account(init_if_needed, seeds=[PAYOUT_TICKET_PREFIX.as_bytes(), market.key().as_ref(), funder.key().as_ref()], bump, payer=payer, space=PayoutTicket::LEN)]
     payout_ticket: Box<Account<'info, PayoutTicket>>,
+    #[account(init_if_needed, seeds=[CLAIM_TALLY_PREFIX.as_bytes(), market.key().as_ref()], bump, payer=payer, space=ClaimTally::LEN)]
+    claim_tally: Box<Account<'info, ClaimTally>>,
     rent: Sysvar<'info, Rent>,
     clock: Sysvar<'info, Clock>,
     token_program: Program<'info, Token>,
@@ -295,11 +726,43 @@ pub struct Withdraw<'info> {
     system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+#[instruction(treasury_owner_bump: u8)]
+pub struct PartialSweep<'info> {
+    #[account(mut, has_one=owner, has_one=treasury_holder, has_one=selling_resource, has_one=treasury_mint, has_one=store)]
+    market: Box<Account<'info, Market>>,
+    #[account(mut)]
+    store: Box<Account<'info, Store>>,
+    selling_resource: Box<Account<'info, SellingResource>>,
+    #[account(owner=mpl_token_metadata::id())]
+    /// CHECK: checked in program
+    metadata: UncheckedAccount<'info>,
+    #[account(mut)]
+    /// CHECK: checked in program
+    treasury_holder: UncheckedAccount<'info>,
+    /// CHECK: checked in program
+    treasury_mint: UncheckedAccount<'info>,
+    #[account(seeds=[HOLDER_PREFIX.as_bytes(), market.treasury_mint.as_ref(), market.selling_resource.as_ref()], bump=treasury_owner_bump)]
+    /// CHECK: checked in program
+    treasury_owner: UncheckedAccount<'info>,
+    #[account(mut)]
+    /// CHECK: checked in program
+    destination: UncheckedAccount<'info>,
+    owner: Signer<'info>,
+    clock: Sysvar<'info, Clock>,
+    token_program: Program<'info, Token>,
+    system_program: Program<'info, System>,
+    // if primary sale didn't happen `PrimaryMetadataCreators` should be passed
+    // primary_metadata_creators: Account<'info, PrimaryMetadataCreators>
+}
+
 #[derive(Accounts)]
 #[instruction(vault_owner_bump: u8)]
 pub struct ClaimResource<'info> {
-    #[account(has_one=selling_resource, has_one=treasury_holder)]
+    #[account(has_one=selling_resource, has_one=treasury_holder, has_one=store)]
     market: Account<'info, Market>,
+    #[account(mut)]
+    store: Box<Account<'info, Store>>,
     /// CHECK: checked in program
     treasury_holder: UncheckedAccount<'info>,
     #[account(has_one=vault, constraint = selling_resource.owner == selling_resource_owner.key())]
@@ -310,7 +773,7 @@ pub struct ClaimResource<'info> {
     #[account(mut, owner=mpl_token_metadata::id())]
     /// CHECK: checked in program
     metadata: UncheckedAccount<'info>,
-    #[account(seeds=[VAULT_OWNER_PREFIX.as_bytes(), selling_resource.resource.as_ref(), selling_resource.store.as_ref()], bump=vault_owner_bump)]
+    #[account(seeds=[VAULT_OWNER_PREFIX.as_bytes(), selling_resource.resource.as_ref(), selling_resource.store.as_ref(), selling_resource.vault_owner_version_seed()], bump=vault_owner_bump)]
     /// CHECK: checked in program
     owner: UncheckedAccount<'info>,
     #[account(mut)]
@@ -325,39 +788,269 @@ pub struct ClaimResource<'info> {
 #[derive(Accounts)]
 #[instruction()]
 pub struct CloseMarket<'info> {
-    #[account(mut, has_one=owner)]
+    #[account(mut, has_one=owner, has_one=store)]
     market: Account<'info, Market>,
+    #[account(mut)]
+    store: Box<Account<'info, Store>>,
+    #[account(mut)]
     owner: Signer<'info>,
     clock: Sysvar<'info, Clock>,
+    // `MarketNameRegistry` of the market name, closed if it's registered
+    // market_name_registry: UncheckedAccount<'info>
 }
 
 #[derive(Accounts)]
 #[instruction()]
 pub struct SuspendMarket<'info> {
-    #[account(mut, has_one=owner)]
+    #[account(mut, has_one=store)]
     market: Account<'info, Market>,
+    #[account(mut)]
+    store: Box<Account<'info, Store>>,
     owner: Signer<'info>,
+    // May be empty, if signed by the market owner
+    #[account(seeds=[MARKET_MANAGER_PREFIX.as_bytes(), market.key().as_ref(), owner.key().as_ref()], bump)]
+    /// CHECK: checked in program
+    market_manager: UncheckedAccount<'info>,
     clock: Sysvar<'info, Clock>,
 }
 
 #[derive(Accounts)]
 #[instruction()]
 pub struct ResumeMarket<'info> {
-    #[account(mut, has_one=owner)]
+    #[account(mut, has_one=store)]
     market: Account<'info, Market>,
+    #[account(mut)]
+    store: Box<Account<'info, Store>>,
     owner: Signer<'info>,
+    // May be empty, if signed by the market owner
+    #[account(seeds=[MARKET_MANAGER_PREFIX.as_bytes(), market.key().as_ref(), owner.key().as_ref()], bump)]
+    /// CHECK: checked in program
+    market_manager: UncheckedAccount<'info>,
     clock: Sysvar<'info, Clock>,
 }
 
 #[derive(Accounts)]
 #[instruction(new_name: Option<String>, new_description: Option<String>, mutable: Option<bool>, new_price: Option<u64>, new_pieces_in_one_wallet: Option<u64>)]
 pub struct ChangeMarket<'info> {
-    #[account(mut, has_one=owner)]
+    #[account(mut, has_one=store)]
+    market: Account<'info, Market>,
+    #[account(mut)]
+    store: Box<Account<'info, Store>>,
+    #[account(mut)]
+    owner: Signer<'info>,
+    // May be empty, if signed by the market owner
+    #[account(seeds=[MARKET_MANAGER_PREFIX.as_bytes(), market.key().as_ref(), owner.key().as_ref()], bump)]
+    /// CHECK: checked in program
+    market_manager: UncheckedAccount<'info>,
+    clock: Sysvar<'info, Clock>,
+    system_program: Program<'info, System>,
+    // if market is renamed these accounts also should be passed
+    // IMPORTANT: accounts should be passed strictly in this order
+    // new_market_name_registry: UncheckedAccount<'info>
+    // market_name_registry: UncheckedAccount<'info> (of the current name, closed if it's registered)
+}
+
+#[derive(Accounts)]
+#[instruction(voucher_collection: Option<Pubkey>)]
+pub struct SetVoucherCollection<'info> {
+    #[account(mut, has_one=store)]
     market: Account<'info, Market>,
+    #[account(mut)]
+    store: Box<Account<'info, Store>>,
     owner: Signer<'info>,
+    // May be empty, if signed by the market owner
+    #[account(seeds=[MARKET_MANAGER_PREFIX.as_bytes(), market.key().as_ref(), owner.key().as_ref()], bump)]
+    /// CHECK: checked in program
+    market_manager: UncheckedAccount<'info>,
     clock: Sysvar<'info, Clock>,
 }
 
+#[derive(Accounts)]
+#[instruction(dust_policy: DustPolicy)]
+pub struct SetDustPolicy<'info> {
+    #[account(mut, has_one=store)]
+    market: Account<'info, Market>,
+    #[account(mut)]
+    store: Box<Account<'info, Store>>,
+    owner: Signer<'info>,
+    // May be empty, if signed by the market owner
+    #[account(seeds=[MARKET_MANAGER_PREFIX.as_bytes(), market.key().as_ref(), owner.key().as_ref()], bump)]
+    /// CHECK: checked in program
+    market_manager: UncheckedAccount<'info>,
+    clock: Sysvar<'info, Clock>,
+}
+
+#[derive(Accounts)]
+#[instruction(gatekeeper_program: Option<GatekeeperProgram>)]
+pub struct SetGatekeeperProgram<'info> {
+    #[account(mut, has_one=store)]
+    market: Account<'info, Market>,
+    #[account(mut)]
+    store: Box<Account<'info, Store>>,
+    owner: Signer<'info>,
+    // May be empty, if signed by the market owner
+    #[account(seeds=[MARKET_MANAGER_PREFIX.as_bytes(), market.key().as_ref(), owner.key().as_ref()], bump)]
+    /// CHECK: checked in program
+    market_manager: UncheckedAccount<'info>,
+    clock: Sysvar<'info, Clock>,
+}
+
+#[derive(Accounts)]
+#[instruction(price_oracle: Option<PriceOracle>, oracle_override: bool)]
+pub struct SetPriceOracle<'info> {
+    #[account(mut, has_one=store)]
+    market: Account<'info, Market>,
+    #[account(mut)]
+    store: Box<Account<'info, Store>>,
+    owner: Signer<'info>,
+    // May be empty, if signed by the market owner
+    #[account(seeds=[MARKET_MANAGER_PREFIX.as_bytes(), market.key().as_ref(), owner.key().as_ref()], bump)]
+    /// CHECK: checked in program
+    market_manager: UncheckedAccount<'info>,
+    clock: Sysvar<'info, Clock>,
+    // May be empty, if the upgrade authority never set a global config
+    #[account(seeds=[GLOBAL_CONFIG_PREFIX.as_bytes()], bump)]
+    /// CHECK: checked in program
+    global_config: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(physical_redemption: Option<PhysicalRedemption>)]
+pub struct SetPhysicalRedemption<'info> {
+    #[account(mut, has_one=store)]
+    market: Account<'info, Market>,
+    #[account(mut)]
+    store: Box<Account<'info, Store>>,
+    owner: Signer<'info>,
+    // May be empty, if signed by the market owner
+    #[account(seeds=[MARKET_MANAGER_PREFIX.as_bytes(), market.key().as_ref(), owner.key().as_ref()], bump)]
+    /// CHECK: checked in program
+    market_manager: UncheckedAccount<'info>,
+    clock: Sysvar<'info, Clock>,
+}
+
+#[derive(Accounts)]
+#[instruction()]
+pub struct ConfirmDelivery<'info> {
+    #[account(mut, has_one=store)]
+    market: Box<Account<'info, Market>>,
+    #[account(mut)]
+    store: Box<Account<'info, Store>>,
+    #[account(mut, has_one=market)]
+    provenance_record: Box<Account<'info, ProvenanceRecord>>,
+    // Buyer of the edition or arbiter of the delivery
+    signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(treasury_owner_bump: u8)]
+pub struct RefundUndelivered<'info> {
+    #[account(mut, has_one=treasury_holder, has_one=store)]
+    market: Box<Account<'info, Market>>,
+    #[account(mut)]
+    store: Box<Account<'info, Store>>,
+    #[account(mut, has_one=market)]
+    provenance_record: Box<Account<'info, ProvenanceRecord>>,
+    #[account(mut)]
+    /// CHECK: checked in program
+    treasury_holder: UncheckedAccount<'info>,
+    #[account(seeds=[HOLDER_PREFIX.as_bytes(), market.treasury_mint.as_ref(), market.selling_resource.as_ref()], bump=treasury_owner_bump)]
+    /// CHECK: checked in program
+    treasury_owner: UncheckedAccount<'info>,
+    #[account(mut)]
+    /// CHECK: checked in program
+    destination: UncheckedAccount<'info>,
+    // Edition of the purchase, burned as part of the refund
+    #[account(mut)]
+    edition_mint: Box<Account<'info, Mint>>,
+    #[account(mut)]
+    edition_token_account: Box<Account<'info, TokenAccount>>,
+    buyer: Signer<'info>,
+    clock: Sysvar<'info, Clock>,
+    token_program: Program<'info, Token>,
+    system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(worker: Pubkey)]
+pub struct CreateActionQueue<'info> {
+    #[account(mut, has_one=owner, has_one=store)]
+    market: Box<Account<'info, Market>>,
+    #[account(mut)]
+    store: Box<Account<'info, Store>>,
+    #[account(mut)]
+    owner: Signer<'info>,
+    #[account(init, seeds=[ACTION_QUEUE_PREFIX.as_bytes(), market.key().as_ref()], bump, payer=owner, space=ActionQueue::LEN)]
+    action_queue: Box<Account<'info, ActionQueue>>,
+    system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(sequence: u64)]
+pub struct ConsumeAction<'info> {
+    market: Box<Account<'info, Market>>,
+    #[account(mut, seeds=[ACTION_QUEUE_PREFIX.as_bytes(), market.key().as_ref()], bump, has_one=market, has_one=worker)]
+    action_queue: Box<Account<'info, ActionQueue>>,
+    worker: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(quantity_discounts: Vec<QuantityDiscount>)]
+pub struct SetQuantityDiscounts<'info> {
+    #[account(mut, has_one=store)]
+    market: Account<'info, Market>,
+    #[account(mut)]
+    store: Box<Account<'info, Store>>,
+    owner: Signer<'info>,
+    // May be empty, if signed by the market owner
+    #[account(seeds=[MARKET_MANAGER_PREFIX.as_bytes(), market.key().as_ref(), owner.key().as_ref()], bump)]
+    /// CHECK: checked in program
+    market_manager: UncheckedAccount<'info>,
+    clock: Sysvar<'info, Clock>,
+}
+
+#[derive(Accounts)]
+#[instruction(phases: Vec<Phase>)]
+pub struct SetPhases<'info> {
+    #[account(mut, has_one=store)]
+    market: Account<'info, Market>,
+    #[account(mut)]
+    store: Box<Account<'info, Store>>,
+    owner: Signer<'info>,
+    // May be empty, if signed by the market owner
+    #[account(seeds=[MARKET_MANAGER_PREFIX.as_bytes(), market.key().as_ref(), owner.key().as_ref()], bump)]
+    /// CHECK: checked in program
+    market_manager: UncheckedAccount<'info>,
+    clock: Sysvar<'info, Clock>,
+}
+
+#[derive(Accounts)]
+pub struct AddMarketManager<'info> {
+    #[account(mut, has_one=owner, has_one=store)]
+    market: Account<'info, Market>,
+    #[account(mut)]
+    store: Box<Account<'info, Store>>,
+    #[account(mut)]
+    owner: Signer<'info>,
+    /// CHECK: any wallet may be made a manager
+    manager: UncheckedAccount<'info>,
+    #[account(init, seeds=[MARKET_MANAGER_PREFIX.as_bytes(), market.key().as_ref(), manager.key().as_ref()], bump, payer=owner, space=MarketManager::LEN)]
+    market_manager: Box<Account<'info, MarketManager>>,
+    system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveMarketManager<'info> {
+    #[account(mut, has_one=owner, has_one=store)]
+    market: Account<'info, Market>,
+    #[account(mut)]
+    store: Box<Account<'info, Store>>,
+    #[account(mut)]
+    owner: Signer<'info>,
+    #[account(mut, has_one=market, close=owner)]
+    market_manager: Box<Account<'info, MarketManager>>,
+}
+
 #[derive(Accounts)]
 #[instruction(primary_metadata_creators: u8, creators: Vec<mpl_token_metadata::state::Creator>)]
 pub struct SavePrimaryMetadataCreators<'info> {
@@ -370,3 +1063,75 @@ pub struct SavePrimaryMetadataCreators<'info> {
     primary_metadata_creators: Box<Account<'info, PrimaryMetadataCreators>>,
     system_program: Program<'info, System>,
 }
+
+#[derive(Accounts)]
+pub struct SyncStoreStats<'info> {
+    #[account(mut)]
+    store: Box<Account<'info, Store>>,
+    #[account(mut, has_one=store, has_one=selling_resource)]
+    market: Box<Account<'info, Market>>,
+    selling_resource: Box<Account<'info, SellingResource>>,
+}
+
+// Grows an account allocated before fields were added to its type, anyone may pay the rent of the added space
+#[derive(Accounts)]
+pub struct MigrateAccount<'info> {
+    #[account(mut, owner=crate::id())]
+    /// CHECK: checked in program
+    account: UncheckedAccount<'info>,
+    #[account(mut)]
+    payer: Signer<'info>,
+    system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RotateStoreAuthority<'info> {
+    #[account(mut, has_one=admin)]
+    store: Box<Account<'info, Store>>,
+    admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptStoreAuthority<'info> {
+    #[account(mut)]
+    store: Box<Account<'info, Store>>,
+    new_admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(mints: Vec<Pubkey>, admin_override: bool)]
+pub struct SetStorePaymentMints<'info> {
+    #[account(mut, has_one=admin)]
+    store: Box<Account<'info, Store>>,
+    #[account(mut)]
+    admin: Signer<'info>,
+    #[account(init_if_needed, seeds=[STORE_PAYMENT_MINTS_PREFIX.as_bytes(), store.key().as_ref()], bump, payer=admin, space=StorePaymentMints::LEN)]
+    store_payment_mints: Box<Account<'info, StorePaymentMints>>,
+    system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(raffle_mode_enabled: bool, oracle_pricing_enabled: bool, max_markets_per_store: Option<u64>, max_processor_fee_basis_points: u16)]
+pub struct SetGlobalConfig<'info> {
+    #[account(mut)]
+    authority: Signer<'info>,
+    #[account(seeds=[crate::id().as_ref()], bump, seeds::program=bpf_loader_upgradeable::id(), constraint=program_data.upgrade_authority_address == Some(authority.key()) @ ErrorCode::InvalidGlobalConfigAuthority)]
+    program_data: Box<Account<'info, ProgramData>>,
+    #[account(init_if_needed, seeds=[GLOBAL_CONFIG_PREFIX.as_bytes()], bump, payer=authority, space=GlobalConfig::LEN)]
+    global_config: Box<Account<'info, GlobalConfig>>,
+    system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ViewMarket<'info> {
+    #[account(has_one=selling_resource)]
+    market: Box<Account<'info, Market>>,
+    selling_resource: Box<Account<'info, SellingResource>>,
+    clock: Sysvar<'info, Clock>,
+    // trade_history: Account<'info, TradeHistory> (optional, to price the next edition of its wallet)
+}
+
+#[derive(Accounts)]
+pub struct ViewSellingResource<'info> {
+    selling_resource: Box<Account<'info, SellingResource>>,
+}
@@ -0,0 +1,35 @@
+use crate::{error::ErrorCode, state::StoreAuthorityRotated, AcceptStoreAuthority};
+use anchor_lang::prelude::*;
+
+impl<'info> AcceptStoreAuthority<'info> {
+    pub fn process(&mut self) -> Result<()> {
+        let store = &mut self.store;
+        let new_admin = &self.new_admin;
+
+        let pending_admin = store.pending_admin.ok_or(ErrorCode::NoPendingStoreAdmin)?;
+
+        if pending_admin != new_admin.key() {
+            return Err(ErrorCode::InvalidPendingStoreAdmin.into());
+        }
+
+        let previous_admin = store.admin;
+
+        store.admin = pending_admin;
+        store.pending_admin = None;
+        // New `SellingResource` vault owners are derived from the bumped version
+        store.authority_version = store
+            .authority_version
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(StoreAuthorityRotated {
+            store: store.key(),
+            sequence: store.next_sequence()?,
+            previous_admin,
+            admin: store.admin,
+            authority_version: store.authority_version,
+        });
+
+        Ok(())
+    }
+}
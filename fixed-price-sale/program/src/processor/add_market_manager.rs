@@ -0,0 +1,38 @@
+use crate::{
+    error::ErrorCode,
+    state::{MarketState, StoreActivityKind},
+    utils::{emit_store_activity, MAX_MARKET_MANAGERS},
+    AddMarketManager,
+};
+use anchor_lang::prelude::*;
+
+impl<'info> AddMarketManager<'info> {
+    pub fn process(&mut self) -> Result<()> {
+        let market = &mut self.market;
+        let market_manager = &mut self.market_manager;
+
+        // Check, that `Market` is not in `Ended` state
+        if market.state == MarketState::Ended {
+            return Err(ErrorCode::MarketIsEnded.into());
+        }
+
+        if market.managers >= MAX_MARKET_MANAGERS {
+            return Err(ErrorCode::TooManyMarketManagers.into());
+        }
+
+        market_manager.market = market.key();
+        market_manager.manager = self.manager.key();
+        market.managers = market
+            .managers
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit_store_activity(
+            &mut self.store,
+            StoreActivityKind::AddMarketManager,
+            market.key(),
+        )?;
+
+        Ok(())
+    }
+}
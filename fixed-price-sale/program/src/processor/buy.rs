@@ -1,55 +1,371 @@
 use crate::{
     error::ErrorCode,
-    state::{GatingConfig, MarketState, SellingResourceState},
+    state::{
+        ActionQueue, BuyDiagnostics, Delivery, DeliveryState, GatekeeperProgram, GatingConfig,
+        MarketState, MetadataOverrides, PriceOracle, SellingResourceState, SellingResourceType,
+        StoreActivityKind,
+    },
     utils::*,
     Buy,
 };
 use anchor_lang::prelude::*;
 use anchor_lang::{
-    solana_program::{program::invoke, program_pack::Pack, system_instruction},
+    solana_program::{
+        hash::hash,
+        instruction::{AccountMeta, Instruction},
+        program::{invoke, set_return_data},
+        program_pack::Pack,
+        system_instruction,
+    },
     system_program::System,
 };
-use anchor_spl::token;
-use mpl_token_metadata::{state::Metadata, utils::get_supply_off_master_edition};
+use anchor_spl::{associated_token, token};
+use mpl_token_metadata::{
+    state::{DataV2, Metadata},
+    utils::get_supply_off_master_edition,
+};
 
 impl<'info> Buy<'info> {
     pub fn process(
         &mut self,
         _trade_history_bump: u8,
         vault_owner_bump: u8,
+        client_nonce: Option<u64>,
         remaining_accounts: &[AccountInfo<'info>],
     ) -> Result<()> {
+        self.assert_preflight()?;
+        self.assert_can_buy()?;
+
+        // Retried transaction which already landed should not buy another edition
+        if self.trade_history.is_client_nonce_used(client_nonce) {
+            return Err(ErrorCode::DuplicatePurchaseNonce.into());
+        }
+
         let market = &mut self.market;
-        let selling_resource = &mut self.selling_resource;
         let user_token_account = Box::new(&self.user_token_account);
         let user_wallet = &mut self.user_wallet;
-        let trade_history = &mut self.trade_history;
         let treasury_holder = Box::new(&self.treasury_holder);
-        let new_metadata = Box::new(&self.new_metadata);
-        let new_edition = Box::new(&self.new_edition);
-        let master_edition = Box::new(&self.master_edition);
-        let new_mint = &mut self.new_mint;
-        let edition_marker_info = &mut self.edition_marker.to_account_info();
-        let vault = &mut self.vault;
-        let owner = Box::new(&self.owner);
-        let new_token_account = &self.new_token_account;
-        let master_edition_metadata = Box::new(&self.master_edition_metadata);
         let clock = &self.clock;
-        let rent = &self.rent;
         let token_program = &self.token_program;
-        let system_program = &self.system_program;
 
-        let metadata_mint = selling_resource.resource.clone();
-        // do supply +1 to increase master edition supply
-        let edition = get_supply_off_master_edition(&master_edition.to_account_info())?
+        // Staged sales are priced and gated by the phase active now
+        let now = clock.unix_timestamp as u64;
+        let phase = market.active_phase(now).cloned();
+
+        // Wallet pays the price of its next edition, discounted once it bought enough of them
+        let price = self
+            .trade_history
+            .already_bought
+            .checked_add(1)
+            .and_then(|piece| market.piece_price_at(piece, now))
+            .ok_or(ErrorCode::MathOverflow)?;
+        let discount = market
+            .base_price_at(now)
+            .checked_sub(price)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        // Processor fee destination precedes gating accounts
+        let (processor_destination, gating_accounts) = match &market.processor_fee {
+            Some(processor_fee) => {
+                let destination = remaining_accounts
+                    .first()
+                    .ok_or(ErrorCode::InvalidProcessorAccount)?;
+                if destination.key() != processor_fee.destination {
+                    return Err(ErrorCode::InvalidProcessorAccount.into());
+                }
+
+                (Some(destination), &remaining_accounts[1..])
+            }
+            None => (None, remaining_accounts),
+        };
+
+        // Gatekeeper program and its accounts precede gating accounts as well
+        let gating_accounts = match &market.gatekeeper_program {
+            Some(gatekeeper_program) => {
+                let approval_accounts_len = 1 + gatekeeper_program.additional_accounts as usize;
+                if gating_accounts.len() < approval_accounts_len {
+                    return Err(ErrorCode::InvalidGatekeeperProgram.into());
+                }
+                let (approval_accounts, gating_accounts) =
+                    gating_accounts.split_at(approval_accounts_len);
+
+                Self::approve_purchase(
+                    gatekeeper_program,
+                    approval_accounts,
+                    &user_wallet,
+                    &market.to_account_info(),
+                    price,
+                )?;
+
+                gating_accounts
+            }
+            None => gating_accounts,
+        };
+
+        // Price feed of the treasury stablecoin precedes gating accounts as well
+        let gating_accounts = match &market.price_oracle {
+            Some(price_oracle) => {
+                let (price_feed, gating_accounts) = gating_accounts
+                    .split_first()
+                    .ok_or(ErrorCode::InvalidPriceFeed)?;
+                if price_feed.key() != price_oracle.price_feed {
+                    return Err(ErrorCode::InvalidPriceFeed.into());
+                }
+
+                // Owner may resume sales manually while the feed still reports a depeg
+                if !market.oracle_override {
+                    Self::assert_treasury_pegged(price_oracle, price_feed)?;
+                }
+
+                gating_accounts
+            }
+            None => gating_accounts,
+        };
+
+        // Action queue of the market precedes gating accounts as well
+        let (action_queue, gating_accounts) = if market.action_queue {
+            let (action_queue, gating_accounts) = gating_accounts
+                .split_first()
+                .ok_or(ErrorCode::InvalidActionQueue)?;
+            if action_queue.key() != find_action_queue_address(&market.key()).0 {
+                return Err(ErrorCode::InvalidActionQueue.into());
+            }
+
+            (Some(action_queue), gating_accounts)
+        } else {
+            (None, gating_accounts)
+        };
+
+        let gate = match &phase {
+            Some(phase) => &phase.gate,
+            None => &market.gatekeeper,
+        };
+        Self::verify_gating_token(
+            gate,
+            &user_wallet,
+            gating_accounts,
+            clock.unix_timestamp as u64,
+        )?;
+
+        let processor_fee_amount = match &market.processor_fee {
+            Some(processor_fee) => processor_fee.amount(price).ok_or(ErrorCode::MathOverflow)?,
+            None => 0,
+        };
+        let treasury_amount = price
+            .checked_sub(processor_fee_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        // Buy new edition
+        let is_native = market.treasury_mint == System::id();
+
+        if !is_native {
+            if user_token_account.owner != &spl_token::id()
+                || spl_token::state::Account::unpack(
+                    user_token_account.try_borrow_data()?.as_ref(),
+                )?
+                .mint
+                    != market.treasury_mint
+            {
+                return Err(ErrorCode::TreasuryMismatch.into());
+            }
+
+            // Lamports sent straight to a wrapped SOL account only count once it is synced
+            if market.treasury_mint == spl_token::native_mint::id() {
+                Self::sync_wrapped_sol(
+                    &user_token_account.to_account_info(),
+                    &user_wallet.to_account_info(),
+                    &token_program.to_account_info(),
+                    price,
+                )?;
+            }
+
+            Self::transfer_spl(
+                &user_token_account.to_account_info(),
+                &treasury_holder.to_account_info(),
+                &user_wallet.to_account_info(),
+                &token_program.to_account_info(),
+                treasury_amount,
+            )?;
+
+            if let Some(processor_destination) = processor_destination {
+                Self::transfer_spl(
+                    &user_token_account.to_account_info(),
+                    processor_destination,
+                    &user_wallet.to_account_info(),
+                    &token_program.to_account_info(),
+                    processor_fee_amount,
+                )?;
+            }
+        } else {
+            if user_token_account.key() != user_wallet.key() {
+                return Err(ErrorCode::UserWalletMustMatchUserTokenAccount.into());
+            }
+
+            // for native SOL transfer user_wallet key == user_token_account key
+            Self::transfer_native(
+                &user_token_account.to_account_info(),
+                &treasury_holder.to_account_info(),
+                treasury_amount,
+            )?;
+
+            if let Some(processor_destination) = processor_destination {
+                Self::transfer_native(
+                    &user_token_account.to_account_info(),
+                    processor_destination,
+                    processor_fee_amount,
+                )?;
+            }
+        }
+
+        // Processor fee never reaches the treasury, so it is not part of royalties calculation
+        let delivery = match &market.physical_redemption {
+            // Proceeds of physical goods are only shared out once delivery is confirmed
+            Some(physical_redemption) => {
+                market.funds_escrowed = market
+                    .funds_escrowed
+                    .checked_add(treasury_amount)
+                    .ok_or(ErrorCode::MathOverflow)?;
+
+                Some(Delivery {
+                    state: DeliveryState::Pending,
+                    deadline: now
+                        .checked_add(physical_redemption.delivery_window)
+                        .ok_or(ErrorCode::MathOverflow)?,
+                    escrowed: treasury_amount,
+                    arbiter: physical_redemption.arbiter,
+                })
+            }
+            None => {
+                market.funds_collected = market
+                    .funds_collected
+                    .checked_add(treasury_amount)
+                    .ok_or(ErrorCode::MathOverflow)?;
+
+                None
+            }
+        };
+
+        self.mint_edition(vault_owner_bump, price)?;
+        self.provenance_record.delivery = delivery;
+
+        // Worker of the queue is guaranteed to see every purchase, buys stop while it lags behind
+        if let Some(action_queue_info) = action_queue {
+            let mut action_queue = Account::<ActionQueue>::try_from(action_queue_info)?;
+            action_queue
+                .push(
+                    self.user_wallet.key(),
+                    self.provenance_record.edition_mint,
+                    price,
+                    self.clock.slot,
+                )
+                .ok_or(ErrorCode::ActionQueueFull)?;
+            action_queue.exit(&crate::id())?;
+        }
+
+        let market_key = self.market.key();
+        emit_store_activity(&mut self.store, StoreActivityKind::Buy, market_key)?;
+
+        // Markets created before stats were introduced are counted by `sync_store_stats`
+        if self.market.stats_synced {
+            self.store
+                .add_volume(self.market.treasury_mint, price)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+
+        self.trade_history.already_bought = self
+            .trade_history
+            .already_bought
             .checked_add(1)
             .ok_or(ErrorCode::MathOverflow)?;
 
+        self.trade_history.processor_fees_paid = self
+            .trade_history
+            .processor_fees_paid
+            .checked_add(processor_fee_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        if discount > 0 {
+            self.trade_history.discounted_bought = self
+                .trade_history
+                .discounted_bought
+                .checked_add(1)
+                .ok_or(ErrorCode::MathOverflow)?;
+
+            self.trade_history.discounts_received = self
+                .trade_history
+                .discounts_received
+                .checked_add(discount)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+
+        if client_nonce.is_some() {
+            self.trade_history.last_client_nonce = client_nonce;
+        }
+
+        Ok(())
+    }
+
+    /// Checks of client provided accounts, shared by `buy` and `redeem_voucher_buy`, failing fast with a dedicated error
+    /// instead of an opaque one of a downstream CPI
+    pub(crate) fn assert_preflight(&self) -> Result<()> {
+        assert_metadata_accounts_len(&self.master_edition_metadata, &self.master_edition)?;
+
+        if self.market.treasury_mint != System::id()
+            && self.user_token_account.owner != &spl_token::id()
+        {
+            return Err(ErrorCode::TokenAccountOwnerInvalid.into());
+        }
+
+        // New mint of a market with `pda_mints` is created by the program, the master edition sold as is ignores it
+        if self.selling_resource.resource_type == SellingResourceType::MasterEditionAsIs
+            || !self.market.pda_mints
+        {
+            if self.new_mint.owner != &spl_token::id() {
+                return Err(ErrorCode::NewMintOwnerInvalid.into());
+            }
+
+            let new_mint_data = self.new_mint.try_borrow_data()?;
+            if new_mint_data.len() != spl_token::state::Mint::LEN {
+                return Err(ErrorCode::MintAccountSizeInvalid.into());
+            }
+            if new_mint_data[MINT_DECIMALS_OFFSET] != 0 {
+                return Err(ErrorCode::MintDecimalsInvalid.into());
+            }
+
+            if self.new_token_account.owner != &spl_token::id() {
+                return Err(ErrorCode::TokenAccountOwnerInvalid.into());
+            }
+            if self.new_token_account.data_len() != spl_token::state::Account::LEN {
+                return Err(ErrorCode::TokenAccountSizeInvalid.into());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks shared by `buy` and `redeem_voucher_buy`, that `Market` is open and user not reach buy limit
+    pub(crate) fn assert_can_buy(&mut self) -> Result<()> {
+        let market = &mut self.market;
+        let selling_resource = &self.selling_resource;
+        let user_wallet = &self.user_wallet;
+        let trade_history = &mut self.trade_history;
+        let clock = &self.clock;
+
         // Check, that `Market` is not in `Suspended` state
         if market.state == MarketState::Suspended {
             return Err(ErrorCode::MarketIsSuspended.into());
         }
 
+        // Check, that `SellingResource` has editions left, before the ended check it implies
+        if selling_resource.state == SellingResourceState::Exhausted {
+            return Err(ErrorCode::SoldOut.into());
+        }
+        if let Some(max_supply) = selling_resource.max_supply {
+            if selling_resource.supply >= max_supply {
+                return Err(ErrorCode::SoldOut.into());
+            }
+        }
+
         // Check, that `Market` is started
         if market.start_date > clock.unix_timestamp as u64 {
             return Err(ErrorCode::MarketIsNotStarted.into());
@@ -72,65 +388,171 @@ impl<'info> Buy<'info> {
             trade_history.wallet = user_wallet.key();
         }
 
-        // Check, that user not reach buy limit
+        // Check, that user not reach buy limit, editions redeemed with vouchers are counted too
         if let Some(pieces_in_one_wallet) = market.pieces_in_one_wallet {
-            if trade_history.already_bought == pieces_in_one_wallet {
+            if trade_history
+                .pieces_acquired()
+                .ok_or(ErrorCode::MathOverflow)?
+                == pieces_in_one_wallet
+            {
                 return Err(ErrorCode::UserReachBuyLimit.into());
             }
         }
 
+        // Check, that a phase of a staged sale is active and user not reach its buy limit
+        if !market.phases.is_empty() {
+            let phase = market
+                .active_phase(clock.unix_timestamp as u64)
+                .ok_or(ErrorCode::NoActivePhase)?;
+            if let Some(wallet_limit) = phase.wallet_limit {
+                if trade_history
+                    .pieces_acquired()
+                    .ok_or(ErrorCode::MathOverflow)?
+                    >= wallet_limit
+                {
+                    return Err(ErrorCode::UserReachBuyLimit.into());
+                }
+            }
+        }
+
         if market.state != MarketState::Active {
             market.state = MarketState::Active;
         }
 
-        Self::verify_gating_token(
-            &market.gatekeeper,
-            &user_wallet,
-            remaining_accounts,
-            clock.unix_timestamp as u64,
-        )?;
+        Ok(())
+    }
 
-        // Buy new edition
-        let is_native = market.treasury_mint == System::id();
+    /// Mint new edition (or hand over the master edition sold as is) to the user and record its provenance,
+    /// shared by `buy` and `redeem_voucher_buy`
+    pub(crate) fn mint_edition(&mut self, vault_owner_bump: u8, price: u64) -> Result<()> {
+        if self.selling_resource.resource_type == SellingResourceType::MasterEditionAsIs {
+            return self.transfer_master_edition(vault_owner_bump, price);
+        }
 
-        if !is_native {
-            let cpi_program = token_program.to_account_info();
-            let cpi_accounts = token::Transfer {
-                from: user_token_account.to_account_info(),
-                to: treasury_holder.to_account_info(),
-                authority: user_wallet.to_account_info(),
-            };
-            let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-            token::transfer(cpi_ctx, market.price)?;
-        } else {
-            if user_token_account.key() != user_wallet.key() {
-                return Err(ErrorCode::UserWalletMustMatchUserTokenAccount.into());
-            }
+        let market = &mut self.market;
+        let store = &mut self.store;
+        let selling_resource = &mut self.selling_resource;
+        let user_wallet = &self.user_wallet;
+        let trade_history = &self.trade_history;
+        let new_metadata = Box::new(&self.new_metadata);
+        let new_edition = Box::new(&self.new_edition);
+        let master_edition = Box::new(&self.master_edition);
+        let new_mint = &mut self.new_mint;
+        let edition_marker_info = &mut self.edition_marker.to_account_info();
+        let vault = &mut self.vault;
+        let owner = Box::new(&self.owner);
+        let new_token_account = &self.new_token_account;
+        let master_edition_metadata = Box::new(&self.master_edition_metadata);
+        let rent = &self.rent;
+        let token_program = &self.token_program;
+        let system_program = &self.system_program;
+        let associated_token_program = &self.associated_token_program;
+        let provenance_record = &mut self.provenance_record;
 
-            invoke(
-                // for native SOL transfer user_wallet key == user_token_account key
-                &system_instruction::transfer(
-                    &user_token_account.key(),
-                    &treasury_holder.key(),
-                    market.price,
-                ),
+        let metadata_mint = selling_resource.resource.clone();
+        // do supply +1 to increase master edition supply
+        let edition = get_supply_off_master_edition(&master_edition.to_account_info())
+            .ok()
+            .and_then(|supply| supply.checked_add(1))
+            .ok_or(ErrorCode::EditionDerivationFailed)?;
+
+        let vault_owner_seeds = [
+            VAULT_OWNER_PREFIX.as_bytes(),
+            selling_resource.resource.as_ref(),
+            selling_resource.store.as_ref(),
+            selling_resource.vault_owner_version_seed(),
+            &[vault_owner_bump],
+        ];
+
+        // Mint authority of the new edition mint, moved to edition by `mpl_token_metadata`
+        let new_mint_authority = if market.pda_mints {
+            let market_key = market.key();
+            let trade_history_key = trade_history.key();
+            let index = trade_history
+                .pieces_acquired()
+                .ok_or(ErrorCode::MathOverflow)?
+                .to_le_bytes();
+            let new_mint_bump = assert_derivation(
+                &crate::id(),
+                &new_mint.to_account_info(),
                 &[
-                    user_token_account.to_account_info(),
-                    treasury_holder.to_account_info(),
+                    NEW_MINT_PREFIX.as_bytes(),
+                    market_key.as_ref(),
+                    trade_history_key.as_ref(),
+                    &index,
                 ],
             )?;
-        }
 
-        market.funds_collected = market
-            .funds_collected
-            .checked_add(market.price)
-            .ok_or(ErrorCode::MathOverflow)?;
+            sys_create_account(
+                &user_wallet.to_account_info(),
+                &new_mint.to_account_info(),
+                rent.minimum_balance(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN,
+                &spl_token::id(),
+                &[
+                    NEW_MINT_PREFIX.as_bytes(),
+                    market_key.as_ref(),
+                    trade_history_key.as_ref(),
+                    &index,
+                    &[new_mint_bump],
+                ],
+            )?;
+
+            token::initialize_mint(
+                CpiContext::new(
+                    token_program.to_account_info(),
+                    token::InitializeMint {
+                        mint: new_mint.to_account_info(),
+                        rent: rent.to_account_info(),
+                    },
+                ),
+                0,
+                &owner.key(),
+                Some(&owner.key()),
+            )?;
+
+            associated_token::create(CpiContext::new(
+                associated_token_program.to_account_info(),
+                associated_token::Create {
+                    payer: user_wallet.to_account_info(),
+                    associated_token: new_token_account.to_account_info(),
+                    authority: user_wallet.to_account_info(),
+                    mint: new_mint.to_account_info(),
+                    system_program: system_program.to_account_info(),
+                    token_program: token_program.to_account_info(),
+                    rent: rent.to_account_info(),
+                },
+            ))?;
+
+            token::mint_to(
+                CpiContext::new_with_signer(
+                    token_program.to_account_info(),
+                    token::MintTo {
+                        mint: new_mint.to_account_info(),
+                        to: new_token_account.to_account_info(),
+                        authority: owner.to_account_info(),
+                    },
+                    &[&vault_owner_seeds],
+                ),
+                1,
+            )?;
+
+            owner.to_account_info()
+        } else {
+            Self::verify_new_token_account(
+                &new_mint.to_account_info(),
+                &new_token_account.to_account_info(),
+                &user_wallet.key(),
+            )?;
+
+            user_wallet.to_account_info()
+        };
 
         mpl_mint_new_edition_from_master_edition_via_token(
             &new_metadata.to_account_info(),
             &new_edition.to_account_info(),
             &new_mint.to_account_info(),
-            &user_wallet.to_account_info(),
+            &new_mint_authority,
             &user_wallet.to_account_info(),
             &owner.to_account_info(),
             &vault.to_account_info(),
@@ -142,14 +564,19 @@ impl<'info> Buy<'info> {
             &system_program.to_account_info(),
             &rent.to_account_info(),
             edition,
-            &[
-                VAULT_OWNER_PREFIX.as_bytes(),
-                selling_resource.resource.as_ref(),
-                selling_resource.store.as_ref(),
-                &[vault_owner_bump],
-            ],
+            &vault_owner_seeds,
         )?;
 
+        // Apply market specific name suffix / uri to the new edition
+        if let Some(metadata_overrides) = &market.metadata_overrides {
+            Self::apply_metadata_overrides(
+                metadata_overrides,
+                &new_metadata.to_account_info(),
+                &owner.to_account_info(),
+                &vault_owner_seeds,
+            )?;
+        }
+
         mpl_update_primary_sale_happened_via_token(
             &new_metadata.to_account_info(),
             &user_wallet.to_account_info(),
@@ -157,10 +584,19 @@ impl<'info> Buy<'info> {
             &[],
         )?;
 
-        trade_history.already_bought = trade_history
-            .already_bought
-            .checked_add(1)
-            .ok_or(ErrorCode::MathOverflow)?;
+        provenance_record.market = market.key();
+        provenance_record.buyer = user_wallet.key();
+        provenance_record.edition_mint = new_mint.key();
+        provenance_record.edition = edition;
+        provenance_record.price = price;
+        provenance_record.slot = Clock::get()?.slot;
+
+        if market.stats_synced {
+            store.total_editions_sold = store
+                .total_editions_sold
+                .checked_add(1)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
 
         selling_resource.supply = selling_resource
             .supply
@@ -180,7 +616,299 @@ impl<'info> Buy<'info> {
         Ok(())
     }
 
-    fn verify_gating_token(
+    /// Hand the vaulted master edition token over to the user and end the `Market`
+    fn transfer_master_edition(&mut self, vault_owner_bump: u8, price: u64) -> Result<()> {
+        let market = &mut self.market;
+        let store = &mut self.store;
+        let selling_resource = &mut self.selling_resource;
+        let user_wallet = &self.user_wallet;
+        let new_mint = &self.new_mint;
+        let new_token_account = &self.new_token_account;
+        let vault = &self.vault;
+        let owner = &self.owner;
+        let master_edition_metadata = &self.master_edition_metadata;
+        let token_program = &self.token_program;
+        let provenance_record = &mut self.provenance_record;
+
+        if new_mint.key() != selling_resource.resource {
+            return Err(ErrorCode::ResourceMintMismatch.into());
+        }
+
+        Self::verify_new_token_account(
+            &new_mint.to_account_info(),
+            &new_token_account.to_account_info(),
+            &user_wallet.key(),
+        )?;
+
+        // Check, that provided metadata is correct
+        assert_derivation(
+            &mpl_token_metadata::id(),
+            &master_edition_metadata.to_account_info(),
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                selling_resource.resource.as_ref(),
+            ],
+        )?;
+
+        let vault_owner_seeds = [
+            VAULT_OWNER_PREFIX.as_bytes(),
+            selling_resource.resource.as_ref(),
+            selling_resource.store.as_ref(),
+            selling_resource.vault_owner_version_seed(),
+            &[vault_owner_bump],
+        ];
+
+        // Transfer token(ownership)
+        token::transfer(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                token::Transfer {
+                    from: vault.to_account_info(),
+                    to: new_token_account.to_account_info(),
+                    authority: owner.to_account_info(),
+                },
+                &[&vault_owner_seeds],
+            ),
+            1,
+        )?;
+
+        // Update primary sale flag
+        let metadata_state =
+            Metadata::from_account_info(&master_edition_metadata.to_account_info())?;
+        if !metadata_state.primary_sale_happened {
+            mpl_update_primary_sale_happened_via_token(
+                &master_edition_metadata.to_account_info(),
+                &user_wallet.to_account_info(),
+                &new_token_account.to_account_info(),
+                &[],
+            )?;
+        }
+
+        provenance_record.market = market.key();
+        provenance_record.buyer = user_wallet.key();
+        provenance_record.edition_mint = new_mint.key();
+        provenance_record.edition = 0;
+        provenance_record.price = price;
+        provenance_record.slot = Clock::get()?.slot;
+
+        if market.stats_synced {
+            store.total_editions_sold = store
+                .total_editions_sold
+                .checked_add(1)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+
+        selling_resource.supply = 1;
+        selling_resource.state = SellingResourceState::Exhausted;
+        market.state = MarketState::Ended;
+
+        Ok(())
+    }
+
+    /// Set the edition the failed purchase attempted and the resource supply as return data
+    pub(crate) fn set_buy_diagnostics(&self) {
+        let edition = get_supply_off_master_edition(&self.master_edition.to_account_info())
+            .ok()
+            .and_then(|supply| supply.checked_add(1))
+            .unwrap_or_default();
+
+        let diagnostics = BuyDiagnostics {
+            edition,
+            supply: self.selling_resource.supply,
+            max_supply: self.selling_resource.max_supply,
+        };
+        if let Ok(data) = diagnostics.try_to_vec() {
+            set_return_data(&data);
+        }
+    }
+
+    fn transfer_spl(
+        from: &AccountInfo<'info>,
+        to: &AccountInfo<'info>,
+        authority: &AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
+        amount: u64,
+    ) -> Result<()> {
+        let cpi_accounts = token::Transfer {
+            from: from.clone(),
+            to: to.clone(),
+            authority: authority.clone(),
+        };
+        let cpi_ctx = CpiContext::new(token_program.clone(), cpi_accounts);
+
+        token::transfer(cpi_ctx, amount)
+    }
+
+    /// Sync buyer wrapped SOL account, topping it up from the wallet when it holds less than `amount`
+    fn sync_wrapped_sol(
+        user_token_account: &AccountInfo<'info>,
+        user_wallet: &AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
+        amount: u64,
+    ) -> Result<()> {
+        let sync_native = || -> Result<()> {
+            invoke(
+                &spl_token::instruction::sync_native(token_program.key, user_token_account.key)?,
+                &[user_token_account.clone(), token_program.clone()],
+            )?;
+
+            Ok(())
+        };
+
+        sync_native()?;
+
+        let balance =
+            spl_token::state::Account::unpack(user_token_account.try_borrow_data()?.as_ref())?
+                .amount;
+        if balance < amount {
+            Self::transfer_native(user_wallet, user_token_account, amount - balance)?;
+            sync_native()?;
+        }
+
+        Ok(())
+    }
+
+    fn transfer_native(
+        from: &AccountInfo<'info>,
+        to: &AccountInfo<'info>,
+        amount: u64,
+    ) -> Result<()> {
+        invoke(
+            &system_instruction::transfer(&from.key(), &to.key(), amount),
+            &[from.clone(), to.clone()],
+        )?;
+
+        Ok(())
+    }
+
+    fn verify_new_token_account(
+        new_mint: &AccountInfo<'info>,
+        new_token_account: &AccountInfo<'info>,
+        user_wallet: &Pubkey,
+    ) -> Result<()> {
+        if new_mint.owner != &spl_token::id() || new_token_account.owner != &spl_token::id() {
+            return Err(ProgramError::IllegalOwner.into());
+        }
+
+        spl_token::state::Mint::unpack(new_mint.try_borrow_data()?.as_ref())?;
+        let new_token_account_data =
+            spl_token::state::Account::unpack(new_token_account.try_borrow_data()?.as_ref())?;
+
+        if new_token_account_data.owner != *user_wallet
+            || new_token_account_data.mint != new_mint.key()
+        {
+            return Err(ErrorCode::InvalidNewTokenAccount.into());
+        }
+
+        Ok(())
+    }
+
+    fn apply_metadata_overrides(
+        metadata_overrides: &MetadataOverrides,
+        new_metadata: &AccountInfo<'info>,
+        vault_owner: &AccountInfo<'info>,
+        signers_seeds: &[&[u8]],
+    ) -> Result<()> {
+        let metadata = Metadata::from_account_info(new_metadata)?;
+
+        // Edition inherits update authority from the master edition metadata
+        if metadata.update_authority != vault_owner.key() {
+            return Err(ErrorCode::VaultOwnerIsNotUpdateAuthority.into());
+        }
+
+        let mut name = metadata.data.name.trim_matches(char::from(0)).to_string();
+        if let Some(name_suffix) = &metadata_overrides.name_suffix {
+            name.push_str(name_suffix);
+        }
+
+        let uri = match &metadata_overrides.uri {
+            Some(uri) => uri.clone(),
+            None => metadata.data.uri.trim_matches(char::from(0)).to_string(),
+        };
+
+        mpl_update_metadata_accounts_v2(
+            new_metadata,
+            vault_owner,
+            None,
+            Some(DataV2 {
+                name,
+                symbol: metadata.data.symbol.trim_matches(char::from(0)).to_string(),
+                uri,
+                seller_fee_basis_points: metadata.data.seller_fee_basis_points,
+                creators: metadata.data.creators,
+                collection: metadata.collection,
+                uses: metadata.uses,
+            }),
+            None,
+            None,
+            signers_seeds,
+        )
+    }
+
+    /// CPI `approve_purchase(wallet, market, amount)` of the market gatekeeper program, which fails to reject the purchase
+    fn approve_purchase(
+        gatekeeper_program: &GatekeeperProgram,
+        approval_accounts: &[AccountInfo<'info>],
+        user_wallet: &AccountInfo<'info>,
+        market: &AccountInfo<'info>,
+        amount: u64,
+    ) -> Result<()> {
+        let (program, additional_accounts) = approval_accounts
+            .split_first()
+            .ok_or(ErrorCode::InvalidGatekeeperProgram)?;
+        if program.key() != gatekeeper_program.program_id || !program.executable {
+            return Err(ErrorCode::InvalidGatekeeperProgram.into());
+        }
+
+        let mut data = hash(b"global:approve_purchase").to_bytes()[..8].to_vec();
+        data.extend_from_slice(user_wallet.key.as_ref());
+        data.extend_from_slice(market.key.as_ref());
+        data.extend_from_slice(&amount.to_le_bytes());
+
+        let mut accounts = vec![
+            AccountMeta::new_readonly(user_wallet.key(), true),
+            AccountMeta::new_readonly(market.key(), false),
+        ];
+        accounts.extend(additional_accounts.iter().map(|account| {
+            if account.is_writable {
+                AccountMeta::new(account.key(), account.is_signer)
+            } else {
+                AccountMeta::new_readonly(account.key(), account.is_signer)
+            }
+        }));
+
+        let mut account_infos = vec![user_wallet.clone(), market.clone()];
+        account_infos.extend_from_slice(additional_accounts);
+        account_infos.push(program.clone());
+
+        invoke(
+            &Instruction {
+                program_id: gatekeeper_program.program_id,
+                accounts,
+                data,
+            },
+            &account_infos,
+        )?;
+
+        Ok(())
+    }
+
+    /// Refuse the purchase while the treasury stablecoin trades outside of the market tolerance
+    fn assert_treasury_pegged(price_oracle: &PriceOracle, price_feed: &AccountInfo) -> Result<()> {
+        let (price, expo) = get_pyth_price(price_feed)?;
+
+        if price_oracle
+            .is_depegged(price, expo)
+            .ok_or(ErrorCode::InvalidPriceFeed)?
+        {
+            return Err(ErrorCode::TreasuryDepegged.into());
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn verify_gating_token(
         gate: &Option<GatingConfig>,
         user_wallet: &AccountInfo<'info>,
         remaining_accounts: &[AccountInfo<'info>],
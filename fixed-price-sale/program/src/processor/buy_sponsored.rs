@@ -0,0 +1,84 @@
+use crate::{
+    error::ErrorCode,
+    state::{SellingResourceType, SponsorVault, TradeHistory},
+    utils::*,
+    Buy,
+};
+use anchor_lang::{prelude::*, solana_program::program_pack::Pack};
+
+impl<'info> Buy<'info> {
+    /// `buy`, with the trade history and new mint rent paid by the buyer reimbursed from the market `SponsorVault`
+    pub fn process_sponsored(
+        &mut self,
+        trade_history_bump: u8,
+        vault_owner_bump: u8,
+        client_nonce: Option<u64>,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let (sponsor_vault_info, remaining_accounts) = remaining_accounts
+            .split_first()
+            .ok_or(ErrorCode::InvalidSponsorVault)?;
+        if sponsor_vault_info.key() != find_sponsor_vault_address(&self.market.key()).0 {
+            return Err(ErrorCode::InvalidSponsorVault.into());
+        }
+        let mut sponsor_vault = Account::<SponsorVault>::try_from(sponsor_vault_info)?;
+
+        // Trade history is still zeroed when `init_if_needed` created it for this purchase
+        let is_new_trade_history = self.trade_history.wallet == Pubkey::default();
+
+        self.process(
+            trade_history_bump,
+            vault_owner_bump,
+            client_nonce,
+            remaining_accounts,
+        )?;
+
+        let rent = &self.rent;
+        let user_wallet = &self.user_wallet;
+        let trade_history = &mut self.trade_history;
+
+        let mut rent_paid = 0;
+        if is_new_trade_history {
+            rent_paid += rent.minimum_balance(TradeHistory::LEN);
+        }
+        // Master edition sold as is is handed over without a new mint
+        if self.selling_resource.resource_type != SellingResourceType::MasterEditionAsIs {
+            rent_paid += rent.minimum_balance(spl_token::state::Mint::LEN);
+        }
+
+        // Caps keep a single wallet, and all of them together, from draining the vault
+        let wallet_allowance = sponsor_vault
+            .per_wallet_cap
+            .saturating_sub(trade_history.sponsored);
+        let amount = rent_paid
+            .min(wallet_allowance)
+            .min(sponsor_vault.remaining().ok_or(ErrorCode::MathOverflow)?);
+        if amount == 0 {
+            return Ok(());
+        }
+
+        let sponsor_vault_lamports = sponsor_vault_info
+            .lamports()
+            .checked_sub(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let user_wallet_lamports = user_wallet
+            .lamports()
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        **sponsor_vault_info.try_borrow_mut_lamports()? = sponsor_vault_lamports;
+        **user_wallet.try_borrow_mut_lamports()? = user_wallet_lamports;
+
+        sponsor_vault.total_spent = sponsor_vault
+            .total_spent
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        sponsor_vault.exit(&crate::id())?;
+
+        trade_history.sponsored = trade_history
+            .sponsored
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        Ok(())
+    }
+}
@@ -1,4 +1,9 @@
-use crate::{error::ErrorCode, state::MarketState, utils::*, ChangeMarket};
+use crate::{
+    error::ErrorCode,
+    state::{MarketState, StoreActivityKind},
+    utils::*,
+    ChangeMarket,
+};
 use anchor_lang::prelude::*;
 
 impl<'info> ChangeMarket<'info> {
@@ -9,7 +14,10 @@ impl<'info> ChangeMarket<'info> {
         mutable: Option<bool>,
         new_price: Option<u64>,
         new_pieces_in_one_wallet: Option<u64>,
+        remaining_accounts: &[AccountInfo<'info>],
     ) -> Result<()> {
+        assert_market_authority(&self.market, self.owner.key, &self.market_manager)?;
+
         let market = &mut self.market;
         let clock = &self.clock;
 
@@ -35,6 +43,31 @@ impl<'info> ChangeMarket<'info> {
                 return Err(ErrorCode::NameIsTooLong.into());
             }
 
+            // Registered name moves along with the market, unless it stays the same
+            if market_name_hash(&new_name) != market_name_hash(&market.name) {
+                let new_market_name_registry = remaining_accounts
+                    .first()
+                    .ok_or(ErrorCode::InvalidMarketNameRegistry)?;
+                create_market_name_registry(
+                    new_market_name_registry,
+                    &self.owner.to_account_info(),
+                    &market.store,
+                    &market.key(),
+                    &new_name,
+                )?;
+
+                let market_name_registry = remaining_accounts
+                    .get(1)
+                    .ok_or(ErrorCode::InvalidMarketNameRegistry)?;
+                close_market_name_registry(
+                    market_name_registry,
+                    &self.owner.to_account_info(),
+                    &market.store,
+                    &market.key(),
+                    &market.name,
+                )?;
+            }
+
             market.name = puffed_out_string(new_name, NAME_MAX_LEN);
         }
 
@@ -63,6 +96,12 @@ impl<'info> ChangeMarket<'info> {
             market.pieces_in_one_wallet = Some(new_pieces_in_one_wallet);
         }
 
+        emit_store_activity(
+            &mut self.store,
+            StoreActivityKind::ChangeMarket,
+            market.key(),
+        )?;
+
         Ok(())
     }
 }
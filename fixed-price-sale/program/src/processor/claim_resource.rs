@@ -1,6 +1,6 @@
 use crate::{
     error::ErrorCode,
-    state::{MarketState, MINIMUM_BALANCE_FOR_SYSTEM_ACCS},
+    state::{MarketState, StoreActivityKind, MINIMUM_BALANCE_FOR_SYSTEM_ACCS},
     utils::*,
     ClaimResource,
 };
@@ -66,6 +66,7 @@ impl<'info> ClaimResource<'info> {
             VAULT_OWNER_PREFIX.as_bytes(),
             selling_resource.resource.as_ref(),
             selling_resource.store.as_ref(),
+            selling_resource.vault_owner_version_seed(),
             &[vault_owner_bump],
         ]];
 
@@ -90,6 +91,12 @@ impl<'info> ClaimResource<'info> {
         let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
         token::transfer(cpi_ctx, 1)?;
 
+        emit_store_activity(
+            &mut self.store,
+            StoreActivityKind::ClaimResource,
+            market.key(),
+        )?;
+
         Ok(())
     }
 }
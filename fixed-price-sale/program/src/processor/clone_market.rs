@@ -0,0 +1,177 @@
+use crate::{
+    error::ErrorCode,
+    state::{
+        MarketState, SellingResourceState, StoreActivityKind, MINIMUM_BALANCE_FOR_SYSTEM_ACCS,
+    },
+    utils::*,
+    CloneMarket,
+};
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program::invoke, system_instruction},
+};
+use anchor_spl::token::accessor;
+
+impl<'info> CloneMarket<'info> {
+    pub fn process(
+        &mut self,
+        _treasury_owner_bump: u8,
+        start_date: u64,
+        end_date: Option<u64>,
+    ) -> Result<()> {
+        let market = &mut self.market;
+        let source_market = &self.source_market;
+        let store = &mut self.store;
+        let owner = &self.owner;
+        let selling_resource = &mut self.selling_resource;
+        let treasury_holder = self.treasury_holder.to_account_info();
+        let treasury_owner = &self.treasury_owner;
+        let market_name_registry = self.market_name_registry.to_account_info();
+        let clock = &self.clock;
+
+        // Check, that source `Market` is ended, either closed or past its end date
+        let source_ended = source_market.state == MarketState::Ended
+            || matches!(source_market.end_date, Some(end_date) if end_date < clock.unix_timestamp as u64);
+        if !source_ended {
+            return Err(ErrorCode::MarketIsNotEnded.into());
+        }
+
+        // Pieces in one wallet cannot be greater than Max Supply value
+        if source_market.pieces_in_one_wallet.is_some()
+            && selling_resource.max_supply.is_some()
+            && source_market.pieces_in_one_wallet.unwrap() > selling_resource.max_supply.unwrap()
+        {
+            return Err(ErrorCode::PiecesInOneWalletIsTooMuch.into());
+        }
+
+        // Only new just created selling resource can be used to create market
+        if selling_resource.state != SellingResourceState::Created {
+            return Err(ErrorCode::SellingResourceAlreadyTaken.into());
+        }
+
+        // start_date cannot be in the past
+        if start_date < clock.unix_timestamp as u64 {
+            return Err(ErrorCode::StartDateIsInPast.into());
+        }
+
+        // end_date should not be greater than start_date
+        if end_date.is_some() && start_date > end_date.unwrap() {
+            return Err(ErrorCode::EndDateIsEarlierThanBeginDate.into());
+        }
+
+        // Gating collection was checked at source creation, only the gating date has to fit new dates
+        if let Some(gating_time) = source_market
+            .gatekeeper
+            .as_ref()
+            .and_then(|gating_data| gating_data.gating_time)
+        {
+            if gating_time < start_date {
+                return Err(ErrorCode::WrongGatingDate.into());
+            }
+            if let Some(end_date) = end_date {
+                if gating_time > end_date {
+                    return Err(ErrorCode::WrongGatingDate.into());
+                }
+            }
+        }
+
+        let is_native = source_market.treasury_mint == System::id();
+
+        if !is_native {
+            if treasury_holder.owner != &anchor_spl::token::ID {
+                return Err(ProgramError::IllegalOwner.into());
+            }
+
+            if accessor::mint(&treasury_holder)? != source_market.treasury_mint {
+                return Err(ProgramError::InvalidAccountData.into());
+            }
+
+            if accessor::authority(&treasury_holder)? != treasury_owner.key() {
+                return Err(ProgramError::InvalidAccountData.into());
+            }
+        } else {
+            // for native SOL we use PDA as a treasury holder
+            // because of security reasons(only program can spend this SOL)
+            if treasury_holder.key != treasury_owner.key {
+                return Err(ProgramError::InvalidAccountData.into());
+            }
+
+            // we need fund treasury holder account such as it will hold some metadata with SOL balance
+            invoke(
+                &system_instruction::transfer(
+                    &owner.key(),
+                    &treasury_holder.key(),
+                    MINIMUM_BALANCE_FOR_SYSTEM_ACCS,
+                ),
+                &[owner.to_account_info(), treasury_holder.to_account_info()],
+            )?;
+        }
+
+        // Name is released when the source market is closed, otherwise it moves to the clone
+        if market_name_registry.data_is_empty() {
+            create_market_name_registry(
+                &market_name_registry,
+                &owner.to_account_info(),
+                &store.key(),
+                &market.key(),
+                &source_market.name,
+            )?;
+        } else {
+            transfer_market_name_registry(
+                &market_name_registry,
+                &store.key(),
+                &source_market.key(),
+                &market.key(),
+                &source_market.name,
+            )?;
+        }
+
+        market.store = store.key();
+        market.selling_resource = selling_resource.key();
+        market.treasury_mint = source_market.treasury_mint;
+        market.treasury_holder = treasury_holder.key();
+        market.treasury_owner = treasury_owner.key();
+        market.owner = owner.key();
+        market.name = source_market.name.clone();
+        market.description = source_market.description.clone();
+        market.mutable = source_market.mutable;
+        market.price = source_market.price;
+        market.pieces_in_one_wallet = source_market.pieces_in_one_wallet;
+        market.start_date = start_date;
+        market.end_date = end_date;
+        market.state = MarketState::Created;
+        market.funds_collected = 0;
+        market.gatekeeper = source_market.gatekeeper.clone();
+        market.metadata_overrides = source_market.metadata_overrides.clone();
+        market.pda_mints = source_market.pda_mints;
+        market.stats_synced = true;
+        market.voucher_collection = source_market.voucher_collection;
+        market.processor_fee = source_market.processor_fee.clone();
+        market.sweep_basis_points = source_market.sweep_basis_points;
+        market.last_sweep_epoch = None;
+        market.funds_swept = 0;
+        market.gatekeeper_program = source_market.gatekeeper_program.clone();
+        market.price_oracle = source_market.price_oracle.clone();
+        market.oracle_override = false;
+        market.dust_policy = source_market.dust_policy;
+        market.quantity_discounts = source_market.quantity_discounts.clone();
+        // Phases are dated for the source market's sale
+        market.phases = Vec::new();
+        // Managers are granted per market
+        market.managers = 0;
+        market.physical_redemption = source_market.physical_redemption.clone();
+        market.funds_escrowed = 0;
+        // Action queues are created per market
+        market.action_queue = false;
+        selling_resource.state = SellingResourceState::InUse;
+
+        store.total_markets = store
+            .total_markets
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit_store_activity(store, StoreActivityKind::CloneMarket, market.key())?;
+
+        Ok(())
+    }
+}
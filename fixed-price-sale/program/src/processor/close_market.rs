@@ -1,8 +1,13 @@
-use crate::{error::ErrorCode, state::MarketState, CloseMarket};
+use crate::{
+    error::ErrorCode,
+    state::{MarketState, StoreActivityKind},
+    utils::*,
+    CloseMarket,
+};
 use anchor_lang::prelude::*;
 
 impl<'info> CloseMarket<'info> {
-    pub fn process(&mut self) -> Result<()> {
+    pub fn process(&mut self, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
         let market = &mut self.market;
         let clock = &self.clock;
 
@@ -18,6 +23,23 @@ impl<'info> CloseMarket<'info> {
 
         market.state = MarketState::Ended;
 
+        let market_name_registry = remaining_accounts
+            .first()
+            .ok_or(ErrorCode::InvalidMarketNameRegistry)?;
+        close_market_name_registry(
+            market_name_registry,
+            &self.owner.to_account_info(),
+            &market.store,
+            &market.key(),
+            &market.name,
+        )?;
+
+        emit_store_activity(
+            &mut self.store,
+            StoreActivityKind::CloseMarket,
+            market.key(),
+        )?;
+
         Ok(())
     }
 }
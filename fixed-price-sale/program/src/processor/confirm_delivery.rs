@@ -0,0 +1,46 @@
+use crate::{
+    error::ErrorCode,
+    state::{DeliveryState, StoreActivityKind},
+    utils::emit_store_activity,
+    ConfirmDelivery,
+};
+use anchor_lang::prelude::*;
+
+impl<'info> ConfirmDelivery<'info> {
+    pub fn process(&mut self) -> Result<()> {
+        let market = &mut self.market;
+        let provenance_record = &mut self.provenance_record;
+        let signer = self.signer.key();
+        let buyer = provenance_record.buyer;
+
+        let delivery = provenance_record
+            .delivery
+            .as_mut()
+            .filter(|delivery| delivery.state == DeliveryState::Pending)
+            .ok_or(ErrorCode::DeliveryNotPending)?;
+
+        // Check, that signer is the buyer or the arbiter set at purchase
+        if signer != buyer && Some(signer) != delivery.arbiter {
+            return Err(ErrorCode::InvalidDeliverySigner.into());
+        }
+
+        // Confirmed proceeds are shared out at `withdraw` like any other sale
+        market.funds_escrowed = market
+            .funds_escrowed
+            .checked_sub(delivery.escrowed)
+            .ok_or(ErrorCode::MathOverflow)?;
+        market.funds_collected = market
+            .funds_collected
+            .checked_add(delivery.escrowed)
+            .ok_or(ErrorCode::MathOverflow)?;
+        delivery.state = DeliveryState::Confirmed;
+
+        emit_store_activity(
+            &mut self.store,
+            StoreActivityKind::ConfirmDelivery,
+            market.key(),
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,21 @@
+use crate::{error::ErrorCode, state::ActionConsumed, ConsumeAction};
+use anchor_lang::prelude::*;
+
+impl<'info> ConsumeAction<'info> {
+    pub fn process(&mut self, sequence: u64) -> Result<()> {
+        // Consuming twice fails, so a worker retrying after a dropped transaction is told it already went through
+        let action = self
+            .action_queue
+            .consume(sequence)
+            .ok_or(ErrorCode::ActionNotQueued)?;
+
+        emit!(ActionConsumed {
+            market: self.market.key(),
+            sequence: action.sequence,
+            buyer: action.buyer,
+            edition_mint: action.edition_mint,
+        });
+
+        Ok(())
+    }
+}
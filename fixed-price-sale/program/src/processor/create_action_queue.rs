@@ -0,0 +1,33 @@
+use crate::{
+    error::ErrorCode,
+    state::{MarketState, StoreActivityKind},
+    utils::emit_store_activity,
+    CreateActionQueue,
+};
+use anchor_lang::prelude::*;
+
+impl<'info> CreateActionQueue<'info> {
+    pub fn process(&mut self, worker: Pubkey) -> Result<()> {
+        let market = &mut self.market;
+        let action_queue = &mut self.action_queue;
+
+        // Check, that `Market` is not in `Ended` state
+        if market.state == MarketState::Ended {
+            return Err(ErrorCode::MarketIsEnded.into());
+        }
+
+        action_queue.market = market.key();
+        action_queue.worker = worker;
+
+        // From now on every purchase waits in the queue until the worker consumes it
+        market.action_queue = true;
+
+        emit_store_activity(
+            &mut self.store,
+            StoreActivityKind::SetMarketConfig,
+            market.key(),
+        )?;
+
+        Ok(())
+    }
+}
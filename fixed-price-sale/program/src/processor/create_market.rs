@@ -1,6 +1,10 @@
 use crate::{
     error::ErrorCode,
-    state::{GatingConfig, MarketState, SellingResourceState, MINIMUM_BALANCE_FOR_SYSTEM_ACCS},
+    state::{
+        DustPolicy, GatingConfig, Market, MarketState, MetadataOverrides, ProcessorFee,
+        SellingResource, SellingResourceState, Store, StoreActivityKind,
+        MINIMUM_BALANCE_FOR_SYSTEM_ACCS,
+    },
     utils::*,
     CreateMarket,
 };
@@ -9,6 +13,7 @@ use anchor_lang::{
     solana_program::{program::invoke, system_instruction},
 };
 use anchor_spl::token::accessor;
+use mpl_token_metadata::state::Metadata;
 
 impl<'info> CreateMarket<'info> {
     pub fn process(
@@ -22,129 +27,287 @@ impl<'info> CreateMarket<'info> {
         start_date: u64,
         end_date: Option<u64>,
         gating_config: Option<GatingConfig>,
+        metadata_overrides: Option<MetadataOverrides>,
+        pda_mints: bool,
+        processor_fee: Option<ProcessorFee>,
+        sweep_basis_points: Option<u16>,
         remaining_accounts: &[AccountInfo<'info>],
     ) -> Result<()> {
-        let market = &mut self.market;
-        let store = &self.store;
-        let selling_resource_owner = &self.selling_resource_owner;
-        let selling_resource = &mut self.selling_resource;
-        let mint = self.mint.to_account_info();
-        let treasury_holder = self.treasury_holder.to_account_info();
-        let owner = &self.owner;
-
-        if name.len() > NAME_MAX_LEN {
-            return Err(ErrorCode::NameIsTooLong.into());
-        }
+        init_market(
+            &mut self.market,
+            &mut self.store,
+            &self.selling_resource_owner,
+            &mut self.selling_resource,
+            self.mint.to_account_info(),
+            self.treasury_holder.to_account_info(),
+            self.owner.to_account_info(),
+            self.market_name_registry.to_account_info(),
+            self.store_payment_mints.to_account_info(),
+            self.global_config.to_account_info(),
+            name,
+            description,
+            mutable,
+            price,
+            pieces_in_one_wallet,
+            start_date,
+            end_date,
+            gating_config,
+            metadata_overrides,
+            pda_mints,
+            processor_fee,
+            sweep_basis_points,
+            remaining_accounts,
+        )
+    }
+}
+
+/// Validate market params and fill `market` for `selling_resource`, shared by keypair and PDA markets
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn init_market<'info>(
+    market: &mut Account<'info, Market>,
+    store: &mut Account<'info, Store>,
+    selling_resource_owner: &Signer<'info>,
+    selling_resource: &mut Account<'info, SellingResource>,
+    mint: AccountInfo<'info>,
+    treasury_holder: AccountInfo<'info>,
+    owner: AccountInfo<'info>,
+    market_name_registry: AccountInfo<'info>,
+    store_payment_mints: AccountInfo<'info>,
+    global_config: AccountInfo<'info>,
+    name: String,
+    description: String,
+    mutable: bool,
+    price: u64,
+    pieces_in_one_wallet: Option<u64>,
+    start_date: u64,
+    end_date: Option<u64>,
+    gating_config: Option<GatingConfig>,
+    metadata_overrides: Option<MetadataOverrides>,
+    pda_mints: bool,
+    processor_fee: Option<ProcessorFee>,
+    sweep_basis_points: Option<u16>,
+    remaining_accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    if name.len() > NAME_MAX_LEN {
+        return Err(ErrorCode::NameIsTooLong.into());
+    }
+
+    if description.len() > DESCRIPTION_MAX_LEN {
+        return Err(ErrorCode::DescriptionIsTooLong.into());
+    }
 
-        if description.len() > DESCRIPTION_MAX_LEN {
-            return Err(ErrorCode::DescriptionIsTooLong.into());
+    // Pieces in one wallet cannot be greater than Max Supply value
+    if pieces_in_one_wallet.is_some()
+        && selling_resource.max_supply.is_some()
+        && pieces_in_one_wallet.unwrap() > selling_resource.max_supply.unwrap()
+    {
+        return Err(ErrorCode::PiecesInOneWalletIsTooMuch.into());
+    }
+
+    // Only new just created selling resource can be used to create market
+    if selling_resource.state != SellingResourceState::Created {
+        return Err(ErrorCode::SellingResourceAlreadyTaken.into());
+    }
+
+    // start_date cannot be in the past
+    if start_date < Clock::get().unwrap().unix_timestamp as u64 {
+        return Err(ErrorCode::StartDateIsInPast.into());
+    }
+
+    // end_date should not be greater than start_date
+    if end_date.is_some() && start_date > end_date.unwrap() {
+        return Err(ErrorCode::EndDateIsEarlierThanBeginDate.into());
+    }
+
+    if let Some(gating_data) = &gating_config {
+        if let Some(gating_time) = gating_data.gating_time {
+            if gating_time < start_date {
+                return Err(ErrorCode::WrongGatingDate.into());
+            }
+            if let Some(end_date) = end_date {
+                if gating_time > end_date {
+                    return Err(ErrorCode::WrongGatingDate.into());
+                }
+            }
         }
 
-        // Pieces in one wallet cannot be greater than Max Supply value
-        if pieces_in_one_wallet.is_some()
-            && selling_resource.max_supply.is_some()
-            && pieces_in_one_wallet.unwrap() > selling_resource.max_supply.unwrap()
+        let collection_mint = remaining_accounts
+            .first()
+            .ok_or(ErrorCode::CollectionMintMissing)?;
+
+        if collection_mint.key != &gating_data.collection
+            || collection_mint.owner != &spl_token::id()
         {
-            return Err(ErrorCode::PiecesInOneWalletIsTooMuch.into());
+            return Err(ErrorCode::WrongCollectionMintKey.into());
         }
+    }
 
-        // Only new just created selling resource can be used to create market
-        if selling_resource.state != SellingResourceState::Created {
-            return Err(ErrorCode::SellingResourceAlreadyTaken.into());
+    if let Some(overrides) = &metadata_overrides {
+        if let Some(name_suffix) = &overrides.name_suffix {
+            if name_suffix.len() > NAME_SUFFIX_MAX_LEN {
+                return Err(ErrorCode::NameSuffixIsTooLong.into());
+            }
         }
 
-        // start_date cannot be in the past
-        if start_date < Clock::get().unwrap().unix_timestamp as u64 {
-            return Err(ErrorCode::StartDateIsInPast.into());
+        if let Some(uri) = &overrides.uri {
+            if uri.len() > URI_MAX_LEN {
+                return Err(ErrorCode::UriIsTooLong.into());
+            }
         }
 
-        // end_date should not be greater than start_date
-        if end_date.is_some() && start_date > end_date.unwrap() {
-            return Err(ErrorCode::EndDateIsEarlierThanBeginDate.into());
-        }
+        // Master edition metadata follows the collection mint, if any
+        let master_metadata = remaining_accounts
+            .get(gating_config.is_some() as usize)
+            .ok_or(ErrorCode::MasterMetadataMissing)?;
 
-        if let Some(gating_data) = &gating_config {
-            if let Some(gating_time) = gating_data.gating_time {
-                if gating_time < start_date {
-                    return Err(ErrorCode::WrongGatingDate.into());
-                }
-                if let Some(end_date) = end_date {
-                    if gating_time > end_date {
-                        return Err(ErrorCode::WrongGatingDate.into());
-                    }
-                }
-            }
+        assert_derivation(
+            &mpl_token_metadata::id(),
+            master_metadata,
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                selling_resource.resource.as_ref(),
+            ],
+        )?;
+
+        let master_metadata = Metadata::from_account_info(master_metadata)?;
 
-            if remaining_accounts.len() != 1 {
-                return Err(ErrorCode::CollectionMintMissing.into());
+        // Edition names are the master edition name followed by the suffix
+        if let Some(name_suffix) = &overrides.name_suffix {
+            let name_len = master_metadata.data.name.trim_matches(char::from(0)).len();
+            if name_len + name_suffix.len() > NAME_SUFFIX_MAX_LEN {
+                return Err(ErrorCode::NameSuffixIsTooLong.into());
             }
+        }
 
-            let collection_mint = &remaining_accounts[0];
+        // Overrides are applied to every edition by the vault owner, so it should be able to sign them
+        if master_metadata.update_authority != selling_resource.vault_owner {
+            return Err(ErrorCode::VaultOwnerIsNotUpdateAuthority.into());
+        }
+    }
 
-            if collection_mint.key != &gating_data.collection
-                || collection_mint.owner != &spl_token::id()
-            {
-                return Err(ErrorCode::WrongCollectionMintKey.into());
-            }
+    if let Some(processor_fee) = &processor_fee {
+        if processor_fee.basis_points > 10000 {
+            return Err(ErrorCode::InvalidProcessorFee.into());
         }
+    }
 
-        let is_native = mint.key() == System::id();
+    if let Some(sweep_basis_points) = sweep_basis_points {
+        if sweep_basis_points > 10000 {
+            return Err(ErrorCode::InvalidSweepBasisPoints.into());
+        }
+    }
 
-        if !is_native {
-            if mint.owner != &anchor_spl::token::ID
-                || treasury_holder.owner != &anchor_spl::token::ID
-            {
-                return Err(ProgramError::IllegalOwner.into());
+    if let Some(global_config) = load_global_config(&global_config)? {
+        if let Some(max_markets_per_store) = global_config.max_markets_per_store {
+            if store.total_markets >= max_markets_per_store {
+                return Err(ErrorCode::TooManyMarkets.into());
             }
+        }
 
-            if accessor::mint(&treasury_holder)? != *mint.key {
-                return Err(ProgramError::InvalidAccountData.into());
+        if let Some(processor_fee) = &processor_fee {
+            if processor_fee.basis_points > global_config.max_processor_fee_basis_points {
+                return Err(ErrorCode::InvalidProcessorFee.into());
             }
+        }
+    }
 
-            if accessor::authority(&treasury_holder)? != owner.key() {
-                return Err(ProgramError::InvalidAccountData.into());
-            }
-        } else {
-            // for native SOL we use PDA as a treasury holder
-            // because of security reasons(only program can spend this SOL)
-            if treasury_holder.key != owner.key {
-                return Err(ProgramError::InvalidAccountData.into());
-            }
+    assert_payment_mint_allowed(
+        &store_payment_mints,
+        store,
+        &selling_resource_owner.key(),
+        mint.key,
+    )?;
 
-            // we need fund treasury holder account such as it will hold some metadata with SOL balance
-            invoke(
-                &system_instruction::transfer(
-                    &selling_resource_owner.key(),
-                    &treasury_holder.key(),
-                    MINIMUM_BALANCE_FOR_SYSTEM_ACCS,
-                ),
-                &[
-                    selling_resource_owner.to_account_info(),
-                    treasury_holder.to_account_info(),
-                ],
-            )?;
+    let is_native = mint.key() == System::id();
+
+    if !is_native {
+        if mint.owner != &anchor_spl::token::ID
+            || treasury_holder.owner != &anchor_spl::token::ID
+        {
+            return Err(ProgramError::IllegalOwner.into());
+        }
+
+        if accessor::mint(&treasury_holder)? != *mint.key {
+            return Err(ProgramError::InvalidAccountData.into());
+        }
+
+        if accessor::authority(&treasury_holder)? != owner.key() {
+            return Err(ProgramError::InvalidAccountData.into());
+        }
+    } else {
+        // for native SOL we use PDA as a treasury holder
+        // because of security reasons(only program can spend this SOL)
+        if treasury_holder.key != owner.key {
+            return Err(ProgramError::InvalidAccountData.into());
         }
 
-        // Check selling resource ownership
-        assert_keys_equal(selling_resource.owner, selling_resource_owner.key())?;
-
-        market.store = store.key();
-        market.selling_resource = selling_resource.key();
-        market.treasury_mint = mint.key();
-        market.treasury_holder = treasury_holder.key();
-        market.treasury_owner = owner.key();
-        market.owner = selling_resource_owner.key();
-        market.name = puffed_out_string(name, NAME_MAX_LEN);
-        market.description = puffed_out_string(description, DESCRIPTION_MAX_LEN);
-        market.mutable = mutable;
-        market.price = price;
-        market.pieces_in_one_wallet = pieces_in_one_wallet;
-        market.start_date = start_date;
-        market.end_date = end_date;
-        market.state = MarketState::Created;
-        market.gatekeeper = gating_config;
-        selling_resource.state = SellingResourceState::InUse;
-
-        Ok(())
+        // we need fund treasury holder account such as it will hold some metadata with SOL balance
+        invoke(
+            &system_instruction::transfer(
+                &selling_resource_owner.key(),
+                &treasury_holder.key(),
+                MINIMUM_BALANCE_FOR_SYSTEM_ACCS,
+            ),
+            &[
+                selling_resource_owner.to_account_info(),
+                treasury_holder.to_account_info(),
+            ],
+        )?;
     }
+
+    // Check selling resource ownership
+    assert_keys_equal(selling_resource.owner, selling_resource_owner.key())?;
+
+    create_market_name_registry(
+        &market_name_registry,
+        &selling_resource_owner.to_account_info(),
+        &store.key(),
+        &market.key(),
+        &name,
+    )?;
+
+    market.store = store.key();
+    market.selling_resource = selling_resource.key();
+    market.treasury_mint = mint.key();
+    market.treasury_holder = treasury_holder.key();
+    market.treasury_owner = owner.key();
+    market.owner = selling_resource_owner.key();
+    market.name = puffed_out_string(name, NAME_MAX_LEN);
+    market.description = puffed_out_string(description, DESCRIPTION_MAX_LEN);
+    market.mutable = mutable;
+    market.price = price;
+    market.pieces_in_one_wallet = pieces_in_one_wallet;
+    market.start_date = start_date;
+    market.end_date = end_date;
+    market.state = MarketState::Created;
+    market.gatekeeper = gating_config;
+    market.metadata_overrides = metadata_overrides;
+    market.pda_mints = pda_mints;
+    market.stats_synced = true;
+    market.voucher_collection = None;
+    market.processor_fee = processor_fee;
+    market.sweep_basis_points = sweep_basis_points;
+    market.last_sweep_epoch = None;
+    market.funds_swept = 0;
+    market.gatekeeper_program = None;
+    market.price_oracle = None;
+    market.oracle_override = false;
+    market.dust_policy = DustPolicy::Leave;
+    market.quantity_discounts = Vec::new();
+    market.phases = Vec::new();
+    market.managers = 0;
+    market.physical_redemption = None;
+    market.funds_escrowed = 0;
+    market.action_queue = false;
+    selling_resource.state = SellingResourceState::InUse;
+
+    store.total_markets = store
+        .total_markets
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    emit_store_activity(store, StoreActivityKind::CreateMarket, market.key())?;
+
+    Ok(())
 }
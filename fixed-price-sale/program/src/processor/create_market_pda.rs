@@ -0,0 +1,61 @@
+use crate::{
+    error::ErrorCode,
+    processor::create_market::init_market,
+    state::{GatingConfig, MetadataOverrides, ProcessorFee},
+    CreateMarketPda,
+};
+use anchor_lang::prelude::*;
+
+impl<'info> CreateMarketPda<'info> {
+    pub fn process(
+        &mut self,
+        _treasury_owner_bump: u8,
+        name: String,
+        description: String,
+        mutable: bool,
+        price: u64,
+        pieces_in_one_wallet: Option<u64>,
+        start_date: u64,
+        end_date: Option<u64>,
+        gating_config: Option<GatingConfig>,
+        metadata_overrides: Option<MetadataOverrides>,
+        pda_mints: bool,
+        processor_fee: Option<ProcessorFee>,
+        sweep_basis_points: Option<u16>,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        init_market(
+            &mut self.market,
+            &mut self.store,
+            &self.selling_resource_owner,
+            &mut self.selling_resource,
+            self.mint.to_account_info(),
+            self.treasury_holder.to_account_info(),
+            self.owner.to_account_info(),
+            self.market_name_registry.to_account_info(),
+            self.store_payment_mints.to_account_info(),
+            self.global_config.to_account_info(),
+            name,
+            description,
+            mutable,
+            price,
+            pieces_in_one_wallet,
+            start_date,
+            end_date,
+            gating_config,
+            metadata_overrides,
+            pda_mints,
+            processor_fee,
+            sweep_basis_points,
+            remaining_accounts,
+        )?;
+
+        self.selling_resource.market_index = self
+            .selling_resource
+            .market_index
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,72 @@
+use crate::{
+    error::ErrorCode,
+    state::{GatingConfig, MetadataOverrides, ProcessorFee},
+    CreateMarketV2,
+};
+use anchor_lang::{prelude::*, system_program::System};
+use anchor_spl::associated_token::{self, get_associated_token_address};
+
+impl<'info> CreateMarketV2<'info> {
+    pub fn process(
+        &mut self,
+        treasury_owner_bump: u8,
+        name: String,
+        description: String,
+        mutable: bool,
+        price: u64,
+        pieces_in_one_wallet: Option<u64>,
+        start_date: u64,
+        end_date: Option<u64>,
+        gating_config: Option<GatingConfig>,
+        metadata_overrides: Option<MetadataOverrides>,
+        pda_mints: bool,
+        processor_fee: Option<ProcessorFee>,
+        sweep_basis_points: Option<u16>,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let create_market = &self.create_market;
+        let mint = create_market.mint.to_account_info();
+        let treasury_holder = create_market.treasury_holder.to_account_info();
+        let owner = create_market.owner.to_account_info();
+
+        // for native SOL treasury owner PDA itself holds the funds, checked by `create_market`
+        if mint.key() != System::id() {
+            if treasury_holder.key() != get_associated_token_address(&owner.key(), &mint.key()) {
+                return Err(ErrorCode::TreasuryMismatch.into());
+            }
+
+            // Already created treasury holder is accepted, so the instruction cannot be front-run
+            if treasury_holder.data_is_empty() {
+                associated_token::create(CpiContext::new(
+                    self.associated_token_program.to_account_info(),
+                    associated_token::Create {
+                        payer: create_market.selling_resource_owner.to_account_info(),
+                        associated_token: treasury_holder,
+                        authority: owner,
+                        mint,
+                        system_program: create_market.system_program.to_account_info(),
+                        token_program: self.token_program.to_account_info(),
+                        rent: self.rent.to_account_info(),
+                    },
+                ))?;
+            }
+        }
+
+        self.create_market.process(
+            treasury_owner_bump,
+            name,
+            description,
+            mutable,
+            price,
+            pieces_in_one_wallet,
+            start_date,
+            end_date,
+            gating_config,
+            metadata_overrides,
+            pda_mints,
+            processor_fee,
+            sweep_basis_points,
+            remaining_accounts,
+        )
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{error::ErrorCode, utils::*, CreateStore};
+use crate::{error::ErrorCode, state::StoreActivityKind, utils::*, CreateStore};
 use anchor_lang::prelude::*;
 
 impl<'info> CreateStore<'info> {
@@ -18,6 +18,9 @@ impl<'info> CreateStore<'info> {
         store.name = puffed_out_string(name, NAME_MAX_LEN);
         store.description = puffed_out_string(description, DESCRIPTION_MAX_LEN);
 
+        let store_key = store.key();
+        emit_store_activity(store, StoreActivityKind::CreateStore, store_key)?;
+
         Ok(())
     }
 }
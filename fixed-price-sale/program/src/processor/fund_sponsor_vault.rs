@@ -0,0 +1,43 @@
+use crate::{
+    error::ErrorCode, state::StoreActivityKind, utils::emit_store_activity, FundSponsorVault,
+};
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program::invoke, system_instruction},
+};
+
+impl<'info> FundSponsorVault<'info> {
+    pub fn process(&mut self, amount: u64, per_wallet_cap: u64) -> Result<()> {
+        let market = &self.market;
+        let owner = &self.owner;
+        let sponsor_vault = &mut self.sponsor_vault;
+        let system_program = &self.system_program;
+
+        sponsor_vault.market = market.key();
+        sponsor_vault.per_wallet_cap = per_wallet_cap;
+
+        if amount > 0 {
+            invoke(
+                &system_instruction::transfer(&owner.key(), &sponsor_vault.key(), amount),
+                &[
+                    owner.to_account_info(),
+                    sponsor_vault.to_account_info(),
+                    system_program.to_account_info(),
+                ],
+            )?;
+
+            sponsor_vault.total_budget = sponsor_vault
+                .total_budget
+                .checked_add(amount)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+
+        emit_store_activity(
+            &mut self.store,
+            StoreActivityKind::FundSponsorVault,
+            market.key(),
+        )?;
+
+        Ok(())
+    }
+}
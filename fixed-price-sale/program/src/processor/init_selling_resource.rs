@@ -1,5 +1,10 @@
-use crate::{error::ErrorCode, state::SellingResourceState, utils::*, InitSellingResource};
-use anchor_lang::prelude::*;
+use crate::{
+    error::ErrorCode,
+    state::{SellingResourceState, SellingResourceType, StoreActivityKind},
+    utils::*,
+    InitSellingResource,
+};
+use anchor_lang::{prelude::*, solana_program::program_pack::Pack};
 use anchor_spl::token;
 
 impl<'info> InitSellingResource<'info> {
@@ -8,7 +13,10 @@ impl<'info> InitSellingResource<'info> {
         _master_edition_bump: u8,
         _vault_owner_bump: u8,
         max_supply: Option<u64>,
+        resource_type: SellingResourceType,
     ) -> Result<()> {
+        self.assert_preflight()?;
+
         let store = &self.store;
         let admin = &self.admin;
         let selling_resource = &mut self.selling_resource;
@@ -63,8 +71,11 @@ impl<'info> InitSellingResource<'info> {
 
         let mut actual_max_supply = max_supply;
 
-        // Ensure, that provided `max_supply` is under `MasterEditionV2::max_supply` bounds
-        if let Some(me_max_supply) = master_edition.max_supply {
+        if resource_type == SellingResourceType::MasterEditionAsIs {
+            // Master edition sold as is goes to the single buyer, no editions are printed
+            actual_max_supply = Some(1);
+        } else if let Some(me_max_supply) = master_edition.max_supply {
+            // Ensure, that provided `max_supply` is under `MasterEditionV2::max_supply` bounds
             let x = if let Some(max_supply) = max_supply {
                 let available_supply = me_max_supply - master_edition.supply;
                 if max_supply > available_supply {
@@ -97,6 +108,33 @@ impl<'info> InitSellingResource<'info> {
         selling_resource.supply = 0;
         selling_resource.max_supply = actual_max_supply;
         selling_resource.state = SellingResourceState::Created;
+        selling_resource.vault_owner_version = store.authority_version;
+        selling_resource.resource_type = resource_type;
+        selling_resource.market_index = 0;
+
+        emit_store_activity(
+            &mut self.store,
+            StoreActivityKind::InitSellingResource,
+            selling_resource.key(),
+        )?;
+
+        Ok(())
+    }
+
+    /// Checks of client provided accounts, failing fast with a dedicated error instead of an opaque one of a downstream CPI
+    fn assert_preflight(&self) -> Result<()> {
+        assert_metadata_accounts_len(&self.metadata, &self.master_edition)?;
+
+        if self.resource_mint.decimals != 0 {
+            return Err(ErrorCode::MintDecimalsInvalid.into());
+        }
+
+        if self.resource_token.owner != &spl_token::id() {
+            return Err(ErrorCode::TokenAccountOwnerInvalid.into());
+        }
+        if self.resource_token.data_len() != spl_token::state::Account::LEN {
+            return Err(ErrorCode::TokenAccountSizeInvalid.into());
+        }
 
         Ok(())
     }
@@ -0,0 +1,149 @@
+use crate::{
+    error::ErrorCode,
+    state::{
+        GatingConfig, Market, MarketState, PayoutTicket, SellingResource, SellingResourceState,
+        Store, TradeHistory,
+    },
+    MigrateAccount,
+};
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program::invoke, program_memory::sol_memset, system_instruction},
+    Discriminator,
+};
+
+/// `Store` as allocated before its stats and authority rotation were added
+#[derive(AnchorSerialize, AnchorDeserialize)]
+struct LegacyStore {
+    admin: Pubkey,
+    name: String,
+    description: String,
+}
+
+/// `Market` as allocated before the fields following `gatekeeper` were added
+#[derive(AnchorSerialize, AnchorDeserialize)]
+struct LegacyMarket {
+    store: Pubkey,
+    selling_resource: Pubkey,
+    treasury_mint: Pubkey,
+    treasury_holder: Pubkey,
+    treasury_owner: Pubkey,
+    owner: Pubkey,
+    name: String,
+    description: String,
+    mutable: bool,
+    price: u64,
+    pieces_in_one_wallet: Option<u64>,
+    start_date: u64,
+    end_date: Option<u64>,
+    state: MarketState,
+    funds_collected: u64,
+    gatekeeper: Option<GatingConfig>,
+}
+
+/// `TradeHistory` as allocated before the fields following `already_bought` were added
+#[derive(AnchorSerialize, AnchorDeserialize)]
+struct LegacyTradeHistory {
+    market: Pubkey,
+    wallet: Pubkey,
+    already_bought: u64,
+}
+
+/// `SellingResource` as allocated before the fields following `state` were added
+#[derive(AnchorSerialize, AnchorDeserialize)]
+struct LegacySellingResource {
+    store: Pubkey,
+    owner: Pubkey,
+    resource: Pubkey,
+    vault: Pubkey,
+    vault_owner: Pubkey,
+    supply: u64,
+    max_supply: Option<u64>,
+    state: SellingResourceState,
+}
+
+/// `PayoutTicket` as allocated before partial claims were added
+#[derive(AnchorSerialize, AnchorDeserialize)]
+struct LegacyPayoutTicket {
+    used: bool,
+}
+
+impl<'info> MigrateAccount<'info> {
+    pub fn process(&mut self) -> Result<()> {
+        let account = self.account.to_account_info();
+        let payer = &self.payer;
+        let system_program = &self.system_program;
+
+        // Legacy fields are decoded and written back, so bytes left past them can't leak into new fields
+        let (len, legacy_data) = {
+            let data = account.try_borrow_data()?;
+            let discriminator = data.get(..8).ok_or(ErrorCode::AccountNotMigratable)?;
+
+            let legacy_data = &mut &data[8..];
+            let (len, legacy_data) = if discriminator == Store::discriminator() {
+                (
+                    Store::LEN,
+                    LegacyStore::deserialize(legacy_data)?.try_to_vec()?,
+                )
+            } else if discriminator == Market::discriminator() {
+                (
+                    Market::LEN,
+                    LegacyMarket::deserialize(legacy_data)?.try_to_vec()?,
+                )
+            } else if discriminator == TradeHistory::discriminator() {
+                (
+                    TradeHistory::LEN,
+                    LegacyTradeHistory::deserialize(legacy_data)?.try_to_vec()?,
+                )
+            } else if discriminator == SellingResource::discriminator() {
+                (
+                    SellingResource::LEN,
+                    LegacySellingResource::deserialize(legacy_data)?.try_to_vec()?,
+                )
+            } else if discriminator == PayoutTicket::discriminator() {
+                // Used legacy tickets were paid the whole share, so nothing is left to claim on them
+                let used = LegacyPayoutTicket::deserialize(legacy_data)?.used;
+                let claimed = if used {
+                    PayoutTicket::LEGACY_CLAIMED
+                } else {
+                    0
+                };
+                (
+                    PayoutTicket::LEN,
+                    PayoutTicket { used, claimed }.try_to_vec()?,
+                )
+            } else {
+                return Err(ErrorCode::AccountNotMigratable.into());
+            };
+
+            if data.len() >= len {
+                return Err(ErrorCode::AccountAlreadyMigrated.into());
+            }
+
+            (len, legacy_data)
+        };
+
+        let rent = Rent::get()?
+            .minimum_balance(len)
+            .saturating_sub(account.lamports());
+        if rent > 0 {
+            invoke(
+                &system_instruction::transfer(&payer.key(), &account.key(), rent),
+                &[
+                    payer.to_account_info(),
+                    account.clone(),
+                    system_program.to_account_info(),
+                ],
+            )?;
+        }
+
+        account.realloc(len, false)?;
+
+        // New fields start zeroed, which reads as their default
+        let mut data = account.try_borrow_mut_data()?;
+        sol_memset(&mut data[8..], 0, len - 8);
+        data[8..8 + legacy_data.len()].copy_from_slice(&legacy_data);
+
+        Ok(())
+    }
+}
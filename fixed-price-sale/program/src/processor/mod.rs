@@ -1,11 +1,39 @@
+pub mod accept_store_authority;
+pub mod add_market_manager;
 pub mod buy;
+pub mod buy_sponsored;
 pub mod change_market;
 pub mod claim_resource;
+pub mod clone_market;
 pub mod close_market;
+pub mod confirm_delivery;
+pub mod consume_action;
+pub mod create_action_queue;
 pub mod create_market;
+pub mod create_market_pda;
+pub mod create_market_v2;
 pub mod create_store;
+pub mod fund_sponsor_vault;
 pub mod init_selling_resource;
+pub mod migrate_account;
+pub mod partial_sweep;
+pub mod redeem_voucher_buy;
+pub mod refund_undelivered;
+pub mod remove_market_manager;
 pub mod resume_market;
+pub mod rotate_store_authority;
 pub mod save_primary_metadata_creators;
+pub mod set_dust_policy;
+pub mod set_gatekeeper_program;
+pub mod set_global_config;
+pub mod set_phases;
+pub mod set_physical_redemption;
+pub mod set_price_oracle;
+pub mod set_quantity_discounts;
+pub mod set_store_payment_mints;
+pub mod set_voucher_collection;
 pub mod suspend_market;
+pub mod sync_store_stats;
+pub mod view_market;
+pub mod view_selling_resource;
 pub mod withdraw;
@@ -0,0 +1,181 @@
+use crate::{
+    error::ErrorCode,
+    state::{
+        Creator, MarketState, PrimaryMetadataCreators, StoreActivityKind,
+        MINIMUM_BALANCE_FOR_SYSTEM_ACCS,
+    },
+    utils::*,
+    PartialSweep,
+};
+use anchor_lang::{prelude::*, solana_program::borsh::try_from_slice_unchecked};
+use anchor_spl::token::{self, accessor};
+use std::convert::TryFrom;
+
+impl<'info> PartialSweep<'info> {
+    pub fn process(
+        &mut self,
+        treasury_owner_bump: u8,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let market = &mut self.market;
+        let selling_resource = &self.selling_resource;
+        let treasury_holder = &self.treasury_holder;
+        let treasury_owner = &self.treasury_owner;
+        let destination = &self.destination;
+        let token_program = &self.token_program;
+        let clock = &self.clock;
+        let metadata = &self.metadata.to_account_info();
+
+        let sweep_basis_points = market
+            .sweep_basis_points
+            .ok_or(ErrorCode::PartialSweepDisabled)?;
+
+        // Check, that `Market` is with unlimited duration
+        if market.end_date.is_some() {
+            return Err(ErrorCode::MarketDurationIsNotUnlimited.into());
+        }
+
+        // Check, that `Market` is active
+        if market.state != MarketState::Active {
+            return Err(ErrorCode::MarketInInvalidState.into());
+        }
+
+        // Check, that treasury wasn't swept in current epoch
+        if market.last_sweep_epoch == Some(clock.epoch) {
+            return Err(ErrorCode::PartialSweepRateLimited.into());
+        }
+
+        // Check, that provided metadata is correct
+        assert_derivation(
+            &mpl_token_metadata::id(),
+            metadata,
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                selling_resource.resource.as_ref(),
+            ],
+        )?;
+
+        // Obtain right creators according to sale type
+        let metadata = mpl_token_metadata::state::Metadata::from_account_info(metadata)?;
+        let actual_creators = if !metadata.primary_sale_happened {
+            if remaining_accounts.is_empty() {
+                return Err(ErrorCode::PrimaryMetadataCreatorsNotProvided.into());
+            }
+
+            let primary_metadata_creators = &remaining_accounts[0];
+            assert_derivation(
+                &crate::id(),
+                primary_metadata_creators,
+                &[
+                    PRIMARY_METADATA_CREATORS_PREFIX.as_bytes(),
+                    self.metadata.key().as_ref(),
+                ],
+            )?;
+
+            let primary_metadata_creators_data =
+                primary_metadata_creators.data.borrow()[8..].to_vec();
+            let primary_metadata_creators = try_from_slice_unchecked::<PrimaryMetadataCreators>(
+                &primary_metadata_creators_data,
+            )?;
+            primary_metadata_creators.creators
+        } else if let Some(creators) = &metadata.data.creators {
+            creators
+                .iter()
+                .map(|item| Creator::from(item.clone()))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        // Market owner can sweep only funds it would receive at withdraw
+        let owner_creator = actual_creators.iter().find(|c| c.address == market.owner);
+        let owner_share = if metadata.primary_sale_happened {
+            let owner_as_market_owner_share = calculate_secondary_shares_for_market_owner(
+                market.funds_collected,
+                metadata.data.seller_fee_basis_points as u64,
+            )?;
+
+            if let Some(owner_creator) = owner_creator {
+                calculate_secondary_shares_for_creator(
+                    market.funds_collected,
+                    metadata.data.seller_fee_basis_points as u64,
+                    owner_creator.share as u64,
+                )?
+                .checked_add(owner_as_market_owner_share)
+                .ok_or(ErrorCode::MathOverflow)?
+            } else {
+                owner_as_market_owner_share
+            }
+        } else if let Some(owner_creator) = owner_creator {
+            calculate_primary_shares_for_creator(
+                market.funds_collected,
+                owner_creator.share as u64,
+            )?
+        } else {
+            return Err(ErrorCode::MarketOwnerDoesntHaveShares.into());
+        };
+
+        let available = owner_share
+            .checked_sub(market.funds_swept)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        let is_native = market.treasury_mint == System::id();
+
+        let treasury_balance = if is_native {
+            treasury_holder
+                .lamports()
+                .saturating_sub(MINIMUM_BALANCE_FOR_SYSTEM_ACCS)
+        } else {
+            accessor::amount(&treasury_holder.to_account_info())?
+        };
+
+        let cap = (treasury_balance as u128)
+            .checked_mul(sweep_basis_points as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(10000)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let amount = u64::try_from(cap)
+            .map_err(|_| ErrorCode::MathOverflow)?
+            .min(available);
+
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            HOLDER_PREFIX.as_bytes(),
+            market.treasury_mint.as_ref(),
+            market.selling_resource.as_ref(),
+            &[treasury_owner_bump],
+        ]];
+
+        if is_native {
+            sys_transfer(
+                &treasury_holder.to_account_info(),
+                &destination.to_account_info(),
+                amount,
+                signer_seeds[0],
+            )?;
+        } else {
+            let cpi_program = token_program.to_account_info();
+            let cpi_accounts = token::Transfer {
+                from: treasury_holder.to_account_info(),
+                to: destination.to_account_info(),
+                authority: treasury_owner.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+            token::transfer(cpi_ctx, amount)?;
+        }
+
+        market.funds_swept = market
+            .funds_swept
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        market.last_sweep_epoch = Some(clock.epoch);
+
+        emit_store_activity(
+            &mut self.store,
+            StoreActivityKind::PartialSweep,
+            market.key(),
+        )?;
+
+        Ok(())
+    }
+}
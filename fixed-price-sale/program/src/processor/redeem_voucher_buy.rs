@@ -0,0 +1,115 @@
+use crate::{error::ErrorCode, state::StoreActivityKind, utils::emit_store_activity, Buy};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{program::invoke, program_pack::Pack};
+use mpl_token_metadata::state::Metadata;
+
+impl<'info> Buy<'info> {
+    pub fn process_redeem_voucher(
+        &mut self,
+        vault_owner_bump: u8,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let voucher_collection = self
+            .market
+            .voucher_collection
+            .ok_or(ErrorCode::VouchersNotAccepted)?;
+
+        self.assert_preflight()?;
+        self.assert_can_buy()?;
+
+        let (voucher_token_acc, voucher_mint, voucher_metadata, gating_accounts) =
+            match remaining_accounts {
+                [voucher_token_acc, voucher_mint, voucher_metadata, gating_accounts @ ..] => (
+                    voucher_token_acc,
+                    voucher_mint,
+                    voucher_metadata,
+                    gating_accounts,
+                ),
+                _ => return Err(ErrorCode::InvalidVoucher.into()),
+            };
+
+        Self::verify_voucher(
+            voucher_token_acc,
+            voucher_metadata,
+            &self.user_wallet.key(),
+            &voucher_collection,
+        )?;
+
+        // Voucher replaces the payment only, the gate of the market or its active phase still applies
+        let now = self.clock.unix_timestamp as u64;
+        let gate = match self.market.active_phase(now) {
+            Some(phase) => &phase.gate,
+            None => &self.market.gatekeeper,
+        };
+        Self::verify_gating_token(
+            gate,
+            &self.user_wallet.to_account_info(),
+            gating_accounts,
+            now,
+        )?;
+
+        // Voucher is burned in lieu of payment, so `Market::funds_collected` stays the same
+        invoke(
+            &spl_token::instruction::burn(
+                &spl_token::id(),
+                &voucher_token_acc.key(),
+                &voucher_mint.key(),
+                &self.user_wallet.key(),
+                &[&self.user_wallet.key()],
+                1,
+            )?,
+            &[
+                voucher_token_acc.clone(),
+                voucher_mint.clone(),
+                self.user_wallet.to_account_info(),
+            ],
+        )?;
+
+        self.mint_edition(vault_owner_bump, 0)?;
+        let market_key = self.market.key();
+        emit_store_activity(&mut self.store, StoreActivityKind::Buy, market_key)?;
+
+        self.trade_history.already_redeemed = self
+            .trade_history
+            .already_redeemed
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        Ok(())
+    }
+
+    fn verify_voucher(
+        voucher_token_acc: &AccountInfo,
+        voucher_metadata: &AccountInfo,
+        user_wallet: &Pubkey,
+        voucher_collection: &Pubkey,
+    ) -> Result<()> {
+        if voucher_token_acc.owner != &spl_token::id() {
+            return Err(ErrorCode::InvalidVoucher.into());
+        }
+
+        let voucher_token_acc_data = spl_token::state::Account::unpack_from_slice(
+            voucher_token_acc.try_borrow_data()?.as_ref(),
+        )?;
+
+        if voucher_token_acc_data.owner != *user_wallet {
+            return Err(ErrorCode::InvalidVoucher.into());
+        }
+
+        let (metadata_key, _) =
+            mpl_token_metadata::pda::find_metadata_account(&voucher_token_acc_data.mint);
+
+        if voucher_metadata.key() != metadata_key {
+            return Err(ErrorCode::InvalidVoucher.into());
+        }
+
+        let metadata = Metadata::from_account_info(voucher_metadata)?;
+
+        match metadata.collection {
+            Some(collection) if collection.verified && collection.key == *voucher_collection => {
+                Ok(())
+            }
+            _ => Err(ErrorCode::InvalidVoucher.into()),
+        }
+    }
+}
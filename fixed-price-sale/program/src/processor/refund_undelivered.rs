@@ -0,0 +1,113 @@
+use crate::{
+    error::ErrorCode,
+    state::{DeliveryState, StoreActivityKind},
+    utils::*,
+    RefundUndelivered,
+};
+use anchor_lang::{prelude::*, solana_program::program_pack::Pack};
+use anchor_spl::token;
+
+impl<'info> RefundUndelivered<'info> {
+    pub fn process(&mut self, treasury_owner_bump: u8) -> Result<()> {
+        let market = &mut self.market;
+        let provenance_record = &mut self.provenance_record;
+        let treasury_holder = &self.treasury_holder;
+        let treasury_owner = &self.treasury_owner;
+        let destination = &self.destination;
+        let edition_mint = &self.edition_mint;
+        let edition_token_account = &self.edition_token_account;
+        let buyer = &self.buyer;
+        let clock = &self.clock;
+        let token_program = &self.token_program;
+
+        // Check, that signer made the purchase
+        if buyer.key() != provenance_record.buyer {
+            return Err(ErrorCode::InvalidDeliverySigner.into());
+        }
+
+        // Check, that buyer still holds the edition, it is burned so it can't be kept with the refund
+        if edition_mint.key() != provenance_record.edition_mint
+            || edition_token_account.mint != edition_mint.key()
+            || edition_token_account.owner != buyer.key()
+            || edition_token_account.amount != 1
+        {
+            return Err(ErrorCode::EditionNotHeld.into());
+        }
+
+        let delivery = provenance_record
+            .delivery
+            .as_mut()
+            .filter(|delivery| delivery.state == DeliveryState::Pending)
+            .ok_or(ErrorCode::DeliveryNotPending)?;
+
+        // Check, that delivery was not confirmed in time
+        if clock.unix_timestamp as u64 <= delivery.deadline {
+            return Err(ErrorCode::DeliveryDeadlineNotPassed.into());
+        }
+
+        let cpi_program = token_program.to_account_info();
+        let cpi_accounts = token::Burn {
+            mint: edition_mint.to_account_info(),
+            to: edition_token_account.to_account_info(),
+            authority: buyer.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        token::burn(cpi_ctx, 1)?;
+
+        let amount = delivery.escrowed;
+        market.funds_escrowed = market
+            .funds_escrowed
+            .checked_sub(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        delivery.state = DeliveryState::Refunded;
+
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            HOLDER_PREFIX.as_bytes(),
+            market.treasury_mint.as_ref(),
+            market.selling_resource.as_ref(),
+            &[treasury_owner_bump],
+        ]];
+
+        if market.treasury_mint == System::id() {
+            if destination.key() != buyer.key() {
+                return Err(ErrorCode::InvalidRefundDestination.into());
+            }
+
+            sys_transfer(
+                &treasury_holder.to_account_info(),
+                &destination.to_account_info(),
+                amount,
+                signer_seeds[0],
+            )?;
+        } else {
+            // Check, that destination holds the treasury mint for the buyer
+            if destination.owner != &spl_token::id() {
+                return Err(ErrorCode::InvalidRefundDestination.into());
+            }
+            let destination_account =
+                spl_token::state::Account::unpack(&destination.try_borrow_data()?)?;
+            if destination_account.mint != market.treasury_mint
+                || destination_account.owner != buyer.key()
+            {
+                return Err(ErrorCode::InvalidRefundDestination.into());
+            }
+
+            let cpi_program = token_program.to_account_info();
+            let cpi_accounts = token::Transfer {
+                from: treasury_holder.to_account_info(),
+                to: destination.to_account_info(),
+                authority: treasury_owner.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+            token::transfer(cpi_ctx, amount)?;
+        }
+
+        emit_store_activity(
+            &mut self.store,
+            StoreActivityKind::RefundDelivery,
+            market.key(),
+        )?;
+
+        Ok(())
+    }
+}
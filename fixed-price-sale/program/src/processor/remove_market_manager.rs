@@ -0,0 +1,24 @@
+use crate::{
+    error::ErrorCode, state::StoreActivityKind, utils::emit_store_activity, RemoveMarketManager,
+};
+use anchor_lang::prelude::*;
+
+impl<'info> RemoveMarketManager<'info> {
+    pub fn process(&mut self) -> Result<()> {
+        let market = &mut self.market;
+
+        // `MarketManager` account itself is closed to the owner by anchor
+        market.managers = market
+            .managers
+            .checked_sub(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit_store_activity(
+            &mut self.store,
+            StoreActivityKind::RemoveMarketManager,
+            market.key(),
+        )?;
+
+        Ok(())
+    }
+}
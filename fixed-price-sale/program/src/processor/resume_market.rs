@@ -1,8 +1,15 @@
-use crate::{error::ErrorCode, state::MarketState, ResumeMarket};
+use crate::{
+    error::ErrorCode,
+    state::{MarketState, StoreActivityKind},
+    utils::{assert_market_authority, emit_store_activity},
+    ResumeMarket,
+};
 use anchor_lang::prelude::*;
 
 impl<'info> ResumeMarket<'info> {
     pub fn process(&mut self) -> Result<()> {
+        assert_market_authority(&self.market, self.owner.key, &self.market_manager)?;
+
         let market = &mut self.market;
         let clock = &self.clock;
 
@@ -24,6 +31,12 @@ impl<'info> ResumeMarket<'info> {
 
         market.state = MarketState::Active;
 
+        emit_store_activity(
+            &mut self.store,
+            StoreActivityKind::ResumeMarket,
+            market.key(),
+        )?;
+
         Ok(())
     }
 }
@@ -0,0 +1,20 @@
+use crate::{state::StoreAuthorityProposed, RotateStoreAuthority};
+use anchor_lang::prelude::*;
+
+impl<'info> RotateStoreAuthority<'info> {
+    pub fn process(&mut self, new_admin: Pubkey) -> Result<()> {
+        let store = &mut self.store;
+
+        // Proposing again replaces the previous proposal
+        store.pending_admin = Some(new_admin);
+
+        emit!(StoreAuthorityProposed {
+            store: store.key(),
+            sequence: store.next_sequence()?,
+            admin: store.admin,
+            pending_admin: new_admin,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,37 @@
+use crate::{
+    error::ErrorCode,
+    state::{DustPolicy, MarketState, StoreActivityKind},
+    utils::{assert_market_authority, emit_store_activity},
+    SetDustPolicy,
+};
+use anchor_lang::prelude::*;
+
+impl<'info> SetDustPolicy<'info> {
+    pub fn process(&mut self, dust_policy: DustPolicy) -> Result<()> {
+        assert_market_authority(&self.market, self.owner.key, &self.market_manager)?;
+
+        let market = &mut self.market;
+        let clock = &self.clock;
+
+        // Check, that `Market` is not in `Ended` state
+        if market.state == MarketState::Ended {
+            return Err(ErrorCode::MarketIsEnded.into());
+        }
+
+        if let Some(end_date) = market.end_date {
+            if clock.unix_timestamp as u64 > end_date {
+                return Err(ErrorCode::MarketIsEnded.into());
+            }
+        }
+
+        market.dust_policy = dust_policy;
+
+        emit_store_activity(
+            &mut self.store,
+            StoreActivityKind::SetMarketConfig,
+            market.key(),
+        )?;
+
+        Ok(())
+    }
+}
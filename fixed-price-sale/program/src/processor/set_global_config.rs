@@ -0,0 +1,26 @@
+use crate::{error::ErrorCode, SetGlobalConfig};
+use anchor_lang::prelude::*;
+
+impl<'info> SetGlobalConfig<'info> {
+    pub fn process(
+        &mut self,
+        raffle_mode_enabled: bool,
+        oracle_pricing_enabled: bool,
+        max_markets_per_store: Option<u64>,
+        max_processor_fee_basis_points: u16,
+    ) -> Result<()> {
+        let global_config = &mut self.global_config;
+
+        if max_processor_fee_basis_points > 10000 {
+            return Err(ErrorCode::InvalidProcessorFee.into());
+        }
+
+        global_config.authority = self.authority.key();
+        global_config.raffle_mode_enabled = raffle_mode_enabled;
+        global_config.oracle_pricing_enabled = oracle_pricing_enabled;
+        global_config.max_markets_per_store = max_markets_per_store;
+        global_config.max_processor_fee_basis_points = max_processor_fee_basis_points;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,57 @@
+use crate::{
+    error::ErrorCode,
+    state::{MarketState, Phase, StoreActivityKind},
+    utils::*,
+    SetPhases,
+};
+use anchor_lang::prelude::*;
+
+impl<'info> SetPhases<'info> {
+    pub fn process(&mut self, phases: Vec<Phase>) -> Result<()> {
+        assert_market_authority(&self.market, self.owner.key, &self.market_manager)?;
+
+        let market = &mut self.market;
+        let clock = &self.clock;
+
+        // Check, that `Market` is not in `Ended` state
+        if market.state == MarketState::Ended {
+            return Err(ErrorCode::MarketIsEnded.into());
+        }
+
+        if let Some(end_date) = market.end_date {
+            if clock.unix_timestamp as u64 > end_date {
+                return Err(ErrorCode::MarketIsEnded.into());
+            }
+        }
+
+        if phases.len() > MAX_PHASES {
+            return Err(ErrorCode::InvalidPhases.into());
+        }
+
+        // Phases follow each other, `buy` is closed in the gaps between them
+        let mut previous: Option<&Phase> = None;
+        for phase in &phases {
+            if phase.start >= phase.end || phase.wallet_limit == Some(0) {
+                return Err(ErrorCode::InvalidPhases.into());
+            }
+
+            if let Some(previous) = previous {
+                if phase.start < previous.end {
+                    return Err(ErrorCode::InvalidPhases.into());
+                }
+            }
+
+            previous = Some(phase);
+        }
+
+        market.phases = phases;
+
+        emit_store_activity(
+            &mut self.store,
+            StoreActivityKind::SetMarketConfig,
+            market.key(),
+        )?;
+
+        Ok(())
+    }
+}
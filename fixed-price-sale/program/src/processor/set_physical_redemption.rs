@@ -0,0 +1,43 @@
+use crate::{
+    error::ErrorCode,
+    state::{MarketState, PhysicalRedemption, StoreActivityKind},
+    utils::{assert_market_authority, emit_store_activity},
+    SetPhysicalRedemption,
+};
+use anchor_lang::prelude::*;
+
+impl<'info> SetPhysicalRedemption<'info> {
+    pub fn process(&mut self, physical_redemption: Option<PhysicalRedemption>) -> Result<()> {
+        assert_market_authority(&self.market, self.owner.key, &self.market_manager)?;
+
+        let market = &mut self.market;
+        let clock = &self.clock;
+
+        // Check, that `Market` took no purchases yet, escrow terms can't change under buyers
+        if market.state != MarketState::Created {
+            return Err(ErrorCode::MarketInInvalidState.into());
+        }
+
+        if let Some(end_date) = market.end_date {
+            if clock.unix_timestamp as u64 > end_date {
+                return Err(ErrorCode::MarketIsEnded.into());
+            }
+        }
+
+        if let Some(physical_redemption) = &physical_redemption {
+            if physical_redemption.delivery_window == 0 {
+                return Err(ErrorCode::InvalidPhysicalRedemption.into());
+            }
+        }
+
+        market.physical_redemption = physical_redemption;
+
+        emit_store_activity(
+            &mut self.store,
+            StoreActivityKind::SetMarketConfig,
+            market.key(),
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,51 @@
+use crate::{
+    error::ErrorCode,
+    state::{MarketState, PriceOracle, StoreActivityKind},
+    utils::{assert_market_authority, emit_store_activity, load_global_config},
+    SetPriceOracle,
+};
+use anchor_lang::prelude::*;
+
+impl<'info> SetPriceOracle<'info> {
+    pub fn process(
+        &mut self,
+        price_oracle: Option<PriceOracle>,
+        oracle_override: bool,
+    ) -> Result<()> {
+        assert_market_authority(&self.market, self.owner.key, &self.market_manager)?;
+
+        let market = &mut self.market;
+        let clock = &self.clock;
+
+        // Check, that `Market` is not in `Ended` state
+        if market.state == MarketState::Ended {
+            return Err(ErrorCode::MarketIsEnded.into());
+        }
+
+        if let Some(end_date) = market.end_date {
+            if clock.unix_timestamp as u64 > end_date {
+                return Err(ErrorCode::MarketIsEnded.into());
+            }
+        }
+
+        // Price feeds can always be detached, attaching one may be disabled program wide
+        if price_oracle.is_some() {
+            if let Some(global_config) = load_global_config(&self.global_config)? {
+                if !global_config.oracle_pricing_enabled {
+                    return Err(ErrorCode::FeatureDisabled.into());
+                }
+            }
+        }
+
+        market.price_oracle = price_oracle;
+        market.oracle_override = oracle_override;
+
+        emit_store_activity(
+            &mut self.store,
+            StoreActivityKind::SetMarketConfig,
+            market.key(),
+        )?;
+
+        Ok(())
+    }
+}
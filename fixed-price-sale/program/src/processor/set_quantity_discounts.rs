@@ -0,0 +1,62 @@
+use crate::{
+    error::ErrorCode,
+    state::{MarketState, QuantityDiscount, StoreActivityKind},
+    utils::*,
+    SetQuantityDiscounts,
+};
+use anchor_lang::prelude::*;
+
+impl<'info> SetQuantityDiscounts<'info> {
+    pub fn process(&mut self, quantity_discounts: Vec<QuantityDiscount>) -> Result<()> {
+        assert_market_authority(&self.market, self.owner.key, &self.market_manager)?;
+
+        let market = &mut self.market;
+        let clock = &self.clock;
+
+        // Check, that `Market` is not in `Ended` state
+        if market.state == MarketState::Ended {
+            return Err(ErrorCode::MarketIsEnded.into());
+        }
+
+        if let Some(end_date) = market.end_date {
+            if clock.unix_timestamp as u64 > end_date {
+                return Err(ErrorCode::MarketIsEnded.into());
+            }
+        }
+
+        if quantity_discounts.len() > MAX_QUANTITY_DISCOUNTS {
+            return Err(ErrorCode::InvalidQuantityDiscounts.into());
+        }
+
+        // Tiers for more editions must start later and never discount less
+        let mut previous: Option<&QuantityDiscount> = None;
+        for discount in &quantity_discounts {
+            if discount.min_quantity < 2
+                || discount.discount_basis_points == 0
+                || discount.discount_basis_points > 10000
+            {
+                return Err(ErrorCode::InvalidQuantityDiscounts.into());
+            }
+
+            if let Some(previous) = previous {
+                if discount.min_quantity <= previous.min_quantity
+                    || discount.discount_basis_points < previous.discount_basis_points
+                {
+                    return Err(ErrorCode::InvalidQuantityDiscounts.into());
+                }
+            }
+
+            previous = Some(discount);
+        }
+
+        market.quantity_discounts = quantity_discounts;
+
+        emit_store_activity(
+            &mut self.store,
+            StoreActivityKind::SetMarketConfig,
+            market.key(),
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,25 @@
+use crate::{error::ErrorCode, state::StoreActivityKind, utils::*, SetStorePaymentMints};
+use anchor_lang::prelude::*;
+
+impl<'info> SetStorePaymentMints<'info> {
+    pub fn process(&mut self, mints: Vec<Pubkey>, admin_override: bool) -> Result<()> {
+        let store_payment_mints = &mut self.store_payment_mints;
+
+        if mints.len() > MAX_STORE_PAYMENT_MINTS {
+            return Err(ErrorCode::TooManyPaymentMints.into());
+        }
+
+        store_payment_mints.store = self.store.key();
+        store_payment_mints.mints = mints;
+        store_payment_mints.admin_override = admin_override;
+
+        let store_key = self.store.key();
+        emit_store_activity(
+            &mut self.store,
+            StoreActivityKind::SetStorePaymentMints,
+            store_key,
+        )?;
+
+        Ok(())
+    }
+}
@@ -1,8 +1,15 @@
-use crate::{error::ErrorCode, state::MarketState, SuspendMarket};
+use crate::{
+    error::ErrorCode,
+    state::{MarketState, StoreActivityKind},
+    utils::{assert_market_authority, emit_store_activity},
+    SuspendMarket,
+};
 use anchor_lang::prelude::*;
 
 impl<'info> SuspendMarket<'info> {
     pub fn process(&mut self) -> Result<()> {
+        assert_market_authority(&self.market, self.owner.key, &self.market_manager)?;
+
         let market = &mut self.market;
         let clock = &self.clock;
 
@@ -34,6 +41,12 @@ impl<'info> SuspendMarket<'info> {
 
         market.state = MarketState::Suspended;
 
+        emit_store_activity(
+            &mut self.store,
+            StoreActivityKind::SuspendMarket,
+            market.key(),
+        )?;
+
         Ok(())
     }
 }
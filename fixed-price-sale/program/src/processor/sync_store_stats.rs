@@ -0,0 +1,38 @@
+use crate::{
+    error::ErrorCode, state::StoreActivityKind, utils::emit_store_activity, SyncStoreStats,
+};
+use anchor_lang::prelude::*;
+
+impl<'info> SyncStoreStats<'info> {
+    pub fn process(&mut self) -> Result<()> {
+        let store = &mut self.store;
+        let market = &mut self.market;
+        let selling_resource = &self.selling_resource;
+
+        // Markets created after stats were introduced are already counted
+        if market.stats_synced {
+            return Err(ErrorCode::MarketStatsAlreadySynced.into());
+        }
+
+        store.total_markets = store
+            .total_markets
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        // Selling resource is used by one market only, so its supply is the sold editions
+        store.total_editions_sold = store
+            .total_editions_sold
+            .checked_add(selling_resource.supply)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        store
+            .add_volume(market.treasury_mint, market.funds_collected)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        market.stats_synced = true;
+
+        emit_store_activity(store, StoreActivityKind::SyncStoreStats, market.key())?;
+
+        Ok(())
+    }
+}
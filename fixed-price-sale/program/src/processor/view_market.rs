@@ -0,0 +1,56 @@
+use crate::{
+    error::ErrorCode,
+    state::{MarketState, MarketView, TradeHistory},
+    utils::*,
+    ViewMarket,
+};
+use anchor_lang::{prelude::*, solana_program::program::set_return_data};
+
+impl<'info> ViewMarket<'info> {
+    pub fn process(&self, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        let market = &self.market;
+        let selling_resource = &self.selling_resource;
+        let now = self.clock.unix_timestamp as u64;
+
+        // Markets only switch state on the next `buy`, so the dates decide until then
+        let state = match market.state {
+            MarketState::Created | MarketState::Active
+                if market.end_date.map_or(false, |end_date| now > end_date) =>
+            {
+                MarketState::Ended
+            }
+            MarketState::Created if market.start_date <= now => MarketState::Active,
+            _ => market.state.clone(),
+        };
+
+        // Trade history of a wallet, if passed, prices its next edition with the quantity discount it reached,
+        // staged sales from the price of the active phase
+        let already_bought = match remaining_accounts.first() {
+            Some(trade_history_info) => {
+                let trade_history = Account::<TradeHistory>::try_from(trade_history_info)?;
+                if trade_history.market != market.key() {
+                    return Err(ErrorCode::InvalidTradeHistory.into());
+                }
+                trade_history.already_bought
+            }
+            None => 0,
+        };
+        let price = already_bought
+            .checked_add(1)
+            .and_then(|piece| market.piece_price_at(piece, now))
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        let view = MarketView {
+            version: VIEW_VERSION,
+            state,
+            price,
+            treasury_mint: market.treasury_mint,
+            remaining_supply: selling_resource.remaining_supply(),
+            start_date: market.start_date,
+            end_date: market.end_date,
+        };
+        set_return_data(&view.try_to_vec()?);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,20 @@
+use crate::{state::SellingResourceView, utils::*, ViewSellingResource};
+use anchor_lang::{prelude::*, solana_program::program::set_return_data};
+
+impl<'info> ViewSellingResource<'info> {
+    pub fn process(&self) -> Result<()> {
+        let selling_resource = &self.selling_resource;
+
+        let view = SellingResourceView {
+            version: VIEW_VERSION,
+            state: selling_resource.state.clone(),
+            resource: selling_resource.resource,
+            supply: selling_resource.supply,
+            max_supply: selling_resource.max_supply,
+            remaining_supply: selling_resource.remaining_supply(),
+        };
+        set_return_data(&view.try_to_vec()?);
+
+        Ok(())
+    }
+}
@@ -1,7 +1,9 @@
 use crate::state::from_mpl_creators;
 use crate::{
     error::ErrorCode,
-    state::{Creator, MarketState, PrimaryMetadataCreators},
+    state::{
+        Creator, DustPolicy, MarketState, PayoutTicket, PrimaryMetadataCreators, StoreActivityKind,
+    },
     utils::*,
     Withdraw,
 };
@@ -18,7 +20,7 @@ impl<'info> Withdraw<'info> {
         _payout_ticket_bump: u8,
         remaining_accounts: &[AccountInfo<'info>],
     ) -> Result<()> {
-        let market = &self.market;
+        let market = &mut self.market;
         let token_program = &self.token_program;
         let associated_token_program = &self.associated_token_program;
         let system_program = &self.system_program;
@@ -30,23 +32,27 @@ impl<'info> Withdraw<'info> {
         let funder = &self.funder;
         let payer = &self.payer;
         let payout_ticket = &mut self.payout_ticket;
-        let rent = &self.rent;
+        let claim_tally = &mut self.claim_tally;
         let clock = &self.clock;
+        let rent = &self.rent;
         let metadata = &self.metadata.to_account_info();
 
         let selling_resource_key = selling_resource.key().clone();
         let treasury_mint_key = market.treasury_mint.clone();
         let funder_key = funder.key();
 
-        // Check, that `Market` is `Ended`
-        if let Some(end_date) = market.end_date {
-            if clock.unix_timestamp as u64 <= end_date {
-                return Err(ErrorCode::MarketInInvalidState.into());
-            }
-        } else {
-            if market.state != MarketState::Ended {
-                return Err(ErrorCode::MarketInInvalidState.into());
-            }
+        // Check, that `Market` has taken sales, creators may claim again as funds come in
+        if market.state == MarketState::Created {
+            return Err(ErrorCode::MarketInInvalidState.into());
+        }
+
+        // Check, that `Market` is `Ended` for the market owner, who sweeps mid-sale only through `partial_sweep`
+        let is_ended = market.state == MarketState::Ended
+            || market
+                .end_date
+                .map_or(false, |end_date| clock.unix_timestamp as u64 > end_date);
+        if funder_key == market.owner && !is_ended {
+            return Err(ErrorCode::MarketInInvalidState.into());
         }
 
         // Check, that provided metadata is correct
@@ -67,7 +73,18 @@ impl<'info> Withdraw<'info> {
                 return Err(ErrorCode::PrimaryMetadataCreatorsNotProvided.into());
             }
 
-            let primary_metadata_creators_data = remaining_accounts[0].data.borrow()[8..].to_vec();
+            let primary_metadata_creators = &remaining_accounts[0];
+            assert_derivation(
+                &crate::id(),
+                primary_metadata_creators,
+                &[
+                    PRIMARY_METADATA_CREATORS_PREFIX.as_bytes(),
+                    self.metadata.key().as_ref(),
+                ],
+            )?;
+
+            let primary_metadata_creators_data =
+                primary_metadata_creators.data.borrow()[8..].to_vec();
             let primary_metadata_creators = try_from_slice_unchecked::<PrimaryMetadataCreators>(
                 &primary_metadata_creators_data,
             )?;
@@ -88,7 +105,7 @@ impl<'info> Withdraw<'info> {
         // Check, that funder is `Creator` or `Market` owner
         // `Some` mean funder is `Creator`
         // `None` mean funder is `Market` owner
-        let funder_creator = if let Some(creators) = *actual_creators {
+        let funder_creator = if let Some(creators) = actual_creators.as_ref() {
             let funder_creator = creators.iter().find(|c| c.address == funder_key).cloned();
             if funder_creator.is_none() && funder_key != market.owner {
                 return Err(ErrorCode::FunderIsInvalid.into());
@@ -101,15 +118,19 @@ impl<'info> Withdraw<'info> {
             None
         };
 
-        // Check, that user can withdraw funds(first time)
-        if payout_ticket.used {
-            return Err(ErrorCode::PayoutTicketExists.into());
-        } else {
-            payout_ticket.used = true;
-        }
-
         let is_native = market.treasury_mint == System::id();
 
+        // `None` mean dust stays in the treasury
+        let dust_recipient = match market.dust_policy {
+            DustPolicy::Leave => None,
+            DustPolicy::MarketOwner => Some(market.owner),
+            DustPolicy::FirstCreator => actual_creators
+                .as_deref()
+                .and_then(|creators| creators.first())
+                .map(|creator| creator.address),
+        };
+        let is_dust_recipient = dust_recipient == Some(funder_key);
+
         let amount = if metadata.primary_sale_happened {
             if funder_creator.is_some() && funder_key == market.owner {
                 // if funder is NFT creator and market owner at the same time
@@ -148,11 +169,64 @@ impl<'info> Withdraw<'info> {
                     market.funds_collected,
                     funder_creator.share as u64,
                 )?
+            } else if is_dust_recipient {
+                // market owner may only collect the dust of a primary sale
+                0
             } else {
                 return Err(ErrorCode::MarketOwnerDoesntHaveShares.into());
             }
         };
 
+        // Pay only the part of the share not claimed yet, market owner claims are tracked together with `partial_sweep`
+        let claimed = if payout_ticket.claimed == PayoutTicket::LEGACY_CLAIMED {
+            amount
+        } else if funder_key == market.owner {
+            market.funds_swept
+        } else {
+            payout_ticket.claimed
+        };
+        let mut amount = amount.checked_sub(claimed).ok_or(ErrorCode::MathOverflow)?;
+
+        payout_ticket.used = true;
+        payout_ticket.claimed = payout_ticket
+            .claimed
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        if funder_key == market.owner {
+            market.funds_swept = market
+                .funds_swept
+                .checked_add(amount)
+                .ok_or(ErrorCode::MathOverflow)?;
+        } else {
+            claim_tally.claimed = claim_tally
+                .claimed
+                .checked_add(amount)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+
+        // Once sales are over and every share is paid out, the dust recipient also gets the remainder
+        if is_dust_recipient && is_ended && !claim_tally.dust_paid {
+            let dust = calculate_dust(
+                market.funds_collected,
+                metadata.data.seller_fee_basis_points as u64,
+                metadata.primary_sale_happened,
+                actual_creators.as_deref().unwrap_or(&[]),
+            )?;
+            let paid_out = claim_tally
+                .claimed
+                .checked_add(market.funds_swept)
+                .ok_or(ErrorCode::MathOverflow)?;
+
+            if dust > 0 && paid_out == market.funds_collected - dust {
+                amount = amount.checked_add(dust).ok_or(ErrorCode::MathOverflow)?;
+                claim_tally.dust_paid = true;
+            }
+        }
+
+        if amount == 0 {
+            return Err(ErrorCode::NothingToWithdraw.into());
+        }
+
         // Transfer royalties
         let signer_seeds: &[&[&[u8]]] = &[&[
             HOLDER_PREFIX.as_bytes(),
@@ -215,6 +289,8 @@ impl<'info> Withdraw<'info> {
             token::transfer(cpi_ctx, amount)?;
         }
 
+        emit_store_activity(&mut self.store, StoreActivityKind::Withdraw, market.key())?;
+
         Ok(())
     }
 }
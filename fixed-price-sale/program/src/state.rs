@@ -1,9 +1,16 @@
 //! Module provide program defined state
 
-use crate::utils::{DESCRIPTION_DEFAULT_SIZE, MAX_PRIMARY_CREATORS_LEN, NAME_DEFAULT_SIZE};
+use crate::{
+    error::ErrorCode,
+    utils::{
+        vault_owner_version_seed, DESCRIPTION_DEFAULT_SIZE, MAX_PHASES, MAX_PRIMARY_CREATORS_LEN,
+        MAX_QUANTITY_DISCOUNTS, MAX_QUEUED_ACTIONS, MAX_STORE_PAYMENT_MINTS,
+        MAX_STORE_VOLUME_MINTS, NAME_DEFAULT_SIZE, NAME_SUFFIX_MAX_LEN, URI_MAX_LEN,
+    },
+};
 use anchor_lang::prelude::*;
 use mpl_token_metadata::state::Creator as MPL_Creator;
-use std::convert::From;
+use std::convert::{From, TryFrom};
 
 // by system acc I mean account to hold only native SOL
 pub const MINIMUM_BALANCE_FOR_SYSTEM_ACCS: u64 = 890880;
@@ -13,10 +20,71 @@ pub struct Store {
     pub admin: Pubkey,
     pub name: String,
     pub description: String,
+    pub total_markets: u64,
+    pub total_editions_sold: u64,
+    /// gross volume per treasury mint, mints above `MAX_STORE_VOLUME_MINTS` are not tracked
+    pub volume: Vec<TreasuryVolume>,
+    /// admin proposed by `rotate_store_authority`, becomes `admin` once it accepts
+    pub pending_admin: Option<Pubkey>,
+    /// number of accepted admin rotations, new `SellingResource` vault owners derive from it
+    pub authority_version: u8,
+    /// number of state changes under the store, embedded into their events so indexers can detect missed ones
+    pub sequence: u64,
 }
 
 impl Store {
-    pub const LEN: usize = 8 + 32 + NAME_DEFAULT_SIZE + DESCRIPTION_DEFAULT_SIZE;
+    pub const LEN: usize = 8
+        + 32
+        + NAME_DEFAULT_SIZE
+        + DESCRIPTION_DEFAULT_SIZE
+        + 8
+        + 8
+        + 4
+        + TreasuryVolume::LEN * MAX_STORE_VOLUME_MINTS
+        + 33
+        + 1
+        + 8;
+
+    /// `vault_owner` seed for resources created under the current `authority_version`
+    pub fn vault_owner_version_seed(&self) -> &[u8] {
+        vault_owner_version_seed(&self.authority_version)
+    }
+
+    /// Bump `sequence` for a state change under the store, returns the sequence of its event
+    pub fn next_sequence(&mut self) -> Result<u64> {
+        self.sequence = self
+            .sequence
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(self.sequence)
+    }
+
+    pub fn add_volume(&mut self, treasury_mint: Pubkey, amount: u64) -> Option<()> {
+        if let Some(entry) = self
+            .volume
+            .iter_mut()
+            .find(|e| e.treasury_mint == treasury_mint)
+        {
+            entry.amount = entry.amount.checked_add(amount)?;
+        } else if self.volume.len() < MAX_STORE_VOLUME_MINTS {
+            self.volume.push(TreasuryVolume {
+                treasury_mint,
+                amount,
+            });
+        }
+
+        Some(())
+    }
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug, PartialEq, Eq)]
+pub struct TreasuryVolume {
+    pub treasury_mint: Pubkey,
+    pub amount: u64,
+}
+
+impl TreasuryVolume {
+    pub const LEN: usize = 32 + 8;
 }
 
 #[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug, PartialEq, Eq)]
@@ -28,6 +96,14 @@ pub enum SellingResourceState {
     Stopped,
 }
 
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug, PartialEq, Eq)]
+pub enum SellingResourceType {
+    /// buy prints new editions from the vaulted master edition
+    Editions,
+    /// single buy takes the vaulted master edition token itself
+    MasterEditionAsIs,
+}
+
 #[account]
 pub struct SellingResource {
     pub store: Pubkey,
@@ -38,10 +114,26 @@ pub struct SellingResource {
     pub supply: u64,
     pub max_supply: Option<u64>,
     pub state: SellingResourceState,
+    /// `Store::authority_version` at the time the resource was created
+    pub vault_owner_version: u8,
+    pub resource_type: SellingResourceType,
+    /// Index of the next market derived by `create_market_pda`
+    pub market_index: u64,
 }
 
 impl SellingResource {
-    pub const LEN: usize = 8 + 32 + 32 + 32 + 32 + 32 + 8 + 9 + 1;
+    pub const LEN: usize = 8 + 32 + 32 + 32 + 32 + 32 + 8 + 9 + 1 + 1 + 1 + 8;
+
+    /// Editions left to sell, `None` for unlimited supply
+    pub fn remaining_supply(&self) -> Option<u64> {
+        self.max_supply
+            .map(|max_supply| max_supply.saturating_sub(self.supply))
+    }
+
+    /// `vault_owner` seed matching the `authority_version` the resource was created under
+    pub fn vault_owner_version_seed(&self) -> &[u8] {
+        vault_owner_version_seed(&self.vault_owner_version)
+    }
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
@@ -72,6 +164,41 @@ pub struct Market {
     // need this field to calculate royalties at withdraw
     pub funds_collected: u64,
     pub gatekeeper: Option<GatingConfig>,
+    pub metadata_overrides: Option<MetadataOverrides>,
+    /// whether new edition mints are created by program at PDA addresses
+    pub pda_mints: bool,
+    /// whether market is counted in `Store` stats
+    pub stats_synced: bool,
+    /// collection of the voucher NFTs burned by `redeem_voucher_buy` in lieu of payment
+    pub voucher_collection: Option<Pubkey>,
+    /// fee skimmed from every sale for the payment processor
+    pub processor_fee: Option<ProcessorFee>,
+    /// share of the treasury the owner may sweep once per epoch while an unlimited market is active
+    pub sweep_basis_points: Option<u16>,
+    /// epoch of the last `partial_sweep`
+    pub last_sweep_epoch: Option<u64>,
+    /// funds taken by `partial_sweep` or earlier owner `withdraw`, deducted from the owner share at withdraw
+    pub funds_swept: u64,
+    /// program approving every `buy` through `approve_purchase` CPI
+    pub gatekeeper_program: Option<GatekeeperProgram>,
+    /// price feed of the treasury stablecoin, `buy` is paused while it is depegged
+    pub price_oracle: Option<PriceOracle>,
+    /// whether owner resumed sales regardless of the `price_oracle`
+    pub oracle_override: bool,
+    /// recipient of the remainder left in the treasury by floored share math
+    pub dust_policy: DustPolicy,
+    /// discount tiers by number of editions a wallet bought, sorted by `min_quantity`
+    pub quantity_discounts: Vec<QuantityDiscount>,
+    /// phases of a staged sale, sorted by `start` and not overlapping
+    pub phases: Vec<Phase>,
+    /// number of `MarketManager` accounts allowed to change the market besides its owner
+    pub managers: u8,
+    /// whether proceeds of every purchase stay escrowed until its delivery is confirmed
+    pub physical_redemption: Option<PhysicalRedemption>,
+    /// proceeds of purchases awaiting delivery, not part of `funds_collected` until confirmed
+    pub funds_escrowed: u64,
+    /// whether `buy` appends a record to the market `ActionQueue`
+    pub action_queue: bool,
 }
 
 impl Market {
@@ -94,7 +221,67 @@ impl Market {
         + 1
         + 32
         + 1
-        + 9;
+        + 9
+        + 1
+        + MetadataOverrides::LEN
+        + 1
+        + 1
+        + 33
+        + 1
+        + ProcessorFee::LEN
+        + 3
+        + 9
+        + 8
+        + 1
+        + GatekeeperProgram::LEN
+        + 1
+        + PriceOracle::LEN
+        + 1
+        + 1
+        + 4
+        + QuantityDiscount::LEN * MAX_QUANTITY_DISCOUNTS
+        + 4
+        + Phase::LEN * MAX_PHASES
+        + 1
+        + 1
+        + PhysicalRedemption::LEN
+        + 8
+        + 1;
+
+    /// Price of the `piece`-th edition bought by a wallet, with the deepest quantity discount it reached
+    pub fn piece_price(&self, piece: u64) -> Option<u64> {
+        self.discounted_price(self.price, piece)
+    }
+
+    /// Price of the `piece`-th edition bought by a wallet at `now`, discounted from the price of the active phase
+    pub fn piece_price_at(&self, piece: u64, now: u64) -> Option<u64> {
+        self.discounted_price(self.base_price_at(now), piece)
+    }
+
+    /// Price before quantity discounts at `now`, the price of the active phase for staged sales
+    pub fn base_price_at(&self, now: u64) -> u64 {
+        self.active_phase(now)
+            .map_or(self.price, |phase| phase.price)
+    }
+
+    /// Phase of a staged sale active at `now`, `None` between phases or for markets without them
+    pub fn active_phase(&self, now: u64) -> Option<&Phase> {
+        self.phases
+            .iter()
+            .find(|phase| phase.start <= now && now < phase.end)
+    }
+
+    fn discounted_price(&self, price: u64, piece: u64) -> Option<u64> {
+        match self
+            .quantity_discounts
+            .iter()
+            .rev()
+            .find(|discount| piece >= discount.min_quantity)
+        {
+            Some(discount) => discount.apply(price),
+            None => Some(price),
+        }
+    }
 }
 
 #[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug, PartialEq, Eq)]
@@ -105,16 +292,266 @@ pub struct GatingConfig {
     pub gating_time: Option<u64>,
 }
 
+impl GatingConfig {
+    pub const LEN: usize = 32 + 1 + 9;
+}
+
+/// Fee of the payment processor, e.g. a fiat on-ramp, deducted from the price of every sale
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug, PartialEq, Eq)]
+pub struct ProcessorFee {
+    /// wallet for native SOL markets or token account of the treasury mint receiving the fee
+    pub destination: Pubkey,
+    pub basis_points: u16,
+}
+
+impl ProcessorFee {
+    pub const LEN: usize = 32 + 2;
+
+    /// Part of the `price` going to the processor
+    pub fn amount(&self, price: u64) -> Option<u64> {
+        let amount = (price as u128)
+            .checked_mul(self.basis_points as u128)?
+            .checked_div(10000)?;
+
+        u64::try_from(amount).ok()
+    }
+}
+
+/// External program called by `buy` to approve the purchase, e.g. for staking, quest or KYC requirements
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug, PartialEq, Eq)]
+pub struct GatekeeperProgram {
+    pub program_id: Pubkey,
+    /// number of `buy` remaining accounts following the program, passed through to `approve_purchase`
+    pub additional_accounts: u8,
+}
+
+impl GatekeeperProgram {
+    pub const LEN: usize = 32 + 1;
+}
+
+/// Where `withdraw` sends the treasury remainder left by flooring every share, once all shares are claimed
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DustPolicy {
+    /// remainder stays in the treasury
+    Leave,
+    MarketOwner,
+    /// first of the creators the market pays out to
+    FirstCreator,
+}
+
+/// Discount on every edition a wallet buys from its `min_quantity`-th on,
+/// e.g. 3 for the price of 2.5 is a 5000 basis points discount from the 3rd edition
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QuantityDiscount {
+    pub min_quantity: u64,
+    pub discount_basis_points: u16,
+}
+
+impl QuantityDiscount {
+    pub const LEN: usize = 8 + 2;
+
+    /// `price` less the discount
+    pub fn apply(&self, price: u64) -> Option<u64> {
+        let discount = (price as u128)
+            .checked_mul(self.discount_basis_points as u128)?
+            .checked_div(10000)?;
+
+        price.checked_sub(u64::try_from(discount).ok()?)
+    }
+}
+
+/// Phase of a staged sale, e.g. allowlist, public or cleanup, with its own price, wallet limit and gate.
+/// Active from `start` up to but not including `end`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Phase {
+    pub start: u64,
+    pub end: u64,
+    pub price: u64,
+    /// editions a wallet may have acquired from the market by the end of the phase
+    pub wallet_limit: Option<u64>,
+    /// gate of the phase, in place of `Market::gatekeeper`
+    pub gate: Option<GatingConfig>,
+}
+
+impl Phase {
+    pub const LEN: usize = 8 + 8 + 8 + 9 + 1 + GatingConfig::LEN;
+}
+
+/// Pyth price feed of a treasury stablecoin quoted against the asset it is pegged to
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug, PartialEq, Eq)]
+pub struct PriceOracle {
+    pub price_feed: Pubkey,
+    /// deviation from the 1.0 peg tolerated before purchases are refused
+    pub tolerance_basis_points: u16,
+}
+
+impl PriceOracle {
+    pub const LEN: usize = 32 + 2;
+
+    /// Whether feed `price` with exponent `expo` deviates from the peg beyond tolerance
+    pub fn is_depegged(&self, price: i64, expo: i32) -> Option<bool> {
+        let peg = 10i128.checked_pow(u32::try_from(expo.checked_neg()?).ok()?)?;
+        let deviation = (price as i128 - peg)
+            .checked_abs()?
+            .checked_mul(10000)?
+            .checked_div(peg)?;
+
+        Some(deviation > self.tolerance_basis_points as i128)
+    }
+}
+
+/// Escrow of the proceeds of a market selling editions redeemable for physical goods
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug, PartialEq, Eq)]
+pub struct PhysicalRedemption {
+    /// may confirm delivery in place of the buyer
+    pub arbiter: Option<Pubkey>,
+    /// seconds after purchase the buyer is refunded from, unless delivery was confirmed
+    pub delivery_window: u64,
+}
+
+impl PhysicalRedemption {
+    pub const LEN: usize = 33 + 8;
+}
+
+/// Overrides applied to the metadata of every edition minted by the market
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug, PartialEq, Eq)]
+pub struct MetadataOverrides {
+    /// appended to the master edition name, e.g. " - Wave 2"
+    pub name_suffix: Option<String>,
+    /// replaces the master edition uri, e.g. to point to json with market specific attributes
+    pub uri: Option<String>,
+}
+
+impl MetadataOverrides {
+    pub const LEN: usize = 1 + 4 + NAME_SUFFIX_MAX_LEN + 1 + 4 + URI_MAX_LEN;
+}
+
 #[account]
 #[derive(Default)]
 pub struct TradeHistory {
     pub market: Pubkey,
     pub wallet: Pubkey,
     pub already_bought: u64,
+    /// editions acquired by burning vouchers, not counted in `already_bought`
+    pub already_redeemed: u64,
+    /// processor fees paid by the wallet, for reconciliation with the processor
+    pub processor_fees_paid: u64,
+    /// nonce passed by the client with the last successful `buy`, nonces not greater than it are rejected
+    pub last_client_nonce: Option<u64>,
+    /// editions bought at a quantity discount, counted in `already_bought` as well
+    pub discounted_bought: u64,
+    /// amount taken off the market price by quantity discounts
+    pub discounts_received: u64,
+    /// rent reimbursed to the wallet by the market `SponsorVault`
+    pub sponsored: u64,
 }
 
 impl TradeHistory {
-    pub const LEN: usize = 8 + 32 + 32 + 8;
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 8 + 1 + 8 + 8 + 8 + 8;
+
+    /// Editions acquired by the wallet, bought or redeemed with vouchers
+    pub fn pieces_acquired(&self) -> Option<u64> {
+        self.already_bought.checked_add(self.already_redeemed)
+    }
+
+    /// Client nonces have to grow with every purchase, so a retry of any earlier purchase is caught
+    pub fn is_client_nonce_used(&self, client_nonce: Option<u64>) -> bool {
+        matches!(
+            (client_nonce, self.last_client_nonce),
+            (Some(nonce), Some(last_nonce)) if nonce <= last_nonce
+        )
+    }
+}
+
+/// Reservation of a `Market` name within a `Store`, so one storefront can't list the same name twice
+#[account]
+#[derive(Default)]
+pub struct MarketNameRegistry {
+    pub store: Pubkey,
+    pub market: Pubkey,
+}
+
+impl MarketNameRegistry {
+    pub const LEN: usize = 8 + 32 + 32;
+}
+
+/// Origin of an edition minted by the market, written at mint time
+#[account]
+#[derive(Default)]
+pub struct ProvenanceRecord {
+    pub market: Pubkey,
+    pub buyer: Pubkey,
+    pub edition_mint: Pubkey,
+    pub edition: u64,
+    /// zero for editions redeemed with vouchers
+    pub price: u64,
+    pub slot: u64,
+    /// delivery of the physical goods, set for purchases from `Market::physical_redemption` markets
+    pub delivery: Option<Delivery>,
+}
+
+impl ProvenanceRecord {
+    pub const LEN: usize = 8 + 32 + 32 + 32 + 8 + 8 + 8 + 1 + Delivery::LEN;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeliveryState {
+    Pending,
+    Confirmed,
+    Refunded,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Delivery {
+    pub state: DeliveryState,
+    /// buyer may be refunded after this time while delivery is `Pending`
+    pub deadline: u64,
+    /// treasury amount of the purchase held in `Market::funds_escrowed`
+    pub escrowed: u64,
+    /// `PhysicalRedemption::arbiter` at the time of purchase
+    pub arbiter: Option<Pubkey>,
+}
+
+impl Delivery {
+    pub const LEN: usize = 1 + 8 + 8 + 33;
+}
+
+/// Set as return data when `buy` fails, so clients can adapt their retry behavior
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct BuyDiagnostics {
+    /// edition number the purchase attempted to mint, zero if it could not be derived
+    pub edition: u64,
+    pub supply: u64,
+    pub max_supply: Option<u64>,
+}
+
+/// Compact summary of a `Market` set as return data by `view_market`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct MarketView {
+    /// layout version, fields are only ever appended
+    pub version: u8,
+    /// state as of the current clock, e.g. `Ended` once `end_date` passed
+    pub state: MarketState,
+    /// price the next edition costs, discounted for the wallet of the trade history passed
+    pub price: u64,
+    pub treasury_mint: Pubkey,
+    /// editions left to sell, `None` for unlimited supply
+    pub remaining_supply: Option<u64>,
+    pub start_date: u64,
+    pub end_date: Option<u64>,
+}
+
+/// Compact summary of a `SellingResource` set as return data by `view_selling_resource`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct SellingResourceView {
+    /// layout version, fields are only ever appended
+    pub version: u8,
+    pub state: SellingResourceState,
+    pub resource: Pubkey,
+    pub supply: u64,
+    pub max_supply: Option<u64>,
+    /// editions left to sell, `None` for unlimited supply
+    pub remaining_supply: Option<u64>,
 }
 
 #[account]
@@ -142,10 +579,174 @@ impl PrimaryMetadataCreators {
 #[derive(Default)]
 pub struct PayoutTicket {
     pub used: bool,
+    /// funds paid out to the funder so far, `withdraw` pays the rest of its share of `Market::funds_collected`
+    pub claimed: u64,
 }
 
 impl PayoutTicket {
-    pub const LEN: usize = 9;
+    pub const LEN: usize = 8 + 1 + 8;
+    /// `claimed` of a ticket used before partial claims, its whole share was paid out at once
+    pub const LEGACY_CLAIMED: u64 = u64::MAX;
+}
+
+/// Treasury mints the store admin allows markets of the store to be paid in
+#[account]
+#[derive(Default)]
+pub struct StorePaymentMints {
+    pub store: Pubkey,
+    /// allowed treasury mints, `System::id()` stands for native SOL
+    pub mints: Vec<Pubkey>,
+    /// lets the store admin create markets in any treasury mint
+    pub admin_override: bool,
+}
+
+impl StorePaymentMints {
+    pub const LEN: usize = 8 + 32 + 4 + 32 * MAX_STORE_PAYMENT_MINTS + 1;
+}
+
+/// Program wide settings of the upgrade authority, handlers keep their default behaviour until it is created
+#[account]
+#[derive(Default)]
+pub struct GlobalConfig {
+    /// upgrade authority which last set the config
+    pub authority: Pubkey,
+    /// reserved for raffle markets, no handler reads it yet
+    pub raffle_mode_enabled: bool,
+    /// lets market owners attach price feeds with `set_price_oracle`
+    pub oracle_pricing_enabled: bool,
+    /// markets a store may create over its lifetime, unlimited if `None`
+    pub max_markets_per_store: Option<u64>,
+    /// cap on `ProcessorFee::basis_points` of new markets
+    pub max_processor_fee_basis_points: u16,
+}
+
+impl GlobalConfig {
+    pub const LEN: usize = 8 + 32 + 1 + 1 + 9 + 2;
+}
+
+/// Lamports the market owner set aside to reimburse the rent buyers pay in `buy_sponsored`
+#[account]
+#[derive(Default)]
+pub struct SponsorVault {
+    pub market: Pubkey,
+    /// rent reimbursed to a single wallet at most
+    pub per_wallet_cap: u64,
+    /// lamports funded by the market owner, not counting the vault rent
+    pub total_budget: u64,
+    /// lamports reimbursed to buyers so far
+    pub total_spent: u64,
+}
+
+impl SponsorVault {
+    pub const LEN: usize = 8 + 32 + 8 + 8 + 8;
+
+    /// Budget not reimbursed yet
+    pub fn remaining(&self) -> Option<u64> {
+        self.total_budget.checked_sub(self.total_spent)
+    }
+}
+
+/// Payouts of a market across all funders, used to tell the last `withdraw` reconciling the dust
+#[account]
+#[derive(Default)]
+pub struct ClaimTally {
+    /// funds paid to funders other than the market owner, whose payouts are tracked by `Market::funds_swept`
+    pub claimed: u64,
+    pub dust_paid: bool,
+}
+
+impl ClaimTally {
+    pub const LEN: usize = 8 + 8 + 1;
+}
+
+/// Wallet the market owner allowed to suspend, resume and change the market, but not to withdraw or close it
+#[account]
+#[derive(Default)]
+pub struct MarketManager {
+    pub market: Pubkey,
+    pub manager: Pubkey,
+}
+
+impl MarketManager {
+    pub const LEN: usize = 8 + 32 + 32;
+}
+
+/// Purchase awaiting the post-sale action of the `ActionQueue` worker
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ActionRecord {
+    pub sequence: u64,
+    pub buyer: Pubkey,
+    pub edition_mint: Pubkey,
+    pub price: u64,
+    pub slot: u64,
+}
+
+impl ActionRecord {
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 8;
+}
+
+/// Records `buy` appends for an off-chain worker, each kept until the worker calls `consume_action` for it
+#[account]
+#[derive(Default)]
+pub struct ActionQueue {
+    pub market: Pubkey,
+    /// only wallet allowed to consume actions
+    pub worker: Pubkey,
+    /// sequence of the next appended record, consecutive per queue
+    pub next_sequence: u64,
+    /// number of records consumed so far
+    pub consumed: u64,
+    /// records not consumed yet, oldest first
+    pub actions: Vec<ActionRecord>,
+}
+
+impl ActionQueue {
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 4 + ActionRecord::LEN * MAX_QUEUED_ACTIONS;
+
+    /// Append a record of a purchase, `None` once `MAX_QUEUED_ACTIONS` records wait for the worker
+    pub fn push(
+        &mut self,
+        buyer: Pubkey,
+        edition_mint: Pubkey,
+        price: u64,
+        slot: u64,
+    ) -> Option<u64> {
+        if self.actions.len() >= MAX_QUEUED_ACTIONS {
+            return None;
+        }
+
+        let sequence = self.next_sequence;
+        self.next_sequence = sequence.checked_add(1)?;
+        self.actions.push(ActionRecord {
+            sequence,
+            buyer,
+            edition_mint,
+            price,
+            slot,
+        });
+
+        Some(sequence)
+    }
+
+    /// Remove the record with `sequence`, `None` if it is not queued
+    pub fn consume(&mut self, sequence: u64) -> Option<ActionRecord> {
+        let index = self
+            .actions
+            .iter()
+            .position(|action| action.sequence == sequence)?;
+        self.consumed = self.consumed.checked_add(1)?;
+
+        Some(self.actions.remove(index))
+    }
+}
+
+/// Post-sale action of a purchase processed by the `ActionQueue` worker
+#[event]
+pub struct ActionConsumed {
+    pub market: Pubkey,
+    pub sequence: u64,
+    pub buyer: Pubkey,
+    pub edition_mint: Pubkey,
 }
 
 // Unfortunate duplication of token metadata so that IDL picks it up.
@@ -166,3 +767,55 @@ impl From<MPL_Creator> for Creator {
         }
     }
 }
+
+#[event]
+pub struct StoreAuthorityProposed {
+    pub store: Pubkey,
+    pub sequence: u64,
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct StoreAuthorityRotated {
+    pub store: Pubkey,
+    pub sequence: u64,
+    pub previous_admin: Pubkey,
+    pub admin: Pubkey,
+    pub authority_version: u8,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StoreActivityKind {
+    CreateStore,
+    InitSellingResource,
+    CreateMarket,
+    CloneMarket,
+    ChangeMarket,
+    SuspendMarket,
+    ResumeMarket,
+    CloseMarket,
+    SetMarketConfig,
+    FundSponsorVault,
+    Buy,
+    Withdraw,
+    PartialSweep,
+    ClaimResource,
+    SyncStoreStats,
+    SetStorePaymentMints,
+    AddMarketManager,
+    RemoveMarketManager,
+    ConfirmDelivery,
+    RefundDelivery,
+}
+
+/// State change under a `Store`, emitted by every instruction writing to the store or one of its markets
+#[event]
+pub struct StoreActivity {
+    pub store: Pubkey,
+    /// `Store::sequence` after the change, consecutive per store
+    pub sequence: u64,
+    pub kind: StoreActivityKind,
+    /// market or selling resource changed, the store itself for store level changes
+    pub subject: Pubkey,
+}
@@ -1,22 +1,58 @@
 //! Module provide runtime utilities
 
-use crate::{id, ErrorCode};
+use crate::{
+    id,
+    state::{
+        Creator, GlobalConfig, Market, MarketManager, MarketNameRegistry, Store, StoreActivity,
+        StoreActivityKind, StorePaymentMints,
+    },
+    ErrorCode,
+};
 use anchor_lang::{
     prelude::*,
-    solana_program::{program::invoke_signed, system_instruction},
+    solana_program::{
+        hash::hash,
+        program::{invoke, invoke_signed},
+        system_instruction,
+    },
 };
+use std::convert::TryInto;
 
 pub const NAME_MAX_LEN: usize = 40; // max len of a string buffer in bytes
 pub const NAME_DEFAULT_SIZE: usize = 4 + NAME_MAX_LEN; // max lenght of serialized string (str_len + <buffer>)
 pub const DESCRIPTION_MAX_LEN: usize = 60;
 pub const DESCRIPTION_DEFAULT_SIZE: usize = 4 + DESCRIPTION_MAX_LEN;
+pub const NAME_SUFFIX_MAX_LEN: usize = mpl_token_metadata::state::MAX_NAME_LENGTH; // edition name is still limited by token-metadata
+pub const URI_MAX_LEN: usize = mpl_token_metadata::state::MAX_URI_LENGTH;
 pub const HOLDER_PREFIX: &str = "holder";
 pub const HISTORY_PREFIX: &str = "history";
 pub const VAULT_OWNER_PREFIX: &str = "mt_vault";
 pub const PAYOUT_TICKET_PREFIX: &str = "payout_ticket";
+pub const CLAIM_TALLY_PREFIX: &str = "claim_tally";
+pub const SPONSOR_VAULT_PREFIX: &str = "sponsor_vault";
 pub const PRIMARY_METADATA_CREATORS_PREFIX: &str = "primary_creators";
+pub const NEW_MINT_PREFIX: &str = "new_mint";
+pub const PROVENANCE_RECORD_PREFIX: &str = "provenance";
+pub const MARKET_NAME_PREFIX: &str = "market_name";
+pub const MARKET_PREFIX: &str = "market";
+pub const STORE_PAYMENT_MINTS_PREFIX: &str = "store_payment_mints";
+pub const GLOBAL_CONFIG_PREFIX: &str = "global_config";
+pub const MARKET_MANAGER_PREFIX: &str = "market_manager";
+pub const ACTION_QUEUE_PREFIX: &str = "action_queue";
 pub const FLAG_ACCOUNT_SIZE: usize = 1; // Size for flag account to indicate something
 pub const MAX_PRIMARY_CREATORS_LEN: usize = 5; // Total allowed creators in `PrimaryMetadataCreators`
+pub const MAX_STORE_VOLUME_MINTS: usize = 5; // Total treasury mints tracked in `Store::volume`
+pub const MAX_QUANTITY_DISCOUNTS: usize = 4; // Total tiers allowed in `Market::quantity_discounts`
+pub const MAX_STORE_PAYMENT_MINTS: usize = 8; // Total mints allowed in `StorePaymentMints::mints`
+pub const MAX_PHASES: usize = 4; // Total phases allowed in `Market::phases`
+pub const MAX_MARKET_MANAGERS: u8 = 5; // Total `MarketManager` accounts allowed per `Market`
+pub const MAX_QUEUED_ACTIONS: usize = 16; // Total unconsumed records allowed in `ActionQueue::actions`
+pub const VIEW_VERSION: u8 = 1; // Version of `MarketView` and `SellingResourceView` return data
+pub const PYTH_MAGIC: u32 = 0xa1b2c3d4;
+pub const PYTH_PRICE_ACCOUNT_TYPE: u32 = 3;
+pub const PYTH_STATUS_TRADING: u32 = 1;
+pub const PYTH_PRICE_ACCOUNT_LEN: usize = 240; // Up to the end of the aggregate price info
+pub const MINT_DECIMALS_OFFSET: usize = 44; // Offset of `decimals` in a packed `spl_token::state::Mint`
 
 /// Runtime derivation check
 pub fn assert_derivation(program_id: &Pubkey, account: &AccountInfo, path: &[&[u8]]) -> Result<u8> {
@@ -44,16 +80,56 @@ pub fn find_treasury_owner_address(
 
 /// Return `vault_owner` `Pubkey` and bump seed.
 pub fn find_vault_owner_address(resource_mint: &Pubkey, store: &Pubkey) -> (Pubkey, u8) {
+    find_versioned_vault_owner_address(resource_mint, store, 0)
+}
+
+/// Return `vault_owner` `Pubkey` and bump seed for a store at `authority_version`.
+pub fn find_versioned_vault_owner_address(
+    resource_mint: &Pubkey,
+    store: &Pubkey,
+    authority_version: u8,
+) -> (Pubkey, u8) {
     Pubkey::find_program_address(
         &[
             VAULT_OWNER_PREFIX.as_bytes(),
             resource_mint.as_ref(),
             store.as_ref(),
+            vault_owner_version_seed(&authority_version),
+        ],
+        &id(),
+    )
+}
+
+/// Version seed of `vault_owner`, empty before the first admin rotation so earlier addresses stay the same.
+pub fn vault_owner_version_seed(authority_version: &u8) -> &[u8] {
+    if *authority_version == 0 {
+        &[]
+    } else {
+        std::slice::from_ref(authority_version)
+    }
+}
+
+/// Return new edition mint `Pubkey` and bump seed.
+pub fn find_new_mint_address(market: &Pubkey, trade_history: &Pubkey, index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            NEW_MINT_PREFIX.as_bytes(),
+            market.as_ref(),
+            trade_history.as_ref(),
+            &index.to_le_bytes(),
         ],
         &id(),
     )
 }
 
+/// Return `ProvenanceRecord` `Pubkey` and bump seed.
+pub fn find_provenance_record_address(edition_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PROVENANCE_RECORD_PREFIX.as_bytes(), edition_mint.as_ref()],
+        &id(),
+    )
+}
+
 /// Return `TradeHistory` `Pubkey` and bump seed.
 pub fn find_trade_history_address(wallet: &Pubkey, market: &Pubkey) -> (Pubkey, u8) {
     Pubkey::find_program_address(
@@ -74,6 +150,29 @@ pub fn find_payout_ticket_address(market: &Pubkey, funder: &Pubkey) -> (Pubkey,
     )
 }
 
+/// Return claim tally `Pubkey` and bump seed.
+pub fn find_claim_tally_address(market: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CLAIM_TALLY_PREFIX.as_bytes(), market.as_ref()], &id())
+}
+
+/// Return sponsor vault `Pubkey` and bump seed.
+pub fn find_sponsor_vault_address(market: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SPONSOR_VAULT_PREFIX.as_bytes(), market.as_ref()], &id())
+}
+
+/// Return `Market` `Pubkey` and bump seed of a market created by `create_market_pda`.
+pub fn find_market_address(store: &Pubkey, selling_resource: &Pubkey, index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            MARKET_PREFIX.as_bytes(),
+            store.as_ref(),
+            selling_resource.as_ref(),
+            &index.to_le_bytes(),
+        ],
+        &id(),
+    )
+}
+
 /// Return `PrimaryMetadataCreators` `Pubkey` and bump seed.
 pub fn find_primary_metadata_creators(metadata: &Pubkey) -> (Pubkey, u8) {
     Pubkey::find_program_address(
@@ -85,6 +184,275 @@ pub fn find_primary_metadata_creators(metadata: &Pubkey) -> (Pubkey, u8) {
     )
 }
 
+/// Return hash of `Market` name used in `MarketNameRegistry` seeds, padding of stored names is ignored.
+pub fn market_name_hash(name: &str) -> [u8; 32] {
+    hash(name.trim_end_matches(char::from(0)).as_bytes()).to_bytes()
+}
+
+/// Return `MarketNameRegistry` `Pubkey` and bump seed.
+pub fn find_market_name_registry_address(store: &Pubkey, name: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            MARKET_NAME_PREFIX.as_bytes(),
+            store.as_ref(),
+            &market_name_hash(name),
+        ],
+        &id(),
+    )
+}
+
+/// Return `StorePaymentMints` `Pubkey` and bump seed.
+pub fn find_store_payment_mints_address(store: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[STORE_PAYMENT_MINTS_PREFIX.as_bytes(), store.as_ref()],
+        &id(),
+    )
+}
+
+/// Return `GlobalConfig` `Pubkey` and bump seed.
+pub fn find_global_config_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[GLOBAL_CONFIG_PREFIX.as_bytes()], &id())
+}
+
+/// Return `MarketManager` `Pubkey` and bump seed.
+pub fn find_market_manager_address(market: &Pubkey, manager: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            MARKET_MANAGER_PREFIX.as_bytes(),
+            market.as_ref(),
+            manager.as_ref(),
+        ],
+        &id(),
+    )
+}
+
+/// Return `ActionQueue` `Pubkey` and bump seed.
+pub fn find_action_queue_address(market: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ACTION_QUEUE_PREFIX.as_bytes(), market.as_ref()], &id())
+}
+
+/// Check, that metadata and master edition accounts are large enough to hold their `mpl_token_metadata` state,
+/// instead of failing with an opaque error once they are deserialized or passed to a CPI
+pub fn assert_metadata_accounts_len(
+    metadata: &AccountInfo,
+    master_edition: &AccountInfo,
+) -> Result<()> {
+    if metadata.data_len() < mpl_token_metadata::state::MAX_METADATA_LEN {
+        return Err(ErrorCode::MetadataAccountTooSmall.into());
+    }
+
+    if master_edition.data_len() < mpl_token_metadata::state::MAX_MASTER_EDITION_LEN {
+        return Err(ErrorCode::MasterEditionAccountTooSmall.into());
+    }
+
+    Ok(())
+}
+
+/// Check `authority` is the owner of `market` or, with its `MarketManager` passed, one of the market managers
+pub fn assert_market_authority(
+    market: &Account<Market>,
+    authority: &Pubkey,
+    market_manager: &AccountInfo,
+) -> Result<()> {
+    if market.owner == *authority {
+        return Ok(());
+    }
+
+    if market_manager.data_is_empty() || market_manager.owner != &id() {
+        return Err(ErrorCode::InvalidMarketManager.into());
+    }
+
+    let market_manager =
+        MarketManager::try_deserialize(&mut market_manager.data.borrow().as_ref())?;
+    if market_manager.market != market.key() || market_manager.manager != *authority {
+        return Err(ErrorCode::InvalidMarketManager.into());
+    }
+
+    Ok(())
+}
+
+/// Bump the store `sequence` and emit the `StoreActivity` of a state change under the store
+pub fn emit_store_activity(
+    store: &mut Account<Store>,
+    kind: StoreActivityKind,
+    subject: Pubkey,
+) -> Result<()> {
+    let sequence = store.next_sequence()?;
+    emit!(StoreActivity {
+        store: store.key(),
+        sequence,
+        kind,
+        subject,
+    });
+
+    Ok(())
+}
+
+/// Load the `GlobalConfig` of the program, `None` until the upgrade authority sets one
+pub fn load_global_config(global_config: &AccountInfo) -> Result<Option<GlobalConfig>> {
+    if global_config.data_is_empty() {
+        return Ok(None);
+    }
+
+    if global_config.owner != &id() {
+        return Err(ProgramError::IllegalOwner.into());
+    }
+
+    let global_config = GlobalConfig::try_deserialize(&mut global_config.data.borrow().as_ref())?;
+
+    Ok(Some(global_config))
+}
+
+/// Check `mint` is allowed by the `StorePaymentMints` of `store`, any mint is allowed until the admin sets one
+pub fn assert_payment_mint_allowed(
+    store_payment_mints: &AccountInfo,
+    store: &Store,
+    creator: &Pubkey,
+    mint: &Pubkey,
+) -> Result<()> {
+    if store_payment_mints.data_is_empty() {
+        return Ok(());
+    }
+
+    if store_payment_mints.owner != &id() {
+        return Err(ProgramError::IllegalOwner.into());
+    }
+
+    let store_payment_mints =
+        StorePaymentMints::try_deserialize(&mut store_payment_mints.data.borrow().as_ref())?;
+    if store_payment_mints.admin_override && *creator == store.admin {
+        return Ok(());
+    }
+
+    if !store_payment_mints.mints.contains(mint) {
+        return Err(ErrorCode::PaymentMintNotAllowed.into());
+    }
+
+    Ok(())
+}
+
+/// Create `MarketNameRegistry` reserving `name` within `store` for `market`
+pub fn create_market_name_registry<'a>(
+    registry: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    store: &Pubkey,
+    market: &Pubkey,
+    name: &str,
+) -> Result<()> {
+    let name_hash = market_name_hash(name);
+    let bump = assert_derivation(
+        &id(),
+        registry,
+        &[MARKET_NAME_PREFIX.as_bytes(), store.as_ref(), &name_hash],
+    )?;
+
+    if !registry.data_is_empty() {
+        return Err(ErrorCode::MarketNameTaken.into());
+    }
+
+    sys_create_pda_account(
+        payer,
+        registry,
+        Rent::get()?.minimum_balance(MarketNameRegistry::LEN),
+        MarketNameRegistry::LEN,
+        &id(),
+        &[
+            MARKET_NAME_PREFIX.as_bytes(),
+            store.as_ref(),
+            &name_hash,
+            &[bump],
+        ],
+    )?;
+
+    let mut data = registry.try_borrow_mut_data()?;
+    let mut writer: &mut [u8] = &mut data;
+    MarketNameRegistry {
+        store: *store,
+        market: *market,
+    }
+    .try_serialize(&mut writer)?;
+
+    Ok(())
+}
+
+/// Close `MarketNameRegistry` of `market`, releasing its name and returning rent to `destination`
+pub fn close_market_name_registry<'a>(
+    registry: &AccountInfo<'a>,
+    destination: &AccountInfo<'a>,
+    store: &Pubkey,
+    market: &Pubkey,
+    name: &str,
+) -> Result<()> {
+    assert_derivation(
+        &id(),
+        registry,
+        &[
+            MARKET_NAME_PREFIX.as_bytes(),
+            store.as_ref(),
+            &market_name_hash(name),
+        ],
+    )?;
+
+    // Markets created before names were registered have nothing to release
+    if registry.data_is_empty() {
+        return Ok(());
+    }
+
+    if registry.owner != &id() {
+        return Err(ErrorCode::InvalidMarketNameRegistry.into());
+    }
+
+    let registry_data = MarketNameRegistry::try_deserialize(&mut registry.data.borrow().as_ref())?;
+    if registry_data.market != *market {
+        return Err(ErrorCode::InvalidMarketNameRegistry.into());
+    }
+
+    **destination.lamports.borrow_mut() = destination
+        .lamports()
+        .checked_add(registry.lamports())
+        .ok_or(ErrorCode::MathOverflow)?;
+    **registry.lamports.borrow_mut() = 0;
+    registry.try_borrow_mut_data()?.fill(0);
+
+    Ok(())
+}
+
+/// Move `MarketNameRegistry` of `market` over to `new_market`, keeping the name reserved
+pub fn transfer_market_name_registry(
+    registry: &AccountInfo,
+    store: &Pubkey,
+    market: &Pubkey,
+    new_market: &Pubkey,
+    name: &str,
+) -> Result<()> {
+    assert_derivation(
+        &id(),
+        registry,
+        &[
+            MARKET_NAME_PREFIX.as_bytes(),
+            store.as_ref(),
+            &market_name_hash(name),
+        ],
+    )?;
+
+    if registry.owner != &id() {
+        return Err(ErrorCode::InvalidMarketNameRegistry.into());
+    }
+
+    let mut registry_data =
+        MarketNameRegistry::try_deserialize(&mut registry.data.borrow().as_ref())?;
+    if registry_data.market != *market {
+        return Err(ErrorCode::MarketNameTaken.into());
+    }
+
+    registry_data.market = *new_market;
+    let mut data = registry.try_borrow_mut_data()?;
+    let mut writer: &mut [u8] = &mut data;
+    registry_data.try_serialize(&mut writer)?;
+
+    Ok(())
+}
+
 /// Wrapper of `create_account` instruction from `system_program` program
 #[inline(always)]
 pub fn sys_create_account<'a>(
@@ -104,6 +472,39 @@ pub fn sys_create_account<'a>(
     Ok(())
 }
 
+/// Create a PDA account with `transfer`, `allocate` and `assign` instructions from `system_program` program,
+/// so lamports sent to the address beforehand can't block its creation
+pub fn sys_create_pda_account<'a>(
+    from: &AccountInfo<'a>,
+    to: &AccountInfo<'a>,
+    lamports: u64,
+    space: usize,
+    owner: &Pubkey,
+    signer_seeds: &[&[u8]],
+) -> Result<()> {
+    let required_lamports = lamports.saturating_sub(to.lamports());
+    if required_lamports > 0 {
+        invoke(
+            &system_instruction::transfer(from.key, to.key, required_lamports),
+            &[from.clone(), to.clone()],
+        )?;
+    }
+
+    invoke_signed(
+        &system_instruction::allocate(to.key, space as u64),
+        &[to.clone()],
+        &[&signer_seeds],
+    )?;
+
+    invoke_signed(
+        &system_instruction::assign(to.key, owner),
+        &[to.clone()],
+        &[&signer_seeds],
+    )?;
+
+    Ok(())
+}
+
 /// Wrapper of `transfer` instruction from `system_program` program
 #[inline(always)]
 pub fn sys_transfer<'a>(
@@ -235,6 +636,27 @@ pub fn mpl_update_metadata_accounts_v2<'a>(
     Ok(())
 }
 
+/// Return aggregate price and exponent of a Pyth v2 price account, which must be trading
+pub fn get_pyth_price(price_feed: &AccountInfo) -> Result<(i64, i32)> {
+    let data = price_feed.try_borrow_data()?;
+    if data.len() < PYTH_PRICE_ACCOUNT_LEN {
+        return Err(ErrorCode::InvalidPriceFeed.into());
+    }
+
+    let read_u32 = |offset: usize| u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap());
+    if read_u32(0) != PYTH_MAGIC
+        || read_u32(8) != PYTH_PRICE_ACCOUNT_TYPE
+        || read_u32(224) != PYTH_STATUS_TRADING
+    {
+        return Err(ErrorCode::InvalidPriceFeed.into());
+    }
+
+    let expo = i32::from_le_bytes(data[20..24].try_into().unwrap());
+    let price = i64::from_le_bytes(data[208..216].try_into().unwrap());
+
+    Ok((price, expo))
+}
+
 /// Add zeroes to the end of the String.
 /// This allows to have the size of allocated for this string memory fixed.
 pub fn puffed_out_string(s: String, size: usize) -> String {
@@ -288,3 +710,36 @@ pub fn calculate_secondary_shares_for_market_owner(
         )
         .ok_or(ErrorCode::MathOverflow)?)
 }
+
+/// Part of `total_amount` left over once every creator and the market owner got their floored share
+pub fn calculate_dust(
+    total_amount: u64,
+    seller_fee_basis_points: u64,
+    primary_sale_happened: bool,
+    creators: &[Creator],
+) -> Result<u64> {
+    let mut shares_total = if primary_sale_happened {
+        calculate_secondary_shares_for_market_owner(total_amount, seller_fee_basis_points)?
+    } else {
+        0
+    };
+
+    for creator in creators {
+        let creator_share = if primary_sale_happened {
+            calculate_secondary_shares_for_creator(
+                total_amount,
+                seller_fee_basis_points,
+                creator.share as u64,
+            )?
+        } else {
+            calculate_primary_shares_for_creator(total_amount, creator.share as u64)?
+        };
+        shares_total = shares_total
+            .checked_add(creator_share)
+            .ok_or(ErrorCode::MathOverflow)?;
+    }
+
+    Ok(total_amount
+        .checked_sub(shares_total)
+        .ok_or(ErrorCode::MathOverflow)?)
+}
@@ -9,7 +9,10 @@ mod buy {
                 airdrop, create_collection, create_master_nft, create_mint, create_token_account,
                 mint_to,
             },
-            setup_functions::{setup_selling_resource, setup_store},
+            setup_functions::{
+                setup_market, setup_selling_resource, setup_selling_resource_with_owner,
+                setup_store,
+            },
         },
     };
     use anchor_lang::{
@@ -19,8 +22,14 @@ mod buy {
         accounts as mpl_fixed_price_sale_accounts,
         error::ErrorCode,
         instruction as mpl_fixed_price_sale_instruction,
-        state::{GatingConfig, SellingResource, TradeHistory},
+        state::{
+            GatingConfig, Market, MarketState, ProcessorFee, ProvenanceRecord, SellingResource,
+            SellingResourceState, SellingResourceType, Store, TradeHistory, TreasuryVolume,
+        },
         utils::{
+            find_global_config_address, find_market_manager_address,
+            find_market_name_registry_address, find_new_mint_address,
+            find_provenance_record_address, find_store_payment_mints_address,
             find_trade_history_address, find_treasury_owner_address, find_vault_owner_address,
         },
     };
@@ -109,6 +118,10 @@ mod buy {
             mint: treasury_mint_keypair.pubkey(),
             treasury_holder: treasury_holder_keypair.pubkey(),
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -123,6 +136,10 @@ mod buy {
             start_date: start_date as u64,
             end_date: None,
             gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -269,6 +286,7 @@ mod buy {
             new_mint: new_mint_keypair.pubkey(),
             edition_marker,
             vault: selling_resource.vault,
+            store: selling_resource.store,
             owner,
             new_token_account: new_mint_token_account.pubkey(),
             master_edition_metadata,
@@ -277,12 +295,15 @@ mod buy {
             token_metadata_program: mpl_token_metadata::id(),
             token_program: spl_token::id(),
             system_program: system_program::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            provenance_record: find_provenance_record_address(&new_mint_keypair.pubkey()).0,
         }
         .to_account_metas(None);
 
         let data = mpl_fixed_price_sale_instruction::Buy {
             _trade_history_bump: trade_history_bump,
             vault_owner_bump,
+            client_nonce: None,
         }
         .data();
 
@@ -325,22 +346,61 @@ mod buy {
 
         assert_eq!(selling_resource_data.supply, 1);
         assert_eq!(trade_history_data.already_bought, 1);
+
+        let store_acc = context
+            .banks_client
+            .get_account(store_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let store_data = Store::try_deserialize(&mut store_acc.data.as_ref()).unwrap();
+
+        assert_eq!(store_data.total_markets, 1);
+        assert_eq!(store_data.total_editions_sold, 1);
+        assert_eq!(
+            store_data.volume,
+            vec![TreasuryVolume {
+                treasury_mint: treasury_mint_keypair.pubkey(),
+                amount: price,
+            }]
+        );
+
+        let provenance_record_acc = context
+            .banks_client
+            .get_account(find_provenance_record_address(&new_mint_keypair.pubkey()).0)
+            .await
+            .unwrap()
+            .unwrap();
+        let provenance_record_data =
+            ProvenanceRecord::try_deserialize(&mut provenance_record_acc.data.as_ref()).unwrap();
+
+        assert_eq!(provenance_record_data.market, market_keypair.pubkey());
+        assert_eq!(provenance_record_data.buyer, context.payer.pubkey());
+        assert_eq!(
+            provenance_record_data.edition_mint,
+            new_mint_keypair.pubkey()
+        );
+        assert_eq!(provenance_record_data.edition, 1);
+        assert_eq!(provenance_record_data.price, price);
     }
 
     #[tokio::test]
-    async fn success_native_sol() {
+    async fn success_master_edition_as_is() {
         setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
         let (admin_wallet, store_keypair) = setup_store(&mut context).await;
 
         let (selling_resource_keypair, selling_resource_owner_keypair, _vault) =
-            setup_selling_resource(
+            setup_selling_resource_with_owner(
                 &mut context,
                 &admin_wallet,
                 &store_keypair,
+                Keypair::new(),
                 100,
                 None,
                 true,
                 false,
+                1,
+                SellingResourceType::MasterEditionAsIs,
             )
             .await;
 
@@ -353,10 +413,28 @@ mod buy {
 
         let market_keypair = Keypair::new();
 
-        let treasury_mint = anchor_lang::solana_program::system_program::id();
+        let treasury_mint_keypair = Keypair::new();
+        create_mint(
+            &mut context,
+            &treasury_mint_keypair,
+            &admin_wallet.pubkey(),
+            0,
+        )
+        .await;
 
-        let (treasury_owner, treasyry_owner_bump) =
-            find_treasury_owner_address(&treasury_mint, &selling_resource_keypair.pubkey());
+        let (treasury_owner, treasyry_owner_bump) = find_treasury_owner_address(
+            &treasury_mint_keypair.pubkey(),
+            &selling_resource_keypair.pubkey(),
+        );
+
+        let treasury_holder_keypair = Keypair::new();
+        create_token_account(
+            &mut context,
+            &treasury_holder_keypair,
+            &treasury_mint_keypair.pubkey(),
+            &treasury_owner,
+        )
+        .await;
 
         let start_date = context
             .banks_client
@@ -367,10 +445,7 @@ mod buy {
             + 1;
 
         let name = "Marktname".to_string();
-        let description = "Marktbeschreibung".to_string();
-        let mutable = true;
         let price = 1_000_000;
-        let pieces_in_one_wallet = Some(1);
 
         // CreateMarket
         let accounts = mpl_fixed_price_sale_accounts::CreateMarket {
@@ -378,9 +453,13 @@ mod buy {
             store: store_keypair.pubkey(),
             selling_resource_owner: selling_resource_owner_keypair.pubkey(),
             selling_resource: selling_resource_keypair.pubkey(),
-            mint: treasury_mint,
-            treasury_holder: treasury_owner,
+            mint: treasury_mint_keypair.pubkey(),
+            treasury_holder: treasury_holder_keypair.pubkey(),
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -388,13 +467,17 @@ mod buy {
         let data = mpl_fixed_price_sale_instruction::CreateMarket {
             _treasury_owner_bump: treasyry_owner_bump,
             name: name.to_owned(),
-            description: description.to_owned(),
-            mutable,
+            description: "Marktbeschreibung".to_string(),
+            mutable: true,
             price,
-            pieces_in_one_wallet,
+            pieces_in_one_wallet: None,
             start_date: start_date as u64,
             end_date: None,
             gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -431,35 +514,38 @@ mod buy {
         let selling_resource =
             SellingResource::try_deserialize(&mut selling_resource_data.as_ref()).unwrap();
 
-        let user_wallet = Keypair::new();
-        airdrop(&mut context, &user_wallet.pubkey(), 1_000_000_000).await;
-
         let (trade_history, trade_history_bump) =
-            find_trade_history_address(&user_wallet.pubkey(), &market_keypair.pubkey());
+            find_trade_history_address(&context.payer.pubkey(), &market_keypair.pubkey());
         let (owner, vault_owner_bump) =
             find_vault_owner_address(&selling_resource.resource, &selling_resource.store);
 
-        let payer_pubkey = user_wallet.pubkey();
-
-        let new_mint_keypair = Keypair::new();
-        create_mint(&mut context, &new_mint_keypair, &payer_pubkey, 0).await;
+        let payer_pubkey = context.payer.pubkey();
 
-        let new_mint_token_account = Keypair::new();
+        let user_token_account = Keypair::new();
         create_token_account(
             &mut context,
-            &new_mint_token_account,
-            &new_mint_keypair.pubkey(),
+            &user_token_account,
+            &treasury_mint_keypair.pubkey(),
             &payer_pubkey,
         )
         .await;
 
-        let _payer_keypair = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
         mint_to(
             &mut context,
-            &new_mint_keypair.pubkey(),
-            &new_mint_token_account.pubkey(),
-            &user_wallet,
-            1,
+            &treasury_mint_keypair.pubkey(),
+            &user_token_account.pubkey(),
+            &admin_wallet,
+            price,
+        )
+        .await;
+
+        // Master edition token goes to the buyer token account of the resource mint
+        let resource_token_account = Keypair::new();
+        create_token_account(
+            &mut context,
+            &resource_token_account,
+            &selling_resource.resource,
+            &payer_pubkey,
         )
         .await;
 
@@ -482,64 +568,38 @@ mod buy {
             &mpl_token_metadata::id(),
         );
 
-        let (edition_marker, _) = Pubkey::find_program_address(
-            &[
-                mpl_token_metadata::state::PREFIX.as_bytes(),
-                mpl_token_metadata::id().as_ref(),
-                selling_resource.resource.as_ref(),
-                mpl_token_metadata::state::EDITION.as_bytes(),
-                selling_resource.supply.to_string().as_bytes(),
-            ],
-            &mpl_token_metadata::id(),
-        );
-
-        let (new_metadata, _) = Pubkey::find_program_address(
-            &[
-                mpl_token_metadata::state::PREFIX.as_bytes(),
-                mpl_token_metadata::id().as_ref(),
-                new_mint_keypair.pubkey().as_ref(),
-            ],
-            &mpl_token_metadata::id(),
-        );
-
-        let (new_edition, _) = Pubkey::find_program_address(
-            &[
-                mpl_token_metadata::state::PREFIX.as_bytes(),
-                mpl_token_metadata::id().as_ref(),
-                new_mint_keypair.pubkey().as_ref(),
-                mpl_token_metadata::state::EDITION.as_bytes(),
-            ],
-            &mpl_token_metadata::id(),
-        );
-
         // Buy
         let accounts = mpl_fixed_price_sale_accounts::Buy {
             market: market_keypair.pubkey(),
             selling_resource: selling_resource_keypair.pubkey(),
-            user_token_account: user_wallet.pubkey(),
-            user_wallet: user_wallet.pubkey(),
+            user_token_account: user_token_account.pubkey(),
+            user_wallet: context.payer.pubkey(),
             trade_history,
-            treasury_holder: treasury_owner,
-            new_metadata,
-            new_edition,
+            treasury_holder: treasury_holder_keypair.pubkey(),
+            new_metadata: master_edition_metadata,
+            new_edition: master_edition,
             master_edition,
-            new_mint: new_mint_keypair.pubkey(),
-            edition_marker,
+            new_mint: selling_resource.resource,
+            edition_marker: master_edition,
             vault: selling_resource.vault,
+            store: selling_resource.store,
             owner,
-            new_token_account: new_mint_token_account.pubkey(),
+            new_token_account: resource_token_account.pubkey(),
             master_edition_metadata,
             clock: sysvar::clock::id(),
             rent: sysvar::rent::id(),
             token_metadata_program: mpl_token_metadata::id(),
             token_program: spl_token::id(),
             system_program: system_program::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            provenance_record: find_provenance_record_address(&selling_resource.resource).0,
         }
         .to_account_metas(None);
 
         let data = mpl_fixed_price_sale_instruction::Buy {
             _trade_history_bump: trade_history_bump,
             vault_owner_bump,
+            client_nonce: None,
         }
         .data();
 
@@ -552,16 +612,23 @@ mod buy {
         let tx = Transaction::new_signed_with_payer(
             &[instruction],
             Some(&context.payer.pubkey()),
-            &[&context.payer, &user_wallet],
+            &[&context.payer],
             context.last_blockhash,
         );
 
         context.banks_client.process_transaction(tx).await.unwrap();
 
-        let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
-        context.warp_to_slot(clock.slot + 3).unwrap();
-
         // Checks
+        let resource_token_acc = context
+            .banks_client
+            .get_account(resource_token_account.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let resource_token_data =
+            spl_token::state::Account::unpack(&resource_token_acc.data).unwrap();
+        assert_eq!(resource_token_data.amount, 1);
+
         let selling_resource_acc = context
             .banks_client
             .get_account(selling_resource_keypair.pubkey())
@@ -570,22 +637,35 @@ mod buy {
             .unwrap();
         let selling_resource_data =
             SellingResource::try_deserialize(&mut selling_resource_acc.data.as_ref()).unwrap();
+        assert_eq!(selling_resource_data.supply, 1);
+        assert_eq!(selling_resource_data.state, SellingResourceState::Exhausted);
 
-        let trade_history_acc = context
+        let market_acc = context
             .banks_client
-            .get_account(trade_history)
+            .get_account(market_keypair.pubkey())
             .await
             .unwrap()
             .unwrap();
-        let trade_history_data =
-            TradeHistory::try_deserialize(&mut trade_history_acc.data.as_ref()).unwrap();
+        let market_data = Market::try_deserialize(&mut market_acc.data.as_ref()).unwrap();
+        assert_eq!(market_data.state, MarketState::Ended);
 
-        assert_eq!(selling_resource_data.supply, 1);
-        assert_eq!(trade_history_data.already_bought, 1);
+        let provenance_record_acc = context
+            .banks_client
+            .get_account(find_provenance_record_address(&selling_resource.resource).0)
+            .await
+            .unwrap()
+            .unwrap();
+        let provenance_record_data =
+            ProvenanceRecord::try_deserialize(&mut provenance_record_acc.data.as_ref()).unwrap();
+        assert_eq!(
+            provenance_record_data.edition_mint,
+            selling_resource.resource
+        );
+        assert_eq!(provenance_record_data.edition, 0);
     }
 
     #[tokio::test]
-    async fn fail_market_is_not_started() {
+    async fn success_processor_fee() {
         setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
         let (admin_wallet, store_keypair) = setup_store(&mut context).await;
 
@@ -633,6 +713,16 @@ mod buy {
         )
         .await;
 
+        let processor_wallet = Keypair::new();
+        let processor_token_account = Keypair::new();
+        create_token_account(
+            &mut context,
+            &processor_token_account,
+            &treasury_mint_keypair.pubkey(),
+            &processor_wallet.pubkey(),
+        )
+        .await;
+
         let start_date = context
             .banks_client
             .get_sysvar::<Clock>()
@@ -656,6 +746,10 @@ mod buy {
             mint: treasury_mint_keypair.pubkey(),
             treasury_holder: treasury_holder_keypair.pubkey(),
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -670,6 +764,13 @@ mod buy {
             start_date: start_date as u64,
             end_date: None,
             gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: Some(ProcessorFee {
+                destination: processor_token_account.pubkey(),
+                basis_points: 250,
+            }),
+            sweep_basis_points: None,
         }
         .data();
 
@@ -692,6 +793,9 @@ mod buy {
 
         context.banks_client.process_transaction(tx).await.unwrap();
 
+        let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        context.warp_to_slot(clock.slot + 1500).unwrap();
+
         // Buy setup
         let selling_resource_data = context
             .banks_client
@@ -813,6 +917,7 @@ mod buy {
             new_mint: new_mint_keypair.pubkey(),
             edition_marker,
             vault: selling_resource.vault,
+            store: selling_resource.store,
             owner,
             new_token_account: new_mint_token_account.pubkey(),
             master_edition_metadata,
@@ -821,19 +926,26 @@ mod buy {
             token_metadata_program: mpl_token_metadata::id(),
             token_program: spl_token::id(),
             system_program: system_program::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            provenance_record: find_provenance_record_address(&new_mint_keypair.pubkey()).0,
         }
         .to_account_metas(None);
+        let missing_processor_accounts = accounts.clone();
+        let mut accounts = accounts;
+        accounts.push(AccountMeta::new(processor_token_account.pubkey(), false));
 
         let data = mpl_fixed_price_sale_instruction::Buy {
             _trade_history_bump: trade_history_bump,
             vault_owner_bump,
+            client_nonce: None,
         }
         .data();
 
+        // Buy without processor fee destination
         let instruction = Instruction {
             program_id: mpl_fixed_price_sale::id(),
-            data,
-            accounts,
+            data: data.clone(),
+            accounts: missing_processor_accounts,
         };
 
         let tx = Transaction::new_signed_with_payer(
@@ -850,14 +962,85 @@ mod buy {
             .unwrap_err();
 
         match err {
-            TransportError::Custom(_) => assert!(true),
-            TransportError::TransactionError(_) => assert!(true),
+            TransportError::TransactionError(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(err_code),
+            )) => {
+                assert_eq!(
+                    err_code,
+                    ERROR_CODE_OFFSET + ErrorCode::InvalidProcessorAccount as u32
+                );
+            }
             _ => assert!(false),
         }
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        context.warp_to_slot(clock.slot + 3).unwrap();
+
+        // Checks
+        let selling_resource_acc = context
+            .banks_client
+            .get_account(selling_resource_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let selling_resource_data =
+            SellingResource::try_deserialize(&mut selling_resource_acc.data.as_ref()).unwrap();
+
+        let trade_history_acc = context
+            .banks_client
+            .get_account(trade_history)
+            .await
+            .unwrap()
+            .unwrap();
+        let trade_history_data =
+            TradeHistory::try_deserialize(&mut trade_history_acc.data.as_ref()).unwrap();
+
+        let processor_fee = price * 250 / 10000;
+        assert_eq!(selling_resource_data.supply, 1);
+        assert_eq!(trade_history_data.already_bought, 1);
+        assert_eq!(trade_history_data.processor_fees_paid, processor_fee);
+
+        let processor_token_acc = context
+            .banks_client
+            .get_account(processor_token_account.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let processor_token_acc_data =
+            spl_token::state::Account::unpack(&processor_token_acc.data).unwrap();
+
+        assert_eq!(processor_token_acc_data.amount, processor_fee);
+
+        let treasury_holder_acc = context
+            .banks_client
+            .get_account(treasury_holder_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let treasury_holder_acc_data =
+            spl_token::state::Account::unpack(&treasury_holder_acc.data).unwrap();
+
+        assert_eq!(treasury_holder_acc_data.amount, price - processor_fee);
     }
 
     #[tokio::test]
-    async fn fail_market_is_ended() {
+    async fn success_pda_mints() {
         setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
         let (admin_wallet, store_keypair) = setup_store(&mut context).await;
 
@@ -913,8 +1096,6 @@ mod buy {
             .unix_timestamp
             + 1;
 
-        let end_date = start_date + 2;
-
         let name = "Marktname".to_string();
         let description = "Marktbeschreibung".to_string();
         let mutable = true;
@@ -930,6 +1111,10 @@ mod buy {
             mint: treasury_mint_keypair.pubkey(),
             treasury_holder: treasury_holder_keypair.pubkey(),
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -942,8 +1127,12 @@ mod buy {
             price,
             pieces_in_one_wallet,
             start_date: start_date as u64,
-            end_date: Some(end_date as u64),
+            end_date: None,
             gating_config: None,
+            metadata_overrides: None,
+            pda_mints: true,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -967,7 +1156,7 @@ mod buy {
         context.banks_client.process_transaction(tx).await.unwrap();
 
         let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
-        context.warp_to_slot(clock.slot + 3000).unwrap();
+        context.warp_to_slot(clock.slot + 1500).unwrap();
 
         // Buy setup
         let selling_resource_data = context
@@ -1005,27 +1194,10 @@ mod buy {
         )
         .await;
 
-        let new_mint_keypair = Keypair::new();
-        create_mint(&mut context, &new_mint_keypair, &payer_pubkey, 0).await;
-
-        let new_mint_token_account = Keypair::new();
-        create_token_account(
-            &mut context,
-            &new_mint_token_account,
-            &new_mint_keypair.pubkey(),
-            &payer_pubkey,
-        )
-        .await;
-
-        let payer_keypair = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
-        mint_to(
-            &mut context,
-            &new_mint_keypair.pubkey(),
-            &new_mint_token_account.pubkey(),
-            &payer_keypair,
-            1,
-        )
-        .await;
+        // New mint and its token account are created by program
+        let (new_mint, _) = find_new_mint_address(&market_keypair.pubkey(), &trade_history, 0);
+        let new_mint_token_account =
+            spl_associated_token_account::get_associated_token_address(&payer_pubkey, &new_mint);
 
         let (master_edition_metadata, _) = Pubkey::find_program_address(
             &[
@@ -1061,7 +1233,7 @@ mod buy {
             &[
                 mpl_token_metadata::state::PREFIX.as_bytes(),
                 mpl_token_metadata::id().as_ref(),
-                new_mint_keypair.pubkey().as_ref(),
+                new_mint.as_ref(),
             ],
             &mpl_token_metadata::id(),
         );
@@ -1070,7 +1242,7 @@ mod buy {
             &[
                 mpl_token_metadata::state::PREFIX.as_bytes(),
                 mpl_token_metadata::id().as_ref(),
-                new_mint_keypair.pubkey().as_ref(),
+                new_mint.as_ref(),
                 mpl_token_metadata::state::EDITION.as_bytes(),
             ],
             &mpl_token_metadata::id(),
@@ -1087,23 +1259,27 @@ mod buy {
             new_metadata,
             new_edition,
             master_edition,
-            new_mint: new_mint_keypair.pubkey(),
+            new_mint,
             edition_marker,
             vault: selling_resource.vault,
+            store: selling_resource.store,
             owner,
-            new_token_account: new_mint_token_account.pubkey(),
+            new_token_account: new_mint_token_account,
             master_edition_metadata,
             clock: sysvar::clock::id(),
             rent: sysvar::rent::id(),
             token_metadata_program: mpl_token_metadata::id(),
             token_program: spl_token::id(),
             system_program: system_program::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            provenance_record: find_provenance_record_address(&new_mint).0,
         }
         .to_account_metas(None);
 
         let data = mpl_fixed_price_sale_instruction::Buy {
             _trade_history_bump: trade_history_bump,
             vault_owner_bump,
+            client_nonce: None,
         }
         .data();
 
@@ -1120,11 +1296,1823 @@ mod buy {
             context.last_blockhash,
         );
 
-        let err = context
-            .banks_client
-            .process_transaction(tx)
-            .await
-            .unwrap_err();
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        context.warp_to_slot(clock.slot + 3).unwrap();
+
+        // Checks
+        let selling_resource_acc = context
+            .banks_client
+            .get_account(selling_resource_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let selling_resource_data =
+            SellingResource::try_deserialize(&mut selling_resource_acc.data.as_ref()).unwrap();
+
+        let trade_history_acc = context
+            .banks_client
+            .get_account(trade_history)
+            .await
+            .unwrap()
+            .unwrap();
+        let trade_history_data =
+            TradeHistory::try_deserialize(&mut trade_history_acc.data.as_ref()).unwrap();
+
+        assert_eq!(selling_resource_data.supply, 1);
+        assert_eq!(trade_history_data.already_bought, 1);
+
+        let new_mint_acc = context
+            .banks_client
+            .get_account(new_mint)
+            .await
+            .unwrap()
+            .unwrap();
+        let new_mint_data = spl_token::state::Mint::unpack(&new_mint_acc.data).unwrap();
+
+        // Mint authority is moved to the edition by `mpl_token_metadata`
+        assert_eq!(new_mint_data.supply, 1);
+        assert_eq!(new_mint_data.mint_authority, Some(new_edition).into());
+    }
+
+    #[tokio::test]
+    async fn success_native_sol() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _vault) =
+            setup_selling_resource(
+                &mut context,
+                &admin_wallet,
+                &store_keypair,
+                100,
+                None,
+                true,
+                false,
+            )
+            .await;
+
+        airdrop(
+            &mut context,
+            &selling_resource_owner_keypair.pubkey(),
+            10_000_000_000,
+        )
+        .await;
+
+        let market_keypair = Keypair::new();
+
+        let treasury_mint = anchor_lang::solana_program::system_program::id();
+
+        let (treasury_owner, treasyry_owner_bump) =
+            find_treasury_owner_address(&treasury_mint, &selling_resource_keypair.pubkey());
+
+        let start_date = context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+            .unix_timestamp
+            + 1;
+
+        let name = "Marktname".to_string();
+        let description = "Marktbeschreibung".to_string();
+        let mutable = true;
+        let price = 1_000_000;
+        let pieces_in_one_wallet = Some(1);
+
+        // CreateMarket
+        let accounts = mpl_fixed_price_sale_accounts::CreateMarket {
+            market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
+            selling_resource_owner: selling_resource_owner_keypair.pubkey(),
+            selling_resource: selling_resource_keypair.pubkey(),
+            mint: treasury_mint,
+            treasury_holder: treasury_owner,
+            owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::CreateMarket {
+            _treasury_owner_bump: treasyry_owner_bump,
+            name: name.to_owned(),
+            description: description.to_owned(),
+            mutable,
+            price,
+            pieces_in_one_wallet,
+            start_date: start_date as u64,
+            end_date: None,
+            gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[
+                &context.payer,
+                &market_keypair,
+                &selling_resource_owner_keypair,
+            ],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        context.warp_to_slot(clock.slot + 1500).unwrap();
+
+        // Buy setup
+        let selling_resource_data = context
+            .banks_client
+            .get_account(selling_resource_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap()
+            .data;
+        let selling_resource =
+            SellingResource::try_deserialize(&mut selling_resource_data.as_ref()).unwrap();
+
+        let user_wallet = Keypair::new();
+        airdrop(&mut context, &user_wallet.pubkey(), 1_000_000_000).await;
+
+        let (trade_history, trade_history_bump) =
+            find_trade_history_address(&user_wallet.pubkey(), &market_keypair.pubkey());
+        let (owner, vault_owner_bump) =
+            find_vault_owner_address(&selling_resource.resource, &selling_resource.store);
+
+        let payer_pubkey = user_wallet.pubkey();
+
+        let new_mint_keypair = Keypair::new();
+        create_mint(&mut context, &new_mint_keypair, &payer_pubkey, 0).await;
+
+        let new_mint_token_account = Keypair::new();
+        create_token_account(
+            &mut context,
+            &new_mint_token_account,
+            &new_mint_keypair.pubkey(),
+            &payer_pubkey,
+        )
+        .await;
+
+        let _payer_keypair = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+        mint_to(
+            &mut context,
+            &new_mint_keypair.pubkey(),
+            &new_mint_token_account.pubkey(),
+            &user_wallet,
+            1,
+        )
+        .await;
+
+        let (master_edition_metadata, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                selling_resource.resource.as_ref(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+
+        let (master_edition, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                selling_resource.resource.as_ref(),
+                mpl_token_metadata::state::EDITION.as_bytes(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+
+        let (edition_marker, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                selling_resource.resource.as_ref(),
+                mpl_token_metadata::state::EDITION.as_bytes(),
+                selling_resource.supply.to_string().as_bytes(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+
+        let (new_metadata, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                new_mint_keypair.pubkey().as_ref(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+
+        let (new_edition, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                new_mint_keypair.pubkey().as_ref(),
+                mpl_token_metadata::state::EDITION.as_bytes(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+
+        // Buy
+        let accounts = mpl_fixed_price_sale_accounts::Buy {
+            market: market_keypair.pubkey(),
+            selling_resource: selling_resource_keypair.pubkey(),
+            user_token_account: user_wallet.pubkey(),
+            user_wallet: user_wallet.pubkey(),
+            trade_history,
+            treasury_holder: treasury_owner,
+            new_metadata,
+            new_edition,
+            master_edition,
+            new_mint: new_mint_keypair.pubkey(),
+            edition_marker,
+            vault: selling_resource.vault,
+            store: selling_resource.store,
+            owner,
+            new_token_account: new_mint_token_account.pubkey(),
+            master_edition_metadata,
+            clock: sysvar::clock::id(),
+            rent: sysvar::rent::id(),
+            token_metadata_program: mpl_token_metadata::id(),
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            provenance_record: find_provenance_record_address(&new_mint_keypair.pubkey()).0,
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::Buy {
+            _trade_history_bump: trade_history_bump,
+            vault_owner_bump,
+            client_nonce: None,
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, &user_wallet],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        context.warp_to_slot(clock.slot + 3).unwrap();
+
+        // Checks
+        let selling_resource_acc = context
+            .banks_client
+            .get_account(selling_resource_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let selling_resource_data =
+            SellingResource::try_deserialize(&mut selling_resource_acc.data.as_ref()).unwrap();
+
+        let trade_history_acc = context
+            .banks_client
+            .get_account(trade_history)
+            .await
+            .unwrap()
+            .unwrap();
+        let trade_history_data =
+            TradeHistory::try_deserialize(&mut trade_history_acc.data.as_ref()).unwrap();
+
+        assert_eq!(selling_resource_data.supply, 1);
+        assert_eq!(trade_history_data.already_bought, 1);
+    }
+
+    #[tokio::test]
+    async fn success_wrapped_sol_sync() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _vault) =
+            setup_selling_resource(
+                &mut context,
+                &admin_wallet,
+                &store_keypair,
+                100,
+                None,
+                true,
+                false,
+            )
+            .await;
+
+        airdrop(
+            &mut context,
+            &selling_resource_owner_keypair.pubkey(),
+            10_000_000_000,
+        )
+        .await;
+
+        let market_keypair = Keypair::new();
+
+        let treasury_mint = spl_token::native_mint::id();
+
+        let (treasury_owner, treasyry_owner_bump) =
+            find_treasury_owner_address(&treasury_mint, &selling_resource_keypair.pubkey());
+
+        let treasury_holder_keypair = Keypair::new();
+        create_token_account(
+            &mut context,
+            &treasury_holder_keypair,
+            &treasury_mint,
+            &treasury_owner,
+        )
+        .await;
+
+        let start_date = context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+            .unix_timestamp
+            + 1;
+
+        let name = "Marktname".to_string();
+        let description = "Marktbeschreibung".to_string();
+        let mutable = true;
+        let price = 1_000_000;
+        let pieces_in_one_wallet = Some(1);
+
+        // CreateMarket
+        let accounts = mpl_fixed_price_sale_accounts::CreateMarket {
+            market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
+            selling_resource_owner: selling_resource_owner_keypair.pubkey(),
+            selling_resource: selling_resource_keypair.pubkey(),
+            mint: treasury_mint,
+            treasury_holder: treasury_holder_keypair.pubkey(),
+            owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::CreateMarket {
+            _treasury_owner_bump: treasyry_owner_bump,
+            name: name.to_owned(),
+            description: description.to_owned(),
+            mutable,
+            price,
+            pieces_in_one_wallet,
+            start_date: start_date as u64,
+            end_date: None,
+            gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[
+                &context.payer,
+                &market_keypair,
+                &selling_resource_owner_keypair,
+            ],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        context.warp_to_slot(clock.slot + 1500).unwrap();
+
+        // Buy setup
+        let selling_resource_data = context
+            .banks_client
+            .get_account(selling_resource_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap()
+            .data;
+        let selling_resource =
+            SellingResource::try_deserialize(&mut selling_resource_data.as_ref()).unwrap();
+
+        let user_wallet = Keypair::new();
+        airdrop(&mut context, &user_wallet.pubkey(), 1_000_000_000).await;
+
+        // Half of the price is sent to the wrapped SOL account without a sync, the rest is topped up
+        let user_token_account = Keypair::new();
+        create_token_account(
+            &mut context,
+            &user_token_account,
+            &treasury_mint,
+            &user_wallet.pubkey(),
+        )
+        .await;
+        airdrop(&mut context, &user_token_account.pubkey(), price / 2).await;
+
+        let (trade_history, trade_history_bump) =
+            find_trade_history_address(&user_wallet.pubkey(), &market_keypair.pubkey());
+        let (owner, vault_owner_bump) =
+            find_vault_owner_address(&selling_resource.resource, &selling_resource.store);
+
+        let payer_pubkey = user_wallet.pubkey();
+
+        let new_mint_keypair = Keypair::new();
+        create_mint(&mut context, &new_mint_keypair, &payer_pubkey, 0).await;
+
+        let new_mint_token_account = Keypair::new();
+        create_token_account(
+            &mut context,
+            &new_mint_token_account,
+            &new_mint_keypair.pubkey(),
+            &payer_pubkey,
+        )
+        .await;
+
+        let _payer_keypair = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+        mint_to(
+            &mut context,
+            &new_mint_keypair.pubkey(),
+            &new_mint_token_account.pubkey(),
+            &user_wallet,
+            1,
+        )
+        .await;
+
+        let (master_edition_metadata, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                selling_resource.resource.as_ref(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+
+        let (master_edition, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                selling_resource.resource.as_ref(),
+                mpl_token_metadata::state::EDITION.as_bytes(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+
+        let (edition_marker, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                selling_resource.resource.as_ref(),
+                mpl_token_metadata::state::EDITION.as_bytes(),
+                selling_resource.supply.to_string().as_bytes(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+
+        let (new_metadata, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                new_mint_keypair.pubkey().as_ref(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+
+        let (new_edition, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                new_mint_keypair.pubkey().as_ref(),
+                mpl_token_metadata::state::EDITION.as_bytes(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+
+        // Buy
+        let accounts = mpl_fixed_price_sale_accounts::Buy {
+            market: market_keypair.pubkey(),
+            selling_resource: selling_resource_keypair.pubkey(),
+            user_token_account: user_token_account.pubkey(),
+            user_wallet: user_wallet.pubkey(),
+            trade_history,
+            treasury_holder: treasury_holder_keypair.pubkey(),
+            new_metadata,
+            new_edition,
+            master_edition,
+            new_mint: new_mint_keypair.pubkey(),
+            edition_marker,
+            vault: selling_resource.vault,
+            store: selling_resource.store,
+            owner,
+            new_token_account: new_mint_token_account.pubkey(),
+            master_edition_metadata,
+            clock: sysvar::clock::id(),
+            rent: sysvar::rent::id(),
+            token_metadata_program: mpl_token_metadata::id(),
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            provenance_record: find_provenance_record_address(&new_mint_keypair.pubkey()).0,
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::Buy {
+            _trade_history_bump: trade_history_bump,
+            vault_owner_bump,
+            client_nonce: None,
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, &user_wallet],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        context.warp_to_slot(clock.slot + 3).unwrap();
+
+        // Checks
+        let selling_resource_acc = context
+            .banks_client
+            .get_account(selling_resource_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let selling_resource_data =
+            SellingResource::try_deserialize(&mut selling_resource_acc.data.as_ref()).unwrap();
+
+        let trade_history_acc = context
+            .banks_client
+            .get_account(trade_history)
+            .await
+            .unwrap()
+            .unwrap();
+        let trade_history_data =
+            TradeHistory::try_deserialize(&mut trade_history_acc.data.as_ref()).unwrap();
+
+        let user_token_account_acc = context
+            .banks_client
+            .get_account(user_token_account.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let user_token_account_data =
+            spl_token::state::Account::unpack(&user_token_account_acc.data).unwrap();
+
+        let treasury_holder_acc = context
+            .banks_client
+            .get_account(treasury_holder_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let treasury_holder_data =
+            spl_token::state::Account::unpack(&treasury_holder_acc.data).unwrap();
+
+        assert_eq!(selling_resource_data.supply, 1);
+        assert_eq!(trade_history_data.already_bought, 1);
+        assert_eq!(user_token_account_data.amount, 0);
+        assert_eq!(treasury_holder_data.amount, price);
+    }
+
+    #[tokio::test]
+    async fn fail_market_is_not_started() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _vault) =
+            setup_selling_resource(
+                &mut context,
+                &admin_wallet,
+                &store_keypair,
+                100,
+                None,
+                true,
+                false,
+            )
+            .await;
+
+        airdrop(
+            &mut context,
+            &selling_resource_owner_keypair.pubkey(),
+            10_000_000_000,
+        )
+        .await;
+
+        let market_keypair = Keypair::new();
+
+        let treasury_mint_keypair = Keypair::new();
+        create_mint(
+            &mut context,
+            &treasury_mint_keypair,
+            &admin_wallet.pubkey(),
+            0,
+        )
+        .await;
+
+        let (treasury_owner, treasyry_owner_bump) = find_treasury_owner_address(
+            &treasury_mint_keypair.pubkey(),
+            &selling_resource_keypair.pubkey(),
+        );
+
+        let treasury_holder_keypair = Keypair::new();
+        create_token_account(
+            &mut context,
+            &treasury_holder_keypair,
+            &treasury_mint_keypair.pubkey(),
+            &treasury_owner,
+        )
+        .await;
+
+        let start_date = context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+            .unix_timestamp
+            + 1;
+
+        let name = "Marktname".to_string();
+        let description = "Marktbeschreibung".to_string();
+        let mutable = true;
+        let price = 1_000_000;
+        let pieces_in_one_wallet = Some(1);
+
+        // CreateMarket
+        let accounts = mpl_fixed_price_sale_accounts::CreateMarket {
+            market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
+            selling_resource_owner: selling_resource_owner_keypair.pubkey(),
+            selling_resource: selling_resource_keypair.pubkey(),
+            mint: treasury_mint_keypair.pubkey(),
+            treasury_holder: treasury_holder_keypair.pubkey(),
+            owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::CreateMarket {
+            _treasury_owner_bump: treasyry_owner_bump,
+            name: name.to_owned(),
+            description: description.to_owned(),
+            mutable,
+            price,
+            pieces_in_one_wallet,
+            start_date: start_date as u64,
+            end_date: None,
+            gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[
+                &context.payer,
+                &market_keypair,
+                &selling_resource_owner_keypair,
+            ],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        // Buy setup
+        let selling_resource_data = context
+            .banks_client
+            .get_account(selling_resource_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap()
+            .data;
+        let selling_resource =
+            SellingResource::try_deserialize(&mut selling_resource_data.as_ref()).unwrap();
+
+        let (trade_history, trade_history_bump) =
+            find_trade_history_address(&context.payer.pubkey(), &market_keypair.pubkey());
+        let (owner, vault_owner_bump) =
+            find_vault_owner_address(&selling_resource.resource, &selling_resource.store);
+
+        let payer_pubkey = context.payer.pubkey();
+
+        let user_token_account = Keypair::new();
+        create_token_account(
+            &mut context,
+            &user_token_account,
+            &treasury_mint_keypair.pubkey(),
+            &payer_pubkey,
+        )
+        .await;
+
+        mint_to(
+            &mut context,
+            &treasury_mint_keypair.pubkey(),
+            &user_token_account.pubkey(),
+            &admin_wallet,
+            1_000_000,
+        )
+        .await;
+
+        let new_mint_keypair = Keypair::new();
+        create_mint(&mut context, &new_mint_keypair, &payer_pubkey, 0).await;
+
+        let new_mint_token_account = Keypair::new();
+        create_token_account(
+            &mut context,
+            &new_mint_token_account,
+            &new_mint_keypair.pubkey(),
+            &payer_pubkey,
+        )
+        .await;
+
+        let payer_keypair = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+        mint_to(
+            &mut context,
+            &new_mint_keypair.pubkey(),
+            &new_mint_token_account.pubkey(),
+            &payer_keypair,
+            1,
+        )
+        .await;
+
+        let (master_edition_metadata, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                selling_resource.resource.as_ref(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+
+        let (master_edition, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                selling_resource.resource.as_ref(),
+                mpl_token_metadata::state::EDITION.as_bytes(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+
+        let (edition_marker, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                selling_resource.resource.as_ref(),
+                mpl_token_metadata::state::EDITION.as_bytes(),
+                selling_resource.supply.to_string().as_bytes(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+
+        let (new_metadata, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                new_mint_keypair.pubkey().as_ref(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+
+        let (new_edition, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                new_mint_keypair.pubkey().as_ref(),
+                mpl_token_metadata::state::EDITION.as_bytes(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+
+        // Buy
+        let accounts = mpl_fixed_price_sale_accounts::Buy {
+            market: market_keypair.pubkey(),
+            selling_resource: selling_resource_keypair.pubkey(),
+            user_token_account: user_token_account.pubkey(),
+            user_wallet: context.payer.pubkey(),
+            trade_history,
+            treasury_holder: treasury_holder_keypair.pubkey(),
+            new_metadata,
+            new_edition,
+            master_edition,
+            new_mint: new_mint_keypair.pubkey(),
+            edition_marker,
+            vault: selling_resource.vault,
+            store: selling_resource.store,
+            owner,
+            new_token_account: new_mint_token_account.pubkey(),
+            master_edition_metadata,
+            clock: sysvar::clock::id(),
+            rent: sysvar::rent::id(),
+            token_metadata_program: mpl_token_metadata::id(),
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            provenance_record: find_provenance_record_address(&new_mint_keypair.pubkey()).0,
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::Buy {
+            _trade_history_bump: trade_history_bump,
+            vault_owner_bump,
+            client_nonce: None,
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+
+        let err = context
+            .banks_client
+            .process_transaction(tx)
+            .await
+            .unwrap_err();
+
+        match err {
+            TransportError::Custom(_) => assert!(true),
+            TransportError::TransactionError(_) => assert!(true),
+            _ => assert!(false),
+        }
+    }
+
+    #[tokio::test]
+    async fn fail_market_is_ended() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _vault) =
+            setup_selling_resource(
+                &mut context,
+                &admin_wallet,
+                &store_keypair,
+                100,
+                None,
+                true,
+                false,
+            )
+            .await;
+
+        airdrop(
+            &mut context,
+            &selling_resource_owner_keypair.pubkey(),
+            10_000_000_000,
+        )
+        .await;
+
+        let market_keypair = Keypair::new();
+
+        let treasury_mint_keypair = Keypair::new();
+        create_mint(
+            &mut context,
+            &treasury_mint_keypair,
+            &admin_wallet.pubkey(),
+            0,
+        )
+        .await;
+
+        let (treasury_owner, treasyry_owner_bump) = find_treasury_owner_address(
+            &treasury_mint_keypair.pubkey(),
+            &selling_resource_keypair.pubkey(),
+        );
+
+        let treasury_holder_keypair = Keypair::new();
+        create_token_account(
+            &mut context,
+            &treasury_holder_keypair,
+            &treasury_mint_keypair.pubkey(),
+            &treasury_owner,
+        )
+        .await;
+
+        let start_date = context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+            .unix_timestamp
+            + 1;
+
+        let end_date = start_date + 2;
+
+        let name = "Marktname".to_string();
+        let description = "Marktbeschreibung".to_string();
+        let mutable = true;
+        let price = 1_000_000;
+        let pieces_in_one_wallet = Some(1);
+
+        // CreateMarket
+        let accounts = mpl_fixed_price_sale_accounts::CreateMarket {
+            market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
+            selling_resource_owner: selling_resource_owner_keypair.pubkey(),
+            selling_resource: selling_resource_keypair.pubkey(),
+            mint: treasury_mint_keypair.pubkey(),
+            treasury_holder: treasury_holder_keypair.pubkey(),
+            owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::CreateMarket {
+            _treasury_owner_bump: treasyry_owner_bump,
+            name: name.to_owned(),
+            description: description.to_owned(),
+            mutable,
+            price,
+            pieces_in_one_wallet,
+            start_date: start_date as u64,
+            end_date: Some(end_date as u64),
+            gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[
+                &context.payer,
+                &market_keypair,
+                &selling_resource_owner_keypair,
+            ],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        context.warp_to_slot(clock.slot + 3000).unwrap();
+
+        // Buy setup
+        let selling_resource_data = context
+            .banks_client
+            .get_account(selling_resource_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap()
+            .data;
+        let selling_resource =
+            SellingResource::try_deserialize(&mut selling_resource_data.as_ref()).unwrap();
+
+        let (trade_history, trade_history_bump) =
+            find_trade_history_address(&context.payer.pubkey(), &market_keypair.pubkey());
+        let (owner, vault_owner_bump) =
+            find_vault_owner_address(&selling_resource.resource, &selling_resource.store);
+
+        let payer_pubkey = context.payer.pubkey();
+
+        let user_token_account = Keypair::new();
+        create_token_account(
+            &mut context,
+            &user_token_account,
+            &treasury_mint_keypair.pubkey(),
+            &payer_pubkey,
+        )
+        .await;
+
+        mint_to(
+            &mut context,
+            &treasury_mint_keypair.pubkey(),
+            &user_token_account.pubkey(),
+            &admin_wallet,
+            1_000_000,
+        )
+        .await;
+
+        let new_mint_keypair = Keypair::new();
+        create_mint(&mut context, &new_mint_keypair, &payer_pubkey, 0).await;
+
+        let new_mint_token_account = Keypair::new();
+        create_token_account(
+            &mut context,
+            &new_mint_token_account,
+            &new_mint_keypair.pubkey(),
+            &payer_pubkey,
+        )
+        .await;
+
+        let payer_keypair = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+        mint_to(
+            &mut context,
+            &new_mint_keypair.pubkey(),
+            &new_mint_token_account.pubkey(),
+            &payer_keypair,
+            1,
+        )
+        .await;
+
+        let (master_edition_metadata, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                selling_resource.resource.as_ref(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+
+        let (master_edition, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                selling_resource.resource.as_ref(),
+                mpl_token_metadata::state::EDITION.as_bytes(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+
+        let (edition_marker, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                selling_resource.resource.as_ref(),
+                mpl_token_metadata::state::EDITION.as_bytes(),
+                selling_resource.supply.to_string().as_bytes(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+
+        let (new_metadata, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                new_mint_keypair.pubkey().as_ref(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+
+        let (new_edition, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                new_mint_keypair.pubkey().as_ref(),
+                mpl_token_metadata::state::EDITION.as_bytes(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+
+        // Buy
+        let accounts = mpl_fixed_price_sale_accounts::Buy {
+            market: market_keypair.pubkey(),
+            selling_resource: selling_resource_keypair.pubkey(),
+            user_token_account: user_token_account.pubkey(),
+            user_wallet: context.payer.pubkey(),
+            trade_history,
+            treasury_holder: treasury_holder_keypair.pubkey(),
+            new_metadata,
+            new_edition,
+            master_edition,
+            new_mint: new_mint_keypair.pubkey(),
+            edition_marker,
+            vault: selling_resource.vault,
+            store: selling_resource.store,
+            owner,
+            new_token_account: new_mint_token_account.pubkey(),
+            master_edition_metadata,
+            clock: sysvar::clock::id(),
+            rent: sysvar::rent::id(),
+            token_metadata_program: mpl_token_metadata::id(),
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            provenance_record: find_provenance_record_address(&new_mint_keypair.pubkey()).0,
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::Buy {
+            _trade_history_bump: trade_history_bump,
+            vault_owner_bump,
+            client_nonce: None,
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+
+        let err = context
+            .banks_client
+            .process_transaction(tx)
+            .await
+            .unwrap_err();
+
+        match err {
+            TransportError::Custom(_) => assert!(true),
+            TransportError::TransactionError(_) => assert!(true),
+            _ => assert!(false),
+        }
+    }
+
+    #[tokio::test]
+    async fn fail_market_is_ended_unlimited_duration() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _vault) =
+            setup_selling_resource(
+                &mut context,
+                &admin_wallet,
+                &store_keypair,
+                100,
+                None,
+                true,
+                false,
+            )
+            .await;
+
+        airdrop(
+            &mut context,
+            &selling_resource_owner_keypair.pubkey(),
+            10_000_000_000,
+        )
+        .await;
+
+        let market_keypair = Keypair::new();
+
+        let treasury_mint_keypair = Keypair::new();
+        create_mint(
+            &mut context,
+            &treasury_mint_keypair,
+            &admin_wallet.pubkey(),
+            0,
+        )
+        .await;
+
+        let (treasury_owner, treasyry_owner_bump) = find_treasury_owner_address(
+            &treasury_mint_keypair.pubkey(),
+            &selling_resource_keypair.pubkey(),
+        );
+
+        let treasury_holder_keypair = Keypair::new();
+        create_token_account(
+            &mut context,
+            &treasury_holder_keypair,
+            &treasury_mint_keypair.pubkey(),
+            &treasury_owner,
+        )
+        .await;
+
+        let start_date = context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+            .unix_timestamp
+            + 1;
+
+        let name = "Marktname".to_string();
+        let description = "Marktbeschreibung".to_string();
+        let mutable = true;
+        let price = 1_000_000;
+        let pieces_in_one_wallet = Some(1);
+
+        // CreateMarket
+        let accounts = mpl_fixed_price_sale_accounts::CreateMarket {
+            market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
+            selling_resource_owner: selling_resource_owner_keypair.pubkey(),
+            selling_resource: selling_resource_keypair.pubkey(),
+            mint: treasury_mint_keypair.pubkey(),
+            treasury_holder: treasury_holder_keypair.pubkey(),
+            owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::CreateMarket {
+            _treasury_owner_bump: treasyry_owner_bump,
+            name: name.to_owned(),
+            description: description.to_owned(),
+            mutable,
+            price,
+            pieces_in_one_wallet,
+            start_date: start_date as u64,
+            end_date: None,
+            gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[
+                &context.payer,
+                &market_keypair,
+                &selling_resource_owner_keypair,
+            ],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        context.warp_to_slot(clock.slot + 1500).unwrap();
+
+        // Buy setup
+        let selling_resource_data = context
+            .banks_client
+            .get_account(selling_resource_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap()
+            .data;
+        let selling_resource =
+            SellingResource::try_deserialize(&mut selling_resource_data.as_ref()).unwrap();
+
+        let (trade_history, trade_history_bump) =
+            find_trade_history_address(&context.payer.pubkey(), &market_keypair.pubkey());
+        let (owner, vault_owner_bump) =
+            find_vault_owner_address(&selling_resource.resource, &selling_resource.store);
+
+        let payer_pubkey = context.payer.pubkey();
+
+        let user_token_account = Keypair::new();
+        create_token_account(
+            &mut context,
+            &user_token_account,
+            &treasury_mint_keypair.pubkey(),
+            &payer_pubkey,
+        )
+        .await;
+
+        mint_to(
+            &mut context,
+            &treasury_mint_keypair.pubkey(),
+            &user_token_account.pubkey(),
+            &admin_wallet,
+            1_000_000,
+        )
+        .await;
+
+        let new_mint_keypair = Keypair::new();
+        create_mint(&mut context, &new_mint_keypair, &payer_pubkey, 0).await;
+
+        let new_mint_token_account = Keypair::new();
+        create_token_account(
+            &mut context,
+            &new_mint_token_account,
+            &new_mint_keypair.pubkey(),
+            &payer_pubkey,
+        )
+        .await;
+
+        let payer_keypair = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+        mint_to(
+            &mut context,
+            &new_mint_keypair.pubkey(),
+            &new_mint_token_account.pubkey(),
+            &payer_keypair,
+            1,
+        )
+        .await;
+
+        let (master_edition_metadata, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                selling_resource.resource.as_ref(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+
+        let (master_edition, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                selling_resource.resource.as_ref(),
+                mpl_token_metadata::state::EDITION.as_bytes(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+
+        let (edition_marker, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                selling_resource.resource.as_ref(),
+                mpl_token_metadata::state::EDITION.as_bytes(),
+                selling_resource.supply.to_string().as_bytes(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+
+        let (new_metadata, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                new_mint_keypair.pubkey().as_ref(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+
+        let (new_edition, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                new_mint_keypair.pubkey().as_ref(),
+                mpl_token_metadata::state::EDITION.as_bytes(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+
+        // CloseMarket
+        let mut accounts = mpl_fixed_price_sale_accounts::CloseMarket {
+            market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
+            owner: selling_resource_owner_keypair.pubkey(),
+            clock: sysvar::clock::id(),
+        }
+        .to_account_metas(None);
+        let (market_name_registry, _) =
+            find_market_name_registry_address(&store_keypair.pubkey(), &name);
+        accounts.push(AccountMeta::new(market_name_registry, false));
+
+        let data = mpl_fixed_price_sale_instruction::CloseMarket {}.data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, &selling_resource_owner_keypair],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        // Buy
+        let accounts = mpl_fixed_price_sale_accounts::Buy {
+            market: market_keypair.pubkey(),
+            selling_resource: selling_resource_keypair.pubkey(),
+            user_token_account: user_token_account.pubkey(),
+            user_wallet: context.payer.pubkey(),
+            trade_history,
+            treasury_holder: treasury_holder_keypair.pubkey(),
+            new_metadata,
+            new_edition,
+            master_edition,
+            new_mint: new_mint_keypair.pubkey(),
+            edition_marker,
+            vault: selling_resource.vault,
+            store: selling_resource.store,
+            owner,
+            new_token_account: new_mint_token_account.pubkey(),
+            master_edition_metadata,
+            clock: sysvar::clock::id(),
+            rent: sysvar::rent::id(),
+            token_metadata_program: mpl_token_metadata::id(),
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            provenance_record: find_provenance_record_address(&new_mint_keypair.pubkey()).0,
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::Buy {
+            _trade_history_bump: trade_history_bump,
+            vault_owner_bump,
+            client_nonce: None,
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+
+        let err = context
+            .banks_client
+            .process_transaction(tx)
+            .await
+            .unwrap_err();
+
+        match err {
+            TransportError::Custom(_) => assert!(true),
+            TransportError::TransactionError(_) => assert!(true),
+            _ => assert!(false),
+        }
+    }
+
+    #[tokio::test]
+    async fn fail_market_is_suspended() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _vault) =
+            setup_selling_resource(
+                &mut context,
+                &admin_wallet,
+                &store_keypair,
+                100,
+                None,
+                true,
+                false,
+            )
+            .await;
+
+        airdrop(
+            &mut context,
+            &selling_resource_owner_keypair.pubkey(),
+            10_000_000_000,
+        )
+        .await;
+
+        let market_keypair = Keypair::new();
+
+        let treasury_mint_keypair = Keypair::new();
+        create_mint(
+            &mut context,
+            &treasury_mint_keypair,
+            &admin_wallet.pubkey(),
+            0,
+        )
+        .await;
+
+        let (treasury_owner, treasyry_owner_bump) = find_treasury_owner_address(
+            &treasury_mint_keypair.pubkey(),
+            &selling_resource_keypair.pubkey(),
+        );
+
+        let treasury_holder_keypair = Keypair::new();
+        create_token_account(
+            &mut context,
+            &treasury_holder_keypair,
+            &treasury_mint_keypair.pubkey(),
+            &treasury_owner,
+        )
+        .await;
+
+        let start_date = context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+            .unix_timestamp
+            + 1;
+
+        let name = "Marktname".to_string();
+        let description = "Marktbeschreibung".to_string();
+        let mutable = true;
+        let price = 1_000_000;
+        let pieces_in_one_wallet = Some(1);
+
+        // CreateMarket
+        let accounts = mpl_fixed_price_sale_accounts::CreateMarket {
+            market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
+            selling_resource_owner: selling_resource_owner_keypair.pubkey(),
+            selling_resource: selling_resource_keypair.pubkey(),
+            mint: treasury_mint_keypair.pubkey(),
+            treasury_holder: treasury_holder_keypair.pubkey(),
+            owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::CreateMarket {
+            _treasury_owner_bump: treasyry_owner_bump,
+            name: name.to_owned(),
+            description: description.to_owned(),
+            mutable,
+            price,
+            pieces_in_one_wallet,
+            start_date: start_date as u64,
+            end_date: None,
+            gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[
+                &context.payer,
+                &market_keypair,
+                &selling_resource_owner_keypair,
+            ],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        context.warp_to_slot(clock.slot + 1500).unwrap();
+
+        // Buy setup
+        let selling_resource_data = context
+            .banks_client
+            .get_account(selling_resource_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap()
+            .data;
+        let selling_resource =
+            SellingResource::try_deserialize(&mut selling_resource_data.as_ref()).unwrap();
+
+        let (trade_history, trade_history_bump) =
+            find_trade_history_address(&context.payer.pubkey(), &market_keypair.pubkey());
+        let (owner, vault_owner_bump) =
+            find_vault_owner_address(&selling_resource.resource, &selling_resource.store);
+
+        let payer_pubkey = context.payer.pubkey();
+
+        let user_token_account = Keypair::new();
+        create_token_account(
+            &mut context,
+            &user_token_account,
+            &treasury_mint_keypair.pubkey(),
+            &payer_pubkey,
+        )
+        .await;
+
+        mint_to(
+            &mut context,
+            &treasury_mint_keypair.pubkey(),
+            &user_token_account.pubkey(),
+            &admin_wallet,
+            1_000_000,
+        )
+        .await;
+
+        let new_mint_keypair = Keypair::new();
+        create_mint(&mut context, &new_mint_keypair, &payer_pubkey, 0).await;
+
+        let new_mint_token_account = Keypair::new();
+        create_token_account(
+            &mut context,
+            &new_mint_token_account,
+            &new_mint_keypair.pubkey(),
+            &payer_pubkey,
+        )
+        .await;
+
+        let payer_keypair = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+        mint_to(
+            &mut context,
+            &new_mint_keypair.pubkey(),
+            &new_mint_token_account.pubkey(),
+            &payer_keypair,
+            1,
+        )
+        .await;
+
+        let (master_edition_metadata, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                selling_resource.resource.as_ref(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+
+        let (master_edition, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                selling_resource.resource.as_ref(),
+                mpl_token_metadata::state::EDITION.as_bytes(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+
+        let (edition_marker, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                selling_resource.resource.as_ref(),
+                mpl_token_metadata::state::EDITION.as_bytes(),
+                selling_resource.supply.to_string().as_bytes(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+
+        let (new_metadata, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                new_mint_keypair.pubkey().as_ref(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+
+        let (new_edition, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                new_mint_keypair.pubkey().as_ref(),
+                mpl_token_metadata::state::EDITION.as_bytes(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+
+        // SuspendMarket
+        let accounts = mpl_fixed_price_sale_accounts::SuspendMarket {
+            market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
+            owner: selling_resource_owner_keypair.pubkey(),
+            market_manager: find_market_manager_address(
+                &market_keypair.pubkey(),
+                &selling_resource_owner_keypair.pubkey(),
+            )
+            .0,
+            clock: sysvar::clock::id(),
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::SuspendMarket {}.data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, &selling_resource_owner_keypair],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        // Buy
+        let accounts = mpl_fixed_price_sale_accounts::Buy {
+            market: market_keypair.pubkey(),
+            selling_resource: selling_resource_keypair.pubkey(),
+            user_token_account: user_token_account.pubkey(),
+            user_wallet: context.payer.pubkey(),
+            trade_history,
+            treasury_holder: treasury_holder_keypair.pubkey(),
+            new_metadata,
+            new_edition,
+            master_edition,
+            new_mint: new_mint_keypair.pubkey(),
+            edition_marker,
+            vault: selling_resource.vault,
+            store: selling_resource.store,
+            owner,
+            new_token_account: new_mint_token_account.pubkey(),
+            master_edition_metadata,
+            clock: sysvar::clock::id(),
+            rent: sysvar::rent::id(),
+            token_metadata_program: mpl_token_metadata::id(),
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            provenance_record: find_provenance_record_address(&new_mint_keypair.pubkey()).0,
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::Buy {
+            _trade_history_bump: trade_history_bump,
+            vault_owner_bump,
+            client_nonce: None,
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+
+        let err = context
+            .banks_client
+            .process_transaction(tx)
+            .await
+            .unwrap_err();
 
         match err {
             TransportError::Custom(_) => assert!(true),
@@ -1134,7 +3122,7 @@ mod buy {
     }
 
     #[tokio::test]
-    async fn fail_market_is_ended_unlimited_duration() {
+    async fn fail_user_reach_buy_limit() {
         setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
         let (admin_wallet, store_keypair) = setup_store(&mut context).await;
 
@@ -1205,6 +3193,10 @@ mod buy {
             mint: treasury_mint_keypair.pubkey(),
             treasury_holder: treasury_holder_keypair.pubkey(),
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -1219,6 +3211,10 @@ mod buy {
             start_date: start_date as u64,
             end_date: None,
             gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -1345,37 +3341,12 @@ mod buy {
             &[
                 mpl_token_metadata::state::PREFIX.as_bytes(),
                 mpl_token_metadata::id().as_ref(),
-                new_mint_keypair.pubkey().as_ref(),
-                mpl_token_metadata::state::EDITION.as_bytes(),
-            ],
-            &mpl_token_metadata::id(),
-        );
-
-        // CloseMarket
-        let accounts = mpl_fixed_price_sale_accounts::CloseMarket {
-            market: market_keypair.pubkey(),
-            owner: selling_resource_owner_keypair.pubkey(),
-            clock: sysvar::clock::id(),
-        }
-        .to_account_metas(None);
-
-        let data = mpl_fixed_price_sale_instruction::CloseMarket {}.data();
-
-        let instruction = Instruction {
-            program_id: mpl_fixed_price_sale::id(),
-            data,
-            accounts,
-        };
-
-        let tx = Transaction::new_signed_with_payer(
-            &[instruction],
-            Some(&context.payer.pubkey()),
-            &[&context.payer, &selling_resource_owner_keypair],
-            context.last_blockhash,
+                new_mint_keypair.pubkey().as_ref(),
+                mpl_token_metadata::state::EDITION.as_bytes(),
+            ],
+            &mpl_token_metadata::id(),
         );
 
-        context.banks_client.process_transaction(tx).await.unwrap();
-
         // Buy
         let accounts = mpl_fixed_price_sale_accounts::Buy {
             market: market_keypair.pubkey(),
@@ -1390,6 +3361,7 @@ mod buy {
             new_mint: new_mint_keypair.pubkey(),
             edition_marker,
             vault: selling_resource.vault,
+            store: selling_resource.store,
             owner,
             new_token_account: new_mint_token_account.pubkey(),
             master_edition_metadata,
@@ -1398,12 +3370,15 @@ mod buy {
             token_metadata_program: mpl_token_metadata::id(),
             token_program: spl_token::id(),
             system_program: system_program::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            provenance_record: find_provenance_record_address(&new_mint_keypair.pubkey()).0,
         }
         .to_account_metas(None);
 
         let data = mpl_fixed_price_sale_instruction::Buy {
             _trade_history_bump: trade_history_bump,
             vault_owner_bump,
+            client_nonce: None,
         }
         .data();
 
@@ -1413,6 +3388,18 @@ mod buy {
             accounts,
         };
 
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction.clone()],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        context.warp_to_slot(clock.slot + 3).unwrap();
+
         let tx = Transaction::new_signed_with_payer(
             &[instruction],
             Some(&context.payer.pubkey()),
@@ -1434,7 +3421,7 @@ mod buy {
     }
 
     #[tokio::test]
-    async fn fail_market_is_suspended() {
+    async fn fail_duplicate_client_nonce() {
         setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
         let (admin_wallet, store_keypair) = setup_store(&mut context).await;
 
@@ -1494,7 +3481,7 @@ mod buy {
         let description = "Marktbeschreibung".to_string();
         let mutable = true;
         let price = 1_000_000;
-        let pieces_in_one_wallet = Some(1);
+        let pieces_in_one_wallet = None;
 
         // CreateMarket
         let accounts = mpl_fixed_price_sale_accounts::CreateMarket {
@@ -1505,6 +3492,10 @@ mod buy {
             mint: treasury_mint_keypair.pubkey(),
             treasury_holder: treasury_holder_keypair.pubkey(),
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -1519,6 +3510,10 @@ mod buy {
             start_date: start_date as u64,
             end_date: None,
             gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -1576,7 +3571,7 @@ mod buy {
             &treasury_mint_keypair.pubkey(),
             &user_token_account.pubkey(),
             &admin_wallet,
-            1_000_000,
+            2_000_000,
         )
         .await;
 
@@ -1651,15 +3646,40 @@ mod buy {
             &mpl_token_metadata::id(),
         );
 
-        // SuspendMarket
-        let accounts = mpl_fixed_price_sale_accounts::SuspendMarket {
+        // Buy
+        let accounts = mpl_fixed_price_sale_accounts::Buy {
             market: market_keypair.pubkey(),
-            owner: selling_resource_owner_keypair.pubkey(),
+            selling_resource: selling_resource_keypair.pubkey(),
+            user_token_account: user_token_account.pubkey(),
+            user_wallet: context.payer.pubkey(),
+            trade_history,
+            treasury_holder: treasury_holder_keypair.pubkey(),
+            new_metadata,
+            new_edition,
+            master_edition,
+            new_mint: new_mint_keypair.pubkey(),
+            edition_marker,
+            vault: selling_resource.vault,
+            store: selling_resource.store,
+            owner,
+            new_token_account: new_mint_token_account.pubkey(),
+            master_edition_metadata,
             clock: sysvar::clock::id(),
+            rent: sysvar::rent::id(),
+            token_metadata_program: mpl_token_metadata::id(),
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            provenance_record: find_provenance_record_address(&new_mint_keypair.pubkey()).0,
         }
         .to_account_metas(None);
 
-        let data = mpl_fixed_price_sale_instruction::SuspendMarket {}.data();
+        let data = mpl_fixed_price_sale_instruction::Buy {
+            _trade_history_bump: trade_history_bump,
+            vault_owner_bump,
+            client_nonce: Some(2),
+        }
+        .data();
 
         let instruction = Instruction {
             program_id: mpl_fixed_price_sale::id(),
@@ -1670,13 +3690,66 @@ mod buy {
         let tx = Transaction::new_signed_with_payer(
             &[instruction],
             Some(&context.payer.pubkey()),
-            &[&context.payer, &selling_resource_owner_keypair],
+            &[&context.payer],
             context.last_blockhash,
         );
 
         context.banks_client.process_transaction(tx).await.unwrap();
 
-        // Buy
+        let trade_history_acc = context
+            .banks_client
+            .get_account(trade_history)
+            .await
+            .unwrap()
+            .unwrap();
+        let trade_history_data =
+            TradeHistory::try_deserialize(&mut trade_history_acc.data.as_ref()).unwrap();
+        assert_eq!(trade_history_data.last_client_nonce, Some(2));
+
+        let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        context.warp_to_slot(clock.slot + 3).unwrap();
+
+        // Retry of an earlier purchase carries a lower nonce
+        let retry_mint_keypair = Keypair::new();
+        create_mint(&mut context, &retry_mint_keypair, &payer_pubkey, 0).await;
+
+        let retry_mint_token_account = Keypair::new();
+        create_token_account(
+            &mut context,
+            &retry_mint_token_account,
+            &retry_mint_keypair.pubkey(),
+            &payer_pubkey,
+        )
+        .await;
+
+        mint_to(
+            &mut context,
+            &retry_mint_keypair.pubkey(),
+            &retry_mint_token_account.pubkey(),
+            &payer_keypair,
+            1,
+        )
+        .await;
+
+        let (retry_metadata, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                retry_mint_keypair.pubkey().as_ref(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+
+        let (retry_edition, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                retry_mint_keypair.pubkey().as_ref(),
+                mpl_token_metadata::state::EDITION.as_bytes(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+
         let accounts = mpl_fixed_price_sale_accounts::Buy {
             market: market_keypair.pubkey(),
             selling_resource: selling_resource_keypair.pubkey(),
@@ -1684,26 +3757,30 @@ mod buy {
             user_wallet: context.payer.pubkey(),
             trade_history,
             treasury_holder: treasury_holder_keypair.pubkey(),
-            new_metadata,
-            new_edition,
+            new_metadata: retry_metadata,
+            new_edition: retry_edition,
             master_edition,
-            new_mint: new_mint_keypair.pubkey(),
+            new_mint: retry_mint_keypair.pubkey(),
             edition_marker,
             vault: selling_resource.vault,
+            store: selling_resource.store,
             owner,
-            new_token_account: new_mint_token_account.pubkey(),
+            new_token_account: retry_mint_token_account.pubkey(),
             master_edition_metadata,
             clock: sysvar::clock::id(),
             rent: sysvar::rent::id(),
             token_metadata_program: mpl_token_metadata::id(),
             token_program: spl_token::id(),
             system_program: system_program::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            provenance_record: find_provenance_record_address(&retry_mint_keypair.pubkey()).0,
         }
         .to_account_metas(None);
 
         let data = mpl_fixed_price_sale_instruction::Buy {
             _trade_history_bump: trade_history_bump,
             vault_owner_bump,
+            client_nonce: Some(1),
         }
         .data();
 
@@ -1727,14 +3804,21 @@ mod buy {
             .unwrap_err();
 
         match err {
-            TransportError::Custom(_) => assert!(true),
-            TransportError::TransactionError(_) => assert!(true),
+            TransportError::TransactionError(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(err_code),
+            )) => {
+                assert_eq!(
+                    err_code,
+                    ERROR_CODE_OFFSET + ErrorCode::DuplicatePurchaseNonce as u32
+                );
+            }
             _ => assert!(false),
         }
     }
 
     #[tokio::test]
-    async fn fail_user_reach_buy_limit() {
+    async fn fail_treasury_mismatch() {
         setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
         let (admin_wallet, store_keypair) = setup_store(&mut context).await;
 
@@ -1805,6 +3889,10 @@ mod buy {
             mint: treasury_mint_keypair.pubkey(),
             treasury_holder: treasury_holder_keypair.pubkey(),
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -1819,6 +3907,10 @@ mod buy {
             start_date: start_date as u64,
             end_date: None,
             gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -1951,6 +4043,16 @@ mod buy {
             &mpl_token_metadata::id(),
         );
 
+        // Token account of the right mint, but not the market treasury holder
+        let wrong_treasury_holder_keypair = Keypair::new();
+        create_token_account(
+            &mut context,
+            &wrong_treasury_holder_keypair,
+            &treasury_mint_keypair.pubkey(),
+            &treasury_owner,
+        )
+        .await;
+
         // Buy
         let accounts = mpl_fixed_price_sale_accounts::Buy {
             market: market_keypair.pubkey(),
@@ -1958,13 +4060,14 @@ mod buy {
             user_token_account: user_token_account.pubkey(),
             user_wallet: context.payer.pubkey(),
             trade_history,
-            treasury_holder: treasury_holder_keypair.pubkey(),
+            treasury_holder: wrong_treasury_holder_keypair.pubkey(),
             new_metadata,
             new_edition,
             master_edition,
             new_mint: new_mint_keypair.pubkey(),
             edition_marker,
             vault: selling_resource.vault,
+            store: selling_resource.store,
             owner,
             new_token_account: new_mint_token_account.pubkey(),
             master_edition_metadata,
@@ -1973,12 +4076,15 @@ mod buy {
             token_metadata_program: mpl_token_metadata::id(),
             token_program: spl_token::id(),
             system_program: system_program::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            provenance_record: find_provenance_record_address(&new_mint_keypair.pubkey()).0,
         }
         .to_account_metas(None);
 
         let data = mpl_fixed_price_sale_instruction::Buy {
             _trade_history_bump: trade_history_bump,
             vault_owner_bump,
+            client_nonce: None,
         }
         .data();
 
@@ -1989,16 +4095,167 @@ mod buy {
         };
 
         let tx = Transaction::new_signed_with_payer(
-            &[instruction.clone()],
+            &[instruction],
             Some(&context.payer.pubkey()),
             &[&context.payer],
             context.last_blockhash,
         );
 
-        context.banks_client.process_transaction(tx).await.unwrap();
+        let err = context
+            .banks_client
+            .process_transaction(tx)
+            .await
+            .unwrap_err();
+
+        match err {
+            TransportError::TransactionError(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(err_code),
+            )) => {
+                assert_eq!(
+                    err_code,
+                    ERROR_CODE_OFFSET + ErrorCode::TreasuryMismatch as u32
+                );
+            }
+            _ => assert!(false),
+        }
+    }
+
+    #[tokio::test]
+    async fn fail_new_mint_owner_invalid() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _vault) =
+            setup_selling_resource(
+                &mut context,
+                &admin_wallet,
+                &store_keypair,
+                100,
+                None,
+                true,
+                false,
+            )
+            .await;
+
+        let market_keypair = setup_market(
+            &mut context,
+            &admin_wallet,
+            &store_keypair,
+            &selling_resource_keypair,
+            &selling_resource_owner_keypair,
+        )
+        .await;
 
         let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
-        context.warp_to_slot(clock.slot + 3).unwrap();
+        context.warp_to_slot(clock.slot + 1500).unwrap();
+
+        // Buy setup
+        let market_data = context
+            .banks_client
+            .get_account(market_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap()
+            .data;
+        let market = Market::try_deserialize(&mut market_data.as_ref()).unwrap();
+
+        let selling_resource_data = context
+            .banks_client
+            .get_account(selling_resource_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap()
+            .data;
+        let selling_resource =
+            SellingResource::try_deserialize(&mut selling_resource_data.as_ref()).unwrap();
+
+        let (trade_history, trade_history_bump) =
+            find_trade_history_address(&context.payer.pubkey(), &market_keypair.pubkey());
+        let (owner, vault_owner_bump) =
+            find_vault_owner_address(&selling_resource.resource, &selling_resource.store);
+
+        let payer_pubkey = context.payer.pubkey();
+
+        let user_token_account = Keypair::new();
+        create_token_account(
+            &mut context,
+            &user_token_account,
+            &market.treasury_mint,
+            &payer_pubkey,
+        )
+        .await;
+
+        mint_to(
+            &mut context,
+            &market.treasury_mint,
+            &user_token_account.pubkey(),
+            &admin_wallet,
+            market.price,
+        )
+        .await;
+
+        // New mint which was never created, so it is still owned by the system program
+        let new_mint = Pubkey::new_unique();
+        let new_mint_token_account = Pubkey::new_unique();
+
+        let (master_edition_metadata, _) =
+            mpl_token_metadata::pda::find_metadata_account(&selling_resource.resource);
+        let (master_edition, _) =
+            mpl_token_metadata::pda::find_master_edition_account(&selling_resource.resource);
+        let (edition_marker, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                selling_resource.resource.as_ref(),
+                mpl_token_metadata::state::EDITION.as_bytes(),
+                selling_resource.supply.to_string().as_bytes(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+        let (new_metadata, _) = mpl_token_metadata::pda::find_metadata_account(&new_mint);
+        let (new_edition, _) = mpl_token_metadata::pda::find_master_edition_account(&new_mint);
+
+        // Buy
+        let accounts = mpl_fixed_price_sale_accounts::Buy {
+            market: market_keypair.pubkey(),
+            selling_resource: selling_resource_keypair.pubkey(),
+            user_token_account: user_token_account.pubkey(),
+            user_wallet: context.payer.pubkey(),
+            trade_history,
+            treasury_holder: market.treasury_holder,
+            new_metadata,
+            new_edition,
+            master_edition,
+            new_mint,
+            edition_marker,
+            vault: selling_resource.vault,
+            store: selling_resource.store,
+            owner,
+            new_token_account: new_mint_token_account,
+            master_edition_metadata,
+            clock: sysvar::clock::id(),
+            rent: sysvar::rent::id(),
+            token_metadata_program: mpl_token_metadata::id(),
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            provenance_record: find_provenance_record_address(&new_mint).0,
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::Buy {
+            _trade_history_bump: trade_history_bump,
+            vault_owner_bump,
+            client_nonce: None,
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
 
         let tx = Transaction::new_signed_with_payer(
             &[instruction],
@@ -2014,8 +4271,15 @@ mod buy {
             .unwrap_err();
 
         match err {
-            TransportError::Custom(_) => assert!(true),
-            TransportError::TransactionError(_) => assert!(true),
+            TransportError::TransactionError(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(err_code),
+            )) => {
+                assert_eq!(
+                    err_code,
+                    ERROR_CODE_OFFSET + ErrorCode::NewMintOwnerInvalid as u32
+                );
+            }
             _ => assert!(false),
         }
     }
@@ -2092,6 +4356,10 @@ mod buy {
             mint: treasury_mint_keypair.pubkey(),
             treasury_holder: treasury_holder_keypair.pubkey(),
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -2106,6 +4374,10 @@ mod buy {
             start_date: start_date as u64,
             end_date: None,
             gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -2252,6 +4524,7 @@ mod buy {
             new_mint: new_mint_keypair.pubkey(),
             edition_marker,
             vault: selling_resource.vault,
+            store: selling_resource.store,
             owner,
             new_token_account: new_mint_token_account.pubkey(),
             master_edition_metadata,
@@ -2260,12 +4533,15 @@ mod buy {
             token_metadata_program: mpl_token_metadata::id(),
             token_program: spl_token::id(),
             system_program: system_program::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            provenance_record: find_provenance_record_address(&new_mint_keypair.pubkey()).0,
         }
         .to_account_metas(None);
 
         let data = mpl_fixed_price_sale_instruction::Buy {
             _trade_history_bump: trade_history_bump,
             vault_owner_bump,
+            client_nonce: None,
         }
         .data();
 
@@ -2366,6 +4642,7 @@ mod buy {
             new_mint: new_mint_keypair.pubkey(),
             edition_marker,
             vault: selling_resource.vault,
+            store: selling_resource.store,
             owner,
             new_token_account: new_mint_token_account.pubkey(),
             master_edition_metadata,
@@ -2374,12 +4651,15 @@ mod buy {
             token_metadata_program: mpl_token_metadata::id(),
             token_program: spl_token::id(),
             system_program: system_program::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            provenance_record: find_provenance_record_address(&new_mint_keypair.pubkey()).0,
         }
         .to_account_metas(None);
 
         let data = mpl_fixed_price_sale_instruction::Buy {
             _trade_history_bump: trade_history_bump,
             vault_owner_bump,
+            client_nonce: None,
         }
         .data();
 
@@ -2466,6 +4746,10 @@ mod buy {
             mint: treasury_mint,
             treasury_holder: treasury_owner,
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -2490,6 +4774,10 @@ mod buy {
                 expire_on_use: true,
                 gating_time: None,
             }),
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -2631,6 +4919,7 @@ mod buy {
             new_mint: new_mint_keypair.pubkey(),
             edition_marker,
             vault: selling_resource.vault,
+            store: selling_resource.store,
             owner,
             new_token_account: new_mint_token_account.pubkey(),
             master_edition_metadata,
@@ -2639,6 +4928,8 @@ mod buy {
             token_metadata_program: mpl_token_metadata::id(),
             token_program: spl_token::id(),
             system_program: system_program::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            provenance_record: find_provenance_record_address(&new_mint_keypair.pubkey()).0,
         }
         .to_account_metas(None);
 
@@ -2661,6 +4952,7 @@ mod buy {
         let data = mpl_fixed_price_sale_instruction::Buy {
             _trade_history_bump: trade_history_bump,
             vault_owner_bump,
+            client_nonce: None,
         }
         .data();
 
@@ -2778,6 +5070,10 @@ mod buy {
             mint: treasury_mint,
             treasury_holder: treasury_owner,
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -2802,6 +5098,10 @@ mod buy {
                 expire_on_use: false,
                 gating_time: None,
             }),
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -2943,6 +5243,7 @@ mod buy {
             new_mint: new_mint_keypair.pubkey(),
             edition_marker,
             vault: selling_resource.vault,
+            store: selling_resource.store,
             owner,
             new_token_account: new_mint_token_account.pubkey(),
             master_edition_metadata,
@@ -2951,6 +5252,8 @@ mod buy {
             token_metadata_program: mpl_token_metadata::id(),
             token_program: spl_token::id(),
             system_program: system_program::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            provenance_record: find_provenance_record_address(&new_mint_keypair.pubkey()).0,
         }
         .to_account_metas(None);
 
@@ -2973,6 +5276,7 @@ mod buy {
         let data = mpl_fixed_price_sale_instruction::Buy {
             _trade_history_bump: trade_history_bump,
             vault_owner_bump,
+            client_nonce: None,
         }
         .data();
 
@@ -3090,6 +5394,10 @@ mod buy {
             mint: treasury_mint,
             treasury_holder: treasury_owner,
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -3114,6 +5422,10 @@ mod buy {
                 expire_on_use: false,
                 gating_time: None,
             }),
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -3255,6 +5567,7 @@ mod buy {
             new_mint: new_mint_keypair.pubkey(),
             edition_marker,
             vault: selling_resource.vault,
+            store: selling_resource.store,
             owner,
             new_token_account: new_mint_token_account.pubkey(),
             master_edition_metadata,
@@ -3263,6 +5576,8 @@ mod buy {
             token_metadata_program: mpl_token_metadata::id(),
             token_program: spl_token::id(),
             system_program: system_program::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            provenance_record: find_provenance_record_address(&new_mint_keypair.pubkey()).0,
         }
         .to_account_metas(None);
 
@@ -3285,6 +5600,7 @@ mod buy {
         let data = mpl_fixed_price_sale_instruction::Buy {
             _trade_history_bump: trade_history_bump,
             vault_owner_bump,
+            client_nonce: None,
         }
         .data();
 
@@ -3379,6 +5695,10 @@ mod buy {
             mint: treasury_mint,
             treasury_holder: treasury_owner,
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -3403,6 +5723,10 @@ mod buy {
                 expire_on_use: true,
                 gating_time: None,
             }),
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -3552,6 +5876,7 @@ mod buy {
             new_mint: new_mint_keypair.pubkey(),
             edition_marker,
             vault: selling_resource.vault,
+            store: selling_resource.store,
             owner,
             new_token_account: new_mint_token_account.pubkey(),
             master_edition_metadata,
@@ -3560,6 +5885,8 @@ mod buy {
             token_metadata_program: mpl_token_metadata::id(),
             token_program: spl_token::id(),
             system_program: system_program::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            provenance_record: find_provenance_record_address(&new_mint_keypair.pubkey()).0,
         }
         .to_account_metas(None);
 
@@ -3577,6 +5904,7 @@ mod buy {
         let data = mpl_fixed_price_sale_instruction::Buy {
             _trade_history_bump: trade_history_bump,
             vault_owner_bump,
+            client_nonce: None,
         }
         .data();
 
@@ -3695,6 +6023,10 @@ mod buy {
             mint: treasury_mint,
             treasury_holder: treasury_owner,
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -3719,6 +6051,10 @@ mod buy {
                 expire_on_use: true,
                 gating_time: None,
             }),
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -3872,6 +6208,7 @@ mod buy {
             new_mint: new_mint_keypair.pubkey(),
             edition_marker,
             vault: selling_resource.vault,
+            store: selling_resource.store,
             owner,
             new_token_account: new_mint_token_account.pubkey(),
             master_edition_metadata,
@@ -3880,6 +6217,8 @@ mod buy {
             token_metadata_program: mpl_token_metadata::id(),
             token_program: spl_token::id(),
             system_program: system_program::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            provenance_record: find_provenance_record_address(&new_mint_keypair.pubkey()).0,
         }
         .to_account_metas(None);
 
@@ -3897,6 +6236,7 @@ mod buy {
         let data = mpl_fixed_price_sale_instruction::Buy {
             _trade_history_bump: trade_history_bump,
             vault_owner_bump,
+            client_nonce: None,
         }
         .data();
 
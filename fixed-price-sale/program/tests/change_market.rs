@@ -12,14 +12,16 @@ mod change_market {
     use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
     use mpl_fixed_price_sale::{
         accounts as mpl_fixed_price_sale_accounts, instruction as mpl_fixed_price_sale_instruction,
-        state::Market,
+        state::{Market, MarketNameRegistry},
         utils::{
+            find_global_config_address, find_market_manager_address,
+            find_market_name_registry_address, find_store_payment_mints_address,
             find_treasury_owner_address, puffed_out_string, DESCRIPTION_MAX_LEN, NAME_MAX_LEN,
         },
     };
     use solana_program_test::*;
     use solana_sdk::{
-        instruction::Instruction,
+        instruction::{AccountMeta, Instruction},
         signature::Keypair,
         signer::Signer,
         system_program,
@@ -92,6 +94,10 @@ mod change_market {
             mint: treasury_mint_keypair.pubkey(),
             treasury_holder: treasury_holder_keypair.pubkey(),
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -106,6 +112,10 @@ mod change_market {
             start_date: start_date as u64,
             end_date: None,
             gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -134,7 +144,13 @@ mod change_market {
         // SuspendMarket
         let accounts = mpl_fixed_price_sale_accounts::SuspendMarket {
             market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
             owner: selling_resource_owner_keypair.pubkey(),
+            market_manager: find_market_manager_address(
+                &market_keypair.pubkey(),
+                &selling_resource_owner_keypair.pubkey(),
+            )
+            .0,
             clock: sysvar::clock::id(),
         }
         .to_account_metas(None);
@@ -157,12 +173,25 @@ mod change_market {
         context.banks_client.process_transaction(tx).await.unwrap();
 
         // ChangeMarket
-        let accounts = mpl_fixed_price_sale_accounts::ChangeMarket {
+        let mut accounts = mpl_fixed_price_sale_accounts::ChangeMarket {
             market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
             owner: selling_resource_owner_keypair.pubkey(),
+            market_manager: find_market_manager_address(
+                &market_keypair.pubkey(),
+                &selling_resource_owner_keypair.pubkey(),
+            )
+            .0,
             clock: sysvar::clock::id(),
+            system_program: system_program::id(),
         }
         .to_account_metas(None);
+        let (new_market_name_registry, _) =
+            find_market_name_registry_address(&store_keypair.pubkey(), "1");
+        let (market_name_registry, _) =
+            find_market_name_registry_address(&store_keypair.pubkey(), &name);
+        accounts.push(AccountMeta::new(new_market_name_registry, false));
+        accounts.push(AccountMeta::new(market_name_registry, false));
 
         let data = mpl_fixed_price_sale_instruction::ChangeMarket {
             new_name: Some(String::from("1")),
@@ -207,6 +236,23 @@ mod change_market {
             puffed_out_string(String::from("2"), DESCRIPTION_MAX_LEN),
             market_data.description
         );
+
+        // Registered name moved along with the market
+        let new_registry_acc = context
+            .banks_client
+            .get_account(new_market_name_registry)
+            .await
+            .expect("account not found")
+            .expect("account empty");
+        let new_registry_data =
+            MarketNameRegistry::try_deserialize(&mut new_registry_acc.data.as_ref()).unwrap();
+        assert_eq!(new_registry_data.market, market_keypair.pubkey());
+        assert!(context
+            .banks_client
+            .get_account(market_name_registry)
+            .await
+            .unwrap()
+            .is_none());
     }
 
     #[tokio::test]
@@ -273,6 +319,10 @@ mod change_market {
             mint: treasury_mint_keypair.pubkey(),
             treasury_holder: treasury_holder_keypair.pubkey(),
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -287,6 +337,10 @@ mod change_market {
             start_date: start_date as u64,
             end_date: None,
             gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -315,7 +369,13 @@ mod change_market {
         // SuspendMarket
         let accounts = mpl_fixed_price_sale_accounts::SuspendMarket {
             market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
             owner: selling_resource_owner_keypair.pubkey(),
+            market_manager: find_market_manager_address(
+                &market_keypair.pubkey(),
+                &selling_resource_owner_keypair.pubkey(),
+            )
+            .0,
             clock: sysvar::clock::id(),
         }
         .to_account_metas(None);
@@ -341,12 +401,16 @@ mod change_market {
         context.warp_to_slot(clock.slot + 3).unwrap();
 
         // CloseMarket
-        let accounts = mpl_fixed_price_sale_accounts::CloseMarket {
+        let mut accounts = mpl_fixed_price_sale_accounts::CloseMarket {
             market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
             owner: selling_resource_owner_keypair.pubkey(),
             clock: sysvar::clock::id(),
         }
         .to_account_metas(None);
+        let (market_name_registry, _) =
+            find_market_name_registry_address(&store_keypair.pubkey(), &name);
+        accounts.push(AccountMeta::new(market_name_registry, false));
 
         let data = mpl_fixed_price_sale_instruction::CloseMarket {}.data();
 
@@ -371,8 +435,15 @@ mod change_market {
         // ChangeMarket
         let accounts = mpl_fixed_price_sale_accounts::ChangeMarket {
             market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
             owner: selling_resource_owner_keypair.pubkey(),
+            market_manager: find_market_manager_address(
+                &market_keypair.pubkey(),
+                &selling_resource_owner_keypair.pubkey(),
+            )
+            .0,
             clock: sysvar::clock::id(),
+            system_program: system_program::id(),
         }
         .to_account_metas(None);
 
@@ -477,6 +548,10 @@ mod change_market {
             mint: treasury_mint_keypair.pubkey(),
             treasury_holder: treasury_holder_keypair.pubkey(),
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -491,6 +566,10 @@ mod change_market {
             start_date: start_date as u64,
             end_date: Some(end_date as u64),
             gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -522,7 +601,13 @@ mod change_market {
         // SuspendMarket
         let accounts = mpl_fixed_price_sale_accounts::SuspendMarket {
             market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
             owner: selling_resource_owner_keypair.pubkey(),
+            market_manager: find_market_manager_address(
+                &market_keypair.pubkey(),
+                &selling_resource_owner_keypair.pubkey(),
+            )
+            .0,
             clock: sysvar::clock::id(),
         }
         .to_account_metas(None);
@@ -553,8 +638,15 @@ mod change_market {
         // ChangeMarket
         let accounts = mpl_fixed_price_sale_accounts::ChangeMarket {
             market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
             owner: selling_resource_owner_keypair.pubkey(),
+            market_manager: find_market_manager_address(
+                &market_keypair.pubkey(),
+                &selling_resource_owner_keypair.pubkey(),
+            )
+            .0,
             clock: sysvar::clock::id(),
+            system_program: system_program::id(),
         }
         .to_account_metas(None);
 
@@ -16,7 +16,10 @@ mod claim_resource {
         accounts as mpl_fixed_price_sale_accounts, instruction as mpl_fixed_price_sale_instruction,
         state::SellingResource,
         utils::{
-            find_payout_ticket_address, find_primary_metadata_creators, find_trade_history_address,
+            find_claim_tally_address, find_global_config_address,
+            find_market_name_registry_address, find_payout_ticket_address,
+            find_primary_metadata_creators, find_provenance_record_address,
+            find_store_payment_mints_address, find_trade_history_address,
             find_treasury_owner_address, find_vault_owner_address,
         },
     };
@@ -104,6 +107,10 @@ mod claim_resource {
             mint: treasury_mint_keypair.pubkey(),
             treasury_holder: treasury_holder_keypair.pubkey(),
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -118,6 +125,10 @@ mod claim_resource {
             start_date: start_date as u64,
             end_date: None,
             gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -303,6 +314,7 @@ mod claim_resource {
             new_mint: new_mint_keypair.pubkey(),
             edition_marker,
             vault: selling_resource.vault,
+            store: selling_resource.store,
             owner,
             new_token_account: new_mint_token_account.pubkey(),
             master_edition_metadata,
@@ -311,12 +323,15 @@ mod claim_resource {
             token_metadata_program: mpl_token_metadata::id(),
             token_program: spl_token::id(),
             system_program: system_program::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            provenance_record: find_provenance_record_address(&new_mint_keypair.pubkey()).0,
         }
         .to_account_metas(None);
 
         let data = mpl_fixed_price_sale_instruction::Buy {
             _trade_history_bump: trade_history_bump,
             vault_owner_bump,
+            client_nonce: None,
         }
         .data();
 
@@ -339,12 +354,16 @@ mod claim_resource {
         context.warp_to_slot(clock.slot + 3).unwrap();
 
         // CloseMarket
-        let accounts = mpl_fixed_price_sale_accounts::CloseMarket {
+        let mut accounts = mpl_fixed_price_sale_accounts::CloseMarket {
             market: market_keypair.pubkey(),
+            store: selling_resource.store,
             owner: selling_resource_owner_keypair.pubkey(),
             clock: sysvar::clock::id(),
         }
         .to_account_metas(None);
+        let (market_name_registry, _) =
+            find_market_name_registry_address(&selling_resource.store, &name);
+        accounts.push(AccountMeta::new(market_name_registry, false));
 
         let data = mpl_fixed_price_sale_instruction::CloseMarket {}.data();
 
@@ -385,6 +404,7 @@ mod claim_resource {
 
         let mut accounts = mpl_fixed_price_sale_accounts::Withdraw {
             market: market_keypair.pubkey(),
+            store: selling_resource.store,
             selling_resource: selling_resource_keypair.pubkey(),
             metadata,
             treasury_holder: treasury_holder_keypair.pubkey(),
@@ -394,6 +414,7 @@ mod claim_resource {
             funder: primary_royalties_holder.pubkey(),
             payer: payer_pubkey,
             payout_ticket,
+            claim_tally: find_claim_tally_address(&market_keypair.pubkey()).0,
             rent: sysvar::rent::id(),
             clock: sysvar::clock::id(),
             token_program: spl_token::id(),
@@ -436,6 +457,7 @@ mod claim_resource {
 
         let accounts = mpl_fixed_price_sale_accounts::ClaimResource {
             market: market_keypair.pubkey(),
+            store: selling_resource.store,
             treasury_holder: treasury_holder_keypair.pubkey(),
             selling_resource: selling_resource_keypair.pubkey(),
             selling_resource_owner: selling_resource_owner_keypair.pubkey(),
@@ -523,6 +545,10 @@ mod claim_resource {
             mint: treasury_mint,
             treasury_holder,
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -537,6 +563,10 @@ mod claim_resource {
             start_date: start_date as u64,
             end_date: None,
             gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -707,6 +737,7 @@ mod claim_resource {
             new_mint: new_mint_keypair.pubkey(),
             edition_marker,
             vault: selling_resource.vault,
+            store: selling_resource.store,
             owner,
             new_token_account: new_mint_token_account.pubkey(),
             master_edition_metadata,
@@ -715,12 +746,15 @@ mod claim_resource {
             token_metadata_program: mpl_token_metadata::id(),
             token_program: spl_token::id(),
             system_program: system_program::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            provenance_record: find_provenance_record_address(&new_mint_keypair.pubkey()).0,
         }
         .to_account_metas(None);
 
         let data = mpl_fixed_price_sale_instruction::Buy {
             _trade_history_bump: trade_history_bump,
             vault_owner_bump,
+            client_nonce: None,
         }
         .data();
 
@@ -743,12 +777,16 @@ mod claim_resource {
         context.warp_to_slot(clock.slot + 3).unwrap();
 
         // CloseMarket
-        let accounts = mpl_fixed_price_sale_accounts::CloseMarket {
+        let mut accounts = mpl_fixed_price_sale_accounts::CloseMarket {
             market: market_keypair.pubkey(),
+            store: selling_resource.store,
             owner: selling_resource_owner_keypair.pubkey(),
             clock: sysvar::clock::id(),
         }
         .to_account_metas(None);
+        let (market_name_registry, _) =
+            find_market_name_registry_address(&selling_resource.store, &name);
+        accounts.push(AccountMeta::new(market_name_registry, false));
 
         let data = mpl_fixed_price_sale_instruction::CloseMarket {}.data();
 
@@ -784,6 +822,7 @@ mod claim_resource {
 
         let mut accounts = mpl_fixed_price_sale_accounts::Withdraw {
             market: market_keypair.pubkey(),
+            store: selling_resource.store,
             selling_resource: selling_resource_keypair.pubkey(),
             metadata,
             treasury_holder,
@@ -793,6 +832,7 @@ mod claim_resource {
             funder: primary_royalties_receiver.pubkey(),
             payer: payer_pubkey,
             payout_ticket,
+            claim_tally: find_claim_tally_address(&market_keypair.pubkey()).0,
             rent: sysvar::rent::id(),
             clock: sysvar::clock::id(),
             token_program: spl_token::id(),
@@ -835,6 +875,7 @@ mod claim_resource {
 
         let accounts = mpl_fixed_price_sale_accounts::ClaimResource {
             market: market_keypair.pubkey(),
+            store: selling_resource.store,
             treasury_holder,
             selling_resource: selling_resource_keypair.pubkey(),
             selling_resource_owner: selling_resource_owner_keypair.pubkey(),
@@ -939,6 +980,10 @@ mod claim_resource {
             mint: treasury_mint_keypair.pubkey(),
             treasury_holder: treasury_holder_keypair.pubkey(),
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -953,6 +998,10 @@ mod claim_resource {
             start_date: start_date as u64,
             end_date: None,
             gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -1137,6 +1186,7 @@ mod claim_resource {
             new_mint: new_mint_keypair.pubkey(),
             edition_marker,
             vault: selling_resource.vault,
+            store: selling_resource.store,
             owner,
             new_token_account: new_mint_token_account.pubkey(),
             master_edition_metadata,
@@ -1145,12 +1195,15 @@ mod claim_resource {
             token_metadata_program: mpl_token_metadata::id(),
             token_program: spl_token::id(),
             system_program: system_program::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            provenance_record: find_provenance_record_address(&new_mint_keypair.pubkey()).0,
         }
         .to_account_metas(None);
 
         let data = mpl_fixed_price_sale_instruction::Buy {
             _trade_history_bump: trade_history_bump,
             vault_owner_bump,
+            client_nonce: None,
         }
         .data();
 
@@ -1173,12 +1226,16 @@ mod claim_resource {
         context.warp_to_slot(clock.slot + 3).unwrap();
 
         // CloseMarket
-        let accounts = mpl_fixed_price_sale_accounts::CloseMarket {
+        let mut accounts = mpl_fixed_price_sale_accounts::CloseMarket {
             market: market_keypair.pubkey(),
+            store: selling_resource.store,
             owner: selling_resource_owner_keypair.pubkey(),
             clock: sysvar::clock::id(),
         }
         .to_account_metas(None);
+        let (market_name_registry, _) =
+            find_market_name_registry_address(&selling_resource.store, &name);
+        accounts.push(AccountMeta::new(market_name_registry, false));
 
         let data = mpl_fixed_price_sale_instruction::CloseMarket {}.data();
 
@@ -1218,6 +1275,7 @@ mod claim_resource {
 
         let accounts = mpl_fixed_price_sale_accounts::ClaimResource {
             market: market_keypair.pubkey(),
+            store: selling_resource.store,
             treasury_holder: treasury_holder_keypair.pubkey(),
             selling_resource: selling_resource_keypair.pubkey(),
             selling_resource_owner: selling_resource_owner_keypair.pubkey(),
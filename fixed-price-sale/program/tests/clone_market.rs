@@ -0,0 +1,320 @@
+mod utils;
+
+#[cfg(feature = "test-bpf")]
+mod clone_market {
+    use crate::{
+        setup_context,
+        utils::{
+            helpers::create_token_account,
+            setup_functions::{
+                setup_market, setup_selling_resource, setup_selling_resource_with_owner,
+                setup_store,
+            },
+        },
+    };
+    use anchor_lang::{
+        error::ERROR_CODE_OFFSET, AccountDeserialize, InstructionData, ToAccountMetas,
+    };
+    use mpl_fixed_price_sale::{
+        accounts as mpl_fixed_price_sale_accounts,
+        error::ErrorCode,
+        instruction as mpl_fixed_price_sale_instruction,
+        state::{Market, MarketNameRegistry, MarketState, SellingResourceType},
+        utils::{
+            find_global_config_address, find_market_name_registry_address,
+            find_store_payment_mints_address, find_treasury_owner_address,
+        },
+    };
+    use solana_program_test::*;
+    use solana_sdk::{
+        instruction::{AccountMeta, Instruction, InstructionError},
+        pubkey::Pubkey,
+        signature::Keypair,
+        signer::Signer,
+        system_program,
+        sysvar::{self, clock::Clock},
+        transaction::{Transaction, TransactionError},
+        transport::TransportError,
+    };
+
+    async fn get_market(context: &mut ProgramTestContext, market: &Pubkey) -> Market {
+        let market_acc = context
+            .banks_client
+            .get_account(*market)
+            .await
+            .expect("account not found")
+            .expect("account empty");
+
+        Market::try_deserialize(&mut market_acc.data.as_ref()).unwrap()
+    }
+
+    async fn clone_market_tx(
+        context: &mut ProgramTestContext,
+        market_keypair: &Keypair,
+        source_market: &Market,
+        source_market_key: &Pubkey,
+        owner_keypair: &Keypair,
+        selling_resource: &Pubkey,
+        start_date: u64,
+    ) -> Transaction {
+        let (treasury_owner, treasury_owner_bump) =
+            find_treasury_owner_address(&source_market.treasury_mint, selling_resource);
+
+        let treasury_holder_keypair = Keypair::new();
+        create_token_account(
+            context,
+            &treasury_holder_keypair,
+            &source_market.treasury_mint,
+            &treasury_owner,
+        )
+        .await;
+
+        let accounts = mpl_fixed_price_sale_accounts::CloneMarket {
+            market: market_keypair.pubkey(),
+            source_market: *source_market_key,
+            store: store_keypair.pubkey(),
+            owner: owner_keypair.pubkey(),
+            selling_resource: *selling_resource,
+            treasury_holder: treasury_holder_keypair.pubkey(),
+            treasury_owner,
+            market_name_registry: find_market_name_registry_address(
+                &source_market.store,
+                &source_market.name,
+            )
+            .0,
+            store_payment_mints: find_store_payment_mints_address(&source_market.store).0,
+            global_config: find_global_config_address().0,
+            clock: sysvar::clock::id(),
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::CloneMarket {
+            treasury_owner_bump,
+            start_date,
+            end_date: None,
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, market_keypair, owner_keypair],
+            context.last_blockhash,
+        )
+    }
+
+    #[tokio::test]
+    async fn success() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _) = setup_selling_resource(
+            &mut context,
+            &admin_wallet,
+            &store_keypair,
+            100,
+            None,
+            true,
+            false,
+        )
+        .await;
+
+        let source_market_keypair = setup_market(
+            &mut context,
+            &admin_wallet,
+            &store_keypair,
+            &selling_resource_keypair,
+            &selling_resource_owner_keypair,
+        )
+        .await;
+
+        let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        context.warp_to_slot(clock.slot + 1500).unwrap();
+
+        // CloseMarket
+        let source_market = get_market(&mut context, &source_market_keypair.pubkey()).await;
+        let (market_name_registry, _) =
+            find_market_name_registry_address(&store_keypair.pubkey(), &source_market.name);
+        let mut accounts = mpl_fixed_price_sale_accounts::CloseMarket {
+            market: source_market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
+            owner: selling_resource_owner_keypair.pubkey(),
+            clock: sysvar::clock::id(),
+        }
+        .to_account_metas(None);
+        accounts.push(AccountMeta::new(market_name_registry, false));
+
+        let data = mpl_fixed_price_sale_instruction::CloseMarket {}.data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, &selling_resource_owner_keypair],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        // Next drop uses a new resource of the same owner
+        let (new_selling_resource_keypair, _, _) = setup_selling_resource_with_owner(
+            &mut context,
+            &admin_wallet,
+            &store_keypair,
+            Keypair::from_bytes(&selling_resource_owner_keypair.to_bytes()).unwrap(),
+            100,
+            None,
+            true,
+            false,
+            1,
+            SellingResourceType::Editions,
+        )
+        .await;
+
+        let source_market = get_market(&mut context, &source_market_keypair.pubkey()).await;
+        let start_date = context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+            .unix_timestamp as u64
+            + 1;
+
+        let market_keypair = Keypair::new();
+        let tx = clone_market_tx(
+            &mut context,
+            &market_keypair,
+            &source_market,
+            &source_market_keypair.pubkey(),
+            &selling_resource_owner_keypair,
+            &new_selling_resource_keypair.pubkey(),
+            start_date,
+        )
+        .await;
+
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        let market_data = get_market(&mut context, &market_keypair.pubkey()).await;
+        assert_eq!(market_data.state, MarketState::Created);
+        assert_eq!(
+            market_data.selling_resource,
+            new_selling_resource_keypair.pubkey()
+        );
+        assert_ne!(market_data.treasury_holder, source_market.treasury_holder);
+        assert_eq!(market_data.treasury_mint, source_market.treasury_mint);
+        assert_eq!(market_data.owner, source_market.owner);
+        assert_eq!(market_data.name, source_market.name);
+        assert_eq!(market_data.description, source_market.description);
+        assert_eq!(market_data.price, source_market.price);
+        assert_eq!(
+            market_data.pieces_in_one_wallet,
+            source_market.pieces_in_one_wallet
+        );
+        assert_eq!(market_data.start_date, start_date);
+        assert_eq!(market_data.end_date, None);
+        assert_eq!(market_data.funds_collected, 0);
+
+        // Name is registered for the clone
+        let registry_acc = context
+            .banks_client
+            .get_account(market_name_registry)
+            .await
+            .expect("account not found")
+            .expect("account empty");
+        let registry_data =
+            MarketNameRegistry::try_deserialize(&mut registry_acc.data.as_ref()).unwrap();
+        assert_eq!(registry_data.market, market_keypair.pubkey());
+    }
+
+    #[tokio::test]
+    async fn fail_source_not_ended() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _) = setup_selling_resource(
+            &mut context,
+            &admin_wallet,
+            &store_keypair,
+            100,
+            None,
+            true,
+            false,
+        )
+        .await;
+
+        let source_market_keypair = setup_market(
+            &mut context,
+            &admin_wallet,
+            &store_keypair,
+            &selling_resource_keypair,
+            &selling_resource_owner_keypair,
+        )
+        .await;
+
+        let (new_selling_resource_keypair, _, _) = setup_selling_resource_with_owner(
+            &mut context,
+            &admin_wallet,
+            &store_keypair,
+            Keypair::from_bytes(&selling_resource_owner_keypair.to_bytes()).unwrap(),
+            100,
+            None,
+            true,
+            false,
+            1,
+            SellingResourceType::Editions,
+        )
+        .await;
+
+        let source_market = get_market(&mut context, &source_market_keypair.pubkey()).await;
+        let start_date = context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+            .unix_timestamp as u64
+            + 1;
+
+        let market_keypair = Keypair::new();
+        let tx = clone_market_tx(
+            &mut context,
+            &market_keypair,
+            &source_market,
+            &source_market_keypair.pubkey(),
+            &selling_resource_owner_keypair,
+            &new_selling_resource_keypair.pubkey(),
+            start_date,
+        )
+        .await;
+
+        let err = context
+            .banks_client
+            .process_transaction(tx)
+            .await
+            .unwrap_err();
+
+        match err {
+            TransportError::TransactionError(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(err_code),
+            )) => {
+                assert_eq!(
+                    err_code,
+                    ERROR_CODE_OFFSET + ErrorCode::MarketIsNotEnded as u32
+                );
+            }
+            _ => assert!(false),
+        }
+    }
+}
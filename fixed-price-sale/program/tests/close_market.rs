@@ -6,23 +6,30 @@ mod close_market {
         setup_context,
         utils::{
             helpers::{create_mint, create_token_account},
-            setup_functions::{setup_selling_resource, setup_store},
+            setup_functions::{setup_market, setup_selling_resource, setup_store},
         },
     };
-    use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+    use anchor_lang::{
+        error::ERROR_CODE_OFFSET, AccountDeserialize, InstructionData, ToAccountMetas,
+    };
     use mpl_fixed_price_sale::{
-        accounts as mpl_fixed_price_sale_accounts, instruction as mpl_fixed_price_sale_instruction,
+        accounts as mpl_fixed_price_sale_accounts,
+        error::ErrorCode,
+        instruction as mpl_fixed_price_sale_instruction,
         state::{Market, MarketState},
-        utils::find_treasury_owner_address,
+        utils::{
+            find_global_config_address, find_market_name_registry_address,
+            find_store_payment_mints_address, find_treasury_owner_address,
+        },
     };
     use solana_program_test::*;
     use solana_sdk::{
-        instruction::Instruction,
+        instruction::{AccountMeta, Instruction, InstructionError},
         signature::Keypair,
         signer::Signer,
         system_program,
         sysvar::{self, clock::Clock},
-        transaction::Transaction,
+        transaction::{Transaction, TransactionError},
         transport::TransportError,
     };
 
@@ -90,6 +97,10 @@ mod close_market {
             mint: treasury_mint_keypair.pubkey(),
             treasury_holder: treasury_holder_keypair.pubkey(),
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -104,6 +115,10 @@ mod close_market {
             start_date: start_date as u64,
             end_date: None,
             gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -130,12 +145,16 @@ mod close_market {
         context.warp_to_slot(clock.slot + 1500).unwrap();
 
         // CloseMarket
-        let accounts = mpl_fixed_price_sale_accounts::CloseMarket {
+        let mut accounts = mpl_fixed_price_sale_accounts::CloseMarket {
             market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
             owner: selling_resource_owner_keypair.pubkey(),
             clock: sysvar::clock::id(),
         }
         .to_account_metas(None);
+        let (market_name_registry, _) =
+            find_market_name_registry_address(&store_keypair.pubkey(), &name);
+        accounts.push(AccountMeta::new(market_name_registry, false));
 
         let data = mpl_fixed_price_sale_instruction::CloseMarket {}.data();
 
@@ -163,6 +182,14 @@ mod close_market {
 
         let market_data = Market::try_deserialize(&mut market_acc.data.as_ref()).unwrap();
         assert_eq!(market_data.state, MarketState::Ended);
+
+        // Market name is released
+        assert!(context
+            .banks_client
+            .get_account(market_name_registry)
+            .await
+            .unwrap()
+            .is_none());
     }
 
     #[tokio::test]
@@ -229,6 +256,10 @@ mod close_market {
             mint: treasury_mint_keypair.pubkey(),
             treasury_holder: treasury_holder_keypair.pubkey(),
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -243,6 +274,10 @@ mod close_market {
             start_date: start_date as u64,
             end_date: Some((start_date + 2) as u64),
             gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -269,12 +304,16 @@ mod close_market {
         context.warp_to_slot(clock.slot + 1500).unwrap();
 
         // CloseMarket
-        let accounts = mpl_fixed_price_sale_accounts::CloseMarket {
+        let mut accounts = mpl_fixed_price_sale_accounts::CloseMarket {
             market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
             owner: selling_resource_owner_keypair.pubkey(),
             clock: sysvar::clock::id(),
         }
         .to_account_metas(None);
+        let (market_name_registry, _) =
+            find_market_name_registry_address(&store_keypair.pubkey(), &name);
+        accounts.push(AccountMeta::new(market_name_registry, false));
 
         let data = mpl_fixed_price_sale_instruction::CloseMarket {}.data();
 
@@ -303,4 +342,76 @@ mod close_market {
             _ => assert!(false),
         }
     }
+
+    #[tokio::test]
+    async fn fail_market_name_registry_not_provided() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _) = setup_selling_resource(
+            &mut context,
+            &admin_wallet,
+            &store_keypair,
+            100,
+            None,
+            true,
+            false,
+        )
+        .await;
+
+        let market_keypair = setup_market(
+            &mut context,
+            &admin_wallet,
+            &store_keypair,
+            &selling_resource_keypair,
+            &selling_resource_owner_keypair,
+        )
+        .await;
+
+        let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        context.warp_to_slot(clock.slot + 1500).unwrap();
+
+        // CloseMarket without the registry of the market name
+        let accounts = mpl_fixed_price_sale_accounts::CloseMarket {
+            market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
+            owner: selling_resource_owner_keypair.pubkey(),
+            clock: sysvar::clock::id(),
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::CloseMarket {}.data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, &selling_resource_owner_keypair],
+            context.last_blockhash,
+        );
+
+        let tx_error = context
+            .banks_client
+            .process_transaction(tx)
+            .await
+            .unwrap_err();
+
+        match tx_error {
+            TransportError::TransactionError(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(code),
+            )) => {
+                assert_eq!(
+                    code,
+                    ERROR_CODE_OFFSET + ErrorCode::InvalidMarketNameRegistry as u32
+                );
+            }
+            _ => assert!(false),
+        }
+    }
 }
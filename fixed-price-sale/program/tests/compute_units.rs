@@ -0,0 +1,133 @@
+mod utils;
+
+#[cfg(feature = "test-bpf")]
+mod compute_units {
+    use crate::{
+        setup_context,
+        utils::bench::{measure, BenchMarket, Scenario, Treasury},
+    };
+    use serde_json::json;
+    use solana_program_test::*;
+    use std::{collections::BTreeMap, env, fs, path::PathBuf};
+
+    /// Growth over the recorded baseline tolerated before a measurement counts as a regression
+    const REGRESSION_THRESHOLD_PERCENT: u64 = 5;
+
+    const SCENARIOS: [Scenario; 4] = [
+        Scenario {
+            treasury: Treasury::Native,
+            creators: 1,
+        },
+        Scenario {
+            treasury: Treasury::Native,
+            creators: 5,
+        },
+        Scenario {
+            treasury: Treasury::Spl,
+            creators: 1,
+        },
+        Scenario {
+            treasury: Treasury::Spl,
+            creators: 5,
+        },
+    ];
+
+    // Recorded by running with `UPDATE_CU_BASELINE=1`, regressions are only checked for scenarios listed in it
+    fn baseline_path() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/compute_units_baseline.json")
+    }
+
+    fn report_path() -> PathBuf {
+        env::var("CU_REPORT_PATH")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| {
+                PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../target/compute_units.json")
+            })
+    }
+
+    #[tokio::test]
+    async fn no_regressions() {
+        let mut measurements = Vec::new();
+        for scenario in SCENARIOS {
+            setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+            let market = BenchMarket::setup(&mut context, scenario).await;
+
+            let (instruction, signers) = market.create_market(&mut context).await;
+            measurements.push(
+                measure(
+                    &mut context,
+                    scenario.name("create_market"),
+                    instruction,
+                    signers,
+                )
+                .await,
+            );
+
+            let (instruction, signers) = market.buy(&mut context).await;
+            measurements
+                .push(measure(&mut context, scenario.name("buy"), instruction, signers).await);
+
+            let (instruction, signers) = market.withdraw(&mut context).await;
+            measurements.push(
+                measure(
+                    &mut context,
+                    scenario.name("withdraw"),
+                    instruction,
+                    signers,
+                )
+                .await,
+            );
+        }
+
+        let baseline: BTreeMap<String, u64> = fs::read(baseline_path())
+            .map(|data| serde_json::from_slice(&data).unwrap())
+            .unwrap_or_default();
+
+        let report = json!({
+            "threshold_percent": REGRESSION_THRESHOLD_PERCENT,
+            "measurements": measurements
+                .iter()
+                .map(|measurement| measurement.to_json(baseline.get(&measurement.name).copied()))
+                .collect::<Vec<_>>(),
+        });
+        let report_path = report_path();
+        if let Some(dir) = report_path.parent() {
+            fs::create_dir_all(dir).unwrap();
+        }
+        fs::write(&report_path, serde_json::to_vec_pretty(&report).unwrap()).unwrap();
+
+        if env::var("UPDATE_CU_BASELINE").is_ok() {
+            let baseline: BTreeMap<&str, u32> = measurements
+                .iter()
+                .map(|measurement| (measurement.name.as_str(), measurement.compute_units))
+                .collect();
+            fs::write(
+                baseline_path(),
+                serde_json::to_vec_pretty(&baseline).unwrap(),
+            )
+            .unwrap();
+            return;
+        }
+
+        let regressions: Vec<String> = measurements
+            .iter()
+            .filter_map(|measurement| {
+                let baseline = *baseline.get(&measurement.name)?;
+                let limit = baseline + baseline * REGRESSION_THRESHOLD_PERCENT / 100;
+                (measurement.compute_units as u64 > limit).then(|| {
+                    format!(
+                        "{}: {} compute units, baseline {}",
+                        measurement.name, measurement.compute_units, baseline
+                    )
+                })
+            })
+            .collect();
+        assert!(
+            regressions.is_empty(),
+            "compute units regressed by more than {}%, see {}:\n{}",
+            REGRESSION_THRESHOLD_PERCENT,
+            report_path.display(),
+            regressions.join("\n")
+        );
+    }
+}
@@ -0,0 +1,436 @@
+mod utils;
+
+#[cfg(feature = "test-bpf")]
+mod consume_action {
+    use crate::{
+        setup_context,
+        utils::{
+            helpers::{airdrop, create_mint, create_token_account, mint_to},
+            setup_functions::{setup_market, setup_selling_resource, setup_store},
+        },
+    };
+    use anchor_lang::{
+        error::ERROR_CODE_OFFSET, AccountDeserialize, InstructionData, ToAccountMetas,
+    };
+    use mpl_fixed_price_sale::{
+        accounts as mpl_fixed_price_sale_accounts,
+        error::ErrorCode,
+        instruction as mpl_fixed_price_sale_instruction,
+        state::{ActionQueue, Market, SellingResource},
+        utils::{
+            find_action_queue_address, find_provenance_record_address, find_trade_history_address,
+            find_vault_owner_address,
+        },
+    };
+    use solana_program::clock::Clock;
+    use solana_program_test::*;
+    use solana_sdk::{
+        instruction::{AccountMeta, Instruction, InstructionError},
+        pubkey::Pubkey,
+        signature::Keypair,
+        signer::Signer,
+        system_program, sysvar,
+        transaction::{Transaction, TransactionError},
+        transport::TransportError,
+    };
+
+    const PRICE: u64 = 1_000_000;
+
+    async fn create_action_queue(
+        context: &mut ProgramTestContext,
+        market_keypair: &Keypair,
+        owner: &Keypair,
+        worker: Pubkey,
+    ) {
+        let market_acc = context
+            .banks_client
+            .get_account(market_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let market = Market::try_deserialize(&mut market_acc.data.as_ref()).unwrap();
+
+        let accounts = mpl_fixed_price_sale_accounts::CreateActionQueue {
+            market: market_keypair.pubkey(),
+            store: market.store,
+            owner: owner.pubkey(),
+            action_queue: find_action_queue_address(&market_keypair.pubkey()).0,
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::CreateActionQueue { worker }.data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, owner],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    async fn buy(
+        context: &mut ProgramTestContext,
+        market_keypair: &Keypair,
+        selling_resource_keypair: &Keypair,
+        admin_wallet: &Keypair,
+        user_wallet: &Keypair,
+        remaining_accounts: Vec<AccountMeta>,
+    ) -> Result<Pubkey, TransportError> {
+        let market_acc = context
+            .banks_client
+            .get_account(market_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let market = Market::try_deserialize(&mut market_acc.data.as_ref()).unwrap();
+
+        let selling_resource_acc = context
+            .banks_client
+            .get_account(selling_resource_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let selling_resource =
+            SellingResource::try_deserialize(&mut selling_resource_acc.data.as_ref()).unwrap();
+
+        let user_token_account = Keypair::new();
+        create_token_account(
+            context,
+            &user_token_account,
+            &market.treasury_mint,
+            &user_wallet.pubkey(),
+        )
+        .await;
+        mint_to(
+            context,
+            &market.treasury_mint,
+            &user_token_account.pubkey(),
+            admin_wallet,
+            market.price,
+        )
+        .await;
+
+        let (trade_history, trade_history_bump) =
+            find_trade_history_address(&user_wallet.pubkey(), &market_keypair.pubkey());
+        let (owner, vault_owner_bump) =
+            find_vault_owner_address(&selling_resource.resource, &selling_resource.store);
+
+        let new_mint_keypair = Keypair::new();
+        create_mint(context, &new_mint_keypair, &user_wallet.pubkey(), 0).await;
+
+        let new_mint_token_account = Keypair::new();
+        create_token_account(
+            context,
+            &new_mint_token_account,
+            &new_mint_keypair.pubkey(),
+            &user_wallet.pubkey(),
+        )
+        .await;
+
+        mint_to(
+            context,
+            &new_mint_keypair.pubkey(),
+            &new_mint_token_account.pubkey(),
+            user_wallet,
+            1,
+        )
+        .await;
+
+        let (master_edition_metadata, _) =
+            mpl_token_metadata::pda::find_metadata_account(&selling_resource.resource);
+        let (master_edition, _) =
+            mpl_token_metadata::pda::find_master_edition_account(&selling_resource.resource);
+        let (edition_marker, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                selling_resource.resource.as_ref(),
+                mpl_token_metadata::state::EDITION.as_bytes(),
+                selling_resource.supply.to_string().as_bytes(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+        let (new_metadata, _) =
+            mpl_token_metadata::pda::find_metadata_account(&new_mint_keypair.pubkey());
+        let (new_edition, _) =
+            mpl_token_metadata::pda::find_master_edition_account(&new_mint_keypair.pubkey());
+
+        let accounts = mpl_fixed_price_sale_accounts::Buy {
+            market: market_keypair.pubkey(),
+            selling_resource: selling_resource_keypair.pubkey(),
+            user_token_account: user_token_account.pubkey(),
+            user_wallet: user_wallet.pubkey(),
+            trade_history,
+            treasury_holder: market.treasury_holder,
+            new_metadata,
+            new_edition,
+            master_edition,
+            new_mint: new_mint_keypair.pubkey(),
+            edition_marker,
+            vault: selling_resource.vault,
+            store: selling_resource.store,
+            owner,
+            new_token_account: new_mint_token_account.pubkey(),
+            master_edition_metadata,
+            clock: sysvar::clock::id(),
+            rent: sysvar::rent::id(),
+            token_metadata_program: mpl_token_metadata::id(),
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            provenance_record: find_provenance_record_address(&new_mint_keypair.pubkey()).0,
+        }
+        .to_account_metas(None);
+        let accounts = [accounts, remaining_accounts].concat();
+
+        let data = mpl_fixed_price_sale_instruction::Buy {
+            _trade_history_bump: trade_history_bump,
+            vault_owner_bump,
+            client_nonce: None,
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, user_wallet],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await?;
+
+        Ok(new_mint_keypair.pubkey())
+    }
+
+    /// Market with an action queue consumed by the returned worker, ready for purchases
+    async fn setup_queued_market(
+        context: &mut ProgramTestContext,
+    ) -> (Keypair, Keypair, Keypair, Keypair) {
+        let (admin_wallet, store_keypair) = setup_store(context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _vault) =
+            setup_selling_resource(
+                context,
+                &admin_wallet,
+                &store_keypair,
+                100,
+                None,
+                true,
+                false,
+            )
+            .await;
+
+        let market_keypair = setup_market(
+            context,
+            &admin_wallet,
+            &store_keypair,
+            &selling_resource_keypair,
+            &selling_resource_owner_keypair,
+        )
+        .await;
+
+        let worker = Keypair::new();
+        create_action_queue(
+            context,
+            &market_keypair,
+            &selling_resource_owner_keypair,
+            worker.pubkey(),
+        )
+        .await;
+
+        let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        context.warp_to_slot(clock.slot + 1500).unwrap();
+
+        (
+            admin_wallet,
+            selling_resource_keypair,
+            market_keypair,
+            worker,
+        )
+    }
+
+    async fn get_action_queue(context: &mut ProgramTestContext, market: &Pubkey) -> ActionQueue {
+        let action_queue_acc = context
+            .banks_client
+            .get_account(find_action_queue_address(market).0)
+            .await
+            .unwrap()
+            .unwrap();
+
+        ActionQueue::try_deserialize(&mut action_queue_acc.data.as_ref()).unwrap()
+    }
+
+    async fn consume_action(
+        context: &mut ProgramTestContext,
+        market: &Pubkey,
+        worker: &Keypair,
+        sequence: u64,
+    ) -> Result<(), TransportError> {
+        let accounts = mpl_fixed_price_sale_accounts::ConsumeAction {
+            market: *market,
+            action_queue: find_action_queue_address(market).0,
+            worker: worker.pubkey(),
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::ConsumeAction { sequence }.data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, worker],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    #[tokio::test]
+    async fn success() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, selling_resource_keypair, market_keypair, worker) =
+            setup_queued_market(&mut context).await;
+
+        let user_wallet = Keypair::new();
+        airdrop(&mut context, &user_wallet.pubkey(), 1_000_000_000).await;
+
+        let edition_mint = buy(
+            &mut context,
+            &market_keypair,
+            &selling_resource_keypair,
+            &admin_wallet,
+            &user_wallet,
+            vec![AccountMeta::new(
+                find_action_queue_address(&market_keypair.pubkey()).0,
+                false,
+            )],
+        )
+        .await
+        .unwrap();
+
+        let action_queue = get_action_queue(&mut context, &market_keypair.pubkey()).await;
+        assert_eq!(action_queue.next_sequence, 1);
+        assert_eq!(action_queue.actions.len(), 1);
+        let action = &action_queue.actions[0];
+        assert_eq!(action.sequence, 0);
+        assert_eq!(action.buyer, user_wallet.pubkey());
+        assert_eq!(action.edition_mint, edition_mint);
+        assert_eq!(action.price, PRICE);
+
+        consume_action(&mut context, &market_keypair.pubkey(), &worker, 0)
+            .await
+            .unwrap();
+
+        let action_queue = get_action_queue(&mut context, &market_keypair.pubkey()).await;
+        assert!(action_queue.actions.is_empty());
+        assert_eq!(action_queue.consumed, 1);
+    }
+
+    #[tokio::test]
+    async fn fail_invalid_worker() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, selling_resource_keypair, market_keypair, _worker) =
+            setup_queued_market(&mut context).await;
+
+        let user_wallet = Keypair::new();
+        airdrop(&mut context, &user_wallet.pubkey(), 1_000_000_000).await;
+
+        buy(
+            &mut context,
+            &market_keypair,
+            &selling_resource_keypair,
+            &admin_wallet,
+            &user_wallet,
+            vec![AccountMeta::new(
+                find_action_queue_address(&market_keypair.pubkey()).0,
+                false,
+            )],
+        )
+        .await
+        .unwrap();
+
+        let err = consume_action(&mut context, &market_keypair.pubkey(), &Keypair::new(), 0)
+            .await
+            .unwrap_err();
+
+        match err {
+            TransportError::TransactionError(TransactionError::InstructionError(0, _)) => {}
+            _ => assert!(false),
+        }
+    }
+
+    #[tokio::test]
+    async fn fail_action_not_queued() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (_, _, market_keypair, worker) = setup_queued_market(&mut context).await;
+
+        let err = consume_action(&mut context, &market_keypair.pubkey(), &worker, 0)
+            .await
+            .unwrap_err();
+
+        match err {
+            TransportError::TransactionError(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(code),
+            )) => {
+                assert_eq!(code, ERROR_CODE_OFFSET + ErrorCode::ActionNotQueued as u32);
+            }
+            _ => assert!(false),
+        }
+    }
+
+    #[tokio::test]
+    async fn fail_buy_without_action_queue() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, selling_resource_keypair, market_keypair, _worker) =
+            setup_queued_market(&mut context).await;
+
+        let user_wallet = Keypair::new();
+        airdrop(&mut context, &user_wallet.pubkey(), 1_000_000_000).await;
+
+        let err = buy(
+            &mut context,
+            &market_keypair,
+            &selling_resource_keypair,
+            &admin_wallet,
+            &user_wallet,
+            Vec::new(),
+        )
+        .await
+        .unwrap_err();
+
+        match err {
+            TransportError::TransactionError(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(code),
+            )) => {
+                assert_eq!(
+                    code,
+                    ERROR_CODE_OFFSET + ErrorCode::InvalidActionQueue as u32
+                );
+            }
+            _ => assert!(false),
+        }
+    }
+}
@@ -0,0 +1,166 @@
+mod utils;
+
+#[cfg(feature = "test-bpf")]
+mod create_action_queue {
+    use crate::{
+        setup_context,
+        utils::setup_functions::{setup_market, setup_selling_resource, setup_store},
+    };
+    use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+    use mpl_fixed_price_sale::{
+        accounts as mpl_fixed_price_sale_accounts, instruction as mpl_fixed_price_sale_instruction,
+        state::{ActionQueue, Market},
+        utils::find_action_queue_address,
+    };
+    use solana_program_test::*;
+    use solana_sdk::{
+        instruction::Instruction,
+        pubkey::Pubkey,
+        signature::Keypair,
+        signer::Signer,
+        system_program,
+        transaction::{Transaction, TransactionError},
+        transport::TransportError,
+    };
+
+    async fn create_action_queue(
+        context: &mut ProgramTestContext,
+        market_keypair: &Keypair,
+        owner: &Keypair,
+        worker: Pubkey,
+    ) -> Result<(), TransportError> {
+        let market_acc = context
+            .banks_client
+            .get_account(market_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let market = Market::try_deserialize(&mut market_acc.data.as_ref()).unwrap();
+
+        let accounts = mpl_fixed_price_sale_accounts::CreateActionQueue {
+            market: market_keypair.pubkey(),
+            store: market.store,
+            owner: owner.pubkey(),
+            action_queue: find_action_queue_address(&market_keypair.pubkey()).0,
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::CreateActionQueue { worker }.data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, owner],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    #[tokio::test]
+    async fn success() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _vault) =
+            setup_selling_resource(
+                &mut context,
+                &admin_wallet,
+                &store_keypair,
+                100,
+                None,
+                true,
+                false,
+            )
+            .await;
+
+        let market_keypair = setup_market(
+            &mut context,
+            &admin_wallet,
+            &store_keypair,
+            &selling_resource_keypair,
+            &selling_resource_owner_keypair,
+        )
+        .await;
+
+        let worker = Keypair::new();
+        create_action_queue(
+            &mut context,
+            &market_keypair,
+            &selling_resource_owner_keypair,
+            worker.pubkey(),
+        )
+        .await
+        .unwrap();
+
+        let market_acc = context
+            .banks_client
+            .get_account(market_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let market = Market::try_deserialize(&mut market_acc.data.as_ref()).unwrap();
+        assert!(market.action_queue);
+
+        let action_queue_acc = context
+            .banks_client
+            .get_account(find_action_queue_address(&market_keypair.pubkey()).0)
+            .await
+            .unwrap()
+            .unwrap();
+        let action_queue =
+            ActionQueue::try_deserialize(&mut action_queue_acc.data.as_ref()).unwrap();
+        assert_eq!(action_queue.market, market_keypair.pubkey());
+        assert_eq!(action_queue.worker, worker.pubkey());
+        assert_eq!(action_queue.next_sequence, 0);
+        assert!(action_queue.actions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn fail_not_market_owner() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _vault) =
+            setup_selling_resource(
+                &mut context,
+                &admin_wallet,
+                &store_keypair,
+                100,
+                None,
+                true,
+                false,
+            )
+            .await;
+
+        let market_keypair = setup_market(
+            &mut context,
+            &admin_wallet,
+            &store_keypair,
+            &selling_resource_keypair,
+            &selling_resource_owner_keypair,
+        )
+        .await;
+
+        let err = create_action_queue(
+            &mut context,
+            &market_keypair,
+            &admin_wallet,
+            Keypair::new().pubkey(),
+        )
+        .await
+        .unwrap_err();
+
+        match err {
+            TransportError::TransactionError(TransactionError::InstructionError(0, _)) => {}
+            _ => assert!(false),
+        }
+    }
+}
@@ -3,21 +3,33 @@ mod utils;
 #[cfg(feature = "test-bpf")]
 mod create_market {
     use crate::utils::{
-        helpers::{create_mint, create_token_account},
+        helpers::{airdrop, create_mint, create_token_account},
         setup_functions::{setup_market, setup_selling_resource, setup_store},
     };
-    use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+    use anchor_lang::{
+        error::ERROR_CODE_OFFSET, AccountDeserialize, InstructionData, ToAccountMetas,
+    };
     use mpl_fixed_price_sale::{
-        accounts as mpl_fixed_price_sale_accounts, instruction as mpl_fixed_price_sale_instruction,
-        state::{Market, MarketState},
+        accounts as mpl_fixed_price_sale_accounts,
+        error::ErrorCode,
+        instruction as mpl_fixed_price_sale_instruction,
+        state::{Market, MarketState, MetadataOverrides, SellingResource},
         utils::{
-            find_treasury_owner_address, puffed_out_string, DESCRIPTION_MAX_LEN, NAME_MAX_LEN,
+            find_global_config_address, find_market_name_registry_address,
+            find_store_payment_mints_address, find_treasury_owner_address, puffed_out_string,
+            DESCRIPTION_MAX_LEN, NAME_MAX_LEN,
         },
     };
     use solana_program_test::*;
     use solana_sdk::{
-        instruction::Instruction, signature::Keypair, signer::Signer, system_program,
-        sysvar::clock::Clock, transaction::Transaction, transport::TransportError,
+        instruction::{AccountMeta, Instruction, InstructionError},
+        pubkey::Pubkey,
+        signature::Keypair,
+        signer::Signer,
+        system_program,
+        sysvar::clock::Clock,
+        transaction::{Transaction, TransactionError},
+        transport::TransportError,
     };
 
     use crate::setup_context;
@@ -85,6 +97,10 @@ mod create_market {
             mint: treasury_mint_keypair.pubkey(),
             treasury_holder: treasury_holder_keypair.pubkey(),
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -99,6 +115,10 @@ mod create_market {
             start_date: start_date as u64,
             end_date: None,
             gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -153,6 +173,152 @@ mod create_market {
         assert_eq!(MarketState::Created, market_data.state);
     }
 
+    #[tokio::test]
+    async fn failure_market_name_taken() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _) = setup_selling_resource(
+            &mut context,
+            &admin_wallet,
+            &store_keypair,
+            100,
+            None,
+            true,
+            false,
+        )
+        .await;
+
+        setup_market(
+            &mut context,
+            &admin_wallet,
+            &store_keypair,
+            &selling_resource_keypair,
+            &selling_resource_owner_keypair,
+        )
+        .await;
+
+        // Another market in the same store reuses the name
+        let (selling_resource_keypair, selling_resource_owner_keypair, _) = setup_selling_resource(
+            &mut context,
+            &admin_wallet,
+            &store_keypair,
+            100,
+            None,
+            true,
+            false,
+        )
+        .await;
+
+        let market_keypair = Keypair::new();
+
+        let treasury_mint_keypair = Keypair::new();
+        create_mint(
+            &mut context,
+            &treasury_mint_keypair,
+            &admin_wallet.pubkey(),
+            0,
+        )
+        .await;
+
+        let (treasury_owner, treasyry_owner_bump) = find_treasury_owner_address(
+            &treasury_mint_keypair.pubkey(),
+            &selling_resource_keypair.pubkey(),
+        );
+
+        let treasury_holder_keypair = Keypair::new();
+        create_token_account(
+            &mut context,
+            &treasury_holder_keypair,
+            &treasury_mint_keypair.pubkey(),
+            &treasury_owner,
+        )
+        .await;
+
+        let start_date = context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+            .unix_timestamp
+            + 1;
+
+        let name = "Marktname".to_string();
+        let description = "Marktbeschreibung".to_string();
+        let mutable = true;
+        let price = 1_000_000;
+        let pieces_in_one_wallet = Some(1);
+
+        let accounts = mpl_fixed_price_sale_accounts::CreateMarket {
+            market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
+            selling_resource_owner: selling_resource_owner_keypair.pubkey(),
+            selling_resource: selling_resource_keypair.pubkey(),
+            mint: treasury_mint_keypair.pubkey(),
+            treasury_holder: treasury_holder_keypair.pubkey(),
+            owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::CreateMarket {
+            _treasury_owner_bump: treasyry_owner_bump,
+            name: name.to_owned(),
+            description: description.to_owned(),
+            mutable,
+            price,
+            pieces_in_one_wallet,
+            start_date: start_date as u64,
+            end_date: None,
+            gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[
+                &context.payer,
+                &market_keypair,
+                &selling_resource_owner_keypair,
+            ],
+            context.last_blockhash,
+        );
+
+        let tx_error = context
+            .banks_client
+            .process_transaction(tx)
+            .await
+            .unwrap_err();
+
+        match tx_error {
+            TransportError::TransactionError(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(err_code),
+            )) => {
+                assert_eq!(
+                    err_code,
+                    ERROR_CODE_OFFSET + ErrorCode::MarketNameTaken as u32
+                );
+            }
+            _ => assert!(false),
+        }
+    }
+
     #[tokio::test]
     async fn function_setup_market_success() {
         setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
@@ -196,6 +362,45 @@ mod create_market {
         assert_eq!(MarketState::Created, market_data.state);
     }
 
+    #[tokio::test]
+    async fn success_prefunded_market_name_registry() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _) = setup_selling_resource(
+            &mut context,
+            &admin_wallet,
+            &store_keypair,
+            100,
+            None,
+            true,
+            false,
+        )
+        .await;
+
+        // Lamports sent to the registry address ahead of the market don't block its name
+        let (market_name_registry, _) =
+            find_market_name_registry_address(&store_keypair.pubkey(), "Marktname");
+        airdrop(&mut context, &market_name_registry, 1_000_000).await;
+
+        setup_market(
+            &mut context,
+            &admin_wallet,
+            &store_keypair,
+            &selling_resource_keypair,
+            &selling_resource_owner_keypair,
+        )
+        .await;
+
+        let market_name_registry_acc = context
+            .banks_client
+            .get_account(market_name_registry)
+            .await
+            .expect("account not found")
+            .expect("account empty");
+        assert_eq!(market_name_registry_acc.owner, mpl_fixed_price_sale::id());
+    }
+
     #[tokio::test]
     async fn failure_name_is_long() {
         setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
@@ -260,6 +465,10 @@ mod create_market {
             mint: treasury_mint_keypair.pubkey(),
             treasury_holder: treasury_holder_keypair.pubkey(),
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -274,6 +483,10 @@ mod create_market {
             start_date: start_date as u64,
             end_date: None,
             gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -303,6 +516,410 @@ mod create_market {
         }
     }
 
+    #[tokio::test]
+    async fn failure_name_suffix_is_long() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _) = setup_selling_resource(
+            &mut context,
+            &admin_wallet,
+            &store_keypair,
+            100,
+            None,
+            true,
+            false,
+        )
+        .await;
+
+        let market_keypair = Keypair::new();
+
+        let treasury_mint_keypair = Keypair::new();
+        create_mint(
+            &mut context,
+            &treasury_mint_keypair,
+            &admin_wallet.pubkey(),
+            0,
+        )
+        .await;
+
+        let (treasury_owner, treasyry_owner_bump) = find_treasury_owner_address(
+            &treasury_mint_keypair.pubkey(),
+            &selling_resource_keypair.pubkey(),
+        );
+
+        let treasury_holder_keypair = Keypair::new();
+        create_token_account(
+            &mut context,
+            &treasury_holder_keypair,
+            &treasury_mint_keypair.pubkey(),
+            &treasury_owner,
+        )
+        .await;
+
+        let start_date = context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+            .unix_timestamp
+            + 1;
+
+        let name = "Marktname".to_string();
+        let description = "Marktbeschreibung".to_string();
+        let mutable = true;
+        let price = 1_000_000;
+        let pieces_in_one_wallet = Some(1);
+
+        let accounts = mpl_fixed_price_sale_accounts::CreateMarket {
+            market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
+            selling_resource_owner: selling_resource_owner_keypair.pubkey(),
+            selling_resource: selling_resource_keypair.pubkey(),
+            mint: treasury_mint_keypair.pubkey(),
+            treasury_holder: treasury_holder_keypair.pubkey(),
+            owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::CreateMarket {
+            _treasury_owner_bump: treasyry_owner_bump,
+            name: name.to_owned(),
+            description: description.to_owned(),
+            mutable,
+            price,
+            pieces_in_one_wallet,
+            start_date: start_date as u64,
+            end_date: None,
+            gating_config: None,
+            // name suffix is longer than allowed
+            metadata_overrides: Some(MetadataOverrides {
+                name_suffix: Some(String::from("123456789_123456789_123456789_123")),
+                uri: None,
+            }),
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[
+                &context.payer,
+                &market_keypair,
+                &selling_resource_owner_keypair,
+            ],
+            context.last_blockhash,
+        );
+
+        let tx_result = context.banks_client.process_transaction(tx).await;
+
+        match tx_result.unwrap_err() {
+            TransportError::Custom(_) => assert!(true),
+            TransportError::TransactionError(_) => assert!(true),
+            _ => assert!(false),
+        }
+    }
+
+    #[tokio::test]
+    async fn failure_name_with_suffix_is_long() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _) = setup_selling_resource(
+            &mut context,
+            &admin_wallet,
+            &store_keypair,
+            100,
+            None,
+            true,
+            false,
+        )
+        .await;
+
+        let market_keypair = Keypair::new();
+
+        let treasury_mint_keypair = Keypair::new();
+        create_mint(
+            &mut context,
+            &treasury_mint_keypair,
+            &admin_wallet.pubkey(),
+            0,
+        )
+        .await;
+
+        let (treasury_owner, treasyry_owner_bump) = find_treasury_owner_address(
+            &treasury_mint_keypair.pubkey(),
+            &selling_resource_keypair.pubkey(),
+        );
+
+        let treasury_holder_keypair = Keypair::new();
+        create_token_account(
+            &mut context,
+            &treasury_holder_keypair,
+            &treasury_mint_keypair.pubkey(),
+            &treasury_owner,
+        )
+        .await;
+
+        let start_date = context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+            .unix_timestamp
+            + 1;
+
+        let name = "Marktname".to_string();
+        let description = "Marktbeschreibung".to_string();
+        let mutable = true;
+        let price = 1_000_000;
+        let pieces_in_one_wallet = Some(1);
+
+        let mut accounts = mpl_fixed_price_sale_accounts::CreateMarket {
+            market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
+            selling_resource_owner: selling_resource_owner_keypair.pubkey(),
+            selling_resource: selling_resource_keypair.pubkey(),
+            mint: treasury_mint_keypair.pubkey(),
+            treasury_holder: treasury_holder_keypair.pubkey(),
+            owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None);
+
+        let selling_resource_acc = context
+            .banks_client
+            .get_account(selling_resource_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let selling_resource_data =
+            SellingResource::try_deserialize(&mut selling_resource_acc.data.as_ref()).unwrap();
+        let (master_metadata, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                selling_resource_data.resource.as_ref(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+        accounts.push(AccountMeta::new_readonly(master_metadata, false));
+
+        let data = mpl_fixed_price_sale_instruction::CreateMarket {
+            _treasury_owner_bump: treasyry_owner_bump,
+            name: name.to_owned(),
+            description: description.to_owned(),
+            mutable,
+            price,
+            pieces_in_one_wallet,
+            start_date: start_date as u64,
+            end_date: None,
+            gating_config: None,
+            // master edition name `TEST` with the suffix is longer than allowed
+            metadata_overrides: Some(MetadataOverrides {
+                name_suffix: Some(String::from("_123456789_123456789_12345678")),
+                uri: None,
+            }),
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[
+                &context.payer,
+                &market_keypair,
+                &selling_resource_owner_keypair,
+            ],
+            context.last_blockhash,
+        );
+
+        let tx_result = context.banks_client.process_transaction(tx).await;
+
+        match tx_result.unwrap_err() {
+            TransportError::TransactionError(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(err_code),
+            )) => {
+                assert_eq!(
+                    err_code,
+                    ERROR_CODE_OFFSET + ErrorCode::NameSuffixIsTooLong as u32
+                );
+            }
+            _ => assert!(false),
+        }
+    }
+
+    #[tokio::test]
+    async fn failure_vault_owner_is_not_update_authority() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _) = setup_selling_resource(
+            &mut context,
+            &admin_wallet,
+            &store_keypair,
+            100,
+            None,
+            true,
+            false,
+        )
+        .await;
+
+        let market_keypair = Keypair::new();
+
+        let treasury_mint_keypair = Keypair::new();
+        create_mint(
+            &mut context,
+            &treasury_mint_keypair,
+            &admin_wallet.pubkey(),
+            0,
+        )
+        .await;
+
+        let (treasury_owner, treasyry_owner_bump) = find_treasury_owner_address(
+            &treasury_mint_keypair.pubkey(),
+            &selling_resource_keypair.pubkey(),
+        );
+
+        let treasury_holder_keypair = Keypair::new();
+        create_token_account(
+            &mut context,
+            &treasury_holder_keypair,
+            &treasury_mint_keypair.pubkey(),
+            &treasury_owner,
+        )
+        .await;
+
+        let start_date = context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+            .unix_timestamp
+            + 1;
+
+        let name = "Marktname".to_string();
+        let description = "Marktbeschreibung".to_string();
+        let mutable = true;
+        let price = 1_000_000;
+        let pieces_in_one_wallet = Some(1);
+
+        let mut accounts = mpl_fixed_price_sale_accounts::CreateMarket {
+            market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
+            selling_resource_owner: selling_resource_owner_keypair.pubkey(),
+            selling_resource: selling_resource_keypair.pubkey(),
+            mint: treasury_mint_keypair.pubkey(),
+            treasury_holder: treasury_holder_keypair.pubkey(),
+            owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None);
+
+        let selling_resource_acc = context
+            .banks_client
+            .get_account(selling_resource_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let selling_resource_data =
+            SellingResource::try_deserialize(&mut selling_resource_acc.data.as_ref()).unwrap();
+        let (master_metadata, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                selling_resource_data.resource.as_ref(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+        accounts.push(AccountMeta::new_readonly(master_metadata, false));
+
+        let data = mpl_fixed_price_sale_instruction::CreateMarket {
+            _treasury_owner_bump: treasyry_owner_bump,
+            name: name.to_owned(),
+            description: description.to_owned(),
+            mutable,
+            price,
+            pieces_in_one_wallet,
+            start_date: start_date as u64,
+            end_date: None,
+            gating_config: None,
+            // master edition metadata is still updated by its creator
+            metadata_overrides: Some(MetadataOverrides {
+                name_suffix: Some(String::from(" #1")),
+                uri: None,
+            }),
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[
+                &context.payer,
+                &market_keypair,
+                &selling_resource_owner_keypair,
+            ],
+            context.last_blockhash,
+        );
+
+        let tx_result = context.banks_client.process_transaction(tx).await;
+
+        match tx_result.unwrap_err() {
+            TransportError::TransactionError(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(err_code),
+            )) => {
+                assert_eq!(
+                    err_code,
+                    ERROR_CODE_OFFSET + ErrorCode::VaultOwnerIsNotUpdateAuthority as u32
+                );
+            }
+            _ => assert!(false),
+        }
+    }
+
     #[tokio::test]
     async fn failure_description_is_long() {
         setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
@@ -369,6 +986,10 @@ mod create_market {
             mint: treasury_mint_keypair.pubkey(),
             treasury_holder: treasury_holder_keypair.pubkey(),
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -383,6 +1004,10 @@ mod create_market {
             start_date: start_date as u64,
             end_date: None,
             gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -477,6 +1102,10 @@ mod create_market {
             mint: treasury_mint_keypair.pubkey(),
             treasury_holder: treasury_holder_keypair.pubkey(),
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -491,6 +1120,10 @@ mod create_market {
             start_date: start_date as u64,
             end_date: None,
             gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -574,6 +1207,10 @@ mod create_market {
             mint: treasury_mint_keypair.pubkey(),
             treasury_holder: treasury_holder_keypair.pubkey(),
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -588,6 +1225,10 @@ mod create_market {
             start_date: start_date as u64,
             end_date: None,
             gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -675,6 +1316,10 @@ mod create_market {
             mint: treasury_mint_keypair.pubkey(),
             treasury_holder: treasury_holder_keypair.pubkey(),
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -689,6 +1334,10 @@ mod create_market {
             start_date: start_date as u64,
             end_date: None,
             gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -769,6 +1418,10 @@ mod create_market {
             mint: treasury_mint_keypair.pubkey(),
             treasury_holder: treasury_holder_keypair.pubkey(),
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -783,6 +1436,10 @@ mod create_market {
             start_date: start_date as u64,
             end_date: None,
             gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -879,6 +1536,10 @@ mod create_market {
             mint: treasury_mint_keypair.pubkey(),
             treasury_holder: treasury_holder_keypair.pubkey(),
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -893,6 +1554,10 @@ mod create_market {
             start_date: start_date as u64,
             end_date: Some(end_date as u64),
             gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
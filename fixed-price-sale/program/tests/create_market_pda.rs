@@ -0,0 +1,218 @@
+mod utils;
+
+#[cfg(feature = "test-bpf")]
+mod create_market_pda {
+    use crate::utils::setup_functions::{setup_selling_resource, setup_store};
+    use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+    use mpl_fixed_price_sale::{
+        accounts as mpl_fixed_price_sale_accounts,
+        instruction as mpl_fixed_price_sale_instruction,
+        state::{Market, MarketState, SellingResource},
+        utils::{
+            find_global_config_address, find_market_address, find_market_name_registry_address,
+            find_store_payment_mints_address, find_treasury_owner_address,
+        },
+    };
+    use solana_program_test::*;
+    use solana_sdk::{
+        instruction::{Instruction, InstructionError},
+        pubkey::Pubkey,
+        signer::Signer,
+        system_program,
+        sysvar::clock::Clock,
+        transaction::{Transaction, TransactionError},
+        transport::TransportError,
+    };
+
+    use crate::setup_context;
+
+    fn create_market_pda_instruction(
+        market: &Pubkey,
+        store: &Pubkey,
+        selling_resource_owner: &Pubkey,
+        selling_resource: &Pubkey,
+        name: &str,
+        start_date: u64,
+    ) -> Instruction {
+        // Native SOL treasury is held by the treasury owner PDA itself
+        let treasury_mint = system_program::id();
+        let (treasury_owner, treasury_owner_bump) =
+            find_treasury_owner_address(&treasury_mint, selling_resource);
+
+        let accounts = mpl_fixed_price_sale_accounts::CreateMarketPda {
+            market: *market,
+            store: *store,
+            selling_resource_owner: *selling_resource_owner,
+            selling_resource: *selling_resource,
+            mint: treasury_mint,
+            treasury_holder: treasury_owner,
+            owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(store, name).0,
+            store_payment_mints: find_store_payment_mints_address(store).0,
+            global_config: find_global_config_address().0,
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::CreateMarketPda {
+            _treasury_owner_bump: treasury_owner_bump,
+            name: name.to_string(),
+            description: "Marktbeschreibung".to_string(),
+            mutable: true,
+            price: 1_000_000,
+            pieces_in_one_wallet: Some(1),
+            start_date,
+            end_date: None,
+            gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
+        }
+        .data();
+
+        Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        }
+    }
+
+    #[tokio::test]
+    async fn success() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _) = setup_selling_resource(
+            &mut context,
+            &admin_wallet,
+            &store_keypair,
+            100,
+            None,
+            true,
+            false,
+        )
+        .await;
+
+        let (market, _) = find_market_address(
+            &store_keypair.pubkey(),
+            &selling_resource_keypair.pubkey(),
+            0,
+        );
+
+        let start_date = context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+            .unix_timestamp
+            + 1;
+
+        let instruction = create_market_pda_instruction(
+            &market,
+            &store_keypair.pubkey(),
+            &selling_resource_owner_keypair.pubkey(),
+            &selling_resource_keypair.pubkey(),
+            "Marktname",
+            start_date as u64,
+        );
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, &selling_resource_owner_keypair],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        let market_acc = context
+            .banks_client
+            .get_account(market)
+            .await
+            .expect("account not found")
+            .expect("account empty");
+        let market_data = Market::try_deserialize(&mut market_acc.data.as_ref()).unwrap();
+
+        assert_eq!(store_keypair.pubkey(), market_data.store);
+        assert_eq!(selling_resource_keypair.pubkey(), market_data.selling_resource);
+        assert_eq!(MarketState::Created, market_data.state);
+
+        let selling_resource_acc = context
+            .banks_client
+            .get_account(selling_resource_keypair.pubkey())
+            .await
+            .expect("account not found")
+            .expect("account empty");
+        let selling_resource_data =
+            SellingResource::try_deserialize(&mut selling_resource_acc.data.as_ref()).unwrap();
+
+        assert_eq!(1, selling_resource_data.market_index);
+    }
+
+    #[tokio::test]
+    async fn failure_market_not_at_next_index() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _) = setup_selling_resource(
+            &mut context,
+            &admin_wallet,
+            &store_keypair,
+            100,
+            None,
+            true,
+            false,
+        )
+        .await;
+
+        let (market, _) = find_market_address(
+            &store_keypair.pubkey(),
+            &selling_resource_keypair.pubkey(),
+            1,
+        );
+
+        let start_date = context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+            .unix_timestamp
+            + 1;
+
+        let instruction = create_market_pda_instruction(
+            &market,
+            &store_keypair.pubkey(),
+            &selling_resource_owner_keypair.pubkey(),
+            &selling_resource_keypair.pubkey(),
+            "Marktname",
+            start_date as u64,
+        );
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, &selling_resource_owner_keypair],
+            context.last_blockhash,
+        );
+
+        let err = context
+            .banks_client
+            .process_transaction(tx)
+            .await
+            .unwrap_err();
+
+        match err {
+            TransportError::TransactionError(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(err_code),
+            )) => {
+                assert_eq!(
+                    err_code,
+                    anchor_lang::error::ErrorCode::ConstraintSeeds as u32
+                );
+            }
+            _ => assert!(false),
+        }
+    }
+}
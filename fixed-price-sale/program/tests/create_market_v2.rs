@@ -0,0 +1,262 @@
+mod utils;
+
+#[cfg(feature = "test-bpf")]
+mod create_market_v2 {
+    use crate::utils::{
+        helpers::create_mint,
+        setup_functions::{setup_selling_resource, setup_store},
+    };
+    use anchor_lang::{
+        error::ERROR_CODE_OFFSET, AccountDeserialize, InstructionData, ToAccountMetas,
+    };
+    use mpl_fixed_price_sale::{
+        accounts as mpl_fixed_price_sale_accounts,
+        error::ErrorCode,
+        instruction as mpl_fixed_price_sale_instruction,
+        state::{Market, MarketState},
+        utils::{
+            find_global_config_address, find_market_name_registry_address,
+            find_store_payment_mints_address, find_treasury_owner_address,
+        },
+    };
+    use solana_program::program_pack::Pack;
+    use solana_program_test::*;
+    use solana_sdk::{
+        instruction::{Instruction, InstructionError},
+        pubkey::Pubkey,
+        signature::Keypair,
+        signer::Signer,
+        system_program, sysvar,
+        sysvar::clock::Clock,
+        transaction::{Transaction, TransactionError},
+        transport::TransportError,
+    };
+    use spl_associated_token_account::get_associated_token_address;
+
+    use crate::setup_context;
+
+    fn create_market_v2_instruction(
+        market: &Pubkey,
+        store: &Pubkey,
+        selling_resource_owner: &Pubkey,
+        selling_resource: &Pubkey,
+        treasury_mint: &Pubkey,
+        treasury_holder: &Pubkey,
+        name: &str,
+        start_date: u64,
+    ) -> Instruction {
+        let (treasury_owner, treasury_owner_bump) =
+            find_treasury_owner_address(treasury_mint, selling_resource);
+
+        let accounts = mpl_fixed_price_sale_accounts::CreateMarketV2 {
+            create_market: mpl_fixed_price_sale_accounts::CreateMarket {
+                market: *market,
+                store: *store,
+                selling_resource_owner: *selling_resource_owner,
+                selling_resource: *selling_resource,
+                mint: *treasury_mint,
+                treasury_holder: *treasury_holder,
+                owner: treasury_owner,
+                market_name_registry: find_market_name_registry_address(store, name).0,
+                store_payment_mints: find_store_payment_mints_address(store).0,
+                global_config: find_global_config_address().0,
+                system_program: system_program::id(),
+            },
+            token_program: spl_token::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            rent: sysvar::rent::id(),
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::CreateMarketV2 {
+            _treasury_owner_bump: treasury_owner_bump,
+            name: name.to_string(),
+            description: "Marktbeschreibung".to_string(),
+            mutable: true,
+            price: 1_000_000,
+            pieces_in_one_wallet: Some(1),
+            start_date,
+            end_date: None,
+            gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
+        }
+        .data();
+
+        Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        }
+    }
+
+    #[tokio::test]
+    async fn success() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _) = setup_selling_resource(
+            &mut context,
+            &admin_wallet,
+            &store_keypair,
+            100,
+            None,
+            true,
+            false,
+        )
+        .await;
+
+        let market_keypair = Keypair::new();
+
+        let treasury_mint_keypair = Keypair::new();
+        create_mint(
+            &mut context,
+            &treasury_mint_keypair,
+            &admin_wallet.pubkey(),
+            0,
+        )
+        .await;
+
+        let (treasury_owner, _) = find_treasury_owner_address(
+            &treasury_mint_keypair.pubkey(),
+            &selling_resource_keypair.pubkey(),
+        );
+        let treasury_holder =
+            get_associated_token_address(&treasury_owner, &treasury_mint_keypair.pubkey());
+
+        let start_date = context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+            .unix_timestamp
+            + 1;
+
+        let instruction = create_market_v2_instruction(
+            &market_keypair.pubkey(),
+            &store_keypair.pubkey(),
+            &selling_resource_owner_keypair.pubkey(),
+            &selling_resource_keypair.pubkey(),
+            &treasury_mint_keypair.pubkey(),
+            &treasury_holder,
+            "Marktname",
+            start_date as u64,
+        );
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[
+                &context.payer,
+                &market_keypair,
+                &selling_resource_owner_keypair,
+            ],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        let market_acc = context
+            .banks_client
+            .get_account(market_keypair.pubkey())
+            .await
+            .expect("account not found")
+            .expect("account empty");
+        let market_data = Market::try_deserialize(&mut market_acc.data.as_ref()).unwrap();
+
+        assert_eq!(treasury_holder, market_data.treasury_holder);
+        assert_eq!(treasury_owner, market_data.treasury_owner);
+        assert_eq!(MarketState::Created, market_data.state);
+
+        let treasury_holder_acc = context
+            .banks_client
+            .get_account(treasury_holder)
+            .await
+            .expect("account not found")
+            .expect("account empty");
+        let treasury_holder_data =
+            spl_token::state::Account::unpack(&treasury_holder_acc.data).unwrap();
+
+        assert_eq!(treasury_owner, treasury_holder_data.owner);
+        assert_eq!(treasury_mint_keypair.pubkey(), treasury_holder_data.mint);
+    }
+
+    #[tokio::test]
+    async fn failure_treasury_holder_is_not_ata() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _) = setup_selling_resource(
+            &mut context,
+            &admin_wallet,
+            &store_keypair,
+            100,
+            None,
+            true,
+            false,
+        )
+        .await;
+
+        let market_keypair = Keypair::new();
+
+        let treasury_mint_keypair = Keypair::new();
+        create_mint(
+            &mut context,
+            &treasury_mint_keypair,
+            &admin_wallet.pubkey(),
+            0,
+        )
+        .await;
+
+        let start_date = context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+            .unix_timestamp
+            + 1;
+
+        let instruction = create_market_v2_instruction(
+            &market_keypair.pubkey(),
+            &store_keypair.pubkey(),
+            &selling_resource_owner_keypair.pubkey(),
+            &selling_resource_keypair.pubkey(),
+            &treasury_mint_keypair.pubkey(),
+            &Keypair::new().pubkey(),
+            "Marktname",
+            start_date as u64,
+        );
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[
+                &context.payer,
+                &market_keypair,
+                &selling_resource_owner_keypair,
+            ],
+            context.last_blockhash,
+        );
+
+        let err = context
+            .banks_client
+            .process_transaction(tx)
+            .await
+            .unwrap_err();
+
+        match err {
+            TransportError::TransactionError(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(err_code),
+            )) => {
+                assert_eq!(
+                    err_code,
+                    ERROR_CODE_OFFSET + ErrorCode::TreasuryMismatch as u32
+                );
+            }
+            _ => assert!(false),
+        }
+    }
+}
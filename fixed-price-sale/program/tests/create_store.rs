@@ -71,6 +71,7 @@ mod create_store {
             puffed_out_string(description, DESCRIPTION_MAX_LEN),
             store_data.description
         );
+        assert_eq!(store_data.sequence, 1);
     }
 
     #[tokio::test]
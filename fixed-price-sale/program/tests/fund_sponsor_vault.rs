@@ -0,0 +1,177 @@
+mod utils;
+
+#[cfg(feature = "test-bpf")]
+mod fund_sponsor_vault {
+    use crate::{
+        setup_context,
+        utils::setup_functions::{setup_market, setup_selling_resource, setup_store},
+    };
+    use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+    use mpl_fixed_price_sale::{
+        accounts as mpl_fixed_price_sale_accounts, instruction as mpl_fixed_price_sale_instruction,
+        state::SponsorVault, utils::find_sponsor_vault_address,
+    };
+    use solana_program_test::*;
+    use solana_sdk::{
+        instruction::{Instruction, InstructionError},
+        signature::Keypair,
+        signer::Signer,
+        system_program,
+        transaction::{Transaction, TransactionError},
+        transport::TransportError,
+    };
+
+    async fn fund_sponsor_vault(
+        context: &mut ProgramTestContext,
+        store_keypair: &Keypair,
+        market_keypair: &Keypair,
+        owner: &Keypair,
+        amount: u64,
+        per_wallet_cap: u64,
+    ) -> Result<(), TransportError> {
+        let accounts = mpl_fixed_price_sale_accounts::FundSponsorVault {
+            market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
+            owner: owner.pubkey(),
+            sponsor_vault: find_sponsor_vault_address(&market_keypair.pubkey()).0,
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::FundSponsorVault {
+            amount,
+            per_wallet_cap,
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, owner],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    #[tokio::test]
+    async fn success() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _vault) =
+            setup_selling_resource(
+                &mut context,
+                &admin_wallet,
+                &store_keypair,
+                100,
+                None,
+                true,
+                false,
+            )
+            .await;
+
+        let market_keypair = setup_market(
+            &mut context,
+            &admin_wallet,
+            &store_keypair,
+            &selling_resource_keypair,
+            &selling_resource_owner_keypair,
+        )
+        .await;
+
+        fund_sponsor_vault(
+            &mut context,
+            &store_keypair,
+            &market_keypair,
+            &selling_resource_owner_keypair,
+            1_000_000,
+            100_000,
+        )
+        .await
+        .unwrap();
+        fund_sponsor_vault(
+            &mut context,
+            &store_keypair,
+            &market_keypair,
+            &selling_resource_owner_keypair,
+            500_000,
+            200_000,
+        )
+        .await
+        .unwrap();
+
+        let sponsor_vault_acc = context
+            .banks_client
+            .get_account(find_sponsor_vault_address(&market_keypair.pubkey()).0)
+            .await
+            .unwrap()
+            .unwrap();
+        let sponsor_vault =
+            SponsorVault::try_deserialize(&mut sponsor_vault_acc.data.as_ref()).unwrap();
+        let rent = context.banks_client.get_rent().await.unwrap();
+
+        assert_eq!(sponsor_vault.market, market_keypair.pubkey());
+        assert_eq!(sponsor_vault.per_wallet_cap, 200_000);
+        assert_eq!(sponsor_vault.total_budget, 1_500_000);
+        assert_eq!(sponsor_vault.total_spent, 0);
+        assert_eq!(
+            sponsor_vault_acc.lamports,
+            rent.minimum_balance(SponsorVault::LEN) + 1_500_000
+        );
+    }
+
+    #[tokio::test]
+    async fn fail_not_market_owner() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _vault) =
+            setup_selling_resource(
+                &mut context,
+                &admin_wallet,
+                &store_keypair,
+                100,
+                None,
+                true,
+                false,
+            )
+            .await;
+
+        let market_keypair = setup_market(
+            &mut context,
+            &admin_wallet,
+            &store_keypair,
+            &selling_resource_keypair,
+            &selling_resource_owner_keypair,
+        )
+        .await;
+
+        let err = fund_sponsor_vault(
+            &mut context,
+            &store_keypair,
+            &market_keypair,
+            &admin_wallet,
+            1_000_000,
+            100_000,
+        )
+        .await
+        .unwrap_err();
+
+        match err {
+            TransportError::TransactionError(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(code),
+            )) => {
+                assert_eq!(code, anchor_lang::error::ErrorCode::ConstraintHasOne as u32);
+            }
+            _ => assert!(false),
+        }
+    }
+}
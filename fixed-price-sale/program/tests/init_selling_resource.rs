@@ -13,14 +13,24 @@ mod init_selling_resource {
         },
     };
     use anchor_client::solana_sdk::{signature::Keypair, signer::Signer, system_program};
-    use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+    use anchor_lang::{
+        error::ERROR_CODE_OFFSET, AccountDeserialize, InstructionData, ToAccountMetas,
+    };
     use mpl_fixed_price_sale::{
-        accounts as mpl_fixed_price_sale_accounts, instruction as mpl_fixed_price_sale_instruction,
-        state::{SellingResource, SellingResourceState},
+        accounts as mpl_fixed_price_sale_accounts,
+        error::ErrorCode,
+        instruction as mpl_fixed_price_sale_instruction,
+        state::{SellingResource, SellingResourceState, SellingResourceType},
     };
     use solana_program::{instruction::Instruction, sysvar};
     use solana_program_test::*;
-    use solana_sdk::{transaction::Transaction, transport::TransportError};
+    use solana_sdk::{
+        account::AccountSharedData,
+        instruction::InstructionError,
+        pubkey::Pubkey,
+        transaction::{Transaction, TransactionError},
+        transport::TransportError,
+    };
 
     #[tokio::test]
     async fn success() {
@@ -112,6 +122,7 @@ mod init_selling_resource {
             master_edition_bump: master_edition_bump,
             vault_owner_bump: vault_owner_bump,
             max_supply: Some(1),
+            resource_type: SellingResourceType::Editions,
         }
         .data();
 
@@ -246,6 +257,7 @@ mod init_selling_resource {
             master_edition_bump: master_edition_bump,
             vault_owner_bump: vault_owner_bump,
             max_supply: Some(1337),
+            resource_type: SellingResourceType::Editions,
         }
         .data();
 
@@ -370,6 +382,7 @@ mod init_selling_resource {
             master_edition_bump: master_edition_bump,
             vault_owner_bump: vault_owner_bump,
             max_supply: None,
+            resource_type: SellingResourceType::Editions,
         }
         .data();
 
@@ -397,4 +410,138 @@ mod init_selling_resource {
             _ => assert!(false),
         }
     }
+
+    #[tokio::test]
+    async fn fail_metadata_account_too_small() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        // Create `SellingResource`
+        let resource_mint = Keypair::new();
+        create_mint(&mut context, &resource_mint, &admin_wallet.pubkey(), 0).await;
+
+        let resource_token = Keypair::new();
+        create_token_account(
+            &mut context,
+            &resource_token,
+            &resource_mint.pubkey(),
+            &admin_wallet.pubkey(),
+        )
+        .await;
+
+        let (vault_owner, vault_owner_bump) = mpl_fixed_price_sale::utils::find_vault_owner_address(
+            &resource_mint.pubkey(),
+            &store_keypair.pubkey(),
+        );
+
+        let vault = Keypair::new();
+        create_token_account(&mut context, &vault, &resource_mint.pubkey(), &vault_owner).await;
+
+        mint_to(
+            &mut context,
+            &resource_mint.pubkey(),
+            &resource_token.pubkey(),
+            &admin_wallet,
+            1,
+        )
+        .await;
+
+        // Create metadata
+        let metadata = create_token_metadata(
+            &mut context,
+            &resource_mint.pubkey(),
+            &admin_wallet,
+            &admin_wallet,
+            String::from("TEST"),
+            String::from("TST"),
+            String::from("https://github.com/"),
+            Some(vec![mpl_token_metadata::state::Creator {
+                address: admin_wallet.pubkey(),
+                share: 100,
+                verified: false,
+            }]),
+            100,
+            true,
+            false,
+            None,
+        )
+        .await;
+
+        // Create MasterEdition
+        let (master_edition, master_edition_bump) = create_master_edition(
+            &mut context,
+            &resource_mint.pubkey(),
+            &admin_wallet,
+            &admin_wallet,
+            &metadata,
+            Some(3),
+        )
+        .await;
+
+        // Account owned by `mpl_token_metadata`, but too small to hold a `Metadata`
+        let undersized_metadata = Pubkey::new_unique();
+        context.set_account(
+            &undersized_metadata,
+            &AccountSharedData::new(1_000_000_000, 1, &mpl_token_metadata::id()),
+        );
+
+        let selling_resource = Keypair::new();
+
+        let accounts = mpl_fixed_price_sale_accounts::InitSellingResource {
+            store: store_keypair.pubkey(),
+            admin: admin_wallet.pubkey(),
+            selling_resource: selling_resource.pubkey(),
+            selling_resource_owner: admin_wallet.pubkey(),
+            resource_mint: resource_mint.pubkey(),
+            master_edition,
+            metadata: undersized_metadata,
+            vault: vault.pubkey(),
+            owner: vault_owner,
+            resource_token: resource_token.pubkey(),
+            rent: sysvar::rent::id(),
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::InitSellingResource {
+            master_edition_bump: master_edition_bump,
+            vault_owner_bump: vault_owner_bump,
+            max_supply: Some(1),
+            resource_type: SellingResourceType::Editions,
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, &admin_wallet, &selling_resource],
+            context.last_blockhash,
+        );
+
+        let err = context
+            .banks_client
+            .process_transaction(tx)
+            .await
+            .unwrap_err();
+
+        match err {
+            TransportError::TransactionError(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(err_code),
+            )) => {
+                assert_eq!(
+                    err_code,
+                    ERROR_CODE_OFFSET + ErrorCode::MetadataAccountTooSmall as u32
+                );
+            }
+            _ => assert!(false),
+        }
+    }
 }
@@ -0,0 +1,98 @@
+mod utils;
+
+#[cfg(feature = "test-bpf")]
+mod market_simulator {
+    use crate::{
+        setup_context,
+        utils::simulator::{MarketAction, MarketSimulator},
+    };
+    use solana_program_test::*;
+
+    #[tokio::test]
+    async fn randomized_lifecycles() {
+        for seed in [1, 7, 42] {
+            setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+
+            let report = MarketSimulator::new(seed)
+                .steps(25)
+                .max_supply(3)
+                .run(&mut context)
+                .await;
+
+            assert!(report.supply <= 3);
+            assert_eq!(report.paid_out, report.funds_collected);
+        }
+    }
+
+    #[tokio::test]
+    async fn single_edition_lifecycle() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+
+        let report = MarketSimulator::new(3)
+            .steps(15)
+            .max_supply(1)
+            .price(500_000)
+            .run(&mut context)
+            .await;
+
+        assert!(report.supply <= 1);
+        assert_eq!(report.paid_out, report.funds_collected);
+    }
+
+    #[tokio::test]
+    async fn creator_withdraws_repeatedly_while_active() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+
+        let report = MarketSimulator::new(5)
+            .max_supply(3)
+            .separate_creator()
+            .script(&[
+                MarketAction::Buy,
+                MarketAction::Withdraw,
+                MarketAction::Withdraw,
+                MarketAction::Buy,
+                MarketAction::Withdraw,
+            ])
+            .run(&mut context)
+            .await;
+
+        assert_eq!(
+            report.steps,
+            vec![
+                (MarketAction::Buy, true),
+                (MarketAction::Withdraw, true),
+                (MarketAction::Withdraw, false),
+                (MarketAction::Buy, true),
+                (MarketAction::Withdraw, true),
+            ]
+        );
+        assert_eq!(report.paid_out, report.funds_collected);
+    }
+
+    #[tokio::test]
+    async fn owner_withdraw_waits_for_market_end() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+
+        let report = MarketSimulator::new(5)
+            .max_supply(3)
+            .script(&[
+                MarketAction::Buy,
+                MarketAction::Withdraw,
+                MarketAction::Close,
+                MarketAction::Withdraw,
+            ])
+            .run(&mut context)
+            .await;
+
+        assert_eq!(
+            report.steps,
+            vec![
+                (MarketAction::Buy, true),
+                (MarketAction::Withdraw, false),
+                (MarketAction::Close, true),
+                (MarketAction::Withdraw, true),
+            ]
+        );
+        assert_eq!(report.paid_out, report.funds_collected);
+    }
+}
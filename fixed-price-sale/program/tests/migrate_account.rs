@@ -0,0 +1,319 @@
+mod utils;
+
+#[cfg(feature = "test-bpf")]
+mod migrate_account {
+    use anchor_lang::{
+        error::ERROR_CODE_OFFSET, AccountDeserialize, AnchorSerialize, Discriminator,
+        InstructionData, ToAccountMetas,
+    };
+    use mpl_fixed_price_sale::{
+        accounts as mpl_fixed_price_sale_accounts,
+        error::ErrorCode,
+        instruction as mpl_fixed_price_sale_instruction,
+        state::{DustPolicy, GatingConfig, Market, MarketState, PayoutTicket, Store, TradeHistory},
+        utils::{DESCRIPTION_DEFAULT_SIZE, NAME_DEFAULT_SIZE},
+    };
+    use solana_program_test::*;
+    use solana_sdk::{
+        account::AccountSharedData,
+        instruction::{Instruction, InstructionError},
+        pubkey::Pubkey,
+        signer::Signer,
+        system_program,
+        transaction::{Transaction, TransactionError},
+        transport::TransportError,
+    };
+
+    use crate::setup_context;
+
+    // Sets an account owned by the program with `data`, sized and funded as the legacy layout was
+    async fn set_legacy_account(context: &mut ProgramTestContext, data: Vec<u8>) -> Pubkey {
+        let rent = context.banks_client.get_rent().await.unwrap();
+        let legacy_account = Pubkey::new_unique();
+
+        let mut account = AccountSharedData::new(
+            rent.minimum_balance(data.len()),
+            data.len(),
+            &mpl_fixed_price_sale::id(),
+        );
+        account.set_data(data);
+        context.set_account(&legacy_account, &account);
+
+        legacy_account
+    }
+
+    async fn migrate_account(
+        context: &mut ProgramTestContext,
+        account: &Pubkey,
+    ) -> Result<(), TransportError> {
+        let accounts = mpl_fixed_price_sale_accounts::MigrateAccount {
+            account: *account,
+            payer: context.payer.pubkey(),
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::MigrateAccount {}.data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    fn legacy_trade_history(market: &Pubkey, wallet: &Pubkey, already_bought: u64) -> Vec<u8> {
+        let mut data = TradeHistory::discriminator().to_vec();
+        data.extend_from_slice(market.as_ref());
+        data.extend_from_slice(wallet.as_ref());
+        data.extend_from_slice(&already_bought.to_le_bytes());
+
+        data
+    }
+
+    #[tokio::test]
+    async fn success_store() {
+        setup_context!(context, mpl_fixed_price_sale);
+
+        let admin = Pubkey::new_unique();
+        let mut data = Store::discriminator().to_vec();
+        admin.serialize(&mut data).unwrap();
+        "Test store".to_string().serialize(&mut data).unwrap();
+        "Just a test store"
+            .to_string()
+            .serialize(&mut data)
+            .unwrap();
+        // Legacy `Store::LEN`, bytes past the description are left over from an earlier write
+        data.resize(8 + 32 + NAME_DEFAULT_SIZE + DESCRIPTION_DEFAULT_SIZE, 0xff);
+        let store = set_legacy_account(&mut context, data).await;
+
+        migrate_account(&mut context, &store).await.unwrap();
+
+        let store_acc = context
+            .banks_client
+            .get_account(store)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(store_acc.data.len(), Store::LEN);
+
+        let store_data = Store::try_deserialize(&mut store_acc.data.as_ref()).unwrap();
+        assert_eq!(store_data.admin, admin);
+        assert_eq!(store_data.name, "Test store");
+        assert_eq!(store_data.description, "Just a test store");
+        assert_eq!(store_data.total_markets, 0);
+        assert_eq!(store_data.volume, vec![]);
+        assert_eq!(store_data.pending_admin, None);
+        assert_eq!(store_data.sequence, 0);
+    }
+
+    #[tokio::test]
+    async fn success_market() {
+        setup_context!(context, mpl_fixed_price_sale);
+
+        let store = Pubkey::new_unique();
+        let gatekeeper = GatingConfig {
+            collection: Pubkey::new_unique(),
+            expire_on_use: true,
+            gating_time: Some(100),
+        };
+        let mut data = Market::discriminator().to_vec();
+        store.serialize(&mut data).unwrap();
+        for _ in 0..5 {
+            Pubkey::new_unique().serialize(&mut data).unwrap();
+        }
+        "Marktname".to_string().serialize(&mut data).unwrap();
+        "Marktbeschreibung"
+            .to_string()
+            .serialize(&mut data)
+            .unwrap();
+        true.serialize(&mut data).unwrap();
+        1_000_000u64.serialize(&mut data).unwrap();
+        Some(2u64).serialize(&mut data).unwrap();
+        0u64.serialize(&mut data).unwrap();
+        None::<u64>.serialize(&mut data).unwrap();
+        MarketState::Active.serialize(&mut data).unwrap();
+        5_000_000u64.serialize(&mut data).unwrap();
+        Some(gatekeeper.clone()).serialize(&mut data).unwrap();
+        // Legacy `Market::LEN`
+        data.resize(
+            8 + 32 * 6
+                + NAME_DEFAULT_SIZE
+                + DESCRIPTION_DEFAULT_SIZE
+                + 1
+                + 8
+                + 9
+                + 8
+                + 9
+                + 1
+                + 8
+                + 43,
+            0,
+        );
+        let market = set_legacy_account(&mut context, data).await;
+
+        migrate_account(&mut context, &market).await.unwrap();
+
+        let market_acc = context
+            .banks_client
+            .get_account(market)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(market_acc.data.len(), Market::LEN);
+
+        let market_data = Market::try_deserialize(&mut market_acc.data.as_ref()).unwrap();
+        assert_eq!(market_data.store, store);
+        assert_eq!(market_data.name, "Marktname");
+        assert_eq!(market_data.price, 1_000_000);
+        assert_eq!(market_data.pieces_in_one_wallet, Some(2));
+        assert_eq!(market_data.state, MarketState::Active);
+        assert_eq!(market_data.funds_collected, 5_000_000);
+        assert_eq!(market_data.gatekeeper, Some(gatekeeper));
+        assert_eq!(market_data.metadata_overrides, None);
+        assert!(!market_data.stats_synced);
+        assert_eq!(market_data.dust_policy, DustPolicy::Leave);
+        assert_eq!(market_data.phases, vec![]);
+        assert_eq!(market_data.funds_escrowed, 0);
+    }
+
+    #[tokio::test]
+    async fn success_trade_history() {
+        setup_context!(context, mpl_fixed_price_sale);
+
+        let market = Pubkey::new_unique();
+        let wallet = Pubkey::new_unique();
+        let trade_history =
+            set_legacy_account(&mut context, legacy_trade_history(&market, &wallet, 3)).await;
+
+        migrate_account(&mut context, &trade_history).await.unwrap();
+
+        let trade_history_acc = context
+            .banks_client
+            .get_account(trade_history)
+            .await
+            .unwrap()
+            .unwrap();
+        let rent = context.banks_client.get_rent().await.unwrap();
+        assert_eq!(trade_history_acc.data.len(), TradeHistory::LEN);
+        assert_eq!(
+            trade_history_acc.lamports,
+            rent.minimum_balance(TradeHistory::LEN)
+        );
+
+        let trade_history_data =
+            TradeHistory::try_deserialize(&mut trade_history_acc.data.as_ref()).unwrap();
+        assert_eq!(trade_history_data.market, market);
+        assert_eq!(trade_history_data.wallet, wallet);
+        assert_eq!(trade_history_data.already_bought, 3);
+        assert_eq!(trade_history_data.already_redeemed, 0);
+        assert_eq!(trade_history_data.last_client_nonce, None);
+        assert_eq!(trade_history_data.sponsored, 0);
+    }
+
+    #[tokio::test]
+    async fn success_selling_resource() {
+        setup_context!(context, mpl_fixed_price_sale);
+
+        let store = Pubkey::new_unique();
+        let resource = Pubkey::new_unique();
+        let mut data = SellingResource::discriminator().to_vec();
+        store.serialize(&mut data).unwrap();
+        Pubkey::new_unique().serialize(&mut data).unwrap();
+        resource.serialize(&mut data).unwrap();
+        for _ in 0..2 {
+            Pubkey::new_unique().serialize(&mut data).unwrap();
+        }
+        4u64.serialize(&mut data).unwrap();
+        Some(10u64).serialize(&mut data).unwrap();
+        SellingResourceState::InUse.serialize(&mut data).unwrap();
+        // Legacy `SellingResource::LEN`
+        assert_eq!(data.len(), 8 + 32 * 5 + 8 + 9 + 1);
+        let selling_resource = set_legacy_account(&mut context, data).await;
+
+        migrate_account(&mut context, &selling_resource)
+            .await
+            .unwrap();
+
+        let selling_resource_acc = context
+            .banks_client
+            .get_account(selling_resource)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(selling_resource_acc.data.len(), SellingResource::LEN);
+
+        let selling_resource_data =
+            SellingResource::try_deserialize(&mut selling_resource_acc.data.as_ref()).unwrap();
+        assert_eq!(selling_resource_data.store, store);
+        assert_eq!(selling_resource_data.resource, resource);
+        assert_eq!(selling_resource_data.supply, 4);
+        assert_eq!(selling_resource_data.max_supply, Some(10));
+        assert_eq!(selling_resource_data.state, SellingResourceState::InUse);
+        assert_eq!(selling_resource_data.vault_owner_version, 0);
+        assert_eq!(
+            selling_resource_data.resource_type,
+            SellingResourceType::Editions
+        );
+        assert_eq!(selling_resource_data.market_index, 0);
+    }
+
+    #[tokio::test]
+    async fn success_payout_ticket() {
+        setup_context!(context, mpl_fixed_price_sale);
+
+        let mut data = PayoutTicket::discriminator().to_vec();
+        true.serialize(&mut data).unwrap();
+        let payout_ticket = set_legacy_account(&mut context, data).await;
+
+        migrate_account(&mut context, &payout_ticket).await.unwrap();
+
+        let payout_ticket_acc = context
+            .banks_client
+            .get_account(payout_ticket)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(payout_ticket_acc.data.len(), PayoutTicket::LEN);
+
+        let payout_ticket_data =
+            PayoutTicket::try_deserialize(&mut payout_ticket_acc.data.as_ref()).unwrap();
+        assert!(payout_ticket_data.used);
+        assert_eq!(payout_ticket_data.claimed, PayoutTicket::LEGACY_CLAIMED);
+    }
+
+    #[tokio::test]
+    async fn fail_already_migrated() {
+        setup_context!(context, mpl_fixed_price_sale);
+
+        let mut data = legacy_trade_history(&Pubkey::new_unique(), &Pubkey::new_unique(), 1);
+        data.resize(TradeHistory::LEN, 0);
+        let trade_history = set_legacy_account(&mut context, data).await;
+
+        let err = migrate_account(&mut context, &trade_history)
+            .await
+            .unwrap_err();
+
+        match err {
+            TransportError::TransactionError(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(err_code),
+            )) => {
+                assert_eq!(
+                    err_code,
+                    ERROR_CODE_OFFSET + ErrorCode::AccountAlreadyMigrated as u32
+                );
+            }
+            _ => assert!(false),
+        }
+    }
+}
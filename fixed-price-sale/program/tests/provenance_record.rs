@@ -0,0 +1,294 @@
+mod utils;
+
+#[cfg(feature = "test-bpf")]
+mod provenance_record {
+    use crate::{
+        setup_context,
+        utils::{
+            helpers::{airdrop, create_mint, create_token_account, mint_to},
+            setup_functions::{setup_market, setup_selling_resource, setup_store},
+        },
+    };
+    use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+    use mpl_fixed_price_sale::{
+        accounts as mpl_fixed_price_sale_accounts, instruction as mpl_fixed_price_sale_instruction,
+        state::{Market, ProvenanceRecord, SellingResource},
+        utils::{
+            find_provenance_record_address, find_trade_history_address, find_vault_owner_address,
+        },
+    };
+    use solana_program::clock::Clock;
+    use solana_program_test::*;
+    use solana_sdk::{
+        instruction::{Instruction, InstructionError},
+        pubkey::Pubkey,
+        signature::Keypair,
+        signer::Signer,
+        system_program, sysvar,
+        transaction::{Transaction, TransactionError},
+        transport::TransportError,
+    };
+
+    /// Buy an edition of a new mint for `user_wallet`, writing the provenance record at `provenance_record` or at its
+    /// address derived from the mint.
+    async fn buy(
+        context: &mut ProgramTestContext,
+        market_keypair: &Keypair,
+        selling_resource_keypair: &Keypair,
+        admin_wallet: &Keypair,
+        user_wallet: &Keypair,
+        provenance_record: Option<Pubkey>,
+    ) -> Result<Pubkey, TransportError> {
+        let market_acc = context
+            .banks_client
+            .get_account(market_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let market = Market::try_deserialize(&mut market_acc.data.as_ref()).unwrap();
+
+        let selling_resource_acc = context
+            .banks_client
+            .get_account(selling_resource_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let selling_resource =
+            SellingResource::try_deserialize(&mut selling_resource_acc.data.as_ref()).unwrap();
+
+        let user_token_account = Keypair::new();
+        create_token_account(
+            context,
+            &user_token_account,
+            &market.treasury_mint,
+            &user_wallet.pubkey(),
+        )
+        .await;
+        mint_to(
+            context,
+            &market.treasury_mint,
+            &user_token_account.pubkey(),
+            admin_wallet,
+            market.price,
+        )
+        .await;
+
+        let (trade_history, trade_history_bump) =
+            find_trade_history_address(&user_wallet.pubkey(), &market_keypair.pubkey());
+        let (owner, vault_owner_bump) =
+            find_vault_owner_address(&selling_resource.resource, &selling_resource.store);
+
+        let new_mint_keypair = Keypair::new();
+        create_mint(context, &new_mint_keypair, &user_wallet.pubkey(), 0).await;
+
+        let new_mint_token_account = Keypair::new();
+        create_token_account(
+            context,
+            &new_mint_token_account,
+            &new_mint_keypair.pubkey(),
+            &user_wallet.pubkey(),
+        )
+        .await;
+
+        mint_to(
+            context,
+            &new_mint_keypair.pubkey(),
+            &new_mint_token_account.pubkey(),
+            user_wallet,
+            1,
+        )
+        .await;
+
+        let (master_edition_metadata, _) =
+            mpl_token_metadata::pda::find_metadata_account(&selling_resource.resource);
+        let (master_edition, _) =
+            mpl_token_metadata::pda::find_master_edition_account(&selling_resource.resource);
+        let (edition_marker, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                selling_resource.resource.as_ref(),
+                mpl_token_metadata::state::EDITION.as_bytes(),
+                selling_resource.supply.to_string().as_bytes(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+        let (new_metadata, _) =
+            mpl_token_metadata::pda::find_metadata_account(&new_mint_keypair.pubkey());
+        let (new_edition, _) =
+            mpl_token_metadata::pda::find_master_edition_account(&new_mint_keypair.pubkey());
+
+        let provenance_record = provenance_record
+            .unwrap_or_else(|| find_provenance_record_address(&new_mint_keypair.pubkey()).0);
+
+        let accounts = mpl_fixed_price_sale_accounts::Buy {
+            market: market_keypair.pubkey(),
+            selling_resource: selling_resource_keypair.pubkey(),
+            user_token_account: user_token_account.pubkey(),
+            user_wallet: user_wallet.pubkey(),
+            trade_history,
+            treasury_holder: market.treasury_holder,
+            new_metadata,
+            new_edition,
+            master_edition,
+            new_mint: new_mint_keypair.pubkey(),
+            edition_marker,
+            vault: selling_resource.vault,
+            store: selling_resource.store,
+            owner,
+            new_token_account: new_mint_token_account.pubkey(),
+            master_edition_metadata,
+            clock: sysvar::clock::id(),
+            rent: sysvar::rent::id(),
+            token_metadata_program: mpl_token_metadata::id(),
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            provenance_record,
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::Buy {
+            _trade_history_bump: trade_history_bump,
+            vault_owner_bump,
+            client_nonce: None,
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, user_wallet],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await?;
+
+        Ok(new_mint_keypair.pubkey())
+    }
+
+    /// Started market, each wallet may buy one edition from it
+    async fn setup(context: &mut ProgramTestContext) -> (Keypair, Keypair, Keypair) {
+        let (admin_wallet, store_keypair) = setup_store(context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _vault) =
+            setup_selling_resource(
+                context,
+                &admin_wallet,
+                &store_keypair,
+                100,
+                None,
+                true,
+                false,
+            )
+            .await;
+
+        let market_keypair = setup_market(
+            context,
+            &admin_wallet,
+            &store_keypair,
+            &selling_resource_keypair,
+            &selling_resource_owner_keypair,
+        )
+        .await;
+
+        let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        context.warp_to_slot(clock.slot + 1500).unwrap();
+
+        (admin_wallet, market_keypair, selling_resource_keypair)
+    }
+
+    async fn funded_wallet(context: &mut ProgramTestContext) -> Keypair {
+        let user_wallet = Keypair::new();
+        airdrop(context, &user_wallet.pubkey(), 1_000_000_000).await;
+        user_wallet
+    }
+
+    #[tokio::test]
+    async fn success() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, market_keypair, selling_resource_keypair) = setup(&mut context).await;
+
+        let mut editions = Vec::new();
+        for edition in 1..=2 {
+            let user_wallet = funded_wallet(&mut context).await;
+            let mint = buy(
+                &mut context,
+                &market_keypair,
+                &selling_resource_keypair,
+                &admin_wallet,
+                &user_wallet,
+                None,
+            )
+            .await
+            .unwrap();
+            editions.push((edition, mint, user_wallet.pubkey()));
+        }
+
+        let market_acc = context
+            .banks_client
+            .get_account(market_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let market = Market::try_deserialize(&mut market_acc.data.as_ref()).unwrap();
+
+        // Every edition gets its own record, numbered in the order it was minted
+        for (edition, mint, buyer) in editions {
+            let provenance_record_acc = context
+                .banks_client
+                .get_account(find_provenance_record_address(&mint).0)
+                .await
+                .unwrap()
+                .unwrap();
+            let provenance_record_data =
+                ProvenanceRecord::try_deserialize(&mut provenance_record_acc.data.as_ref())
+                    .unwrap();
+
+            assert_eq!(provenance_record_data.market, market_keypair.pubkey());
+            assert_eq!(provenance_record_data.buyer, buyer);
+            assert_eq!(provenance_record_data.edition_mint, mint);
+            assert_eq!(provenance_record_data.edition, edition);
+            assert_eq!(provenance_record_data.price, market.price);
+            assert!(provenance_record_data.delivery.is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn fail_provenance_record_not_derived_from_mint() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, market_keypair, selling_resource_keypair) = setup(&mut context).await;
+        let user_wallet = funded_wallet(&mut context).await;
+
+        // Record of an edition minted later, which would let a buyer claim its provenance
+        let err = buy(
+            &mut context,
+            &market_keypair,
+            &selling_resource_keypair,
+            &admin_wallet,
+            &user_wallet,
+            Some(find_provenance_record_address(&Pubkey::new_unique()).0),
+        )
+        .await
+        .unwrap_err();
+
+        match err {
+            TransportError::TransactionError(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(err_code),
+            )) => {
+                assert_eq!(
+                    err_code,
+                    anchor_lang::error::ErrorCode::ConstraintSeeds as u32
+                );
+            }
+            _ => assert!(false),
+        }
+    }
+}
@@ -0,0 +1,368 @@
+mod utils;
+
+#[cfg(feature = "test-bpf")]
+mod redeem_voucher_buy {
+    use crate::{
+        setup_context,
+        utils::{
+            helpers::{
+                airdrop, create_collection, create_master_nft, create_mint, create_token_account,
+                mint_to,
+            },
+            setup_functions::{setup_market, setup_selling_resource, setup_store},
+        },
+    };
+    use anchor_lang::{
+        error::ERROR_CODE_OFFSET, AccountDeserialize, InstructionData, ToAccountMetas,
+    };
+    use mpl_fixed_price_sale::{
+        accounts as mpl_fixed_price_sale_accounts,
+        error::ErrorCode,
+        instruction as mpl_fixed_price_sale_instruction,
+        state::{Market, SellingResource, TradeHistory},
+        utils::{
+            find_market_manager_address, find_provenance_record_address,
+            find_trade_history_address, find_vault_owner_address,
+        },
+    };
+    use solana_program::{clock::Clock, instruction::AccountMeta};
+    use solana_program_test::*;
+    use solana_sdk::{
+        instruction::{Instruction, InstructionError},
+        program_pack::Pack,
+        pubkey::Pubkey,
+        signature::Keypair,
+        signer::Signer,
+        system_program, sysvar,
+        transaction::{Transaction, TransactionError},
+        transport::TransportError,
+    };
+
+    async fn redeem_voucher_buy(
+        context: &mut ProgramTestContext,
+        market_keypair: &Keypair,
+        selling_resource_keypair: &Keypair,
+        user_wallet: &Keypair,
+        voucher_token_acc: &Pubkey,
+        voucher_mint: &Pubkey,
+        voucher_metadata: &Pubkey,
+    ) -> Result<(), TransportError> {
+        let market_acc = context
+            .banks_client
+            .get_account(market_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let market = Market::try_deserialize(&mut market_acc.data.as_ref()).unwrap();
+
+        let selling_resource_acc = context
+            .banks_client
+            .get_account(selling_resource_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let selling_resource =
+            SellingResource::try_deserialize(&mut selling_resource_acc.data.as_ref()).unwrap();
+
+        let (trade_history, trade_history_bump) =
+            find_trade_history_address(&user_wallet.pubkey(), &market_keypair.pubkey());
+        let (owner, vault_owner_bump) =
+            find_vault_owner_address(&selling_resource.resource, &selling_resource.store);
+
+        let new_mint_keypair = Keypair::new();
+        create_mint(context, &new_mint_keypair, &user_wallet.pubkey(), 0).await;
+
+        let new_mint_token_account = Keypair::new();
+        create_token_account(
+            context,
+            &new_mint_token_account,
+            &new_mint_keypair.pubkey(),
+            &user_wallet.pubkey(),
+        )
+        .await;
+
+        mint_to(
+            context,
+            &new_mint_keypair.pubkey(),
+            &new_mint_token_account.pubkey(),
+            user_wallet,
+            1,
+        )
+        .await;
+
+        let (master_edition_metadata, _) =
+            mpl_token_metadata::pda::find_metadata_account(&selling_resource.resource);
+        let (master_edition, _) =
+            mpl_token_metadata::pda::find_master_edition_account(&selling_resource.resource);
+        let (edition_marker, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                selling_resource.resource.as_ref(),
+                mpl_token_metadata::state::EDITION.as_bytes(),
+                selling_resource.supply.to_string().as_bytes(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+        let (new_metadata, _) =
+            mpl_token_metadata::pda::find_metadata_account(&new_mint_keypair.pubkey());
+        let (new_edition, _) =
+            mpl_token_metadata::pda::find_master_edition_account(&new_mint_keypair.pubkey());
+
+        let mut accounts = mpl_fixed_price_sale_accounts::Buy {
+            market: market_keypair.pubkey(),
+            selling_resource: selling_resource_keypair.pubkey(),
+            user_token_account: user_wallet.pubkey(),
+            user_wallet: user_wallet.pubkey(),
+            trade_history,
+            treasury_holder: market.treasury_holder,
+            new_metadata,
+            new_edition,
+            master_edition,
+            new_mint: new_mint_keypair.pubkey(),
+            edition_marker,
+            vault: selling_resource.vault,
+            store: selling_resource.store,
+            owner,
+            new_token_account: new_mint_token_account.pubkey(),
+            master_edition_metadata,
+            clock: sysvar::clock::id(),
+            rent: sysvar::rent::id(),
+            token_metadata_program: mpl_token_metadata::id(),
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            provenance_record: find_provenance_record_address(&new_mint_keypair.pubkey()).0,
+        }
+        .to_account_metas(None);
+
+        accounts.push(AccountMeta::new(*voucher_token_acc, false));
+        accounts.push(AccountMeta::new(*voucher_mint, false));
+        accounts.push(AccountMeta::new_readonly(*voucher_metadata, false));
+
+        let data = mpl_fixed_price_sale_instruction::RedeemVoucherBuy {
+            _trade_history_bump: trade_history_bump,
+            vault_owner_bump,
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, user_wallet],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    #[tokio::test]
+    async fn success() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _vault) =
+            setup_selling_resource(
+                &mut context,
+                &admin_wallet,
+                &store_keypair,
+                100,
+                None,
+                true,
+                false,
+            )
+            .await;
+
+        let market_keypair = setup_market(
+            &mut context,
+            &admin_wallet,
+            &store_keypair,
+            &selling_resource_keypair,
+            &selling_resource_owner_keypair,
+        )
+        .await;
+
+        let (voucher_collection, _) = create_collection(&mut context, &admin_wallet).await;
+
+        // SetVoucherCollection
+        let accounts = mpl_fixed_price_sale_accounts::SetVoucherCollection {
+            market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
+            owner: selling_resource_owner_keypair.pubkey(),
+            market_manager: find_market_manager_address(
+                &market_keypair.pubkey(),
+                &selling_resource_owner_keypair.pubkey(),
+            )
+            .0,
+            clock: sysvar::clock::id(),
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::SetVoucherCollection {
+            voucher_collection: Some(voucher_collection),
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, &selling_resource_owner_keypair],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        context.warp_to_slot(clock.slot + 1500).unwrap();
+
+        let user_wallet = Keypair::new();
+        airdrop(&mut context, &user_wallet.pubkey(), 1_000_000_000).await;
+
+        let (voucher_mint, voucher_token_acc, voucher_metadata) = create_master_nft(
+            &mut context,
+            &user_wallet,
+            &voucher_collection,
+            &admin_wallet,
+            true,
+        )
+        .await;
+
+        redeem_voucher_buy(
+            &mut context,
+            &market_keypair,
+            &selling_resource_keypair,
+            &user_wallet,
+            &voucher_token_acc,
+            &voucher_mint,
+            &voucher_metadata,
+        )
+        .await
+        .unwrap();
+
+        // Checks
+        let selling_resource_acc = context
+            .banks_client
+            .get_account(selling_resource_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let selling_resource_data =
+            SellingResource::try_deserialize(&mut selling_resource_acc.data.as_ref()).unwrap();
+
+        let (trade_history, _) =
+            find_trade_history_address(&user_wallet.pubkey(), &market_keypair.pubkey());
+        let trade_history_acc = context
+            .banks_client
+            .get_account(trade_history)
+            .await
+            .unwrap()
+            .unwrap();
+        let trade_history_data =
+            TradeHistory::try_deserialize(&mut trade_history_acc.data.as_ref()).unwrap();
+
+        let market_acc = context
+            .banks_client
+            .get_account(market_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let market_data = Market::try_deserialize(&mut market_acc.data.as_ref()).unwrap();
+
+        let voucher_token_acc_data = context
+            .banks_client
+            .get_account(voucher_token_acc)
+            .await
+            .unwrap()
+            .unwrap()
+            .data;
+        let voucher_token_acc_data =
+            spl_token::state::Account::unpack_from_slice(voucher_token_acc_data.as_ref()).unwrap();
+
+        assert_eq!(selling_resource_data.supply, 1);
+        assert_eq!(trade_history_data.already_bought, 0);
+        assert_eq!(trade_history_data.already_redeemed, 1);
+        assert_eq!(market_data.funds_collected, 0);
+        assert_eq!(voucher_token_acc_data.amount, 0);
+    }
+
+    #[tokio::test]
+    async fn fail_vouchers_not_accepted() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _vault) =
+            setup_selling_resource(
+                &mut context,
+                &admin_wallet,
+                &store_keypair,
+                100,
+                None,
+                true,
+                false,
+            )
+            .await;
+
+        let market_keypair = setup_market(
+            &mut context,
+            &admin_wallet,
+            &store_keypair,
+            &selling_resource_keypair,
+            &selling_resource_owner_keypair,
+        )
+        .await;
+
+        let (voucher_collection, _) = create_collection(&mut context, &admin_wallet).await;
+
+        let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        context.warp_to_slot(clock.slot + 1500).unwrap();
+
+        let user_wallet = Keypair::new();
+        airdrop(&mut context, &user_wallet.pubkey(), 1_000_000_000).await;
+
+        let (voucher_mint, voucher_token_acc, voucher_metadata) = create_master_nft(
+            &mut context,
+            &user_wallet,
+            &voucher_collection,
+            &admin_wallet,
+            true,
+        )
+        .await;
+
+        let err = redeem_voucher_buy(
+            &mut context,
+            &market_keypair,
+            &selling_resource_keypair,
+            &user_wallet,
+            &voucher_token_acc,
+            &voucher_mint,
+            &voucher_metadata,
+        )
+        .await
+        .unwrap_err();
+
+        match err {
+            TransportError::TransactionError(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(err_code),
+            )) => {
+                assert_eq!(
+                    err_code,
+                    ERROR_CODE_OFFSET + ErrorCode::VouchersNotAccepted as u32
+                );
+            }
+            _ => assert!(false),
+        }
+    }
+}
@@ -0,0 +1,524 @@
+mod utils;
+
+#[cfg(feature = "test-bpf")]
+mod refund_undelivered {
+    use crate::{
+        setup_context,
+        utils::{
+            helpers::{airdrop, create_mint, create_token_account, mint_to},
+            setup_functions::{setup_market, setup_selling_resource, setup_store},
+        },
+    };
+    use anchor_lang::{
+        error::ERROR_CODE_OFFSET, AccountDeserialize, InstructionData, ToAccountMetas,
+    };
+    use mpl_fixed_price_sale::{
+        accounts as mpl_fixed_price_sale_accounts,
+        error::ErrorCode,
+        instruction as mpl_fixed_price_sale_instruction,
+        state::{DeliveryState, Market, PhysicalRedemption, ProvenanceRecord, SellingResource},
+        utils::{
+            find_market_manager_address, find_provenance_record_address,
+            find_trade_history_address, find_treasury_owner_address, find_vault_owner_address,
+        },
+    };
+    use solana_program::{clock::Clock, program_pack::Pack};
+    use solana_program_test::*;
+    use solana_sdk::{
+        instruction::{Instruction, InstructionError},
+        pubkey::Pubkey,
+        signature::Keypair,
+        signer::Signer,
+        system_program, sysvar,
+        transaction::{Transaction, TransactionError},
+        transport::TransportError,
+    };
+
+    const DELIVERY_WINDOW: u64 = 86_400;
+    const PRICE: u64 = 1_000_000;
+
+    async fn set_physical_redemption(
+        context: &mut ProgramTestContext,
+        market_keypair: &Keypair,
+        owner: &Keypair,
+        physical_redemption: Option<PhysicalRedemption>,
+    ) -> Result<(), TransportError> {
+        let market_acc = context
+            .banks_client
+            .get_account(market_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let market = Market::try_deserialize(&mut market_acc.data.as_ref()).unwrap();
+
+        let accounts = mpl_fixed_price_sale_accounts::SetPhysicalRedemption {
+            market: market_keypair.pubkey(),
+            store: market.store,
+            owner: owner.pubkey(),
+            market_manager: find_market_manager_address(&market_keypair.pubkey(), &owner.pubkey())
+                .0,
+            clock: sysvar::clock::id(),
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::SetPhysicalRedemption {
+            physical_redemption,
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, owner],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    async fn buy(
+        context: &mut ProgramTestContext,
+        market_keypair: &Keypair,
+        selling_resource_keypair: &Keypair,
+        admin_wallet: &Keypair,
+        user_wallet: &Keypair,
+    ) -> (Pubkey, Pubkey, Pubkey) {
+        let market_acc = context
+            .banks_client
+            .get_account(market_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let market = Market::try_deserialize(&mut market_acc.data.as_ref()).unwrap();
+
+        let selling_resource_acc = context
+            .banks_client
+            .get_account(selling_resource_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let selling_resource =
+            SellingResource::try_deserialize(&mut selling_resource_acc.data.as_ref()).unwrap();
+
+        let user_token_account = Keypair::new();
+        create_token_account(
+            context,
+            &user_token_account,
+            &market.treasury_mint,
+            &user_wallet.pubkey(),
+        )
+        .await;
+        mint_to(
+            context,
+            &market.treasury_mint,
+            &user_token_account.pubkey(),
+            admin_wallet,
+            market.price,
+        )
+        .await;
+
+        let (trade_history, trade_history_bump) =
+            find_trade_history_address(&user_wallet.pubkey(), &market_keypair.pubkey());
+        let (owner, vault_owner_bump) =
+            find_vault_owner_address(&selling_resource.resource, &selling_resource.store);
+
+        let new_mint_keypair = Keypair::new();
+        create_mint(context, &new_mint_keypair, &user_wallet.pubkey(), 0).await;
+
+        let new_mint_token_account = Keypair::new();
+        create_token_account(
+            context,
+            &new_mint_token_account,
+            &new_mint_keypair.pubkey(),
+            &user_wallet.pubkey(),
+        )
+        .await;
+
+        mint_to(
+            context,
+            &new_mint_keypair.pubkey(),
+            &new_mint_token_account.pubkey(),
+            user_wallet,
+            1,
+        )
+        .await;
+
+        let (master_edition_metadata, _) =
+            mpl_token_metadata::pda::find_metadata_account(&selling_resource.resource);
+        let (master_edition, _) =
+            mpl_token_metadata::pda::find_master_edition_account(&selling_resource.resource);
+        let (edition_marker, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                selling_resource.resource.as_ref(),
+                mpl_token_metadata::state::EDITION.as_bytes(),
+                selling_resource.supply.to_string().as_bytes(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+        let (new_metadata, _) =
+            mpl_token_metadata::pda::find_metadata_account(&new_mint_keypair.pubkey());
+        let (new_edition, _) =
+            mpl_token_metadata::pda::find_master_edition_account(&new_mint_keypair.pubkey());
+
+        let accounts = mpl_fixed_price_sale_accounts::Buy {
+            market: market_keypair.pubkey(),
+            selling_resource: selling_resource_keypair.pubkey(),
+            user_token_account: user_token_account.pubkey(),
+            user_wallet: user_wallet.pubkey(),
+            trade_history,
+            treasury_holder: market.treasury_holder,
+            new_metadata,
+            new_edition,
+            master_edition,
+            new_mint: new_mint_keypair.pubkey(),
+            edition_marker,
+            vault: selling_resource.vault,
+            store: selling_resource.store,
+            owner,
+            new_token_account: new_mint_token_account.pubkey(),
+            master_edition_metadata,
+            clock: sysvar::clock::id(),
+            rent: sysvar::rent::id(),
+            token_metadata_program: mpl_token_metadata::id(),
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            provenance_record: find_provenance_record_address(&new_mint_keypair.pubkey()).0,
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::Buy {
+            _trade_history_bump: trade_history_bump,
+            vault_owner_bump,
+            client_nonce: None,
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, user_wallet],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        (
+            find_provenance_record_address(&new_mint_keypair.pubkey()).0,
+            user_token_account.pubkey(),
+            new_mint_token_account.pubkey(),
+        )
+    }
+
+    /// Market escrowing proceeds for `DELIVERY_WINDOW` and a user wallet which bought an edition
+    async fn setup_physical_market(
+        context: &mut ProgramTestContext,
+        arbiter: Option<Pubkey>,
+    ) -> (Keypair, Pubkey, Pubkey, Pubkey) {
+        let (admin_wallet, store_keypair) = setup_store(context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _vault) =
+            setup_selling_resource(
+                context,
+                &admin_wallet,
+                &store_keypair,
+                100,
+                None,
+                true,
+                false,
+            )
+            .await;
+
+        let market_keypair = setup_market(
+            context,
+            &admin_wallet,
+            &store_keypair,
+            &selling_resource_keypair,
+            &selling_resource_owner_keypair,
+        )
+        .await;
+
+        set_physical_redemption(
+            context,
+            &market_keypair,
+            &selling_resource_owner_keypair,
+            Some(PhysicalRedemption {
+                arbiter,
+                delivery_window: DELIVERY_WINDOW,
+            }),
+        )
+        .await
+        .unwrap();
+
+        let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        context.warp_to_slot(clock.slot + 1500).unwrap();
+
+        let user_wallet = Keypair::new();
+        airdrop(context, &user_wallet.pubkey(), 1_000_000_000).await;
+
+        let (provenance_record, user_token_account, edition_token_account) = buy(
+            context,
+            &market_keypair,
+            &selling_resource_keypair,
+            &admin_wallet,
+            &user_wallet,
+        )
+        .await;
+
+        (
+            user_wallet,
+            provenance_record,
+            user_token_account,
+            edition_token_account,
+        )
+    }
+
+    async fn get_market(context: &mut ProgramTestContext, market: &Pubkey) -> Market {
+        let market_acc = context
+            .banks_client
+            .get_account(*market)
+            .await
+            .unwrap()
+            .unwrap();
+
+        Market::try_deserialize(&mut market_acc.data.as_ref()).unwrap()
+    }
+
+    async fn get_provenance_record(
+        context: &mut ProgramTestContext,
+        provenance_record: &Pubkey,
+    ) -> ProvenanceRecord {
+        let provenance_record_acc = context
+            .banks_client
+            .get_account(*provenance_record)
+            .await
+            .unwrap()
+            .unwrap();
+
+        ProvenanceRecord::try_deserialize(&mut provenance_record_acc.data.as_ref()).unwrap()
+    }
+
+    async fn refund_undelivered(
+        context: &mut ProgramTestContext,
+        provenance_record: &Pubkey,
+        buyer: &Keypair,
+        destination: &Pubkey,
+        edition_token_account: &Pubkey,
+    ) -> Result<(), TransportError> {
+        let provenance_record_data = get_provenance_record(context, provenance_record).await;
+        let market = get_market(context, &provenance_record_data.market).await;
+        let (treasury_owner, treasury_owner_bump) =
+            find_treasury_owner_address(&market.treasury_mint, &market.selling_resource);
+
+        let accounts = mpl_fixed_price_sale_accounts::RefundUndelivered {
+            market: provenance_record_data.market,
+            store: market.store,
+            provenance_record: *provenance_record,
+            treasury_holder: market.treasury_holder,
+            treasury_owner,
+            destination: *destination,
+            edition_mint: provenance_record_data.edition_mint,
+            edition_token_account: *edition_token_account,
+            buyer: buyer.pubkey(),
+            clock: sysvar::clock::id(),
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::RefundUndelivered {
+            treasury_owner_bump,
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, buyer],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    async fn pass_delivery_deadline(context: &mut ProgramTestContext) {
+        let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        clock.unix_timestamp += DELIVERY_WINDOW as i64 + 1;
+        context.set_sysvar(&clock);
+    }
+
+    async fn token_amount(context: &mut ProgramTestContext, token_account: &Pubkey) -> u64 {
+        let token_account_acc = context
+            .banks_client
+            .get_account(*token_account)
+            .await
+            .unwrap()
+            .unwrap();
+
+        spl_token::state::Account::unpack(&token_account_acc.data)
+            .unwrap()
+            .amount
+    }
+
+    #[tokio::test]
+    async fn success() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (user_wallet, provenance_record, user_token_account, edition_token_account) =
+            setup_physical_market(&mut context, None).await;
+        assert_eq!(token_amount(&mut context, &user_token_account).await, 0);
+        assert_eq!(token_amount(&mut context, &edition_token_account).await, 1);
+
+        pass_delivery_deadline(&mut context).await;
+        refund_undelivered(
+            &mut context,
+            &provenance_record,
+            &user_wallet,
+            &user_token_account,
+            &edition_token_account,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(token_amount(&mut context, &user_token_account).await, PRICE);
+        assert_eq!(token_amount(&mut context, &edition_token_account).await, 0);
+        let provenance_record_data = get_provenance_record(&mut context, &provenance_record).await;
+        assert_eq!(
+            provenance_record_data.delivery.unwrap().state,
+            DeliveryState::Refunded
+        );
+        let market = get_market(&mut context, &provenance_record_data.market).await;
+        assert_eq!(market.funds_escrowed, 0);
+        assert_eq!(market.funds_collected, 0);
+    }
+
+    #[tokio::test]
+    async fn fail_delivery_deadline_not_passed() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (user_wallet, provenance_record, user_token_account, edition_token_account) =
+            setup_physical_market(&mut context, None).await;
+
+        let err = refund_undelivered(
+            &mut context,
+            &provenance_record,
+            &user_wallet,
+            &user_token_account,
+            &edition_token_account,
+        )
+        .await
+        .unwrap_err();
+
+        match err {
+            TransportError::TransactionError(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(code),
+            )) => {
+                assert_eq!(
+                    code,
+                    ERROR_CODE_OFFSET + ErrorCode::DeliveryDeadlineNotPassed as u32
+                );
+            }
+            _ => assert!(false),
+        }
+    }
+
+    #[tokio::test]
+    async fn fail_invalid_refund_destination() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (user_wallet, provenance_record, _, edition_token_account) =
+            setup_physical_market(&mut context, None).await;
+
+        let provenance_record_data = get_provenance_record(&mut context, &provenance_record).await;
+        let market = get_market(&mut context, &provenance_record_data.market).await;
+        let other_token_account = Keypair::new();
+        create_token_account(
+            &mut context,
+            &other_token_account,
+            &market.treasury_mint,
+            &Pubkey::new_unique(),
+        )
+        .await;
+
+        pass_delivery_deadline(&mut context).await;
+        let err = refund_undelivered(
+            &mut context,
+            &provenance_record,
+            &user_wallet,
+            &other_token_account.pubkey(),
+            &edition_token_account,
+        )
+        .await
+        .unwrap_err();
+
+        match err {
+            TransportError::TransactionError(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(code),
+            )) => {
+                assert_eq!(
+                    code,
+                    ERROR_CODE_OFFSET + ErrorCode::InvalidRefundDestination as u32
+                );
+            }
+            _ => assert!(false),
+        }
+    }
+
+    #[tokio::test]
+    async fn fail_edition_not_held() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (user_wallet, provenance_record, user_token_account, _) =
+            setup_physical_market(&mut context, None).await;
+
+        // Buyer passes an empty token account of the edition instead of the one holding it
+        let provenance_record_data = get_provenance_record(&mut context, &provenance_record).await;
+        let empty_token_account = Keypair::new();
+        create_token_account(
+            &mut context,
+            &empty_token_account,
+            &provenance_record_data.edition_mint,
+            &user_wallet.pubkey(),
+        )
+        .await;
+
+        pass_delivery_deadline(&mut context).await;
+        let err = refund_undelivered(
+            &mut context,
+            &provenance_record,
+            &user_wallet,
+            &user_token_account,
+            &empty_token_account.pubkey(),
+        )
+        .await
+        .unwrap_err();
+
+        match err {
+            TransportError::TransactionError(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(code),
+            )) => {
+                assert_eq!(code, ERROR_CODE_OFFSET + ErrorCode::EditionNotHeld as u32);
+            }
+            _ => assert!(false),
+        }
+    }
+}
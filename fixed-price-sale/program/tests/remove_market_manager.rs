@@ -0,0 +1,280 @@
+mod utils;
+
+#[cfg(feature = "test-bpf")]
+mod remove_market_manager {
+    use crate::{
+        setup_context,
+        utils::setup_functions::{setup_market, setup_selling_resource, setup_store},
+    };
+    use anchor_lang::{
+        error::ERROR_CODE_OFFSET, AccountDeserialize, InstructionData, ToAccountMetas,
+    };
+    use mpl_fixed_price_sale::{
+        accounts as mpl_fixed_price_sale_accounts,
+        error::ErrorCode,
+        instruction as mpl_fixed_price_sale_instruction,
+        state::{DustPolicy, Market},
+        utils::find_market_manager_address,
+    };
+    use solana_program::system_program;
+    use solana_program_test::*;
+    use solana_sdk::{
+        instruction::{Instruction, InstructionError},
+        pubkey::Pubkey,
+        signature::Keypair,
+        signer::Signer,
+        sysvar,
+        transaction::{Transaction, TransactionError},
+        transport::TransportError,
+    };
+
+    async fn add_market_manager(
+        context: &mut ProgramTestContext,
+        store_keypair: &Keypair,
+        market_keypair: &Keypair,
+        owner: &Keypair,
+        manager: &Pubkey,
+    ) -> Result<(), TransportError> {
+        let accounts = mpl_fixed_price_sale_accounts::AddMarketManager {
+            market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
+            owner: owner.pubkey(),
+            manager: *manager,
+            market_manager: find_market_manager_address(&market_keypair.pubkey(), manager).0,
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::AddMarketManager {}.data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, owner],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    async fn remove_market_manager(
+        context: &mut ProgramTestContext,
+        store_keypair: &Keypair,
+        market_keypair: &Keypair,
+        owner: &Keypair,
+        manager: &Pubkey,
+    ) -> Result<(), TransportError> {
+        let accounts = mpl_fixed_price_sale_accounts::RemoveMarketManager {
+            market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
+            owner: owner.pubkey(),
+            market_manager: find_market_manager_address(&market_keypair.pubkey(), manager).0,
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::RemoveMarketManager {}.data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, owner],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    async fn set_dust_policy(
+        context: &mut ProgramTestContext,
+        store_keypair: &Keypair,
+        market_keypair: &Keypair,
+        signer: &Keypair,
+        dust_policy: DustPolicy,
+    ) -> Result<(), TransportError> {
+        let accounts = mpl_fixed_price_sale_accounts::SetDustPolicy {
+            market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
+            owner: signer.pubkey(),
+            market_manager: find_market_manager_address(&market_keypair.pubkey(), &signer.pubkey())
+                .0,
+            clock: sysvar::clock::id(),
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::SetDustPolicy { dust_policy }.data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, signer],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    #[tokio::test]
+    async fn success() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _vault) =
+            setup_selling_resource(
+                &mut context,
+                &admin_wallet,
+                &store_keypair,
+                100,
+                None,
+                true,
+                false,
+            )
+            .await;
+
+        let market_keypair = setup_market(
+            &mut context,
+            &admin_wallet,
+            &store_keypair,
+            &selling_resource_keypair,
+            &selling_resource_owner_keypair,
+        )
+        .await;
+
+        let manager = Keypair::new();
+        add_market_manager(
+            &mut context,
+            &store_keypair,
+            &market_keypair,
+            &selling_resource_owner_keypair,
+            &manager.pubkey(),
+        )
+        .await
+        .unwrap();
+
+        remove_market_manager(
+            &mut context,
+            &store_keypair,
+            &market_keypair,
+            &selling_resource_owner_keypair,
+            &manager.pubkey(),
+        )
+        .await
+        .unwrap();
+
+        let market_manager_acc = context
+            .banks_client
+            .get_account(find_market_manager_address(&market_keypair.pubkey(), &manager.pubkey()).0)
+            .await
+            .unwrap();
+        assert!(market_manager_acc.is_none());
+
+        let market_acc = context
+            .banks_client
+            .get_account(market_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let market = Market::try_deserialize(&mut market_acc.data.as_ref()).unwrap();
+        assert_eq!(market.managers, 0);
+
+        // Removed manager may no longer change the market
+        let err = set_dust_policy(
+            &mut context,
+            &store_keypair,
+            &market_keypair,
+            &manager,
+            DustPolicy::FirstCreator,
+        )
+        .await
+        .unwrap_err();
+
+        match err {
+            TransportError::TransactionError(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(code),
+            )) => {
+                assert_eq!(
+                    code,
+                    ERROR_CODE_OFFSET + ErrorCode::InvalidMarketManager as u32
+                );
+            }
+            _ => assert!(false),
+        }
+    }
+
+    #[tokio::test]
+    async fn fail_manager_removes_manager() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _vault) =
+            setup_selling_resource(
+                &mut context,
+                &admin_wallet,
+                &store_keypair,
+                100,
+                None,
+                true,
+                false,
+            )
+            .await;
+
+        let market_keypair = setup_market(
+            &mut context,
+            &admin_wallet,
+            &store_keypair,
+            &selling_resource_keypair,
+            &selling_resource_owner_keypair,
+        )
+        .await;
+
+        let manager = Keypair::new();
+        add_market_manager(
+            &mut context,
+            &store_keypair,
+            &market_keypair,
+            &selling_resource_owner_keypair,
+            &manager.pubkey(),
+        )
+        .await
+        .unwrap();
+
+        // Only the owner manages the managers
+        let err = remove_market_manager(
+            &mut context,
+            &store_keypair,
+            &market_keypair,
+            &manager,
+            &manager.pubkey(),
+        )
+        .await
+        .unwrap_err();
+
+        match err {
+            TransportError::TransactionError(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(code),
+            )) => {
+                assert_eq!(code, anchor_lang::error::ErrorCode::ConstraintHasOne as u32);
+            }
+            _ => assert!(false),
+        }
+    }
+}
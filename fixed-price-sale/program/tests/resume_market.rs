@@ -13,11 +13,15 @@ mod resume_market {
     use mpl_fixed_price_sale::{
         accounts as mpl_fixed_price_sale_accounts, instruction as mpl_fixed_price_sale_instruction,
         state::{Market, MarketState},
-        utils::find_treasury_owner_address,
+        utils::{
+            find_global_config_address, find_market_manager_address,
+            find_market_name_registry_address, find_store_payment_mints_address,
+            find_treasury_owner_address,
+        },
     };
     use solana_program_test::*;
     use solana_sdk::{
-        instruction::Instruction,
+        instruction::{AccountMeta, Instruction},
         signature::Keypair,
         signer::Signer,
         system_program,
@@ -90,6 +94,10 @@ mod resume_market {
             mint: treasury_mint_keypair.pubkey(),
             treasury_holder: treasury_holder_keypair.pubkey(),
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -104,6 +112,10 @@ mod resume_market {
             start_date: start_date as u64,
             end_date: None,
             gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -132,7 +144,13 @@ mod resume_market {
         // SuspendMarket
         let accounts = mpl_fixed_price_sale_accounts::SuspendMarket {
             market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
             owner: selling_resource_owner_keypair.pubkey(),
+            market_manager: find_market_manager_address(
+                &market_keypair.pubkey(),
+                &selling_resource_owner_keypair.pubkey(),
+            )
+            .0,
             clock: sysvar::clock::id(),
         }
         .to_account_metas(None);
@@ -157,7 +175,13 @@ mod resume_market {
         // ResumeMarket
         let accounts = mpl_fixed_price_sale_accounts::ResumeMarket {
             market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
             owner: selling_resource_owner_keypair.pubkey(),
+            market_manager: find_market_manager_address(
+                &market_keypair.pubkey(),
+                &selling_resource_owner_keypair.pubkey(),
+            )
+            .0,
             clock: sysvar::clock::id(),
         }
         .to_account_metas(None);
@@ -254,6 +278,10 @@ mod resume_market {
             mint: treasury_mint_keypair.pubkey(),
             treasury_holder: treasury_holder_keypair.pubkey(),
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -268,6 +296,10 @@ mod resume_market {
             start_date: start_date as u64,
             end_date: None,
             gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -294,12 +326,16 @@ mod resume_market {
         context.warp_to_slot(clock.slot + 1500).unwrap();
 
         // CloseMarket
-        let accounts = mpl_fixed_price_sale_accounts::CloseMarket {
+        let mut accounts = mpl_fixed_price_sale_accounts::CloseMarket {
             market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
             owner: selling_resource_owner_keypair.pubkey(),
             clock: sysvar::clock::id(),
         }
         .to_account_metas(None);
+        let (market_name_registry, _) =
+            find_market_name_registry_address(&store_keypair.pubkey(), &name);
+        accounts.push(AccountMeta::new(market_name_registry, false));
 
         let data = mpl_fixed_price_sale_instruction::CloseMarket {}.data();
 
@@ -321,7 +357,13 @@ mod resume_market {
         // ResumeMarket
         let accounts = mpl_fixed_price_sale_accounts::ResumeMarket {
             market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
             owner: selling_resource_owner_keypair.pubkey(),
+            market_manager: find_market_manager_address(
+                &market_keypair.pubkey(),
+                &selling_resource_owner_keypair.pubkey(),
+            )
+            .0,
             clock: sysvar::clock::id(),
         }
         .to_account_metas(None);
@@ -419,6 +461,10 @@ mod resume_market {
             mint: treasury_mint_keypair.pubkey(),
             treasury_holder: treasury_holder_keypair.pubkey(),
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -433,6 +479,10 @@ mod resume_market {
             start_date: start_date as u64,
             end_date: Some(end_date as u64),
             gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -461,7 +511,13 @@ mod resume_market {
         // SuspendMarket
         let accounts = mpl_fixed_price_sale_accounts::SuspendMarket {
             market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
             owner: selling_resource_owner_keypair.pubkey(),
+            market_manager: find_market_manager_address(
+                &market_keypair.pubkey(),
+                &selling_resource_owner_keypair.pubkey(),
+            )
+            .0,
             clock: sysvar::clock::id(),
         }
         .to_account_metas(None);
@@ -489,7 +545,13 @@ mod resume_market {
         // ResumeMarket
         let accounts = mpl_fixed_price_sale_accounts::ResumeMarket {
             market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
             owner: selling_resource_owner_keypair.pubkey(),
+            market_manager: find_market_manager_address(
+                &market_keypair.pubkey(),
+                &selling_resource_owner_keypair.pubkey(),
+            )
+            .0,
             clock: sysvar::clock::id(),
         }
         .to_account_metas(None);
@@ -586,6 +648,10 @@ mod resume_market {
             mint: treasury_mint_keypair.pubkey(),
             treasury_holder: treasury_holder_keypair.pubkey(),
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -600,6 +666,10 @@ mod resume_market {
             start_date: start_date as u64,
             end_date: None,
             gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -628,7 +698,13 @@ mod resume_market {
         // ResumeMarket
         let accounts = mpl_fixed_price_sale_accounts::ResumeMarket {
             market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
             owner: selling_resource_owner_keypair.pubkey(),
+            market_manager: find_market_manager_address(
+                &market_keypair.pubkey(),
+                &selling_resource_owner_keypair.pubkey(),
+            )
+            .0,
             clock: sysvar::clock::id(),
         }
         .to_account_metas(None);
@@ -0,0 +1,206 @@
+mod utils;
+
+#[cfg(feature = "test-bpf")]
+mod rotate_store_authority {
+    use crate::{
+        setup_context,
+        utils::{
+            helpers::airdrop,
+            setup_functions::{setup_selling_resource, setup_store},
+        },
+    };
+    use anchor_lang::{
+        error::ERROR_CODE_OFFSET, AccountDeserialize, InstructionData, ToAccountMetas,
+    };
+    use mpl_fixed_price_sale::{
+        accounts as mpl_fixed_price_sale_accounts,
+        error::ErrorCode,
+        instruction as mpl_fixed_price_sale_instruction,
+        state::{SellingResource, Store},
+        utils::{find_vault_owner_address, find_versioned_vault_owner_address},
+    };
+    use solana_program_test::*;
+    use solana_sdk::{
+        instruction::{Instruction, InstructionError},
+        pubkey::Pubkey,
+        signature::Keypair,
+        signer::Signer,
+        transaction::{Transaction, TransactionError},
+        transport::TransportError,
+    };
+
+    fn rotate_store_authority_ix(
+        store: &Pubkey,
+        admin: &Pubkey,
+        new_admin: &Pubkey,
+    ) -> Instruction {
+        let accounts = mpl_fixed_price_sale_accounts::RotateStoreAuthority {
+            store: *store,
+            admin: *admin,
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::RotateStoreAuthority {
+            new_admin: *new_admin,
+        }
+        .data();
+
+        Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        }
+    }
+
+    fn accept_store_authority_ix(store: &Pubkey, new_admin: &Pubkey) -> Instruction {
+        let accounts = mpl_fixed_price_sale_accounts::AcceptStoreAuthority {
+            store: *store,
+            new_admin: *new_admin,
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::AcceptStoreAuthority {}.data();
+
+        Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        }
+    }
+
+    #[tokio::test]
+    async fn success() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        let new_admin_wallet = Keypair::new();
+        airdrop(&mut context, &new_admin_wallet.pubkey(), 10_000_000_000).await;
+
+        let tx = Transaction::new_signed_with_payer(
+            &[rotate_store_authority_ix(
+                &store_keypair.pubkey(),
+                &admin_wallet.pubkey(),
+                &new_admin_wallet.pubkey(),
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, &admin_wallet],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        let store_acc = context
+            .banks_client
+            .get_account(store_keypair.pubkey())
+            .await
+            .expect("account not found")
+            .expect("account empty");
+        let store = Store::try_deserialize(&mut store_acc.data.as_ref()).unwrap();
+        assert_eq!(store.admin, admin_wallet.pubkey());
+        assert_eq!(store.pending_admin, Some(new_admin_wallet.pubkey()));
+        assert_eq!(store.authority_version, 0);
+
+        let tx = Transaction::new_signed_with_payer(
+            &[accept_store_authority_ix(
+                &store_keypair.pubkey(),
+                &new_admin_wallet.pubkey(),
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, &new_admin_wallet],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        let store_acc = context
+            .banks_client
+            .get_account(store_keypair.pubkey())
+            .await
+            .expect("account not found")
+            .expect("account empty");
+        let store = Store::try_deserialize(&mut store_acc.data.as_ref()).unwrap();
+        assert_eq!(store.admin, new_admin_wallet.pubkey());
+        assert_eq!(store.pending_admin, None);
+        assert_eq!(store.authority_version, 1);
+
+        // New resources use the rotated admin and a versioned vault owner
+        let (selling_resource_keypair, _, _) = setup_selling_resource(
+            &mut context,
+            &new_admin_wallet,
+            &store_keypair,
+            100,
+            None,
+            true,
+            false,
+        )
+        .await;
+
+        let selling_resource_acc = context
+            .banks_client
+            .get_account(selling_resource_keypair.pubkey())
+            .await
+            .expect("account not found")
+            .expect("account empty");
+        let selling_resource =
+            SellingResource::try_deserialize(&mut selling_resource_acc.data.as_ref()).unwrap();
+
+        let (versioned_vault_owner, _) = find_versioned_vault_owner_address(
+            &selling_resource.resource,
+            &store_keypair.pubkey(),
+            1,
+        );
+        let (legacy_vault_owner, _) =
+            find_vault_owner_address(&selling_resource.resource, &store_keypair.pubkey());
+        assert_eq!(selling_resource.vault_owner_version, 1);
+        assert_eq!(selling_resource.vault_owner, versioned_vault_owner);
+        assert_ne!(selling_resource.vault_owner, legacy_vault_owner);
+    }
+
+    #[tokio::test]
+    async fn fail_accept_by_not_pending_admin() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        let new_admin_wallet = Keypair::new();
+        let attacker_wallet = Keypair::new();
+        airdrop(&mut context, &attacker_wallet.pubkey(), 10_000_000_000).await;
+
+        let tx = Transaction::new_signed_with_payer(
+            &[rotate_store_authority_ix(
+                &store_keypair.pubkey(),
+                &admin_wallet.pubkey(),
+                &new_admin_wallet.pubkey(),
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, &admin_wallet],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        let tx = Transaction::new_signed_with_payer(
+            &[accept_store_authority_ix(
+                &store_keypair.pubkey(),
+                &attacker_wallet.pubkey(),
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, &attacker_wallet],
+            context.last_blockhash,
+        );
+        let err = context
+            .banks_client
+            .process_transaction(tx)
+            .await
+            .unwrap_err();
+
+        match err {
+            TransportError::TransactionError(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(err_code),
+            )) => {
+                assert_eq!(
+                    err_code,
+                    ERROR_CODE_OFFSET + ErrorCode::InvalidPendingStoreAdmin as u32
+                );
+            }
+            _ => assert!(false),
+        }
+    }
+}
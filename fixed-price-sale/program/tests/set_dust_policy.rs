@@ -0,0 +1,197 @@
+mod utils;
+
+#[cfg(feature = "test-bpf")]
+mod set_dust_policy {
+    use crate::{
+        setup_context,
+        utils::setup_functions::{setup_market, setup_selling_resource, setup_store},
+    };
+    use anchor_lang::{
+        error::ERROR_CODE_OFFSET, AccountDeserialize, InstructionData, ToAccountMetas,
+    };
+    use mpl_fixed_price_sale::{
+        accounts as mpl_fixed_price_sale_accounts,
+        error::ErrorCode,
+        instruction as mpl_fixed_price_sale_instruction,
+        state::{DustPolicy, Market},
+        utils::{find_market_manager_address, find_market_name_registry_address},
+    };
+    use solana_program::{clock::Clock, instruction::AccountMeta};
+    use solana_program_test::*;
+    use solana_sdk::{
+        instruction::{Instruction, InstructionError},
+        signature::Keypair,
+        signer::Signer,
+        sysvar,
+        transaction::{Transaction, TransactionError},
+        transport::TransportError,
+    };
+
+    async fn set_dust_policy(
+        context: &mut ProgramTestContext,
+        store_keypair: &Keypair,
+        market_keypair: &Keypair,
+        owner: &Keypair,
+        dust_policy: DustPolicy,
+    ) -> Result<(), TransportError> {
+        let accounts = mpl_fixed_price_sale_accounts::SetDustPolicy {
+            market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
+            owner: owner.pubkey(),
+            market_manager: find_market_manager_address(&market_keypair.pubkey(), &owner.pubkey())
+                .0,
+            clock: sysvar::clock::id(),
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::SetDustPolicy { dust_policy }.data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, owner],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    #[tokio::test]
+    async fn success() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _vault) =
+            setup_selling_resource(
+                &mut context,
+                &admin_wallet,
+                &store_keypair,
+                100,
+                None,
+                true,
+                false,
+            )
+            .await;
+
+        let market_keypair = setup_market(
+            &mut context,
+            &admin_wallet,
+            &store_keypair,
+            &selling_resource_keypair,
+            &selling_resource_owner_keypair,
+        )
+        .await;
+
+        let market_acc = context
+            .banks_client
+            .get_account(market_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let market = Market::try_deserialize(&mut market_acc.data.as_ref()).unwrap();
+        assert_eq!(market.dust_policy, DustPolicy::Leave);
+
+        set_dust_policy(
+            &mut context,
+            &store_keypair,
+            &market_keypair,
+            &selling_resource_owner_keypair,
+            DustPolicy::FirstCreator,
+        )
+        .await
+        .unwrap();
+
+        let market_acc = context
+            .banks_client
+            .get_account(market_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let market = Market::try_deserialize(&mut market_acc.data.as_ref()).unwrap();
+        assert_eq!(market.dust_policy, DustPolicy::FirstCreator);
+    }
+
+    #[tokio::test]
+    async fn fail_market_is_ended() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _vault) =
+            setup_selling_resource(
+                &mut context,
+                &admin_wallet,
+                &store_keypair,
+                100,
+                None,
+                true,
+                false,
+            )
+            .await;
+
+        let market_keypair = setup_market(
+            &mut context,
+            &admin_wallet,
+            &store_keypair,
+            &selling_resource_keypair,
+            &selling_resource_owner_keypair,
+        )
+        .await;
+
+        let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        context.warp_to_slot(clock.slot + 1500).unwrap();
+
+        let mut accounts = mpl_fixed_price_sale_accounts::CloseMarket {
+            market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
+            owner: selling_resource_owner_keypair.pubkey(),
+            clock: sysvar::clock::id(),
+        }
+        .to_account_metas(None);
+        let (market_name_registry, _) =
+            find_market_name_registry_address(&store_keypair.pubkey(), "Marktname");
+        accounts.push(AccountMeta::new(market_name_registry, false));
+
+        let data = mpl_fixed_price_sale_instruction::CloseMarket {}.data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, &selling_resource_owner_keypair],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        let err = set_dust_policy(
+            &mut context,
+            &store_keypair,
+            &market_keypair,
+            &selling_resource_owner_keypair,
+            DustPolicy::MarketOwner,
+        )
+        .await
+        .unwrap_err();
+
+        match err {
+            TransportError::TransactionError(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(code),
+            )) => {
+                assert_eq!(code, ERROR_CODE_OFFSET + ErrorCode::MarketIsEnded as u32);
+            }
+            _ => assert!(false),
+        }
+    }
+}
@@ -0,0 +1,432 @@
+mod utils;
+
+#[cfg(feature = "test-bpf")]
+mod set_gatekeeper_program {
+    use crate::utils::{
+        helpers::{airdrop, create_mint, create_token_account, mint_to},
+        setup_functions::{setup_market, setup_selling_resource, setup_store},
+    };
+    use anchor_lang::{
+        error::ERROR_CODE_OFFSET, AccountDeserialize, InstructionData, ToAccountMetas,
+    };
+    use mpl_fixed_price_sale::{
+        accounts as mpl_fixed_price_sale_accounts,
+        error::ErrorCode,
+        instruction as mpl_fixed_price_sale_instruction,
+        state::{GatekeeperProgram, Market, SellingResource, TradeHistory},
+        utils::{
+            find_market_manager_address, find_provenance_record_address,
+            find_trade_history_address, find_vault_owner_address,
+        },
+    };
+    use solana_program::{
+        account_info::AccountInfo, clock::Clock, entrypoint::ProgramResult,
+        instruction::AccountMeta, program_error::ProgramError,
+    };
+    use solana_program_test::*;
+    use solana_sdk::{
+        instruction::{Instruction, InstructionError},
+        pubkey::Pubkey,
+        signature::Keypair,
+        signer::Signer,
+        system_program, sysvar,
+        transaction::{Transaction, TransactionError},
+        transport::TransportError,
+    };
+
+    const NOT_APPROVED: u32 = 1;
+
+    /// Gatekeeper approving wallets, which have a funded KYC record at `[wallet]` PDA
+    fn process_approve_purchase(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        data: &[u8],
+    ) -> ProgramResult {
+        let wallet = &accounts[0];
+        let kyc_record = &accounts[2];
+
+        if data.len() != 8 + 32 + 32 + 8 || data[8..40] != wallet.key.to_bytes() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let (kyc_record_key, _) = Pubkey::find_program_address(&[wallet.key.as_ref()], program_id);
+        if !wallet.is_signer || *kyc_record.key != kyc_record_key || kyc_record.lamports() == 0 {
+            return Err(ProgramError::Custom(NOT_APPROVED));
+        }
+
+        Ok(())
+    }
+
+    async fn setup_context(gatekeeper: &Pubkey) -> ProgramTestContext {
+        let mut program_test = ProgramTest::default();
+        program_test.add_program("mpl_fixed_price_sale", mpl_fixed_price_sale::id(), None);
+        program_test.add_program("mpl_token_metadata", mpl_token_metadata::id(), None);
+        program_test.add_builtin_program(
+            "gatekeeper",
+            *gatekeeper,
+            processor!(process_approve_purchase).unwrap(),
+        );
+
+        program_test.start_with_context().await
+    }
+
+    async fn set_gatekeeper_program(
+        context: &mut ProgramTestContext,
+        store_keypair: &Keypair,
+        market_keypair: &Keypair,
+        owner: &Keypair,
+        gatekeeper_program: Option<GatekeeperProgram>,
+    ) -> Result<(), TransportError> {
+        let accounts = mpl_fixed_price_sale_accounts::SetGatekeeperProgram {
+            market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
+            owner: owner.pubkey(),
+            market_manager: find_market_manager_address(&market_keypair.pubkey(), &owner.pubkey())
+                .0,
+            clock: sysvar::clock::id(),
+        }
+        .to_account_metas(None);
+
+        let data =
+            mpl_fixed_price_sale_instruction::SetGatekeeperProgram { gatekeeper_program }.data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, owner],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    async fn buy(
+        context: &mut ProgramTestContext,
+        market_keypair: &Keypair,
+        selling_resource_keypair: &Keypair,
+        admin_wallet: &Keypair,
+        user_wallet: &Keypair,
+        gatekeeper_accounts: Vec<AccountMeta>,
+    ) -> Result<(), TransportError> {
+        let market_acc = context
+            .banks_client
+            .get_account(market_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let market = Market::try_deserialize(&mut market_acc.data.as_ref()).unwrap();
+
+        let selling_resource_acc = context
+            .banks_client
+            .get_account(selling_resource_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let selling_resource =
+            SellingResource::try_deserialize(&mut selling_resource_acc.data.as_ref()).unwrap();
+
+        let user_token_account = Keypair::new();
+        create_token_account(
+            context,
+            &user_token_account,
+            &market.treasury_mint,
+            &user_wallet.pubkey(),
+        )
+        .await;
+        mint_to(
+            context,
+            &market.treasury_mint,
+            &user_token_account.pubkey(),
+            admin_wallet,
+            market.price,
+        )
+        .await;
+
+        let (trade_history, trade_history_bump) =
+            find_trade_history_address(&user_wallet.pubkey(), &market_keypair.pubkey());
+        let (owner, vault_owner_bump) =
+            find_vault_owner_address(&selling_resource.resource, &selling_resource.store);
+
+        let new_mint_keypair = Keypair::new();
+        create_mint(context, &new_mint_keypair, &user_wallet.pubkey(), 0).await;
+
+        let new_mint_token_account = Keypair::new();
+        create_token_account(
+            context,
+            &new_mint_token_account,
+            &new_mint_keypair.pubkey(),
+            &user_wallet.pubkey(),
+        )
+        .await;
+
+        mint_to(
+            context,
+            &new_mint_keypair.pubkey(),
+            &new_mint_token_account.pubkey(),
+            user_wallet,
+            1,
+        )
+        .await;
+
+        let (master_edition_metadata, _) =
+            mpl_token_metadata::pda::find_metadata_account(&selling_resource.resource);
+        let (master_edition, _) =
+            mpl_token_metadata::pda::find_master_edition_account(&selling_resource.resource);
+        let (edition_marker, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                selling_resource.resource.as_ref(),
+                mpl_token_metadata::state::EDITION.as_bytes(),
+                selling_resource.supply.to_string().as_bytes(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+        let (new_metadata, _) =
+            mpl_token_metadata::pda::find_metadata_account(&new_mint_keypair.pubkey());
+        let (new_edition, _) =
+            mpl_token_metadata::pda::find_master_edition_account(&new_mint_keypair.pubkey());
+
+        let mut accounts = mpl_fixed_price_sale_accounts::Buy {
+            market: market_keypair.pubkey(),
+            selling_resource: selling_resource_keypair.pubkey(),
+            user_token_account: user_token_account.pubkey(),
+            user_wallet: user_wallet.pubkey(),
+            trade_history,
+            treasury_holder: market.treasury_holder,
+            new_metadata,
+            new_edition,
+            master_edition,
+            new_mint: new_mint_keypair.pubkey(),
+            edition_marker,
+            vault: selling_resource.vault,
+            store: selling_resource.store,
+            owner,
+            new_token_account: new_mint_token_account.pubkey(),
+            master_edition_metadata,
+            clock: sysvar::clock::id(),
+            rent: sysvar::rent::id(),
+            token_metadata_program: mpl_token_metadata::id(),
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            provenance_record: find_provenance_record_address(&new_mint_keypair.pubkey()).0,
+        }
+        .to_account_metas(None);
+
+        accounts.extend(gatekeeper_accounts);
+
+        let data = mpl_fixed_price_sale_instruction::Buy {
+            _trade_history_bump: trade_history_bump,
+            vault_owner_bump,
+            client_nonce: None,
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, user_wallet],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    /// Market with KYC gatekeeper and a user wallet
+    async fn setup_gated_market(
+        context: &mut ProgramTestContext,
+        gatekeeper: &Pubkey,
+    ) -> (Keypair, Keypair, Keypair, Keypair) {
+        let (admin_wallet, store_keypair) = setup_store(context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _vault) =
+            setup_selling_resource(
+                context,
+                &admin_wallet,
+                &store_keypair,
+                100,
+                None,
+                true,
+                false,
+            )
+            .await;
+
+        let market_keypair = setup_market(
+            context,
+            &admin_wallet,
+            &store_keypair,
+            &selling_resource_keypair,
+            &selling_resource_owner_keypair,
+        )
+        .await;
+
+        set_gatekeeper_program(
+            context,
+            &store_keypair,
+            &market_keypair,
+            &selling_resource_owner_keypair,
+            Some(GatekeeperProgram {
+                program_id: *gatekeeper,
+                additional_accounts: 1,
+            }),
+        )
+        .await
+        .unwrap();
+
+        let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        context.warp_to_slot(clock.slot + 1500).unwrap();
+
+        let user_wallet = Keypair::new();
+        airdrop(context, &user_wallet.pubkey(), 1_000_000_000).await;
+
+        (
+            admin_wallet,
+            selling_resource_keypair,
+            market_keypair,
+            user_wallet,
+        )
+    }
+
+    #[tokio::test]
+    async fn success() {
+        let gatekeeper = Pubkey::new_unique();
+        let mut context = setup_context(&gatekeeper).await;
+        let (admin_wallet, selling_resource_keypair, market_keypair, user_wallet) =
+            setup_gated_market(&mut context, &gatekeeper).await;
+
+        let market_acc = context
+            .banks_client
+            .get_account(market_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let market = Market::try_deserialize(&mut market_acc.data.as_ref()).unwrap();
+        assert_eq!(
+            market.gatekeeper_program,
+            Some(GatekeeperProgram {
+                program_id: gatekeeper,
+                additional_accounts: 1,
+            })
+        );
+
+        let (kyc_record, _) =
+            Pubkey::find_program_address(&[user_wallet.pubkey().as_ref()], &gatekeeper);
+        airdrop(&mut context, &kyc_record, 1_000_000).await;
+
+        buy(
+            &mut context,
+            &market_keypair,
+            &selling_resource_keypair,
+            &admin_wallet,
+            &user_wallet,
+            vec![
+                AccountMeta::new_readonly(gatekeeper, false),
+                AccountMeta::new_readonly(kyc_record, false),
+            ],
+        )
+        .await
+        .unwrap();
+
+        let (trade_history, _) =
+            find_trade_history_address(&user_wallet.pubkey(), &market_keypair.pubkey());
+        let trade_history_acc = context
+            .banks_client
+            .get_account(trade_history)
+            .await
+            .unwrap()
+            .unwrap();
+        let trade_history_data =
+            TradeHistory::try_deserialize(&mut trade_history_acc.data.as_ref()).unwrap();
+
+        assert_eq!(trade_history_data.already_bought, 1);
+    }
+
+    #[tokio::test]
+    async fn fail_purchase_not_approved() {
+        let gatekeeper = Pubkey::new_unique();
+        let mut context = setup_context(&gatekeeper).await;
+        let (admin_wallet, selling_resource_keypair, market_keypair, user_wallet) =
+            setup_gated_market(&mut context, &gatekeeper).await;
+
+        // KYC record is not funded
+        let (kyc_record, _) =
+            Pubkey::find_program_address(&[user_wallet.pubkey().as_ref()], &gatekeeper);
+
+        let err = buy(
+            &mut context,
+            &market_keypair,
+            &selling_resource_keypair,
+            &admin_wallet,
+            &user_wallet,
+            vec![
+                AccountMeta::new_readonly(gatekeeper, false),
+                AccountMeta::new_readonly(kyc_record, false),
+            ],
+        )
+        .await
+        .unwrap_err();
+
+        match err {
+            TransportError::TransactionError(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(code),
+            )) => {
+                assert_eq!(code, NOT_APPROVED);
+            }
+            _ => assert!(false),
+        }
+    }
+
+    #[tokio::test]
+    async fn fail_invalid_gatekeeper_program() {
+        let gatekeeper = Pubkey::new_unique();
+        let mut context = setup_context(&gatekeeper).await;
+        let (admin_wallet, selling_resource_keypair, market_keypair, user_wallet) =
+            setup_gated_market(&mut context, &gatekeeper).await;
+
+        let (kyc_record, _) =
+            Pubkey::find_program_address(&[user_wallet.pubkey().as_ref()], &gatekeeper);
+        airdrop(&mut context, &kyc_record, 1_000_000).await;
+
+        // Gatekeeper program is skipped
+        let err = buy(
+            &mut context,
+            &market_keypair,
+            &selling_resource_keypair,
+            &admin_wallet,
+            &user_wallet,
+            vec![
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(kyc_record, false),
+            ],
+        )
+        .await
+        .unwrap_err();
+
+        match err {
+            TransportError::TransactionError(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(code),
+            )) => {
+                assert_eq!(
+                    code,
+                    ERROR_CODE_OFFSET + ErrorCode::InvalidGatekeeperProgram as u32
+                );
+            }
+            _ => assert!(false),
+        }
+    }
+}
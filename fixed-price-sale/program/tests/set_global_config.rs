@@ -0,0 +1,271 @@
+mod utils;
+
+#[cfg(feature = "test-bpf")]
+mod set_global_config {
+    use crate::utils::{
+        helpers::airdrop,
+        setup_functions::{setup_selling_resource, setup_store},
+    };
+    use anchor_lang::{
+        error::ERROR_CODE_OFFSET, AccountDeserialize, InstructionData, ToAccountMetas,
+    };
+    use mpl_fixed_price_sale::{
+        accounts as mpl_fixed_price_sale_accounts,
+        error::ErrorCode,
+        instruction as mpl_fixed_price_sale_instruction,
+        state::GlobalConfig,
+        utils::{
+            find_global_config_address, find_market_address, find_market_name_registry_address,
+            find_store_payment_mints_address, find_treasury_owner_address,
+        },
+    };
+    use solana_program_test::*;
+    use solana_sdk::{
+        account::AccountSharedData,
+        bpf_loader_upgradeable,
+        instruction::{Instruction, InstructionError},
+        pubkey::Pubkey,
+        signature::Keypair,
+        signer::Signer,
+        system_program,
+        sysvar::clock::Clock,
+        transaction::{Transaction, TransactionError},
+        transport::TransportError,
+    };
+
+    use crate::setup_context;
+
+    // Programs of `ProgramTest` are not upgradeable, so fake the program data account with `upgrade_authority`
+    fn set_upgrade_authority(
+        context: &mut ProgramTestContext,
+        upgrade_authority: &Pubkey,
+    ) -> Pubkey {
+        let (program_data, _) = Pubkey::find_program_address(
+            &[mpl_fixed_price_sale::id().as_ref()],
+            &bpf_loader_upgradeable::id(),
+        );
+
+        // `UpgradeableLoaderState::ProgramData` with `slot` 0 and `Some(upgrade_authority)`
+        let mut data = Vec::with_capacity(45);
+        data.extend_from_slice(&3u32.to_le_bytes());
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.push(1);
+        data.extend_from_slice(upgrade_authority.as_ref());
+
+        let mut account =
+            AccountSharedData::new(1_000_000_000, data.len(), &bpf_loader_upgradeable::id());
+        account.set_data(data);
+        context.set_account(&program_data, &account);
+
+        program_data
+    }
+
+    async fn set_global_config(
+        context: &mut ProgramTestContext,
+        authority: &Keypair,
+        program_data: &Pubkey,
+        max_markets_per_store: Option<u64>,
+    ) -> Result<(), TransportError> {
+        let accounts = mpl_fixed_price_sale_accounts::SetGlobalConfig {
+            authority: authority.pubkey(),
+            program_data: *program_data,
+            global_config: find_global_config_address().0,
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::SetGlobalConfig {
+            raffle_mode_enabled: false,
+            oracle_pricing_enabled: true,
+            max_markets_per_store,
+            max_processor_fee_basis_points: 500,
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, authority],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    // Creates a native SOL market at the first PDA of `selling_resource`
+    async fn create_native_market(
+        context: &mut ProgramTestContext,
+        store: &Pubkey,
+        selling_resource_owner: &Keypair,
+        selling_resource: &Pubkey,
+    ) -> Result<Pubkey, TransportError> {
+        let (market, _) = find_market_address(store, selling_resource, 0);
+        let treasury_mint = system_program::id();
+        let (treasury_owner, treasury_owner_bump) =
+            find_treasury_owner_address(&treasury_mint, selling_resource);
+        let name = "Marktname";
+
+        let accounts = mpl_fixed_price_sale_accounts::CreateMarketPda {
+            market,
+            store: *store,
+            selling_resource_owner: selling_resource_owner.pubkey(),
+            selling_resource: *selling_resource,
+            mint: treasury_mint,
+            treasury_holder: treasury_owner,
+            owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(store, name).0,
+            store_payment_mints: find_store_payment_mints_address(store).0,
+            global_config: find_global_config_address().0,
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None);
+
+        let start_date = context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+            .unix_timestamp
+            + 1;
+
+        let data = mpl_fixed_price_sale_instruction::CreateMarketPda {
+            _treasury_owner_bump: treasury_owner_bump,
+            name: name.to_string(),
+            description: "Marktbeschreibung".to_string(),
+            mutable: true,
+            price: 1_000_000,
+            pieces_in_one_wallet: Some(1),
+            start_date: start_date as u64,
+            end_date: None,
+            gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, selling_resource_owner],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await?;
+
+        Ok(market)
+    }
+
+    #[tokio::test]
+    async fn success() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let upgrade_authority = Keypair::new();
+        airdrop(&mut context, &upgrade_authority.pubkey(), 10_000_000_000).await;
+        let program_data = set_upgrade_authority(&mut context, &upgrade_authority.pubkey());
+
+        set_global_config(&mut context, &upgrade_authority, &program_data, Some(0))
+            .await
+            .unwrap();
+
+        let global_config_acc = context
+            .banks_client
+            .get_account(find_global_config_address().0)
+            .await
+            .expect("account not found")
+            .expect("account empty");
+        let global_config =
+            GlobalConfig::try_deserialize(&mut global_config_acc.data.as_ref()).unwrap();
+
+        assert_eq!(upgrade_authority.pubkey(), global_config.authority);
+        assert!(global_config.oracle_pricing_enabled);
+        assert_eq!(Some(0), global_config.max_markets_per_store);
+        assert_eq!(500, global_config.max_processor_fee_basis_points);
+    }
+
+    #[tokio::test]
+    async fn failure_not_upgrade_authority() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let upgrade_authority = Keypair::new();
+        airdrop(&mut context, &upgrade_authority.pubkey(), 10_000_000_000).await;
+        let program_data = set_upgrade_authority(&mut context, &upgrade_authority.pubkey());
+
+        let authority = Keypair::new();
+        airdrop(&mut context, &authority.pubkey(), 10_000_000_000).await;
+
+        let err = set_global_config(&mut context, &authority, &program_data, None)
+            .await
+            .unwrap_err();
+
+        match err {
+            TransportError::TransactionError(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(err_code),
+            )) => {
+                assert_eq!(
+                    ERROR_CODE_OFFSET + ErrorCode::InvalidGlobalConfigAuthority as u32,
+                    err_code
+                );
+            }
+            _ => assert!(false),
+        }
+    }
+
+    #[tokio::test]
+    async fn failure_too_many_markets() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _) = setup_selling_resource(
+            &mut context,
+            &admin_wallet,
+            &store_keypair,
+            100,
+            None,
+            true,
+            false,
+        )
+        .await;
+
+        let upgrade_authority = Keypair::new();
+        airdrop(&mut context, &upgrade_authority.pubkey(), 10_000_000_000).await;
+        let program_data = set_upgrade_authority(&mut context, &upgrade_authority.pubkey());
+        set_global_config(&mut context, &upgrade_authority, &program_data, Some(0))
+            .await
+            .unwrap();
+
+        let err = create_native_market(
+            &mut context,
+            &store_keypair.pubkey(),
+            &selling_resource_owner_keypair,
+            &selling_resource_keypair.pubkey(),
+        )
+        .await
+        .unwrap_err();
+
+        match err {
+            TransportError::TransactionError(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(err_code),
+            )) => {
+                assert_eq!(
+                    ERROR_CODE_OFFSET + ErrorCode::TooManyMarkets as u32,
+                    err_code
+                );
+            }
+            _ => assert!(false),
+        }
+    }
+}
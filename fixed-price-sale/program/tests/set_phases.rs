@@ -0,0 +1,202 @@
+mod utils;
+
+#[cfg(feature = "test-bpf")]
+mod set_phases {
+    use crate::{
+        setup_context,
+        utils::setup_functions::{setup_market, setup_selling_resource, setup_store},
+    };
+    use anchor_lang::{
+        error::ERROR_CODE_OFFSET, AccountDeserialize, InstructionData, ToAccountMetas,
+    };
+    use mpl_fixed_price_sale::{
+        accounts as mpl_fixed_price_sale_accounts,
+        error::ErrorCode,
+        instruction as mpl_fixed_price_sale_instruction,
+        state::{Market, Phase},
+        utils::find_market_manager_address,
+    };
+    use solana_program_test::*;
+    use solana_sdk::{
+        instruction::{Instruction, InstructionError},
+        signature::Keypair,
+        signer::Signer,
+        sysvar,
+        transaction::{Transaction, TransactionError},
+        transport::TransportError,
+    };
+
+    async fn set_phases(
+        context: &mut ProgramTestContext,
+        store_keypair: &Keypair,
+        market_keypair: &Keypair,
+        owner: &Keypair,
+        phases: Vec<Phase>,
+    ) -> Result<(), TransportError> {
+        let accounts = mpl_fixed_price_sale_accounts::SetPhases {
+            market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
+            owner: owner.pubkey(),
+            market_manager: find_market_manager_address(&market_keypair.pubkey(), &owner.pubkey())
+                .0,
+            clock: sysvar::clock::id(),
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::SetPhases { phases }.data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, owner],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    #[tokio::test]
+    async fn success() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _vault) =
+            setup_selling_resource(
+                &mut context,
+                &admin_wallet,
+                &store_keypair,
+                100,
+                None,
+                true,
+                false,
+            )
+            .await;
+
+        let market_keypair = setup_market(
+            &mut context,
+            &admin_wallet,
+            &store_keypair,
+            &selling_resource_keypair,
+            &selling_resource_owner_keypair,
+        )
+        .await;
+
+        let market_acc = context
+            .banks_client
+            .get_account(market_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let market = Market::try_deserialize(&mut market_acc.data.as_ref()).unwrap();
+        assert!(market.phases.is_empty());
+
+        let start = market.start_date;
+        let phases = vec![
+            Phase {
+                start,
+                end: start + 100,
+                price: market.price / 2,
+                wallet_limit: Some(1),
+                gate: None,
+            },
+            Phase {
+                start: start + 200,
+                end: start + 300,
+                price: market.price,
+                wallet_limit: None,
+                gate: None,
+            },
+        ];
+
+        set_phases(
+            &mut context,
+            &store_keypair,
+            &market_keypair,
+            &selling_resource_owner_keypair,
+            phases.clone(),
+        )
+        .await
+        .unwrap();
+
+        let market_acc = context
+            .banks_client
+            .get_account(market_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let market = Market::try_deserialize(&mut market_acc.data.as_ref()).unwrap();
+        assert_eq!(market.phases, phases);
+        assert_eq!(market.active_phase(start + 100), None);
+        assert_eq!(market.active_phase(start + 200), Some(&phases[1]));
+        assert_eq!(market.piece_price_at(1, start), Some(market.price / 2));
+        assert_eq!(market.piece_price_at(1, start + 250), Some(market.price));
+    }
+
+    #[tokio::test]
+    async fn fail_overlapping_phases() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _vault) =
+            setup_selling_resource(
+                &mut context,
+                &admin_wallet,
+                &store_keypair,
+                100,
+                None,
+                true,
+                false,
+            )
+            .await;
+
+        let market_keypair = setup_market(
+            &mut context,
+            &admin_wallet,
+            &store_keypair,
+            &selling_resource_keypair,
+            &selling_resource_owner_keypair,
+        )
+        .await;
+
+        let err = set_phases(
+            &mut context,
+            &store_keypair,
+            &market_keypair,
+            &selling_resource_owner_keypair,
+            vec![
+                Phase {
+                    start: 100,
+                    end: 300,
+                    price: 1_000_000,
+                    wallet_limit: Some(1),
+                    gate: None,
+                },
+                Phase {
+                    start: 200,
+                    end: 400,
+                    price: 2_000_000,
+                    wallet_limit: None,
+                    gate: None,
+                },
+            ],
+        )
+        .await
+        .unwrap_err();
+
+        match err {
+            TransportError::TransactionError(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(code),
+            )) => {
+                assert_eq!(code, ERROR_CODE_OFFSET + ErrorCode::InvalidPhases as u32);
+            }
+            _ => assert!(false),
+        }
+    }
+}
@@ -0,0 +1,241 @@
+mod utils;
+
+#[cfg(feature = "test-bpf")]
+mod set_physical_redemption {
+    use crate::{
+        setup_context,
+        utils::setup_functions::{setup_market, setup_selling_resource, setup_store},
+    };
+    use anchor_lang::{
+        error::ERROR_CODE_OFFSET, AccountDeserialize, InstructionData, ToAccountMetas,
+    };
+    use mpl_fixed_price_sale::{
+        accounts as mpl_fixed_price_sale_accounts,
+        error::ErrorCode,
+        instruction as mpl_fixed_price_sale_instruction,
+        state::{Market, MarketState, PhysicalRedemption},
+        utils::find_market_manager_address,
+    };
+    use solana_program::clock::Clock;
+    use solana_program_test::*;
+    use solana_sdk::{
+        instruction::{Instruction, InstructionError},
+        pubkey::Pubkey,
+        signature::Keypair,
+        signer::Signer,
+        sysvar,
+        transaction::{Transaction, TransactionError},
+        transport::TransportError,
+    };
+
+    async fn set_physical_redemption(
+        context: &mut ProgramTestContext,
+        market_keypair: &Keypair,
+        owner: &Keypair,
+        physical_redemption: Option<PhysicalRedemption>,
+    ) -> Result<(), TransportError> {
+        let market_acc = context
+            .banks_client
+            .get_account(market_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let market = Market::try_deserialize(&mut market_acc.data.as_ref()).unwrap();
+
+        let accounts = mpl_fixed_price_sale_accounts::SetPhysicalRedemption {
+            market: market_keypair.pubkey(),
+            store: market.store,
+            owner: owner.pubkey(),
+            market_manager: find_market_manager_address(&market_keypair.pubkey(), &owner.pubkey())
+                .0,
+            clock: sysvar::clock::id(),
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::SetPhysicalRedemption {
+            physical_redemption,
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, owner],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    async fn setup(context: &mut ProgramTestContext) -> (Keypair, Keypair) {
+        let (admin_wallet, store_keypair) = setup_store(context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _vault) =
+            setup_selling_resource(
+                context,
+                &admin_wallet,
+                &store_keypair,
+                100,
+                None,
+                true,
+                false,
+            )
+            .await;
+
+        let market_keypair = setup_market(
+            context,
+            &admin_wallet,
+            &store_keypair,
+            &selling_resource_keypair,
+            &selling_resource_owner_keypair,
+        )
+        .await;
+
+        (market_keypair, selling_resource_owner_keypair)
+    }
+
+    #[tokio::test]
+    async fn success() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (market_keypair, owner) = setup(&mut context).await;
+
+        let physical_redemption = PhysicalRedemption {
+            arbiter: Some(Pubkey::new_unique()),
+            delivery_window: 86_400,
+        };
+        set_physical_redemption(
+            &mut context,
+            &market_keypair,
+            &owner,
+            Some(physical_redemption.clone()),
+        )
+        .await
+        .unwrap();
+
+        let market_acc = context
+            .banks_client
+            .get_account(market_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let market = Market::try_deserialize(&mut market_acc.data.as_ref()).unwrap();
+        assert_eq!(market.physical_redemption, Some(physical_redemption));
+        assert_eq!(market.funds_escrowed, 0);
+    }
+
+    #[tokio::test]
+    async fn fail_empty_delivery_window() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (market_keypair, owner) = setup(&mut context).await;
+
+        let err = set_physical_redemption(
+            &mut context,
+            &market_keypair,
+            &owner,
+            Some(PhysicalRedemption {
+                arbiter: None,
+                delivery_window: 0,
+            }),
+        )
+        .await
+        .unwrap_err();
+
+        match err {
+            TransportError::TransactionError(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(code),
+            )) => {
+                assert_eq!(
+                    code,
+                    ERROR_CODE_OFFSET + ErrorCode::InvalidPhysicalRedemption as u32
+                );
+            }
+            _ => assert!(false),
+        }
+    }
+
+    #[tokio::test]
+    async fn fail_market_in_invalid_state() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (market_keypair, owner) = setup(&mut context).await;
+
+        let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        context.warp_to_slot(clock.slot + 1500).unwrap();
+
+        let market_acc = context
+            .banks_client
+            .get_account(market_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let market = Market::try_deserialize(&mut market_acc.data.as_ref()).unwrap();
+
+        // SuspendMarket
+        let accounts = mpl_fixed_price_sale_accounts::SuspendMarket {
+            market: market_keypair.pubkey(),
+            store: market.store,
+            owner: owner.pubkey(),
+            market_manager: find_market_manager_address(&market_keypair.pubkey(), &owner.pubkey())
+                .0,
+            clock: sysvar::clock::id(),
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::SuspendMarket {}.data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, &owner],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        let market_acc = context
+            .banks_client
+            .get_account(market_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let market = Market::try_deserialize(&mut market_acc.data.as_ref()).unwrap();
+        assert_eq!(market.state, MarketState::Suspended);
+
+        let err = set_physical_redemption(
+            &mut context,
+            &market_keypair,
+            &owner,
+            Some(PhysicalRedemption {
+                arbiter: None,
+                delivery_window: 86_400,
+            }),
+        )
+        .await
+        .unwrap_err();
+
+        match err {
+            TransportError::TransactionError(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(code),
+            )) => {
+                assert_eq!(
+                    code,
+                    ERROR_CODE_OFFSET + ErrorCode::MarketInInvalidState as u32
+                );
+            }
+            _ => assert!(false),
+        }
+    }
+}
@@ -0,0 +1,462 @@
+mod utils;
+
+#[cfg(feature = "test-bpf")]
+mod set_price_oracle {
+    use crate::utils::{
+        helpers::{airdrop, create_mint, create_token_account, mint_to},
+        setup_functions::{setup_market, setup_selling_resource, setup_store},
+    };
+    use anchor_lang::{
+        error::ERROR_CODE_OFFSET, AccountDeserialize, InstructionData, ToAccountMetas,
+    };
+    use mpl_fixed_price_sale::{
+        accounts as mpl_fixed_price_sale_accounts,
+        error::ErrorCode,
+        instruction as mpl_fixed_price_sale_instruction,
+        state::{Market, PriceOracle, SellingResource, TradeHistory},
+        utils::{
+            find_global_config_address, find_market_manager_address,
+            find_provenance_record_address, find_trade_history_address, find_vault_owner_address,
+        },
+    };
+    use solana_program::{clock::Clock, instruction::AccountMeta};
+    use solana_program_test::*;
+    use solana_sdk::{
+        account::Account,
+        instruction::{Instruction, InstructionError},
+        pubkey::Pubkey,
+        signature::Keypair,
+        signer::Signer,
+        system_program, sysvar,
+        transaction::{Transaction, TransactionError},
+        transport::TransportError,
+    };
+
+    const TOLERANCE_BASIS_POINTS: u16 = 100;
+
+    /// Pyth v2 price account of a trading stablecoin quoted with 8 decimals
+    fn price_feed_account(price: i64) -> Account {
+        let mut data = vec![0; 3312];
+        data[0..4].copy_from_slice(&0xa1b2c3d4u32.to_le_bytes());
+        data[4..8].copy_from_slice(&2u32.to_le_bytes());
+        data[8..12].copy_from_slice(&3u32.to_le_bytes());
+        data[20..24].copy_from_slice(&(-8i32).to_le_bytes());
+        data[208..216].copy_from_slice(&price.to_le_bytes());
+        data[224..228].copy_from_slice(&1u32.to_le_bytes());
+
+        Account {
+            lamports: 1_000_000_000,
+            data,
+            owner: Pubkey::new_unique(),
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    async fn setup_context(price_feed: &Pubkey, price: i64) -> ProgramTestContext {
+        let mut program_test = ProgramTest::default();
+        program_test.add_program("mpl_fixed_price_sale", mpl_fixed_price_sale::id(), None);
+        program_test.add_program("mpl_token_metadata", mpl_token_metadata::id(), None);
+        program_test.add_account(*price_feed, price_feed_account(price));
+
+        program_test.start_with_context().await
+    }
+
+    async fn set_price_oracle(
+        context: &mut ProgramTestContext,
+        market_keypair: &Keypair,
+        owner: &Keypair,
+        price_oracle: Option<PriceOracle>,
+        oracle_override: bool,
+    ) -> Result<(), TransportError> {
+        let market_acc = context
+            .banks_client
+            .get_account(market_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let market = Market::try_deserialize(&mut market_acc.data.as_ref()).unwrap();
+
+        let accounts = mpl_fixed_price_sale_accounts::SetPriceOracle {
+            market: market_keypair.pubkey(),
+            store: market.store,
+            owner: owner.pubkey(),
+            market_manager: find_market_manager_address(&market_keypair.pubkey(), &owner.pubkey())
+                .0,
+            clock: sysvar::clock::id(),
+            global_config: find_global_config_address().0,
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::SetPriceOracle {
+            price_oracle,
+            oracle_override,
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, owner],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    async fn buy(
+        context: &mut ProgramTestContext,
+        market_keypair: &Keypair,
+        selling_resource_keypair: &Keypair,
+        admin_wallet: &Keypair,
+        user_wallet: &Keypair,
+        oracle_accounts: Vec<AccountMeta>,
+    ) -> Result<(), TransportError> {
+        let market_acc = context
+            .banks_client
+            .get_account(market_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let market = Market::try_deserialize(&mut market_acc.data.as_ref()).unwrap();
+
+        let selling_resource_acc = context
+            .banks_client
+            .get_account(selling_resource_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let selling_resource =
+            SellingResource::try_deserialize(&mut selling_resource_acc.data.as_ref()).unwrap();
+
+        let user_token_account = Keypair::new();
+        create_token_account(
+            context,
+            &user_token_account,
+            &market.treasury_mint,
+            &user_wallet.pubkey(),
+        )
+        .await;
+        mint_to(
+            context,
+            &market.treasury_mint,
+            &user_token_account.pubkey(),
+            admin_wallet,
+            market.price,
+        )
+        .await;
+
+        let (trade_history, trade_history_bump) =
+            find_trade_history_address(&user_wallet.pubkey(), &market_keypair.pubkey());
+        let (owner, vault_owner_bump) =
+            find_vault_owner_address(&selling_resource.resource, &selling_resource.store);
+
+        let new_mint_keypair = Keypair::new();
+        create_mint(context, &new_mint_keypair, &user_wallet.pubkey(), 0).await;
+
+        let new_mint_token_account = Keypair::new();
+        create_token_account(
+            context,
+            &new_mint_token_account,
+            &new_mint_keypair.pubkey(),
+            &user_wallet.pubkey(),
+        )
+        .await;
+
+        mint_to(
+            context,
+            &new_mint_keypair.pubkey(),
+            &new_mint_token_account.pubkey(),
+            user_wallet,
+            1,
+        )
+        .await;
+
+        let (master_edition_metadata, _) =
+            mpl_token_metadata::pda::find_metadata_account(&selling_resource.resource);
+        let (master_edition, _) =
+            mpl_token_metadata::pda::find_master_edition_account(&selling_resource.resource);
+        let (edition_marker, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                selling_resource.resource.as_ref(),
+                mpl_token_metadata::state::EDITION.as_bytes(),
+                selling_resource.supply.to_string().as_bytes(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+        let (new_metadata, _) =
+            mpl_token_metadata::pda::find_metadata_account(&new_mint_keypair.pubkey());
+        let (new_edition, _) =
+            mpl_token_metadata::pda::find_master_edition_account(&new_mint_keypair.pubkey());
+
+        let mut accounts = mpl_fixed_price_sale_accounts::Buy {
+            market: market_keypair.pubkey(),
+            selling_resource: selling_resource_keypair.pubkey(),
+            user_token_account: user_token_account.pubkey(),
+            user_wallet: user_wallet.pubkey(),
+            trade_history,
+            treasury_holder: market.treasury_holder,
+            new_metadata,
+            new_edition,
+            master_edition,
+            new_mint: new_mint_keypair.pubkey(),
+            edition_marker,
+            vault: selling_resource.vault,
+            store: selling_resource.store,
+            owner,
+            new_token_account: new_mint_token_account.pubkey(),
+            master_edition_metadata,
+            clock: sysvar::clock::id(),
+            rent: sysvar::rent::id(),
+            token_metadata_program: mpl_token_metadata::id(),
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            provenance_record: find_provenance_record_address(&new_mint_keypair.pubkey()).0,
+        }
+        .to_account_metas(None);
+
+        accounts.extend(oracle_accounts);
+
+        let data = mpl_fixed_price_sale_instruction::Buy {
+            _trade_history_bump: trade_history_bump,
+            vault_owner_bump,
+            client_nonce: None,
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, user_wallet],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    /// Market watching `price_feed` and a user wallet
+    async fn setup_oracle_market(
+        context: &mut ProgramTestContext,
+        price_feed: &Pubkey,
+    ) -> (Keypair, Keypair, Keypair, Keypair, Keypair) {
+        let (admin_wallet, store_keypair) = setup_store(context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _vault) =
+            setup_selling_resource(
+                context,
+                &admin_wallet,
+                &store_keypair,
+                100,
+                None,
+                true,
+                false,
+            )
+            .await;
+
+        let market_keypair = setup_market(
+            context,
+            &admin_wallet,
+            &store_keypair,
+            &selling_resource_keypair,
+            &selling_resource_owner_keypair,
+        )
+        .await;
+
+        set_price_oracle(
+            context,
+            &market_keypair,
+            &selling_resource_owner_keypair,
+            Some(PriceOracle {
+                price_feed: *price_feed,
+                tolerance_basis_points: TOLERANCE_BASIS_POINTS,
+            }),
+            false,
+        )
+        .await
+        .unwrap();
+
+        let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        context.warp_to_slot(clock.slot + 1500).unwrap();
+
+        let user_wallet = Keypair::new();
+        airdrop(context, &user_wallet.pubkey(), 1_000_000_000).await;
+
+        (
+            admin_wallet,
+            selling_resource_owner_keypair,
+            selling_resource_keypair,
+            market_keypair,
+            user_wallet,
+        )
+    }
+
+    async fn already_bought(
+        context: &mut ProgramTestContext,
+        market_keypair: &Keypair,
+        user_wallet: &Keypair,
+    ) -> u64 {
+        let (trade_history, _) =
+            find_trade_history_address(&user_wallet.pubkey(), &market_keypair.pubkey());
+        let trade_history_acc = context
+            .banks_client
+            .get_account(trade_history)
+            .await
+            .unwrap()
+            .unwrap();
+        let trade_history_data =
+            TradeHistory::try_deserialize(&mut trade_history_acc.data.as_ref()).unwrap();
+
+        trade_history_data.already_bought
+    }
+
+    #[tokio::test]
+    async fn success() {
+        let price_feed = Pubkey::new_unique();
+        // 0.995 is within 1% of the peg
+        let mut context = setup_context(&price_feed, 99_500_000).await;
+        let (admin_wallet, _owner, selling_resource_keypair, market_keypair, user_wallet) =
+            setup_oracle_market(&mut context, &price_feed).await;
+
+        let market_acc = context
+            .banks_client
+            .get_account(market_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let market = Market::try_deserialize(&mut market_acc.data.as_ref()).unwrap();
+        assert_eq!(
+            market.price_oracle,
+            Some(PriceOracle {
+                price_feed,
+                tolerance_basis_points: TOLERANCE_BASIS_POINTS,
+            })
+        );
+        assert!(!market.oracle_override);
+
+        buy(
+            &mut context,
+            &market_keypair,
+            &selling_resource_keypair,
+            &admin_wallet,
+            &user_wallet,
+            vec![AccountMeta::new_readonly(price_feed, false)],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            already_bought(&mut context, &market_keypair, &user_wallet).await,
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn fail_treasury_depegged() {
+        let price_feed = Pubkey::new_unique();
+        let mut context = setup_context(&price_feed, 95_000_000).await;
+        let (admin_wallet, _owner, selling_resource_keypair, market_keypair, user_wallet) =
+            setup_oracle_market(&mut context, &price_feed).await;
+
+        let err = buy(
+            &mut context,
+            &market_keypair,
+            &selling_resource_keypair,
+            &admin_wallet,
+            &user_wallet,
+            vec![AccountMeta::new_readonly(price_feed, false)],
+        )
+        .await
+        .unwrap_err();
+
+        match err {
+            TransportError::TransactionError(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(code),
+            )) => {
+                assert_eq!(code, ERROR_CODE_OFFSET + ErrorCode::TreasuryDepegged as u32);
+            }
+            _ => assert!(false),
+        }
+    }
+
+    #[tokio::test]
+    async fn success_oracle_override() {
+        let price_feed = Pubkey::new_unique();
+        let mut context = setup_context(&price_feed, 95_000_000).await;
+        let (admin_wallet, owner, selling_resource_keypair, market_keypair, user_wallet) =
+            setup_oracle_market(&mut context, &price_feed).await;
+
+        set_price_oracle(
+            &mut context,
+            &market_keypair,
+            &owner,
+            Some(PriceOracle {
+                price_feed,
+                tolerance_basis_points: TOLERANCE_BASIS_POINTS,
+            }),
+            true,
+        )
+        .await
+        .unwrap();
+
+        buy(
+            &mut context,
+            &market_keypair,
+            &selling_resource_keypair,
+            &admin_wallet,
+            &user_wallet,
+            vec![AccountMeta::new_readonly(price_feed, false)],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            already_bought(&mut context, &market_keypair, &user_wallet).await,
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn fail_invalid_price_feed() {
+        let price_feed = Pubkey::new_unique();
+        let mut context = setup_context(&price_feed, 99_500_000).await;
+        let (admin_wallet, _owner, selling_resource_keypair, market_keypair, user_wallet) =
+            setup_oracle_market(&mut context, &price_feed).await;
+
+        let err = buy(
+            &mut context,
+            &market_keypair,
+            &selling_resource_keypair,
+            &admin_wallet,
+            &user_wallet,
+            vec![AccountMeta::new_readonly(Pubkey::new_unique(), false)],
+        )
+        .await
+        .unwrap_err();
+
+        match err {
+            TransportError::TransactionError(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(code),
+            )) => {
+                assert_eq!(code, ERROR_CODE_OFFSET + ErrorCode::InvalidPriceFeed as u32);
+            }
+            _ => assert!(false),
+        }
+    }
+}
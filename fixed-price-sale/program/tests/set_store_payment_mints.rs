@@ -0,0 +1,318 @@
+mod utils;
+
+#[cfg(feature = "test-bpf")]
+mod set_store_payment_mints {
+    use crate::utils::setup_functions::{
+        setup_selling_resource, setup_selling_resource_with_owner, setup_store,
+    };
+    use anchor_lang::{
+        error::ERROR_CODE_OFFSET, AccountDeserialize, InstructionData, ToAccountMetas,
+    };
+    use mpl_fixed_price_sale::{
+        accounts as mpl_fixed_price_sale_accounts,
+        error::ErrorCode,
+        instruction as mpl_fixed_price_sale_instruction,
+        state::{Market, SellingResourceType, StorePaymentMints},
+        utils::{
+            find_global_config_address, find_market_address, find_market_name_registry_address,
+            find_store_payment_mints_address, find_treasury_owner_address,
+        },
+    };
+    use solana_program_test::*;
+    use solana_sdk::{
+        instruction::{Instruction, InstructionError},
+        pubkey::Pubkey,
+        signature::Keypair,
+        signer::Signer,
+        system_program,
+        sysvar::clock::Clock,
+        transaction::{Transaction, TransactionError},
+        transport::TransportError,
+    };
+
+    use crate::setup_context;
+
+    async fn set_store_payment_mints(
+        context: &mut ProgramTestContext,
+        admin_wallet: &Keypair,
+        store: &Pubkey,
+        mints: Vec<Pubkey>,
+        admin_override: bool,
+    ) -> Result<(), TransportError> {
+        let accounts = mpl_fixed_price_sale_accounts::SetStorePaymentMints {
+            store: *store,
+            admin: admin_wallet.pubkey(),
+            store_payment_mints: find_store_payment_mints_address(store).0,
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::SetStorePaymentMints {
+            mints,
+            admin_override,
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, admin_wallet],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    // Creates a native SOL market at the next PDA of `selling_resource`
+    async fn create_native_market(
+        context: &mut ProgramTestContext,
+        store: &Pubkey,
+        selling_resource_owner: &Keypair,
+        selling_resource: &Pubkey,
+    ) -> Result<Pubkey, TransportError> {
+        let (market, _) = find_market_address(store, selling_resource, 0);
+        let treasury_mint = system_program::id();
+        let (treasury_owner, treasury_owner_bump) =
+            find_treasury_owner_address(&treasury_mint, selling_resource);
+        let name = "Marktname";
+
+        let accounts = mpl_fixed_price_sale_accounts::CreateMarketPda {
+            market,
+            store: *store,
+            selling_resource_owner: selling_resource_owner.pubkey(),
+            selling_resource: *selling_resource,
+            mint: treasury_mint,
+            treasury_holder: treasury_owner,
+            owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(store, name).0,
+            store_payment_mints: find_store_payment_mints_address(store).0,
+            global_config: find_global_config_address().0,
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None);
+
+        let start_date = context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+            .unix_timestamp
+            + 1;
+
+        let data = mpl_fixed_price_sale_instruction::CreateMarketPda {
+            _treasury_owner_bump: treasury_owner_bump,
+            name: name.to_string(),
+            description: "Marktbeschreibung".to_string(),
+            mutable: true,
+            price: 1_000_000,
+            pieces_in_one_wallet: Some(1),
+            start_date: start_date as u64,
+            end_date: None,
+            gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, selling_resource_owner],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await?;
+
+        Ok(market)
+    }
+
+    #[tokio::test]
+    async fn success() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _) = setup_selling_resource(
+            &mut context,
+            &admin_wallet,
+            &store_keypair,
+            100,
+            None,
+            true,
+            false,
+        )
+        .await;
+
+        set_store_payment_mints(
+            &mut context,
+            &admin_wallet,
+            &store_keypair.pubkey(),
+            vec![system_program::id()],
+            false,
+        )
+        .await
+        .unwrap();
+
+        let store_payment_mints_acc = context
+            .banks_client
+            .get_account(find_store_payment_mints_address(&store_keypair.pubkey()).0)
+            .await
+            .expect("account not found")
+            .expect("account empty");
+        let store_payment_mints =
+            StorePaymentMints::try_deserialize(&mut store_payment_mints_acc.data.as_ref()).unwrap();
+
+        assert_eq!(store_keypair.pubkey(), store_payment_mints.store);
+        assert_eq!(vec![system_program::id()], store_payment_mints.mints);
+
+        let market = create_native_market(
+            &mut context,
+            &store_keypair.pubkey(),
+            &selling_resource_owner_keypair,
+            &selling_resource_keypair.pubkey(),
+        )
+        .await
+        .unwrap();
+
+        let market_acc = context
+            .banks_client
+            .get_account(market)
+            .await
+            .expect("account not found")
+            .expect("account empty");
+        let market_data = Market::try_deserialize(&mut market_acc.data.as_ref()).unwrap();
+
+        assert_eq!(system_program::id(), market_data.treasury_mint);
+    }
+
+    #[tokio::test]
+    async fn failure_mint_not_allowed() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _) = setup_selling_resource(
+            &mut context,
+            &admin_wallet,
+            &store_keypair,
+            100,
+            None,
+            true,
+            false,
+        )
+        .await;
+
+        set_store_payment_mints(
+            &mut context,
+            &admin_wallet,
+            &store_keypair.pubkey(),
+            vec![Pubkey::new_unique()],
+            true,
+        )
+        .await
+        .unwrap();
+
+        let err = create_native_market(
+            &mut context,
+            &store_keypair.pubkey(),
+            &selling_resource_owner_keypair,
+            &selling_resource_keypair.pubkey(),
+        )
+        .await
+        .unwrap_err();
+
+        match err {
+            TransportError::TransactionError(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(err_code),
+            )) => {
+                assert_eq!(
+                    ERROR_CODE_OFFSET + ErrorCode::PaymentMintNotAllowed as u32,
+                    err_code
+                );
+            }
+            _ => assert!(false),
+        }
+    }
+
+    #[tokio::test]
+    async fn success_admin_override() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        let (selling_resource_keypair, _, _) = setup_selling_resource_with_owner(
+            &mut context,
+            &admin_wallet,
+            &store_keypair,
+            Keypair::from_bytes(&admin_wallet.to_bytes()).unwrap(),
+            100,
+            None,
+            true,
+            false,
+            1,
+            SellingResourceType::Editions,
+        )
+        .await;
+
+        set_store_payment_mints(
+            &mut context,
+            &admin_wallet,
+            &store_keypair.pubkey(),
+            vec![Pubkey::new_unique()],
+            true,
+        )
+        .await
+        .unwrap();
+
+        create_native_market(
+            &mut context,
+            &store_keypair.pubkey(),
+            &admin_wallet,
+            &selling_resource_keypair.pubkey(),
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn failure_too_many_mints() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        let mints = (0..9).map(|_| Pubkey::new_unique()).collect();
+        let err = set_store_payment_mints(
+            &mut context,
+            &admin_wallet,
+            &store_keypair.pubkey(),
+            mints,
+            false,
+        )
+        .await
+        .unwrap_err();
+
+        match err {
+            TransportError::TransactionError(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(err_code),
+            )) => {
+                assert_eq!(
+                    ERROR_CODE_OFFSET + ErrorCode::TooManyPaymentMints as u32,
+                    err_code
+                );
+            }
+            _ => assert!(false),
+        }
+    }
+}
@@ -0,0 +1,202 @@
+mod utils;
+
+#[cfg(feature = "test-bpf")]
+mod store_sequence {
+    use crate::{
+        setup_context,
+        utils::setup_functions::{setup_market, setup_selling_resource, setup_store},
+    };
+    use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+    use mpl_fixed_price_sale::{
+        accounts as mpl_fixed_price_sale_accounts, instruction as mpl_fixed_price_sale_instruction,
+        state::{Market, MarketState, Store},
+        utils::find_market_manager_address,
+    };
+    use solana_program::clock::Clock;
+    use solana_program_test::*;
+    use solana_sdk::{
+        instruction::{Instruction, InstructionError},
+        pubkey::Pubkey,
+        signature::Keypair,
+        signer::Signer,
+        sysvar,
+        transaction::{Transaction, TransactionError},
+        transport::TransportError,
+    };
+
+    /// Suspend the market, or resume it, passing `store` as the store of the market
+    async fn set_suspended(
+        context: &mut ProgramTestContext,
+        market_keypair: &Keypair,
+        store: &Pubkey,
+        owner: &Keypair,
+        suspended: bool,
+    ) -> Result<(), TransportError> {
+        let market_manager =
+            find_market_manager_address(&market_keypair.pubkey(), &owner.pubkey()).0;
+        let (accounts, data) = if suspended {
+            (
+                mpl_fixed_price_sale_accounts::SuspendMarket {
+                    market: market_keypair.pubkey(),
+                    store: *store,
+                    owner: owner.pubkey(),
+                    market_manager,
+                    clock: sysvar::clock::id(),
+                }
+                .to_account_metas(None),
+                mpl_fixed_price_sale_instruction::SuspendMarket {}.data(),
+            )
+        } else {
+            (
+                mpl_fixed_price_sale_accounts::ResumeMarket {
+                    market: market_keypair.pubkey(),
+                    store: *store,
+                    owner: owner.pubkey(),
+                    market_manager,
+                    clock: sysvar::clock::id(),
+                }
+                .to_account_metas(None),
+                mpl_fixed_price_sale_instruction::ResumeMarket {}.data(),
+            )
+        };
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, owner],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    async fn get_sequence(context: &mut ProgramTestContext, store: &Pubkey) -> u64 {
+        let store_acc = context
+            .banks_client
+            .get_account(*store)
+            .await
+            .unwrap()
+            .unwrap();
+
+        Store::try_deserialize(&mut store_acc.data.as_ref())
+            .unwrap()
+            .sequence
+    }
+
+    /// Started market of a new store, with the owner of its selling resource
+    async fn setup(context: &mut ProgramTestContext) -> (Keypair, Keypair, Keypair) {
+        let (admin_wallet, store_keypair) = setup_store(context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _vault) =
+            setup_selling_resource(
+                context,
+                &admin_wallet,
+                &store_keypair,
+                100,
+                None,
+                true,
+                false,
+            )
+            .await;
+
+        let market_keypair = setup_market(
+            context,
+            &admin_wallet,
+            &store_keypair,
+            &selling_resource_keypair,
+            &selling_resource_owner_keypair,
+        )
+        .await;
+
+        let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        context.warp_to_slot(clock.slot + 1500).unwrap();
+
+        (
+            store_keypair,
+            market_keypair,
+            selling_resource_owner_keypair,
+        )
+    }
+
+    #[tokio::test]
+    async fn success() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (store_keypair, market_keypair, owner) = setup(&mut context).await;
+        let sequence = get_sequence(&mut context, &store_keypair.pubkey()).await;
+
+        // Consecutive state changes get consecutive sequence numbers, so indexers can spot a missed event
+        for (expected, suspended) in [(sequence + 1, true), (sequence + 2, false)] {
+            set_suspended(
+                &mut context,
+                &market_keypair,
+                &store_keypair.pubkey(),
+                &owner,
+                suspended,
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(
+                get_sequence(&mut context, &store_keypair.pubkey()).await,
+                expected
+            );
+        }
+
+        let market_acc = context
+            .banks_client
+            .get_account(market_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let market = Market::try_deserialize(&mut market_acc.data.as_ref()).unwrap();
+        assert_eq!(market.state, MarketState::Active);
+    }
+
+    #[tokio::test]
+    async fn fail_store_of_other_market() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (store_keypair, market_keypair, owner) = setup(&mut context).await;
+        let (_, other_store_keypair) = setup_store(&mut context).await;
+        let sequence = get_sequence(&mut context, &store_keypair.pubkey()).await;
+        let other_sequence = get_sequence(&mut context, &other_store_keypair.pubkey()).await;
+
+        let err = set_suspended(
+            &mut context,
+            &market_keypair,
+            &other_store_keypair.pubkey(),
+            &owner,
+            true,
+        )
+        .await
+        .unwrap_err();
+
+        match err {
+            TransportError::TransactionError(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(err_code),
+            )) => {
+                assert_eq!(
+                    err_code,
+                    anchor_lang::error::ErrorCode::ConstraintHasOne as u32
+                );
+            }
+            _ => assert!(false),
+        }
+
+        // Neither store saw a state change
+        assert_eq!(
+            get_sequence(&mut context, &store_keypair.pubkey()).await,
+            sequence
+        );
+        assert_eq!(
+            get_sequence(&mut context, &other_store_keypair.pubkey()).await,
+            other_sequence
+        );
+    }
+}
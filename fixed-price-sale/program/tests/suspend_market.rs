@@ -12,12 +12,16 @@ mod suspend_market {
     use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
     use mpl_fixed_price_sale::{
         accounts as mpl_fixed_price_sale_accounts, instruction as mpl_fixed_price_sale_instruction,
-        state::{Market, MarketState},
-        utils::find_treasury_owner_address,
+        state::{Market, MarketState, Store},
+        utils::{
+            find_global_config_address, find_market_manager_address,
+            find_market_name_registry_address, find_store_payment_mints_address,
+            find_treasury_owner_address,
+        },
     };
     use solana_program_test::*;
     use solana_sdk::{
-        instruction::Instruction,
+        instruction::{AccountMeta, Instruction},
         signature::Keypair,
         signer::Signer,
         system_program,
@@ -90,6 +94,10 @@ mod suspend_market {
             mint: treasury_mint_keypair.pubkey(),
             treasury_holder: treasury_holder_keypair.pubkey(),
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -104,6 +112,10 @@ mod suspend_market {
             start_date: start_date as u64,
             end_date: None,
             gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -129,10 +141,26 @@ mod suspend_market {
         let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
         context.warp_to_slot(clock.slot + 1500).unwrap();
 
+        let store_acc = context
+            .banks_client
+            .get_account(store_keypair.pubkey())
+            .await
+            .expect("account not found")
+            .expect("account empty");
+        let sequence = Store::try_deserialize(&mut store_acc.data.as_ref())
+            .unwrap()
+            .sequence;
+
         // SuspendMarket
         let accounts = mpl_fixed_price_sale_accounts::SuspendMarket {
             market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
             owner: selling_resource_owner_keypair.pubkey(),
+            market_manager: find_market_manager_address(
+                &market_keypair.pubkey(),
+                &selling_resource_owner_keypair.pubkey(),
+            )
+            .0,
             clock: sysvar::clock::id(),
         }
         .to_account_metas(None);
@@ -163,6 +191,16 @@ mod suspend_market {
 
         let market_data = Market::try_deserialize(&mut market_acc.data.as_ref()).unwrap();
         assert_eq!(market_data.state, MarketState::Suspended);
+
+        // Every state change under the store bumps its sequence
+        let store_acc = context
+            .banks_client
+            .get_account(store_keypair.pubkey())
+            .await
+            .expect("account not found")
+            .expect("account empty");
+        let store_data = Store::try_deserialize(&mut store_acc.data.as_ref()).unwrap();
+        assert_eq!(store_data.sequence, sequence + 1);
     }
 
     #[tokio::test]
@@ -229,6 +267,10 @@ mod suspend_market {
             mint: treasury_mint_keypair.pubkey(),
             treasury_holder: treasury_holder_keypair.pubkey(),
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -243,6 +285,10 @@ mod suspend_market {
             start_date: start_date as u64,
             end_date: None,
             gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -271,7 +317,13 @@ mod suspend_market {
         // SuspendMarket instruction
         let accounts = mpl_fixed_price_sale_accounts::SuspendMarket {
             market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
             owner: selling_resource_owner_keypair.pubkey(),
+            market_manager: find_market_manager_address(
+                &market_keypair.pubkey(),
+                &selling_resource_owner_keypair.pubkey(),
+            )
+            .0,
             clock: sysvar::clock::id(),
         }
         .to_account_metas(None);
@@ -368,6 +420,10 @@ mod suspend_market {
             mint: treasury_mint_keypair.pubkey(),
             treasury_holder: treasury_holder_keypair.pubkey(),
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -382,6 +438,10 @@ mod suspend_market {
             start_date: start_date as u64,
             end_date: None,
             gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -410,7 +470,13 @@ mod suspend_market {
         // SuspendMarket
         let accounts = mpl_fixed_price_sale_accounts::SuspendMarket {
             market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
             owner: selling_resource_owner_keypair.pubkey(),
+            market_manager: find_market_manager_address(
+                &market_keypair.pubkey(),
+                &selling_resource_owner_keypair.pubkey(),
+            )
+            .0,
             clock: sysvar::clock::id(),
         }
         .to_account_metas(None);
@@ -438,7 +504,13 @@ mod suspend_market {
         // SuspendMarket
         let accounts = mpl_fixed_price_sale_accounts::SuspendMarket {
             market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
             owner: selling_resource_owner_keypair.pubkey(),
+            market_manager: find_market_manager_address(
+                &market_keypair.pubkey(),
+                &selling_resource_owner_keypair.pubkey(),
+            )
+            .0,
             clock: sysvar::clock::id(),
         }
         .to_account_metas(None);
@@ -535,6 +607,10 @@ mod suspend_market {
             mint: treasury_mint_keypair.pubkey(),
             treasury_holder: treasury_holder_keypair.pubkey(),
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -549,6 +625,10 @@ mod suspend_market {
             start_date: start_date as u64,
             end_date: None,
             gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -575,12 +655,16 @@ mod suspend_market {
         context.warp_to_slot(clock.slot + 1500).unwrap();
 
         // CloseMarket
-        let accounts = mpl_fixed_price_sale_accounts::CloseMarket {
+        let mut accounts = mpl_fixed_price_sale_accounts::CloseMarket {
             market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
             owner: selling_resource_owner_keypair.pubkey(),
             clock: sysvar::clock::id(),
         }
         .to_account_metas(None);
+        let (market_name_registry, _) =
+            find_market_name_registry_address(&store_keypair.pubkey(), &name);
+        accounts.push(AccountMeta::new(market_name_registry, false));
 
         let data = mpl_fixed_price_sale_instruction::CloseMarket {}.data();
 
@@ -602,7 +686,13 @@ mod suspend_market {
         // SuspendMarket
         let accounts = mpl_fixed_price_sale_accounts::SuspendMarket {
             market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
             owner: selling_resource_owner_keypair.pubkey(),
+            market_manager: find_market_manager_address(
+                &market_keypair.pubkey(),
+                &selling_resource_owner_keypair.pubkey(),
+            )
+            .0,
             clock: sysvar::clock::id(),
         }
         .to_account_metas(None);
@@ -701,6 +791,10 @@ mod suspend_market {
             mint: treasury_mint_keypair.pubkey(),
             treasury_holder: treasury_holder_keypair.pubkey(),
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -715,6 +809,10 @@ mod suspend_market {
             start_date: start_date as u64,
             end_date: Some(end_date as u64),
             gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -743,7 +841,13 @@ mod suspend_market {
         // SuspendMarket
         let accounts = mpl_fixed_price_sale_accounts::SuspendMarket {
             market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
             owner: selling_resource_owner_keypair.pubkey(),
+            market_manager: find_market_manager_address(
+                &market_keypair.pubkey(),
+                &selling_resource_owner_keypair.pubkey(),
+            )
+            .0,
             clock: sysvar::clock::id(),
         }
         .to_account_metas(None);
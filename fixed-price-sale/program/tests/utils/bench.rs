@@ -0,0 +1,594 @@
+#![allow(unused)]
+
+use super::{
+    helpers::{airdrop, create_mint, create_token_account, mint_to},
+    setup_functions::{setup_selling_resource_with_owner, setup_store},
+};
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use mpl_fixed_price_sale::{
+    accounts as mpl_fixed_price_sale_accounts, instruction as mpl_fixed_price_sale_instruction,
+    state::{SellingResource, SellingResourceType},
+    utils::{
+        find_claim_tally_address, find_global_config_address, find_market_name_registry_address,
+        find_payout_ticket_address, find_primary_metadata_creators, find_provenance_record_address,
+        find_store_payment_mints_address, find_trade_history_address, find_treasury_owner_address,
+        find_vault_owner_address,
+    },
+};
+use serde_json::json;
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    compute_budget::ComputeBudgetInstruction,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    system_instruction, system_program,
+    sysvar::{self, clock::Clock},
+    transaction::{Transaction, TransactionError},
+    transport::TransportError,
+};
+
+/// Largest compute budget a transaction can request
+pub const MAX_COMPUTE_UNITS: u32 = 1_400_000;
+
+const PRICE: u64 = 1_000_000;
+
+/// Currency the benchmarked market is priced in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Treasury {
+    Native,
+    Spl,
+}
+
+/// Treasury and number of primary creators of one benchmarked market
+#[derive(Clone, Copy, Debug)]
+pub struct Scenario {
+    pub treasury: Treasury,
+    pub creators: usize,
+}
+
+impl Scenario {
+    /// Report key of `instruction` in this scenario, e.g. `buy_native_5_creators`
+    pub fn name(&self, instruction: &str) -> String {
+        let treasury = match self.treasury {
+            Treasury::Native => "native",
+            Treasury::Spl => "spl",
+        };
+        format!("{}_{}_{}_creators", instruction, treasury, self.creators)
+    }
+}
+
+/// Compute units one instruction consumed together with the accounts it allocated
+#[derive(Debug)]
+pub struct Measurement {
+    pub name: String,
+    pub compute_units: u32,
+    pub accounts_created: usize,
+    pub account_bytes: usize,
+}
+
+impl Measurement {
+    pub fn to_json(&self, baseline: Option<u64>) -> serde_json::Value {
+        json!({
+            "name": self.name,
+            "compute_units": self.compute_units,
+            "baseline": baseline,
+            "accounts_created": self.accounts_created,
+            "account_bytes": self.account_bytes,
+        })
+    }
+}
+
+/// Bisect the smallest compute budget `instruction` succeeds with, then execute it with that budget.
+/// Probes are followed by a transfer which always fails, so they never change state.
+pub async fn measure(
+    context: &mut ProgramTestContext,
+    name: String,
+    instruction: Instruction,
+    signers: Vec<Keypair>,
+) -> Measurement {
+    let before = snapshot(context, &instruction).await;
+
+    if !fits(context, &name, &instruction, &signers, MAX_COMPUTE_UNITS).await {
+        panic!("{} exceeds {} compute units", name, MAX_COMPUTE_UNITS);
+    }
+    let (mut low, mut high) = (0, MAX_COMPUTE_UNITS);
+    while high - low > 1 {
+        let units = low + (high - low) / 2;
+        if fits(context, &name, &instruction, &signers, units).await {
+            high = units;
+        } else {
+            low = units;
+        }
+    }
+
+    let payer = clone_keypair(&context.payer);
+    let mut all_signers = vec![&payer];
+    all_signers.extend(signers.iter());
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            ComputeBudgetInstruction::request_units(high, 0),
+            instruction.clone(),
+        ],
+        Some(&payer.pubkey()),
+        &all_signers,
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let after = snapshot(context, &instruction).await;
+    let created = before
+        .iter()
+        .zip(after.iter())
+        .filter(|(before, after)| *before == 0 && *after > 0);
+
+    Measurement {
+        name,
+        compute_units: high,
+        accounts_created: created.clone().count(),
+        account_bytes: created.map(|(_, after)| *after).sum(),
+    }
+}
+
+async fn fits(
+    context: &mut ProgramTestContext,
+    name: &str,
+    instruction: &Instruction,
+    signers: &[Keypair],
+    units: u32,
+) -> bool {
+    let payer = clone_keypair(&context.payer);
+    let mut all_signers = vec![&payer];
+    all_signers.extend(signers.iter());
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            ComputeBudgetInstruction::request_units(units, 0),
+            instruction.clone(),
+            system_instruction::transfer(&payer.pubkey(), &payer.pubkey(), u64::MAX),
+        ],
+        Some(&payer.pubkey()),
+        &all_signers,
+        context.last_blockhash,
+    );
+
+    match context.banks_client.process_transaction(tx).await {
+        Err(TransportError::TransactionError(TransactionError::InstructionError(2, _))) => true,
+        Err(TransportError::TransactionError(TransactionError::InstructionError(
+            1,
+            InstructionError::ComputationalBudgetExceeded
+            | InstructionError::ProgramFailedToComplete,
+        ))) => false,
+        result => panic!("{} failed with {} compute units: {:?}", name, units, result),
+    }
+}
+
+/// Data length of every account of `instruction`, zero for missing ones
+async fn snapshot(context: &mut ProgramTestContext, instruction: &Instruction) -> Vec<usize> {
+    let mut lengths = Vec::with_capacity(instruction.accounts.len());
+    for meta in &instruction.accounts {
+        let account = context.banks_client.get_account(meta.pubkey).await.unwrap();
+        lengths.push(
+            account
+                .map(|account| account.data.len())
+                .unwrap_or_default(),
+        );
+    }
+    lengths
+}
+
+fn clone_keypair(keypair: &Keypair) -> Keypair {
+    Keypair::from_bytes(&keypair.to_bytes()).unwrap()
+}
+
+/// Market of one `Scenario`, built up to the instruction under measurement
+pub struct BenchMarket {
+    scenario: Scenario,
+    store: Pubkey,
+    owner: Keypair,
+    selling_resource: Pubkey,
+    treasury_mint: Pubkey,
+    treasury_mint_authority: Keypair,
+    treasury_holder: Pubkey,
+    treasury_owner: Pubkey,
+    treasury_owner_bump: u8,
+    primary_metadata_creators: Pubkey,
+    creators: Vec<Keypair>,
+    market: Keypair,
+}
+
+impl BenchMarket {
+    /// Store, selling resource, primary creators and treasury of `scenario`, without the market itself
+    pub async fn setup(context: &mut ProgramTestContext, scenario: Scenario) -> Self {
+        let (admin_wallet, store_keypair) = setup_store(context).await;
+        let (selling_resource_keypair, owner, _vault) = setup_selling_resource_with_owner(
+            context,
+            &admin_wallet,
+            &store_keypair,
+            Keypair::new(),
+            100,
+            None,
+            true,
+            true,
+            10,
+            SellingResourceType::Editions,
+        )
+        .await;
+        let selling_resource = selling_resource_keypair.pubkey();
+        let resource = get_selling_resource(context, &selling_resource)
+            .await
+            .resource;
+
+        let creators: Vec<Keypair> = (0..scenario.creators).map(|_| Keypair::new()).collect();
+        let (master_edition_metadata, _) = find_metadata(&resource);
+        let (primary_metadata_creators, primary_metadata_creators_bump) =
+            find_primary_metadata_creators(&master_edition_metadata);
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data: mpl_fixed_price_sale_instruction::SavePrimaryMetadataCreators {
+                primary_metadata_creators_bump,
+                creators: creators
+                    .iter()
+                    .enumerate()
+                    .map(|(i, creator)| mpl_fixed_price_sale::state::Creator {
+                        address: creator.pubkey(),
+                        verified: false,
+                        // Remainder goes to the first creator, so shares add up to 100
+                        share: (100 / scenario.creators
+                            + if i == 0 { 100 % scenario.creators } else { 0 })
+                            as u8,
+                    })
+                    .collect(),
+            }
+            .data(),
+            accounts: mpl_fixed_price_sale_accounts::SavePrimaryMetadataCreators {
+                admin: owner.pubkey(),
+                metadata: master_edition_metadata,
+                primary_metadata_creators,
+                system_program: system_program::id(),
+            }
+            .to_account_metas(None),
+        };
+        process(context, instruction, &[&owner]).await.unwrap();
+
+        let treasury_mint_authority = Keypair::new();
+        let (treasury_mint, treasury_holder, treasury_owner, treasury_owner_bump) =
+            match scenario.treasury {
+                Treasury::Native => {
+                    let treasury_mint = system_program::id();
+                    let (treasury_owner, treasury_owner_bump) =
+                        find_treasury_owner_address(&treasury_mint, &selling_resource);
+                    (
+                        treasury_mint,
+                        treasury_owner,
+                        treasury_owner,
+                        treasury_owner_bump,
+                    )
+                }
+                Treasury::Spl => {
+                    let treasury_mint = Keypair::new();
+                    create_mint(
+                        context,
+                        &treasury_mint,
+                        &treasury_mint_authority.pubkey(),
+                        0,
+                    )
+                    .await;
+                    let (treasury_owner, treasury_owner_bump) =
+                        find_treasury_owner_address(&treasury_mint.pubkey(), &selling_resource);
+                    let treasury_holder = Keypair::new();
+                    create_token_account(
+                        context,
+                        &treasury_holder,
+                        &treasury_mint.pubkey(),
+                        &treasury_owner,
+                    )
+                    .await;
+                    (
+                        treasury_mint.pubkey(),
+                        treasury_holder.pubkey(),
+                        treasury_owner,
+                        treasury_owner_bump,
+                    )
+                }
+            };
+
+        Self {
+            scenario,
+            store: store_keypair.pubkey(),
+            owner,
+            selling_resource,
+            treasury_mint,
+            treasury_mint_authority,
+            treasury_holder,
+            treasury_owner,
+            treasury_owner_bump,
+            primary_metadata_creators,
+            creators,
+            market: Keypair::new(),
+        }
+    }
+
+    pub async fn create_market(
+        &self,
+        context: &mut ProgramTestContext,
+    ) -> (Instruction, Vec<Keypair>) {
+        let start_date = context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+            .unix_timestamp as u64
+            + 1;
+        let name = "Benchmark".to_string();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data: mpl_fixed_price_sale_instruction::CreateMarket {
+                _treasury_owner_bump: self.treasury_owner_bump,
+                name: name.to_owned(),
+                description: "Benchmarked market".to_string(),
+                mutable: true,
+                price: PRICE,
+                pieces_in_one_wallet: None,
+                start_date,
+                end_date: None,
+                gating_config: None,
+                metadata_overrides: None,
+                pda_mints: false,
+                processor_fee: None,
+                sweep_basis_points: None,
+            }
+            .data(),
+            accounts: mpl_fixed_price_sale_accounts::CreateMarket {
+                market: self.market.pubkey(),
+                store: self.store,
+                selling_resource_owner: self.owner.pubkey(),
+                selling_resource: self.selling_resource,
+                mint: self.treasury_mint,
+                treasury_holder: self.treasury_holder,
+                owner: self.treasury_owner,
+                market_name_registry: find_market_name_registry_address(&self.store, &name).0,
+                store_payment_mints: find_store_payment_mints_address(&self.store).0,
+                global_config: find_global_config_address().0,
+                system_program: system_program::id(),
+            }
+            .to_account_metas(None),
+        };
+
+        (
+            instruction,
+            vec![clone_keypair(&self.market), clone_keypair(&self.owner)],
+        )
+    }
+
+    /// Start the market, create a funded buyer with an empty edition mint and build the `buy` of its purchase
+    pub async fn buy(&self, context: &mut ProgramTestContext) -> (Instruction, Vec<Keypair>) {
+        let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        context.warp_to_slot(clock.slot + 1500).unwrap();
+
+        let selling_resource = get_selling_resource(context, &self.selling_resource).await;
+
+        let user_wallet = Keypair::new();
+        airdrop(context, &user_wallet.pubkey(), PRICE + 1_000_000_000).await;
+        let user_token_account = match self.scenario.treasury {
+            Treasury::Native => user_wallet.pubkey(),
+            Treasury::Spl => {
+                let user_token_account = Keypair::new();
+                create_token_account(
+                    context,
+                    &user_token_account,
+                    &self.treasury_mint,
+                    &user_wallet.pubkey(),
+                )
+                .await;
+                mint_to(
+                    context,
+                    &self.treasury_mint,
+                    &user_token_account.pubkey(),
+                    &self.treasury_mint_authority,
+                    PRICE,
+                )
+                .await;
+                user_token_account.pubkey()
+            }
+        };
+
+        let new_mint = Keypair::new();
+        create_mint(context, &new_mint, &user_wallet.pubkey(), 0).await;
+        let new_token_account = Keypair::new();
+        create_token_account(
+            context,
+            &new_token_account,
+            &new_mint.pubkey(),
+            &user_wallet.pubkey(),
+        )
+        .await;
+        mint_to(
+            context,
+            &new_mint.pubkey(),
+            &new_token_account.pubkey(),
+            &user_wallet,
+            1,
+        )
+        .await;
+
+        let (trade_history, trade_history_bump) =
+            find_trade_history_address(&user_wallet.pubkey(), &self.market.pubkey());
+        let (owner, vault_owner_bump) =
+            find_vault_owner_address(&selling_resource.resource, &selling_resource.store);
+        let (edition_marker, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                selling_resource.resource.as_ref(),
+                mpl_token_metadata::state::EDITION.as_bytes(),
+                selling_resource.supply.to_string().as_bytes(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data: mpl_fixed_price_sale_instruction::Buy {
+                _trade_history_bump: trade_history_bump,
+                vault_owner_bump,
+                client_nonce: None,
+            }
+            .data(),
+            accounts: mpl_fixed_price_sale_accounts::Buy {
+                market: self.market.pubkey(),
+                selling_resource: self.selling_resource,
+                user_token_account,
+                user_wallet: user_wallet.pubkey(),
+                trade_history,
+                treasury_holder: self.treasury_holder,
+                new_metadata: find_metadata(&new_mint.pubkey()).0,
+                new_edition: find_edition(&new_mint.pubkey()).0,
+                master_edition: find_edition(&selling_resource.resource).0,
+                new_mint: new_mint.pubkey(),
+                edition_marker,
+                vault: selling_resource.vault,
+                store: selling_resource.store,
+                owner,
+                new_token_account: new_token_account.pubkey(),
+                master_edition_metadata: find_metadata(&selling_resource.resource).0,
+                clock: sysvar::clock::id(),
+                rent: sysvar::rent::id(),
+                token_metadata_program: mpl_token_metadata::id(),
+                token_program: spl_token::id(),
+                system_program: system_program::id(),
+                associated_token_program: spl_associated_token_account::id(),
+                provenance_record: find_provenance_record_address(&new_mint.pubkey()).0,
+            }
+            .to_account_metas(None),
+        };
+
+        (instruction, vec![user_wallet])
+    }
+
+    /// Close the market and build the `withdraw` instruction paying out the first primary creator
+    pub async fn withdraw(&self, context: &mut ProgramTestContext) -> (Instruction, Vec<Keypair>) {
+        let mut accounts = mpl_fixed_price_sale_accounts::CloseMarket {
+            market: self.market.pubkey(),
+            store: self.store,
+            owner: self.owner.pubkey(),
+            clock: sysvar::clock::id(),
+        }
+        .to_account_metas(None);
+        accounts.push(AccountMeta::new(
+            find_market_name_registry_address(&self.store, "Benchmark").0,
+            false,
+        ));
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data: mpl_fixed_price_sale_instruction::CloseMarket {}.data(),
+            accounts,
+        };
+        process(context, instruction, &[&self.owner]).await.unwrap();
+
+        let selling_resource = get_selling_resource(context, &self.selling_resource).await;
+        let creator = self.creators[0].pubkey();
+        let (payout_ticket, payout_ticket_bump) =
+            find_payout_ticket_address(&self.market.pubkey(), &creator);
+        let destination = match self.scenario.treasury {
+            Treasury::Native => creator,
+            Treasury::Spl => spl_associated_token_account::get_associated_token_address(
+                &creator,
+                &self.treasury_mint,
+            ),
+        };
+
+        let mut accounts = mpl_fixed_price_sale_accounts::Withdraw {
+            market: self.market.pubkey(),
+            store: self.store,
+            selling_resource: self.selling_resource,
+            metadata: find_metadata(&selling_resource.resource).0,
+            treasury_holder: self.treasury_holder,
+            treasury_mint: self.treasury_mint,
+            owner: self.treasury_owner,
+            destination,
+            funder: creator,
+            payer: context.payer.pubkey(),
+            payout_ticket,
+            claim_tally: find_claim_tally_address(&self.market.pubkey()).0,
+            rent: sysvar::rent::id(),
+            clock: sysvar::clock::id(),
+            token_program: spl_token::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None);
+        accounts.push(AccountMeta::new(self.primary_metadata_creators, false));
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data: mpl_fixed_price_sale_instruction::Withdraw {
+                treasury_owner_bump: self.treasury_owner_bump,
+                payout_ticket_bump,
+            }
+            .data(),
+            accounts,
+        };
+
+        (instruction, Vec::new())
+    }
+}
+
+async fn process(
+    context: &mut ProgramTestContext,
+    instruction: Instruction,
+    signers: &[&Keypair],
+) -> Result<(), TransportError> {
+    let payer = clone_keypair(&context.payer);
+    let mut all_signers = vec![&payer];
+    all_signers.extend_from_slice(signers);
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &all_signers,
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await
+}
+
+async fn get_selling_resource(
+    context: &mut ProgramTestContext,
+    selling_resource: &Pubkey,
+) -> SellingResource {
+    let selling_resource_acc = context
+        .banks_client
+        .get_account(*selling_resource)
+        .await
+        .expect("account not found")
+        .expect("account empty");
+
+    SellingResource::try_deserialize(&mut selling_resource_acc.data.as_ref()).unwrap()
+}
+
+fn find_metadata(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            mpl_token_metadata::state::PREFIX.as_bytes(),
+            mpl_token_metadata::id().as_ref(),
+            mint.as_ref(),
+        ],
+        &mpl_token_metadata::id(),
+    )
+}
+
+fn find_edition(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            mpl_token_metadata::state::PREFIX.as_bytes(),
+            mpl_token_metadata::id().as_ref(),
+            mint.as_ref(),
+            mpl_token_metadata::state::EDITION.as_bytes(),
+        ],
+        &mpl_token_metadata::id(),
+    )
+}
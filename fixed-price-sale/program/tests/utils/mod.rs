@@ -1,2 +1,4 @@
+pub mod bench;
 pub mod helpers;
 pub mod setup_functions;
+pub mod simulator;
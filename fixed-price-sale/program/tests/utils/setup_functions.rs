@@ -4,10 +4,15 @@ use super::helpers::{
     airdrop, create_master_edition, create_mint, create_token_account, create_token_metadata,
     mint_to,
 };
-use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
 use mpl_fixed_price_sale::{
     accounts as mpl_fixed_price_sale_accounts, instruction as mpl_fixed_price_sale_instruction,
-    utils::{find_treasury_owner_address, find_vault_owner_address},
+    state::{SellingResourceType, Store},
+    utils::{
+        find_global_config_address, find_market_name_registry_address,
+        find_store_payment_mints_address, find_treasury_owner_address, find_vault_owner_address,
+        find_versioned_vault_owner_address,
+    },
 };
 use solana_program_test::ProgramTestContext;
 use solana_sdk::{
@@ -81,9 +86,35 @@ pub async fn setup_selling_resource(
     creators: Option<Vec<mpl_token_metadata::state::Creator>>,
     selling_resource_owner_creator: bool,
     is_mutable: bool,
+) -> (Keypair, Keypair, Keypair) {
+    setup_selling_resource_with_owner(
+        context,
+        admin_wallet,
+        store_keypair,
+        Keypair::new(),
+        seller_fee_basis_points,
+        creators,
+        selling_resource_owner_creator,
+        is_mutable,
+        1,
+        SellingResourceType::Editions,
+    )
+    .await
+}
+
+pub async fn setup_selling_resource_with_owner(
+    context: &mut ProgramTestContext,
+    admin_wallet: &Keypair,
+    store_keypair: &Keypair,
+    selling_resource_owner_keypair: Keypair,
+    seller_fee_basis_points: u16,
+    creators: Option<Vec<mpl_token_metadata::state::Creator>>,
+    selling_resource_owner_creator: bool,
+    is_mutable: bool,
+    max_supply: u64,
+    resource_type: SellingResourceType,
 ) -> (Keypair, Keypair, Keypair) {
     let selling_resource_keypair = Keypair::new();
-    let selling_resource_owner_keypair = Keypair::new();
 
     // Create `SellingResource`
     let resource_mint = Keypair::new();
@@ -98,8 +129,19 @@ pub async fn setup_selling_resource(
     )
     .await;
 
-    let (vault_owner, vault_owner_bump) =
-        find_vault_owner_address(&resource_mint.pubkey(), &store_keypair.pubkey());
+    let store_acc = context
+        .banks_client
+        .get_account(store_keypair.pubkey())
+        .await
+        .expect("account not found")
+        .expect("account empty");
+    let store = Store::try_deserialize(&mut store_acc.data.as_ref()).unwrap();
+
+    let (vault_owner, vault_owner_bump) = find_versioned_vault_owner_address(
+        &resource_mint.pubkey(),
+        &store_keypair.pubkey(),
+        store.authority_version,
+    );
 
     let vault = Keypair::new();
     create_token_account(context, &vault, &resource_mint.pubkey(), &vault_owner).await;
@@ -160,7 +202,7 @@ pub async fn setup_selling_resource(
         &actual_update_authority,
         &admin_wallet,
         &metadata,
-        Some(1),
+        Some(max_supply),
     )
     .await;
 
@@ -191,7 +233,8 @@ pub async fn setup_selling_resource(
     let data = mpl_fixed_price_sale_instruction::InitSellingResource {
         master_edition_bump: master_edition_bump,
         vault_owner_bump: vault_owner_bump,
-        max_supply: Some(1),
+        max_supply: Some(max_supply),
+        resource_type,
     }
     .data();
 
@@ -265,6 +308,9 @@ pub async fn setup_market(
         mint: treasury_mint_keypair.pubkey(),
         treasury_holder: treasury_holder_keypair.pubkey(),
         owner: treasury_owner,
+        market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name).0,
+        store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+        global_config: find_global_config_address().0,
         system_program: system_program::id(),
     }
     .to_account_metas(None);
@@ -279,6 +325,10 @@ pub async fn setup_market(
         start_date: start_date as u64,
         end_date: None,
         gating_config: None,
+        metadata_overrides: None,
+        pda_mints: false,
+        processor_fee: None,
+        sweep_basis_points: None,
     }
     .data();
 
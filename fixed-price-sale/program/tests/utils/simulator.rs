@@ -0,0 +1,675 @@
+#![allow(unused)]
+
+use super::{
+    helpers::{airdrop, create_mint, create_token_account, mint_to},
+    setup_functions::{setup_selling_resource_with_owner, setup_store},
+};
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use mpl_fixed_price_sale::{
+    accounts as mpl_fixed_price_sale_accounts, instruction as mpl_fixed_price_sale_instruction,
+    state::{
+        Market, MarketState, SellingResource, SellingResourceType, MINIMUM_BALANCE_FOR_SYSTEM_ACCS,
+    },
+    utils::{
+        find_claim_tally_address, find_global_config_address, find_market_manager_address,
+        find_market_name_registry_address, find_payout_ticket_address,
+        find_primary_metadata_creators, find_provenance_record_address,
+        find_store_payment_mints_address, find_trade_history_address, find_treasury_owner_address,
+        find_vault_owner_address,
+    },
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    system_program,
+    sysvar::{self, clock::Clock},
+    transaction::Transaction,
+    transport::TransportError,
+};
+
+/// Editions are tracked by the token metadata program in markers of this many bits
+const EDITION_MARKER_BIT_SIZE: u64 = 248;
+
+/// Instruction run against the simulated market at one step
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MarketAction {
+    Buy,
+    Suspend,
+    Change,
+    Resume,
+    Close,
+    Withdraw,
+}
+
+impl MarketAction {
+    const ALL: [MarketAction; 6] = [
+        MarketAction::Buy,
+        MarketAction::Suspend,
+        MarketAction::Change,
+        MarketAction::Resume,
+        MarketAction::Close,
+        MarketAction::Withdraw,
+    ];
+}
+
+/// Outcome of a simulation, one entry per step with whether the instruction succeeded
+#[derive(Debug)]
+pub struct SimulationReport {
+    pub steps: Vec<(MarketAction, bool)>,
+    pub supply: u64,
+    pub funds_collected: u64,
+    pub paid_out: u64,
+}
+
+/// Runs a randomized sequence of market instructions against program-test and checks market invariants after
+/// every step. Sequences are derived from `seed`, so a failing seed replays the same lifecycle.
+pub struct MarketSimulator {
+    seed: u64,
+    steps: usize,
+    max_supply: u64,
+    price: u64,
+    script: Option<Vec<MarketAction>>,
+    separate_creator: bool,
+}
+
+impl MarketSimulator {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            steps: 20,
+            max_supply: 5,
+            price: 1_000_000,
+            script: None,
+            separate_creator: false,
+        }
+    }
+
+    pub fn steps(mut self, steps: usize) -> Self {
+        self.steps = steps;
+        self
+    }
+
+    pub fn max_supply(mut self, max_supply: u64) -> Self {
+        self.max_supply = max_supply;
+        self
+    }
+
+    pub fn price(mut self, price: u64) -> Self {
+        self.price = price;
+        self
+    }
+
+    /// Make a wallet other than the market owner the only primary creator, withdrawing in place of the owner
+    pub fn separate_creator(mut self) -> Self {
+        self.separate_creator = true;
+        self
+    }
+
+    /// Run the given actions in order instead of a randomized sequence
+    pub fn script(mut self, script: &[MarketAction]) -> Self {
+        self.steps = script.len();
+        self.script = Some(script.to_vec());
+        self
+    }
+
+    /// Run the sequence, closing and withdrawing from the market at the end so payouts can be checked against sales.
+    pub async fn run(self, context: &mut ProgramTestContext) -> SimulationReport {
+        let mut rng = self.seed.max(1);
+        let mut market =
+            SimulatedMarket::setup(context, self.max_supply, self.price, self.separate_creator)
+                .await;
+        let mut report = SimulationReport {
+            steps: Vec::with_capacity(self.steps),
+            supply: 0,
+            funds_collected: 0,
+            paid_out: 0,
+        };
+
+        for step in 0..self.steps {
+            let action = match &self.script {
+                Some(script) => script[step],
+                None => MarketAction::ALL[(next_random(&mut rng) % 6) as usize],
+            };
+            let succeeded = market.apply(context, action, &mut rng).await;
+            report.steps.push((action, succeeded));
+            market.assert_invariants(context).await;
+        }
+
+        if !market.closed {
+            market.apply(context, MarketAction::Close, &mut rng).await;
+        }
+        if market.paid_out < market.sales_total {
+            assert!(
+                market
+                    .apply(context, MarketAction::Withdraw, &mut rng)
+                    .await
+            );
+        }
+        market.assert_invariants(context).await;
+        assert_eq!(market.paid_out, market.sales_total);
+
+        report.supply = market.supply;
+        report.funds_collected = market.sales_total;
+        report.paid_out = market.paid_out;
+        report
+    }
+}
+
+/// xorshift64, enough to vary sequences without pulling in a rand dependency
+fn next_random(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+/// Native SOL market of an unlimited duration together with the model of its expected balances
+struct SimulatedMarket {
+    market: Keypair,
+    store: Pubkey,
+    owner: Keypair,
+    /// primary creator other than the owner, withdraws instead of the owner when set
+    creator: Option<Keypair>,
+    selling_resource: Pubkey,
+    treasury_holder: Pubkey,
+    treasury_owner_bump: u8,
+    primary_metadata_creators: Pubkey,
+    max_supply: u64,
+    supply: u64,
+    price: u64,
+    sales_total: u64,
+    paid_out: u64,
+    closed: bool,
+}
+
+impl SimulatedMarket {
+    async fn setup(
+        context: &mut ProgramTestContext,
+        max_supply: u64,
+        price: u64,
+        separate_creator: bool,
+    ) -> Self {
+        let (admin_wallet, store_keypair) = setup_store(context).await;
+        let (selling_resource_keypair, owner, _vault) = setup_selling_resource_with_owner(
+            context,
+            &admin_wallet,
+            &store_keypair,
+            Keypair::new(),
+            100,
+            None,
+            true,
+            true,
+            max_supply,
+            SellingResourceType::Editions,
+        )
+        .await;
+        let selling_resource = selling_resource_keypair.pubkey();
+        let selling_resource_data = get_selling_resource(context, &selling_resource).await;
+
+        // Owner or the separate creator is the only primary creator, so it's paid the whole treasury
+        let creator = if separate_creator {
+            let creator = Keypair::new();
+            airdrop(context, &creator.pubkey(), 1_000_000_000).await;
+            Some(creator)
+        } else {
+            None
+        };
+        let creator_address = creator
+            .as_ref()
+            .map_or(owner.pubkey(), |creator| creator.pubkey());
+        let (master_edition_metadata, _) = find_metadata(&selling_resource_data.resource);
+        let (primary_metadata_creators, primary_metadata_creators_bump) =
+            find_primary_metadata_creators(&master_edition_metadata);
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data: mpl_fixed_price_sale_instruction::SavePrimaryMetadataCreators {
+                primary_metadata_creators_bump,
+                creators: vec![mpl_fixed_price_sale::state::Creator {
+                    address: creator_address,
+                    verified: false,
+                    share: 100,
+                }],
+            }
+            .data(),
+            accounts: mpl_fixed_price_sale_accounts::SavePrimaryMetadataCreators {
+                admin: owner.pubkey(),
+                metadata: master_edition_metadata,
+                primary_metadata_creators,
+                system_program: system_program::id(),
+            }
+            .to_account_metas(None),
+        };
+        process(context, instruction, &[&owner]).await.unwrap();
+
+        let market = Keypair::new();
+        let treasury_mint = system_program::id();
+        let (treasury_holder, treasury_owner_bump) =
+            find_treasury_owner_address(&treasury_mint, &selling_resource);
+        let start_date = context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+            .unix_timestamp as u64
+            + 1;
+        let name = "Simulation".to_string();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data: mpl_fixed_price_sale_instruction::CreateMarket {
+                _treasury_owner_bump: treasury_owner_bump,
+                name: name.to_owned(),
+                description: "Simulated market".to_string(),
+                mutable: true,
+                price,
+                pieces_in_one_wallet: None,
+                start_date,
+                end_date: None,
+                gating_config: None,
+                metadata_overrides: None,
+                pda_mints: false,
+                processor_fee: None,
+                sweep_basis_points: None,
+            }
+            .data(),
+            accounts: mpl_fixed_price_sale_accounts::CreateMarket {
+                market: market.pubkey(),
+                store: store_keypair.pubkey(),
+                selling_resource_owner: owner.pubkey(),
+                selling_resource,
+                mint: treasury_mint,
+                treasury_holder,
+                owner: treasury_holder,
+                market_name_registry: find_market_name_registry_address(
+                    &store_keypair.pubkey(),
+                    &name,
+                )
+                .0,
+                store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+                global_config: find_global_config_address().0,
+                system_program: system_program::id(),
+            }
+            .to_account_metas(None),
+        };
+        process(context, instruction, &[&market, &owner])
+            .await
+            .unwrap();
+
+        let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        context.warp_to_slot(clock.slot + 1500).unwrap();
+
+        Self {
+            market,
+            store: store_keypair.pubkey(),
+            owner,
+            creator,
+            selling_resource,
+            treasury_holder,
+            treasury_owner_bump,
+            primary_metadata_creators,
+            max_supply,
+            supply: 0,
+            price,
+            sales_total: 0,
+            paid_out: 0,
+            closed: false,
+        }
+    }
+
+    /// Run `action`, updating the model when it succeeds
+    async fn apply(
+        &mut self,
+        context: &mut ProgramTestContext,
+        action: MarketAction,
+        rng: &mut u64,
+    ) -> bool {
+        // Fresh blockhash, so repeated actions are not deduplicated
+        let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        context.warp_to_slot(clock.slot + 1).unwrap();
+
+        match action {
+            MarketAction::Buy => {
+                let succeeded = self.buy(context).await.is_ok();
+                if succeeded {
+                    self.supply += 1;
+                    self.sales_total += self.price;
+                }
+                succeeded
+            }
+            MarketAction::Suspend => {
+                let instruction = Instruction {
+                    program_id: mpl_fixed_price_sale::id(),
+                    data: mpl_fixed_price_sale_instruction::SuspendMarket {}.data(),
+                    accounts: mpl_fixed_price_sale_accounts::SuspendMarket {
+                        market: self.market.pubkey(),
+                        store: self.store,
+                        owner: self.owner.pubkey(),
+                        market_manager: find_market_manager_address(
+                            &self.market.pubkey(),
+                            &self.owner.pubkey(),
+                        )
+                        .0,
+                        clock: sysvar::clock::id(),
+                    }
+                    .to_account_metas(None),
+                };
+                process(context, instruction, &[&self.owner]).await.is_ok()
+            }
+            MarketAction::Change => {
+                let new_price = self.price + next_random(rng) % self.price.max(1);
+                let instruction = Instruction {
+                    program_id: mpl_fixed_price_sale::id(),
+                    data: mpl_fixed_price_sale_instruction::ChangeMarket {
+                        new_name: None,
+                        new_description: None,
+                        mutable: None,
+                        new_price: Some(new_price),
+                        new_pieces_in_one_wallet: None,
+                    }
+                    .data(),
+                    accounts: mpl_fixed_price_sale_accounts::ChangeMarket {
+                        market: self.market.pubkey(),
+                        store: self.store,
+                        owner: self.owner.pubkey(),
+                        market_manager: find_market_manager_address(
+                            &self.market.pubkey(),
+                            &self.owner.pubkey(),
+                        )
+                        .0,
+                        clock: sysvar::clock::id(),
+                        system_program: system_program::id(),
+                    }
+                    .to_account_metas(None),
+                };
+                let succeeded = process(context, instruction, &[&self.owner]).await.is_ok();
+                if succeeded {
+                    self.price = new_price;
+                }
+                succeeded
+            }
+            MarketAction::Resume => {
+                let instruction = Instruction {
+                    program_id: mpl_fixed_price_sale::id(),
+                    data: mpl_fixed_price_sale_instruction::ResumeMarket {}.data(),
+                    accounts: mpl_fixed_price_sale_accounts::ResumeMarket {
+                        market: self.market.pubkey(),
+                        store: self.store,
+                        owner: self.owner.pubkey(),
+                        market_manager: find_market_manager_address(
+                            &self.market.pubkey(),
+                            &self.owner.pubkey(),
+                        )
+                        .0,
+                        clock: sysvar::clock::id(),
+                    }
+                    .to_account_metas(None),
+                };
+                process(context, instruction, &[&self.owner]).await.is_ok()
+            }
+            MarketAction::Close => {
+                let mut accounts = mpl_fixed_price_sale_accounts::CloseMarket {
+                    market: self.market.pubkey(),
+                    store: self.store,
+                    owner: self.owner.pubkey(),
+                    clock: sysvar::clock::id(),
+                }
+                .to_account_metas(None);
+                accounts.push(AccountMeta::new(
+                    find_market_name_registry_address(&self.store, "Simulation").0,
+                    false,
+                ));
+
+                let instruction = Instruction {
+                    program_id: mpl_fixed_price_sale::id(),
+                    data: mpl_fixed_price_sale_instruction::CloseMarket {}.data(),
+                    accounts,
+                };
+                let succeeded = process(context, instruction, &[&self.owner]).await.is_ok();
+                self.closed |= succeeded;
+                succeeded
+            }
+            MarketAction::Withdraw => {
+                let funder = self.funder();
+                let balance_before = get_lamports(context, &funder).await;
+                let succeeded = self.withdraw(context).await.is_ok();
+                if succeeded {
+                    self.paid_out += get_lamports(context, &funder).await - balance_before;
+                }
+                succeeded
+            }
+        }
+    }
+
+    async fn buy(&self, context: &mut ProgramTestContext) -> Result<(), TransportError> {
+        let selling_resource = get_selling_resource(context, &self.selling_resource).await;
+
+        let user_wallet = Keypair::new();
+        airdrop(
+            context,
+            &user_wallet.pubkey(),
+            10 * self.price + 1_000_000_000,
+        )
+        .await;
+
+        let new_mint = Keypair::new();
+        create_mint(context, &new_mint, &user_wallet.pubkey(), 0).await;
+        let new_token_account = Keypair::new();
+        create_token_account(
+            context,
+            &new_token_account,
+            &new_mint.pubkey(),
+            &user_wallet.pubkey(),
+        )
+        .await;
+        mint_to(
+            context,
+            &new_mint.pubkey(),
+            &new_token_account.pubkey(),
+            &user_wallet,
+            1,
+        )
+        .await;
+
+        let (trade_history, trade_history_bump) =
+            find_trade_history_address(&user_wallet.pubkey(), &self.market.pubkey());
+        let (owner, vault_owner_bump) =
+            find_vault_owner_address(&selling_resource.resource, &selling_resource.store);
+        let (master_edition_metadata, _) = find_metadata(&selling_resource.resource);
+        let (master_edition, _) = find_edition(&selling_resource.resource);
+        let (edition_marker, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                selling_resource.resource.as_ref(),
+                mpl_token_metadata::state::EDITION.as_bytes(),
+                ((selling_resource.supply + 1) / EDITION_MARKER_BIT_SIZE)
+                    .to_string()
+                    .as_bytes(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data: mpl_fixed_price_sale_instruction::Buy {
+                _trade_history_bump: trade_history_bump,
+                vault_owner_bump,
+                client_nonce: None,
+            }
+            .data(),
+            accounts: mpl_fixed_price_sale_accounts::Buy {
+                market: self.market.pubkey(),
+                selling_resource: self.selling_resource,
+                user_token_account: user_wallet.pubkey(),
+                user_wallet: user_wallet.pubkey(),
+                trade_history,
+                treasury_holder: self.treasury_holder,
+                new_metadata: find_metadata(&new_mint.pubkey()).0,
+                new_edition: find_edition(&new_mint.pubkey()).0,
+                master_edition,
+                new_mint: new_mint.pubkey(),
+                edition_marker,
+                vault: selling_resource.vault,
+                store: selling_resource.store,
+                owner,
+                new_token_account: new_token_account.pubkey(),
+                master_edition_metadata,
+                clock: sysvar::clock::id(),
+                rent: sysvar::rent::id(),
+                token_metadata_program: mpl_token_metadata::id(),
+                token_program: spl_token::id(),
+                system_program: system_program::id(),
+                associated_token_program: spl_associated_token_account::id(),
+                provenance_record: find_provenance_record_address(&new_mint.pubkey()).0,
+            }
+            .to_account_metas(None),
+        };
+
+        process(context, instruction, &[&user_wallet]).await
+    }
+
+    /// Wallet withdrawing the proceeds, the primary creator
+    fn funder(&self) -> Pubkey {
+        self.creator
+            .as_ref()
+            .map_or(self.owner.pubkey(), |creator| creator.pubkey())
+    }
+
+    async fn withdraw(&self, context: &mut ProgramTestContext) -> Result<(), TransportError> {
+        let selling_resource = get_selling_resource(context, &self.selling_resource).await;
+        let funder = self.funder();
+        let (payout_ticket, payout_ticket_bump) =
+            find_payout_ticket_address(&self.market.pubkey(), &funder);
+
+        let mut accounts = mpl_fixed_price_sale_accounts::Withdraw {
+            market: self.market.pubkey(),
+            store: self.store,
+            selling_resource: self.selling_resource,
+            metadata: find_metadata(&selling_resource.resource).0,
+            treasury_holder: self.treasury_holder,
+            treasury_mint: system_program::id(),
+            owner: self.treasury_holder,
+            destination: funder,
+            funder,
+            payer: context.payer.pubkey(),
+            payout_ticket,
+            claim_tally: find_claim_tally_address(&self.market.pubkey()).0,
+            rent: sysvar::rent::id(),
+            clock: sysvar::clock::id(),
+            token_program: spl_token::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None);
+        accounts.push(AccountMeta::new_readonly(
+            self.primary_metadata_creators,
+            false,
+        ));
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data: mpl_fixed_price_sale_instruction::Withdraw {
+                treasury_owner_bump: self.treasury_owner_bump,
+                payout_ticket_bump,
+            }
+            .data(),
+            accounts,
+        };
+
+        process(context, instruction, &[]).await
+    }
+
+    /// Supply never exceeds max, treasury equals sum of sales less payouts
+    async fn assert_invariants(&self, context: &mut ProgramTestContext) {
+        let selling_resource = get_selling_resource(context, &self.selling_resource).await;
+        assert!(selling_resource.supply <= self.max_supply);
+        assert_eq!(selling_resource.supply, self.supply);
+
+        let market_acc = context
+            .banks_client
+            .get_account(self.market.pubkey())
+            .await
+            .expect("account not found")
+            .expect("account empty");
+        let market = Market::try_deserialize(&mut market_acc.data.as_ref()).unwrap();
+        assert_eq!(market.funds_collected, self.sales_total);
+        if self.supply == self.max_supply {
+            assert_eq!(market.state, MarketState::Ended);
+        }
+
+        let treasury_balance = get_lamports(context, &self.treasury_holder).await;
+        assert_eq!(
+            treasury_balance,
+            MINIMUM_BALANCE_FOR_SYSTEM_ACCS + self.sales_total - self.paid_out
+        );
+    }
+}
+
+async fn process(
+    context: &mut ProgramTestContext,
+    instruction: Instruction,
+    signers: &[&Keypair],
+) -> Result<(), TransportError> {
+    let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+    let mut all_signers = vec![&payer];
+    all_signers.extend_from_slice(signers);
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &all_signers,
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await
+}
+
+async fn get_selling_resource(
+    context: &mut ProgramTestContext,
+    selling_resource: &Pubkey,
+) -> SellingResource {
+    let selling_resource_acc = context
+        .banks_client
+        .get_account(*selling_resource)
+        .await
+        .expect("account not found")
+        .expect("account empty");
+
+    SellingResource::try_deserialize(&mut selling_resource_acc.data.as_ref()).unwrap()
+}
+
+async fn get_lamports(context: &mut ProgramTestContext, account: &Pubkey) -> u64 {
+    context
+        .banks_client
+        .get_account(*account)
+        .await
+        .unwrap()
+        .map(|account| account.lamports)
+        .unwrap_or_default()
+}
+
+fn find_metadata(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            mpl_token_metadata::state::PREFIX.as_bytes(),
+            mpl_token_metadata::id().as_ref(),
+            mint.as_ref(),
+        ],
+        &mpl_token_metadata::id(),
+    )
+}
+
+fn find_edition(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            mpl_token_metadata::state::PREFIX.as_bytes(),
+            mpl_token_metadata::id().as_ref(),
+            mint.as_ref(),
+            mpl_token_metadata::state::EDITION.as_bytes(),
+        ],
+        &mpl_token_metadata::id(),
+    )
+}
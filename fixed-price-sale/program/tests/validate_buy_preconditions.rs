@@ -0,0 +1,105 @@
+mod utils;
+
+#[cfg(feature = "test-bpf")]
+mod validate_buy_preconditions {
+    use crate::{
+        setup_context,
+        utils::setup_functions::{setup_market, setup_selling_resource, setup_store},
+    };
+    use anchor_lang::{error::ERROR_CODE_OFFSET, AccountDeserialize};
+    use mpl_fixed_price_sale::{
+        client::{validate_buy_preconditions, BuyRpcState, PreconditionError},
+        error::ErrorCode,
+        state::{Market, SellingResource, TradeHistory},
+    };
+    use solana_program_test::*;
+    use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer, sysvar::clock::Clock};
+
+    #[tokio::test]
+    async fn success() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _) = setup_selling_resource(
+            &mut context,
+            &admin_wallet,
+            &store_keypair,
+            100,
+            None,
+            true,
+            false,
+        )
+        .await;
+
+        let market_keypair = setup_market(
+            &mut context,
+            &admin_wallet,
+            &store_keypair,
+            &selling_resource_keypair,
+            &selling_resource_owner_keypair,
+        )
+        .await;
+
+        let market_acc = context
+            .banks_client
+            .get_account(market_keypair.pubkey())
+            .await
+            .expect("account not found")
+            .expect("account empty");
+        let market = Market::try_deserialize(&mut market_acc.data.as_ref()).unwrap();
+
+        let selling_resource_acc = context
+            .banks_client
+            .get_account(selling_resource_keypair.pubkey())
+            .await
+            .expect("account not found")
+            .expect("account empty");
+        let selling_resource =
+            SellingResource::try_deserialize(&mut selling_resource_acc.data.as_ref()).unwrap();
+
+        let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        let user_wallet = Keypair::new();
+        let treasury_mint = market.treasury_mint;
+        let start_date = market.start_date;
+
+        let mut rpc_state = BuyRpcState {
+            market,
+            selling_resource_key: selling_resource_keypair.pubkey(),
+            selling_resource,
+            trade_history: None,
+            user_wallet: user_wallet.pubkey(),
+            user_token_account: Pubkey::new_unique(),
+            user_token_account_mint: None,
+            client_nonce: None,
+            unix_timestamp: clock.unix_timestamp,
+        };
+
+        let errors = validate_buy_preconditions(&rpc_state);
+        assert_eq!(
+            errors,
+            vec![
+                PreconditionError::MarketIsNotStarted { start_date },
+                PreconditionError::TreasuryMismatch { treasury_mint },
+            ]
+        );
+        assert_eq!(
+            errors[0].error_code(),
+            ERROR_CODE_OFFSET + ErrorCode::MarketIsNotStarted as u32
+        );
+
+        rpc_state.unix_timestamp = start_date as i64;
+        rpc_state.user_token_account_mint = Some(treasury_mint);
+        assert!(validate_buy_preconditions(&rpc_state).is_empty());
+
+        rpc_state.trade_history = Some(TradeHistory {
+            already_bought: 1,
+            ..TradeHistory::default()
+        });
+        assert_eq!(
+            validate_buy_preconditions(&rpc_state),
+            vec![PreconditionError::UserReachBuyLimit {
+                pieces_in_one_wallet: 1
+            }]
+        );
+    }
+}
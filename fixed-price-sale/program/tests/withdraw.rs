@@ -10,27 +10,33 @@ mod withdraw {
         },
     };
     use anchor_lang::{
-        system_program::System, AccountDeserialize, Id, InstructionData, ToAccountMetas,
+        error::ERROR_CODE_OFFSET, system_program::System, AccountDeserialize, Id, InstructionData,
+        ToAccountMetas,
     };
     use mpl_fixed_price_sale::{
-        accounts as mpl_fixed_price_sale_accounts, instruction as mpl_fixed_price_sale_instruction,
+        accounts as mpl_fixed_price_sale_accounts,
+        error::ErrorCode,
+        instruction as mpl_fixed_price_sale_instruction,
         state::SellingResource,
         utils::{
-            find_payout_ticket_address, find_primary_metadata_creators, find_trade_history_address,
+            find_claim_tally_address, find_global_config_address,
+            find_market_name_registry_address, find_payout_ticket_address,
+            find_primary_metadata_creators, find_provenance_record_address,
+            find_store_payment_mints_address, find_trade_history_address,
             find_treasury_owner_address, find_vault_owner_address,
         },
     };
     use solana_program::clock::Clock;
     use solana_program_test::*;
     use solana_sdk::{
-        account::ReadableAccount,
-        instruction::{AccountMeta, Instruction},
+        account::{AccountSharedData, ReadableAccount},
+        instruction::{AccountMeta, Instruction, InstructionError},
         program_pack::Pack,
         pubkey::Pubkey,
         signature::Keypair,
         signer::Signer,
         system_program, sysvar,
-        transaction::Transaction,
+        transaction::{Transaction, TransactionError},
         transport::TransportError,
     };
 
@@ -106,6 +112,10 @@ mod withdraw {
             mint: treasury_mint_keypair.pubkey(),
             treasury_holder: treasury_holder_keypair.pubkey(),
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -120,6 +130,10 @@ mod withdraw {
             start_date: start_date as u64,
             end_date: None,
             gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -305,6 +319,7 @@ mod withdraw {
             new_mint: new_mint_keypair.pubkey(),
             edition_marker,
             vault: selling_resource.vault,
+            store: selling_resource.store,
             owner,
             new_token_account: new_mint_token_account.pubkey(),
             master_edition_metadata,
@@ -313,12 +328,15 @@ mod withdraw {
             token_metadata_program: mpl_token_metadata::id(),
             token_program: spl_token::id(),
             system_program: system_program::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            provenance_record: find_provenance_record_address(&new_mint_keypair.pubkey()).0,
         }
         .to_account_metas(None);
 
         let data = mpl_fixed_price_sale_instruction::Buy {
             _trade_history_bump: trade_history_bump,
             vault_owner_bump,
+            client_nonce: None,
         }
         .data();
 
@@ -341,12 +359,16 @@ mod withdraw {
         context.warp_to_slot(clock.slot + 3).unwrap();
 
         // CloseMarket
-        let accounts = mpl_fixed_price_sale_accounts::CloseMarket {
+        let mut accounts = mpl_fixed_price_sale_accounts::CloseMarket {
             market: market_keypair.pubkey(),
+            store: selling_resource.store,
             owner: selling_resource_owner_keypair.pubkey(),
             clock: sysvar::clock::id(),
         }
         .to_account_metas(None);
+        let (market_name_registry, _) =
+            find_market_name_registry_address(&selling_resource.store, &name);
+        accounts.push(AccountMeta::new(market_name_registry, false));
 
         let data = mpl_fixed_price_sale_instruction::CloseMarket {}.data();
 
@@ -387,6 +409,7 @@ mod withdraw {
 
         let mut accounts = mpl_fixed_price_sale_accounts::Withdraw {
             market: market_keypair.pubkey(),
+            store: selling_resource.store,
             selling_resource: selling_resource_keypair.pubkey(),
             metadata,
             treasury_holder: treasury_holder_keypair.pubkey(),
@@ -396,6 +419,7 @@ mod withdraw {
             funder: primary_royalties_holder.pubkey(),
             payer: payer_pubkey,
             payout_ticket,
+            claim_tally: find_claim_tally_address(&market_keypair.pubkey()).0,
             rent: sysvar::rent::id(),
             clock: sysvar::clock::id(),
             token_program: spl_token::id(),
@@ -500,6 +524,10 @@ mod withdraw {
             mint: treasury_mint,
             treasury_holder,
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -514,6 +542,10 @@ mod withdraw {
             start_date: start_date as u64,
             end_date: None,
             gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -684,6 +716,7 @@ mod withdraw {
             new_mint: new_mint_keypair.pubkey(),
             edition_marker,
             vault: selling_resource.vault,
+            store: selling_resource.store,
             owner,
             new_token_account: new_mint_token_account.pubkey(),
             master_edition_metadata,
@@ -692,12 +725,15 @@ mod withdraw {
             token_metadata_program: mpl_token_metadata::id(),
             token_program: spl_token::id(),
             system_program: system_program::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            provenance_record: find_provenance_record_address(&new_mint_keypair.pubkey()).0,
         }
         .to_account_metas(None);
 
         let data = mpl_fixed_price_sale_instruction::Buy {
             _trade_history_bump: trade_history_bump,
             vault_owner_bump,
+            client_nonce: None,
         }
         .data();
 
@@ -720,12 +756,16 @@ mod withdraw {
         context.warp_to_slot(clock.slot + 3).unwrap();
 
         // CloseMarket
-        let accounts = mpl_fixed_price_sale_accounts::CloseMarket {
+        let mut accounts = mpl_fixed_price_sale_accounts::CloseMarket {
             market: market_keypair.pubkey(),
+            store: selling_resource.store,
             owner: selling_resource_owner_keypair.pubkey(),
             clock: sysvar::clock::id(),
         }
         .to_account_metas(None);
+        let (market_name_registry, _) =
+            find_market_name_registry_address(&selling_resource.store, &name);
+        accounts.push(AccountMeta::new(market_name_registry, false));
 
         let data = mpl_fixed_price_sale_instruction::CloseMarket {}.data();
 
@@ -763,6 +803,7 @@ mod withdraw {
 
         let mut accounts = mpl_fixed_price_sale_accounts::Withdraw {
             market: market_keypair.pubkey(),
+            store: selling_resource.store,
             selling_resource: selling_resource_keypair.pubkey(),
             metadata,
             treasury_holder,
@@ -772,6 +813,7 @@ mod withdraw {
             funder: primary_royalties_holder.pubkey(),
             payer: payer_pubkey,
             payout_ticket,
+            claim_tally: find_claim_tally_address(&market_keypair.pubkey()).0,
             rent: sysvar::rent::id(),
             clock: sysvar::clock::id(),
             token_program: spl_token::id(),
@@ -892,6 +934,10 @@ mod withdraw {
             mint: treasury_mint_keypair.pubkey(),
             treasury_holder: treasury_holder_keypair.pubkey(),
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -906,6 +952,10 @@ mod withdraw {
             start_date: start_date as u64,
             end_date: None,
             gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -1052,6 +1102,7 @@ mod withdraw {
             new_mint: new_mint_keypair.pubkey(),
             edition_marker,
             vault: selling_resource.vault,
+            store: selling_resource.store,
             owner,
             new_token_account: new_mint_token_account.pubkey(),
             master_edition_metadata,
@@ -1060,12 +1111,15 @@ mod withdraw {
             token_metadata_program: mpl_token_metadata::id(),
             token_program: spl_token::id(),
             system_program: system_program::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            provenance_record: find_provenance_record_address(&new_mint_keypair.pubkey()).0,
         }
         .to_account_metas(None);
 
         let data = mpl_fixed_price_sale_instruction::Buy {
             _trade_history_bump: trade_history_bump,
             vault_owner_bump,
+            client_nonce: None,
         }
         .data();
 
@@ -1088,12 +1142,16 @@ mod withdraw {
         context.warp_to_slot(clock.slot + 3).unwrap();
 
         // CloseMarket
-        let accounts = mpl_fixed_price_sale_accounts::CloseMarket {
+        let mut accounts = mpl_fixed_price_sale_accounts::CloseMarket {
             market: market_keypair.pubkey(),
+            store: selling_resource.store,
             owner: selling_resource_owner_keypair.pubkey(),
             clock: sysvar::clock::id(),
         }
         .to_account_metas(None);
+        let (market_name_registry, _) =
+            find_market_name_registry_address(&selling_resource.store, &name);
+        accounts.push(AccountMeta::new(market_name_registry, false));
 
         let data = mpl_fixed_price_sale_instruction::CloseMarket {}.data();
 
@@ -1131,6 +1189,7 @@ mod withdraw {
 
         let accounts = mpl_fixed_price_sale_accounts::Withdraw {
             market: market_keypair.pubkey(),
+            store: selling_resource.store,
             selling_resource: selling_resource_keypair.pubkey(),
             metadata,
             treasury_holder: treasury_holder_keypair.pubkey(),
@@ -1140,6 +1199,7 @@ mod withdraw {
             funder: selling_resource_owner_keypair.pubkey(),
             payer: payer_pubkey,
             payout_ticket,
+            claim_tally: find_claim_tally_address(&market_keypair.pubkey()).0,
             rent: sysvar::rent::id(),
             clock: sysvar::clock::id(),
             token_program: spl_token::id(),
@@ -1251,6 +1311,10 @@ mod withdraw {
             mint: treasury_mint_keypair.pubkey(),
             treasury_holder: treasury_holder_keypair.pubkey(),
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -1265,6 +1329,10 @@ mod withdraw {
             start_date: start_date as u64,
             end_date: None,
             gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -1411,6 +1479,7 @@ mod withdraw {
             new_mint: new_mint_keypair.pubkey(),
             edition_marker,
             vault: selling_resource.vault,
+            store: selling_resource.store,
             owner,
             new_token_account: new_mint_token_account.pubkey(),
             master_edition_metadata,
@@ -1419,12 +1488,15 @@ mod withdraw {
             token_metadata_program: mpl_token_metadata::id(),
             token_program: spl_token::id(),
             system_program: system_program::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            provenance_record: find_provenance_record_address(&new_mint_keypair.pubkey()).0,
         }
         .to_account_metas(None);
 
         let data = mpl_fixed_price_sale_instruction::Buy {
             _trade_history_bump: trade_history_bump,
             vault_owner_bump,
+            client_nonce: None,
         }
         .data();
 
@@ -1447,12 +1519,16 @@ mod withdraw {
         context.warp_to_slot(clock.slot + 3).unwrap();
 
         // CloseMarket
-        let accounts = mpl_fixed_price_sale_accounts::CloseMarket {
+        let mut accounts = mpl_fixed_price_sale_accounts::CloseMarket {
             market: market_keypair.pubkey(),
+            store: selling_resource.store,
             owner: selling_resource_owner_keypair.pubkey(),
             clock: sysvar::clock::id(),
         }
         .to_account_metas(None);
+        let (market_name_registry, _) =
+            find_market_name_registry_address(&selling_resource.store, &name);
+        accounts.push(AccountMeta::new(market_name_registry, false));
 
         let data = mpl_fixed_price_sale_instruction::CloseMarket {}.data();
 
@@ -1489,6 +1565,7 @@ mod withdraw {
 
         let accounts = mpl_fixed_price_sale_accounts::Withdraw {
             market: market_keypair.pubkey(),
+            store: selling_resource.store,
             selling_resource: selling_resource_keypair.pubkey(),
             metadata,
             treasury_holder: treasury_holder_keypair.pubkey(),
@@ -1498,6 +1575,7 @@ mod withdraw {
             funder,
             payer: payer_pubkey,
             payout_ticket,
+            claim_tally: find_claim_tally_address(&market_keypair.pubkey()).0,
             rent: sysvar::rent::id(),
             clock: sysvar::clock::id(),
             token_program: spl_token::id(),
@@ -1537,6 +1615,445 @@ mod withdraw {
         }
     }
 
+    #[tokio::test]
+    async fn fail_spoofed_primary_metadata_creators() {
+        setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
+        let (admin_wallet, store_keypair) = setup_store(&mut context).await;
+
+        let (selling_resource_keypair, selling_resource_owner_keypair, _vault) =
+            setup_selling_resource(
+                &mut context,
+                &admin_wallet,
+                &store_keypair,
+                100,
+                None,
+                true,
+                true,
+            )
+            .await;
+
+        airdrop(
+            &mut context,
+            &selling_resource_owner_keypair.pubkey(),
+            10_000_000_000,
+        )
+        .await;
+
+        let market_keypair = Keypair::new();
+
+        let treasury_mint_keypair = Keypair::new();
+        create_mint(
+            &mut context,
+            &treasury_mint_keypair,
+            &admin_wallet.pubkey(),
+            0,
+        )
+        .await;
+
+        let (treasury_owner, treasyry_owner_bump) = find_treasury_owner_address(
+            &treasury_mint_keypair.pubkey(),
+            &selling_resource_keypair.pubkey(),
+        );
+
+        let treasury_holder_keypair = Keypair::new();
+        create_token_account(
+            &mut context,
+            &treasury_holder_keypair,
+            &treasury_mint_keypair.pubkey(),
+            &treasury_owner,
+        )
+        .await;
+
+        let start_date = context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+            .unix_timestamp
+            + 1;
+
+        let name = "Marktname".to_string();
+        let description = "Marktbeschreibung".to_string();
+        let mutable = true;
+        let price = 1_000_000;
+        let pieces_in_one_wallet = Some(1);
+
+        // CreateMarket
+        let accounts = mpl_fixed_price_sale_accounts::CreateMarket {
+            market: market_keypair.pubkey(),
+            store: store_keypair.pubkey(),
+            selling_resource_owner: selling_resource_owner_keypair.pubkey(),
+            selling_resource: selling_resource_keypair.pubkey(),
+            mint: treasury_mint_keypair.pubkey(),
+            treasury_holder: treasury_holder_keypair.pubkey(),
+            owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::CreateMarket {
+            _treasury_owner_bump: treasyry_owner_bump,
+            name: name.to_owned(),
+            description: description.to_owned(),
+            mutable,
+            price,
+            pieces_in_one_wallet,
+            start_date: start_date as u64,
+            end_date: None,
+            gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[
+                &context.payer,
+                &market_keypair,
+                &selling_resource_owner_keypair,
+            ],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        context.warp_to_slot(clock.slot + 1500).unwrap();
+
+        // Buy setup
+        let selling_resource_data = context
+            .banks_client
+            .get_account(selling_resource_keypair.pubkey())
+            .await
+            .unwrap()
+            .unwrap()
+            .data;
+        let selling_resource =
+            SellingResource::try_deserialize(&mut selling_resource_data.as_ref()).unwrap();
+
+        let (trade_history, trade_history_bump) =
+            find_trade_history_address(&context.payer.pubkey(), &market_keypair.pubkey());
+        let (owner, vault_owner_bump) =
+            find_vault_owner_address(&selling_resource.resource, &selling_resource.store);
+
+        let payer_pubkey = context.payer.pubkey();
+
+        let user_token_account = Keypair::new();
+        create_token_account(
+            &mut context,
+            &user_token_account,
+            &treasury_mint_keypair.pubkey(),
+            &payer_pubkey,
+        )
+        .await;
+
+        mint_to(
+            &mut context,
+            &treasury_mint_keypair.pubkey(),
+            &user_token_account.pubkey(),
+            &admin_wallet,
+            1_000_000,
+        )
+        .await;
+
+        let new_mint_keypair = Keypair::new();
+        create_mint(&mut context, &new_mint_keypair, &payer_pubkey, 0).await;
+
+        let new_mint_token_account = Keypair::new();
+        create_token_account(
+            &mut context,
+            &new_mint_token_account,
+            &new_mint_keypair.pubkey(),
+            &payer_pubkey,
+        )
+        .await;
+
+        let payer_keypair = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+        mint_to(
+            &mut context,
+            &new_mint_keypair.pubkey(),
+            &new_mint_token_account.pubkey(),
+            &payer_keypair,
+            1,
+        )
+        .await;
+
+        let (master_edition_metadata, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                selling_resource.resource.as_ref(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+
+        let (master_edition, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                selling_resource.resource.as_ref(),
+                mpl_token_metadata::state::EDITION.as_bytes(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+
+        let (edition_marker, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                selling_resource.resource.as_ref(),
+                mpl_token_metadata::state::EDITION.as_bytes(),
+                selling_resource.supply.to_string().as_bytes(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+
+        let (new_metadata, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                new_mint_keypair.pubkey().as_ref(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+
+        let (new_edition, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                new_mint_keypair.pubkey().as_ref(),
+                mpl_token_metadata::state::EDITION.as_bytes(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+
+        let (primary_metadata_creators, primary_metadata_creators_bump) =
+            find_primary_metadata_creators(&master_edition_metadata);
+
+        // SavePrimaryMetadataCreators
+        let accounts = mpl_fixed_price_sale_accounts::SavePrimaryMetadataCreators {
+            admin: selling_resource_owner_keypair.pubkey(),
+            metadata: master_edition_metadata,
+            primary_metadata_creators,
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None);
+
+        let primary_royalties_holder = Keypair::new();
+
+        let data = mpl_fixed_price_sale_instruction::SavePrimaryMetadataCreators {
+            primary_metadata_creators_bump: primary_metadata_creators_bump,
+            creators: vec![mpl_fixed_price_sale::state::Creator {
+                address: primary_royalties_holder.pubkey(),
+                verified: false,
+                share: 100,
+            }],
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, &selling_resource_owner_keypair],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        // Buy
+        let accounts = mpl_fixed_price_sale_accounts::Buy {
+            market: market_keypair.pubkey(),
+            selling_resource: selling_resource_keypair.pubkey(),
+            user_token_account: user_token_account.pubkey(),
+            user_wallet: context.payer.pubkey(),
+            trade_history,
+            treasury_holder: treasury_holder_keypair.pubkey(),
+            new_metadata,
+            new_edition,
+            master_edition,
+            new_mint: new_mint_keypair.pubkey(),
+            edition_marker,
+            vault: selling_resource.vault,
+            store: selling_resource.store,
+            owner,
+            new_token_account: new_mint_token_account.pubkey(),
+            master_edition_metadata,
+            clock: sysvar::clock::id(),
+            rent: sysvar::rent::id(),
+            token_metadata_program: mpl_token_metadata::id(),
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            provenance_record: find_provenance_record_address(&new_mint_keypair.pubkey()).0,
+        }
+        .to_account_metas(None);
+
+        let data = mpl_fixed_price_sale_instruction::Buy {
+            _trade_history_bump: trade_history_bump,
+            vault_owner_bump,
+            client_nonce: None,
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        context.warp_to_slot(clock.slot + 3).unwrap();
+
+        // CloseMarket
+        let mut accounts = mpl_fixed_price_sale_accounts::CloseMarket {
+            market: market_keypair.pubkey(),
+            store: selling_resource.store,
+            owner: selling_resource_owner_keypair.pubkey(),
+            clock: sysvar::clock::id(),
+        }
+        .to_account_metas(None);
+        let (market_name_registry, _) =
+            find_market_name_registry_address(&selling_resource.store, &name);
+        accounts.push(AccountMeta::new(market_name_registry, false));
+
+        let data = mpl_fixed_price_sale_instruction::CloseMarket {}.data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, &selling_resource_owner_keypair],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        // Withdraw
+        let (payout_ticket, payout_ticket_bump) = find_payout_ticket_address(
+            &market_keypair.pubkey(),
+            &primary_royalties_holder.pubkey(),
+        );
+
+        let destination = spl_associated_token_account::get_associated_token_address(
+            &primary_royalties_holder.pubkey(),
+            &treasury_mint_keypair.pubkey(),
+        );
+
+        let (metadata, _) = Pubkey::find_program_address(
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                selling_resource.resource.as_ref(),
+            ],
+            &mpl_token_metadata::id(),
+        );
+
+        let mut accounts = mpl_fixed_price_sale_accounts::Withdraw {
+            market: market_keypair.pubkey(),
+            store: selling_resource.store,
+            selling_resource: selling_resource_keypair.pubkey(),
+            metadata,
+            treasury_holder: treasury_holder_keypair.pubkey(),
+            treasury_mint: treasury_mint_keypair.pubkey(),
+            owner: treasury_owner,
+            destination,
+            funder: primary_royalties_holder.pubkey(),
+            payer: payer_pubkey,
+            payout_ticket,
+            claim_tally: find_claim_tally_address(&market_keypair.pubkey()).0,
+            rent: sysvar::rent::id(),
+            clock: sysvar::clock::id(),
+            token_program: spl_token::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None);
+        // Program owned copy of the primary creators at an address not derived from the metadata
+        let primary_metadata_creators_acc = context
+            .banks_client
+            .get_account(primary_metadata_creators)
+            .await
+            .unwrap()
+            .unwrap();
+        let spoofed_primary_metadata_creators = Pubkey::new_unique();
+        context.set_account(
+            &spoofed_primary_metadata_creators,
+            &AccountSharedData::from(primary_metadata_creators_acc),
+        );
+        accounts.push(AccountMeta::new(spoofed_primary_metadata_creators, false));
+
+        let data = mpl_fixed_price_sale_instruction::Withdraw {
+            payout_ticket_bump,
+            treasury_owner_bump: treasyry_owner_bump,
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: mpl_fixed_price_sale::id(),
+            data,
+            accounts,
+        };
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+
+        let tx_err = context
+            .banks_client
+            .process_transaction(tx)
+            .await
+            .unwrap_err();
+        match tx_err {
+            TransportError::TransactionError(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(code),
+            )) => {
+                assert_eq!(
+                    code,
+                    ERROR_CODE_OFFSET + ErrorCode::DerivedKeyInvalid as u32
+                );
+            }
+            _ => assert!(false),
+        }
+    }
+
     #[tokio::test]
     async fn fail_withdraw_twice() {
         setup_context!(context, mpl_fixed_price_sale, mpl_token_metadata);
@@ -1609,6 +2126,10 @@ mod withdraw {
             mint: treasury_mint_keypair.pubkey(),
             treasury_holder: treasury_holder_keypair.pubkey(),
             owner: treasury_owner,
+            market_name_registry: find_market_name_registry_address(&store_keypair.pubkey(), &name)
+                .0,
+            store_payment_mints: find_store_payment_mints_address(&store_keypair.pubkey()).0,
+            global_config: find_global_config_address().0,
             system_program: system_program::id(),
         }
         .to_account_metas(None);
@@ -1623,6 +2144,10 @@ mod withdraw {
             start_date: start_date as u64,
             end_date: None,
             gating_config: None,
+            metadata_overrides: None,
+            pda_mints: false,
+            processor_fee: None,
+            sweep_basis_points: None,
         }
         .data();
 
@@ -1808,6 +2333,7 @@ mod withdraw {
             new_mint: new_mint_keypair.pubkey(),
             edition_marker,
             vault: selling_resource.vault,
+            store: selling_resource.store,
             owner,
             new_token_account: new_mint_token_account.pubkey(),
             master_edition_metadata,
@@ -1816,12 +2342,15 @@ mod withdraw {
             token_metadata_program: mpl_token_metadata::id(),
             token_program: spl_token::id(),
             system_program: system_program::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            provenance_record: find_provenance_record_address(&new_mint_keypair.pubkey()).0,
         }
         .to_account_metas(None);
 
         let data = mpl_fixed_price_sale_instruction::Buy {
             _trade_history_bump: trade_history_bump,
             vault_owner_bump,
+            client_nonce: None,
         }
         .data();
 
@@ -1844,12 +2373,16 @@ mod withdraw {
         context.warp_to_slot(clock.slot + 3).unwrap();
 
         // CloseMarket
-        let accounts = mpl_fixed_price_sale_accounts::CloseMarket {
+        let mut accounts = mpl_fixed_price_sale_accounts::CloseMarket {
             market: market_keypair.pubkey(),
+            store: selling_resource.store,
             owner: selling_resource_owner_keypair.pubkey(),
             clock: sysvar::clock::id(),
         }
         .to_account_metas(None);
+        let (market_name_registry, _) =
+            find_market_name_registry_address(&selling_resource.store, &name);
+        accounts.push(AccountMeta::new(market_name_registry, false));
 
         let data = mpl_fixed_price_sale_instruction::CloseMarket {}.data();
 
@@ -1890,6 +2423,7 @@ mod withdraw {
 
         let mut accounts = mpl_fixed_price_sale_accounts::Withdraw {
             market: market_keypair.pubkey(),
+            store: selling_resource.store,
             selling_resource: selling_resource_keypair.pubkey(),
             metadata,
             treasury_holder: treasury_holder_keypair.pubkey(),
@@ -1899,6 +2433,7 @@ mod withdraw {
             funder: primary_royalties_holder.pubkey(),
             payer: payer_pubkey,
             payout_ticket,
+            claim_tally: find_claim_tally_address(&market_keypair.pubkey()).0,
             rent: sysvar::rent::id(),
             clock: sysvar::clock::id(),
             token_program: spl_token::id(),
@@ -1935,6 +2470,7 @@ mod withdraw {
         // Withdraw
         let mut accounts = mpl_fixed_price_sale_accounts::Withdraw {
             market: market_keypair.pubkey(),
+            store: selling_resource.store,
             selling_resource: selling_resource_keypair.pubkey(),
             metadata,
             treasury_holder: treasury_holder_keypair.pubkey(),
@@ -1944,6 +2480,7 @@ mod withdraw {
             funder: primary_royalties_holder.pubkey(),
             payer: payer_pubkey,
             payout_ticket,
+            claim_tally: find_claim_tally_address(&market_keypair.pubkey()).0,
             rent: sysvar::rent::id(),
             clock: sysvar::clock::id(),
             token_program: spl_token::id(),